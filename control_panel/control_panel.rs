@@ -5,23 +5,35 @@ use jsonrpc_core_client::{transports::ipc, RpcError};
 use mmb_rpc::rest_api::{MmbRpcClient, IPC_ADDRESS};
 use mmb_utils::logger::print_info;
 use parking_lot::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 use std::{sync::mpsc, sync::Arc, time::Duration};
 
 use super::endpoints;
+use crate::events_stream::{self, EventsSender, EVENTS_CHANNEL_CAPACITY};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse};
 use actix_web::{dev::Server, App, HttpResponse, HttpServer};
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 
 use actix_web::web::Data;
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::{spawn_future, FutureOutcome, SpawnFutureFlags};
 use tokio::task::JoinHandle;
 
+/// Max time of waiting for in-flight requests completion on stopping
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
 pub type WebMmbRpcClient = Arc<tokio::sync::Mutex<Option<MmbRpcClient>>>;
 pub type DataWebMmbRpcClient = Data<WebMmbRpcClient>;
 
 pub(crate) struct ControlPanel {
     address: String,
     client: WebMmbRpcClient,
+    events_tx: EventsSender,
+    events_cancellation_token: CancellationToken,
+    /// Count of HTTP requests that are being processed now
+    in_flight_requests: Arc<AtomicUsize>,
     server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<()>>>>,
     work_finished_sender: Arc<Mutex<Option<oneshot::Sender<Result<()>>>>>,
     work_finished_receiver: Arc<Mutex<Option<oneshot::Receiver<Result<()>>>>>,
@@ -31,10 +43,14 @@ impl ControlPanel {
     pub(crate) async fn new(address: &str) -> Arc<Self> {
         let (work_finished_sender, work_finished_receiver) = oneshot::channel();
         let client = Arc::new(tokio::sync::Mutex::new(Self::build_rpc_client().await));
+        let (events_tx, _) = broadcast::channel(EVENTS_CHANNEL_CAPACITY);
 
         Arc::new(Self {
             address: address.to_owned(),
             client,
+            events_tx,
+            events_cancellation_token: CancellationToken::new(),
+            in_flight_requests: Arc::new(AtomicUsize::new(0)),
             server_stopper_tx: Arc::new(Mutex::new(None)),
             work_finished_sender: Arc::new(Mutex::new(Some(work_finished_sender))),
             work_finished_receiver: Arc::new(Mutex::new(Some(work_finished_receiver))),
@@ -48,8 +64,11 @@ impl ControlPanel {
             .ok()
     }
 
-    /// Returned receiver will take a message when shutdown are completed
+    /// Returned receiver will take a message when shutdown are completed.
+    /// In-flight requests are awaited up to `DRAIN_TIMEOUT` before server stopping
     pub(crate) fn stop(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>> {
+        self.events_cancellation_token.cancel();
+
         if let Some(server_stopper_tx) = self.server_stopper_tx.lock().take() {
             if let Err(error) = server_stopper_tx.send(()) {
                 log::error!("Unable to send signal to stop actix server: {}", error);
@@ -66,18 +85,28 @@ impl ControlPanel {
         *self.server_stopper_tx.lock() = Some(server_stopper_tx);
 
         let client = self.client.clone();
+        let events_tx = self.events_tx.clone();
+        let in_flight_requests = self.in_flight_requests.clone();
 
         let server = HttpServer::new(move || {
             let mut webui_dir = std::env::current_dir().expect("Unable get current directory");
             webui_dir.push(r"webui");
 
+            let in_flight_requests = in_flight_requests.clone();
             App::new()
+                .wrap_fn(move |req, srv| count_in_flight(&in_flight_requests, req, srv))
                 .app_data(Data::new(client.clone()))
+                .app_data(Data::new(events_tx.clone()))
                 .service(endpoints::health)
                 .service(endpoints::stop)
                 .service(endpoints::stats)
                 .service(endpoints::get_config)
                 .service(endpoints::set_config)
+                .service(endpoints::list_open_orders)
+                .service(endpoints::cancel_order)
+                .service(endpoints::simulate_order)
+                .service(endpoints::executor_graph)
+                .service(endpoints::events_ws)
                 .service(
                     actix_files::Files::new("/", webui_dir)
                         .use_last_modified(true)
@@ -89,6 +118,8 @@ impl ControlPanel {
         .workers(1)
         .run();
 
+        self.clone().start_events_receiving();
+
         let server_handle = server.handle();
         self.clone()
             .server_stopping(server_handle, server_stopper_rx);
@@ -111,7 +142,7 @@ impl ControlPanel {
                 log::error!("Unable to receive signal to stop actix server: {}", error);
             }
 
-            executor::block_on(server_handle.stop(true));
+            stop_server(server_handle, &self.in_flight_requests);
 
             if let Some(work_finished_sender) = self.work_finished_sender.lock().take() {
                 if work_finished_sender.send(Ok(())).is_err() {
@@ -121,6 +152,20 @@ impl ControlPanel {
         });
     }
 
+    fn start_events_receiving(self: Arc<Self>) {
+        spawn_future(
+            "receive events",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            events_stream::receive_events(
+                self.events_tx.clone(),
+                self.events_cancellation_token.clone(),
+            )
+            .boxed(),
+            |_, _| {},
+            self.events_cancellation_token.clone(),
+        );
+    }
+
     fn start_server(self: Arc<Self>, server: Server) -> JoinHandle<FutureOutcome> {
         spawn_future(
             "start server",
@@ -132,6 +177,59 @@ impl ControlPanel {
     }
 }
 
+/// Decrements counter of in-flight requests when request processing is finished or cancelled
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlightGuard {
+    fn new(in_flight_requests: &Arc<AtomicUsize>) -> Self {
+        in_flight_requests.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(in_flight_requests.clone())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn count_in_flight<S>(
+    in_flight_requests: &Arc<AtomicUsize>,
+    req: ServiceRequest,
+    srv: &S,
+) -> impl std::future::Future<Output = Result<ServiceResponse, actix_web::Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    let guard = InFlightGuard::new(in_flight_requests);
+    let response = srv.call(req);
+    async move {
+        let response = response.await;
+        drop(guard);
+        response
+    }
+}
+
+/// Stop accepting new connections, wait for in-flight requests completion and stop actix server
+fn stop_server(server_handle: ServerHandle, in_flight_requests: &AtomicUsize) {
+    executor::block_on(server_handle.pause());
+
+    let deadline = Instant::now() + DRAIN_TIMEOUT;
+    while in_flight_requests.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            log::warn!(
+                "{} in-flight requests aren't completed in {DRAIN_TIMEOUT:?} so they will be dropped",
+                in_flight_requests.load(Ordering::SeqCst)
+            );
+            break;
+        }
+
+        std::thread::sleep(DRAIN_CHECK_INTERVAL);
+    }
+
+    executor::block_on(server_handle.stop(true));
+}
+
 fn handle_rpc_error(error: RpcError) -> HttpResponse {
     match error {
         RpcError::JsonRpcError(error) => {
@@ -185,3 +283,63 @@ pub async fn send_request(
         try_counter += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    #[actix_web::test]
+    async fn in_flight_request_completed_on_stop() {
+        let in_flight_requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = in_flight_requests.clone();
+        let server = HttpServer::new(move || {
+            let counter = counter.clone();
+            App::new()
+                .wrap_fn(move |req, srv| count_in_flight(&counter, req, srv))
+                .route(
+                    "/slow",
+                    web::get().to(|| async {
+                        // longer than shutdown timeout of actix server
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        "done"
+                    }),
+                )
+        })
+        .bind("127.0.0.1:0")
+        .expect("in test");
+        let address = server.addrs()[0];
+        let server = server.shutdown_timeout(1).workers(1).run();
+        let server_handle = server.handle();
+        let server_task = actix_web::rt::spawn(server);
+
+        let request = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).expect("in test");
+            stream
+                .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .expect("in test");
+            let mut response = String::new();
+            stream.read_to_string(&mut response).expect("in test");
+            response
+        });
+
+        while in_flight_requests.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+        }
+
+        let counter = in_flight_requests.clone();
+        tokio::task::spawn_blocking(move || stop_server(server_handle, &counter))
+            .await
+            .expect("in test");
+
+        let response = request.join().expect("in test");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("done"), "{response}");
+        assert_eq!(in_flight_requests.load(Ordering::SeqCst), 0);
+
+        server_task.await.expect("in test").expect("in test");
+    }
+}
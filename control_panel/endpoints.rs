@@ -1,7 +1,9 @@
-use actix_web::{get, post, web, HttpResponse, Responder};
+use actix_web::web::Data;
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
 use futures::FutureExt;
 
 use crate::control_panel::{send_request, DataWebMmbRpcClient};
+use crate::events_stream::{stream_events, EventsSender};
 
 // New endpoints have to be added as a service for actix server and webui control page. Look at super::control_panel::start() and webui/README.md
 
@@ -41,3 +43,59 @@ pub(super) async fn set_config(body: web::Bytes, client: DataWebMmbRpcClient) ->
 pub(super) async fn stats(client: DataWebMmbRpcClient) -> impl Responder {
     send_request(client, |client| client.stats().boxed()).await
 }
+
+#[get("/orders")]
+pub(super) async fn list_open_orders(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.list_open_orders().boxed()).await
+}
+
+#[post("/orders/{client_order_id}/cancel")]
+pub(super) async fn cancel_order(
+    client_order_id: web::Path<String>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let client_order_id = client_order_id.into_inner();
+    send_request(client, move |client| {
+        client.cancel_order(client_order_id.clone()).boxed()
+    })
+    .await
+}
+
+#[post("/orders/simulate")]
+pub(super) async fn simulate_order(
+    body: web::Bytes,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let order = match String::from_utf8(body.to_vec()) {
+        Ok(order) => order,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Failed to convert input order({body:?}) to utf8 string: {err}",
+            ))
+        }
+    };
+
+    send_request(client, move |client| {
+        client.simulate_order(order.clone()).boxed()
+    })
+    .await
+}
+
+/// Price slots and orders of DispositionExecutor in Graphviz DOT format
+#[get("/executor/graph")]
+pub(super) async fn executor_graph(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.get_executor_graph().boxed()).await
+}
+
+/// WebSocket that streams order events of trading engine in JSON format
+#[get("/events/ws")]
+pub(super) async fn events_ws(
+    req: HttpRequest,
+    body: web::Payload,
+    events_tx: Data<EventsSender>,
+) -> actix_web::Result<HttpResponse> {
+    let (response, session, msg_stream) = actix_ws::handle(&req, body)?;
+    actix_web::rt::spawn(stream_events(session, msg_stream, events_tx.subscribe()));
+
+    Ok(response)
+}
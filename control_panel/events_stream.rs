@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use actix_ws::{Message, MessageStream, Session};
+use anyhow::Result;
+use futures::StreamExt;
+use mmb_utils::cancellation_token::CancellationToken;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::control_panel::ControlPanel;
+
+/// Count of events that are kept for every WebSocket client.
+/// Oldest events are dropped for clients that can't receive events in time
+pub(crate) const EVENTS_CHANNEL_CAPACITY: usize = 1_000;
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub(crate) type EventsSender = broadcast::Sender<String>;
+
+/// Subscribes to order events of trading engine and broadcasts them to all WebSocket clients.
+/// Subscription is restored after trading engine restart
+pub(crate) async fn receive_events(
+    events_tx: EventsSender,
+    cancellation_token: CancellationToken,
+) -> Result<()> {
+    loop {
+        tokio::select! {
+            _ = receive_events_until_disconnect(&events_tx) => {}
+            _ = cancellation_token.when_cancelled() => return Ok(()),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_INTERVAL) => {}
+            _ = cancellation_token.when_cancelled() => return Ok(()),
+        }
+    }
+}
+
+async fn receive_events_until_disconnect(events_tx: &EventsSender) {
+    // Separate connection to not block requests of endpoints while waiting for events
+    let client = match ControlPanel::build_rpc_client().await {
+        Some(client) => client,
+        None => return,
+    };
+
+    let mut events = match client.subscribe_events() {
+        Ok(events) => events,
+        Err(err) => {
+            log::warn!("Failed to subscribe to events of trading engine: {err}");
+            return;
+        }
+    };
+
+    while let Some(event) = events.next().await {
+        match event {
+            // Error means there are no connected clients, so the event can be skipped
+            Ok(event) => {
+                let _ = events_tx.send(event);
+            }
+            Err(err) => {
+                log::warn!("Failed to receive event from trading engine: {err}");
+                return;
+            }
+        }
+    }
+}
+
+/// Sends events to WebSocket client until connection is closed
+pub(crate) async fn stream_events(
+    mut session: Session,
+    mut msg_stream: MessageStream,
+    mut events_rx: broadcast::Receiver<String>,
+) {
+    loop {
+        tokio::select! {
+            event = events_rx.recv() => match event {
+                Ok(event) => {
+                    if session.text(event).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped_count)) => {
+                    log::warn!("WebSocket client is too slow, {skipped_count} events are skipped")
+                }
+                Err(RecvError::Closed) => break,
+            },
+            msg = msg_stream.next() => match msg {
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = session.close(None).await;
+}
@@ -23,12 +23,15 @@ use futures::FutureExt;
 use mmb_utils::{
     infrastructure::init_infrastructure,
     logger::print_info,
-    panic::{PanicState, HOOK_IS_NOT_SET, PANIC_DETECTED_IN_NO_PANIC_STATE, PANIC_STATE},
+    panic::{
+        with_backtrace, PanicState, HOOK_IS_NOT_SET, PANIC_DETECTED_IN_NO_PANIC_STATE, PANIC_STATE,
+    },
 };
 use tokio::signal;
 
 mod control_panel;
 mod endpoints;
+mod events_stream;
 
 static ADDRESS: &str = "127.0.0.1:8080";
 
@@ -61,9 +64,13 @@ async fn main() {
     if (AssertUnwindSafe(control_panel_run()).catch_unwind().await).is_err() {
         PANIC_STATE.with(|panic_state| {
             match &*panic_state.borrow() {
-                PanicState::PanicHookIsNotSet => log::warn!("{HOOK_IS_NOT_SET}"),
-                PanicState::NoPanic => log::error!("{PANIC_DETECTED_IN_NO_PANIC_STATE}"),
-                PanicState::PanicHappened(msg) => log::error!("{msg}"),
+                PanicState::PanicHookIsNotSet => log::warn!("{}", with_backtrace(HOOK_IS_NOT_SET)),
+                PanicState::NoPanic => {
+                    log::error!("{}", with_backtrace(PANIC_DETECTED_IN_NO_PANIC_STATE))
+                }
+                PanicState::PanicHappened { message, backtrace } => {
+                    log::error!("{message}\n{backtrace}")
+                }
             };
         });
     }
@@ -10,6 +10,7 @@ use mmb_utils::{nothing_to_do, DateTime};
 use mockall_double::double;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
+use thiserror::Error;
 
 use crate::balance::balance_position_model::BalancePositionModel;
 use crate::balance::manager::approved_part::ApprovedPart;
@@ -19,7 +20,7 @@ use crate::balance::manager::balance_reservation::BalanceReservation;
 use crate::balance::manager::balances::Balances;
 use crate::balance::manager::position_change::PositionChange;
 use crate::balance::{
-    balance_reservation_storage::BalanceReservationStorage,
+    balance_reservation_storage::{BalanceReservationStorage, ReservationLimitExceeded},
     virtual_balance_holder::VirtualBalanceHolder,
 };
 use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
@@ -37,6 +38,14 @@ use mmb_domain::order::snapshot::{ClientOrderFillId, ClientOrderId, OrderSide};
 
 use super::balance_reservation_preset::BalanceReservationPreset;
 
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+pub enum ReserveError {
+    #[error("Not enough balance")]
+    NotEnoughBalance,
+    #[error(transparent)]
+    ReservationLimitExceeded(#[from] ReservationLimitExceeded),
+}
+
 pub(super) struct CanReserveResult {
     can_reserve: bool,
     preset: BalanceReservationPreset,
@@ -57,6 +66,9 @@ pub(crate) struct BalanceReservationManager {
     pub balance_reservation_storage: BalanceReservationStorage,
 
     pub(crate) is_call_from_clone: bool,
+
+    /// Expiry assigned to new reservations. Reservations never expire if `None`
+    reservation_expiry: Option<chrono::Duration>,
 }
 
 impl BalanceReservationManager {
@@ -71,9 +83,14 @@ impl BalanceReservationManager {
             ),
             balance_reservation_storage: BalanceReservationStorage::new(),
             is_call_from_clone: false,
+            reservation_expiry: None,
         }
     }
 
+    pub fn set_reservation_expiry(&mut self, reservation_expiry: Option<chrono::Duration>) {
+        self.reservation_expiry = reservation_expiry;
+    }
+
     pub fn exchanges_by_id(&self) -> &HashMap<ExchangeAccountId, Arc<Exchange>> {
         self.currency_pair_to_symbol_converter.exchanges_by_id()
     }
@@ -673,6 +690,93 @@ impl BalanceReservationManager {
         position_in_amount_currency
     }
 
+    /// Signed net position of derivative in amount currency: positive is long, negative is short.
+    /// `None` for not derivative symbols or if there were no fills
+    pub fn get_total_net_position(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Decimal> {
+        let symbol = self
+            .currency_pair_to_symbol_converter
+            .get_symbol(exchange_account_id, currency_pair);
+        if !symbol.is_derivative {
+            return None;
+        }
+
+        self.position_by_fill_amount_in_amount_currency
+            .get(exchange_account_id, currency_pair)
+    }
+
+    /// Signed position in amount currency accumulated by fills: positive is long, negative is short.
+    /// Unlike `get_total_net_position` it's calculated for spot too as amount bought minus amount sold
+    pub fn get_net_position_by_fills(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Decimal {
+        let symbol = self
+            .currency_pair_to_symbol_converter
+            .get_symbol(exchange_account_id, currency_pair);
+
+        let position = self
+            .position_by_fill_amount_in_amount_currency
+            .get(exchange_account_id, currency_pair)
+            .unwrap_or(dec!(0));
+
+        // sign of derivative position is already normalized on fill in `handle_position_fill_amount_change`
+        match !symbol.is_derivative && symbol.amount_currency_code == symbol.base_currency_code() {
+            true => -position,
+            false => position,
+        }
+    }
+
+    /// Change of signed net position of derivative in amount currency since last reset.
+    /// `None` for not derivative symbols or if there were no fills
+    pub fn get_net_position(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Decimal> {
+        let symbol = self
+            .currency_pair_to_symbol_converter
+            .get_symbol(exchange_account_id, currency_pair);
+        if !symbol.is_derivative {
+            return None;
+        }
+
+        self.position_by_fill_amount_in_amount_currency
+            .get_since_reset(exchange_account_id, currency_pair)
+    }
+
+    /// Weighted average entry price of derivative position opened by fills.
+    /// `None` for not derivative symbols or if there is no open position
+    pub fn get_average_entry_price(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Price> {
+        let symbol = self
+            .currency_pair_to_symbol_converter
+            .get_symbol(exchange_account_id, currency_pair);
+        if !symbol.is_derivative {
+            return None;
+        }
+
+        self.position_by_fill_amount_in_amount_currency
+            .get_average_entry_price(exchange_account_id, currency_pair)
+    }
+
+    /// Start reporting of net position from zero. Position used for reservations isn't changed
+    pub fn reset_position(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) {
+        self.position_by_fill_amount_in_amount_currency
+            .reset_baseline(exchange_account_id, currency_pair);
+    }
+
     fn unreserve_not_approved_part(
         &mut self,
         reservation_id: ReservationId,
@@ -929,6 +1033,7 @@ impl BalanceReservationManager {
                 request.exchange_account_id,
                 request.currency_pair,
                 position_change,
+                price,
                 client_order_fill_id.clone(),
                 now,
             );
@@ -1305,7 +1410,7 @@ impl BalanceReservationManager {
     ) -> Option<Vec<ReservationId>> {
         let successful_reservations = reserve_parameters
             .iter()
-            .filter_map(|rp| self.try_reserve(rp, explanation).map(|id| (id, rp)))
+            .filter_map(|rp| self.try_reserve(rp, explanation).ok().map(|id| (id, rp)))
             .collect_vec();
 
         if successful_reservations.len() != reserve_parameters.len() {
@@ -1323,7 +1428,7 @@ impl BalanceReservationManager {
         &mut self,
         reserve_parameters: &ReserveParameters,
         explanation: &mut Option<Explanation>,
-    ) -> Option<ReservationId> {
+    ) -> Result<ReservationId, ReserveError> {
         let can_reserve_result = self.can_reserve_core(reserve_parameters, explanation);
         if !can_reserve_result.can_reserve {
             log::info!(
@@ -1336,7 +1441,7 @@ impl BalanceReservationManager {
                 can_reserve_result.old_balance,
                 can_reserve_result.new_balance,
             );
-            return None;
+            return Err(ReserveError::NotEnoughBalance);
         }
 
         let request = BalanceRequest::new(
@@ -1345,7 +1450,7 @@ impl BalanceReservationManager {
             reserve_parameters.symbol.currency_pair(),
             can_reserve_result.preset.reservation_currency_code,
         );
-        let reservation = BalanceReservation::new(
+        let mut reservation = BalanceReservation::new(
             reserve_parameters.configuration_descriptor,
             reserve_parameters.exchange_account_id,
             reserve_parameters.symbol.clone(),
@@ -1358,6 +1463,9 @@ impl BalanceReservationManager {
             can_reserve_result.preset.cost_in_amount_currency_code,
             can_reserve_result.preset.reservation_currency_code,
         );
+        reservation.expiration_time = self
+            .reservation_expiry
+            .map(|expiry| time_manager::now() + expiry);
 
         let reservation_id = ReservationId::generate();
         log::info!(
@@ -1371,8 +1479,14 @@ impl BalanceReservationManager {
             can_reserve_result.new_balance,
         );
 
-        self.balance_reservation_storage
-            .add(reservation_id, reservation);
+        if let Err(err) = self
+            .balance_reservation_storage
+            .try_add(reservation_id, reservation)
+        {
+            log::error!("Failed to reserve {reservation_id:?}: {err}");
+            explanation.with_reason(|| err.to_string());
+            return Err(err.into());
+        }
         self.add_reserved_amount_expected(
             &request,
             reservation_id,
@@ -1381,7 +1495,7 @@ impl BalanceReservationManager {
         );
 
         log::info!("Reserved successfully");
-        Some(reservation_id)
+        Ok(reservation_id)
     }
 
     fn can_reserve_core(
@@ -1,20 +1,38 @@
 use std::collections::HashMap;
 
+use anyhow::{Context, Result};
 use itertools::Itertools;
 use mmb_utils::infrastructure::WithExpect;
+use thiserror::Error;
 
 use crate::balance::manager::balance_reservation::BalanceReservation;
 use mmb_domain::order::snapshot::ReservationId;
+
+/// Protects from memory exhaustion by stale reservations in case of bugs in strategy or exchange client
+const DEFAULT_MAX_RESERVATIONS: usize = 10_000;
+
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+#[error("Reservation count limit {max_reservations} is exceeded")]
+pub struct ReservationLimitExceeded {
+    pub max_reservations: usize,
+}
+
 #[derive(Clone)]
 pub(crate) struct BalanceReservationStorage {
     reserved_balances_by_id: HashMap<ReservationId, BalanceReservation>,
+    max_reservations: usize,
     pub is_call_from_clone: bool,
 }
 
 impl BalanceReservationStorage {
     pub fn new() -> Self {
+        Self::with_max_reservations(DEFAULT_MAX_RESERVATIONS)
+    }
+
+    pub fn with_max_reservations(max_reservations: usize) -> Self {
         Self {
             reserved_balances_by_id: HashMap::new(),
+            max_reservations,
             is_call_from_clone: false,
         }
     }
@@ -29,15 +47,52 @@ impl BalanceReservationStorage {
         self.update_metrics();
     }
 
+    /// Add a new reservation if count of reservations doesn't reach `max_reservations`
+    pub fn try_add(
+        &mut self,
+        reservation_id: ReservationId,
+        reservation: BalanceReservation,
+    ) -> Result<(), ReservationLimitExceeded> {
+        if self.reserved_balances_by_id.len() >= self.max_reservations {
+            return Err(ReservationLimitExceeded {
+                max_reservations: self.max_reservations,
+            });
+        }
+
+        self.add(reservation_id, reservation);
+        Ok(())
+    }
+
     pub fn remove(&mut self, reservation_id: ReservationId) {
         self.reserved_balances_by_id.remove(&reservation_id);
         self.update_metrics();
     }
 
+    /// Snapshot of reservations in MessagePack format to restore them after restart
+    pub fn serialize_to_bytes(&self) -> Result<Vec<u8>> {
+        rmp_serde::to_vec_named(&self.reserved_balances_by_id)
+            .context("Failed to serialize balance reservations")
+    }
+
+    pub fn deserialize_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let reserved_balances_by_id =
+            rmp_serde::from_slice(bytes).context("Failed to deserialize balance reservations")?;
+
+        Ok(Self {
+            reserved_balances_by_id,
+            max_reservations: DEFAULT_MAX_RESERVATIONS,
+            is_call_from_clone: false,
+        })
+    }
+
     pub fn get_all_raw_reservations(&self) -> &HashMap<ReservationId, BalanceReservation> {
         &self.reserved_balances_by_id
     }
 
+    pub fn reservation_count(&self) -> usize {
+        self.reserved_balances_by_id.len()
+    }
+
     pub fn get_reservation_ids(&self) -> Vec<ReservationId> {
         self.reserved_balances_by_id.keys().cloned().collect_vec()
     }
@@ -78,3 +133,123 @@ impl BalanceReservationStorage {
         //TODO: should be implemented
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::manager::approved_part::ApprovedPart;
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+    use chrono::Utc;
+    use mmb_domain::exchanges::symbol::{Precision, Symbol};
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::{Amount, ClientOrderId, OrderSide};
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn reservation(amount: Amount) -> BalanceReservation {
+        let symbol = Arc::new(Symbol::new(
+            false,
+            "BTC".into(),
+            "btc".into(),
+            "USDT".into(),
+            "usdt".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "btc".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ));
+
+        BalanceReservation::new(
+            ConfigurationDescriptor::new("test_strategy".into(), "Binance;btc/usdt".into()),
+            ExchangeAccountId::new("Binance", 0),
+            symbol,
+            OrderSide::Buy,
+            dec!(100),
+            amount,
+            dec!(0),
+            dec!(100) * amount,
+            "usdt".into(),
+        )
+    }
+
+    fn approve(reservation: &mut BalanceReservation, client_order_id: &str, amount: Amount) {
+        let client_order_id = ClientOrderId::new(client_order_id.into());
+        let _ = reservation.approved_parts.insert(
+            client_order_id.clone(),
+            ApprovedPart::new(Utc::now(), client_order_id, amount),
+        );
+        reservation.not_approved_amount -= amount;
+    }
+
+    fn to_json(storage: &BalanceReservationStorage) -> serde_json::Value {
+        serde_json::to_value(storage.get_all_raw_reservations()).expect("in test")
+    }
+
+    #[test]
+    fn round_trip_serialization() {
+        let mut storage = BalanceReservationStorage::new();
+
+        let mut approved = reservation(dec!(2));
+        approve(&mut approved, "approved", dec!(2));
+        storage.add(ReservationId::generate(), approved);
+
+        let mut partially_approved = reservation(dec!(2));
+        approve(&mut partially_approved, "partially_approved", dec!(1));
+        storage.add(ReservationId::generate(), partially_approved);
+
+        let mut unreserved = reservation(dec!(2));
+        approve(&mut unreserved, "unreserved", dec!(2));
+        unreserved.unreserved_amount = dec!(2);
+        for approved_part in unreserved.approved_parts.values_mut() {
+            approved_part.is_canceled = true;
+            approved_part.unreserved_amount = dec!(2);
+        }
+        storage.add(ReservationId::generate(), unreserved);
+
+        let bytes = storage.serialize_to_bytes().expect("in test");
+        let restored = BalanceReservationStorage::deserialize_from_bytes(&bytes).expect("in test");
+
+        assert_eq!(restored.get_all_raw_reservations().len(), 3);
+        assert_eq!(to_json(&restored), to_json(&storage));
+        assert!(!restored.is_call_from_clone);
+    }
+
+    #[test]
+    fn round_trip_empty_storage() {
+        let storage = BalanceReservationStorage::new();
+
+        let bytes = storage.serialize_to_bytes().expect("in test");
+        let restored = BalanceReservationStorage::deserialize_from_bytes(&bytes).expect("in test");
+
+        assert!(restored.get_all_raw_reservations().is_empty());
+    }
+
+    #[test]
+    fn reservation_count_is_limited() {
+        let max_reservations = 3;
+        let mut storage = BalanceReservationStorage::with_max_reservations(max_reservations);
+
+        for _ in 0..max_reservations {
+            storage
+                .try_add(ReservationId::generate(), reservation(dec!(1)))
+                .expect("in test");
+        }
+        assert_eq!(storage.reservation_count(), max_reservations);
+
+        let error = storage
+            .try_add(ReservationId::generate(), reservation(dec!(1)))
+            .expect_err("in test");
+        assert_eq!(error, ReservationLimitExceeded { max_reservations });
+        assert_eq!(storage.reservation_count(), max_reservations);
+    }
+
+    #[test]
+    fn deserialize_invalid_bytes() {
+        assert!(BalanceReservationStorage::deserialize_from_bytes(&[0xc1]).is_err());
+    }
+}
@@ -151,6 +151,7 @@ mod tests {
         create_balance_change, create_balance_change_by_market_account_id, market_account_id,
     };
     use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
     use mmb_domain::market::{CurrencyPair, ExchangeAccountId, ExchangeId};
     use mmb_domain::order::snapshot::ClientOrderFillId;
 
@@ -160,8 +161,7 @@ mod tests {
     fn test_position_change_before_period() {
         let (mut balance_manager, _locker) = BalanceManager::init_mock();
 
-        let seconds_offset_in_mock = Arc::new(Mutex::new(0u32));
-        let (_time_manager_context, _tm_locker) = time::tests::init_mock(seconds_offset_in_mock);
+        let (_time_manager_context, _tm_locker) = time::tests::init_mock(MockClock::default());
         let expect_position_change = Some(PositionChange::new(
             ClientOrderFillId::new("client_order_id_test".into()),
             time_manager::now() - Duration::minutes(30),
@@ -199,8 +199,7 @@ mod tests {
     fn test_get_items() {
         let (mut balance_manager, _locker) = BalanceManager::init_mock();
 
-        let seconds_offset_in_mock = Arc::new(Mutex::new(0u32));
-        let (_time_manager_context, _tm_locker) = time::tests::init_mock(seconds_offset_in_mock);
+        let (_time_manager_context, _tm_locker) = time::tests::init_mock(MockClock::default());
 
         balance_manager
             .expect_get_last_position_change_before_period()
@@ -17,6 +17,7 @@ use crate::services::usd_convertion::usd_converter::UsdConverter;
 
 use crate::balance::changes::{
     balance_changes_accumulator::BalanceChangeAccumulator, profit_balance_changes_calculator,
+    profit_balance_changes_calculator::ProfitBalanceChangesCalculator,
     profit_loss_balance_change::ProfitLossBalanceChange,
 };
 
@@ -24,6 +25,7 @@ use super::balance_change_period_selector::BalanceChangePeriodSelector;
 
 pub struct BalanceChangeUsdPeriodicCalculator {
     balance_change_period_selector: Arc<Mutex<BalanceChangePeriodSelector>>,
+    profit_calculator: Mutex<ProfitBalanceChangesCalculator>,
 }
 
 impl BalanceChangeUsdPeriodicCalculator {
@@ -33,6 +35,7 @@ impl BalanceChangeUsdPeriodicCalculator {
                 period,
                 balance_manager,
             ),
+            profit_calculator: Mutex::new(ProfitBalanceChangesCalculator::with_window(period)),
         })
     }
 
@@ -65,6 +68,11 @@ impl BalanceChangeUsdPeriodicCalculator {
         join_all(actions).await.iter().sum()
     }
 
+    /// Rolling usd profit of all markets over the last `period`, which can't be longer than `Self::period`
+    pub fn period_pnl(&self, period: Duration) -> Amount {
+        self.profit_calculator.lock().period_pnl(period)
+    }
+
     pub fn period(&self) -> Duration {
         self.balance_change_period_selector.lock().period
     }
@@ -96,5 +104,6 @@ impl BalanceChangeAccumulator for BalanceChangeUsdPeriodicCalculator {
         self.balance_change_period_selector
             .lock()
             .add(balance_change);
+        self.profit_calculator.lock().add(balance_change);
     }
 }
@@ -1,8 +1,18 @@
+use std::collections::HashSet;
+
 use async_trait::async_trait;
 
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::Amount;
 use mmb_utils::cancellation_token::CancellationToken;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
 
 use super::profit_loss_balance_change::ProfitLossBalanceChange;
+use crate::balance::manager::balance_request::BalanceRequest;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
 
 #[async_trait]
 pub(crate) trait BalanceChangeAccumulator {
@@ -15,3 +25,127 @@ pub(crate) trait BalanceChangeAccumulator {
         cancellation_token: CancellationToken,
     );
 }
+
+pub(crate) const DEFAULT_DRIFT_WARN_THRESHOLD: Decimal = dec!(0.05);
+
+/// Warning that virtual balance drifted away from balance received from exchange
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceOverflowWarning {
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub currency_code: CurrencyCode,
+    /// Virtual balance before exchange balance update including not approved reserved amounts
+    pub virtual_balance: Amount,
+    pub exchange_balance: Amount,
+    /// Relative difference `abs(virtual - exchange) / exchange`
+    pub drift: Decimal,
+    pub drift_warn_threshold: Decimal,
+}
+
+impl_event!(BalanceOverflowWarning, "balance_overflow_warnings");
+
+/// Tracks drift of virtual balances from balances received on exchange balance updates.
+/// Warning is produced only when drift crosses the threshold, so a single breach isn't reported
+/// on every subsequent update until drift goes back below the threshold.
+#[derive(Debug, Clone)]
+pub(crate) struct BalanceChangesAccumulator {
+    drift_warn_threshold: Decimal,
+    breached_requests: HashSet<BalanceRequest>,
+}
+
+impl BalanceChangesAccumulator {
+    pub fn new(drift_warn_threshold: Decimal) -> Self {
+        Self {
+            drift_warn_threshold,
+            breached_requests: HashSet::new(),
+        }
+    }
+
+    pub fn drift_warn_threshold(&self) -> Decimal {
+        self.drift_warn_threshold
+    }
+
+    pub fn accumulate(
+        &mut self,
+        request: &BalanceRequest,
+        virtual_balance: Amount,
+        exchange_balance: Amount,
+    ) -> Option<BalanceOverflowWarning> {
+        if exchange_balance.is_zero() {
+            self.breached_requests.remove(request);
+            return None;
+        }
+
+        let drift = ((virtual_balance - exchange_balance) / exchange_balance).abs();
+        if drift <= self.drift_warn_threshold {
+            self.breached_requests.remove(request);
+            return None;
+        }
+
+        if !self.breached_requests.insert(request.clone()) {
+            return None;
+        }
+
+        Some(BalanceOverflowWarning {
+            configuration_descriptor: request.configuration_descriptor,
+            exchange_account_id: request.exchange_account_id,
+            currency_pair: request.currency_pair,
+            currency_code: request.currency_code,
+            virtual_balance,
+            exchange_balance,
+            drift,
+            drift_warn_threshold: self.drift_warn_threshold,
+        })
+    }
+}
+
+impl Default for BalanceChangesAccumulator {
+    fn default() -> Self {
+        Self::new(DEFAULT_DRIFT_WARN_THRESHOLD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance_request() -> BalanceRequest {
+        BalanceRequest::new(
+            ConfigurationDescriptor::new("service".into(), "config".into()),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "eth".into()),
+            "btc".into(),
+        )
+    }
+
+    #[test]
+    fn no_warning_when_drift_under_threshold() {
+        let mut accumulator = BalanceChangesAccumulator::default();
+
+        let warning = accumulator.accumulate(&balance_request(), dec!(104), dec!(100));
+
+        assert_eq!(warning, None);
+    }
+
+    #[test]
+    fn warning_emitted_once_per_breach() {
+        let mut accumulator = BalanceChangesAccumulator::default();
+        let request = balance_request();
+
+        let warning = accumulator
+            .accumulate(&request, dec!(110), dec!(100))
+            .expect("warning should be emitted on 10% drift");
+        assert_eq!(warning.drift, dec!(0.1));
+        assert_eq!(warning.drift_warn_threshold, DEFAULT_DRIFT_WARN_THRESHOLD);
+
+        assert_eq!(accumulator.accumulate(&request, dec!(110), dec!(100)), None);
+        assert_eq!(accumulator.accumulate(&request, dec!(90), dec!(100)), None);
+
+        // drift is back under threshold, so next breach should be reported again
+        assert_eq!(accumulator.accumulate(&request, dec!(100), dec!(100)), None);
+        assert!(accumulator
+            .accumulate(&request, dec!(110), dec!(100))
+            .is_some());
+    }
+}
@@ -35,16 +35,10 @@ impl BalanceChangesCalculator {
             .currency_pair_to_symbol_converter
             .get_symbol(order.header.exchange_account_id, order.header.currency_pair);
 
-        self.get_balance_changes_calculator_results(
-            configuration_descriptor,
-            order,
-            order_fill,
-            symbol,
-        )
+        Self::get_balance_changes_by_symbol(configuration_descriptor, order, order_fill, symbol)
     }
 
-    fn get_balance_changes_calculator_results(
-        &self,
+    pub fn get_balance_changes_by_symbol(
         configuration_descriptor: ConfigurationDescriptor,
         order: &OrderSnapshot,
         order_fill: &OrderFill,
@@ -1,8 +1,11 @@
 use std::{sync::Arc, time::Duration};
 
+use crate::infrastructure::spawn_future;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use chrono::NaiveDate;
+use mmb_domain::market::MarketAccountId;
 use mmb_domain::order::fill::OrderFill;
-use mmb_domain::order::snapshot::{ClientOrderFillId, OrderSnapshot};
+use mmb_domain::order::snapshot::{Amount, ClientOrderFillId, OrderSnapshot};
 use mmb_utils::{
     cancellation_token::CancellationToken,
     infrastructure::SpawnFutureFlags,
@@ -29,6 +32,7 @@ use crate::{
 use super::{
     balance_change_calculator_result::BalanceChangesCalculatorResult,
     balance_changes_calculator::BalanceChangesCalculator,
+    daily_pnl_summary::{sleep_until_next_day, DailyPnlSummaryCollector},
     profit_loss_balance_change::ProfitLossBalanceChange,
     profit_loss_stopper_service::ProfitLossStopperService,
 };
@@ -37,6 +41,8 @@ use super::{
 enum BalanceChangeServiceEvent {
     OnTimer,
     BalanceChange(BalanceChange),
+    /// UTC day with specified date has ended
+    DayEnd(NaiveDate),
 }
 
 #[derive(Debug)]
@@ -44,6 +50,8 @@ struct BalanceChange {
     pub balance_changes: BalanceChangesCalculatorResult,
     pub client_order_fill_id: ClientOrderFillId,
     pub change_date: DateTime,
+    pub market_account_id: MarketAccountId,
+    pub fill_amount: Amount,
 }
 
 impl BalanceChange {
@@ -51,11 +59,15 @@ impl BalanceChange {
         balance_changes: BalanceChangesCalculatorResult,
         client_order_fill_id: ClientOrderFillId,
         change_date: DateTime,
+        market_account_id: MarketAccountId,
+        fill_amount: Amount,
     ) -> Self {
         Self {
             balance_changes,
             client_order_fill_id,
             change_date,
+            market_account_id,
+            fill_amount,
         }
     }
 }
@@ -66,6 +78,7 @@ pub struct BalanceChangesService {
     tx_event: mpsc::Sender<BalanceChangeServiceEvent>,
     balance_changes_accumulators: Vec<Arc<dyn BalanceChangeAccumulator + Send + Sync>>,
     profit_loss_stopper_service: Arc<ProfitLossStopperService>,
+    daily_pnl_summary: Arc<DailyPnlSummaryCollector>,
     balance_changes_calculator: BalanceChangesCalculator,
     lifetime_manager: Arc<AppLifetimeManager>,
     event_recorder: Arc<EventRecorder>,
@@ -80,9 +93,11 @@ impl BalanceChangesService {
         event_recorder: Arc<EventRecorder>,
     ) -> Arc<Self> {
         let (tx_event, rx_event) = mpsc::channel(20_000);
-        let balance_changes_accumulators =
-            vec![profit_loss_stopper_service.clone()
-                as Arc<dyn BalanceChangeAccumulator + Send + Sync>];
+        let daily_pnl_summary = Arc::new(DailyPnlSummaryCollector::default());
+        let balance_changes_accumulators = vec![
+            profit_loss_stopper_service.clone() as Arc<dyn BalanceChangeAccumulator + Send + Sync>,
+            daily_pnl_summary.clone(),
+        ];
 
         let this = Arc::new(Self {
             usd_converter,
@@ -90,6 +105,7 @@ impl BalanceChangesService {
             tx_event,
             balance_changes_accumulators,
             profit_loss_stopper_service,
+            daily_pnl_summary,
             balance_changes_calculator: BalanceChangesCalculator::new(
                 currency_pair_to_symbol_converter,
             ),
@@ -97,6 +113,30 @@ impl BalanceChangesService {
             event_recorder,
         });
 
+        let on_day_end = {
+            let tx_event = this.tx_event.clone();
+            let stop_token = lifetime_manager.stop_token();
+            async move {
+                loop {
+                    tokio::select! {
+                        date = sleep_until_next_day() => {
+                            tx_event
+                                .send_expected_async(BalanceChangeServiceEvent::DayEnd(date))
+                                .await;
+                        }
+                        _ = stop_token.when_cancelled() => break,
+                    }
+                }
+
+                Ok(())
+            }
+        };
+        spawn_future(
+            "BalanceChangesService daily P&L summary",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            on_day_end,
+        );
+
         let on_timer_tick = {
             let this = this.clone();
             move || {
@@ -151,6 +191,16 @@ impl BalanceChangesService {
                         .check_for_limit(&self.usd_converter, cancellation_token.clone())
                         .await;
                 }
+                BalanceChangeServiceEvent::DayEnd(date) => {
+                    self.daily_pnl_summary
+                        .save_summaries(
+                            date,
+                            &self.usd_converter,
+                            &self.event_recorder,
+                            cancellation_token.clone(),
+                        )
+                        .await;
+                }
             }
         }
     }
@@ -189,6 +239,8 @@ impl BalanceChangesService {
                 .save(profit_loss_balance_change)
                 .expect("Failure save profit_loss_balance_change");
         }
+        self.daily_pnl_summary
+            .add_trade(event.market_account_id, event.fill_amount);
         self.profit_loss_stopper_service
             .check_for_limit(&self.usd_converter, cancellation_token)
             .await;
@@ -223,6 +275,8 @@ impl BalanceChangesService {
             balance_changes,
             client_order_fill_id,
             time_manager::now(),
+            order.market_account_id(),
+            order_fill.amount(),
         ));
 
         self.tx_event.send_expected(balance_changes_event);
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::mem;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::Amount;
+use mmb_utils::cancellation_token::CancellationToken;
+use mockall_double::double;
+use parking_lot::Mutex;
+use serde::Serialize;
+
+#[double]
+use crate::misc::time::time_manager;
+#[double]
+use crate::services::usd_convertion::usd_converter::UsdConverter;
+
+use super::balance_changes_accumulator::BalanceChangeAccumulator;
+use super::profit_loss_balance_change::ProfitLossBalanceChange;
+use crate::database::events::recorder::EventRecorder;
+
+/// P&L of a market for one UTC day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct DailyPnlSummary {
+    pub date: NaiveDate,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    /// Sum of realized and unrealized P&L
+    pub pnl_usd: Amount,
+    /// USD balance changes calculated with prices at the moment of trades
+    pub realized_pnl_usd: Amount,
+    /// Revaluation of balance changes of the day with prices at the end of the day
+    pub unrealized_pnl_usd: Amount,
+    pub trade_count: u64,
+    pub volume_base: Amount,
+}
+
+impl_event!(DailyPnlSummary, "daily_pnl_summaries");
+
+#[derive(Debug, Default)]
+struct MarketDailyStats {
+    trade_count: u64,
+    volume_base: Amount,
+    realized_pnl_usd: Amount,
+    balance_changes: HashMap<CurrencyCode, Amount>,
+}
+
+/// Collects trades and balance changes by market until the end of UTC day
+#[derive(Debug, Default)]
+pub(crate) struct DailyPnlSummaryCollector {
+    stats: Mutex<HashMap<MarketAccountId, MarketDailyStats>>,
+}
+
+impl DailyPnlSummaryCollector {
+    pub fn add_trade(&self, market_account_id: MarketAccountId, amount: Amount) {
+        let mut stats = self.stats.lock();
+        let market_stats = stats.entry(market_account_id).or_default();
+        market_stats.trade_count += 1;
+        market_stats.volume_base += amount.abs();
+    }
+
+    /// Save summaries of all markets traded since the previous call and start collecting the next day
+    pub async fn save_summaries(
+        &self,
+        date: NaiveDate,
+        usd_converter: &UsdConverter,
+        event_recorder: &EventRecorder,
+        cancellation_token: CancellationToken,
+    ) {
+        let stats = mem::take(&mut *self.stats.lock());
+        for (market_account_id, market_stats) in stats {
+            let unrealized_pnl_usd = match Self::calculate_over_market(
+                &market_stats.balance_changes,
+                usd_converter,
+                cancellation_token.clone(),
+            )
+            .await
+            {
+                Some(over_market_pnl_usd) => over_market_pnl_usd - market_stats.realized_pnl_usd,
+                None => {
+                    log::warn!("Unable to calculate unrealized P&L of {market_account_id:?} for {date}, only realized P&L is saved");
+                    Amount::ZERO
+                }
+            };
+
+            let summary = DailyPnlSummary {
+                date,
+                exchange_account_id: market_account_id.exchange_account_id,
+                currency_pair: market_account_id.currency_pair,
+                pnl_usd: market_stats.realized_pnl_usd + unrealized_pnl_usd,
+                realized_pnl_usd: market_stats.realized_pnl_usd,
+                unrealized_pnl_usd,
+                trade_count: market_stats.trade_count,
+                volume_base: market_stats.volume_base,
+            };
+
+            if let Err(err) = event_recorder.save(summary) {
+                log::error!(
+                    "Failed to save daily P&L summary of {market_account_id:?} for {date}: {err:?}"
+                );
+            }
+        }
+    }
+
+    async fn calculate_over_market(
+        balance_changes: &HashMap<CurrencyCode, Amount>,
+        usd_converter: &UsdConverter,
+        cancellation_token: CancellationToken,
+    ) -> Option<Amount> {
+        let mut over_market_pnl_usd = Amount::ZERO;
+        for (&currency_code, &balance_change) in balance_changes {
+            over_market_pnl_usd += usd_converter
+                .convert_amount(currency_code, balance_change, cancellation_token.clone())
+                .await?;
+        }
+
+        Some(over_market_pnl_usd)
+    }
+}
+
+#[async_trait]
+impl BalanceChangeAccumulator for DailyPnlSummaryCollector {
+    fn add_balance_change(&self, balance_change: &ProfitLossBalanceChange) {
+        let mut stats = self.stats.lock();
+        let market_stats = stats.entry(balance_change.market_account_id).or_default();
+        market_stats.realized_pnl_usd += balance_change.usd_balance_change;
+        *market_stats
+            .balance_changes
+            .entry(balance_change.currency_code)
+            .or_default() += balance_change.balance_change;
+    }
+
+    async fn load_data(&self, _cancellation_token: CancellationToken) {}
+}
+
+/// Wait until the next UTC midnight and return the date of the day that has just ended
+pub(crate) async fn sleep_until_next_day() -> NaiveDate {
+    let now = time_manager::now().naive_utc();
+    let date = now.date();
+    let next_midnight = date.succ().and_hms(0, 0, 0);
+    let delay = (next_midnight - now).to_std().unwrap_or_default();
+
+    tokio::time::sleep_until(tokio::time::Instant::now() + delay).await;
+
+    date
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mmb_domain::order::snapshot::ClientOrderFillId;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::balance::changes::profit_loss_stopper::test::{
+        create_balance_change, market_account_id,
+    };
+    use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
+
+    fn usd_converter() -> (UsdConverter, parking_lot::ReentrantMutexGuard<'static, ()>) {
+        let (mut usd_converter, locker) = UsdConverter::init_mock();
+        // BTC price has doubled since trades
+        usd_converter
+            .expect_convert_amount()
+            .returning(|_, amount, _| Some(amount * dec!(2)));
+        (usd_converter, locker)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn summary_saved_after_midnight() {
+        let clock = MockClock::default();
+        let (_time_manager_mock, _tm_locker) = time::tests::init_mock(clock.clone());
+        let (usd_converter, _usd_converter_locker) = usd_converter();
+        let (event_recorder, mut events_rx) = EventRecorder::with_channel();
+
+        let before_midnight = Duration::from_secs(23 * 60 * 60 + 59 * 60);
+        clock.advance_by(before_midnight);
+
+        let collector = DailyPnlSummaryCollector::default();
+        for (i, usd_balance_change) in [dec!(1), dec!(-0.5)].into_iter().enumerate() {
+            // usd_balance_change of test balance change is doubled
+            collector.add_balance_change(&create_balance_change(
+                usd_balance_change,
+                time_manager::now(),
+                ClientOrderFillId::new(i.to_string().into()),
+            ));
+        }
+        collector.add_trade(market_account_id(), dec!(2));
+        collector.add_trade(market_account_id(), dec!(-1.5));
+
+        let day_end = tokio::spawn(sleep_until_next_day());
+        // let spawned task start sleeping before time goes by
+        tokio::task::yield_now().await;
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert!(!day_end.is_finished());
+
+        clock.advance_by(Duration::from_secs(61));
+        tokio::time::advance(Duration::from_secs(31)).await;
+        let date = day_end.await.expect("in test");
+        assert_eq!(date, NaiveDate::from_ymd(2021, 9, 20));
+
+        collector
+            .save_summaries(
+                date,
+                &usd_converter,
+                &event_recorder,
+                CancellationToken::default(),
+            )
+            .await;
+
+        let (table_name, event) = events_rx.try_recv().expect("in test");
+        assert_eq!(table_name, "daily_pnl_summaries");
+        let expected = DailyPnlSummary {
+            date,
+            exchange_account_id: market_account_id().exchange_account_id,
+            currency_pair: market_account_id().currency_pair,
+            // scale of decimals matters for comparison of serialized values
+            pnl_usd: dec!(2.0),
+            realized_pnl_usd: dec!(1.0),
+            unrealized_pnl_usd: dec!(1.0),
+            trade_count: 2,
+            volume_base: dec!(3.5),
+        };
+        assert_eq!(event.json, serde_json::to_value(expected).expect("in test"));
+
+        // statistics is reset for the next day
+        collector
+            .save_summaries(
+                date.succ(),
+                &usd_converter,
+                &event_recorder,
+                CancellationToken::default(),
+            )
+            .await;
+        assert!(events_rx.try_recv().is_err());
+    }
+}
@@ -4,6 +4,7 @@ pub(crate) mod balance_change_usd_periodic_calculator;
 pub(crate) mod balance_changes_accumulator;
 pub(crate) mod balance_changes_calculator;
 pub(crate) mod balance_changes_service;
+pub(crate) mod daily_pnl_summary;
 pub(crate) mod profit_balance_changes_calculator;
 pub(crate) mod profit_loss_balance_change;
 pub(crate) mod profit_loss_stopper;
@@ -1,9 +1,15 @@
+use std::collections::BTreeMap;
+
+use chrono::Duration;
 use futures::future::join_all;
 use itertools::Itertools;
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
 use mockall_double::double;
 
+#[double]
+use crate::misc::time::time_manager;
 #[double]
 use crate::services::usd_convertion::usd_converter::UsdConverter;
 
@@ -45,3 +51,95 @@ pub(crate) async fn calculate_over_market(
 
     join_all(usd_converter_actions).await.iter().sum()
 }
+
+/// Profit over sliding window of time. Changes outside of the window are dropped, so memory usage
+/// depends only on the amount of changes within the window.
+pub(crate) struct ProfitBalanceChangesCalculator {
+    window: Duration,
+    usd_balance_changes: BTreeMap<DateTime, Vec<Amount>>,
+}
+
+impl ProfitBalanceChangesCalculator {
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            window,
+            usd_balance_changes: BTreeMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, balance_change: &ProfitLossBalanceChange) {
+        self.usd_balance_changes
+            .entry(balance_change.change_date)
+            .or_default()
+            .push(balance_change.usd_balance_change);
+    }
+
+    /// Sum of usd balance changes within the last `period`. Period can't be longer than the window
+    /// because older changes are already pruned.
+    pub fn period_pnl(&mut self, period: Duration) -> Amount {
+        let now = time_manager::now();
+        self.prune(now);
+
+        let start_of_period = now - period;
+        self.usd_balance_changes
+            .range(start_of_period..)
+            .flat_map(|(_, changes)| changes)
+            .sum()
+    }
+
+    fn prune(&mut self, now: DateTime) {
+        let start_of_window = now - self.window;
+        self.usd_balance_changes = self.usd_balance_changes.split_off(&start_of_window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::balance::changes::profit_loss_stopper::test::create_balance_change;
+    use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
+    use mmb_domain::order::snapshot::ClientOrderFillId;
+
+    use super::*;
+
+    #[test]
+    fn period_pnl_includes_only_changes_within_period() {
+        let (_time_manager_context, _tm_locker) = time::tests::init_mock(MockClock::default());
+
+        let mut calculator = ProfitBalanceChangesCalculator::with_window(Duration::hours(24));
+        for hours_ago in 0..48 {
+            let balance_change = create_balance_change(
+                dec!(1),
+                time_manager::now() - Duration::hours(hours_ago) - Duration::minutes(30),
+                ClientOrderFillId::new(hours_ago.to_string().into()),
+            );
+            calculator.add(&balance_change);
+        }
+
+        // usd_balance_change of test balance change is doubled
+        assert_eq!(calculator.period_pnl(Duration::hours(24)), dec!(48));
+        assert_eq!(calculator.period_pnl(Duration::hours(12)), dec!(24));
+    }
+
+    #[test]
+    fn period_pnl_prunes_changes_outside_window() {
+        let (_time_manager_context, _tm_locker) = time::tests::init_mock(MockClock::default());
+
+        let mut calculator = ProfitBalanceChangesCalculator::with_window(Duration::hours(24));
+        for hours_ago in 0..48 {
+            let balance_change = create_balance_change(
+                dec!(1),
+                time_manager::now() - Duration::hours(hours_ago) - Duration::minutes(30),
+                ClientOrderFillId::new(hours_ago.to_string().into()),
+            );
+            calculator.add(&balance_change);
+        }
+
+        let _ = calculator.period_pnl(Duration::hours(1));
+
+        assert_eq!(calculator.usd_balance_changes.len(), 24);
+        assert_eq!(calculator.period_pnl(Duration::hours(48)), dec!(48));
+    }
+}
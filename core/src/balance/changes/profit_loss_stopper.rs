@@ -140,6 +140,7 @@ pub(crate) mod test {
     use parking_lot::{Mutex, ReentrantMutexGuard};
     use rust_decimal_macros::dec;
 
+    use crate::misc::time::tests::MockClock;
     #[double]
     use crate::misc::time::time_manager;
     use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
@@ -193,7 +194,7 @@ pub(crate) mod test {
 
         _exchange_blocker: Arc<ExchangeBlocker>,
         _time_manager_mock: time_manager::__now::Context,
-        seconds_offset_in_mock: Arc<Mutex<u32>>,
+        mock_clock: MockClock,
         _mock_lockers: Vec<ReentrantMutexGuard<'static, ()>>,
     }
 
@@ -206,7 +207,7 @@ pub(crate) mod test {
             usd_converter: UsdConverter,
             balance_manager: Arc<Mutex<BalanceManager>>,
             _time_manager_mock: time_manager::__now::Context,
-            seconds_offset_in_mock: Arc<Mutex<u32>>,
+            mock_clock: MockClock,
             _mock_lockers: Vec<ReentrantMutexGuard<'static, ()>>,
         ) -> Self {
             Self {
@@ -216,7 +217,7 @@ pub(crate) mod test {
                 balance_manager,
                 _exchange_blocker,
                 _time_manager_mock,
-                seconds_offset_in_mock,
+                mock_clock,
                 _mock_lockers,
             }
         }
@@ -238,10 +239,10 @@ pub(crate) mod test {
         exchange_blocker_locker: ReentrantMutexGuard<'static, ()>,
         get_last_position_change_calling_times: usize,
     ) -> TestContext {
-        let seconds_offset_in_mock = Arc::new(Mutex::new(0u32));
+        let mock_clock = MockClock::default();
         let mut mock_lockers = vec![exchange_blocker_locker];
         let (time_manager_mock, time_manager_mock_locker) =
-            time::tests::init_mock(seconds_offset_in_mock.clone());
+            time::tests::init_mock(mock_clock.clone());
         mock_lockers.push(time_manager_mock_locker);
 
         let balance_manager = Arc::new(Mutex::new(BalanceManager::default()));
@@ -286,7 +287,7 @@ pub(crate) mod test {
             usd_converter,
             balance_manager,
             time_manager_mock,
-            seconds_offset_in_mock,
+            mock_clock,
             mock_lockers,
         )
     }
@@ -361,6 +362,31 @@ pub(crate) mod test {
         assert_eq!(over_market_usd_change_2, dec!(2) + dec!(3));
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn period_pnl_includes_only_changes_within_period() {
+        init_logger();
+        let context = init(max_period(), 2);
+
+        for (usd_balance_change, minutes_ago) in [(dec!(2), 50), (dec!(3), 10)] {
+            context
+                .balance_change_usd_periodic_calculator
+                .add_balance_change(&create_balance_change(
+                    usd_balance_change,
+                    time_manager::now() - Duration::minutes(minutes_ago),
+                    client_order_fill_id(),
+                ));
+        }
+
+        let calculator = &context.balance_change_usd_periodic_calculator;
+        assert_eq!(calculator.period_pnl(Duration::minutes(30)), dec!(6));
+        assert_eq!(calculator.period_pnl(max_period()), dec!(10));
+
+        context
+            .mock_clock
+            .advance_by(std::time::Duration::from_secs(30 * 60));
+        assert_eq!(calculator.period_pnl(max_period()), dec!(6));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn add_change_should_ignore_old_data() {
         init_logger();
@@ -654,7 +680,9 @@ pub(crate) mod test {
             .check_for_limit(&context.usd_converter, CancellationToken::default())
             .await;
 
-        *context.seconds_offset_in_mock.lock() = 2;
+        context
+            .mock_clock
+            .advance_by(std::time::Duration::from_secs(2));
 
         context
             .balance_change_usd_periodic_calculator
@@ -675,7 +703,9 @@ pub(crate) mod test {
             .expect_get_last_position_change_before_period()
             .returning(|_, _| None);
 
-        *context.seconds_offset_in_mock.lock() = 4;
+        context
+            .mock_clock
+            .advance_by(std::time::Duration::from_secs(2));
 
         context
             .profit_loss_stopper
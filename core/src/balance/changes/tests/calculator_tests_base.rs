@@ -13,7 +13,7 @@ pub mod tests {
     use mmb_utils::cancellation_token::CancellationToken;
     use mmb_utils::hashmap;
     use mockall_double::double;
-    use parking_lot::{Mutex, ReentrantMutexGuard};
+    use parking_lot::ReentrantMutexGuard;
     use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
     use uuid::Uuid;
@@ -27,6 +27,7 @@ pub mod tests {
 
     use crate::infrastructure::init_lifetime_manager;
     use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
     use crate::service_configuration::configuration_descriptor::{
         ServiceConfigurationKey, ServiceName,
     };
@@ -56,7 +57,6 @@ pub mod tests {
         pub usd_converter: UsdConverter,
 
         _time_manager_mock: time_manager::__now::Context,
-        _seconds_offset: Arc<Mutex<u32>>,
         _mock_lockers: Vec<ReentrantMutexGuard<'static, ()>>,
     }
 
@@ -226,9 +226,8 @@ pub mod tests {
 
             mock_lockers.push(usd_converter_locker);
 
-            let seconds_offset = Arc::new(Mutex::new(0u32));
             let (time_manager_mock, time_manager_locker) =
-                time::tests::init_mock(seconds_offset.clone());
+                time::tests::init_mock(MockClock::default());
             mock_lockers.push(time_manager_locker);
 
             let mut this = Self {
@@ -248,7 +247,6 @@ pub mod tests {
                 profit_loss_balance_changes: Vec::new(),
                 usd_converter,
                 _time_manager_mock: time_manager_mock,
-                _seconds_offset: seconds_offset,
                 _mock_lockers: mock_lockers,
             };
 
@@ -1,15 +1,26 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use crate::balance::balance_reservation_manager::BalanceReservationManager;
+use crate::balance::balance_reservation_manager::{BalanceReservationManager, ReserveError};
+use crate::balance::balance_reservation_storage::BalanceReservationStorage;
+use crate::balance::changes::balance_changes_accumulator::{
+    BalanceChangesAccumulator, BalanceOverflowWarning,
+};
+use crate::balance::changes::balance_changes_calculator::BalanceChangesCalculator;
 use crate::balance::changes::balance_changes_service::BalanceChangesService;
+use crate::balance::manager::balance_request::BalanceRequest;
 use crate::balance::manager::balance_reservation::BalanceReservation;
+use crate::balance::manager::balance_snapshot::{
+    BalanceSnapshot, BalanceSnapshotDiff, UnexplainedBalanceChangeWarning,
+};
 use crate::balance::manager::balances::Balances;
 use crate::balance::manager::position_change::PositionChange;
 use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
 use crate::explanation::Explanation;
 use crate::misc::reserve_parameters::ReserveParameters;
 use crate::misc::service_value_tree::ServiceValueTree;
+#[double]
+use crate::misc::time::time_manager;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
 use mmb_domain::events::ExchangeBalancesAndPositions;
 use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
@@ -29,6 +40,7 @@ use log::log;
 use log::Level::{Error, Warn};
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::{impl_mock_initializer, nothing_to_do, DateTime};
+use mockall_double::double;
 use parking_lot::Mutex;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -52,6 +64,11 @@ pub struct BalanceManager {
     position_differs_times_in_row_by_exchange_id:
         HashMap<ExchangeAccountId, HashMap<CurrencyPair, u32>>,
     event_recorder: Option<Arc<EventRecorder>>,
+    balance_changes_accumulator: BalanceChangesAccumulator,
+    balance_overflow_warnings: HashMap<ExchangeAccountId, Vec<BalanceOverflowWarning>>,
+    last_exchange_balances: HashMap<ExchangeAccountId, HashMap<CurrencyCode, Amount>>,
+    balance_snapshots: HashMap<ExchangeAccountId, BalanceSnapshot>,
+    balance_snapshot_diffs: HashMap<ExchangeAccountId, BalanceSnapshotDiff>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -77,6 +94,11 @@ impl BalanceManager {
             balance_changes_service: None,
             position_differs_times_in_row_by_exchange_id: Default::default(),
             event_recorder,
+            balance_changes_accumulator: Default::default(),
+            balance_overflow_warnings: HashMap::new(),
+            last_exchange_balances: HashMap::new(),
+            balance_snapshots: HashMap::new(),
+            balance_snapshot_diffs: HashMap::new(),
         }))
     }
 
@@ -121,6 +143,94 @@ impl BalanceManager {
         self.last_order_fills = balances.last_order_fills.clone();
     }
 
+    /// Serialized balance reservations to restore them after restart while orders are still open
+    pub fn reservations_snapshot(&self) -> Result<Vec<u8>> {
+        self.balance_reservation_manager
+            .balance_reservation_storage
+            .serialize_to_bytes()
+    }
+
+    /// Restore reservations from snapshot only for approved parts of orders which are still open on exchanges.
+    /// Not approved amounts aren't restored because no strategy owns them after restart.
+    /// Restored reservations get new ids because ids of previous run can collide with newly generated ones.
+    /// Returns ids of restored reservations by orders they are approved for, so the orders added to
+    /// orders pool should refer to them to unreserve balance on fills and cancellation
+    pub fn restore_reservations_snapshot(
+        &mut self,
+        bytes: &[u8],
+        open_orders: &HashMap<ExchangeAccountId, HashSet<ClientOrderId>>,
+    ) -> Result<HashMap<ClientOrderId, ReservationId>> {
+        let storage = BalanceReservationStorage::deserialize_from_bytes(bytes)?;
+
+        let mut restored_reservations = HashMap::new();
+        let mut reservation_ids_by_order = HashMap::new();
+        for (reservation_id, reservation) in storage.get_all_raw_reservations() {
+            match self.reconcile_restored_reservation(reservation, open_orders) {
+                Some(reservation) => {
+                    let restored_reservation_id = ReservationId::generate();
+                    for client_order_id in reservation.approved_parts.keys() {
+                        let _ = reservation_ids_by_order
+                            .insert(client_order_id.clone(), restored_reservation_id);
+                    }
+                    let _ = restored_reservations.insert(restored_reservation_id, reservation);
+                }
+                None => log::info!(
+                    "Balance reservation {reservation_id} from snapshot isn't restored because it has no open orders"
+                ),
+            }
+        }
+
+        self.balance_reservation_manager
+            .update_reserved_balances(&restored_reservations);
+        self.save_balances();
+
+        Ok(reservation_ids_by_order)
+    }
+
+    fn reconcile_restored_reservation(
+        &self,
+        reservation: &BalanceReservation,
+        open_orders: &HashMap<ExchangeAccountId, HashSet<ClientOrderId>>,
+    ) -> Option<BalanceReservation> {
+        let open_order_ids = open_orders.get(&reservation.exchange_account_id)?;
+
+        if self
+            .get_exchange_balance(
+                reservation.exchange_account_id,
+                reservation.symbol.clone(),
+                reservation.reservation_currency_code,
+            )
+            .is_none()
+        {
+            log::warn!(
+                "Balance reservation from snapshot isn't restored because there is no balance for {} on {}",
+                reservation.reservation_currency_code,
+                reservation.exchange_account_id
+            );
+            return None;
+        }
+
+        let mut reservation = reservation.clone();
+        reservation
+            .approved_parts
+            .retain(|client_order_id, approved_part| {
+                !approved_part.is_canceled && open_order_ids.contains(client_order_id)
+            });
+        if reservation.approved_parts.is_empty() {
+            return None;
+        }
+
+        reservation.not_approved_amount = dec!(0);
+        reservation.unreserved_amount = reservation
+            .approved_parts
+            .values()
+            .map(|approved_part| approved_part.unreserved_amount)
+            .sum();
+        reservation.expiration_time = None;
+
+        Some(reservation)
+    }
+
     pub fn get_reservation_ids(&self) -> Vec<ReservationId> {
         self.balance_reservation_manager
             .balance_reservation_storage
@@ -140,6 +250,12 @@ impl BalanceManager {
         Ok(())
     }
 
+    #[cfg(test)]
+    pub(crate) fn set_max_reservations(&mut self, max_reservations: usize) {
+        self.balance_reservation_manager.balance_reservation_storage =
+            BalanceReservationStorage::with_max_reservations(max_reservations);
+    }
+
     pub fn unreserve_rest(&mut self, reservation_id: ReservationId) -> Result<()> {
         let amount = self
             .balance_reservation_manager
@@ -172,6 +288,52 @@ impl BalanceManager {
         Ok(())
     }
 
+    /// Set expiry for new reservations. Reservations never expire if `None`
+    pub fn set_reservation_expiry(&mut self, reservation_expiry: Option<chrono::Duration>) {
+        self.balance_reservation_manager
+            .set_reservation_expiry(reservation_expiry);
+    }
+
+    /// Unreserve expired reservations which weren't approved for any order.
+    /// Such reservations leak if strategy fails before unreserving them.
+    /// Reservations of not finished orders are kept because they will be approved after order creation
+    pub fn unreserve_expired_reservations(&mut self) {
+        let now = time_manager::now();
+        let reservation_ids_of_not_finished_orders: HashSet<ReservationId> = self
+            .balance_reservation_manager
+            .exchanges_by_id()
+            .values()
+            .flat_map(|exchange| {
+                exchange
+                    .orders
+                    .not_finished
+                    .iter()
+                    .filter_map(|order| order.header().reservation_id)
+                    .collect_vec()
+            })
+            .collect();
+
+        let expired_reservation_ids = self
+            .balance_reservation_manager
+            .balance_reservation_storage
+            .get_all_raw_reservations()
+            .iter()
+            .filter(|(reservation_id, reservation)| {
+                reservation.approved_parts.is_empty()
+                    && reservation.is_expired(now)
+                    && !reservation_ids_of_not_finished_orders.contains(reservation_id)
+            })
+            .map(|(&reservation_id, _)| reservation_id)
+            .collect_vec();
+
+        for reservation_id in expired_reservation_ids {
+            log::warn!("Unreserving expired not approved reservation {reservation_id}");
+            if let Err(err) = self.unreserve_rest(reservation_id) {
+                log::error!("Failed to unreserve expired reservation {reservation_id}: {err:?}");
+            }
+        }
+    }
+
     fn save_balances(&mut self) {
         match &self.event_recorder {
             None => {}
@@ -365,17 +527,25 @@ impl BalanceManager {
             .filter(|&x| x.exchange_account_id == exchange_account_id)
             .collect_vec();
 
+        let mut not_approved_costs = HashMap::new();
         for reservation in &reservations_by_exchange_account_id {
             let not_approved_amount_cost =
                 reservation.get_proportional_cost_amount(reservation.not_approved_amount)?;
             if let Some(filtered_exchange_balance) =
                 filtered_exchange_balances.get_mut(&reservation.reservation_currency_code)
             {
-                *filtered_exchange_balance -=
+                let not_approved_cost =
                     reservation.convert_in_reservation_currency(not_approved_amount_cost);
+                *filtered_exchange_balance -= not_approved_cost;
+                *not_approved_costs
+                    .entry(reservation.reservation_currency_code)
+                    .or_default() += not_approved_cost;
             }
         }
 
+        let virtual_balance_totals =
+            self.get_virtual_balance_totals(exchange_account_id, &not_approved_costs);
+
         self.balance_reservation_manager
             .virtual_balance_holder
             .update_balances(exchange_account_id, &filtered_exchange_balances);
@@ -386,9 +556,174 @@ impl BalanceManager {
 
         self.save_balances();
         self.save_balance_update(whole_balances_before, whole_balances_after);
+        self.check_balance_drift(
+            exchange_account_id,
+            balances_and_positions,
+            virtual_balance_totals,
+        );
+        self.diff_balance_snapshot(exchange_account_id, balances_and_positions);
+        Ok(())
+    }
+
+    /// Snapshot last balances received from exchange. On the next exchange balance update
+    /// balance changes will be compared with changes caused by fills received after snapshot.
+    /// Unexplained changes greater than `unexplained_warn_threshold` part of balance are saved as warnings
+    pub fn take_balance_snapshot(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        unexplained_warn_threshold: Decimal,
+    ) -> Result<()> {
+        let exchange_balances = self
+            .last_exchange_balances
+            .get(&exchange_account_id)
+            .with_context(|| {
+                format!("Balances for {exchange_account_id} were not received before snapshot")
+            })?;
+
+        self.balance_snapshots.insert(
+            exchange_account_id,
+            BalanceSnapshot::new(exchange_balances.clone(), unexplained_warn_threshold),
+        );
         Ok(())
     }
 
+    /// Diff between last snapshot and following exchange balance update
+    pub fn get_balance_snapshot_diff(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> Option<&BalanceSnapshotDiff> {
+        self.balance_snapshot_diffs.get(&exchange_account_id)
+    }
+
+    fn diff_balance_snapshot(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        balances_and_positions: &ExchangeBalancesAndPositions,
+    ) {
+        let exchange_balances: HashMap<_, _> = balances_and_positions
+            .balances
+            .iter()
+            .map(|x| (x.currency_code, x.balance))
+            .collect();
+
+        if let Some(snapshot) = self.balance_snapshots.remove(&exchange_account_id) {
+            let diff = snapshot.diff(exchange_account_id, &exchange_balances);
+            for balance_diff in diff.unexplained_above_threshold() {
+                let warning = UnexplainedBalanceChangeWarning {
+                    exchange_account_id,
+                    balance_diff: balance_diff.clone(),
+                    unexplained_warn_threshold: diff.unexplained_warn_threshold,
+                };
+
+                log::warn!("Unexplained balance change: {warning:?}");
+
+                if let Some(event_recorder) = &self.event_recorder {
+                    event_recorder
+                        .save(warning)
+                        .expect("Failure save unexplained balance change warning");
+                }
+            }
+
+            self.balance_snapshot_diffs
+                .insert(exchange_account_id, diff);
+        }
+
+        self.last_exchange_balances
+            .insert(exchange_account_id, exchange_balances);
+    }
+
+    /// Set max relative difference between virtual and exchange balances
+    /// after which `BalanceOverflowWarning` is saved
+    pub fn set_drift_warn_threshold(&mut self, drift_warn_threshold: Decimal) {
+        self.balance_changes_accumulator = BalanceChangesAccumulator::new(drift_warn_threshold);
+    }
+
+    /// Drift warnings found on the last exchange balance update
+    pub fn get_balance_overflow_warnings(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> &[BalanceOverflowWarning] {
+        self.balance_overflow_warnings
+            .get(&exchange_account_id)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Virtual balances tracked since the previous exchange balance update together with
+    /// not approved reserved amounts, which are not locked on exchange yet.
+    /// Must be called before virtual balance diffs are reset by the exchange balance update
+    fn get_virtual_balance_totals(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        not_approved_costs: &HashMap<CurrencyCode, Amount>,
+    ) -> HashMap<BalanceRequest, Amount> {
+        let virtual_balance_holder = &self.balance_reservation_manager.virtual_balance_holder;
+        let last_balances = match virtual_balance_holder
+            .get_raw_exchange_balances()
+            .get(&exchange_account_id)
+        {
+            Some(last_balances) => last_balances,
+            None => return HashMap::new(),
+        };
+
+        virtual_balance_holder
+            .get_virtual_balance_diffs()
+            .get_as_balances()
+            .into_iter()
+            .filter(|(request, _)| request.exchange_account_id == exchange_account_id)
+            .filter_map(|(request, balance_diff)| {
+                let last_balance = last_balances.get(&request.currency_code)?;
+                let not_approved_cost = not_approved_costs
+                    .get(&request.currency_code)
+                    .copied()
+                    .unwrap_or_default();
+
+                Some((request, last_balance + balance_diff + not_approved_cost))
+            })
+            .collect()
+    }
+
+    /// Compare virtual balance totals with balances received from exchange
+    fn check_balance_drift(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        balances_and_positions: &ExchangeBalancesAndPositions,
+        virtual_balance_totals: HashMap<BalanceRequest, Amount>,
+    ) {
+        let mut warnings = Vec::new();
+        for (request, virtual_balance_total) in virtual_balance_totals {
+            let exchange_balance = match balances_and_positions
+                .balances
+                .iter()
+                .find(|x| x.currency_code == request.currency_code)
+            {
+                Some(exchange_balance) => exchange_balance.balance,
+                None => continue,
+            };
+
+            let warning = self.balance_changes_accumulator.accumulate(
+                &request,
+                virtual_balance_total,
+                exchange_balance,
+            );
+
+            if let Some(warning) = warning {
+                log::warn!("Virtual balance drifted from exchange balance: {warning:?}");
+
+                if let Some(event_recorder) = &self.event_recorder {
+                    event_recorder
+                        .save(warning.clone())
+                        .expect("Failure save balance overflow warning");
+                }
+
+                warnings.push(warning);
+            }
+        }
+
+        self.balance_overflow_warnings
+            .insert(exchange_account_id, warnings);
+    }
+
     fn calculate_whole_balances(
         &self,
     ) -> Result<HashMap<ExchangeAccountId, HashMap<CurrencyCode, Amount>>> {
@@ -432,6 +767,9 @@ impl BalanceManager {
         let this_locked = this.lock();
         let balances = this_locked.get_balances();
         let event_recorder = this_locked.event_recorder.clone();
+        let drift_warn_threshold = this_locked
+            .balance_changes_accumulator
+            .drift_warn_threshold();
         let exchanges_by_id = this_locked.balance_reservation_manager.exchanges_by_id();
         let new_balance_manager = Self::new(
             CurrencyPairToSymbolConverter::new(exchanges_by_id.clone()),
@@ -440,6 +778,7 @@ impl BalanceManager {
         drop(this_locked);
 
         let mut new_bm_lock = new_balance_manager.lock();
+        new_bm_lock.set_drift_warn_threshold(drift_warn_threshold);
         new_bm_lock.restore_balance_state(&balances, true);
         new_bm_lock.balance_reservation_manager.is_call_from_clone = true;
         drop(new_bm_lock);
@@ -554,6 +893,18 @@ impl BalanceManager {
             .balance_reservation_manager
             .currency_pair_to_symbol_converter
             .get_symbol(exchange_account_id, order_snapshot.header.currency_pair);
+        if let Some(snapshot) = self.balance_snapshots.get_mut(&exchange_account_id) {
+            let balance_changes = BalanceChangesCalculator::get_balance_changes_by_symbol(
+                configuration_descriptor,
+                order_snapshot,
+                order_fill,
+                symbol.clone(),
+            );
+            for (request, change) in balance_changes.get_changes().get_as_balances() {
+                snapshot.add_change_by_fill(request.currency_code, change);
+            }
+        }
+
         self.handle_order_fill(
             configuration_descriptor,
             exchange_account_id,
@@ -650,6 +1001,70 @@ impl BalanceManager {
         );
     }
 
+    /// Count of balance reservations stored in memory
+    pub fn reservation_count(&self) -> usize {
+        self.balance_reservation_manager
+            .balance_reservation_storage
+            .reservation_count()
+    }
+
+    /// Signed net position of derivative: fills bought minus fills sold.
+    /// Positive value is long position and negative is short.
+    /// Unlike `get_net_position` it isn't affected by `reset_position`, so it should be used for trading decisions
+    pub fn get_total_net_position(
+        &self,
+        exchange_account_id: &ExchangeAccountId,
+        currency_pair: &CurrencyPair,
+    ) -> Option<Decimal> {
+        self.balance_reservation_manager
+            .get_total_net_position(*exchange_account_id, *currency_pair)
+    }
+
+    /// Signed position in amount currency accumulated by fills of derivative or spot symbol.
+    /// Positive value is long position and negative is short
+    pub fn get_net_position_by_fills(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Decimal {
+        self.balance_reservation_manager
+            .get_net_position_by_fills(exchange_account_id, currency_pair)
+    }
+
+    /// Signed net position of derivative for reporting: fills bought minus fills sold since last reset.
+    /// Positive value is long position and negative is short
+    pub fn get_net_position(
+        &self,
+        exchange_account_id: &ExchangeAccountId,
+        currency_pair: &CurrencyPair,
+    ) -> Option<Decimal> {
+        self.balance_reservation_manager
+            .get_net_position(*exchange_account_id, *currency_pair)
+    }
+
+    /// Weighted average entry price of derivative position, updated on each fill.
+    /// `None` for not derivative symbols or if there is no open position
+    pub fn get_average_entry_price(
+        &self,
+        exchange_account_id: &ExchangeAccountId,
+        currency_pair: &CurrencyPair,
+    ) -> Option<Price> {
+        self.balance_reservation_manager
+            .get_average_entry_price(*exchange_account_id, *currency_pair)
+    }
+
+    /// Reset net position reported by `get_net_position` to zero.
+    /// Real position used for reservations and returned by `get_total_net_position` isn't changed
+    pub fn reset_position(
+        &mut self,
+        exchange_account_id: &ExchangeAccountId,
+        currency_pair: &CurrencyPair,
+    ) {
+        self.balance_reservation_manager
+            .reset_position(*exchange_account_id, *currency_pair);
+        self.save_balances();
+    }
+
     fn update_last_order_fill(
         &mut self,
         exchange_account_id: ExchangeAccountId,
@@ -784,15 +1199,12 @@ impl BalanceManager {
         &mut self,
         reserve_parameters: &ReserveParameters,
         explanation: &mut Option<Explanation>,
-    ) -> Option<ReservationId> {
-        if let Some(reservation_id) = self
+    ) -> Result<ReservationId, ReserveError> {
+        let reservation_id = self
             .balance_reservation_manager
-            .try_reserve(reserve_parameters, explanation)
-        {
-            self.save_balances();
-            return Some(reservation_id);
-        }
-        None
+            .try_reserve(reserve_parameters, explanation)?;
+        self.save_balances();
+        Ok(reservation_id)
     }
 
     pub fn try_reserve_pair(
@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use crate::balance::manager::position_change::PositionChange;
 use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
-use mmb_domain::order::snapshot::ClientOrderFillId;
+use mmb_domain::order::snapshot::{ClientOrderFillId, Price};
+use mmb_domain::position::PositionEntry;
 use serde::Serialize;
 
 use mmb_domain::market::CurrencyPair;
-use mmb_utils::DateTime;
+use mmb_utils::{nothing_to_do, DateTime};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
@@ -17,6 +18,13 @@ pub struct BalancePositionByFillAmount {
 
     /// MarketAccountId -> AmountInAmountCurrency
     position_changes: HashMap<MarketAccountId, Vec<PositionChange>>,
+
+    /// MarketAccountId -> position by fills with its average entry price
+    position_entries: HashMap<MarketAccountId, PositionEntry>,
+
+    /// MarketAccountId -> position at the moment of the last reset.
+    /// It's used only for reporting of position since reset and doesn't affect position itself
+    position_baselines: HashMap<MarketAccountId, Decimal>,
 }
 
 impl BalancePositionByFillAmount {
@@ -30,6 +38,47 @@ impl BalancePositionByFillAmount {
             .cloned()
     }
 
+    /// Position change since the last `reset_baseline` or whole position if there were no resets
+    pub fn get_since_reset(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Decimal> {
+        let key = MarketAccountId::new(exchange_account_id, currency_pair);
+        let position = self.position_by_fill_amount.get(&key)?;
+        let baseline = self
+            .position_baselines
+            .get(&key)
+            .cloned()
+            .unwrap_or_default();
+        Some(position - baseline)
+    }
+
+    pub fn reset_baseline(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) {
+        let key = MarketAccountId::new(exchange_account_id, currency_pair);
+        let position = self
+            .position_by_fill_amount
+            .get(&key)
+            .cloned()
+            .unwrap_or_default();
+        self.position_baselines.insert(key, position);
+    }
+
+    pub fn get_average_entry_price(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Price> {
+        self.position_entries
+            .get(&MarketAccountId::new(exchange_account_id, currency_pair))
+            .filter(|x| !x.position.is_zero())
+            .map(|x| x.average_entry_price)
+    }
+
     pub(crate) fn set(
         &mut self,
         exchange_account_id: ExchangeAccountId,
@@ -87,14 +136,34 @@ impl BalancePositionByFillAmount {
                 log::warn!("PositionChanges for key {key:?} not found");
             }
         }
+        self.sync_position_entry(key, new_position);
         self.position_by_fill_amount.insert(key, new_position);
     }
 
+    /// Position can be restored from exchange without fills, so the entry follows the actual position.
+    /// Entry is removed when position is closed or flipped without fill, because entry price
+    /// of such position is unknown
+    fn sync_position_entry(&mut self, key: MarketAccountId, new_position: Decimal) {
+        match self.position_entries.get_mut(&key) {
+            Some(entry)
+                if !new_position.is_zero()
+                    && entry.position.is_sign_positive() == new_position.is_sign_positive() =>
+            {
+                entry.position = new_position
+            }
+            Some(_) => {
+                let _ = self.position_entries.remove(&key);
+            }
+            None => nothing_to_do(),
+        }
+    }
+
     pub fn add(
         &mut self,
         exchange_account_id: ExchangeAccountId,
         currency_pair: CurrencyPair,
         value_to_add: Decimal,
+        price: Price,
         client_order_fill_id: Option<ClientOrderFillId>,
         now: DateTime,
     ) {
@@ -102,6 +171,16 @@ impl BalancePositionByFillAmount {
             .get(exchange_account_id, currency_pair)
             .unwrap_or(dec!(0));
         let new_value = current_value + value_to_add;
+
+        self.position_entries
+            .entry(MarketAccountId::new(exchange_account_id, currency_pair))
+            // entry price of position restored without fills is unknown, so the fill price is used
+            .or_insert_with(|| PositionEntry {
+                position: current_value,
+                average_entry_price: price,
+            })
+            .apply_fill(value_to_add, price);
+
         self.set(
             exchange_account_id,
             currency_pair,
@@ -141,3 +220,89 @@ impl BalancePositionByFillAmount {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn exchange_account_id() -> ExchangeAccountId {
+        "Binance_0".parse().expect("in test")
+    }
+
+    fn currency_pair() -> CurrencyPair {
+        CurrencyPair::from_codes("btc".into(), "usdt".into())
+    }
+
+    fn add_fill(positions: &mut BalancePositionByFillAmount, amount: Decimal, price: Price) {
+        positions.add(
+            exchange_account_id(),
+            currency_pair(),
+            amount,
+            price,
+            Some(ClientOrderFillId::unique_id()),
+            Utc::now(),
+        );
+    }
+
+    fn restore(positions: &mut BalancePositionByFillAmount, new_position: Decimal) {
+        let previous_position = positions.get(exchange_account_id(), currency_pair());
+        positions.set(
+            exchange_account_id(),
+            currency_pair(),
+            previous_position,
+            new_position,
+            None,
+            Utc::now(),
+        );
+    }
+
+    fn average_entry_price(positions: &BalancePositionByFillAmount) -> Option<Price> {
+        positions.get_average_entry_price(exchange_account_id(), currency_pair())
+    }
+
+    #[test]
+    fn entry_is_cleared_when_position_is_closed_by_restore() {
+        let mut positions = BalancePositionByFillAmount::default();
+        add_fill(&mut positions, dec!(2), dec!(100));
+
+        restore(&mut positions, dec!(0));
+        assert_eq!(average_entry_price(&positions), None);
+
+        // new position doesn't take entry price of the closed one
+        add_fill(&mut positions, dec!(1), dec!(200));
+        assert_eq!(average_entry_price(&positions), Some(dec!(200)));
+    }
+
+    #[test]
+    fn entry_follows_position_restored_without_fills() {
+        let mut positions = BalancePositionByFillAmount::default();
+        add_fill(&mut positions, dec!(5), dec!(100));
+
+        restore(&mut positions, dec!(3));
+        assert_eq!(average_entry_price(&positions), Some(dec!(100)));
+
+        // fill closing the restored position closes the entry too
+        add_fill(&mut positions, dec!(-3), dec!(110));
+        assert_eq!(
+            positions.get(exchange_account_id(), currency_pair()),
+            Some(dec!(0))
+        );
+        assert_eq!(average_entry_price(&positions), None);
+    }
+
+    #[test]
+    fn entry_is_cleared_when_position_is_flipped_by_restore() {
+        let mut positions = BalancePositionByFillAmount::default();
+        add_fill(&mut positions, dec!(2), dec!(100));
+
+        restore(&mut positions, dec!(-1));
+        assert_eq!(average_entry_price(&positions), None);
+
+        add_fill(&mut positions, dec!(-1), dec!(90));
+        assert_eq!(average_entry_price(&positions), Some(dec!(90)));
+
+        add_fill(&mut positions, dec!(2), dec!(95));
+        assert_eq!(average_entry_price(&positions), None);
+    }
+}
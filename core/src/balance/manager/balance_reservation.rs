@@ -10,13 +10,14 @@ use mmb_domain::order::snapshot::Amount;
 use mmb_domain::order::snapshot::ClientOrderId;
 use mmb_domain::order::snapshot::OrderSide;
 use mmb_domain::order::snapshot::Price;
-use serde::Serialize;
+use mmb_utils::DateTime;
+use serde::{Deserialize, Serialize};
 
 use anyhow::{bail, Result};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BalanceReservation {
     pub configuration_descriptor: ConfigurationDescriptor,
     pub exchange_account_id: ExchangeAccountId,
@@ -34,6 +35,11 @@ pub struct BalanceReservation {
     /// Not approved amount in AmountCurrencyCode
     pub not_approved_amount: Amount,
     pub approved_parts: HashMap<ClientOrderId, ApprovedPart>,
+
+    /// Time after which reservation without approved parts is unreserved automatically.
+    /// Reservation never expires if `None`
+    #[serde(default)]
+    pub expiration_time: Option<DateTime>,
 }
 
 impl BalanceReservation {
@@ -62,9 +68,15 @@ impl BalanceReservation {
             unreserved_amount: dec!(0),
             not_approved_amount: amount,
             approved_parts: HashMap::new(),
+            expiration_time: None,
         }
     }
 
+    pub fn is_expired(&self, now: DateTime) -> bool {
+        self.expiration_time
+            .is_some_and(|expiration_time| expiration_time <= now)
+    }
+
     pub(crate) fn get_proportional_cost_amount(&self, amount: Amount) -> Result<Decimal> {
         if self.amount.is_zero() {
             if amount.is_zero() {
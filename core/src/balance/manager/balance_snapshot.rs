@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyCode, ExchangeAccountId};
+use mmb_domain::order::snapshot::Amount;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+
+/// Exchange balances at the moment of taking snapshot and balance changes caused by fills after it
+#[derive(Debug, Clone)]
+pub(crate) struct BalanceSnapshot {
+    exchange_balances: HashMap<CurrencyCode, Amount>,
+    changes_by_fills: HashMap<CurrencyCode, Amount>,
+    unexplained_warn_threshold: Decimal,
+}
+
+impl BalanceSnapshot {
+    pub fn new(
+        exchange_balances: HashMap<CurrencyCode, Amount>,
+        unexplained_warn_threshold: Decimal,
+    ) -> Self {
+        Self {
+            exchange_balances,
+            changes_by_fills: HashMap::new(),
+            unexplained_warn_threshold,
+        }
+    }
+
+    pub fn add_change_by_fill(&mut self, currency_code: CurrencyCode, change: Amount) {
+        *self.changes_by_fills.entry(currency_code).or_default() += change;
+    }
+
+    pub fn diff(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        new_exchange_balances: &HashMap<CurrencyCode, Amount>,
+    ) -> BalanceSnapshotDiff {
+        let mut currency_codes: Vec<_> = self
+            .exchange_balances
+            .keys()
+            .chain(new_exchange_balances.keys())
+            .copied()
+            .collect();
+        currency_codes.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        currency_codes.dedup();
+
+        let diffs = currency_codes
+            .into_iter()
+            .map(|currency_code| {
+                let balance_before = balance_or_zero(&self.exchange_balances, currency_code);
+                let balance_after = balance_or_zero(new_exchange_balances, currency_code);
+                let explained_by_fills = balance_or_zero(&self.changes_by_fills, currency_code);
+
+                BalanceDiff {
+                    currency_code,
+                    balance_before,
+                    balance_after,
+                    explained_by_fills,
+                    unexplained: balance_after - balance_before - explained_by_fills,
+                }
+            })
+            .collect();
+
+        BalanceSnapshotDiff {
+            exchange_account_id,
+            diffs,
+            unexplained_warn_threshold: self.unexplained_warn_threshold,
+        }
+    }
+}
+
+fn balance_or_zero(
+    balances: &HashMap<CurrencyCode, Amount>,
+    currency_code: CurrencyCode,
+) -> Amount {
+    balances.get(&currency_code).copied().unwrap_or(dec!(0))
+}
+
+/// Balance change of currency between snapshot and next exchange balance update
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceDiff {
+    pub currency_code: CurrencyCode,
+    pub balance_before: Amount,
+    pub balance_after: Amount,
+    /// Part of balance change that is caused by order fills received after snapshot
+    pub explained_by_fills: Amount,
+    /// Part of balance change that isn't caused by known fills (withdrawals, funding fees, etc.)
+    pub unexplained: Amount,
+}
+
+impl BalanceDiff {
+    /// Relative unexplained change. If balance was zero before snapshot any unexplained change is considered as `1`
+    pub fn unexplained_part(&self) -> Decimal {
+        match self.balance_before.is_zero() {
+            true if self.unexplained.is_zero() => dec!(0),
+            true => dec!(1),
+            false => (self.unexplained / self.balance_before).abs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceSnapshotDiff {
+    pub exchange_account_id: ExchangeAccountId,
+    pub diffs: Vec<BalanceDiff>,
+    pub unexplained_warn_threshold: Decimal,
+}
+
+impl BalanceSnapshotDiff {
+    /// Diffs with unexplained change above threshold specified on taking snapshot
+    pub fn unexplained_above_threshold(&self) -> impl Iterator<Item = &BalanceDiff> {
+        self.diffs
+            .iter()
+            .filter(|x| x.unexplained_part() > self.unexplained_warn_threshold)
+    }
+}
+
+/// Warning that balance received from exchange changed in a way that can't be explained by order fills
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct UnexplainedBalanceChangeWarning {
+    pub exchange_account_id: ExchangeAccountId,
+    pub balance_diff: BalanceDiff,
+    pub unexplained_warn_threshold: Decimal,
+}
+
+impl_event!(
+    UnexplainedBalanceChangeWarning,
+    "unexplained_balance_change_warnings"
+);
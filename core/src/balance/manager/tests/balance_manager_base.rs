@@ -1,6 +1,7 @@
 #[cfg(test)]
 use std::{collections::HashMap, sync::Arc};
 
+use crate::misc::time::tests::MockClock;
 #[double]
 use crate::misc::time::time_manager;
 use crate::{
@@ -31,7 +32,7 @@ pub struct BalanceManagerBase {
     pub currency_pair: CurrencyPair,
     pub configuration_descriptor: ConfigurationDescriptor,
     pub balance_manager: Option<Arc<Mutex<BalanceManager>>>,
-    pub seconds_offset_in_mock: Arc<Mutex<u32>>,
+    pub mock_clock: MockClock,
     symbol: Option<Arc<Symbol>>,
 
     _mock_object: time_manager::__now::Context,
@@ -46,7 +47,7 @@ impl Default for BalanceManagerBase {
 
 impl BalanceManagerBase {
     pub fn exchange_id() -> String {
-        "local_exchange_id".into()
+        "local-exchange-id".into()
     }
     // Quote currency
     pub fn btc() -> CurrencyCode {
@@ -120,8 +121,8 @@ impl BalanceManagerBase {
     }
 
     pub fn new() -> Self {
-        let seconds_offset_in_mock = Arc::new(Mutex::new(0u32));
-        let (mock_object, mock_locker) = time::tests::init_mock(seconds_offset_in_mock.clone());
+        let mock_clock = MockClock::default();
+        let (mock_object, mock_locker) = time::tests::init_mock(mock_clock.clone());
 
         let exchange_id = Self::exchange_id();
         let exchange_id_str = exchange_id.as_str();
@@ -140,7 +141,7 @@ impl BalanceManagerBase {
                     .as_str()
                     .into(),
             ),
-            seconds_offset_in_mock,
+            mock_clock,
             symbol: None,
             balance_manager: None,
             _mock_object: mock_object,
@@ -381,7 +381,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -436,7 +436,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -554,6 +554,126 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn net_position_after_mixed_fills(#[case] is_reversed: bool) {
+        init_logger();
+        let mut test_object =
+            create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(100), is_reversed);
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        let currency_pair = test_object.balance_manager_base.symbol().currency_pair();
+
+        let fill =
+            |test_object: &mut BalanceManagerDerivative, order_side: OrderSide, amount: Amount| {
+                let mut order = test_object
+                    .balance_manager_base
+                    .create_order(order_side, ReservationId::generate());
+                order.add_fill(BalanceManagerDerivative::create_order_fill(
+                    dec!(0.1),
+                    amount,
+                    dec!(0.1),
+                    dec!(0),
+                    is_reversed,
+                ));
+
+                let configuration_descriptor =
+                    test_object.balance_manager_base.configuration_descriptor;
+                test_object
+                    .balance_manager()
+                    .order_was_filled(configuration_descriptor, &order);
+            };
+        let net_position = |test_object: &BalanceManagerDerivative| {
+            test_object
+                .balance_manager()
+                .get_net_position(&exchange_account_id, &currency_pair)
+        };
+        let total_net_position = |test_object: &BalanceManagerDerivative| {
+            test_object
+                .balance_manager()
+                .get_total_net_position(&exchange_account_id, &currency_pair)
+        };
+
+        assert_eq!(net_position(&test_object), None);
+
+        fill(&mut test_object, OrderSide::Buy, dec!(1));
+        fill(&mut test_object, OrderSide::Sell, dec!(0.4));
+        fill(&mut test_object, OrderSide::Buy, dec!(0.2));
+        assert_eq!(net_position(&test_object), Some(dec!(0.8)));
+
+        fill(&mut test_object, OrderSide::Sell, dec!(1.3));
+        assert_eq!(net_position(&test_object), Some(dec!(-0.5)));
+        assert_eq!(total_net_position(&test_object), Some(dec!(-0.5)));
+
+        test_object
+            .balance_manager()
+            .reset_position(&exchange_account_id, &currency_pair);
+        assert_eq!(net_position(&test_object), Some(dec!(0)));
+        // reset affects only reporting, real position is still used for reservations
+        assert_eq!(total_net_position(&test_object), Some(dec!(-0.5)));
+
+        fill(&mut test_object, OrderSide::Sell, dec!(0.3));
+        assert_eq!(net_position(&test_object), Some(dec!(-0.3)));
+        assert_eq!(total_net_position(&test_object), Some(dec!(-0.8)));
+    }
+
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn average_entry_price_after_mixed_fills(#[case] is_reversed: bool) {
+        init_logger();
+        let mut test_object =
+            create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(100), is_reversed);
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        let currency_pair = test_object.balance_manager_base.symbol().currency_pair();
+
+        let fill = |test_object: &mut BalanceManagerDerivative,
+                    order_side: OrderSide,
+                    price: Price,
+                    amount: Amount| {
+            let mut order = test_object
+                .balance_manager_base
+                .create_order(order_side, ReservationId::generate());
+            order.add_fill(BalanceManagerDerivative::create_order_fill(
+                price,
+                amount,
+                price * amount,
+                dec!(0),
+                is_reversed,
+            ));
+
+            let configuration_descriptor =
+                test_object.balance_manager_base.configuration_descriptor;
+            test_object
+                .balance_manager()
+                .order_was_filled(configuration_descriptor, &order);
+        };
+        let average_entry_price = |test_object: &BalanceManagerDerivative| {
+            test_object
+                .balance_manager()
+                .get_average_entry_price(&exchange_account_id, &currency_pair)
+        };
+
+        assert_eq!(average_entry_price(&test_object), None);
+
+        fill(&mut test_object, OrderSide::Buy, dec!(0.1), dec!(1));
+        fill(&mut test_object, OrderSide::Buy, dec!(0.13), dec!(2));
+        assert_eq!(average_entry_price(&test_object), Some(dec!(0.12)));
+
+        // reducing fill doesn't change entry price
+        fill(&mut test_object, OrderSide::Sell, dec!(0.2), dec!(1));
+        assert_eq!(average_entry_price(&test_object), Some(dec!(0.12)));
+
+        // position is flipped and the rest of fill opens short position
+        fill(&mut test_object, OrderSide::Sell, dec!(0.09), dec!(3));
+        assert_eq!(average_entry_price(&test_object), Some(dec!(0.09)));
+
+        fill(&mut test_object, OrderSide::Buy, dec!(0.1), dec!(1));
+        assert_eq!(average_entry_price(&test_object), None);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn fill_buy_should_commission_should_be_deducted_from_balance() {
         init_logger();
@@ -852,7 +972,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -965,7 +1085,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -1076,7 +1196,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -1189,7 +1309,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -1326,7 +1446,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -1487,7 +1607,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
         assert_eq!(
             test_object
                 .balance_manager_base
@@ -1645,7 +1765,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -1810,7 +1930,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
         assert_eq!(
             test_object
                 .balance_manager_base
@@ -2368,7 +2488,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -2437,7 +2557,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -4375,7 +4495,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
             OrderSide::Sell,
@@ -4385,6 +4505,6 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None,)
-            .is_none());
+            .is_err());
     }
 }
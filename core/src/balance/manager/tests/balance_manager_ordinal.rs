@@ -29,16 +29,16 @@ use crate::{
 pub struct BalanceManagerOrdinal {
     pub balance_manager_base: BalanceManagerBase,
     pub now: DateTime,
+    exchanges_by_id: HashMap<ExchangeAccountId, Arc<Exchange>>,
 }
 
 impl BalanceManagerOrdinal {
-    pub fn create_balance_manager() -> (Arc<Symbol>, Arc<Mutex<BalanceManager>>) {
-        let (symbol, exchanges_by_id) =
-            BalanceManagerOrdinal::create_balance_manager_ctor_parameters();
+    pub fn create_balance_manager(
+        exchanges_by_id: HashMap<ExchangeAccountId, Arc<Exchange>>,
+    ) -> Arc<Mutex<BalanceManager>> {
         let currency_pair_to_symbol_converter = CurrencyPairToSymbolConverter::new(exchanges_by_id);
 
-        let balance_manager = BalanceManager::new(currency_pair_to_symbol_converter, None);
-        (symbol, balance_manager)
+        BalanceManager::new(currency_pair_to_symbol_converter, None)
     }
 
     fn create_balance_manager_ctor_parameters(
@@ -81,7 +81,10 @@ impl BalanceManagerOrdinal {
     }
 
     fn new() -> Self {
-        let (symbol, balance_manager) = BalanceManagerOrdinal::create_balance_manager();
+        let (symbol, exchanges_by_id) =
+            BalanceManagerOrdinal::create_balance_manager_ctor_parameters();
+        let balance_manager =
+            BalanceManagerOrdinal::create_balance_manager(exchanges_by_id.clone());
         let mut balance_manager_base = BalanceManagerBase::new();
         balance_manager_base.set_balance_manager(balance_manager);
         balance_manager_base.set_symbol(symbol);
@@ -90,6 +93,7 @@ impl BalanceManagerOrdinal {
         Self {
             balance_manager_base,
             now,
+            exchanges_by_id,
         }
     }
 
@@ -132,10 +136,6 @@ impl BalanceManagerOrdinal {
     fn check_time(&self, _seconds: u32) {
         // TODO: fix me when mock will be added
     }
-
-    fn timer_add_second(&mut self) {
-        *self.balance_manager_base.seconds_offset_in_mock.lock() += 1;
-    }
 }
 #[cfg(test)]
 mod tests {
@@ -144,6 +144,7 @@ mod tests {
     use std::time::Duration;
 
     use chrono::Utc;
+    use itertools::Itertools;
     use mmb_domain::market::CurrencyCode;
     use mmb_domain::order::snapshot::{Amount, Price};
     use mmb_utils::hashmap;
@@ -153,6 +154,8 @@ mod tests {
     use rust_decimal::Decimal;
     use rust_decimal_macros::dec;
 
+    use crate::balance::balance_reservation_manager::ReserveError;
+    use crate::balance::balance_reservation_storage::ReservationLimitExceeded;
     use crate::balance::manager::balance_manager::BalanceManager;
     use crate::balance::manager::position_change::PositionChange;
     use crate::balance::manager::tests::balance_manager_base::BalanceManagerBase;
@@ -514,10 +517,12 @@ mod tests {
             dec!(5),
         );
 
-        assert!(test_object
-            .balance_manager()
-            .try_reserve(&reserve_parameters, &mut None,)
-            .is_none());
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .try_reserve(&reserve_parameters, &mut None),
+            Err(ReserveError::NotEnoughBalance)
+        );
         assert_eq!(
             test_object
                 .balance_manager()
@@ -526,6 +531,41 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn try_reserve_over_reservations_limit() {
+        init_logger();
+        let test_object = create_test_obj_by_currency_code(BalanceManagerBase::btc(), dec!(1));
+        test_object.balance_manager().set_max_reservations(1);
+
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Buy,
+            dec!(0.2),
+            dec!(1),
+        );
+
+        let _ = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .try_reserve(&reserve_parameters, &mut None),
+            Err(ReserveError::ReservationLimitExceeded(
+                ReservationLimitExceeded {
+                    max_reservations: 1
+                }
+            ))
+        );
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(0.8))
+        );
+        assert_eq!(test_object.balance_manager().get_reservation_ids().len(), 1);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn try_reserve_buy_enough_balance() {
         init_logger();
@@ -582,7 +622,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None,)
-            .is_none());
+            .is_err());
         assert_eq!(
             test_object
                 .balance_manager()
@@ -1535,6 +1575,249 @@ mod tests {
             .is_none());
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn unreserve_expired_not_approved_reservations() {
+        init_logger();
+        let mut test_object = create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(5));
+        test_object
+            .balance_manager()
+            .set_reservation_expiry(Some(chrono::Duration::seconds(60)));
+
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Sell,
+            dec!(0.2),
+            dec!(2),
+        );
+        let not_approved_reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+        let approved_reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+
+        let order = test_object
+            .balance_manager_base
+            .create_order(OrderSide::Sell, approved_reservation_id);
+        test_object.balance_manager().approve_reservation(
+            approved_reservation_id,
+            &order.header.client_order_id,
+            dec!(2),
+        );
+
+        // reservations aren't expired yet
+        test_object
+            .balance_manager()
+            .unreserve_expired_reservations();
+        assert!(test_object
+            .balance_manager()
+            .get_reservation(not_approved_reservation_id)
+            .is_some());
+
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(61));
+        test_object
+            .balance_manager()
+            .unreserve_expired_reservations();
+
+        assert!(test_object
+            .balance_manager()
+            .get_reservation(not_approved_reservation_id)
+            .is_none());
+        assert!(test_object
+            .balance_manager()
+            .get_reservation(approved_reservation_id)
+            .is_some());
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(3))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn expired_reservations_of_not_finished_orders_are_not_unreserved() {
+        init_logger();
+        let mut test_object = create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(5));
+        test_object
+            .balance_manager()
+            .set_reservation_expiry(Some(chrono::Duration::seconds(60)));
+
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Sell,
+            dec!(0.2),
+            dec!(2),
+        );
+        let reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+
+        // order is still creating on exchange, so reservation isn't approved yet
+        let order = test_object
+            .balance_manager_base
+            .create_order(OrderSide::Sell, reservation_id);
+        let exchange = test_object
+            .exchanges_by_id
+            .get(&test_object.balance_manager_base.exchange_account_id_1)
+            .expect("in test")
+            .clone();
+        let _ = exchange.orders.add_snapshot_initial(&order);
+
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(61));
+        test_object
+            .balance_manager()
+            .unreserve_expired_reservations();
+
+        assert!(test_object
+            .balance_manager()
+            .get_reservation(reservation_id)
+            .is_some());
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(3))
+        );
+
+        // reservation of finished order leaks if strategy doesn't unreserve it
+        let _ = exchange
+            .orders
+            .not_finished
+            .remove(&order.header.client_order_id);
+        test_object
+            .balance_manager()
+            .unreserve_expired_reservations();
+
+        assert!(test_object
+            .balance_manager()
+            .get_reservation(reservation_id)
+            .is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn restore_reservations_snapshot_only_for_open_orders() {
+        init_logger();
+        let mut test_object = create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(5));
+
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Sell,
+            dec!(0.2),
+            dec!(2),
+        );
+        let approve_new_reservation = |test_object: &mut BalanceManagerOrdinal| {
+            let reservation_id = test_object
+                .balance_manager()
+                .try_reserve(&reserve_parameters, &mut None)
+                .expect("in test");
+            let order = test_object
+                .balance_manager_base
+                .create_order(OrderSide::Sell, reservation_id);
+            test_object.balance_manager().approve_reservation(
+                reservation_id,
+                &order.header.client_order_id,
+                dec!(2),
+            );
+            (reservation_id, order.header.client_order_id.clone())
+        };
+        let (open_order_reservation_id, open_order_id) = approve_new_reservation(&mut test_object);
+        let (closed_order_reservation_id, _) = approve_new_reservation(&mut test_object);
+        let not_approved_reserve_parameters = test_object
+            .balance_manager_base
+            .create_reserve_parameters(OrderSide::Sell, dec!(0.2), dec!(0.5));
+        let _ = test_object
+            .balance_manager()
+            .try_reserve(&not_approved_reserve_parameters, &mut None)
+            .expect("in test");
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(0.5))
+        );
+
+        let snapshot = test_object
+            .balance_manager()
+            .reservations_snapshot()
+            .expect("in test");
+        let open_orders = hashmap![
+            test_object.balance_manager_base.exchange_account_id_1 => [open_order_id.clone()].into_iter().collect()
+        ];
+        let restored_reservation_ids = test_object
+            .balance_manager()
+            .restore_reservations_snapshot(&snapshot, &open_orders)
+            .expect("in test");
+
+        let reservation_ids = test_object.balance_manager().get_reservation_ids();
+        assert_eq!(reservation_ids.len(), 1);
+        assert_ne!(reservation_ids[0], open_order_reservation_id);
+        assert_ne!(reservation_ids[0], closed_order_reservation_id);
+        assert_eq!(
+            restored_reservation_ids,
+            hashmap![open_order_id.clone() => reservation_ids[0]]
+        );
+
+        let restored_reservation = test_object
+            .balance_manager()
+            .get_reservation(reservation_ids[0])
+            .expect("in test")
+            .clone();
+        assert_eq!(restored_reservation.not_approved_amount, dec!(0));
+        assert_eq!(restored_reservation.unreserved_amount, dec!(2));
+        assert_eq!(restored_reservation.expiration_time, None);
+        assert_eq!(
+            restored_reservation.approved_parts.keys().collect_vec(),
+            [&open_order_id]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn restore_invalid_reservations_snapshot() {
+        init_logger();
+        let test_object = create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(5));
+
+        assert!(test_object
+            .balance_manager()
+            .restore_reservations_snapshot(&[0xc1], &HashMap::new())
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn reservations_without_expiry_are_not_unreserved() {
+        init_logger();
+        let test_object = create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(5));
+
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Sell,
+            dec!(0.2),
+            dec!(2),
+        );
+        let reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(60 * 60));
+        test_object
+            .balance_manager()
+            .unreserve_expired_reservations();
+
+        assert!(test_object
+            .balance_manager()
+            .get_reservation(reservation_id)
+            .is_some());
+    }
+
     #[rstest]
     #[case(dec!(5), dec!(0.2), dec!(3), dec!(0.5), dec!(2) ,dec!(2) )]
     #[case(dec!(5), dec!(0.2), dec!(3), dec!(0.2), dec!(2) ,dec!(2) )]
@@ -2507,7 +2790,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         BalanceManagerBase::update_balance(
             &mut test_object.balance_manager(),
@@ -2810,6 +3093,48 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::buy(OrderSide::Buy, dec!(5))]
+    #[case::sell(OrderSide::Sell, dec!(-5))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn net_position_by_fills_of_spot(
+        #[case] side: OrderSide,
+        #[case] expected_position: Amount,
+    ) {
+        init_logger();
+        let mut test_object = create_test_obj_with_multiple_currencies(
+            vec![
+                BalanceManagerBase::btc(),
+                BalanceManagerBase::eth(),
+                BalanceManagerBase::bnb(),
+            ],
+            vec![dec!(7), dec!(11), dec!(0.2)],
+        );
+
+        let mut order = test_object
+            .balance_manager_base
+            .create_order(side, ReservationId::generate());
+        order.add_fill(BalanceManagerOrdinal::create_order_fill(
+            dec!(0.2),
+            dec!(5),
+            dec!(0),
+        ));
+
+        let configuration_descriptor = test_object.balance_manager_base.configuration_descriptor;
+        test_object
+            .balance_manager()
+            .order_was_filled(configuration_descriptor, &order);
+
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        assert_eq!(
+            test_object.balance_manager().get_net_position_by_fills(
+                exchange_account_id,
+                BalanceManagerBase::currency_pair()
+            ),
+            expected_position
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn order_was_filled_specific_fill_buy() {
         init_logger();
@@ -3152,7 +3477,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         let order_pool = OrdersPool::new();
         let order = order_pool.add_snapshot_initial(&order_snapshot);
@@ -3256,7 +3581,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         let order_pool = OrdersPool::new();
         let order = order_pool.add_snapshot_initial(&order_snapshot);
@@ -3369,7 +3694,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
         test_object.balance_manager().approve_reservation(
             order_snapshot.header.reservation_id.expect("in test"),
             &order_snapshot.header.client_order_id,
@@ -3485,7 +3810,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         let cloned_balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
             test_object
@@ -4269,7 +4594,7 @@ mod tests {
         assert!(test_object
             .balance_manager()
             .try_reserve(&reserve_parameters, &mut None)
-            .is_some());
+            .is_ok());
 
         assert_eq!(
             test_object
@@ -5165,7 +5490,10 @@ mod tests {
                 .expect("in test"),
             PositionChange::new(order_fill_id_1.clone(), test_object.now, dec!(1))
         );
-        test_object.timer_add_second();
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(1));
         let _order_fill_id_2 = order_was_filled(&mut test_object, &mut buy_4);
         test_object.check_time(1);
         check_position(&test_object, dec!(-1));
@@ -5177,7 +5505,10 @@ mod tests {
             PositionChange::new(order_fill_id_1, test_object.now, dec!(1))
         );
 
-        test_object.timer_add_second();
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(1));
         let order_fill_id_3 = order_was_filled(&mut test_object, &mut buy_4);
         test_object.check_time(2);
         check_position(&test_object, dec!(3));
@@ -5198,7 +5529,10 @@ mod tests {
             )
         );
 
-        test_object.timer_add_second();
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(1));
         let _order_fill_id_4 = order_was_filled(&mut test_object, &mut buy_4);
         test_object.check_time(3);
         check_position(&test_object, dec!(7));
@@ -5219,7 +5553,10 @@ mod tests {
             )
         );
 
-        test_object.timer_add_second();
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(1));
         let _order_fill_id_5 = order_was_filled(&mut test_object, &mut sell_5);
         test_object.check_time(4);
         check_position(&test_object, dec!(2));
@@ -5240,7 +5577,10 @@ mod tests {
             )
         );
 
-        test_object.timer_add_second();
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(1));
         let order_fill_id_6 = order_was_filled(&mut test_object, &mut sell_5);
         test_object.check_time(5);
         check_position(&test_object, dec!(-3));
@@ -5261,7 +5601,10 @@ mod tests {
             )
         );
 
-        test_object.timer_add_second();
+        test_object
+            .balance_manager_base
+            .mock_clock
+            .advance_by(Duration::from_secs(1));
         let _order_fill_id_7 = order_was_filled(&mut test_object, &mut buy_1);
         let order_fill_id_8 = order_was_filled(&mut test_object, &mut buy_2);
 
@@ -5285,6 +5628,146 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn balance_snapshot_diff_splits_explained_and_unexplained_changes() {
+        init_logger();
+        let mut test_object = create_test_obj_with_multiple_currencies(
+            vec![
+                BalanceManagerBase::btc(),
+                BalanceManagerBase::eth(),
+                BalanceManagerBase::bnb(),
+            ],
+            vec![dec!(2), dec!(0.5), dec!(0.2)],
+        );
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+
+        test_object
+            .balance_manager()
+            .take_balance_snapshot(exchange_account_id, dec!(0.01))
+            .expect("in test");
+
+        let price = dec!(0.2);
+        let mut order = test_object
+            .balance_manager_base
+            .create_order(OrderSide::Buy, ReservationId::generate());
+        order.add_fill(BalanceManagerOrdinal::create_order_fill(
+            price,
+            dec!(5),
+            dec!(1),
+        ));
+        order_was_filled(&mut test_object, &mut order);
+
+        // buying 5 eth costs 1 btc, but 0.5 btc was withdrawn manually
+        BalanceManagerBase::update_balance(
+            &mut test_object.balance_manager(),
+            exchange_account_id,
+            hashmap![
+                BalanceManagerBase::btc() => dec!(0.5),
+                BalanceManagerBase::eth() => dec!(5.4),
+                BalanceManagerBase::bnb() => dec!(0.2)
+            ],
+        );
+
+        let balance_manager = test_object.balance_manager();
+        let diff = balance_manager
+            .get_balance_snapshot_diff(exchange_account_id)
+            .expect("in test");
+        let diff_by_currency_code = |currency_code| {
+            diff.diffs
+                .iter()
+                .find(|x| x.currency_code == currency_code)
+                .expect("in test")
+        };
+
+        let btc_diff = diff_by_currency_code(BalanceManagerBase::btc());
+        assert_eq!(btc_diff.explained_by_fills, dec!(-1));
+        assert_eq!(btc_diff.unexplained, dec!(-0.5));
+
+        // commission 0.1 is subtracted from received amount
+        let eth_diff = diff_by_currency_code(BalanceManagerBase::eth());
+        assert_eq!(eth_diff.explained_by_fills, dec!(4.9));
+        assert_eq!(eth_diff.unexplained, dec!(0));
+
+        let unexplained = diff.unexplained_above_threshold().collect_vec();
+        assert_eq!(unexplained, vec![btc_diff]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn balance_drift_warning_on_exchange_balance_update() {
+        init_logger();
+        let mut test_object = create_test_obj_with_multiple_currencies(
+            vec![
+                BalanceManagerBase::btc(),
+                BalanceManagerBase::eth(),
+                BalanceManagerBase::bnb(),
+            ],
+            vec![dec!(2), dec!(0.5), dec!(0.2)],
+        );
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+
+        let price = dec!(0.2);
+        let mut order = test_object
+            .balance_manager_base
+            .create_order(OrderSide::Buy, ReservationId::generate());
+        order.add_fill(BalanceManagerOrdinal::create_order_fill(
+            price,
+            dec!(5),
+            dec!(1),
+        ));
+        order_was_filled(&mut test_object, &mut order);
+
+        // buying 5 eth costs 1 btc, but 0.5 btc was withdrawn manually
+        BalanceManagerBase::update_balance(
+            &mut test_object.balance_manager(),
+            exchange_account_id,
+            hashmap![
+                BalanceManagerBase::btc() => dec!(0.5),
+                BalanceManagerBase::eth() => dec!(5.4),
+                BalanceManagerBase::bnb() => dec!(0.1)
+            ],
+        );
+
+        {
+            let balance_manager = test_object.balance_manager();
+            let warnings = balance_manager.get_balance_overflow_warnings(exchange_account_id);
+            assert_eq!(warnings.len(), 1);
+
+            let warning = &warnings[0];
+            assert_eq!(warning.currency_code, BalanceManagerBase::btc());
+            assert_eq!(warning.virtual_balance, dec!(1));
+            assert_eq!(warning.exchange_balance, dec!(0.5));
+            assert_eq!(warning.drift, dec!(1));
+        }
+
+        // virtual balances were reset by the previous update, so there is no drift anymore
+        BalanceManagerBase::update_balance(
+            &mut test_object.balance_manager(),
+            exchange_account_id,
+            hashmap![
+                BalanceManagerBase::btc() => dec!(0.5),
+                BalanceManagerBase::eth() => dec!(5.4),
+                BalanceManagerBase::bnb() => dec!(0.1)
+            ],
+        );
+
+        assert!(test_object
+            .balance_manager()
+            .get_balance_overflow_warnings(exchange_account_id)
+            .is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn balance_snapshot_requires_received_balances() {
+        init_logger();
+        let test_object = BalanceManagerOrdinal::new();
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+
+        assert!(test_object
+            .balance_manager()
+            .take_balance_snapshot(exchange_account_id, dec!(0.01))
+            .is_err());
+    }
+
     fn order_was_filled(
         test_object: &mut BalanceManagerOrdinal,
         order: &mut OrderSnapshot,
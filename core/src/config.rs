@@ -12,6 +12,7 @@ use toml_edit::{value, ArrayOfTables, Document, Table};
 pub static EXCHANGE_ACCOUNT_ID: &str = "exchange_account_id";
 pub static API_KEY: &str = "api_key";
 pub static SECRET_KEY: &str = "secret_key";
+pub static PASSPHRASE: &str = "passphrase";
 pub static CONFIG_PATH: &str = "config.toml";
 pub static CREDENTIALS_PATH: &str = "credentials.toml";
 
@@ -69,6 +70,19 @@ where
         .context("Unable parse combined settings")
 }
 
+/// Check that settings received in runtime with inline credentials can be applied:
+/// they should be deserializable to the full settings type and be semantically valid
+pub fn validate_settings<TSettings>(settings: &str) -> Result<()>
+where
+    TSettings: Clone + Debug + DeserializeOwned,
+{
+    let document: Document = settings.parse().context("Unable parse settings")?;
+    let settings = toml_edit::de::from_document::<AppSettings<TSettings>>(document)
+        .context("Unable parse settings")?;
+
+    settings.core.validate()
+}
+
 pub fn save_settings(settings: &str, config_path: &str, credentials_path: &str) -> Result<()> {
     let mut serialized_settings: Document = settings.parse()?;
 
@@ -81,10 +95,17 @@ pub fn save_settings(settings: &str, config_path: &str, credentials_path: &str)
         let (exchange_account_id, api_key, secret_key) = get_credentials_data(exchange_settings)
             .ok_or_else(|| anyhow!("Unable to get credentials data for exchange"))?;
 
-        let creds = hashmap![
+        let mut creds = hashmap![
             API_KEY => api_key,
             SECRET_KEY => secret_key
         ];
+        // Passphrase is optional because it's used by some exchanges only
+        if let Some(passphrase) = exchange_settings
+            .remove(PASSPHRASE)
+            .and_then(|v| v.as_str().map(ToOwned::to_owned))
+        {
+            let _ = creds.insert(PASSPHRASE, passphrase);
+        }
 
         credentials_per_exchange.insert(exchange_account_id, creds);
 
@@ -118,6 +139,7 @@ fn parse_toml_settings(settings: &str, credentials: &str) -> Result<Document> {
             let exchange_account_id = exchange
                 .get(EXCHANGE_ACCOUNT_ID)
                 .and_then(|v| v.as_str())
+                .map(str::to_owned)
                 .ok_or_else(|| {
                     anyhow!(
                 "Unable get 'exchange_account_id' for one of 'core.exchanges' from the settings"
@@ -125,14 +147,14 @@ fn parse_toml_settings(settings: &str, credentials: &str) -> Result<Document> {
                 })?;
 
             let api_key = credentials
-                .get(exchange_account_id)
+                .get(&exchange_account_id)
                 .and_then(|v| v.get(API_KEY))
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| {
                     anyhow!("Unable get 'api_key' for one of 'core.exchanges' from the settings")
                 })?;
             let secret_key = credentials
-                .get(exchange_account_id)
+                .get(&exchange_account_id)
                 .and_then(|v| v.get(SECRET_KEY))
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| {
@@ -145,6 +167,14 @@ fn parse_toml_settings(settings: &str, credentials: &str) -> Result<Document> {
 
             exchange.insert(API_KEY, value(api_key));
             exchange.insert(SECRET_KEY, value(secret_key));
+
+            let passphrase = credentials
+                .get(&exchange_account_id)
+                .and_then(|v| v.get(PASSPHRASE))
+                .and_then(|v| v.as_str());
+            if let Some(passphrase) = passphrase {
+                exchange.insert(PASSPHRASE, value(passphrase));
+            }
         }
     }
 
@@ -1,9 +1,11 @@
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 use thiserror::Error;
 use url::Url;
 
 mod websocket;
 mod websocket_connection;
+mod websocket_frames;
 
 #[derive(Error, Debug)]
 pub enum ConnectivityError {
@@ -21,6 +23,12 @@ pub enum ConnectivityError {
 
 pub type Result<T> = std::result::Result<T, ConnectivityError>;
 
+/// Time interval between pings are sent if no frames were received
+const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time interval without received frames after which connection is considered stale
+const DEFAULT_STALE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum WebSocketRole {
     Main,
@@ -39,11 +47,71 @@ impl Display for WebSocketRole {
 #[derive(Debug, Clone)]
 pub struct WebSocketParams {
     url: Url,
+    /// Extra headers of WebSocket handshake request, e.g. `Authorization`
+    headers: Vec<(String, String)>,
+    /// Protocol-level pings aren't sent if not set
+    ping_interval: Option<Duration>,
+    /// Connection is reset if no frames were received during this time.
+    /// Pings are always sent if set, so quiet but alive connection isn't reset
+    stale_timeout: Option<Duration>,
 }
 
 impl WebSocketParams {
     pub fn new(url: Url) -> Self {
-        WebSocketParams { url }
+        WebSocketParams {
+            url,
+            headers: Vec::new(),
+            ping_interval: Some(DEFAULT_PING_INTERVAL),
+            stale_timeout: Some(DEFAULT_STALE_TIMEOUT),
+        }
+    }
+
+    pub fn builder(url: Url) -> WebSocketParamsBuilder {
+        WebSocketParamsBuilder {
+            params: WebSocketParams::new(url),
+        }
+    }
+
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+}
+
+pub struct WebSocketParamsBuilder {
+    params: WebSocketParams,
+}
+
+impl WebSocketParamsBuilder {
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.params.headers.extend(headers);
+        self
+    }
+
+    pub fn ping_interval(mut self, ping_interval: Option<Duration>) -> Self {
+        self.params.ping_interval = ping_interval;
+        self
+    }
+
+    pub fn stale_timeout(mut self, stale_timeout: Option<Duration>) -> Self {
+        self.params.stale_timeout = stale_timeout;
+        self
+    }
+
+    pub fn build(mut self) -> WebSocketParams {
+        if let Some(stale_timeout) = self.params.stale_timeout {
+            // connection can be silent longer than stale timeout, so pings should be answered before it expires
+            let max_ping_interval = stale_timeout / 2;
+            let ping_interval = self.params.ping_interval.unwrap_or(max_ping_interval);
+            self.params.ping_interval = Some(ping_interval.min(max_ping_interval));
+        }
+
+        self.params
     }
 }
 
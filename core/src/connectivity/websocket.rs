@@ -1,4 +1,5 @@
 use super::websocket_connection::open_connection;
+use super::websocket_frames::split_text_message;
 use super::{ConnectivityError, Result, WebSocketParams, WebSocketRole};
 use crate::infrastructure::spawn_future;
 use futures::FutureExt;
@@ -13,26 +14,49 @@ pub struct WsSender {
     main_sender: mpsc::UnboundedSender<Message>,
     /// Secondary websocket connection sender
     secondary_sender: Option<mpsc::UnboundedSender<Message>>,
+    /// Messages bigger than this size in bytes are sent as continuation frames
+    max_frame_size: Option<usize>,
     /// Cancellation token for service futures
     _cancel: CancellationTokenDropGuard,
 }
 
 /// Websocket send end wrapper
 impl WsSender {
+    /// Split messages bigger than `max_frame_size` bytes, because some exchanges reject big frames
+    pub fn with_max_frame_size(mut self, max_frame_size: Option<usize>) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
     /// Send to main websocket
     pub fn send_main(&self, msg: String) -> Result<()> {
-        self.main_sender
-            .send(Message::Text(msg))
-            .map_err(|_| ConnectivityError::NotConnected)
+        self.send_text(&self.main_sender, msg)
     }
 
     /// Send to secondary websocket
     pub fn send_secondary(&self, msg: String) -> Result<()> {
-        self.secondary_sender
+        let sender = self
+            .secondary_sender
             .as_ref()
-            .ok_or(ConnectivityError::SecondaryConnectorIsNotPresent)?
-            .send(Message::Text(msg))
-            .map_err(|_| ConnectivityError::NotConnected)
+            .ok_or(ConnectivityError::SecondaryConnectorIsNotPresent)?;
+        self.send_text(sender, msg)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn send_text(&self, sender: &mpsc::UnboundedSender<Message>, msg: String) -> Result<()> {
+        let messages = match self.max_frame_size {
+            Some(max_frame_size) => split_text_message(msg, max_frame_size),
+            None => vec![Message::Text(msg)],
+        };
+
+        // fragments are written in order by the single writer, only control frames can be interleaved
+        for message in messages {
+            sender
+                .send(message)
+                .map_err(|_| ConnectivityError::NotConnected)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -79,6 +103,7 @@ async fn connect_both_parallel(
             let sender = WsSender {
                 main_sender: main.0,
                 secondary_sender: Some(secondary.0),
+                max_frame_size: None,
                 _cancel: cancel.drop_guard(),
             };
             spawn_future(
@@ -137,7 +162,84 @@ async fn connect_only_main(
     let sender = WsSender {
         main_sender: tx,
         secondary_sender: None,
+        max_frame_size: None,
         _cancel: cancel.drop_guard(),
     };
     Ok((sender, rx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use futures::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+    use tokio::time::{timeout, Duration};
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::{Data, OpCode};
+    use tokio_tungstenite::tungstenite::protocol::frame::Frame;
+
+    const MAX_FRAME_SIZE: usize = 64 * 1024;
+
+    /// Server receives single message and sends it back split into fragments
+    async fn start_echo_server() -> (WebSocketParams, oneshot::Receiver<Message>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("in test");
+        let address = listener.local_addr().expect("in test");
+
+        let (received_tx, received_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("in test");
+            let mut ws_stream = tokio_tungstenite::accept_async(stream)
+                .await
+                .expect("in test");
+
+            let received = ws_stream.next().await.expect("in test").expect("in test");
+            let payload = received.clone().into_data();
+            let _ = received_tx.send(received);
+
+            let chunks = payload.chunks(MAX_FRAME_SIZE).collect::<Vec<_>>();
+            for (index, chunk) in chunks.iter().enumerate() {
+                let opcode = match index {
+                    0 => OpCode::Data(Data::Text),
+                    _ => OpCode::Data(Data::Continue),
+                };
+                let frame = Frame::message(chunk.to_vec(), opcode, index == chunks.len() - 1);
+                ws_stream
+                    .send(Message::Frame(frame))
+                    .await
+                    .expect("in test");
+            }
+            std::future::pending::<()>().await;
+        });
+
+        let url = format!("ws://{address}").parse().expect("in test");
+        let params = WebSocketParams::builder(url).ping_interval(None).build();
+        (params, received_rx)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn oversized_message_is_split_and_reassembled() {
+        init_lifetime_manager();
+
+        let (params, received_rx) = start_echo_server().await;
+        let (sender, mut receiver) =
+            websocket_open(ExchangeAccountId::new("Binance", 0), params, None)
+                .await
+                .expect("in test");
+        let sender = sender.with_max_frame_size(Some(MAX_FRAME_SIZE));
+
+        let msg = "a".repeat(200 * 1024);
+        sender.send_main(msg.clone()).expect("in test");
+
+        let received = timeout(Duration::from_secs(5), received_rx)
+            .await
+            .expect("in test")
+            .expect("in test");
+        assert_eq!(received, Message::Text(msg.clone()));
+
+        let echoed = timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .expect("in test");
+        assert_eq!(echoed, Some(msg));
+    }
+}
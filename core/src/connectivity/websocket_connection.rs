@@ -8,16 +8,13 @@ use std::fmt::Formatter;
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio::time::{timeout, timeout_at, Duration, Instant};
-use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{connect_async_with_config, MaybeTlsStream, WebSocketStream};
 use tokio_util::sync::CancellationToken;
 
-/// Time interval between heartbeat pings are sent
-const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
-
-/// Time interval before lack of client response causes a timeout
-const HEARTBEAT_FAIL_TIMEOUT: Duration = Duration::from_secs(10);
-
 /// Write deadline
 ///
 /// This timeout is for networking buffer overflow detection.  
@@ -25,6 +22,9 @@ const WRITE_DEADLINE_TIMEOUT: Duration = Duration::from_secs(1);
 
 const PING_MESSAGE: &[u8; 9] = b"heartbeat";
 
+/// Reader wakes up with this interval if neither pings nor stale connection reset are enabled
+const NO_DEADLINE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 type TrySendResult = std::result::Result<(), mpsc::error::TrySendError<Message>>;
 
 /// Compound log records key
@@ -162,6 +162,10 @@ struct ReaderHandle {
     reader_tx: mpsc::UnboundedSender<String>,
     /// Channel to `WriterHandle`
     internal_tx: mpsc::Sender<Message>,
+    /// Pings are sent if no frames were received during this interval
+    ping_interval: Option<Duration>,
+    /// Connection is reset if no frames were received during this time
+    stale_timeout: Option<Duration>,
     /// Cancellation token.
     ///
     /// This one is bidirectional: we use it to trigger signal and to wait for the signal from
@@ -177,7 +181,7 @@ impl ReaderHandle {
         // receive date time point
         let mut receive_ts = Instant::now();
         // next heartbeat time point
-        let mut next_heartbeat_ts = receive_ts + HEARTBEAT_INTERVAL;
+        let mut next_ping_ts = self.ping_interval.map(|interval| receive_ts + interval);
 
         loop {
             let result = tokio::select! {
@@ -186,11 +190,11 @@ impl ReaderHandle {
                     log::debug!("Websocket {} reader received cancel signal", self.meta);
                     break;
                 }
-                res = timeout_at(next_heartbeat_ts, self.reader.next()) => res,
+                res = timeout_at(self.next_deadline(receive_ts, next_ping_ts), self.reader.next()) => res,
             };
 
             // wrap receive in heartbeat timer
-            // send heartbeats only if no data was received for HEARTBEAT_INTERVAL
+            // send heartbeats only if no data was received for ping interval
             let msg = match result {
                 Ok(Some(Err(e))) => {
                     log::error!("Websocket {} reader recv failure: {:?}", self.meta, e);
@@ -210,24 +214,36 @@ impl ReaderHandle {
 
                 Err(_) => {
                     // heartbeat timeout
-                    if receive_ts.elapsed() >= HEARTBEAT_FAIL_TIMEOUT {
-                        log::error!("Websocket {} reader reached heartbeat deadline", self.meta);
+                    let silence = receive_ts.elapsed();
+                    let is_stale = self
+                        .stale_timeout
+                        .is_some_and(|timeout| silence >= timeout);
+                    if is_stale {
+                        log::warn!(
+                            "Websocket {} reader didn't receive frames for {silence:?}, resetting stale connection",
+                            self.meta
+                        );
                         return;
                     }
-                    // will send heartbeat again after HEARTBEAT_INTERVAL
-                    next_heartbeat_ts += HEARTBEAT_INTERVAL;
 
-                    if (self.send_ping()).is_err() {
-                        log::error!("Websocket {} reader failed to send ping", self.meta);
-                        return;
-                    };
+                    if let (Some(ping_ts), Some(interval)) = (next_ping_ts, self.ping_interval) {
+                        if Instant::now() >= ping_ts {
+                            // will send heartbeat again after ping interval
+                            next_ping_ts = Some(ping_ts + interval);
+
+                            if (self.send_ping()).is_err() {
+                                log::error!("Websocket {} reader failed to send ping", self.meta);
+                                return;
+                            };
+                        }
+                    }
                     continue;
                 }
             };
 
             // received message processing
             receive_ts = Instant::now();
-            next_heartbeat_ts = receive_ts + HEARTBEAT_INTERVAL;
+            next_ping_ts = self.ping_interval.map(|interval| receive_ts + interval);
 
             match msg {
                 Message::Text(text) => {
@@ -264,7 +280,7 @@ impl ReaderHandle {
                     break;
                 }
                 Message::Frame(frame) => log::trace!(
-                    "Websocket {} reader received close with reason: {frame:?}",
+                    "Websocket {} reader received raw frame: {frame:?}",
                     self.meta
                 ),
             }
@@ -272,6 +288,16 @@ impl ReaderHandle {
         log::debug!("Websocket {} reader finished", self.meta);
     }
 
+    /// Time point of the next ping or of the stale connection detection
+    fn next_deadline(&self, receive_ts: Instant, next_ping_ts: Option<Instant>) -> Instant {
+        let stale_ts = self.stale_timeout.map(|timeout| receive_ts + timeout);
+        match (next_ping_ts, stale_ts) {
+            (Some(next_ping_ts), Some(stale_ts)) => next_ping_ts.min(stale_ts),
+            (Some(deadline), None) | (None, Some(deadline)) => deadline,
+            (None, None) => receive_ts + NO_DEADLINE_CHECK_INTERVAL,
+        }
+    }
+
     fn send_ping(&self) -> TrySendResult {
         log::trace!("Websocket {} reader triggers ping packet", self.meta);
         self.internal_tx
@@ -307,7 +333,10 @@ pub async fn open_connection(
     mpsc::UnboundedSender<Message>,
     mpsc::UnboundedReceiver<String>,
 )> {
-    let (ws_stream, _) = connect_async(params.url.clone())
+    let request = create_handshake_request(&params)
+        .map_err(|e| ConnectivityError::FailedToConnect(role, params.url.to_string(), e))?;
+
+    let (ws_stream, _) = connect_async_with_config(request, None)
         .await
         .map_err(|e| ConnectivityError::FailedToConnect(role, params.url.to_string(), e))?;
 
@@ -331,6 +360,8 @@ pub async fn open_connection(
         meta,
         internal_tx,
         reader_tx,
+        ping_interval: params.ping_interval,
+        stale_timeout: params.stale_timeout,
         cancel,
     };
 
@@ -347,3 +378,207 @@ pub async fn open_connection(
 
     Ok((writer_tx, reader_rx))
 }
+
+#[allow(clippy::result_large_err)]
+fn create_handshake_request(params: &WebSocketParams) -> tungstenite::Result<Request> {
+    let mut request = params.url.as_str().into_client_request()?;
+
+    let headers = request.headers_mut();
+    for (name, value) in &params.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+        let value =
+            HeaderValue::from_str(value).map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+        let _ = headers.insert(name, value);
+    }
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use url::Url;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn handshake_contains_headers() {
+        init_lifetime_manager();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("in test");
+        let address = listener.local_addr().expect("in test");
+
+        let (headers_tx, headers_rx) = oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("in test");
+            #[allow(clippy::result_large_err)]
+            let callback = |request: &Request, response: Response| {
+                let _ = headers_tx.send(request.headers().clone());
+                Ok(response)
+            };
+            tokio_tungstenite::accept_hdr_async(stream, callback)
+                .await
+                .expect("in test")
+        });
+
+        let url: Url = format!("ws://{address}").parse().expect("in test");
+        let params = WebSocketParams::builder(url)
+            .header("Authorization", "Bearer test_token")
+            .header("X-Api-Key", "test_key")
+            .build();
+
+        let cancel = CancellationToken::new();
+        let _connection = open_connection(
+            ExchangeAccountId::new("Binance", 0),
+            WebSocketRole::Main,
+            params,
+            cancel.clone(),
+        )
+        .await
+        .expect("in test");
+
+        let headers = headers_rx.await.expect("in test");
+        assert_eq!(headers["authorization"], "Bearer test_token");
+        assert_eq!(headers["x-api-key"], "test_key");
+
+        cancel.cancel();
+        let _ = server.await;
+    }
+
+    async fn start_silent_server() -> (Url, oneshot::Receiver<Option<Message>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("in test");
+        let address = listener.local_addr().expect("in test");
+
+        let (first_message_tx, first_message_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("in test");
+            let mut ws_stream = tokio_tungstenite::accept_async(stream)
+                .await
+                .expect("in test");
+
+            // Server never sends anything, only reports what was received from client
+            let first_message = ws_stream.next().await.and_then(|x| x.ok());
+            let _ = first_message_tx.send(first_message);
+            std::future::pending::<()>().await;
+        });
+
+        let url = format!("ws://{address}").parse().expect("in test");
+        (url, first_message_rx)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn stale_connection_is_reset() {
+        init_lifetime_manager();
+
+        let (url, _) = start_silent_server().await;
+        let params = WebSocketParams::builder(url)
+            .stale_timeout(Some(Duration::from_millis(200)))
+            .build();
+
+        let (_writer_tx, mut reader_rx) = open_connection(
+            ExchangeAccountId::new("Binance", 0),
+            WebSocketRole::Main,
+            params,
+            CancellationToken::new(),
+        )
+        .await
+        .expect("in test");
+
+        let closed = timeout(Duration::from_secs(2), reader_rx.recv())
+            .await
+            .expect("Stale connection should be reset");
+        assert_eq!(closed, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn ping_is_sent_when_no_frames_received() {
+        init_lifetime_manager();
+
+        let (url, first_message_rx) = start_silent_server().await;
+        let params = WebSocketParams::builder(url)
+            .ping_interval(Some(Duration::from_millis(50)))
+            .build();
+
+        let cancel = CancellationToken::new();
+        let _connection = open_connection(
+            ExchangeAccountId::new("Binance", 0),
+            WebSocketRole::Main,
+            params,
+            cancel.clone(),
+        )
+        .await
+        .expect("in test");
+
+        let first_message = timeout(Duration::from_secs(2), first_message_rx)
+            .await
+            .expect("in test")
+            .expect("in test");
+        assert_eq!(first_message, Some(Message::Ping(PING_MESSAGE.to_vec())));
+
+        cancel.cancel();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn ping_is_sent_before_stale_timeout_even_if_pings_are_disabled() {
+        init_lifetime_manager();
+
+        let (url, first_message_rx) = start_silent_server().await;
+        let params = WebSocketParams::builder(url)
+            .ping_interval(None)
+            .stale_timeout(Some(Duration::from_millis(200)))
+            .build();
+
+        let cancel = CancellationToken::new();
+        let _connection = open_connection(
+            ExchangeAccountId::new("Binance", 0),
+            WebSocketRole::Main,
+            params,
+            cancel.clone(),
+        )
+        .await
+        .expect("in test");
+
+        let first_message = timeout(Duration::from_secs(2), first_message_rx)
+            .await
+            .expect("in test")
+            .expect("in test");
+        assert_eq!(first_message, Some(Message::Ping(PING_MESSAGE.to_vec())));
+
+        cancel.cancel();
+    }
+
+    #[test]
+    fn ping_interval_is_shorter_than_stale_timeout() {
+        let url: Url = "ws://127.0.0.1".parse().expect("in test");
+
+        let params = WebSocketParams::builder(url.clone())
+            .ping_interval(None)
+            .stale_timeout(Some(Duration::from_secs(10)))
+            .build();
+        assert_eq!(params.ping_interval, Some(Duration::from_secs(5)));
+
+        let params = WebSocketParams::builder(url.clone())
+            .ping_interval(Some(Duration::from_secs(30)))
+            .stale_timeout(Some(Duration::from_secs(10)))
+            .build();
+        assert_eq!(params.ping_interval, Some(Duration::from_secs(5)));
+
+        let params = WebSocketParams::builder(url)
+            .ping_interval(None)
+            .stale_timeout(None)
+            .build();
+        assert_eq!(params.ping_interval, None);
+    }
+
+    #[test]
+    fn invalid_header_name_is_rejected() {
+        let url: Url = "ws://127.0.0.1".parse().expect("in test");
+        let params = WebSocketParams::new(url)
+            .with_headers(vec![("Invalid Header".to_owned(), "value".to_owned())]);
+
+        assert!(create_handshake_request(&params).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use tokio_tungstenite::tungstenite::protocol::frame::coding::{Data, OpCode};
+use tokio_tungstenite::tungstenite::protocol::frame::Frame;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Split text message to fragments with payload not bigger than `max_frame_size` bytes.
+/// Message which fits into single frame is sent as-is. Fragments are reassembled by the receiving side
+/// of websocket, including our reader which gets complete messages from `tungstenite`
+pub(super) fn split_text_message(msg: String, max_frame_size: usize) -> Vec<Message> {
+    let max_frame_size = max_frame_size.max(1);
+    if msg.len() <= max_frame_size {
+        return vec![Message::Text(msg)];
+    }
+
+    // UTF-8 is validated only for the complete message, so a char can be split between frames
+    let chunks = msg.as_bytes().chunks(max_frame_size);
+    let last_index = chunks.len() - 1;
+    chunks
+        .enumerate()
+        .map(|(index, chunk)| {
+            let opcode = match index {
+                0 => OpCode::Data(Data::Text),
+                _ => OpCode::Data(Data::Continue),
+            };
+            Message::Frame(Frame::message(chunk.to_vec(), opcode, index == last_index))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_FRAME_SIZE: usize = 64 * 1024;
+
+    fn into_frame(message: Message) -> Frame {
+        match message {
+            Message::Frame(frame) => frame,
+            _ => panic!("Expected frame but got {message:?}"),
+        }
+    }
+
+    #[test]
+    fn big_message_is_split_into_frames() {
+        let msg = "ёa".repeat(200 * 1024 / 3);
+        assert!(msg.len() > 3 * MAX_FRAME_SIZE);
+
+        let frames = split_text_message(msg.clone(), MAX_FRAME_SIZE);
+        assert_eq!(frames.len(), 4);
+
+        let frames = frames.into_iter().map(into_frame).collect::<Vec<_>>();
+        for (index, frame) in frames.iter().enumerate() {
+            let expected_opcode = match index {
+                0 => OpCode::Data(Data::Text),
+                _ => OpCode::Data(Data::Continue),
+            };
+            assert_eq!(frame.header().opcode, expected_opcode);
+            assert_eq!(frame.header().is_final, index == frames.len() - 1);
+            assert!(frame.payload().len() <= MAX_FRAME_SIZE);
+        }
+
+        let payload = frames.into_iter().flat_map(Frame::into_data).collect();
+        assert_eq!(String::from_utf8(payload).expect("in test"), msg);
+    }
+
+    #[test]
+    fn small_message_is_not_split() {
+        let msg = "small message".to_owned();
+
+        let frames = split_text_message(msg.clone(), MAX_FRAME_SIZE);
+
+        assert_eq!(frames, vec![Message::Text(msg)]);
+    }
+}
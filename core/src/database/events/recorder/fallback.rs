@@ -222,17 +222,15 @@ mod tests {
     use crate::database::events::recorder::fallback::{
         load_from_file, EventRecorderFallback, PostponedEventsFileFormat,
     };
-    use bb8_postgres::bb8::PooledConnection;
-    use bb8_postgres::PostgresConnectionManager;
     use chrono::Utc;
     use mmb_database::impl_event;
     use mmb_database::postgres_db::events::{Event, InsertEvent};
     use mmb_database::postgres_db::tests::{get_database_url, PgPoolMutex};
+    use mmb_database::postgres_db::PgConnection;
     use mmb_utils::DateTime;
     use scopeguard::defer;
     use serde::{Deserialize, Serialize};
     use std::fs;
-    use tokio_postgres::NoTls;
 
     const TABLE_NAME: &str = "fallback_events";
 
@@ -255,9 +253,7 @@ mod tests {
         pool_mutex
     }
 
-    async fn recreate_table<'a>(
-        connection: &'a PooledConnection<'a, PostgresConnectionManager<NoTls>>,
-    ) {
+    async fn recreate_table<'a>(connection: &'a PgConnection<'a>) {
         let sql = include_str!(
             "../../../../../mmb_database/src/postgres_db/sql/create_or_truncate_table.sql"
         )
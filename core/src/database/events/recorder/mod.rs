@@ -9,6 +9,7 @@ use mmb_database::postgres_db::events::{
 use mmb_database::postgres_db::PgPool;
 use mmb_utils::infrastructure::SpawnFutureFlags;
 use mmb_utils::logger::print_info;
+use mmb_utils::time::jittered_interval;
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::mem;
@@ -16,6 +17,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot};
+use tokio::time::sleep;
 
 const BATCH_MAX_SIZE: usize = 65_536;
 const BATCH_SIZE_TO_SAVE: usize = 250;
@@ -36,9 +38,11 @@ pub struct EventRecorder {
 }
 
 impl EventRecorder {
+    /// `jitter_fraction` is a fraction of random jitter added to saving and restoring intervals
     pub async fn start(
         pool: Option<PgPool>,
         postponed_events_dir: Option<PathBuf>,
+        jitter_fraction: f64,
     ) -> Result<Arc<EventRecorder>> {
         let (data_tx, data_rx) = mpsc::channel(20_000);
         let (shutdown_signal_tx, shutdown_signal_rx) = mpsc::unbounded_channel();
@@ -64,12 +68,13 @@ impl EventRecorder {
                         shutdown_signal_rx,
                         shutdown_tx,
                         fallback.clone(),
+                        jitter_fraction,
                     ),
                 );
                 let _ = spawn_future(
                     "start postponed events restoring",
                     SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
-                    start_postponed_events_restoring(pool, fallback),
+                    start_postponed_events_restoring(pool, fallback, jitter_fraction),
                 );
                 print_info("EventRecorder started");
             }
@@ -100,6 +105,20 @@ impl EventRecorder {
         Ok(())
     }
 
+    /// Recorder that sends saved events to returned receiver instead of database
+    #[cfg(test)]
+    pub(crate) fn with_channel() -> (Arc<EventRecorder>, mpsc::Receiver<(TableName, InsertEvent)>) {
+        let (data_tx, data_rx) = mpsc::channel(20_000);
+        let (shutdown_signal_tx, _) = mpsc::unbounded_channel();
+
+        let event_recorder = Arc::new(Self {
+            data_tx,
+            shutdown_signal_tx,
+            shutdown_rx: Mutex::new(None),
+        });
+        (event_recorder, data_rx)
+    }
+
     pub async fn flush_and_stop(&self) -> Result<()> {
         let _ = self.shutdown_signal_tx.send(());
         let receiver = self.shutdown_rx.lock().take();
@@ -115,30 +134,36 @@ impl EventRecorder {
 async fn start_postponed_events_restoring(
     pool: PgPool,
     fallback: EventRecorderFallback,
+    jitter_fraction: f64,
 ) -> Result<()> {
-    let mut interval = tokio::time::interval(RESTORING_EVENTS_TIMEOUT);
     loop {
-        let _ = interval.tick().await;
+        restore_postponed_events(&pool, &fallback).await?;
 
-        let mut file_names = fallback
-            .get_existing_postponed_events_file_names()
-            .await
-            .context("can't get existing postponed events files")?;
+        sleep(jittered_interval(RESTORING_EVENTS_TIMEOUT, jitter_fraction)).await;
+    }
+}
 
-        if file_names.is_empty() {
-            // nothing to restore
-            continue;
-        }
+async fn restore_postponed_events(pool: &PgPool, fallback: &EventRecorderFallback) -> Result<()> {
+    let mut file_names = fallback
+        .get_existing_postponed_events_file_names()
+        .await
+        .context("can't get existing postponed events files")?;
 
-        if !pool.is_connection_health().await {
-            continue;
-        }
+    if file_names.is_empty() {
+        // nothing to restore
+        return Ok(());
+    }
 
-        file_names.sort();
-        fallback
-            .try_restore_to_db_postponed_events(&pool, &file_names)
-            .await;
+    if !pool.is_connection_health().await {
+        return Ok(());
     }
+
+    file_names.sort();
+    fallback
+        .try_restore_to_db_postponed_events(pool, &file_names)
+        .await;
+
+    Ok(())
 }
 
 async fn start_db_event_recorder(
@@ -147,6 +172,7 @@ async fn start_db_event_recorder(
     mut shutdown_signal_rx: mpsc::UnboundedReceiver<()>,
     shutdown_tx: oneshot::Sender<Result<()>>,
     fallback: EventRecorderFallback,
+    jitter_fraction: f64,
 ) -> Result<()> {
     fn create_batch_size_vec() -> Vec<InsertEvent> {
         Vec::<InsertEvent>::with_capacity(BATCH_MAX_SIZE)
@@ -166,8 +192,11 @@ async fn start_db_event_recorder(
         }
     }
     let mut events_map = HashMap::<TableName, EventsByTableName>::new();
+    // period is jittered once, so instances of recorder started at the same time don't save batches simultaneously
+    let saving_period = jittered_interval(SAVING_TIMEOUT, jitter_fraction);
+    let mut interval =
+        tokio::time::interval_at(tokio::time::Instant::now() + saving_period, saving_period);
     loop {
-        let mut interval = tokio::time::interval(SAVING_TIMEOUT);
         tokio::select! {
             _ = shutdown_signal_rx.recv() => break, // in any case we should correctly finish
             result = data_rx.recv() => {
@@ -190,7 +219,7 @@ async fn start_db_event_recorder(
             },
             _ = interval.tick() => {
                 for (table_name, EventsByTableName { ref mut events, ref mut last_time_to_save }) in &mut events_map {
-                    if last_time_to_save.elapsed() < SAVING_TIMEOUT {
+                    if !events.is_empty() {
                         let events = mem::replace(events, create_batch_size_vec());
                         save_batch(&pool, table_name, events, &fallback).await.context("from `start_db_event_recorder` in `save_batch`")?;
 
@@ -330,7 +359,7 @@ mod tests {
     async fn save_1_event() {
         let pool_mutex = init_test().await;
 
-        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None)
+        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None, 0.0)
             .await
             .expect("in test");
 
@@ -358,7 +387,9 @@ mod tests {
         let person = test_person();
 
         // act
-        let event_recorder = EventRecorder::start(None, None).await.expect("in test");
+        let event_recorder = EventRecorder::start(None, None, 0.0)
+            .await
+            .expect("in test");
 
         event_recorder.save(person).expect("in test");
 
@@ -381,7 +412,7 @@ mod tests {
         let person = test_person();
 
         // act
-        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None)
+        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None, 0.0)
             .await
             .expect("in test");
         let connection = pool_mutex.pool.get_connection_expected().await;
@@ -0,0 +1,93 @@
+use crate::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_domain::events::{EventSourceType, TradeId};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderStatus, Price};
+use mmb_utils::DateTime;
+
+/// Order is considered filled in dry run mode when mid price reaches its price
+pub(crate) fn is_crossed_by_mid_price(side: OrderSide, price: Price, mid_price: Price) -> bool {
+    match side {
+        OrderSide::Buy => mid_price <= price,
+        OrderSide::Sell => mid_price >= price,
+    }
+}
+
+/// Synthetic fill of full order amount by order price.
+/// `None` if order isn't created or it is already finished
+pub(crate) fn simulated_fill_event(order: &OrderRef, now: DateTime) -> Option<FillEvent> {
+    if order.status() != OrderStatus::Created {
+        return None;
+    }
+
+    let exchange_order_id = order.exchange_order_id()?;
+    Some(FillEvent {
+        source_type: EventSourceType::WebSocket,
+        trade_id: Some(TradeId::String(
+            format!("dry_run_{}", order.client_order_id()).into(),
+        )),
+        client_order_id: Some(order.client_order_id()),
+        exchange_order_id,
+        fill_price: order.price(),
+        fill_amount: FillAmount::Total {
+            total_filled_amount: order.amount(),
+        },
+        order_role: Some(OrderRole::Maker),
+        commission_currency_code: None,
+        commission_rate: None,
+        commission_amount: None,
+        fill_type: OrderFillType::UserTrade,
+        special_order_data: None,
+        fill_date: Some(now),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper;
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId};
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    #[rstest]
+    #[case::buy_above_mid(OrderSide::Buy, dec!(101), true)]
+    #[case::buy_at_mid(OrderSide::Buy, dec!(100), true)]
+    #[case::buy_below_mid(OrderSide::Buy, dec!(99), false)]
+    #[case::sell_below_mid(OrderSide::Sell, dec!(99), true)]
+    #[case::sell_at_mid(OrderSide::Sell, dec!(100), true)]
+    #[case::sell_above_mid(OrderSide::Sell, dec!(101), false)]
+    fn crossing_by_mid_price(
+        #[case] side: OrderSide,
+        #[case] price: Price,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(is_crossed_by_mid_price(side, price, dec!(100)), expected);
+    }
+
+    #[test]
+    fn fill_event_only_for_created_order() {
+        let order = test_helper::create_order_ref(
+            &ClientOrderId::unique_id(),
+            Some(OrderRole::Maker),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("eth".into(), "btc".into()),
+            dec!(0.5),
+            dec!(2),
+            OrderSide::Buy,
+        );
+        assert!(simulated_fill_event(&order, Utc::now()).is_none());
+
+        order.fn_mut(|x| {
+            x.props.exchange_order_id = Some(ExchangeOrderId::new("1".into()));
+            x.set_status(OrderStatus::Created, Utc::now());
+        });
+
+        let fill_event = simulated_fill_event(&order, Utc::now()).expect("in test");
+        assert_eq!(fill_event.client_order_id, Some(order.client_order_id()));
+        assert_eq!(fill_event.fill_price, dec!(0.5));
+        assert_eq!(fill_event.fill_amount.total_filled_amount(), Some(dec!(2)));
+    }
+}
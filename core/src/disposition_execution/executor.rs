@@ -2,23 +2,32 @@ use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Result};
-use chrono::Utc;
 use itertools::Itertools;
 use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
 use mmb_utils::{nothing_to_do, DateTime};
+use mockall_double::double;
 use parking_lot::Mutex;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tokio::sync::{broadcast, oneshot};
+use tokio::time::{Instant, MissedTickBehavior};
 
+use crate::balance::balance_reservation_manager::ReserveError;
+use crate::disposition_execution::dry_run::{is_crossed_by_mid_price, simulated_fill_event};
+use crate::disposition_execution::min_profit_filter::MinProfitFilter;
 use crate::disposition_execution::strategy::DispositionStrategy;
+use crate::disposition_execution::strategy_metrics::StrategyMetrics;
 use crate::disposition_execution::trading_context_calculation::calculate_trading_context;
+use crate::disposition_execution::watchdog::LastDecisionTime;
 use crate::exchanges::general::exchange::Exchange;
 use crate::exchanges::general::request_type::RequestType;
-use crate::explanation::{Explanation, WithExplanation};
+use crate::explanation::{Explanation, ReasonCode, WithExplanation};
 use crate::lifecycle::trading_engine::{EngineContext, Service};
 use crate::misc::reserve_parameters::ReserveParameters;
+#[double]
+use crate::misc::time::time_manager;
 use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::telemetry;
 use crate::{
     disposition_execution::trade_limit::is_enough_amount_and_cost, infrastructure::spawn_future,
 };
@@ -32,7 +41,7 @@ use chrono::Duration;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::CurrencyPair;
-use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId, MarketId};
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{Amount, Price, UserOrder};
@@ -45,6 +54,8 @@ static DISPOSITION_EXECUTOR: &str = "DispositionExecutor";
 static DISPOSITION_EXECUTOR_REQUESTS_GROUP: &str = "DispositionExecutorRG";
 const ALLOWED_AMOUNT_DEVIATION_RATE: Decimal = dec!(0.001);
 const GROUP_REQUESTS_COUNT: usize = 4;
+const ORDER_TTL_CHECK_PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
+const STATE_GRAPH_PUBLISH_PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
 
 struct DisplaySmallOrder {
     price: Decimal,
@@ -59,6 +70,7 @@ impl Display for DisplaySmallOrder {
 
 pub struct DispositionExecutorService {
     work_finished_receiver: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+    last_decision_at: LastDecisionTime,
 }
 
 impl DispositionExecutorService {
@@ -72,23 +84,38 @@ impl DispositionExecutorService {
         strategy: Box<dyn DispositionStrategy>,
         cancellation_token: CancellationToken,
         statistics: Arc<StatisticService>,
+        price_slots_count: usize,
+        min_profit_bps: Option<Decimal>,
+        order_ttl: Option<std::time::Duration>,
+        timer_interval: Option<std::time::Duration>,
+        strategy_metrics: Box<dyn StrategyMetrics>,
     ) -> Arc<Self> {
         let (work_finished_sender, receiver) = oneshot::channel();
+        let last_decision_at = LastDecisionTime::new(now());
+
+        let action = {
+            let last_decision_at = last_decision_at.clone();
+            async move {
+                let mut disposition_executor = DispositionExecutor::new(
+                    engine_ctx,
+                    events_receiver,
+                    local_snapshots_service,
+                    exchange_account_id,
+                    currency_pair,
+                    strategy,
+                    work_finished_sender,
+                    cancellation_token,
+                    statistics,
+                    last_decision_at,
+                    price_slots_count,
+                    min_profit_bps,
+                    order_ttl,
+                    timer_interval,
+                    strategy_metrics,
+                )?;
 
-        let action = async move {
-            let mut disposition_executor = DispositionExecutor::new(
-                engine_ctx,
-                events_receiver,
-                local_snapshots_service,
-                exchange_account_id,
-                currency_pair,
-                strategy,
-                work_finished_sender,
-                cancellation_token,
-                statistics,
-            );
-
-            disposition_executor.start().await
+                disposition_executor.start().await
+            }
         };
         spawn_future(
             "Start disposition executor",
@@ -98,8 +125,18 @@ impl DispositionExecutorService {
 
         Arc::new(DispositionExecutorService {
             work_finished_receiver: Mutex::new(Some(receiver)),
+            last_decision_at,
         })
     }
+
+    pub fn last_decision_at(&self) -> LastDecisionTime {
+        self.last_decision_at.clone()
+    }
+
+    /// Time passed since the last iteration of DispositionExecutor loop
+    pub fn last_decision_age(&self) -> std::time::Duration {
+        self.last_decision_at.age(now())
+    }
 }
 
 impl Service for DispositionExecutorService {
@@ -120,14 +157,25 @@ impl Service for DispositionExecutorService {
 struct DispositionExecutor {
     engine_ctx: Arc<EngineContext>,
     exchange_account_id: ExchangeAccountId,
+    exchange: Arc<Exchange>,
     symbol: Arc<Symbol>,
     events_receiver: broadcast::Receiver<ExchangeEvent>,
     local_snapshots_service: LocalSnapshotsService,
     orders_state: OrdersState,
     strategy: Box<dyn DispositionStrategy>,
+    min_profit_filter: Option<MinProfitFilter>,
+    order_ttl: Option<std::time::Duration>,
+    /// Interval of calling `DispositionStrategy::on_timer`
+    timer_interval: Option<std::time::Duration>,
     work_finished_sender: Option<oneshot::Sender<Result<()>>>,
     cancellation_token: CancellationToken,
     statistics: Arc<StatisticService>,
+    last_decision_at: LastDecisionTime,
+    /// Fills are simulated by mid price because orders aren't placed on exchange in dry run mode
+    dry_run: bool,
+    strategy_metrics: Box<dyn StrategyMetrics>,
+    /// Count of trading context calculations
+    cycles_count: u64,
 }
 
 impl DispositionExecutor {
@@ -142,34 +190,93 @@ impl DispositionExecutor {
         work_finished_sender: oneshot::Sender<Result<()>>,
         cancellation_token: CancellationToken,
         statistics: Arc<StatisticService>,
-    ) -> Self {
-        let symbol = engine_ctx
-            .exchanges
-            .get(&exchange_account_id)
-            .expect("Target exchange should exists")
+        last_decision_at: LastDecisionTime,
+        price_slots_count: usize,
+        min_profit_bps: Option<Decimal>,
+        order_ttl: Option<std::time::Duration>,
+        timer_interval: Option<std::time::Duration>,
+        strategy_metrics: Box<dyn StrategyMetrics>,
+    ) -> Result<Self> {
+        let exchange = engine_ctx.get_exchange(exchange_account_id)?;
+        let symbol = exchange
             .get_symbol(currency_pair)
             .expect("Currency pair symbol should exists for target trading place");
+        let dry_run = exchange.is_dry_run();
+
+        let min_profit_filter = min_profit_bps.map(|min_profit_bps| {
+            // fee rates are loaded from exchanges on their creation, so they are already known here
+            let taker_fee_rates = engine_ctx
+                .exchanges
+                .iter()
+                .flat_map(|exchange| {
+                    exchange
+                        .symbols
+                        .iter()
+                        .map(|symbol| {
+                            let currency_pair = *symbol.key();
+                            let market_account_id =
+                                MarketAccountId::new(exchange.exchange_account_id, currency_pair);
+                            (market_account_id, exchange.taker_fee(currency_pair))
+                        })
+                        .collect_vec()
+                })
+                .collect();
+            MinProfitFilter::new(min_profit_bps, taker_fee_rates)
+        });
 
-        DispositionExecutor {
+        Ok(DispositionExecutor {
             engine_ctx,
             events_receiver,
             local_snapshots_service,
             exchange_account_id,
+            exchange,
             symbol,
-            orders_state: OrdersState::new(),
+            orders_state: OrdersState::new(price_slots_count),
             strategy,
+            min_profit_filter,
+            order_ttl,
+            // zero interval means a timer that never sleeps, so it's considered as not set
+            timer_interval: timer_interval.filter(|interval| !interval.is_zero()),
             work_finished_sender: Some(work_finished_sender),
             cancellation_token,
             statistics,
-        }
+            last_decision_at,
+            dry_run,
+            strategy_metrics,
+            cycles_count: 0,
+        })
     }
 
     pub async fn start(&mut self) -> Result<()> {
         let mut trading_context: Option<TradingContext> = None;
+        let need_check_order_ttl = self.order_ttl.is_some();
+        let mut order_ttl_check_interval = tokio::time::interval(ORDER_TTL_CHECK_PERIOD);
+        let need_call_timer = self.timer_interval.is_some();
+        let timer_interval = self.timer_interval.unwrap_or(ORDER_TTL_CHECK_PERIOD);
+        // first tick of `interval` completes immediately, but strategy timer is called only after the whole interval
+        let mut strategy_timer =
+            tokio::time::interval_at(Instant::now() + timer_interval, timer_interval);
+        strategy_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut state_graph_publish_interval = tokio::time::interval(STATE_GRAPH_PUBLISH_PERIOD);
 
         loop {
+            // every iteration is a heartbeat, so watchdog doesn't fire in quiet market without events
+            self.last_decision_at.update(now());
+
             let event = tokio::select! {
                 event_res = self.events_receiver.recv() => event_res.map_err(|e| anyhow!("Error during receiving event in DispositionExecutor::start(). Error: {e}."))?,
+                _ = order_ttl_check_interval.tick(), if need_check_order_ttl => {
+                    self.cancel_expired_orders(now());
+                    continue;
+                }
+                _ = strategy_timer.tick(), if need_call_timer => {
+                    self.handle_timer(&mut trading_context)?;
+                    continue;
+                }
+                _ = state_graph_publish_interval.tick() => {
+                    self.engine_ctx.executor_state_graph.update(self.dump_state_dot());
+                    continue;
+                }
                 _ = self.cancellation_token.when_cancelled() => {
                     let _ = self.work_finished_sender.take().ok_or_else(|| anyhow!("Can't take `work_finished_sender` in DispositionExecutor"))?.send(Ok(()));
                     return Ok(());
@@ -180,6 +287,11 @@ impl DispositionExecutor {
         }
     }
 
+    /// Current price slots and orders in them in Graphviz DOT format
+    pub fn dump_state_dot(&self) -> String {
+        self.orders_state.to_dot()
+    }
+
     fn handle_event(
         &mut self,
         event: &ExchangeEvent,
@@ -191,6 +303,9 @@ impl DispositionExecutor {
         match event {
             ExchangeEvent::OrderBookEvent(order_book_event) => {
                 let _ = self.local_snapshots_service.update(order_book_event);
+                if self.dry_run {
+                    self.simulate_fills(now);
+                }
             }
             ExchangeEvent::OrderEvent(order_event) => {
                 let order = &order_event.order;
@@ -199,11 +314,13 @@ impl DispositionExecutor {
                 }
 
                 match order_event.event_type {
-                    OrderEventType::CreateOrderSucceeded => nothing_to_do(),
+                    OrderEventType::CreateOrderSucceeded => self.order_created(order, now),
                     OrderEventType::CreateOrderFailed => {
                         let client_order_id = order.client_order_id();
                         log::trace!("Started handling event CreateOrderFailed {client_order_id} in DispositionExecutor");
-                        let Some(price_slot) = self.get_price_slot(order) else { return Ok(()); };
+                        let Some(price_slot) = self.get_price_slot(order) else {
+                            return Ok(());
+                        };
 
                         self.finish_order(order, price_slot)?;
                         log::trace!("Finished handling event CreateOrderFailed {client_order_id} in DispositionExecutor");
@@ -271,13 +388,67 @@ impl DispositionExecutor {
             _ => nothing_to_do(),
         };
 
-        let mut new_trading_context = estimate_trading_context(
-            need_recalculate_trading_context,
+        self.update_trading_context(
             event,
-            self.strategy.as_mut(),
-            &self.local_snapshots_service,
+            need_recalculate_trading_context,
+            last_trading_context,
             now,
-        )?;
+        )
+    }
+
+    /// Strategy state can be changed by timer without any exchange event,
+    /// so trading context is recalculated and orders are synchronized with it right after the timer
+    fn handle_timer(&mut self, last_trading_context: &mut Option<TradingContext>) -> Result<()> {
+        let now = now();
+        self.strategy.on_timer(now);
+
+        self.update_trading_context(&ExchangeEvent::TimerTick, true, last_trading_context, now)
+    }
+
+    fn update_trading_context(
+        &mut self,
+        event: &ExchangeEvent,
+        need_recalculate_trading_context: bool,
+        last_trading_context: &mut Option<TradingContext>,
+        now: DateTime,
+    ) -> Result<()> {
+        if self.engine_ctx.lifetime_manager.is_paused() {
+            return Ok(());
+        }
+
+        let mut new_trading_context = {
+            let _span = need_recalculate_trading_context.then(|| {
+                telemetry::start_span(
+                    "calculate_trading_context",
+                    self.exchange_account_id,
+                    self.symbol.currency_pair(),
+                )
+            });
+
+            let tick = self.cycles_count;
+            let started_at = need_recalculate_trading_context.then(|| {
+                self.cycles_count += 1;
+                self.strategy_metrics.on_cycle_start(tick);
+                std::time::Instant::now()
+            });
+
+            let trading_context = estimate_trading_context(
+                need_recalculate_trading_context,
+                event,
+                self.strategy.as_mut(),
+                &self.local_snapshots_service,
+                self.orders_state.price_slots_count(),
+                self.min_profit_filter.as_ref(),
+                now,
+            )?;
+
+            if let Some(started_at) = started_at {
+                self.strategy_metrics
+                    .on_cycle_end(tick, started_at.elapsed());
+            }
+
+            trading_context
+        };
 
         if last_trading_context == &mut new_trading_context {
             return Ok(());
@@ -315,6 +486,11 @@ impl DispositionExecutor {
             self.symbol.currency_pair(),
         );
 
+        self.statistics.register_explanations(
+            MarketAccountId::new(self.exchange_account_id, self.symbol.currency_pair()),
+            &explanations,
+        );
+
         self.engine_ctx
             .event_recorder
             .save(explanations)
@@ -422,7 +598,10 @@ impl DispositionExecutor {
                 composite_order_ref.price
             ));
 
-            let remaining_amount = composite_order_ref.remaining_amount();
+            let remaining_amount = match composite_order_ref.is_empty() {
+                true => dec!(0),
+                false => composite_order_ref.remaining_amount(),
+            };
             if remaining_amount >= desired_amount {
                 let desired_amount_with_allowed_deviation =
                     desired_amount * (dec!(1) + ALLOWED_AMOUNT_DEVIATION_RATE);
@@ -496,6 +675,86 @@ impl DispositionExecutor {
         Ok(())
     }
 
+    fn order_created(&self, order: &OrderRef, now: DateTime) {
+        let Some(price_slot) = self.get_price_slot(order) else {
+            return;
+        };
+
+        if let Some(order_record) = price_slot
+            .order
+            .borrow_mut()
+            .orders
+            .get_mut(&order.client_order_id())
+        {
+            order_record.created_at = Some(now);
+        }
+    }
+
+    /// Cancel orders which weren't filled during TTL since creation acknowledged by exchange
+    fn cancel_expired_orders(&self, now: DateTime) {
+        for orders_state_by_side in self.orders_state.by_side.values() {
+            for price_slot in orders_state_by_side.traverse_price_slots() {
+                let mut composite_order = price_slot.order.borrow_mut();
+                let expired_order_records = composite_order
+                    .orders
+                    .values_mut()
+                    .filter(|or| or.is_expired(now))
+                    .collect_vec();
+                if expired_order_records.is_empty() {
+                    continue;
+                }
+
+                let mut explanation = Explanation::default();
+                self.start_cancelling_orders_with_cause(
+                    "order TTL elapsed",
+                    expired_order_records.into_iter(),
+                    &mut explanation,
+                );
+            }
+        }
+    }
+
+    /// Fill own orders crossed by mid price of target market in dry run mode
+    fn simulate_fills(&self, now: DateTime) {
+        let market_id = MarketId::new(
+            self.exchange_account_id.exchange_id,
+            self.symbol.currency_pair(),
+        );
+        let Some(mid_price) = self.local_snapshots_service.mid_price(market_id, None) else {
+            return;
+        };
+
+        let mut fill_events = Vec::new();
+        for orders_state_by_side in self.orders_state.by_side.values() {
+            for price_slot in orders_state_by_side.traverse_price_slots() {
+                let composite_order = price_slot.order.borrow();
+                fill_events.extend(
+                    composite_order
+                        .orders
+                        .values()
+                        .map(|or| &or.order)
+                        .filter(|order| {
+                            is_crossed_by_mid_price(order.side(), order.price(), mid_price)
+                        })
+                        .filter_map(|order| simulated_fill_event(order, now)),
+                );
+            }
+        }
+
+        if fill_events.is_empty() {
+            return;
+        }
+
+        let exchange = self.exchange.clone();
+        for mut fill_event in fill_events {
+            log::info!(
+                "Dry run: simulated fill of order {:?} by mid price {mid_price}",
+                fill_event.client_order_id
+            );
+            exchange.handle_order_filled(&mut fill_event);
+        }
+    }
+
     fn start_cancelling_all_orders(
         &self,
         cause: &str,
@@ -546,10 +805,18 @@ impl DispositionExecutor {
         log::trace!("Begin cancel_order {client_order_id}");
 
         let request_group_id = order_record.request_group_id;
-        let exchange = self.exchange();
+        let exchange = self.exchange.clone();
         let cancellation_token = self.cancellation_token.clone();
 
         let action = async move {
+            let _span = telemetry::start_span(
+                "cancel_order",
+                order.exchange_account_id(),
+                order.currency_pair(),
+            )
+            .with_side(order.side())
+            .with_order_id(&client_order_id);
+
             log::trace!("Begin wait_cancel_order {client_order_id}");
             exchange
                 .wait_cancel_order(order, Some(request_group_id), false, cancellation_token)
@@ -596,7 +863,19 @@ impl DispositionExecutor {
         let found = self.find_new_order_crossing_existing_orders(new_price, side);
         if let Some(crossed_order) = found {
             let msg = format!("Finished `try_create_order` because there is order {} with price {} that crossing current price {new_price}", crossed_order.client_order_id(), crossed_order.price());
-            return log_trace(msg, explanation);
+            return log_trace_with_code(ReasonCode::PriceCrossed, msg, explanation);
+        }
+
+        // `Exchange::create_order` rejects such orders too, but it's checked before reservation
+        // of balance to explain why the order isn't created
+        if let Err(reason) = self
+            .exchange
+            .check_price_band(self.symbol.currency_pair(), new_price)
+        {
+            let msg = format!("Finished `try_create_order` by reason: {reason}");
+            log::warn!("{msg}");
+            explanation.add_reason_with_code(ReasonCode::PriceOutOfBand, msg);
+            return Ok(());
         }
 
         let new_order_amount = self.calculate_new_order_amount(
@@ -610,12 +889,26 @@ impl DispositionExecutor {
         if let Err(reason) =
             is_enough_amount_and_cost(new_disposition, new_order_amount, true, &self.symbol)
         {
-            return log_trace(
+            return log_trace_with_code(
+                ReasonCode::BelowMinNotional,
                 format!("Finished `try_create_order` by reason: {reason}"),
                 explanation,
             );
         }
 
+        // `Exchange::create_order` checks only cap from settings, so cap of the trade cycle is checked here
+        if let Err(reason) = self.exchange.check_net_position(
+            self.symbol.currency_pair(),
+            side,
+            new_order_amount,
+            new_estimating.max_position_size,
+        ) {
+            let msg = format!("Finished `try_create_order` by reason: {reason}");
+            log::warn!("{msg}");
+            explanation.add_reason_with_code(ReasonCode::MaxPositionExceeded, msg);
+            return Ok(());
+        }
+
         let new_client_order_id = ClientOrderId::unique_id();
 
         let requests_group_id = self.engine_ctx.timeout_manager.try_reserve_group(
@@ -626,7 +919,8 @@ impl DispositionExecutor {
 
         let requests_group_id = match requests_group_id {
             None => {
-                return log_trace(
+                return log_trace_with_code(
+                    ReasonCode::RateLimited,
                     "Finished `try_create_order` because can't reserve reservation group",
                     explanation,
                 )
@@ -657,13 +951,19 @@ impl DispositionExecutor {
                 .lock()
                 .try_reserve(&target_reserve_parameters, &mut explanation)
             {
-                Some(reservation_id) => reservation_id,
-                None => {
+                Ok(reservation_id) => reservation_id,
+                Err(err) => {
                     self.engine_ctx
                         .timeout_manager
                         .remove_group(self.exchange_account_id, requests_group_id);
 
-                    return log_trace(format!("Finished try_create_order because can't reserve balance {new_order_amount}"),
+                    let reason_code = match err {
+                        ReserveError::NotEnoughBalance => ReasonCode::InsufficientBalance,
+                        ReserveError::ReservationLimitExceeded(_) => {
+                            ReasonCode::ReservationLimitExceeded
+                        }
+                    };
+                    return log_trace_with_code(reason_code, format!("Finished try_create_order because can't reserve balance {new_order_amount}: {err}"),
                         &mut explanation.expect(explanation_err_msg),
                     );
                 }
@@ -690,7 +990,8 @@ impl DispositionExecutor {
                 .timeout_manager
                 .remove_group(self.exchange_account_id, requests_group_id);
 
-            return log_trace(
+            return log_trace_with_code(
+                ReasonCode::RateLimited,
                 "Finished `try_create_order` because can't reserve requests",
                 explanation,
             );
@@ -708,9 +1009,10 @@ impl DispositionExecutor {
             Some(reservation_id),
             None,
             new_estimating.strategy_name.clone(),
-        );
+        )
+        .with_ttl(self.order_ttl);
 
-        let exchange = self.exchange();
+        let exchange = self.exchange.clone();
 
         let new_order = exchange.orders.add_simple_initial(
             &order_header,
@@ -734,6 +1036,14 @@ impl DispositionExecutor {
             let cancellation_token = self.cancellation_token.clone();
 
             let action = async move {
+                let _span = telemetry::start_span(
+                    "place_order",
+                    order_header.exchange_account_id,
+                    order_header.currency_pair,
+                )
+                .with_side(order_header.side)
+                .with_order_id(&new_client_order_id);
+
                 log::trace!("Begin create_order {new_client_order_id}");
 
                 exchange
@@ -872,15 +1182,6 @@ impl DispositionExecutor {
         result
     }
 
-    fn exchange(&self) -> Arc<Exchange> {
-        self.engine_ctx
-            .exchanges
-            .get(&self.exchange_account_id)
-            .expect("Target exchange for strategy should exists")
-            .value()
-            .clone()
-    }
-
     fn prepare_estimate_trading_context(&self, event: &ExchangeEvent, now: DateTime) -> bool {
         let event_time = match event {
             ExchangeEvent::OrderBookEvent(order_book_event) => order_book_event.creation_time,
@@ -907,6 +1208,8 @@ fn estimate_trading_context(
     event: &ExchangeEvent,
     strategy: &mut dyn DispositionStrategy,
     local_snapshots_service: &LocalSnapshotsService,
+    price_slots_count: usize,
+    min_profit_filter: Option<&MinProfitFilter>,
     now: DateTime,
 ) -> Result<Option<TradingContext>> {
     if !need_recalculate_trading_context {
@@ -917,6 +1220,8 @@ fn estimate_trading_context(
         event,
         strategy,
         local_snapshots_service,
+        price_slots_count,
+        min_profit_filter,
         now,
     ))
 }
@@ -960,14 +1265,403 @@ fn get_cancelling_orders<'a>(
 }
 
 fn now() -> DateTime {
-    Utc::now()
+    time_manager::now()
 }
 
 #[inline(always)]
-fn log_trace(msg: impl AsRef<str>, explanation: &mut Explanation) -> Result<()> {
+fn log_trace_with_code(
+    code: ReasonCode,
+    msg: impl AsRef<str>,
+    explanation: &mut Explanation,
+) -> Result<()> {
     let msg = msg.as_ref();
     log::trace!("{msg}");
-    explanation.add_reason(msg);
+    explanation.add_reason_with_code(code, msg);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::manager::balance_manager::BalanceManager;
+    use crate::database::events::recorder::EventRecorder;
+    use crate::disposition_execution::strategy_metrics::NoopStrategyMetrics;
+    use crate::disposition_execution::watchdog::DecisionWatchdog;
+    use crate::disposition_execution::{TradeDisposition, TradingContextBySide};
+    use crate::exchanges::exchange_blocker::ExchangeBlocker;
+    use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+    use crate::exchanges::general::test_helper::{
+        get_test_exchange_with_symbol_and_client, TestClient,
+    };
+    use crate::exchanges::timeouts::requests_timeout_manager_factory::{
+        RequestTimeoutArguments, RequestsTimeoutManagerFactory,
+    };
+    use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
+    use crate::explanation::WithExplanation;
+    use crate::infrastructure::init_lifetime_manager;
+    use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+    use crate::misc::time;
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+    use crate::settings::CoreSettings;
+    use dashmap::DashMap;
+    use mmb_domain::events::{ExchangeBalance, ExchangeBalancesAndPositions, ExchangeEvents};
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::order::snapshot::OrderRole;
+    use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+    use mmb_domain::order_book::order_book_data::OrderBookData;
+    use mmb_utils::hashmap;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::Ordering;
+
+    /// Strategy which records calls of timer and trading context calculations
+    struct TimerStrategy {
+        calls: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl DispositionStrategy for TimerStrategy {
+        fn calculate_trading_context(
+            &mut self,
+            event: &ExchangeEvent,
+            _now: DateTime,
+            _local_snapshots_service: &LocalSnapshotsService,
+            explanation: &mut Explanation,
+        ) -> Option<TradingContext> {
+            let call = match event {
+                ExchangeEvent::TimerTick => "calculate_trading_context_by_timer",
+                _ => "calculate_trading_context",
+            };
+            self.calls.lock().push(call);
+            Some(TradingContext::new(
+                TradingContextBySide::empty(1, explanation.clone()),
+                TradingContextBySide::empty(1, explanation.clone()),
+            ))
+        }
+
+        fn handle_order_fill(
+            &self,
+            _cloned_order: &Arc<OrderSnapshot>,
+            _price_slot: &PriceSlot,
+            _target_eai: ExchangeAccountId,
+            _cancellation_token: CancellationToken,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+            ConfigurationDescriptor::new("TimerStrategy".into(), "test".into())
+        }
+
+        fn on_timer(&mut self, _now: DateTime) {
+            self.calls.lock().push("on_timer");
+        }
+    }
+
+    /// Strategy which moves buy order between two prices on every trading context calculation,
+    /// so every tick an order is created or cancelled
+    struct MovingOrderStrategy {
+        market_account_id: MarketAccountId,
+        ticks_count: usize,
+    }
+
+    impl DispositionStrategy for MovingOrderStrategy {
+        fn calculate_trading_context(
+            &mut self,
+            _event: &ExchangeEvent,
+            _now: DateTime,
+            _local_snapshots_service: &LocalSnapshotsService,
+            explanation: &mut Explanation,
+        ) -> Option<TradingContext> {
+            self.ticks_count += 1;
+            let price = match self.ticks_count % 2 {
+                0 => dec!(0.5),
+                _ => dec!(0.6),
+            };
+
+            let buy_trading_context = TradingContextBySide {
+                max_amount: dec!(10),
+                estimating: vec![WithExplanation {
+                    value: Some(TradeCycle {
+                        order_role: OrderRole::Maker,
+                        strategy_name: "MovingOrderStrategy".to_string(),
+                        disposition: TradeDisposition::new(
+                            self.market_account_id,
+                            OrderSide::Buy,
+                            price,
+                            dec!(1),
+                        ),
+                        max_position_size: None,
+                    }),
+                    explanation: explanation.clone(),
+                }],
+            };
+            Some(TradingContext::new(
+                buy_trading_context,
+                TradingContextBySide::empty(1, explanation.clone()),
+            ))
+        }
+
+        fn handle_order_fill(
+            &self,
+            _cloned_order: &Arc<OrderSnapshot>,
+            _price_slot: &PriceSlot,
+            _target_eai: ExchangeAccountId,
+            _cancellation_token: CancellationToken,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+            ConfigurationDescriptor::new("MovingOrderStrategy".into(), "test".into())
+        }
+    }
+
+    fn test_symbol() -> Arc<Symbol> {
+        Arc::new(Symbol::new(
+            false,
+            "PHB".into(),
+            "PHB".into(),
+            "BTC".into(),
+            "BTC".into(),
+            None,
+            None,
+            Some(dec!(0.001)),
+            None,
+            None,
+            "PHB".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ))
+    }
+
+    async fn create_engine_context(
+        exchange: Arc<Exchange>,
+        events_sender: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Arc<EngineContext> {
+        let exchange_account_id = exchange.exchange_account_id;
+
+        let request_timeout_manager = RequestsTimeoutManagerFactory::from_requests_per_period(
+            RequestTimeoutArguments::new(100, Duration::minutes(1)),
+            exchange_account_id,
+        );
+        let currency_pair_to_symbol_converter =
+            CurrencyPairToSymbolConverter::new(hashmap![exchange_account_id => exchange.clone()]);
+        let (finish_graceful_shutdown_sender, _finish_graceful_shutdown_receiver) =
+            oneshot::channel();
+        let balance_manager = BalanceManager::new(currency_pair_to_symbol_converter, None);
+        exchange.setup_balance_manager(balance_manager.clone());
+
+        EngineContext::new(
+            CoreSettings::default(),
+            DashMap::from_iter([(exchange_account_id, exchange)]),
+            ExchangeEvents::new(events_sender),
+            finish_graceful_shutdown_sender,
+            ExchangeBlocker::new(vec![exchange_account_id]),
+            TimeoutManager::new(hashmap![exchange_account_id => request_timeout_manager]),
+            lifetime_manager,
+            balance_manager,
+            EventRecorder::start(None, None, 0.0)
+                .await
+                .expect("in test"),
+        )
+    }
+
+    fn create_executor(
+        engine_ctx: Arc<EngineContext>,
+        events_receiver: broadcast::Receiver<ExchangeEvent>,
+        market_account_id: MarketAccountId,
+        strategy: Box<dyn DispositionStrategy>,
+        timer_interval: Option<std::time::Duration>,
+    ) -> DispositionExecutor {
+        let (work_finished_sender, _work_finished_receiver) = oneshot::channel();
+        DispositionExecutor::new(
+            engine_ctx,
+            events_receiver,
+            LocalSnapshotsService::default(),
+            market_account_id.exchange_account_id,
+            market_account_id.currency_pair,
+            strategy,
+            work_finished_sender,
+            CancellationToken::new(),
+            StatisticService::new(None),
+            LastDecisionTime::new(now()),
+            1,
+            None,
+            None,
+            timer_interval,
+            Box::new(NoopStrategyMetrics),
+        )
+        .expect("in test")
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn trading_context_is_recalculated_after_timer() {
+        let lifetime_manager = init_lifetime_manager();
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let (exchange, _exchange_events_receiver) =
+            get_test_exchange_with_symbol_and_client(test_symbol(), TestClient::default());
+        let market_account_id =
+            MarketAccountId::new(exchange.exchange_account_id, test_symbol().currency_pair());
+        let (events_sender, events_receiver) = broadcast::channel(10);
+        let engine_ctx = create_engine_context(exchange, events_sender, lifetime_manager).await;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = create_executor(
+            engine_ctx,
+            events_receiver,
+            market_account_id,
+            Box::new(TimerStrategy {
+                calls: calls.clone(),
+            }),
+            Some(std::time::Duration::from_secs(10)),
+        );
+        let executor_handle = tokio::spawn(async move { executor.start().await });
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        assert!(
+            calls.lock().is_empty(),
+            "timer shouldn't be called before the first interval passed"
+        );
+
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+        assert_eq!(
+            *calls.lock(),
+            vec!["on_timer", "calculate_trading_context_by_timer"]
+        );
+
+        executor_handle.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watchdog_does_not_fire_in_quiet_market() {
+        let lifetime_manager = init_lifetime_manager();
+        let clock = time::tests::MockClock::default();
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(clock.clone());
+        let (exchange, _exchange_events_receiver) =
+            get_test_exchange_with_symbol_and_client(test_symbol(), TestClient::default());
+        let market_account_id =
+            MarketAccountId::new(exchange.exchange_account_id, test_symbol().currency_pair());
+        let (events_sender, events_receiver) = broadcast::channel(10);
+        let engine_ctx = create_engine_context(exchange, events_sender, lifetime_manager).await;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut executor = create_executor(
+            engine_ctx,
+            events_receiver,
+            market_account_id,
+            Box::new(TimerStrategy {
+                calls: calls.clone(),
+            }),
+            None,
+        );
+        let watchdog = DecisionWatchdog::new(
+            executor.last_decision_at.clone(),
+            std::time::Duration::from_secs(10),
+        );
+        let executor_handle = tokio::spawn(async move { executor.start().await });
+
+        // no exchange events, so trading context is never calculated
+        for _ in 0..30 {
+            clock.advance_by(std::time::Duration::from_secs(1));
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+        assert!(calls.lock().is_empty());
+        assert_eq!(watchdog.check(clock.now()), None);
+
+        executor_handle.abort();
+        let _ = executor_handle.await;
+        clock.advance_by(std::time::Duration::from_secs(30));
+
+        assert!(watchdog.check(clock.now()).is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn dry_run_strategy_makes_no_requests_to_exchange() {
+        let lifetime_manager = init_lifetime_manager();
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let exchange_client = TestClient::default();
+        let requests_count = exchange_client.requests_count.clone();
+        let (exchange, mut exchange_events_receiver) =
+            get_test_exchange_with_symbol_and_client(test_symbol(), exchange_client);
+        exchange.set_dry_run(true);
+        let exchange_account_id = exchange.exchange_account_id;
+        let market_account_id =
+            MarketAccountId::new(exchange_account_id, test_symbol().currency_pair());
+
+        let (events_sender, events_receiver) = broadcast::channel(100);
+        let engine_ctx =
+            create_engine_context(exchange.clone(), events_sender.clone(), lifetime_manager).await;
+        engine_ctx
+            .balance_manager
+            .lock()
+            .update_exchange_balance(
+                exchange_account_id,
+                &ExchangeBalancesAndPositions {
+                    balances: vec![
+                        ExchangeBalance {
+                            currency_code: "PHB".into(),
+                            balance: dec!(100),
+                        },
+                        ExchangeBalance {
+                            currency_code: "BTC".into(),
+                            balance: dec!(100),
+                        },
+                    ],
+                    positions: None,
+                },
+            )
+            .expect("in test");
+
+        let mut executor = create_executor(
+            engine_ctx,
+            events_receiver,
+            market_account_id,
+            Box::new(MovingOrderStrategy {
+                market_account_id,
+                ticks_count: 0,
+            }),
+            None,
+        );
+        let executor_handle = tokio::spawn(async move { executor.start().await });
+
+        // order events of exchange are delivered to executor like by the engine events channel
+        let forwarding_events_sender = events_sender.clone();
+        let forwarding_handle = tokio::spawn(async move {
+            while let Ok(event) = exchange_events_receiver.recv().await {
+                let _ = forwarding_events_sender.send(event);
+            }
+        });
+
+        const TICKS_COUNT: usize = 5;
+        for _ in 0..TICKS_COUNT {
+            let order_book = OrderBookData::new(
+                BTreeMap::from([(dec!(1.1), dec!(1))]),
+                BTreeMap::from([(dec!(0.9), dec!(1))]),
+            );
+            let order_book_event = OrderBookEvent::new(
+                now(),
+                exchange_account_id,
+                market_account_id.currency_pair,
+                String::new(),
+                EventType::Snapshot,
+                Arc::new(order_book),
+            );
+            events_sender
+                .send(ExchangeEvent::OrderBookEvent(order_book_event))
+                .expect("in test");
+
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        assert!(
+            exchange.orders.cache_by_client_id.len() > 1,
+            "orders should be created in dry run mode"
+        );
+        assert_eq!(requests_count.load(Ordering::SeqCst), 0);
+
+        executor_handle.abort();
+        forwarding_handle.abort();
+    }
+}
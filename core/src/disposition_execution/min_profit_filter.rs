@@ -0,0 +1,238 @@
+use crate::disposition_execution::{TradeCycle, TradingContext};
+use crate::explanation::ReasonCode;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_domain::market::MarketAccountId;
+use mmb_domain::order::snapshot::{OrderRole, OrderSide, Price};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Taker orders are emitted only if expected edge after fees exceeds `min_profit_bps`
+#[derive(Debug, Clone)]
+pub(crate) struct MinProfitFilter {
+    min_profit_bps: Decimal,
+    /// Taker fee rate by market, e.g. 0.001 for 0.1%
+    taker_fee_rates: HashMap<MarketAccountId, Decimal>,
+}
+
+impl MinProfitFilter {
+    pub fn new(
+        min_profit_bps: Decimal,
+        taker_fee_rates: HashMap<MarketAccountId, Decimal>,
+    ) -> Self {
+        MinProfitFilter {
+            min_profit_bps,
+            taker_fee_rates,
+        }
+    }
+
+    /// Remove taker orders without enough edge from trading context with explanation of the reason
+    pub fn apply(
+        &self,
+        trading_context: &mut TradingContext,
+        local_snapshots_service: &LocalSnapshotsService,
+    ) {
+        for (_, trading_context_by_side) in &mut trading_context.by_side {
+            for estimating in &mut trading_context_by_side.estimating {
+                let (value, explanation) = estimating.as_mut_all();
+                let Some(trade_cycle) = value else { continue };
+                if trade_cycle.order_role != OrderRole::Taker {
+                    continue;
+                }
+
+                if let Err(reason) = self.check(trade_cycle, local_snapshots_service) {
+                    explanation.add_reason_with_code(ReasonCode::BelowMinProfit, reason);
+                    *value = None;
+                }
+            }
+        }
+    }
+
+    fn check(
+        &self,
+        trade_cycle: &TradeCycle,
+        local_snapshots_service: &LocalSnapshotsService,
+    ) -> Result<(), String> {
+        let disposition = &trade_cycle.disposition;
+        let side = disposition.side();
+        let price = disposition.price();
+        let market_account_id = disposition.market_account_id();
+
+        let taker_fee_rate = self
+            .taker_fee_rates
+            .get(&market_account_id)
+            .ok_or_else(|| {
+                format!("Taker {side} order with price {price} is skipped: unknown taker fee on {market_account_id}")
+            })?;
+
+        let snapshot = local_snapshots_service.get_snapshot(disposition.market_id());
+        let book_price = snapshot.and_then(|snapshot| match side {
+            OrderSide::Buy => snapshot.get_top_ask(),
+            OrderSide::Sell => snapshot.get_top_bid(),
+        });
+        let Some((book_price, _)) = book_price else {
+            return Err(format!(
+                "Taker {side} order with price {price} is skipped: there is no opposite side of order book on {market_account_id}"
+            ));
+        };
+
+        let edge_bps = expected_edge_bps(side, price, book_price, *taker_fee_rate);
+        if edge_bps <= self.min_profit_bps {
+            return Err(format!(
+                "Taker {side} order with price {price} is skipped: expected edge {edge_bps} bps by book price {book_price} after fees doesn't exceed min_profit_bps {}",
+                self.min_profit_bps
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Expected edge in bps of taker order with target `price` which is filled by best `book_price`
+fn expected_edge_bps(
+    side: OrderSide,
+    price: Price,
+    book_price: Price,
+    taker_fee_rate: Decimal,
+) -> Decimal {
+    let price_edge = match side {
+        OrderSide::Buy => (price - book_price) / book_price,
+        OrderSide::Sell => (book_price - price) / book_price,
+    };
+
+    ((price_edge - taker_fee_rate) * dec!(10_000)).round_dp(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::{TradeDisposition, TradingContextBySide};
+    use crate::explanation::{Explanation, WithExplanation};
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+    use mmb_utils::hashmap;
+    use rstest::rstest;
+
+    fn market_account_id() -> MarketAccountId {
+        MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        )
+    }
+
+    fn estimating(side: OrderSide, price: Price, order_role: OrderRole) -> TradingContextBySide {
+        TradingContextBySide {
+            max_amount: dec!(1),
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role,
+                    strategy_name: "test".to_owned(),
+                    disposition: TradeDisposition::new(market_account_id(), side, price, dec!(1)),
+                    max_position_size: None,
+                }),
+                explanation: Explanation::default(),
+            }],
+        }
+    }
+
+    fn local_snapshots_service() -> LocalSnapshotsService {
+        let snapshot = LocalOrderBookSnapshot::new(
+            hashmap![dec!(100) => dec!(1)].into_iter().collect(),
+            hashmap![dec!(99) => dec!(1)].into_iter().collect(),
+            Utc::now(),
+        );
+
+        LocalSnapshotsService::new(hashmap![market_account_id().market_id() => snapshot])
+    }
+
+    fn filter(min_profit_bps: Decimal) -> MinProfitFilter {
+        MinProfitFilter::new(min_profit_bps, hashmap![market_account_id() => dec!(0.001)])
+    }
+
+    #[rstest]
+    #[case::buy(OrderSide::Buy, dec!(101), dec!(100), dec!(90))]
+    #[case::sell(OrderSide::Sell, dec!(98), dec!(100), dec!(190))]
+    #[case::buy_above_book(OrderSide::Buy, dec!(99), dec!(100), dec!(-110))]
+    fn edge_after_fees(
+        #[case] side: OrderSide,
+        #[case] price: Price,
+        #[case] book_price: Price,
+        #[case] expected: Decimal,
+    ) {
+        assert_eq!(
+            expected_edge_bps(side, price, book_price, dec!(0.001)),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::enough_edge(dec!(50), true)]
+    #[case::edge_equals_threshold(dec!(90), false)]
+    #[case::not_enough_edge(dec!(100), false)]
+    fn taker_order_is_filtered_by_edge(#[case] min_profit_bps: Decimal, #[case] is_kept: bool) {
+        // buy by 101 when best ask is 100 gives 100 bps of edge and 90 bps after fees
+        let mut trading_context = TradingContext::new(
+            estimating(OrderSide::Buy, dec!(101), OrderRole::Taker),
+            TradingContextBySide::empty(1, Explanation::default()),
+        );
+
+        filter(min_profit_bps).apply(&mut trading_context, &local_snapshots_service());
+
+        let estimating = &trading_context.by_side[OrderSide::Buy].estimating[0];
+        assert_eq!(estimating.value.is_some(), is_kept);
+        assert_eq!(estimating.explanation.get_reasons().is_empty(), is_kept);
+        assert_eq!(
+            estimating.explanation.get_reason_codes().is_empty(),
+            is_kept
+        );
+    }
+
+    #[test]
+    fn maker_order_is_not_filtered() {
+        let mut trading_context = TradingContext::new(
+            TradingContextBySide::empty(1, Explanation::default()),
+            estimating(OrderSide::Sell, dec!(101), OrderRole::Maker),
+        );
+        let expected = trading_context.clone();
+
+        filter(dec!(1000)).apply(&mut trading_context, &local_snapshots_service());
+
+        assert_eq!(trading_context, expected);
+    }
+
+    #[test]
+    fn taker_order_without_fee_of_market_is_filtered() {
+        let mut trading_context = TradingContext::new(
+            estimating(OrderSide::Buy, dec!(101), OrderRole::Taker),
+            TradingContextBySide::empty(1, Explanation::default()),
+        );
+        let other_market_account_id = MarketAccountId::new(
+            market_account_id().exchange_account_id,
+            CurrencyPair::from_codes("eth".into(), "usdt".into()),
+        );
+        let filter = MinProfitFilter::new(dec!(0), hashmap![other_market_account_id => dec!(0)]);
+
+        filter.apply(&mut trading_context, &local_snapshots_service());
+
+        assert_eq!(
+            trading_context.by_side[OrderSide::Buy].estimating[0].value,
+            None
+        );
+    }
+
+    #[test]
+    fn taker_order_without_order_book_is_filtered() {
+        let mut trading_context = TradingContext::new(
+            estimating(OrderSide::Buy, dec!(101), OrderRole::Taker),
+            TradingContextBySide::empty(1, Explanation::default()),
+        );
+
+        filter(dec!(0)).apply(&mut trading_context, &LocalSnapshotsService::default());
+
+        assert_eq!(
+            trading_context.by_side[OrderSide::Buy].estimating[0].value,
+            None
+        );
+    }
+}
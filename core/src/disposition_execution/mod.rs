@@ -1,7 +1,12 @@
+mod dry_run;
 pub mod executor;
+mod min_profit_filter;
+pub mod state_graph;
 pub mod strategy;
+pub mod strategy_metrics;
 pub mod trade_limit;
 mod trading_context_calculation;
+pub mod watchdog;
 
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 use crate::explanation::{Explanation, ExplanationSet, PriceLevelExplanation, WithExplanation};
@@ -11,6 +16,7 @@ use mmb_domain::market::{CurrencyPair, ExchangeAccountId, ExchangeId, MarketAcco
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{ClientOrderId, OrderRole, OrderSide};
+use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::cell::RefCell;
@@ -108,6 +114,9 @@ pub struct TradeCycle {
     pub order_role: OrderRole,
     pub strategy_name: String,
     pub disposition: TradeDisposition,
+    /// Max absolute net position which can be reached by placing order of this cycle.
+    /// Applied together with `max_positions` from exchange settings, so the strictest cap is used
+    pub max_position_size: Option<Amount>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -173,6 +182,7 @@ fn to_price_level_explanation(
         price,
         amount,
         reasons: explanation.explanation.get_reasons(),
+        reason_codes: explanation.explanation.get_reason_codes(),
     }
 }
 
@@ -202,6 +212,8 @@ pub struct OrderRecord {
     pub order: OrderRef,
     pub is_cancellation_requested: bool,
     pub request_group_id: RequestGroupId,
+    /// Time when creation of order was acknowledged by exchange
+    pub created_at: Option<DateTime>,
 }
 
 impl OrderRecord {
@@ -210,6 +222,24 @@ impl OrderRecord {
             order,
             is_cancellation_requested: false,
             request_group_id,
+            created_at: None,
+        }
+    }
+
+    /// Order isn't filled during its TTL since creation acknowledged by exchange and should be cancelled
+    pub fn is_expired(&self, now: DateTime) -> bool {
+        let (Some(ttl), Some(created_at)) = (self.order.header().ttl, self.created_at) else {
+            return false;
+        };
+
+        if self.is_cancellation_requested || self.order.is_finished() {
+            return false;
+        }
+
+        match now.signed_duration_since(created_at).to_std() {
+            Ok(age) => age >= ttl,
+            // creation time is in the future
+            Err(_) => false,
         }
     }
 }
@@ -246,6 +276,19 @@ impl CompositeOrder {
             .sum()
     }
 
+    /// Count of not finished orders
+    pub fn active_order_count(&self) -> usize {
+        self.orders
+            .values()
+            .filter(|or| !or.order.is_finished())
+            .count()
+    }
+
+    /// Cheap check that there are no active orders without calculation of remaining amount
+    pub fn is_empty(&self) -> bool {
+        self.active_order_count() == 0
+    }
+
     pub fn add_order_record(&mut self, order: OrderRef, request_group_id: RequestGroupId) {
         let client_order_id = order.client_order_id();
         log::info!(
@@ -324,21 +367,23 @@ struct OrdersStateBySide {
 }
 
 impl OrdersStateBySide {
-    pub fn new(_side: OrderSide) -> Self {
+    pub fn new(_side: OrderSide, slots_count: usize) -> Self {
         OrdersStateBySide {
             _side,
-            // TODO create list of PriceSlots by config
-            slots: vec![PriceSlot::new(
-                PriceSlotId::new("PriceSlotId".into(), 0),
-                _side,
-            )],
+            slots: (0..slots_count)
+                .map(|level_index| {
+                    PriceSlot::new(PriceSlotId::new("PriceSlotId".into(), level_index), _side)
+                })
+                .collect(),
         }
     }
 
     pub fn calc_total_remaining_amount(&self) -> Decimal {
         self.slots
             .iter()
-            .map(|x| x.order.borrow().remaining_amount())
+            .map(|x| x.order.borrow())
+            .filter(|composite_order| !composite_order.is_empty())
+            .map(|composite_order| composite_order.remaining_amount())
             .sum()
     }
 
@@ -357,11 +402,115 @@ struct OrdersState {
 }
 
 impl OrdersState {
-    pub fn new() -> Self {
+    pub fn new(slots_count: usize) -> Self {
         OrdersState {
             by_side: enum_map! {
-                side => OrdersStateBySide::new(side),
+                side => OrdersStateBySide::new(side, slots_count),
             },
         }
     }
+
+    pub fn price_slots_count(&self) -> usize {
+        self.by_side[OrderSide::Buy].slots.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::{OrderHeader, OrderStatus, UserOrder};
+    use std::time::Duration;
+
+    fn add_order(pool: &OrdersPool, composite_order: &mut CompositeOrder) -> OrderRef {
+        add_order_with_ttl(pool, composite_order, None)
+    }
+
+    fn add_order_with_ttl(
+        pool: &OrdersPool,
+        composite_order: &mut CompositeOrder,
+        ttl: Option<Duration>,
+    ) -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "test".to_string(),
+        )
+        .with_ttl(ttl);
+        let order = pool.add_simple_initial(&header, Utc::now(), None);
+        composite_order.add_order_record(order.clone(), RequestGroupId::generate());
+        order
+    }
+
+    #[test]
+    fn new_composite_order_is_empty() {
+        let composite_order = CompositeOrder::new(OrderSide::Buy);
+
+        assert_eq!(composite_order.active_order_count(), 0);
+        assert!(composite_order.is_empty());
+    }
+
+    #[test]
+    fn is_empty_after_all_orders_finished() {
+        let pool = OrdersPool::new();
+        let mut composite_order = CompositeOrder::new(OrderSide::Buy);
+        let first = add_order(&pool, &mut composite_order);
+        let second = add_order(&pool, &mut composite_order);
+
+        assert_eq!(composite_order.active_order_count(), 2);
+        assert!(!composite_order.is_empty());
+
+        first.fn_mut(|x| x.set_status(OrderStatus::Completed, Utc::now()));
+        assert_eq!(composite_order.active_order_count(), 1);
+        assert!(!composite_order.is_empty());
+
+        second.fn_mut(|x| x.set_status(OrderStatus::Canceled, Utc::now()));
+        assert_eq!(composite_order.active_order_count(), 0);
+        assert!(composite_order.is_empty());
+        assert_eq!(composite_order.remaining_amount(), dec!(0));
+    }
+
+    #[test]
+    fn order_is_expired_after_ttl_since_creation() {
+        let pool = OrdersPool::new();
+        let mut composite_order = CompositeOrder::new(OrderSide::Buy);
+        let order = add_order_with_ttl(&pool, &mut composite_order, Some(Duration::from_secs(10)));
+        let order_record = composite_order
+            .orders
+            .get_mut(&order.client_order_id())
+            .expect("in test");
+        let created_at = Utc::now();
+
+        // TTL isn't started until creation is acknowledged by exchange
+        assert!(!order_record.is_expired(created_at + chrono::Duration::seconds(20)));
+
+        order_record.created_at = Some(created_at);
+        assert!(!order_record.is_expired(created_at + chrono::Duration::seconds(5)));
+        assert!(order_record.is_expired(created_at + chrono::Duration::seconds(10)));
+
+        order_record.is_cancellation_requested = true;
+        assert!(!order_record.is_expired(created_at + chrono::Duration::seconds(20)));
+    }
+
+    #[test]
+    fn order_without_ttl_is_never_expired() {
+        let pool = OrdersPool::new();
+        let mut composite_order = CompositeOrder::new(OrderSide::Buy);
+        let order = add_order(&pool, &mut composite_order);
+        let order_record = composite_order
+            .orders
+            .get_mut(&order.client_order_id())
+            .expect("in test");
+        let created_at = Utc::now();
+        order_record.created_at = Some(created_at);
+
+        assert!(!order_record.is_expired(created_at + chrono::Duration::days(1)));
+    }
 }
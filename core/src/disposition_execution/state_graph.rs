@@ -0,0 +1,138 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use parking_lot::Mutex;
+
+use crate::disposition_execution::OrdersState;
+
+const EMPTY_GRAPH: &str = "digraph DispositionExecutor {\n}\n";
+
+/// The last state of DispositionExecutor in Graphviz DOT format for debugging through control panel
+#[derive(Clone)]
+pub struct ExecutorStateGraph(Arc<Mutex<String>>);
+
+impl ExecutorStateGraph {
+    pub fn new() -> Self {
+        ExecutorStateGraph(Arc::new(Mutex::new(EMPTY_GRAPH.to_owned())))
+    }
+
+    pub fn update(&self, dot: String) {
+        *self.0.lock() = dot;
+    }
+
+    pub fn get(&self) -> String {
+        self.0.lock().clone()
+    }
+}
+
+impl Default for ExecutorStateGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrdersState {
+    /// Price slots with their composite orders and order records as nodes of directed graph.
+    /// Edges are directed from price slot to composite order and from order record to composite order
+    pub(super) fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph DispositionExecutor {\n");
+
+        for (side, state_by_side) in self.by_side.iter() {
+            for slot in state_by_side.traverse_price_slots() {
+                let slot_node = format!("slot {side:?} {}", slot.id.level_index);
+                let composite_node = format!("composite {side:?} {}", slot.id.level_index);
+                let composite_order = slot.order.borrow();
+
+                let _ = writeln!(
+                    dot,
+                    "    \"{slot_node}\" [shape=box, label=\"{}\\n{side:?}\"];",
+                    escape(&slot.id.to_string()),
+                );
+                let _ = writeln!(
+                    dot,
+                    "    \"{composite_node}\" [label=\"CompositeOrder\\n{:?} {}\"];",
+                    composite_order.side, composite_order.price,
+                );
+                let _ = writeln!(dot, "    \"{slot_node}\" -> \"{composite_node}\";");
+
+                for (client_order_id, record) in composite_order
+                    .orders
+                    .iter()
+                    .sorted_by(|(left, _), (right, _)| left.as_str().cmp(right.as_str()))
+                {
+                    let order = &record.order;
+                    let order_node = format!("order {}", escape(client_order_id.as_str()));
+                    let _ = writeln!(
+                        dot,
+                        "    \"{order_node}\" [shape=ellipse, label=\"{}\\n{:?} {}\\n{:?}\"];",
+                        escape(client_order_id.as_str()),
+                        order.side(),
+                        order.price(),
+                        order.status(),
+                    );
+                    let _ = writeln!(dot, "    \"{order_node}\" -> \"{composite_node}\";");
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderHeader, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn two_slots_state_to_dot() {
+        let pool = OrdersPool::new();
+        let orders_state = OrdersState::new(2);
+
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::new("test-order".into()),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "test".to_string(),
+        );
+        let order = pool.add_simple_initial(&header, Utc::now(), None);
+        orders_state.by_side[OrderSide::Buy].slots[1].add_order(
+            OrderSide::Buy,
+            dec!(0.5),
+            order,
+            RequestGroupId::generate(),
+        );
+
+        let dot = orders_state.to_dot();
+
+        dot_parser::ast::Graph::<(&str, &str)>::try_from(dot.as_str())
+            .expect("DOT output should be parsed");
+        assert!(
+            dot.contains(r#""order test-order" -> "composite Buy 1";"#),
+            "{dot}"
+        );
+        assert!(dot.contains(r#"label="CompositeOrder\nBuy 0.5""#), "{dot}");
+        assert!(
+            dot.contains(r#"label="test-order\nBuy 0.5\nCreating""#),
+            "{dot}"
+        );
+        // 2 slots by every side and 1 order
+        assert_eq!(dot.matches(" -> ").count(), 5);
+    }
+}
@@ -30,4 +30,10 @@ pub trait DispositionStrategy: Send + Sync + 'static {
     ) -> Result<()>;
 
     fn configuration_descriptor(&self) -> ConfigurationDescriptor;
+
+    /// Called periodically with interval `DispositionStrategySettings::timer_interval` between
+    /// handling of events, so it can't race with trading context calculation.
+    /// The first call happens after the first interval passed. Trading context is recalculated right after the call
+    /// with `ExchangeEvent::TimerTick` event
+    fn on_timer(&mut self, _now: DateTime) {}
 }
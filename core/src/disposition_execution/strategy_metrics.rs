@@ -0,0 +1,151 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Max latency tracked by `HistogramStrategyMetrics`, bigger values are recorded as this one
+const MAX_TRACKED_LATENCY: Duration = Duration::from_secs(60);
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Hooks called by DispositionExecutor around every trading context calculation
+pub trait StrategyMetrics: Send + 'static {
+    /// `tick` is the number of trading context calculation since DispositionExecutor start
+    fn on_cycle_start(&mut self, tick: u64);
+
+    fn on_cycle_end(&mut self, tick: u64, elapsed: Duration);
+}
+
+/// Metrics aren't collected
+pub struct NoopStrategyMetrics;
+
+impl StrategyMetrics for NoopStrategyMetrics {
+    fn on_cycle_start(&mut self, _tick: u64) {}
+
+    fn on_cycle_end(&mut self, _tick: u64, _elapsed: Duration) {}
+}
+
+/// Allows reading collected metrics while DispositionExecutor owns them
+impl<T: StrategyMetrics> StrategyMetrics for Arc<Mutex<T>> {
+    fn on_cycle_start(&mut self, tick: u64) {
+        self.lock().on_cycle_start(tick);
+    }
+
+    fn on_cycle_end(&mut self, tick: u64, elapsed: Duration) {
+        self.lock().on_cycle_end(tick, elapsed);
+    }
+}
+
+/// Percentiles of trading context calculation latency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MetricsReport {
+    pub p50: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub max: Duration,
+}
+
+/// Records latencies of trading context calculation with microsecond precision
+pub struct HistogramStrategyMetrics {
+    histogram: Histogram<u64>,
+}
+
+impl HistogramStrategyMetrics {
+    pub fn new() -> Self {
+        let histogram = Histogram::new_with_bounds(
+            1,
+            MAX_TRACKED_LATENCY.as_micros() as u64,
+            SIGNIFICANT_DIGITS,
+        )
+        .expect("Histogram bounds should be valid");
+
+        HistogramStrategyMetrics { histogram }
+    }
+
+    pub fn get_report(&self) -> MetricsReport {
+        let quantile = |quantile| Duration::from_micros(self.histogram.value_at_quantile(quantile));
+
+        MetricsReport {
+            p50: quantile(0.5),
+            p99: quantile(0.99),
+            p999: quantile(0.999),
+            max: Duration::from_micros(self.histogram.max()),
+        }
+    }
+}
+
+impl Default for HistogramStrategyMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StrategyMetrics for HistogramStrategyMetrics {
+    fn on_cycle_start(&mut self, _tick: u64) {}
+
+    fn on_cycle_end(&mut self, _tick: u64, elapsed: Duration) {
+        self.histogram.saturating_record(elapsed.as_micros() as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Histogram values are equivalent within 3 significant digits
+    fn assert_close(actual: Duration, expected: Duration) {
+        let diff = actual.as_micros().abs_diff(expected.as_micros());
+        assert!(
+            diff * 1000 <= expected.as_micros(),
+            "actual {actual:?}, expected {expected:?}"
+        );
+    }
+
+    #[test]
+    fn p99_latency_captured() {
+        let mut metrics = HistogramStrategyMetrics::new();
+
+        // 995 fast cycles and 5 slow ones
+        for tick in 0..1000 {
+            let elapsed = match tick % 200 {
+                0 => Duration::from_millis(20),
+                _ => Duration::from_micros(500),
+            };
+            metrics.on_cycle_start(tick);
+            metrics.on_cycle_end(tick, elapsed);
+        }
+
+        let report = metrics.get_report();
+        assert_close(report.p50, Duration::from_micros(500));
+        assert_close(report.p99, Duration::from_micros(500));
+        assert_close(report.p999, Duration::from_millis(20));
+        assert_close(report.max, Duration::from_millis(20));
+
+        // more than 1% of slow cycles moves P99 to slow ones
+        for tick in 1000..1010 {
+            metrics.on_cycle_end(tick, Duration::from_millis(20));
+        }
+        assert_close(metrics.get_report().p99, Duration::from_millis(20));
+    }
+
+    #[test]
+    fn latency_above_max_is_saturated() {
+        let mut metrics = HistogramStrategyMetrics::new();
+
+        metrics.on_cycle_end(0, Duration::from_secs(120));
+
+        assert_close(metrics.get_report().max, MAX_TRACKED_LATENCY);
+    }
+
+    #[test]
+    fn shared_metrics_are_readable() {
+        let metrics = Arc::new(Mutex::new(HistogramStrategyMetrics::new()));
+        let mut executor_metrics: Box<dyn StrategyMetrics> = Box::new(metrics.clone());
+
+        executor_metrics.on_cycle_start(0);
+        executor_metrics.on_cycle_end(0, Duration::from_millis(3));
+
+        assert_close(metrics.lock().get_report().max, Duration::from_millis(3));
+    }
+}
@@ -1,6 +1,10 @@
 use crate::disposition_execution::TradeDisposition;
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::order::snapshot::Amount;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::pool::OrdersPool;
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 
 pub fn is_enough_amount_and_cost(
     disposition: &TradeDisposition,
@@ -30,3 +34,187 @@ pub fn is_enough_amount_and_cost(
 
     Err(msg)
 }
+
+/// Amount of not filled parts of not finished orders of the market on the specified side
+pub fn open_orders_amount(
+    orders: &OrdersPool,
+    currency_pair: CurrencyPair,
+    side: OrderSide,
+) -> Amount {
+    orders
+        .not_finished
+        .iter()
+        .filter(|order| {
+            order.currency_pair() == currency_pair && order.side() == side && !order.is_finished()
+        })
+        .map(|order| order.amount() - order.filled_amount())
+        .sum()
+}
+
+/// Checks that absolute net position doesn't exceed `max_position` after filling the new order
+/// together with all open orders on the same side.
+/// Orders which reduce absolute net position are always allowed
+pub fn is_net_position_allowed(
+    net_position: Amount,
+    side: OrderSide,
+    open_orders_amount: Amount,
+    amount: Amount,
+    max_position: Amount,
+) -> Result<(), String> {
+    let exposure = open_orders_amount + amount;
+    let new_position = match side {
+        OrderSide::Buy => net_position + exposure,
+        OrderSide::Sell => net_position - exposure,
+    };
+
+    if new_position.abs() <= max_position || new_position.abs() < net_position.abs() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Can't create {side:?} order for amount {amount} because net position {new_position} would exceed max position {max_position} (current net position {net_position}, open orders amount {open_orders_amount})"
+    ))
+}
+
+/// Checks that price deviates from `mid_price` not more than `price_band_percent` percents
+pub fn is_price_within_band(
+    price: Price,
+    mid_price: Price,
+    price_band_percent: Decimal,
+) -> Result<(), String> {
+    let deviation_percent = (price - mid_price).abs() / mid_price * dec!(100);
+    if deviation_percent <= price_band_percent {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Can't create order with price {price} because it deviates from mid price {mid_price} by {deviation_percent:.2}% which exceeds price band {price_band_percent}%"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderHeader, OrderStatus, UserOrder};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::increase_within_cap(dec!(2), OrderSide::Buy, dec!(3), true)]
+    #[case::reach_cap(dec!(-2), OrderSide::Sell, dec!(3), true)]
+    #[case::increase_long_beyond_cap(dec!(4), OrderSide::Buy, dec!(2), false)]
+    #[case::increase_short_beyond_cap(dec!(-4), OrderSide::Sell, dec!(2), false)]
+    #[case::reduce_position_above_cap(dec!(8), OrderSide::Sell, dec!(2), true)]
+    #[case::flip_position_beyond_cap(dec!(4), OrderSide::Sell, dec!(10), false)]
+    fn net_position_cap(
+        #[case] net_position: Amount,
+        #[case] side: OrderSide,
+        #[case] amount: Amount,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            is_net_position_allowed(net_position, side, dec!(0), amount, dec!(5)).is_ok(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::within_cap(dec!(1), OrderSide::Buy, dec!(2), true)]
+    #[case::open_orders_push_beyond_cap(dec!(1), OrderSide::Buy, dec!(3), false)]
+    #[case::short_open_orders_push_beyond_cap(dec!(-1), OrderSide::Sell, dec!(3), false)]
+    #[case::open_orders_reduce_position(dec!(4), OrderSide::Sell, dec!(3), true)]
+    fn net_position_cap_with_open_orders(
+        #[case] net_position: Amount,
+        #[case] side: OrderSide,
+        #[case] open_orders_amount: Amount,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            is_net_position_allowed(net_position, side, open_orders_amount, dec!(2), dec!(5))
+                .is_ok(),
+            expected
+        );
+    }
+
+    #[test]
+    fn only_first_order_placed_when_max_position_exceeded() {
+        let orders = OrdersPool::new();
+        let currency_pair = CurrencyPair::from_codes("base".into(), "quote".into());
+        let max_position = dec!(5);
+
+        let mut placed_orders = Vec::new();
+        for amount in [dec!(3), dec!(3)] {
+            let open_orders_amount = open_orders_amount(&orders, currency_pair, OrderSide::Buy);
+            if is_net_position_allowed(
+                dec!(0),
+                OrderSide::Buy,
+                open_orders_amount,
+                amount,
+                max_position,
+            )
+            .is_ok()
+            {
+                let _ = orders.add_simple_initial(
+                    &order_header(currency_pair, amount),
+                    Utc::now(),
+                    None,
+                );
+                placed_orders.push(amount);
+            }
+        }
+
+        assert_eq!(placed_orders, vec![dec!(3)]);
+    }
+
+    #[test]
+    fn open_orders_amount_counts_not_filled_parts_on_side() {
+        let orders = OrdersPool::new();
+        let currency_pair = CurrencyPair::from_codes("base".into(), "quote".into());
+        let now = Utc::now();
+
+        let partially_filled =
+            orders.add_simple_initial(&order_header(currency_pair, dec!(3)), now, None);
+        partially_filled.fn_mut(|order| order.fills.filled_amount = dec!(1));
+        let finished = orders.add_simple_initial(&order_header(currency_pair, dec!(4)), now, None);
+        finished.fn_mut(|order| order.set_status(OrderStatus::Completed, now));
+        let other_pair = CurrencyPair::from_codes("other".into(), "quote".into());
+        let _ = orders.add_simple_initial(&order_header(other_pair, dec!(5)), now, None);
+
+        assert_eq!(
+            open_orders_amount(&orders, currency_pair, OrderSide::Buy),
+            dec!(2)
+        );
+        assert_eq!(
+            open_orders_amount(&orders, currency_pair, OrderSide::Sell),
+            dec!(0)
+        );
+    }
+
+    fn order_header(currency_pair: CurrencyPair, amount: Amount) -> OrderHeader {
+        OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Binance", 0),
+            currency_pair,
+            OrderSide::Buy,
+            amount,
+            UserOrder::limit(dec!(1)),
+            None,
+            None,
+            "".to_string(),
+        )
+    }
+
+    #[rstest]
+    #[case::at_mid_price(dec!(100), true)]
+    #[case::below_within_band(dec!(95), true)]
+    #[case::above_within_band(dec!(105), true)]
+    #[case::below_out_of_band(dec!(94.9), false)]
+    #[case::above_out_of_band(dec!(150), false)]
+    fn price_band(#[case] price: Price, #[case] expected: bool) {
+        assert_eq!(
+            is_price_within_band(price, dec!(100), dec!(5)).is_ok(),
+            expected
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::disposition_execution::min_profit_filter::MinProfitFilter;
 use crate::disposition_execution::strategy::DispositionStrategy;
 use crate::disposition_execution::TradingContext;
 use crate::explanation::Explanation;
@@ -9,6 +10,8 @@ pub fn calculate_trading_context(
     event: &ExchangeEvent,
     strategy: &mut dyn DispositionStrategy,
     local_snapshots_service: &LocalSnapshotsService,
+    price_slots_count: usize,
+    min_profit_filter: Option<&MinProfitFilter>,
     now: DateTime,
 ) -> Option<TradingContext> {
     // TODO check is balance manager initialized for next calculations
@@ -18,5 +21,102 @@ pub fn calculate_trading_context(
 
     // TODO check balance position
 
-    strategy.calculate_trading_context(event, now, local_snapshots_service, &mut explanation)
+    let mut trading_context = strategy.calculate_trading_context(
+        event,
+        now,
+        local_snapshots_service,
+        &mut explanation,
+    )?;
+
+    for (side, trading_context_by_side) in &trading_context.by_side {
+        let estimates_count = trading_context_by_side.estimating.len();
+        if estimates_count != price_slots_count {
+            log::error!(
+                "Strategy {} calculated {estimates_count} estimates for {side} side but {price_slots_count} price slots are configured, trading context is skipped",
+                strategy.configuration_descriptor().service_name
+            );
+            return None;
+        }
+    }
+
+    if let Some(min_profit_filter) = min_profit_filter {
+        min_profit_filter.apply(&mut trading_context, local_snapshots_service);
+    }
+
+    Some(trading_context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::{PriceSlot, TradingContextBySide};
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+    use anyhow::Result;
+    use chrono::Utc;
+    use mmb_domain::events::LifecycleState;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::OrderSnapshot;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use mmb_utils::infrastructure::init_infrastructure;
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    struct TestStrategy {
+        estimates_count: usize,
+    }
+
+    impl DispositionStrategy for TestStrategy {
+        fn calculate_trading_context(
+            &mut self,
+            _event: &ExchangeEvent,
+            _now: DateTime,
+            _local_snapshots_service: &LocalSnapshotsService,
+            explanation: &mut Explanation,
+        ) -> Option<TradingContext> {
+            Some(TradingContext::new(
+                TradingContextBySide::empty(self.estimates_count, explanation.clone()),
+                TradingContextBySide::empty(self.estimates_count, explanation.clone()),
+            ))
+        }
+
+        fn handle_order_fill(
+            &self,
+            _cloned_order: &Arc<OrderSnapshot>,
+            _price_slot: &PriceSlot,
+            _target_eai: ExchangeAccountId,
+            _cancellation_token: CancellationToken,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+            ConfigurationDescriptor::new("TestStrategy".into(), "test".into())
+        }
+    }
+
+    #[rstest]
+    #[case::single_slot(1, 1, true)]
+    #[case::multiple_slots(3, 3, true)]
+    #[case::less_estimates(3, 2, false)]
+    #[case::more_estimates(1, 2, false)]
+    fn estimates_count_should_match_price_slots_count(
+        #[case] price_slots_count: usize,
+        #[case] estimates_count: usize,
+        #[case] is_calculated: bool,
+    ) {
+        init_infrastructure();
+
+        let mut strategy = TestStrategy { estimates_count };
+
+        let trading_context = calculate_trading_context(
+            &ExchangeEvent::LifecycleState(LifecycleState::Running),
+            &mut strategy,
+            &LocalSnapshotsService::default(),
+            price_slots_count,
+            None,
+            Utc::now(),
+        );
+
+        assert_eq!(trading_context.is_some(), is_calculated);
+    }
 }
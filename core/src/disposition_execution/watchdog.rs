@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use mockall_double::double;
+
+use crate::infrastructure::spawn_by_timer;
+use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+#[double]
+use crate::misc::time::time_manager;
+
+const WATCHDOG_CHECK_PERIOD: Duration = Duration::from_secs(30);
+
+/// Time of the last iteration of DispositionExecutor loop (Unix millis).
+/// Loop iterates on every event and timer tick, so it isn't updated only if the loop is stuck
+#[derive(Clone)]
+pub struct LastDecisionTime(Arc<AtomicI64>);
+
+impl LastDecisionTime {
+    pub fn new(now: DateTime) -> Self {
+        LastDecisionTime(Arc::new(AtomicI64::new(now.timestamp_millis())))
+    }
+
+    pub fn update(&self, now: DateTime) {
+        self.0.store(now.timestamp_millis(), Ordering::Relaxed);
+    }
+
+    pub fn age(&self, now: DateTime) -> Duration {
+        let age_millis = now.timestamp_millis() - self.0.load(Ordering::Relaxed);
+        Duration::from_millis(age_millis.max(0) as u64)
+    }
+}
+
+/// Detects that DispositionExecutor is stuck because its loop didn't iterate for too long
+pub struct DecisionWatchdog {
+    last_decision_at: LastDecisionTime,
+    stale_decision_timeout: Duration,
+}
+
+impl DecisionWatchdog {
+    pub fn new(last_decision_at: LastDecisionTime, stale_decision_timeout: Duration) -> Self {
+        DecisionWatchdog {
+            last_decision_at,
+            stale_decision_timeout,
+        }
+    }
+
+    /// Returns age of the last decision if it exceeds `stale_decision_timeout`
+    pub fn check(&self, now: DateTime) -> Option<Duration> {
+        let age = self.last_decision_at.age(now);
+        (age > self.stale_decision_timeout).then_some(age)
+    }
+}
+
+/// Check last decision age every 30 seconds and start graceful shutdown if it's stale
+pub(crate) fn spawn_decision_watchdog(
+    watchdog: DecisionWatchdog,
+    lifetime_manager: Arc<AppLifetimeManager>,
+) {
+    let watchdog = Arc::new(watchdog);
+    spawn_by_timer(
+        "Disposition executor watchdog",
+        WATCHDOG_CHECK_PERIOD,
+        WATCHDOG_CHECK_PERIOD,
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let watchdog = watchdog.clone();
+            let lifetime_manager = lifetime_manager.clone();
+            async move {
+                if let Some(age) = watchdog.check(time_manager::now()) {
+                    let reason = format!("DispositionExecutor loop didn't iterate for {age:?}");
+                    log::error!("{reason}");
+                    lifetime_manager.spawn_graceful_shutdown(&reason);
+                }
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use chrono::Utc;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn fresh_decision_is_not_stale() {
+        let now = Utc::now();
+        let watchdog = DecisionWatchdog::new(LastDecisionTime::new(now), Duration::from_secs(10));
+
+        assert_eq!(watchdog.check(now + ChronoDuration::seconds(5)), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn watchdog_fires_when_calculation_loop_is_frozen() {
+        let last_decision_at = LastDecisionTime::new(Utc::now());
+        let (freeze_tx, mut freeze_rx) = mpsc::channel::<()>(1);
+
+        // Calculation loop that updates decision time until it freezes
+        let calculation_loop = tokio::spawn({
+            let last_decision_at = last_decision_at.clone();
+            async move {
+                loop {
+                    if freeze_rx.try_recv().is_ok() {
+                        std::future::pending::<()>().await;
+                    }
+                    last_decision_at.update(Utc::now());
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            }
+        });
+
+        let watchdog = DecisionWatchdog::new(last_decision_at, Duration::from_millis(100));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(watchdog.check(Utc::now()), None);
+
+        freeze_tx
+            .send(())
+            .await
+            .expect("Failed to freeze calculation loop");
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let age = watchdog.check(Utc::now()).expect("Watchdog should fire");
+        assert!(age > Duration::from_millis(100));
+
+        calculation_loop.abort();
+    }
+}
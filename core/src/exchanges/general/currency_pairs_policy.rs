@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+
+use mmb_domain::market::CurrencyPair;
+
+use crate::settings::ExchangeSettings;
+
+/// Safety control which guarantees that orders are created only for approved currency pairs
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyPairsPolicy {
+    /// All currency pairs are allowed if `None`
+    allowed: Option<HashSet<CurrencyPair>>,
+    denied: HashSet<CurrencyPair>,
+}
+
+impl CurrencyPairsPolicy {
+    pub fn new(allowed: Option<HashSet<CurrencyPair>>, denied: HashSet<CurrencyPair>) -> Self {
+        CurrencyPairsPolicy { allowed, denied }
+    }
+
+    pub fn from_settings(settings: &ExchangeSettings) -> Self {
+        CurrencyPairsPolicy::new(
+            settings
+                .allowed_currency_pairs
+                .as_ref()
+                .map(|x| x.iter().copied().collect()),
+            settings.denied_currency_pairs.iter().copied().collect(),
+        )
+    }
+
+    pub fn is_allowed(&self, currency_pair: CurrencyPair) -> bool {
+        if self.denied.contains(&currency_pair) {
+            return false;
+        }
+
+        match &self.allowed {
+            Some(allowed) => allowed.contains(&currency_pair),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn pairs(values: &[&str]) -> HashSet<CurrencyPair> {
+        values.iter().map(|&x| CurrencyPair::from(x)).collect()
+    }
+
+    #[rstest]
+    #[case::allow_all_by_default(None, &[], "btc/usdt", true)]
+    #[case::allowed(Some(&["btc/usdt", "eth/btc"][..]), &[], "eth/btc", true)]
+    #[case::not_in_allowlist(Some(&["btc/usdt"][..]), &[], "eth/btc", false)]
+    #[case::denied(None, &["eth/btc"], "eth/btc", false)]
+    #[case::not_denied(None, &["eth/btc"], "btc/usdt", true)]
+    #[case::denylist_has_priority(Some(&["eth/btc"][..]), &["eth/btc"], "eth/btc", false)]
+    fn check_currency_pair(
+        #[case] allowed: Option<&[&str]>,
+        #[case] denied: &[&str],
+        #[case] currency_pair: &str,
+        #[case] expected: bool,
+    ) {
+        let policy = CurrencyPairsPolicy::new(allowed.map(pairs), pairs(denied));
+
+        assert_eq!(policy.is_allowed(currency_pair.into()), expected);
+    }
+}
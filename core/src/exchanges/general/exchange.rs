@@ -4,8 +4,12 @@ use crate::connectivity::{
     websocket_open, ConnectivityError, WebSocketParams, WebSocketRole, WsSender,
 };
 use crate::database::events::recorder::EventRecorder;
+use crate::disposition_execution::trade_limit::{
+    is_net_position_allowed, is_price_within_band, open_orders_amount,
+};
 use crate::exchanges::block_reasons::WEBSOCKET_DISCONNECTED;
 use crate::exchanges::exchange_blocker::{BlockType, ExchangeBlocker};
+use crate::exchanges::general::currency_pairs_policy::CurrencyPairsPolicy;
 use crate::exchanges::general::features::ExchangeFeatures;
 use crate::exchanges::general::order::cancel::CancelOrderResult;
 use crate::exchanges::general::order::create::CreateOrderResult;
@@ -15,31 +19,37 @@ use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::exchanges::traits::{ExchangeClient, ExchangeError};
 use crate::infrastructure::spawn_future;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use crate::math::ConvertPercentToRate;
 use crate::misc::time::time_manager;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
 use crate::orders::buffered_fills::buffered_canceled_orders_manager::BufferedCanceledOrdersManager;
 use crate::orders::buffered_fills::buffered_fills_manager::BufferedFillsManager;
+use crate::settings::MarketMaxPosition;
 use anyhow::{bail, Context, Result};
 use dashmap::DashMap;
 use function_name::named;
 use futures::future::join_all;
 use itertools::Itertools;
+use lru::LruCache;
 use mmb_database::impl_event;
 use mmb_domain::events::{
     BalanceUpdateEvent, ExchangeBalancesAndPositions, ExchangeEvent, LiquidationPriceEvent,
     MetricsEvent, MetricsEventInfo, MetricsEventInfoBase, MetricsEventType, MetricsTime, Trade,
+    TradeId,
 };
-use mmb_domain::exchanges::commission::Commission;
+use mmb_domain::exchanges::commission::{Commission, TradingFees};
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::{
-    CurrencyCode, CurrencyPair, ExchangeAccountId, MarketId, SpecificCurrencyPair,
+    CurrencyCode, CurrencyPair, ExchangeAccountId, ExchangeErrorType, MarketId,
+    SpecificCurrencyPair,
 };
 use mmb_domain::order::event::OrderEvent;
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::pool::OrdersPool;
-use mmb_domain::order::snapshot::OrderSide;
 use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId};
+use mmb_domain::order::snapshot::{OrderRole, OrderSide};
 use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
@@ -49,12 +59,18 @@ use parking_lot::Mutex;
 use rust_decimal::Decimal;
 use serde::Serialize;
 use std::fmt::Debug;
+use std::num::NonZeroUsize;
 use std::ops::DerefMut;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio::sync::{broadcast, oneshot};
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
+
+/// Trading pause after exchange reported maintenance. Pause is extended by every next maintenance error
+const MAINTENANCE_PAUSE_MINUTES: i64 = 5;
+const DEFAULT_RECEIVED_FILLS_CACHE_CAPACITY: usize = 1_000;
+const TRADING_FEES_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum RequestResult<T> {
@@ -95,11 +111,15 @@ pub struct Exchange {
     pub currencies: Mutex<Vec<CurrencyCode>>,
     pub leverage_by_currency_pair: DashMap<CurrencyPair, Decimal>,
     pub order_book_top: DashMap<CurrencyPair, OrderBookTop>,
+    /// Local order books of the exchange markets. They are updated by `InternalEventsLoop`
+    pub(crate) local_snapshots_service: Mutex<LocalSnapshotsService>,
     pub exchange_client: BoxExchangeClient,
     pub(super) features: ExchangeFeatures,
     pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
     pub(super) lifetime_manager: Arc<AppLifetimeManager>,
     pub(super) commission: Commission,
+    /// Fee rates received from exchange by market. `commission` is used for markets without received fees
+    pub(super) trading_fees: DashMap<CurrencyPair, TradingFees>,
     pub(super) wait_cancel_order: DashMap<ClientOrderId, broadcast::Sender<()>>,
     pub(super) wait_finish_order: DashMap<ClientOrderId, broadcast::Sender<OrderRef>>,
     pub(super) polling_trades_counts: DashMap<ExchangeAccountId, u32>,
@@ -110,7 +130,15 @@ pub struct Exchange {
     pub(super) last_trades: DashMap<MarketId, Trade>,
     pub(super) timeout_manager: Arc<TimeoutManager>,
     pub(crate) balance_manager: Mutex<Option<Weak<Mutex<BalanceManager>>>>,
+    pub(super) currency_pairs_policy: Mutex<CurrencyPairsPolicy>,
+    /// Max deviation of order price from mid price in percents. Price isn't checked if not set
+    price_band_percent: Mutex<Option<Decimal>>,
+    /// Caps of absolute net position by currency pair. Position isn't limited for pairs without cap
+    max_positions: DashMap<CurrencyPair, Amount>,
     pub(super) buffered_fills_manager: Mutex<BufferedFillsManager>,
+    /// Recently applied fills to ignore the same fill received again from another source after
+    /// the order was removed from cache. Duplicates of fills for orders in cache are detected by order fills
+    pub(super) received_fills: Mutex<LruCache<(ExchangeOrderId, TradeId), ()>>,
     pub(super) buffered_canceled_orders_manager: Mutex<BufferedCanceledOrdersManager>,
     // It allows to send and receive notification about event in websocket channel
     // Websocket event is main source detecting order creation result
@@ -133,6 +161,8 @@ pub struct Exchange {
     exchange_blocker: Weak<ExchangeBlocker>,
     ws_sender: Mutex<Option<WsSender>>,
     auto_reconnect: AtomicBool,
+    /// Paper trading mode: orders are created and cancelled locally without requests to exchange
+    dry_run: AtomicBool,
 
     // Temporary fix before integration ExchangeBlocker to wait_order_finish/wait_cancel_order fallbacks #641
     timeout: Duration,
@@ -151,6 +181,7 @@ impl Exchange {
         orders: Arc<OrdersPool>,
         features: ExchangeFeatures,
         timeout_arguments: RequestTimeoutArguments,
+        polling_jitter_fraction: f64,
         events_channel: broadcast::Sender<ExchangeEvent>,
         lifetime_manager: Arc<AppLifetimeManager>,
         timeout_manager: Arc<TimeoutManager>,
@@ -158,7 +189,14 @@ impl Exchange {
         commission: Commission,
         event_recorder: Arc<EventRecorder>,
     ) -> Arc<Self> {
-        let polling_timeout_manager = PollingTimeoutManager::new(timeout_arguments);
+        let polling_timeout_manager =
+            PollingTimeoutManager::new(timeout_arguments, polling_jitter_fraction);
+        let received_fills_cache_capacity = exchange_client
+            .get_settings()
+            .received_fills_cache_capacity
+            .and_then(NonZeroUsize::new)
+            .or_else(|| NonZeroUsize::new(DEFAULT_RECEIVED_FILLS_CACHE_CAPACITY))
+            .expect("Received fills cache capacity should be positive");
 
         Arc::new_cyclic(move |e| {
             Self::setup_exchange_client(e.clone(), exchange_client.as_mut());
@@ -176,6 +214,7 @@ impl Exchange {
                 events_channel,
                 timeout_manager,
                 commission,
+                trading_fees: DashMap::new(),
                 symbols: Default::default(),
                 currencies: Default::default(),
                 order_book_top: Default::default(),
@@ -189,10 +228,16 @@ impl Exchange {
                 last_trades_update_time: DashMap::new(),
                 last_trades: DashMap::new(),
                 balance_manager: Mutex::new(None),
+                currency_pairs_policy: Default::default(),
+                price_band_percent: Default::default(),
+                local_snapshots_service: Default::default(),
+                max_positions: Default::default(),
                 buffered_fills_manager: Default::default(),
+                received_fills: Mutex::new(LruCache::new(received_fills_cache_capacity)),
                 exchange_blocker,
                 buffered_canceled_orders_manager: Default::default(),
                 auto_reconnect: AtomicBool::new(false),
+                dry_run: AtomicBool::new(false),
                 timeout,
                 server_time_latency: Default::default(),
                 event_recorder,
@@ -359,6 +404,112 @@ impl Exchange {
         *self.balance_manager.lock() = Some(Arc::downgrade(&balance_manager));
     }
 
+    pub fn setup_currency_pairs_policy(&self, currency_pairs_policy: CurrencyPairsPolicy) {
+        *self.currency_pairs_policy.lock() = currency_pairs_policy;
+    }
+
+    pub fn setup_price_band_percent(&self, price_band_percent: Option<Decimal>) {
+        *self.price_band_percent.lock() = price_band_percent;
+    }
+
+    /// Checks that price deviates from mid price of local order book not more than the price band.
+    /// Price isn't checked if price band isn't set. Price can't be checked without order book, so it's rejected
+    pub fn check_price_band(
+        &self,
+        currency_pair: CurrencyPair,
+        price: Price,
+    ) -> Result<(), String> {
+        let price_band_percent = match *self.price_band_percent.lock() {
+            Some(price_band_percent) => price_band_percent,
+            None => return Ok(()),
+        };
+
+        let market_id = MarketId::new(self.exchange_account_id.exchange_id, currency_pair);
+        let mid_price = self
+            .local_snapshots_service
+            .lock()
+            .mid_price(market_id, None);
+
+        match mid_price {
+            Some(mid_price) => is_price_within_band(price, mid_price, price_band_percent),
+            None => Err(format!(
+                "Can't check price {price} by price band because there is no order book for {currency_pair}"
+            )),
+        }
+    }
+
+    pub fn setup_max_positions(&self, max_positions: &[MarketMaxPosition]) {
+        self.max_positions.clear();
+        for market in max_positions {
+            let _ = self
+                .max_positions
+                .insert(market.currency_pair, market.max_position);
+        }
+    }
+
+    /// Checks that absolute net position doesn't exceed the strictest of cap from settings `max_positions`
+    /// and `extra_max_position` after filling the new order together with all open orders on the same side.
+    /// Position isn't checked if none of caps is set
+    pub fn check_net_position(
+        &self,
+        currency_pair: CurrencyPair,
+        side: OrderSide,
+        amount: Amount,
+        extra_max_position: Option<Amount>,
+    ) -> Result<(), String> {
+        let max_position = [
+            self.max_positions.get(&currency_pair).map(|x| *x),
+            extra_max_position,
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        let max_position = match max_position {
+            Some(max_position) => max_position,
+            None => return Ok(()),
+        };
+
+        let balance_manager = self.balance_manager.lock().as_ref().and_then(Weak::upgrade);
+        let net_position = match balance_manager {
+            Some(balance_manager) => balance_manager
+                .lock()
+                .get_net_position_by_fills(self.exchange_account_id, currency_pair),
+            None => {
+                return Err(format!(
+                    "Can't check net position for {currency_pair} because BalanceManager isn't initialized"
+                ))
+            }
+        };
+        let open_orders_amount = open_orders_amount(&self.orders, currency_pair, side);
+
+        is_net_position_allowed(net_position, side, open_orders_amount, amount, max_position)
+    }
+
+    /// Enable paper trading mode. Should be called before connecting websockets,
+    /// so private user data stream isn't opened
+    pub fn set_dry_run(&self, dry_run: bool) {
+        self.dry_run.store(dry_run, Ordering::SeqCst);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::SeqCst)
+    }
+
+    /// Request fresh order book snapshot from exchange to replace the local order book which missed some updates.
+    /// Exchange client validates the next updates against update id of the snapshot
+    pub fn request_order_book_resync(&self, currency_pair: CurrencyPair) {
+        if let Err(err) = self
+            .exchange_client
+            .request_order_book_resync(currency_pair)
+        {
+            log::error!(
+                "Unable to request order book resync for {currency_pair} on {}: {err:?}",
+                self.exchange_account_id
+            );
+        }
+    }
+
     pub async fn reconnect_ws(self: &Arc<Self>) -> Result<()> {
         self.disconnect_ws().await;
         self.connect_ws().await
@@ -441,7 +592,13 @@ impl Exchange {
                 ConnectivityError::FailedToGetParams(WebSocketRole::Main, e.to_string())
             })?;
 
-        let secondary = if self
+        let secondary = if self.is_dry_run() {
+            log::info!(
+                "Secondary websocket isn't used in dry run mode for {}",
+                self.exchange_account_id
+            );
+            None
+        } else if self
             .exchange_client
             .is_websocket_enabled(WebSocketRole::Secondary)
         {
@@ -460,6 +617,7 @@ impl Exchange {
             None
         };
         let (tx, rx) = websocket_open(self.exchange_account_id, main, secondary).await?;
+        let tx = tx.with_max_frame_size(self.features.websocket_options.max_frame_size);
         self.ws_sender.lock().replace(tx);
         Ok(rx)
     }
@@ -490,7 +648,21 @@ impl Exchange {
         role: WebSocketRole,
     ) -> Result<WebSocketParams> {
         let ws_url = self.exchange_client.create_ws_url(role).await?;
-        Ok(WebSocketParams::new(ws_url))
+        let websocket_options = &self.features.websocket_options;
+
+        let mut builder =
+            WebSocketParams::builder(ws_url).headers(self.exchange_client.create_ws_headers(role));
+        match websocket_options.stale_timeout {
+            // pings are sent anyway, otherwise quiet connection would be reset
+            Some(stale_timeout) => builder = builder.stale_timeout(Some(stale_timeout)),
+            // without pings silence doesn't mean that connection is stale
+            None if !websocket_options.supports_ping_pong => {
+                builder = builder.ping_interval(None).stale_timeout(None)
+            }
+            None => {}
+        }
+
+        Ok(builder.build())
     }
 
     pub(crate) fn add_event_on_order_change(
@@ -795,6 +967,20 @@ impl Exchange {
         self.timeout
     }
 
+    pub(crate) fn pause_on_maintenance(&self, error: &ExchangeError) {
+        if error.error_type != ExchangeErrorType::Maintenance {
+            return;
+        }
+
+        self.lifetime_manager.pause_until(
+            time_manager::now() + chrono::Duration::minutes(MAINTENANCE_PAUSE_MINUTES),
+            &format!(
+                "{} is under maintenance: {}",
+                self.exchange_account_id, error.message
+            ),
+        );
+    }
+
     pub fn get_symbol(&self, currency_pair: CurrencyPair) -> Result<Arc<Symbol>> {
         self.symbols
             .get(&currency_pair)
@@ -807,6 +993,65 @@ impl Exchange {
             .map(|pair| pair.value().clone())
     }
 
+    /// Expected fee rate of order with specified role, e.g. 0.001 for 0.1%.
+    /// Fees received from exchange are preferred over default commission
+    pub fn fee_rate(&self, currency_pair: CurrencyPair, order_role: OrderRole) -> Decimal {
+        match self.trading_fees.get(&currency_pair) {
+            Some(trading_fees) => trading_fees.fee_rate(order_role),
+            None => self
+                .commission
+                .get_commission(order_role)
+                .fee
+                .percent_to_rate(),
+        }
+    }
+
+    pub fn maker_fee(&self, currency_pair: CurrencyPair) -> Decimal {
+        self.fee_rate(currency_pair, OrderRole::Maker)
+    }
+
+    pub fn taker_fee(&self, currency_pair: CurrencyPair) -> Decimal {
+        self.fee_rate(currency_pair, OrderRole::Taker)
+    }
+
+    /// Request fee rates of all traded markets from exchange concurrently. Default commission is used
+    /// for markets if request failed or wasn't completed in `TRADING_FEES_REQUEST_TIMEOUT`
+    pub async fn load_trading_fees(&self) {
+        let currency_pairs = self.symbols.iter().map(|x| *x.key()).collect_vec();
+        join_all(currency_pairs.into_iter().map(|currency_pair| async move {
+            let trading_fees = timeout(
+                TRADING_FEES_REQUEST_TIMEOUT,
+                self.exchange_client.get_trading_fees(currency_pair),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                bail!(
+                    "timeout {} secs is exceeded",
+                    TRADING_FEES_REQUEST_TIMEOUT.as_secs()
+                )
+            });
+
+            match trading_fees {
+                Ok(trading_fees) => {
+                    log::info!(
+                        "Trading fees on {} for {currency_pair}: {trading_fees:?}",
+                        self.exchange_account_id
+                    );
+                    self.set_trading_fees(currency_pair, trading_fees);
+                }
+                Err(err) => log::warn!(
+                    "Unable to get trading fees on {} for {currency_pair}, default commission is used: {err:?}",
+                    self.exchange_account_id
+                ),
+            }
+        }))
+        .await;
+    }
+
+    pub(crate) fn set_trading_fees(&self, currency_pair: CurrencyPair, trading_fees: TradingFees) {
+        let _ = self.trading_fees.insert(currency_pair, trading_fees);
+    }
+
     pub fn update_server_time_latency(&self, latency: i64) {
         self.server_time_latency.store(latency, Ordering::SeqCst)
     }
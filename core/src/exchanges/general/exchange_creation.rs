@@ -1,10 +1,13 @@
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use crate::database::events::recorder::EventRecorder;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
+use crate::exchanges::general::currency_pairs_policy::CurrencyPairsPolicy;
+use crate::exchanges::general::features::ExchangeFeatures;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::lifecycle::launcher::EngineBuildConfig;
-use crate::settings::ExchangeSettings;
+use crate::settings::{AllowedEventSources, ExchangeSettings};
 use crate::{
     exchanges::{
         general::exchange::Exchange,
@@ -13,11 +16,43 @@ use crate::{
     },
     settings::CoreSettings,
 };
+use anyhow::{bail, Result};
+use itertools::Itertools;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::exchanges::commission::Commission;
 use mmb_domain::order::pool::OrdersPool;
+use rust_decimal::Decimal;
 use tokio::sync::broadcast;
 
+/// Check that exchanges of all accounts from settings are registered in `EngineBuildConfig`
+/// and count of accounts of every exchange doesn't exceed registered account count
+pub fn check_exchange_accounts(
+    core_settings: &CoreSettings,
+    build_settings: &EngineBuildConfig,
+) -> Result<()> {
+    let account_ids_by_exchange = core_settings
+        .exchanges
+        .iter()
+        .map(|x| x.exchange_account_id)
+        .into_group_map_by(|x| x.exchange_id);
+
+    for (exchange_id, exchange_account_ids) in account_ids_by_exchange {
+        let account_count = match build_settings.account_counts.get(&exchange_id) {
+            Some(&account_count) => account_count,
+            None => bail!("Exchange {exchange_id} isn't registered in EngineBuildConfig"),
+        };
+
+        if exchange_account_ids.len() > account_count as usize {
+            bail!(
+                "Accounts {} are configured, but only {account_count} account(s) of {exchange_id} are registered in EngineBuildConfig. Use `EngineBuildConfig::with_account_count` for several accounts of the same exchange",
+                exchange_account_ids.iter().join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
 pub fn create_timeout_manager(
     core_settings: &CoreSettings,
     build_settings: &EngineBuildConfig,
@@ -43,8 +78,11 @@ pub fn create_timeout_manager(
     TimeoutManager::new(request_timeout_managers)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_exchange(
     user_settings: &ExchangeSettings,
+    jitter_fraction: f64,
+    price_band_percent: Option<Decimal>,
     build_settings: &EngineBuildConfig,
     events_channel: broadcast::Sender<ExchangeEvent>,
     lifetime_manager: Arc<AppLifetimeManager>,
@@ -57,7 +95,7 @@ pub async fn create_exchange(
         &build_settings.supported_exchange_clients[&exchange_account_id.exchange_id];
     let orders = OrdersPool::new();
 
-    let exchange_client = exchange_client_builder.create_exchange_client(
+    let mut exchange_client = exchange_client_builder.create_exchange_client(
         user_settings.clone(),
         events_channel.clone(),
         lifetime_manager.clone(),
@@ -65,12 +103,23 @@ pub async fn create_exchange(
         orders.clone(),
     );
 
+    if let Some(stale_timeout_secs) = user_settings.websocket_stale_timeout_secs {
+        exchange_client.features.websocket_options.stale_timeout =
+            Some(Duration::from_secs(stale_timeout_secs));
+    }
+
+    apply_allowed_event_sources(
+        &mut exchange_client.features,
+        &user_settings.allowed_event_sources,
+    );
+
     let exchange = Exchange::new(
         exchange_account_id,
         exchange_client.client,
         orders,
         exchange_client.features,
         exchange_client_builder.get_timeout_arguments(),
+        jitter_fraction,
         events_channel,
         lifetime_manager,
         timeout_manager,
@@ -79,8 +128,25 @@ pub async fn create_exchange(
         event_recorder,
     );
 
+    exchange.setup_currency_pairs_policy(CurrencyPairsPolicy::from_settings(user_settings));
+    exchange.setup_price_band_percent(price_band_percent);
+    exchange.setup_max_positions(&user_settings.max_positions);
     exchange.build_symbols(&user_settings.currency_pairs).await;
+    // exchange client can prepare signing of requests on initialization, e.g. sync server time
     exchange.exchange_client.initialized(exchange.clone()).await;
+    exchange.load_trading_fees().await;
 
     exchange
 }
+
+fn apply_allowed_event_sources(features: &mut ExchangeFeatures, sources: &AllowedEventSources) {
+    if let Some(create) = sources.create {
+        features.allowed_create_event_source_type = create;
+    }
+    if let Some(fill) = sources.fill {
+        features.allowed_fill_event_source_type = fill;
+    }
+    if let Some(cancel) = sources.cancel {
+        features.allowed_cancel_event_source_type = cancel;
+    }
+}
@@ -8,7 +8,7 @@ use std::sync::Arc;
 
 use crate::settings::CurrencyPairSetting;
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::market::{CurrencyId, ExchangeAccountId};
+use mmb_domain::market::{CurrencyId, CurrencyPair, ExchangeAccountId};
 
 use super::exchange::Exchange;
 
@@ -128,8 +128,15 @@ fn get_matched_currency_pair(
                 symbol.currency_pair().as_str() == currency_pair
             }
             CurrencyPairSetting::Ordinary { base, quote } => {
-                symbol.base_currency_code == *base && symbol.quote_currency_code == *quote
+                symbol.base_currency_code == *base
+                    && symbol.quote_currency_code == *quote
+                    && symbol.expiry.is_none()
             }
+            CurrencyPairSetting::Derivative {
+                base,
+                quote,
+                expiry,
+            } => symbol.currency_pair() == CurrencyPair::from_derivative(*base, *quote, *expiry),
         })
         .take(2)
         .cloned()
@@ -143,3 +150,61 @@ fn get_matched_currency_pair(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use mmb_domain::exchanges::symbol::Precision;
+
+    fn symbol(expiry: Option<NaiveDate>) -> Arc<Symbol> {
+        Arc::new(
+            Symbol::new(
+                true,
+                "BTC".into(),
+                "btc".into(),
+                "USDT".into(),
+                "usdt".into(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                "btc".into(),
+                Some("usdt".into()),
+                Precision::ByTick { tick: dec!(0.1) },
+                Precision::ByTick { tick: dec!(0.001) },
+            )
+            .with_expiry(expiry),
+        )
+    }
+
+    #[test]
+    fn match_perpetual_and_delivery_contracts() {
+        let expiry = NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test");
+        // e.g. BTCUSDT and BTCUSDT_221230 on Binance futures
+        let exchange_symbols = [symbol(None), symbol(Some(expiry))];
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+
+        let ordinary = CurrencyPairSetting::Ordinary {
+            base: "btc".into(),
+            quote: "usdt".into(),
+        };
+        let matched = get_matched_currency_pair(&ordinary, &exchange_symbols, exchange_account_id)
+            .expect("in test");
+        assert_eq!(matched.expiry, None);
+
+        let derivative = CurrencyPairSetting::Derivative {
+            base: "btc".into(),
+            quote: "usdt".into(),
+            expiry,
+        };
+        let matched =
+            get_matched_currency_pair(&derivative, &exchange_symbols, exchange_account_id)
+                .expect("in test");
+        assert_eq!(
+            matched.currency_pair(),
+            CurrencyPair::from_derivative("btc".into(), "usdt".into(), expiry)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use mmb_domain::events::AllowedEventSourceType;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum OpenOrdersType {
@@ -42,10 +43,17 @@ pub struct WebSocketOptions {
     pub execution_notification: bool,
     /// Is order cancellation result able to receive
     pub cancellation_notification: bool,
-    // TODO Not used, is it redundant?
+    /// Protocol-level pings are sent to detect half-open connections
     pub supports_ping_pong: bool,
     // TODO Used in exchange inner not in core, is it redundant?
     pub supports_subscription_response: bool,
+    /// Websocket connection is reset if no frames were received during this time.
+    /// Pings are sent even if `supports_ping_pong` is false when it is set.
+    /// Default timeout is used if not set and pings are supported, otherwise connection isn't reset
+    pub stale_timeout: Option<Duration>,
+    /// Outgoing messages bigger than this size in bytes are split into continuation frames,
+    /// because some exchanges reject big frames. Messages aren't split if not set
+    pub max_frame_size: Option<usize>,
 }
 
 impl WebSocketOptions {
@@ -60,6 +68,8 @@ impl WebSocketOptions {
             cancellation_notification,
             supports_ping_pong,
             supports_subscription_response,
+            stale_timeout: None,
+            max_frame_size: None,
         }
     }
 }
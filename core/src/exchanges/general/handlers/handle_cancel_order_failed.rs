@@ -68,6 +68,8 @@ impl Exchange {
         error: ExchangeError,
         event_source_type: EventSourceType,
     ) {
+        self.pause_on_maintenance(&error);
+
         match error.error_type {
             ExchangeErrorType::OrderNotFound => {
                 self.handle_cancel_order_succeeded(None, exchange_order_id, None, event_source_type)
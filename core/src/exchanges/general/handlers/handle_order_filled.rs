@@ -118,6 +118,13 @@ impl Exchange {
             .get(&fill_event.exchange_order_id)
         {
             None => {
+                // Order can be removed from cache before the same fill is received from another source,
+                // so the fill can't be checked against fills of the order
+                if self.was_fill_received_for_removed_order(fill_event) {
+                    log::debug!("Ignoring fill which was received already {args_to_log:?}");
+                    return;
+                }
+
                 log::info!("Received a fill for not existing order {args_to_log:?}",);
 
                 self.buffered_fills_manager
@@ -136,6 +143,15 @@ impl Exchange {
         }
     }
 
+    fn was_fill_received_for_removed_order(&self, fill_event: &FillEvent) -> bool {
+        let Some(trade_id) = &fill_event.trade_id else {
+            return false;
+        };
+
+        let fill_key = (fill_event.exchange_order_id.clone(), trade_id.clone());
+        self.received_fills.lock().contains(&fill_key)
+    }
+
     fn was_trade_already_received(
         trade_id: &Option<TradeId>,
         order_fills: &[OrderFill],
@@ -387,9 +403,13 @@ impl Exchange {
         }
     }
 
-    fn set_commission_rate(&self, fill_event: &mut FillEvent, order_role: OrderRole) -> Decimal {
-        let commission = self.commission.get_commission(order_role).fee;
-        let expected_commission_rate = commission.percent_to_rate();
+    fn set_commission_rate(
+        &self,
+        fill_event: &mut FillEvent,
+        currency_pair: CurrencyPair,
+        order_role: OrderRole,
+    ) -> Decimal {
+        let expected_commission_rate = self.fee_rate(currency_pair, order_role);
 
         if fill_event.commission_amount.is_none() && fill_event.commission_rate.is_none() {
             fill_event.commission_rate = Some(expected_commission_rate);
@@ -497,7 +517,7 @@ impl Exchange {
         order_role: OrderRole,
         commission_currency_code: CurrencyCode,
         converted_commission_amount: Amount,
-    ) {
+    ) -> bool {
         let last_fill_amount_in_converted_commission_currency_code = symbol
             .convert_amount_from_amount_currency_code(
                 converted_commission_currency_code,
@@ -542,7 +562,15 @@ impl Exchange {
             self.exchange_account_id
         );
 
-        order_ref.fn_mut(move |order| order.add_fill(order_fill));
+        let is_added = order_ref.fn_mut(move |order| order.try_add_fill(order_fill));
+        if !is_added {
+            log::info!(
+                "Fill {trade_id:?} was added already {client_order_id} {exchange_order_id:?} {}",
+                self.exchange_account_id
+            );
+        }
+
+        is_added
     }
 
     fn create_and_add_order_fill(&self, fill_event: &mut FillEvent, order_ref: &OrderRef) {
@@ -590,7 +618,8 @@ impl Exchange {
 
         let order_role = Self::get_order_role(fill_event, order_ref);
 
-        let expected_commission_rate = self.set_commission_rate(fill_event, order_role);
+        let expected_commission_rate =
+            self.set_commission_rate(fill_event, order_ref.currency_pair(), order_role);
 
         let commission_amount = Self::get_commission_amount(
             fill_event.commission_amount,
@@ -613,7 +642,7 @@ impl Exchange {
             &mut converted_commission_currency_code,
         );
 
-        self.add_fill(
+        let is_added = self.add_fill(
             &fill_event.trade_id,
             matches!(fill_event.fill_amount, FillAmount::Incremental { .. }),
             fill_event.fill_type,
@@ -630,6 +659,16 @@ impl Exchange {
             converted_commission_amount,
         );
 
+        if !is_added {
+            // The same trade was added concurrently while handling a fill from another source
+            return;
+        }
+
+        if let Some(trade_id) = &fill_event.trade_id {
+            let fill_key = (fill_event.exchange_order_id.clone(), trade_id.clone());
+            self.received_fills.lock().put(fill_key, ());
+        }
+
         // This order fields updated, so let's use actual values
         let order_filled_amount = order_ref.filled_amount();
 
@@ -736,13 +775,21 @@ impl Exchange {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::balance::manager::balance_manager::BalanceManager;
+    use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+    use crate::misc::time;
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
     use crate::{
         exchanges::general::exchange::OrderBookTop, exchanges::general::exchange::PriceLevel,
         exchanges::general::test_helper, exchanges::general::test_helper::create_order_ref,
         exchanges::general::test_helper::get_test_exchange,
+        exchanges::general::test_helper::get_test_exchange_with_symbol_and_client,
+        exchanges::general::test_helper::TestClient,
     };
     use anyhow::{Context, Result};
     use chrono::Utc;
+    use mmb_domain::events::{ExchangeBalance, ExchangeBalancesAndPositions, ExchangeEvent};
+    use mmb_domain::exchanges::commission::TradingFees;
     use mmb_domain::market::CurrencyCode;
     use mmb_domain::order::fill::OrderFill;
     use mmb_domain::order::pool::OrdersPool;
@@ -751,6 +798,7 @@ mod test {
         SystemInternalOrderProps,
     };
     use mmb_domain::order::snapshot::{OrderType, UserOrder};
+    use mmb_utils::hashmap;
     use serde_json::json;
     use uuid::Uuid;
 
@@ -978,6 +1026,199 @@ mod test {
         assert_eq!(order_filled_amount, total_filled_amount);
     }
 
+    const SAME_TRADE_FILL_PRICE: Price = dec!(0.8);
+
+    /// Buy order PHB/BTC which is added to orders cache by exchange order id
+    fn add_order_to_cache(exchange: &Exchange, exchange_order_id: &ExchangeOrderId) -> OrderRef {
+        let mut order = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(SAME_TRADE_FILL_PRICE),
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            dec!(12),
+            OrderSide::Buy,
+            None,
+            "FromTest",
+        );
+        order.props.exchange_order_id = Some(exchange_order_id.clone());
+        let order_ref = exchange.orders.add_snapshot_initial(&order);
+        let _ = exchange
+            .orders
+            .cache_by_exchange_id
+            .insert(exchange_order_id.clone(), order_ref.clone());
+
+        order_ref
+    }
+
+    fn same_trade_fill_event(
+        source_type: EventSourceType,
+        client_order_id: Option<ClientOrderId>,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> FillEvent {
+        FillEvent {
+            source_type,
+            trade_id: Some(trade_id_from_str("same_trade_id")),
+            client_order_id,
+            exchange_order_id: exchange_order_id.clone(),
+            fill_price: SAME_TRADE_FILL_PRICE,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: dec!(5),
+                total_filled_amount: None,
+            },
+            order_role: Some(OrderRole::Maker),
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: None,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn same_trade_from_different_sources_changes_balances_once() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let (exchange, mut event_receiver) = get_test_exchange(false);
+        let exchange_account_id = exchange.exchange_account_id;
+
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let symbol = exchange.get_symbol(currency_pair).expect("in test");
+        let exchange_order_id = ExchangeOrderId::new("some_exchange_order_id".into());
+        let order_ref = add_order_to_cache(&exchange, &exchange_order_id);
+
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(hashmap![exchange_account_id => exchange.clone()]),
+            None,
+        );
+        balance_manager
+            .lock()
+            .update_exchange_balance(
+                exchange_account_id,
+                &ExchangeBalancesAndPositions {
+                    balances: vec![
+                        ExchangeBalance {
+                            currency_code: "PHB".into(),
+                            balance: dec!(10),
+                        },
+                        ExchangeBalance {
+                            currency_code: "BTC".into(),
+                            balance: dec!(10),
+                        },
+                    ],
+                    positions: None,
+                },
+            )
+            .expect("in test");
+
+        let configuration_descriptor =
+            ConfigurationDescriptor::new("Test".into(), "PHB/BTC".into());
+        let get_balance = |currency_code: &str| {
+            balance_manager.lock().get_balance_by_currency_code(
+                configuration_descriptor,
+                exchange_account_id,
+                symbol.clone(),
+                currency_code.into(),
+                SAME_TRADE_FILL_PRICE,
+            )
+        };
+        let initial_balances = (get_balance("PHB"), get_balance("BTC"));
+
+        let mut balances = Vec::new();
+        for source_type in [EventSourceType::WebSocket, EventSourceType::RestFallback] {
+            exchange.handle_order_filled(&mut same_trade_fill_event(
+                source_type,
+                None,
+                &exchange_order_id,
+            ));
+
+            while let Ok(event) = event_receiver.try_recv() {
+                if let ExchangeEvent::OrderEvent(event) = event {
+                    if let OrderEventType::OrderFilled { cloned_order } = event.event_type {
+                        balance_manager
+                            .lock()
+                            .order_was_filled(configuration_descriptor, &cloned_order);
+                    }
+                }
+            }
+
+            balances.push((get_balance("PHB"), get_balance("BTC")));
+        }
+
+        let (fills, filled_amount) = order_ref.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(filled_amount, dec!(5));
+
+        assert_ne!(balances[0], initial_balances);
+        assert_eq!(balances[1], balances[0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn same_trade_from_fallback_ignored_after_order_removed_from_cache() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let (exchange, _event_receiver) = get_test_exchange(false);
+
+        let exchange_order_id = ExchangeOrderId::new("some_exchange_order_id".into());
+        let order_ref = add_order_to_cache(&exchange, &exchange_order_id);
+        let client_order_id = Some(order_ref.client_order_id());
+
+        exchange.handle_order_filled(&mut same_trade_fill_event(
+            EventSourceType::WebSocket,
+            client_order_id.clone(),
+            &exchange_order_id,
+        ));
+
+        // order can be removed from cache before the same trade is received via fallback
+        let _ = exchange
+            .orders
+            .cache_by_exchange_id
+            .remove(&exchange_order_id);
+        exchange.handle_order_filled(&mut same_trade_fill_event(
+            EventSourceType::RestFallback,
+            client_order_id,
+            &exchange_order_id,
+        ));
+
+        let (fills, filled_amount) = order_ref.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(filled_amount, dec!(5));
+        assert!(exchange
+            .buffered_fills_manager
+            .lock()
+            .get_fills(&exchange_order_id)
+            .is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn same_trade_from_different_sources_concurrently_handled_once() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let (exchange, _event_receiver) = get_test_exchange(false);
+
+        let exchange_order_id = ExchangeOrderId::new("some_exchange_order_id".into());
+        let order_ref = add_order_to_cache(&exchange, &exchange_order_id);
+
+        let barrier = std::sync::Barrier::new(2);
+        std::thread::scope(|scope| {
+            for source_type in [EventSourceType::WebSocket, EventSourceType::RestFallback] {
+                let mut fill_event = same_trade_fill_event(
+                    source_type,
+                    Some(order_ref.client_order_id()),
+                    &exchange_order_id,
+                );
+                let exchange = &exchange;
+                let barrier = &barrier;
+                let _ = scope.spawn(move || {
+                    let _ = barrier.wait();
+                    exchange.handle_order_filled(&mut fill_event);
+                });
+            }
+        });
+
+        let (fills, filled_amount) = order_ref.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(filled_amount, dec!(5));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn ignore_diff_fill_after_non_diff() {
         let (exchange, _event_receiver) = get_test_exchange(false);
@@ -2568,6 +2809,192 @@ mod test {
         assert_eq!(first_fill.commission_amount(), result_value);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn trading_fees_received_from_exchange_are_preferred() {
+        let (exchange, _event_receiver) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let other_currency_pair = CurrencyPair::from_codes("ETH".into(), "BTC".into());
+
+        exchange.set_trading_fees(currency_pair, TradingFees::new(dec!(0.0002), dec!(0.0004)));
+
+        assert_eq!(exchange.maker_fee(currency_pair), dec!(0.0002));
+        assert_eq!(exchange.taker_fee(currency_pair), dec!(0.0004));
+        // default commission from test exchange
+        assert_eq!(exchange.maker_fee(other_currency_pair), dec!(0.001));
+        assert_eq!(exchange.taker_fee(other_currency_pair), dec!(0.002));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn loaded_trading_fees_are_charged_from_balance() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let mut exchange_client = TestClient::default();
+        exchange_client.trading_fees = Some(TradingFees::new(dec!(0.0002), dec!(0.0004)));
+        let symbol = test_helper::get_test_symbol(false, "PHB", "BTC", "PHB");
+        let (exchange, mut event_receiver) =
+            get_test_exchange_with_symbol_and_client(symbol.clone(), exchange_client);
+        let exchange_account_id = exchange.exchange_account_id;
+        let currency_pair = symbol.currency_pair();
+
+        exchange.load_trading_fees().await;
+        assert_eq!(exchange.maker_fee(currency_pair), dec!(0.0002));
+        assert_eq!(exchange.taker_fee(currency_pair), dec!(0.0004));
+
+        let fill_price = dec!(0.8);
+        let fill_amount = dec!(5);
+        let exchange_order_id = ExchangeOrderId::new("some_exchange_order_id".into());
+        let mut order = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(fill_price),
+            Some(OrderRole::Maker),
+            exchange_account_id,
+            currency_pair,
+            dec!(12),
+            OrderSide::Buy,
+            None,
+            "FromTest",
+        );
+        order.props.exchange_order_id = Some(exchange_order_id.clone());
+        let order_ref = exchange.orders.add_snapshot_initial(&order);
+        let _ = exchange
+            .orders
+            .cache_by_exchange_id
+            .insert(exchange_order_id.clone(), order_ref);
+
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(hashmap![exchange_account_id => exchange.clone()]),
+            None,
+        );
+        balance_manager
+            .lock()
+            .update_exchange_balance(
+                exchange_account_id,
+                &ExchangeBalancesAndPositions {
+                    balances: vec![
+                        ExchangeBalance {
+                            currency_code: "PHB".into(),
+                            balance: dec!(10),
+                        },
+                        ExchangeBalance {
+                            currency_code: "BTC".into(),
+                            balance: dec!(10),
+                        },
+                    ],
+                    positions: None,
+                },
+            )
+            .expect("in test");
+
+        exchange.handle_order_filled(&mut FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(trade_id_from_str("test_trade_id")),
+            client_order_id: None,
+            exchange_order_id,
+            fill_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount,
+                total_filled_amount: None,
+            },
+            order_role: Some(OrderRole::Maker),
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: None,
+        });
+
+        let configuration_descriptor =
+            ConfigurationDescriptor::new("Test".into(), "PHB/BTC".into());
+        while let Ok(event) = event_receiver.try_recv() {
+            if let ExchangeEvent::OrderEvent(event) = event {
+                if let OrderEventType::OrderFilled { cloned_order } = event.event_type {
+                    balance_manager
+                        .lock()
+                        .order_was_filled(configuration_descriptor, &cloned_order);
+                }
+            }
+        }
+
+        let phb_balance = balance_manager.lock().get_balance_by_currency_code(
+            configuration_descriptor,
+            exchange_account_id,
+            symbol,
+            "PHB".into(),
+            fill_price,
+        );
+        assert_eq!(
+            phb_balance,
+            Some(dec!(10) + fill_amount - fill_amount * dec!(0.0002))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn default_commission_is_used_if_trading_fees_are_not_loaded() {
+        let (exchange, _event_receiver) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+
+        exchange.load_trading_fees().await;
+
+        assert_eq!(exchange.maker_fee(currency_pair), dec!(0.001));
+        assert_eq!(exchange.taker_fee(currency_pair), dec!(0.002));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_commission_rate_by_trading_fees() {
+        let (exchange, _event_receiver) = get_test_exchange(false);
+
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        exchange.set_trading_fees(currency_pair, TradingFees::new(dec!(0.0002), dec!(0.0004)));
+
+        let fill_price = dec!(0.8);
+        let fill_amount = dec!(5);
+        let mut fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(trade_id_from_str("test_trade_id")),
+            client_order_id: None,
+            exchange_order_id: ExchangeOrderId::new("".into()),
+            fill_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount,
+                total_filled_amount: None,
+            },
+            order_role: None,
+            commission_currency_code: Some("BTC".into()),
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::Liquidation,
+            special_order_data: Some(SpecialOrderData {
+                currency_pair,
+                order_side: OrderSide::Buy,
+                order_amount: dec!(0),
+            }),
+            fill_date: None,
+        };
+
+        let order = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::liquidation(dec!(0.2)),
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            currency_pair,
+            dec!(12),
+            OrderSide::Sell,
+            None,
+            "FromTest",
+        );
+
+        let order_pool = OrdersPool::new();
+        let order_ref = order_pool.add_snapshot_initial(&order);
+
+        exchange.create_and_add_order_fill(&mut fill_event, &order_ref);
+        let (fills, _) = order_ref.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(
+            fills[0].commission_amount(),
+            dec!(0.0002) * fill_price * fill_amount
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn calculate_commission_amount() {
         let (exchange, _event_receiver) = get_test_exchange(false);
@@ -1,4 +1,5 @@
 pub mod currency_pair_to_symbol_converter;
+pub mod currency_pairs_policy;
 pub mod engine_api;
 pub mod exchange;
 pub mod exchange_creation;
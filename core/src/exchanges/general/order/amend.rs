@@ -0,0 +1,594 @@
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::order::cancel::CancelOrderResult;
+use crate::exchanges::general::request_type::RequestType;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::misc::time::time_manager;
+use anyhow::{anyhow, bail, Context, Result};
+use mmb_domain::events::EventSourceType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderHeader, OrderStatus, Price, ReservationId,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::{nothing_to_do, OPERATION_CANCELED_MSG};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+
+/// Max time of waiting cancellation event of the replaced exchange order after successful cancel-replace
+const REPLACED_ORDER_CANCEL_EVENT_TIMEOUT: Duration = Duration::from_secs(2);
+
+impl Exchange {
+    /// Change price and amount of active limit order.
+    /// Native cancel-replace request is used if exchange client supports it and the order keeps its `ClientOrderId`.
+    /// Otherwise the order is cancelled and a new one with new `ClientOrderId` and the same other properties is created.
+    /// The replacement is a new unfilled order for `new_amount`, fills of the replaced order stay in the replaced `OrderRef`.
+    /// If the order has balance reservation, the replacement gets a new reservation for amended price and amount
+    /// and the rest of the replaced order reservation is unreserved, so callers should unreserve only the reservation
+    /// of the returned order.
+    /// Returns the amended order which replaces the old one in orders pool
+    pub async fn amend_order(
+        &self,
+        client_order_id: &ClientOrderId,
+        new_price: Price,
+        new_amount: Amount,
+        cancellation_token: CancellationToken,
+    ) -> Result<OrderRef> {
+        let exchange_account_id = self.exchange_account_id;
+        let order = self
+            .orders
+            .cache_by_client_id
+            .get(client_order_id)
+            .map(|x| x.value().clone())
+            .with_context(|| {
+                format!(
+                    "Order {client_order_id} for amendment is not found on {exchange_account_id}"
+                )
+            })?;
+
+        if order.is_finished() {
+            bail!(
+                "Unable to amend finished order {client_order_id} on {exchange_account_id} with status {:?}",
+                order.status()
+            );
+        }
+
+        let Some(exchange_order_id) = order.exchange_order_id() else {
+            bail!("Unable to amend order {client_order_id} without exchange order id on {exchange_account_id}");
+        };
+
+        log::info!("Submitting amendment of order {client_order_id} {exchange_order_id} on {exchange_account_id} with price {new_price} and amount {new_amount}");
+
+        let mut header = order
+            .header()
+            .clone()
+            .with_price_and_amount(new_price, new_amount);
+        header.reservation_id = self.reserve_for_amended_order(&order, new_price, new_amount)?;
+        let reservation_id = header.reservation_id;
+
+        let amended_order = self
+            .amend_order_core(order, &exchange_order_id, header, cancellation_token)
+            .await;
+
+        if let (Err(_), Some(reservation_id)) = (&amended_order, reservation_id) {
+            self.unreserve_for_failed_amendment(reservation_id);
+        }
+
+        amended_order
+    }
+
+    async fn amend_order_core(
+        &self,
+        order: OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        header: OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<OrderRef> {
+        if !self.is_dry_run() {
+            if let Some(amended_order) = self
+                .native_amend_order(
+                    &order,
+                    exchange_order_id,
+                    header.clone(),
+                    cancellation_token.clone(),
+                )
+                .await
+            {
+                return amended_order;
+            }
+        }
+
+        self.cancel_and_create_order(order, header, cancellation_token)
+            .await
+    }
+
+    /// Returns `None` if exchange client doesn't support native amendment of the order
+    async fn native_amend_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        header: OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Option<Result<OrderRef>> {
+        let client_order_id = order.client_order_id();
+
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::CreateOrder,
+                None,
+                cancellation_token.clone(),
+            )
+            .await;
+
+        // Cancellation event of the replaced exchange order is consumed here,
+        // so the order with the same client order id isn't marked as canceled in orders pool
+        let (tx, mut replaced_order_cancel_receiver) = oneshot::channel();
+        let _ = self
+            .order_cancellation_events
+            .insert(exchange_order_id.clone(), (tx, None));
+        let _guard = scopeguard::guard(exchange_order_id.clone(), |exchange_order_id| {
+            let _ = self.order_cancellation_events.remove(&exchange_order_id);
+        });
+
+        let amend_result = tokio::select! {
+            amend_result = self.exchange_client.amend_order(order, exchange_order_id, header.price(), header.amount) => amend_result?,
+            _ = cancellation_token.when_cancelled() => return Some(Err(anyhow!(OPERATION_CANCELED_MSG))),
+        };
+
+        let new_exchange_order_id = match amend_result {
+            Ok(new_exchange_order_id) => new_exchange_order_id,
+            Err(error) => {
+                self.finish_cancel_after_failed_amendment(
+                    order,
+                    exchange_order_id,
+                    replaced_order_cancel_receiver,
+                )
+                .await;
+
+                return Some(Err(anyhow!(
+                    "Failed to amend order {client_order_id} {exchange_order_id} on {}: {error:?}",
+                    self.exchange_account_id
+                )));
+            }
+        };
+
+        let Some(amended_order) = self.orders.amend_in_place(
+            header,
+            exchange_order_id,
+            new_exchange_order_id.clone(),
+            time_manager::now(),
+        ) else {
+            return Some(Err(anyhow!(
+                "Amended order {client_order_id} was removed from orders pool"
+            )));
+        };
+
+        self.approve_order_reservation(&amended_order);
+        self.unreserve_replaced_order(order);
+        // Fills of the replacement could be received before response on amendment request
+        self.handle_buffered_order_events(
+            &client_order_id,
+            &new_exchange_order_id,
+            EventSourceType::Rest,
+        );
+
+        tokio::select! {
+            cancel_result = timeout(REPLACED_ORDER_CANCEL_EVENT_TIMEOUT, &mut replaced_order_cancel_receiver) => {
+                if let Ok(Ok(cancel_result)) = cancel_result {
+                    order.fn_mut(|order| {
+                        order.set_status(OrderStatus::Canceled, time_manager::now());
+                        order.internal_props.cancellation_event_source_type = Some(cancel_result.source_type);
+                    });
+                }
+            }
+            _ = cancellation_token.when_cancelled() => nothing_to_do(),
+        }
+
+        Some(Ok(amended_order))
+    }
+
+    /// Cancel-replace request can fail after the replaced order is cancelled, e.g. if the replacement
+    /// is maker only order which would take liquidity. Cancellation of the replaced order is finished
+    /// as usual in this case: by consumed cancellation event or by status requested from the exchange
+    async fn finish_cancel_after_failed_amendment(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        mut replaced_order_cancel_receiver: oneshot::Receiver<CancelOrderResult>,
+    ) {
+        // Cancellation event received after this point is handled by the common cancellation handler
+        let _ = self.order_cancellation_events.remove(exchange_order_id);
+
+        let source_type = match replaced_order_cancel_receiver.try_recv() {
+            Ok(cancel_result) => cancel_result.source_type,
+            Err(_) => match self.get_order_info(order).await {
+                Ok(order_info) if order_info.order_status == OrderStatus::Canceled => {
+                    EventSourceType::Rest
+                }
+                Ok(_) => return,
+                Err(error) => {
+                    log::warn!(
+                        "Failed to get status of order {} {exchange_order_id} after failed amendment on {}: {error:?}",
+                        order.client_order_id(),
+                        self.exchange_account_id
+                    );
+                    return;
+                }
+            },
+        };
+
+        self.handle_cancel_order_succeeded(
+            Some(&order.client_order_id()),
+            exchange_order_id,
+            None,
+            source_type,
+        );
+        self.unreserve_replaced_order(order);
+    }
+
+    async fn cancel_and_create_order(
+        &self,
+        order: OrderRef,
+        mut header: OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<OrderRef> {
+        let client_order_id = order.client_order_id();
+
+        self.wait_cancel_order(order.clone(), None, true, cancellation_token.clone())
+            .await
+            .with_context(|| format!("Failed to cancel order {client_order_id} for amendment"))?;
+
+        let status = order.status();
+        if status != OrderStatus::Canceled {
+            bail!("Order {client_order_id} wasn't amended because it became {status:?} before cancellation on {}", self.exchange_account_id);
+        }
+
+        self.unreserve_replaced_order(&order);
+
+        header.client_order_id = ClientOrderId::unique_id();
+        self.create_order(&header, None, cancellation_token)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to create order {} instead of canceled order {client_order_id} for amendment",
+                    header.client_order_id
+                )
+            })
+    }
+
+    fn upgrade_balance_manager(&self) -> Option<Arc<Mutex<BalanceManager>>> {
+        self.balance_manager
+            .lock()
+            .as_ref()
+            .and_then(|x| x.upgrade())
+    }
+
+    /// Reserve balance for the replacement because amended price and amount change required balance.
+    /// Orders created without balance reservation are amended without reservation too
+    fn reserve_for_amended_order(
+        &self,
+        order: &OrderRef,
+        new_price: Price,
+        new_amount: Amount,
+    ) -> Result<Option<ReservationId>> {
+        let client_order_id = order.client_order_id();
+        let Some(reservation_id) = order.header().reservation_id else {
+            log::warn!(
+                "Order {client_order_id} without reservation_id is amended without reservation"
+            );
+            return Ok(None);
+        };
+
+        let balance_manager = self.upgrade_balance_manager().context(
+            "BalanceManager should be initialized before amending order with reservation",
+        )?;
+        let mut balance_manager = balance_manager.lock();
+
+        let configuration_descriptor = balance_manager
+            .get_reservation(reservation_id)
+            .with_context(|| {
+                format!("Reservation {reservation_id} of order {client_order_id} for amendment is not found")
+            })?
+            .configuration_descriptor;
+        let symbol = self.get_symbol(order.currency_pair())?;
+
+        let reserve_parameters = ReserveParameters::new(
+            configuration_descriptor,
+            self.exchange_account_id,
+            symbol,
+            order.side(),
+            new_price,
+            new_amount,
+        );
+        let new_reservation_id = balance_manager
+            .try_reserve(&reserve_parameters, &mut None)
+            .with_context(|| {
+                format!("Unable to reserve balance to amend order {client_order_id} with price {new_price} and amount {new_amount}")
+            })?;
+
+        Ok(Some(new_reservation_id))
+    }
+
+    /// Unreserve the rest of balance reserved for the replaced order because the replacement has own reservation
+    fn unreserve_replaced_order(&self, order: &OrderRef) {
+        let Some(reservation_id) = order.header().reservation_id else {
+            return;
+        };
+        let Some(balance_manager) = self.upgrade_balance_manager() else {
+            return;
+        };
+        let mut balance_manager = balance_manager.lock();
+
+        let client_order_id = order.client_order_id();
+        let rest_amount = balance_manager
+            .get_reservation(reservation_id)
+            .and_then(|reservation| reservation.approved_parts.get(&client_order_id))
+            .filter(|approved_part| !approved_part.is_canceled)
+            .map(|approved_part| approved_part.unreserved_amount);
+
+        if let Some(rest_amount) = rest_amount {
+            if let Err(err) = balance_manager.unreserve_by_client_order_id(
+                reservation_id,
+                client_order_id.clone(),
+                rest_amount,
+            ) {
+                log::error!("Failed to unreserve {rest_amount} of replaced order {client_order_id} from {reservation_id}: {err:?}");
+            }
+        }
+    }
+
+    fn unreserve_for_failed_amendment(&self, reservation_id: ReservationId) {
+        let Some(balance_manager) = self.upgrade_balance_manager() else {
+            return;
+        };
+        let unreserve_result = balance_manager.lock().unreserve_rest(reservation_id);
+        if let Err(err) = unreserve_result {
+            log::error!("Failed to unreserve {reservation_id} after failed amendment: {err:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+    use crate::exchanges::general::test_helper::{
+        get_test_exchange_with_symbol_and_client, TestClient,
+    };
+    use crate::infrastructure::init_lifetime_manager;
+    use crate::misc::time;
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+    use chrono::Utc;
+    use mmb_domain::events::{ExchangeBalance, ExchangeBalancesAndPositions, ExchangeEvent};
+    use mmb_domain::exchanges::symbol::{Precision, Symbol};
+    use mmb_domain::order::snapshot::{OrderSide, UserOrder};
+    use mmb_utils::hashmap;
+    use rust_decimal_macros::dec;
+    use tokio::sync::broadcast;
+
+    const ORDER_AMOUNT: Amount = dec!(5);
+    const FILLED_AMOUNT: Amount = dec!(2);
+
+    struct TestContext {
+        exchange: Arc<Exchange>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        order: OrderRef,
+        reservation_id: ReservationId,
+        _events_receiver: broadcast::Receiver<ExchangeEvent>,
+    }
+
+    /// Created partially filled buy order with balance reservation
+    fn init_partially_filled_order(exchange_client: TestClient) -> TestContext {
+        init_lifetime_manager();
+        let symbol = Arc::new(Symbol::new(
+            false,
+            "PHB".into(),
+            "PHB".into(),
+            "BTC".into(),
+            "BTC".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "PHB".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ));
+        let (exchange, events_receiver) =
+            get_test_exchange_with_symbol_and_client(symbol.clone(), exchange_client);
+        let exchange_account_id = exchange.exchange_account_id;
+        let currency_pair = symbol.currency_pair();
+
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(hashmap![exchange_account_id => exchange.clone()]),
+            None,
+        );
+        balance_manager
+            .lock()
+            .update_exchange_balance(
+                exchange_account_id,
+                &ExchangeBalancesAndPositions {
+                    balances: vec![
+                        ExchangeBalance {
+                            currency_code: "PHB".into(),
+                            balance: dec!(10),
+                        },
+                        ExchangeBalance {
+                            currency_code: "BTC".into(),
+                            balance: dec!(10),
+                        },
+                    ],
+                    positions: None,
+                },
+            )
+            .expect("in test");
+        exchange.setup_balance_manager(balance_manager.clone());
+
+        let price = dec!(0.8);
+        let reservation_id = balance_manager
+            .lock()
+            .try_reserve(
+                &ReserveParameters::new(
+                    ConfigurationDescriptor::new("Test".into(), "PHB/BTC".into()),
+                    exchange_account_id,
+                    symbol,
+                    OrderSide::Buy,
+                    price,
+                    ORDER_AMOUNT,
+                ),
+                &mut None,
+            )
+            .expect("in test");
+
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            exchange_account_id,
+            currency_pair,
+            OrderSide::Buy,
+            ORDER_AMOUNT,
+            UserOrder::maker_only(price),
+            Some(reservation_id),
+            None,
+            "FromTest".to_owned(),
+        );
+        let order = exchange
+            .orders
+            .add_simple_initial(&header, Utc::now(), None);
+        let exchange_order_id: ExchangeOrderId = "initial".into();
+        order.fn_mut(|x| {
+            x.props.exchange_order_id = Some(exchange_order_id.clone());
+            x.set_status(OrderStatus::Created, Utc::now());
+            x.fills.filled_amount = FILLED_AMOUNT;
+        });
+        let _ = exchange
+            .orders
+            .cache_by_exchange_id
+            .insert(exchange_order_id, order.clone());
+        balance_manager.lock().approve_reservation(
+            reservation_id,
+            &order.client_order_id(),
+            ORDER_AMOUNT,
+        );
+
+        TestContext {
+            exchange,
+            balance_manager,
+            order,
+            reservation_id,
+            _events_receiver: events_receiver,
+        }
+    }
+
+    fn assert_amended_order(ctx: &TestContext, amended_order: &OrderRef, new_price: Price) {
+        assert_eq!(amended_order.price(), new_price);
+        assert_eq!(amended_order.amount(), ORDER_AMOUNT);
+        assert_eq!(amended_order.filled_amount(), dec!(0));
+        assert_eq!(amended_order.status(), OrderStatus::Created);
+
+        assert_eq!(ctx.order.status(), OrderStatus::Canceled);
+        assert_eq!(ctx.order.filled_amount(), FILLED_AMOUNT);
+        assert_eq!(ctx.order.exchange_order_id(), Some("initial".into()));
+
+        let balance_manager = ctx.balance_manager.lock();
+        assert!(balance_manager
+            .get_reservation(ctx.reservation_id)
+            .is_none());
+
+        let new_reservation_id = amended_order.header().reservation_id.expect("in test");
+        let new_reservation = balance_manager
+            .get_reservation(new_reservation_id)
+            .expect("in test");
+        assert_eq!(new_reservation.price, new_price);
+        assert_eq!(new_reservation.amount, ORDER_AMOUNT);
+        let approved_amount = new_reservation
+            .approved_parts
+            .get(&amended_order.client_order_id())
+            .map(|x| x.amount);
+        assert_eq!(approved_amount, Some(ORDER_AMOUNT));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn amend_partially_filled_order_by_native_cancel_replace() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let mut exchange_client = TestClient::default();
+        exchange_client.amended_exchange_order_id = Some("amended".into());
+        let ctx = init_partially_filled_order(exchange_client);
+
+        let new_price = dec!(0.7);
+        let amended_order = ctx
+            .exchange
+            .amend_order(
+                &ctx.order.client_order_id(),
+                new_price,
+                ORDER_AMOUNT,
+                CancellationToken::default(),
+            )
+            .await
+            .expect("in test");
+
+        assert_amended_order(&ctx, &amended_order, new_price);
+        assert_eq!(amended_order.client_order_id(), ctx.order.client_order_id());
+        assert_eq!(amended_order.exchange_order_id(), Some("amended".into()));
+        let by_initial_exchange_order_id = ctx
+            .exchange
+            .orders
+            .cache_by_exchange_id
+            .get(&"initial".into())
+            .map(|x| x.value().clone());
+        assert_eq!(by_initial_exchange_order_id, Some(ctx.order.clone()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn amend_partially_filled_order_by_cancel_and_create() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let mut exchange_client = TestClient::default();
+        exchange_client.created_exchange_order_id = Some("created".into());
+        let ctx = init_partially_filled_order(exchange_client);
+
+        let new_price = dec!(0.7);
+        let amended_order = ctx
+            .exchange
+            .amend_order(
+                &ctx.order.client_order_id(),
+                new_price,
+                ORDER_AMOUNT,
+                CancellationToken::default(),
+            )
+            .await
+            .expect("in test");
+
+        assert_amended_order(&ctx, &amended_order, new_price);
+        assert_ne!(amended_order.client_order_id(), ctx.order.client_order_id());
+        assert_eq!(amended_order.exchange_order_id(), Some("created".into()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn order_cancelled_by_failed_native_cancel_replace() {
+        let (_time_manager_mock, _mock_locker) = time::tests::init_mock(Default::default());
+        let mut exchange_client = TestClient::default();
+        exchange_client.amended_order_rejected = true;
+        let ctx = init_partially_filled_order(exchange_client);
+
+        let amend_result = ctx
+            .exchange
+            .amend_order(
+                &ctx.order.client_order_id(),
+                dec!(0.9),
+                ORDER_AMOUNT,
+                CancellationToken::default(),
+            )
+            .await;
+
+        assert!(amend_result.is_err());
+        assert_eq!(ctx.order.status(), OrderStatus::Canceled);
+        assert_eq!(ctx.order.filled_amount(), FILLED_AMOUNT);
+        assert_eq!(ctx.balance_manager.lock().reservation_count(), 0);
+        assert!(ctx.exchange.order_cancellation_events.is_empty());
+    }
+}
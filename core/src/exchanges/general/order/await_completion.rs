@@ -0,0 +1,165 @@
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::timeout;
+
+use crate::exchanges::general::exchange::Exchange;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderSnapshot};
+use mmb_utils::nothing_to_do;
+
+impl Exchange {
+    /// Wait until order reaches terminal status (`Completed`, `Canceled` or `FailedToCreate`)
+    /// and return its snapshot. Intended for strategies written in imperative style.
+    /// Subscription on order events is made before checking current order state,
+    /// so events raised between order placement and this call can't be missed.
+    pub async fn await_order_completion(
+        &self,
+        client_order_id: &ClientOrderId,
+        timeout_duration: Duration,
+    ) -> Result<OrderSnapshot> {
+        let mut events_rx = self.events_channel.subscribe();
+
+        let order = match self.orders.cache_by_client_id.get(client_order_id) {
+            Some(order) => order.clone(),
+            None => bail!(
+                "Order {client_order_id} not found on exchange {}",
+                self.exchange_account_id
+            ),
+        };
+
+        if order.is_finished() {
+            return Ok(order.deep_clone());
+        }
+
+        let wait_completion = async {
+            loop {
+                match events_rx.recv().await {
+                    Ok(ExchangeEvent::OrderEvent(event)) => {
+                        if event.order.client_order_id() == *client_order_id
+                            && event.order.is_finished()
+                        {
+                            return Ok(event.order.deep_clone());
+                        }
+                    }
+                    Ok(_) => nothing_to_do(),
+                    // some events were skipped, so we should check order state directly
+                    Err(RecvError::Lagged(_)) => {
+                        if order.is_finished() {
+                            return Ok(order.deep_clone());
+                        }
+                    }
+                    Err(RecvError::Closed) => bail!(
+                        "Events channel closed while waiting completion of order {client_order_id}"
+                    ),
+                }
+            }
+        };
+
+        timeout(timeout_duration, wait_completion)
+            .await
+            .with_context(|| {
+                format!(
+                    "Timeout {timeout_duration:?} waiting completion of order {client_order_id}"
+                )
+            })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper;
+    use chrono::Utc;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::event::OrderEventType;
+    use mmb_domain::order::pool::OrderRef;
+    use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderStatus};
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn add_order(exchange: &Exchange, client_order_id: &ClientOrderId) -> OrderRef {
+        let order_ref = test_helper::create_order_ref(
+            client_order_id,
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            dec!(0.8),
+            dec!(12),
+            OrderSide::Buy,
+        );
+
+        exchange
+            .orders
+            .add_snapshot_initial(&order_ref.deep_clone())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn return_already_finished_order() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let client_order_id = ClientOrderId::unique_id();
+        let order_ref = add_order(&exchange, &client_order_id);
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Canceled, Utc::now()));
+
+        let snapshot = exchange
+            .await_order_completion(&client_order_id, Duration::from_millis(100))
+            .await
+            .expect("in test");
+
+        assert_eq!(snapshot.status(), OrderStatus::Canceled);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn wait_until_order_completed() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let client_order_id = ClientOrderId::unique_id();
+        let order_ref = add_order(&exchange, &client_order_id);
+
+        let completion = tokio::spawn({
+            let exchange = exchange.clone();
+            let client_order_id = client_order_id.clone();
+            async move {
+                exchange
+                    .await_order_completion(&client_order_id, Duration::from_secs(5))
+                    .await
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Completed, Utc::now()));
+        let cloned_order = Arc::new(order_ref.deep_clone());
+        exchange
+            .add_event_on_order_change(&order_ref, OrderEventType::OrderCompleted { cloned_order })
+            .expect("in test");
+
+        let snapshot = completion.await.expect("in test").expect("in test");
+
+        assert_eq!(snapshot.header.client_order_id, client_order_id);
+        assert_eq!(snapshot.status(), OrderStatus::Completed);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn timeout_if_order_not_finished() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let client_order_id = ClientOrderId::unique_id();
+        let _ = add_order(&exchange, &client_order_id);
+
+        let error = exchange
+            .await_order_completion(&client_order_id, Duration::from_millis(50))
+            .await
+            .expect_err("in test");
+
+        assert!(error.to_string().starts_with("Timeout"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn error_for_unknown_order() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+
+        let result = exchange
+            .await_order_completion(&ClientOrderId::unique_id(), Duration::from_millis(50))
+            .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -133,6 +133,16 @@ impl Exchange {
         exchange_order_id: &ExchangeOrderId,
         cancellation_token: CancellationToken,
     ) -> Option<CancelOrderResult> {
+        if self.is_dry_run() {
+            let client_order_id = order.client_order_id();
+            log::info!("Dry run: order {client_order_id} is cancelled without request to exchange");
+            return Some(CancelOrderResult::succeed(
+                client_order_id,
+                EventSourceType::Rest,
+                None,
+            ));
+        }
+
         let (tx, mut websocket_event_receiver) = oneshot::channel();
 
         // TODO insert is not analog of C# GetOrAd!
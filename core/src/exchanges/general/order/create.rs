@@ -57,6 +57,30 @@ impl Exchange {
 
         log::info!("Submitting order {order_header:?}");
 
+        let currency_pair = order_header.currency_pair;
+        if !self.currency_pairs_policy.lock().is_allowed(currency_pair) {
+            let client_order_id = &order_header.client_order_id;
+            let exchange_account_id = self.exchange_account_id;
+            log::warn!("Order {client_order_id} rejected because currency pair {currency_pair} is not allowed for trading on {exchange_account_id}");
+            bail!("Currency pair {currency_pair} is not allowed for trading on {exchange_account_id}, order {client_order_id} rejected");
+        }
+
+        if let Some(price) = order_header.source_price() {
+            if let Err(reason) = self.check_price_band(currency_pair, price) {
+                let client_order_id = &order_header.client_order_id;
+                log::warn!("Order {client_order_id} rejected: {reason}");
+                bail!("Order {client_order_id} rejected: {reason}");
+            }
+        }
+
+        if let Err(reason) =
+            self.check_net_position(currency_pair, order_header.side, order_header.amount, None)
+        {
+            let client_order_id = &order_header.client_order_id;
+            log::warn!("Order {client_order_id} rejected: {reason}");
+            bail!("Order {client_order_id} rejected: {reason}");
+        }
+
         let order = self.orders.add_simple_initial(
             order_header,
             time_manager::now(),
@@ -498,6 +522,8 @@ impl Exchange {
                     )?;
                 }
                 Error(exchange_error) => {
+                    self.pause_on_maintenance(exchange_error);
+
                     if exchange_error.error_type != ExchangeErrorType::ParsingError {
                         self.handle_create_order_failed(
                             &client_order_id,
@@ -594,7 +620,13 @@ impl Exchange {
                     .save(&mut order.deep_clone())
                     .expect("Failure save order");
 
-                log::error!("Order creation failed {args_to_log:?}: {exchange_error:?}");
+                match exchange_error.error_type {
+                    // Maintenance is expected and trading is paused, so it isn't an error
+                    ExchangeErrorType::Maintenance => {
+                        log::warn!("Order creation failed {args_to_log:?}: {exchange_error:?}")
+                    }
+                    _ => log::error!("Order creation failed {args_to_log:?}: {exchange_error:?}"),
+                }
 
                 Ok(())
             }
@@ -699,58 +731,12 @@ impl Exchange {
                     .cache_by_exchange_id
                     .insert(exchange_order_id.clone(), order.clone());
 
-                let header = order.header();
-                let client_order_id = header.client_order_id.clone();
-                if order.order_type() != OrderType::Liquidation {
-                    match header.reservation_id {
-                        None => {
-                            log::warn!("Created order {client_order_id} without reservation_id")
-                        }
-                        Some(reservation_id) => {
-                            let bm_lock = self.balance_manager.lock();
-                            match bm_lock.as_ref().expect("BalanceManager should be initialized before receiving order events").upgrade() {
-                                None => log::warn!("BalanceManager ref can't be upgraded in handler create order succeeded event"),
-                                Some(balance_manager) => balance_manager.lock().approve_reservation(
-                                    reservation_id,
-                                    &client_order_id,
-                                    header.amount,
-                                )
-                            }
-                        }
-                    };
-                }
+                let client_order_id = order.client_order_id();
+                self.approve_order_reservation(order);
 
                 self.add_event_on_order_change(order, OrderEventType::CreateOrderSucceeded)?;
 
-                let mut buffered_fills_manager = self.buffered_fills_manager.lock();
-                if let Some(buffered_fills) = buffered_fills_manager.get_fills(exchange_order_id) {
-                    log::trace!(
-                        "Found buffered fills for an order {client_order_id} {exchange_order_id} on {}:\n{buffered_fills:?}",
-                        self.exchange_account_id,
-                    );
-
-                    for buffered_fill in buffered_fills {
-                        let mut fill_event =
-                            buffered_fill.to_fill_event_data(client_order_id.clone());
-                        self.handle_order_filled(&mut fill_event);
-                    }
-
-                    buffered_fills_manager.remove_fills(exchange_order_id);
-                }
-                drop(buffered_fills_manager);
-
-                let mut buffered_canceled_orders_manager =
-                    self.buffered_canceled_orders_manager.lock();
-                if buffered_canceled_orders_manager.is_order_buffered(exchange_order_id) {
-                    self.handle_cancel_order_succeeded(
-                        Some(&client_order_id),
-                        exchange_order_id,
-                        None,
-                        source_type,
-                    );
-                    buffered_canceled_orders_manager.remove_order(exchange_order_id);
-                }
-                drop(buffered_canceled_orders_manager);
+                self.handle_buffered_order_events(&client_order_id, exchange_order_id, source_type);
 
                 self.event_recorder
                     .save(&mut order.deep_clone())
@@ -763,6 +749,64 @@ impl Exchange {
         }
     }
 
+    pub(super) fn approve_order_reservation(&self, order: &OrderRef) {
+        if order.order_type() == OrderType::Liquidation {
+            return;
+        }
+
+        let header = order.header();
+        let client_order_id = &header.client_order_id;
+        match header.reservation_id {
+            None => log::warn!("Created order {client_order_id} without reservation_id"),
+            Some(reservation_id) => {
+                let bm_lock = self.balance_manager.lock();
+                match bm_lock.as_ref().expect("BalanceManager should be initialized before receiving order events").upgrade() {
+                    None => log::warn!("BalanceManager ref can't be upgraded in handler create order succeeded event"),
+                    Some(balance_manager) => balance_manager.lock().approve_reservation(
+                        reservation_id,
+                        client_order_id,
+                        header.amount,
+                    )
+                }
+            }
+        };
+    }
+
+    /// Handle fills and cancellation of the order which were received before the order creation
+    pub(super) fn handle_buffered_order_events(
+        &self,
+        client_order_id: &ClientOrderId,
+        exchange_order_id: &ExchangeOrderId,
+        source_type: EventSourceType,
+    ) {
+        let mut buffered_fills_manager = self.buffered_fills_manager.lock();
+        if let Some(buffered_fills) = buffered_fills_manager.get_fills(exchange_order_id) {
+            log::trace!(
+                "Found buffered fills for an order {client_order_id} {exchange_order_id} on {}:\n{buffered_fills:?}",
+                self.exchange_account_id,
+            );
+
+            for buffered_fill in buffered_fills {
+                let mut fill_event = buffered_fill.to_fill_event_data(client_order_id.clone());
+                self.handle_order_filled(&mut fill_event);
+            }
+
+            buffered_fills_manager.remove_fills(exchange_order_id);
+        }
+        drop(buffered_fills_manager);
+
+        let mut buffered_canceled_orders_manager = self.buffered_canceled_orders_manager.lock();
+        if buffered_canceled_orders_manager.is_order_buffered(exchange_order_id) {
+            self.handle_cancel_order_succeeded(
+                Some(client_order_id),
+                exchange_order_id,
+                None,
+                source_type,
+            );
+            buffered_canceled_orders_manager.remove_order(exchange_order_id);
+        }
+    }
+
     pub(super) async fn create_order_created_fut(
         &self,
         order: &OrderRef,
@@ -805,3 +849,240 @@ impl Exchange {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::manager::balance_manager::BalanceManager;
+    use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+    use crate::exchanges::general::currency_pairs_policy::CurrencyPairsPolicy;
+    use crate::exchanges::general::test_helper;
+    use crate::infrastructure::init_lifetime_manager;
+    use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+    use crate::settings::MarketMaxPosition;
+    use mmb_domain::market::{CurrencyPair, MarketId};
+    use mmb_domain::order::snapshot::{Amount, OrderRole, OrderSide, Price};
+    use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+    use mmb_utils::hashmap;
+    use rstest::rstest;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::collections::{BTreeMap, HashSet};
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn reject_order_for_not_allowed_currency_pair() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        exchange.setup_currency_pairs_policy(CurrencyPairsPolicy::new(
+            None,
+            HashSet::from([currency_pair]),
+        ));
+
+        let client_order_id = ClientOrderId::unique_id();
+        let order_ref = test_helper::create_order_ref(
+            &client_order_id,
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            currency_pair,
+            dec!(0.8),
+            dec!(12),
+            OrderSide::Buy,
+        );
+
+        let error = exchange
+            .create_order(order_ref.header(), None, CancellationToken::default())
+            .await
+            .expect_err("in test");
+
+        assert!(error.to_string().contains("is not allowed for trading"));
+        assert!(exchange
+            .orders
+            .cache_by_client_id
+            .get(&client_order_id)
+            .is_none());
+    }
+
+    fn set_order_book(exchange: &Exchange, currency_pair: CurrencyPair, ask: Price, bid: Price) {
+        let market_id = MarketId::new(exchange.exchange_account_id.exchange_id, currency_pair);
+        let snapshot = LocalOrderBookSnapshot::new(
+            BTreeMap::from([(ask, dec!(1))]),
+            BTreeMap::from([(bid, dec!(1))]),
+            Utc::now(),
+        );
+        *exchange.local_snapshots_service.lock() =
+            LocalSnapshotsService::new(hashmap![market_id => snapshot]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn reject_order_with_price_out_of_band() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        exchange.setup_price_band_percent(Some(dec!(5)));
+        set_order_book(&exchange, currency_pair, dec!(1.01), dec!(0.99));
+
+        let client_order_id = ClientOrderId::unique_id();
+        let order_ref = test_helper::create_order_ref(
+            &client_order_id,
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            currency_pair,
+            dec!(0.8),
+            dec!(12),
+            OrderSide::Buy,
+        );
+
+        let error = exchange
+            .create_order(order_ref.header(), None, CancellationToken::default())
+            .await
+            .expect_err("in test");
+
+        assert!(error.to_string().contains("exceeds price band"));
+        assert!(exchange
+            .orders
+            .cache_by_client_id
+            .get(&client_order_id)
+            .is_none());
+    }
+
+    #[rstest]
+    #[case::within_band(Some(dec!(5)), true, dec!(0.96), true)]
+    #[case::out_of_band(Some(dec!(5)), true, dec!(1.06), false)]
+    #[case::band_not_set(None, true, dec!(1.06), true)]
+    #[case::order_book_not_received(Some(dec!(5)), false, dec!(0.96), false)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn check_price_band(
+        #[case] price_band_percent: Option<Decimal>,
+        #[case] has_order_book: bool,
+        #[case] price: Price,
+        #[case] expected: bool,
+    ) {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        exchange.setup_price_band_percent(price_band_percent);
+        if has_order_book {
+            set_order_book(&exchange, currency_pair, dec!(1.01), dec!(0.99));
+        }
+
+        assert_eq!(
+            exchange.check_price_band(currency_pair, price).is_ok(),
+            expected
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn reject_order_exceeding_max_position() {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(
+                hashmap![exchange.exchange_account_id => exchange.clone()],
+            ),
+            None,
+        );
+        exchange.setup_balance_manager(balance_manager.clone());
+        exchange.setup_max_positions(&[MarketMaxPosition {
+            currency_pair,
+            max_position: dec!(10),
+        }]);
+
+        let client_order_id = ClientOrderId::unique_id();
+        let order_ref = test_helper::create_order_ref(
+            &client_order_id,
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            currency_pair,
+            dec!(0.8),
+            dec!(12),
+            OrderSide::Buy,
+        );
+
+        let error = exchange
+            .create_order(order_ref.header(), None, CancellationToken::default())
+            .await
+            .expect_err("in test");
+
+        assert!(error.to_string().contains("would exceed max position"));
+        assert!(exchange
+            .orders
+            .cache_by_client_id
+            .get(&client_order_id)
+            .is_none());
+    }
+
+    #[rstest]
+    #[case::within_cap(Some(dec!(15)), None, dec!(12), true)]
+    #[case::exceeds_cap_from_settings(Some(dec!(10)), None, dec!(12), false)]
+    #[case::exceeds_extra_cap(Some(dec!(15)), Some(dec!(10)), dec!(12), false)]
+    #[case::caps_not_set(None, None, dec!(12), true)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn check_net_position(
+        #[case] max_position: Option<Amount>,
+        #[case] extra_max_position: Option<Amount>,
+        #[case] amount: Amount,
+        #[case] expected: bool,
+    ) {
+        let (exchange, _rx) = test_helper::get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(
+                hashmap![exchange.exchange_account_id => exchange.clone()],
+            ),
+            None,
+        );
+        exchange.setup_balance_manager(balance_manager.clone());
+        if let Some(max_position) = max_position {
+            exchange.setup_max_positions(&[MarketMaxPosition {
+                currency_pair,
+                max_position,
+            }]);
+        }
+
+        assert_eq!(
+            exchange
+                .check_net_position(currency_pair, OrderSide::Buy, amount, extra_max_position)
+                .is_ok(),
+            expected
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn dry_run_orders_are_not_sent_to_exchange() {
+        init_lifetime_manager();
+        let exchange_client = test_helper::TestClient::default();
+        let requests_count = exchange_client.requests_count.clone();
+        let (exchange, _rx) = test_helper::get_test_exchange_with_symbol_and_client(
+            test_helper::get_test_symbol(false, "PHB", "BTC", "PHB"),
+            exchange_client,
+        );
+        exchange.set_dry_run(true);
+
+        const TICKS_COUNT: usize = 5;
+        for _ in 0..TICKS_COUNT {
+            let order_ref = test_helper::create_order_ref(
+                &ClientOrderId::unique_id(),
+                Some(OrderRole::Maker),
+                exchange.exchange_account_id,
+                CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+                dec!(0.8),
+                dec!(12),
+                OrderSide::Buy,
+            );
+
+            let order = exchange
+                .create_order(order_ref.header(), None, CancellationToken::default())
+                .await
+                .expect("in test");
+            assert_eq!(order.status(), OrderStatus::Created);
+
+            let cancel_result = exchange
+                .cancel_order(&order, CancellationToken::default())
+                .await
+                .expect("in test");
+            assert!(matches!(cancel_result.outcome, Success(_)));
+            assert_eq!(order.status(), OrderStatus::Canceled);
+        }
+
+        assert_eq!(requests_count.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::request_type::RequestType;
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::OrderHeader;
+use mmb_utils::cancellation_token::CancellationToken;
+use tokio::sync::Semaphore;
+
+impl Exchange {
+    /// Create orders keeping at most `max_in_flight` creation requests running at the same time.
+    /// Each creation waits for the requests timeout manager before sending, so rate limits are respected.
+    /// Results are returned in the same order as `order_headers`
+    pub async fn create_orders_batch(
+        &self,
+        order_headers: &[OrderHeader],
+        max_in_flight: usize,
+        cancellation_token: CancellationToken,
+    ) -> Vec<Result<OrderRef>> {
+        let semaphore = Semaphore::new(max_in_flight.max(1));
+
+        let create_order_futures = order_headers.iter().map(|order_header| {
+            let semaphore = &semaphore;
+            let cancellation_token = cancellation_token.clone();
+            async move {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .context("Semaphore of create_orders_batch was closed")?;
+
+                self.timeout_manager
+                    .reserve_when_available(
+                        self.exchange_account_id,
+                        RequestType::CreateOrder,
+                        None,
+                        cancellation_token.clone(),
+                    )
+                    .await
+                    .into_result()
+                    .with_context(|| {
+                        format!(
+                            "Failed to reserve request for creating order {}",
+                            order_header.client_order_id
+                        )
+                    })?;
+
+                self.create_order(order_header, None, cancellation_token)
+                    .await
+            }
+        });
+
+        join_all(create_order_futures).await
+    }
+}
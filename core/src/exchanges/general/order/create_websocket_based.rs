@@ -16,6 +16,16 @@ impl Exchange {
         cancellation_token: CancellationToken,
     ) -> Option<CreateOrderResult> {
         let client_order_id = order.client_order_id();
+        if self.is_dry_run() {
+            log::info!("Dry run: order {client_order_id} is created without request to exchange");
+            let exchange_order_id =
+                ExchangeOrderId::new(format!("dry_run_{client_order_id}").into());
+            return Some(CreateOrderResult::succeed(
+                &exchange_order_id,
+                EventSourceType::Rest,
+            ));
+        }
+
         let (tx, mut websocket_event_receiver) = oneshot::channel();
 
         // TODO insert is not analog of C# GetOrAd!
@@ -5,8 +5,10 @@ use anyhow::bail;
 use itertools::Itertools;
 use mmb_domain::order::snapshot::{
     ClientOrderId, OrderHeader, OrderInfo, OrderOptions, OrderSimpleProps, OrderSnapshot,
+    ReservationId,
 };
 use mmb_utils::cancellation_token::CancellationToken;
+use std::collections::HashMap;
 use tokio::time::Duration;
 
 impl Exchange {
@@ -82,13 +84,19 @@ impl Exchange {
         };
 
         if check_missing_orders {
-            self.add_missing_open_orders(&open_orders);
+            self.add_missing_open_orders(&open_orders, &HashMap::new());
         }
 
         Ok(open_orders)
     }
 
-    fn add_missing_open_orders(&self, open_orders: &[OrderInfo]) {
+    /// Add open orders which are unknown in orders pool.
+    /// Added orders refer to balance reservations from `reservation_ids` if they were restored for them
+    pub(crate) fn add_missing_open_orders(
+        &self,
+        open_orders: &[OrderInfo],
+        reservation_ids: &HashMap<ClientOrderId, ReservationId>,
+    ) {
         for order_info in open_orders {
             if order_info.client_order_id.as_str().is_empty()
                 && self
@@ -122,7 +130,7 @@ impl Exchange {
                 order_info.order_side,
                 order_info.amount,
                 OrderOptions::unknown(Some(order_info.price)),
-                None,
+                reservation_ids.get(&order_info.client_order_id).copied(),
                 None,
                 "MissedOpenOrder".to_string(),
             );
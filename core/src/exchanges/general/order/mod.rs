@@ -1,5 +1,8 @@
+pub mod amend;
+pub mod await_completion;
 pub mod cancel;
 pub mod create;
+pub mod create_batch;
 pub mod create_websocket_based;
 pub mod get_info;
 pub mod get_open_orders;
@@ -4,15 +4,19 @@ use mmb_utils::DateTime;
 use tokio::time::timeout;
 
 use crate::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
-use mmb_utils::time::ToStdExpected;
+use mmb_utils::time::{jittered_interval, ToStdExpected};
 
 pub(crate) struct PollingTimeoutManager {
     timeout_arguments: RequestTimeoutArguments,
+    jitter_fraction: f64,
 }
 
 impl PollingTimeoutManager {
-    pub(crate) fn new(timeout_arguments: RequestTimeoutArguments) -> Self {
-        Self { timeout_arguments }
+    pub(crate) fn new(timeout_arguments: RequestTimeoutArguments, jitter_fraction: f64) -> Self {
+        Self {
+            timeout_arguments,
+            jitter_fraction,
+        }
     }
 
     pub(crate) async fn wait(
@@ -30,7 +34,10 @@ impl PollingTimeoutManager {
         let requests_per_period = self.timeout_arguments.requests_per_period;
 
         let divisor = requests_per_period as f64 * request_range * 0.01;
-        let interval = Duration::milliseconds((period.num_milliseconds() as f64 / divisor) as i64);
+        let interval =
+            std::time::Duration::from_millis((period.num_milliseconds() as f64 / divisor) as u64);
+        let interval = Duration::from_std(jittered_interval(interval, self.jitter_fraction))
+            .unwrap_or(Duration::max_value());
 
         let time_since_last_request = Utc::now() - last_request_time;
         let delay_till_fallback_request = interval - time_since_last_request;
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
@@ -22,20 +23,25 @@ use crate::{
     },
     settings::ExchangeSettings,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Duration;
 use dashmap::DashMap;
 use futures::executor::block_on;
-use mmb_domain::events::{AllowedEventSourceType, ExchangeBalancesAndPositions, ExchangeEvent};
-use mmb_domain::exchanges::commission::{Commission, CommissionForType};
+use mmb_domain::candle::{Candle, KlineInterval};
+use mmb_domain::events::{
+    AllowedEventSourceType, EventSourceType, ExchangeBalancesAndPositions, ExchangeEvent,
+};
+use mmb_domain::exchanges::commission::{Commission, CommissionForType, TradingFees};
 use mmb_domain::exchanges::symbol::{BeforeAfter, Precision, Symbol};
 use mmb_domain::market::{
-    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, SpecificCurrencyPair,
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, ExchangeErrorType,
+    SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, OrderOptions, Price};
 use mmb_domain::order::snapshot::{ClientOrderId, OrderInfo, OrderRole, OrderSide, OrderSnapshot};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use rust_decimal_macros::dec;
 use tokio::sync::broadcast;
@@ -54,27 +60,103 @@ use mmb_utils::{cancellation_token::CancellationToken, hashmap, DateTime};
 
 use super::order::get_order_trades::OrderTrade;
 
-pub struct TestClient;
+#[derive(Default)]
+pub struct TestClient {
+    settings: ExchangeSettings,
+    /// Exchange order id of created order. Order creation isn't expected if `None`
+    pub(crate) created_exchange_order_id: Option<ExchangeOrderId>,
+    /// Exchange order id of order replacement. Native amendment isn't supported if `None`
+    pub(crate) amended_exchange_order_id: Option<ExchangeOrderId>,
+    /// Native amendment cancels the order but fails to create the replacement
+    pub(crate) amended_order_rejected: bool,
+    order_created_callback: Option<OrderCreatedCb>,
+    order_cancelled_callback: Option<OrderCancelledCb>,
+    /// Trading fees of every market. Request of trading fees fails if `None`
+    pub(crate) trading_fees: Option<TradingFees>,
+    /// Count of requests to exchange made through the client
+    pub(crate) requests_count: Arc<AtomicUsize>,
+}
+
+impl TestClient {
+    fn register_request(&self) {
+        let _ = self.requests_count.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn raise_order_created(&self, order: &OrderRef, exchange_order_id: &ExchangeOrderId) {
+        let callback = self
+            .order_created_callback
+            .as_ref()
+            .expect("order created callback should be set");
+        callback(
+            order.client_order_id(),
+            exchange_order_id.clone(),
+            EventSourceType::WebSocket,
+        );
+    }
+
+    fn raise_order_cancelled(&self, order: &OrderRef, exchange_order_id: &ExchangeOrderId) {
+        let callback = self
+            .order_cancelled_callback
+            .as_ref()
+            .expect("order cancelled callback should be set");
+        callback(
+            order.client_order_id(),
+            exchange_order_id.clone(),
+            EventSourceType::WebSocket,
+        );
+    }
+}
 
 #[async_trait]
 impl ExchangeClient for TestClient {
-    async fn create_order(&self, _order: &OrderRef) -> CreateOrderResult {
-        unimplemented!("doesn't need in UT")
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        self.register_request();
+        let exchange_order_id = self
+            .created_exchange_order_id
+            .as_ref()
+            .expect("doesn't need in UT");
+        self.raise_order_created(order, exchange_order_id);
+        CreateOrderResult::succeed(exchange_order_id, EventSourceType::Rest)
     }
 
     async fn cancel_order(
         &self,
-        _order: &OrderRef,
-        _exchange_order_id: &ExchangeOrderId,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
     ) -> CancelOrderResult {
-        unimplemented!("doesn't need in UT")
+        self.register_request();
+        self.raise_order_cancelled(order, exchange_order_id);
+        CancelOrderResult::succeed(order.client_order_id(), EventSourceType::Rest, None)
+    }
+
+    async fn amend_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        _new_price: Price,
+        _new_amount: Amount,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        self.register_request();
+        if self.amended_order_rejected {
+            self.raise_order_cancelled(order, exchange_order_id);
+            return Some(Err(ExchangeError::new(
+                ExchangeErrorType::WouldTake,
+                "Replacement would immediately match and take".to_owned(),
+                None,
+            )));
+        }
+        let amended_exchange_order_id = self.amended_exchange_order_id.clone()?;
+        self.raise_order_cancelled(order, exchange_order_id);
+        Some(Ok(amended_exchange_order_id))
     }
 
     async fn cancel_all_orders(&self, _currency_pair: CurrencyPair) -> Result<()> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
@@ -82,10 +164,12 @@ impl ExchangeClient for TestClient {
         &self,
         _currency_pair: CurrencyPair,
     ) -> Result<Vec<OrderInfo>> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
     async fn get_order_info(&self, _order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
@@ -94,14 +178,17 @@ impl ExchangeClient for TestClient {
         _position: &ActivePosition,
         _price: Option<Price>,
     ) -> Result<ClosedPosition> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
     async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
     async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
@@ -110,14 +197,42 @@ impl ExchangeClient for TestClient {
         _symbol: &Symbol,
         _last_date_time: Option<DateTime>,
     ) -> RequestResult<Vec<OrderTrade>> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        self.register_request();
+        unimplemented!("doesn't need in UT")
+    }
+
+    async fn get_klines(
+        &self,
+        _currency_pair: CurrencyPair,
+        _interval: KlineInterval,
+        _limit: u32,
+    ) -> Result<Vec<Candle>> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 
     async fn get_server_time(&self) -> Option<Result<i64>> {
+        self.register_request();
+        unimplemented!("doesn't need in UT")
+    }
+
+    async fn get_trading_fees(&self, _currency_pair: CurrencyPair) -> Result<TradingFees> {
+        self.register_request();
+        self.trading_fees
+            .context("Trading fees aren't set for TestClient")
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        _currency_pair: CurrencyPair,
+        _depth: u32,
+    ) -> Result<OrderBookData> {
+        self.register_request();
         unimplemented!("doesn't need in UT")
     }
 }
@@ -145,9 +260,13 @@ impl Support for TestClient {
 
     fn set_send_websocket_message_callback(&mut self, _callback: SendWebsocketMessageCb) {}
 
-    fn set_order_created_callback(&mut self, _callback: OrderCreatedCb) {}
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = Some(callback);
+    }
 
-    fn set_order_cancelled_callback(&mut self, _callback: OrderCancelledCb) {}
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = Some(callback);
+    }
 
     fn set_handle_order_filled_callback(&mut self, _callback: HandleOrderFilledCb) {}
 
@@ -190,7 +309,7 @@ impl Support for TestClient {
     }
 
     fn get_settings(&self) -> &ExchangeSettings {
-        unimplemented!("doesn't need in UT")
+        &self.settings
     }
 }
 
@@ -202,14 +321,38 @@ pub(crate) fn get_test_exchange(
     get_test_exchange_by_currency_codes(is_derivative, base_currency_code, quote_currency_code)
 }
 
+/// Test exchange with specified symbol and exchange client
+pub(crate) fn get_test_exchange_with_symbol_and_client(
+    symbol: Arc<Symbol>,
+    exchange_client: TestClient,
+) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    let exchange_account_id = ExchangeAccountId::new("local_exchange_account_id", 0);
+    create_test_exchange(symbol, exchange_account_id, exchange_client)
+}
+
 pub(crate) fn get_test_exchange_by_currency_codes_and_amount_code(
     is_derivative: bool,
     base_currency_code: &str,
     quote_currency_code: &str,
     amount_currency_code: &str,
 ) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    let symbol = get_test_symbol(
+        is_derivative,
+        base_currency_code,
+        quote_currency_code,
+        amount_currency_code,
+    );
+    get_test_exchange_with_symbol(symbol)
+}
+
+pub(crate) fn get_test_symbol(
+    is_derivative: bool,
+    base_currency_code: &str,
+    quote_currency_code: &str,
+    amount_currency_code: &str,
+) -> Arc<Symbol> {
     let price_tick = dec!(0.1);
-    let symbol = Arc::new(Symbol::new(
+    Arc::new(Symbol::new(
         is_derivative,
         base_currency_code.into(),
         base_currency_code.into(),
@@ -224,8 +367,7 @@ pub(crate) fn get_test_exchange_by_currency_codes_and_amount_code(
         None,
         Precision::ByTick { tick: price_tick },
         Precision::ByTick { tick: dec!(0) },
-    ));
-    get_test_exchange_with_symbol(symbol)
+    ))
 }
 
 pub(crate) fn get_test_exchange_by_currency_codes(
@@ -255,11 +397,19 @@ pub(crate) fn get_test_exchange_with_symbol(
 pub(crate) fn get_test_exchange_with_symbol_and_id(
     symbol: Arc<Symbol>,
     exchange_account_id: ExchangeAccountId,
+) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    create_test_exchange(symbol, exchange_account_id, TestClient::default())
+}
+
+fn create_test_exchange(
+    symbol: Arc<Symbol>,
+    exchange_account_id: ExchangeAccountId,
+    exchange_client: TestClient,
 ) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
     let lifetime_manager = AppLifetimeManager::new(CancellationToken::new());
     let (tx, rx) = broadcast::channel(10);
 
-    let exchange_client = Box::new(TestClient);
+    let exchange_client = Box::new(exchange_client);
     let referral_reward = dec!(40);
     let commission = Commission::new(
         CommissionForType::new(dec!(0.1), referral_reward),
@@ -275,7 +425,7 @@ pub(crate) fn get_test_exchange_with_symbol_and_id(
     let timeout_managers = hashmap![exchange_account_id => request_timeout_manager];
     let timeout_manager = TimeoutManager::new(timeout_managers);
     let event_recorder =
-        block_on(EventRecorder::start(None, None)).expect("Failure start EventRecorder");
+        block_on(EventRecorder::start(None, None, 0.0)).expect("Failure start EventRecorder");
 
     let exchange = Exchange::new(
         exchange_account_id,
@@ -296,6 +446,7 @@ pub(crate) fn get_test_exchange_with_symbol_and_id(
             AllowedEventSourceType::default(),
         ),
         RequestTimeoutArguments::from_requests_per_minute(1200),
+        0.0,
         tx,
         lifetime_manager,
         timeout_manager,
@@ -10,7 +10,6 @@ use tokio::sync::{broadcast, oneshot};
 
 use crate::exchanges::general::exchange::{Exchange, OrderBookTop, PriceLevel};
 use crate::lifecycle::trading_engine::Service;
-use crate::order_book::local_snapshot_service::LocalSnapshotsService;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::market::ExchangeAccountId;
 use mmb_domain::order::event::OrderEventType;
@@ -34,7 +33,6 @@ impl InternalEventsLoop {
         exchanges_map: HashMap<ExchangeAccountId, Arc<Exchange>>,
         cancellation_token: CancellationToken,
     ) -> Result<()> {
-        let mut local_snapshots_service = LocalSnapshotsService::default();
         let (work_finished_sender, receiver) = oneshot::channel();
         *self.work_finished_receiver.lock() = Some(receiver);
 
@@ -49,11 +47,7 @@ impl InternalEventsLoop {
 
             match event {
                 ExchangeEvent::OrderBookEvent(ref order_book_event) => {
-                    update_order_book_top_for_exchange(
-                        order_book_event,
-                        &mut local_snapshots_service,
-                        &exchanges_map,
-                    )
+                    update_order_book_top_for_exchange(order_book_event, &exchanges_map)
                 }
                 ExchangeEvent::OrderEvent(order_event) => {
                     let target_eai = order_event.order.exchange_account_id();
@@ -82,6 +76,9 @@ impl InternalEventsLoop {
                 ExchangeEvent::BalanceUpdate(_) => {}
                 ExchangeEvent::LiquidationPrice(_) => {}
                 ExchangeEvent::Trades(_) => {}
+                ExchangeEvent::CustomEvent(_) => {}
+                ExchangeEvent::LifecycleState(_) => {}
+                ExchangeEvent::TimerTick => {}
             }
         }
     }
@@ -89,29 +86,43 @@ impl InternalEventsLoop {
 
 fn update_order_book_top_for_exchange(
     order_book_event: &OrderBookEvent,
-    local_snapshots_service: &mut LocalSnapshotsService,
     exchanges_map: &HashMap<ExchangeAccountId, Arc<Exchange>>,
 ) {
-    let market_account_id = local_snapshots_service.update(order_book_event);
-    if let Some(market_account_id) = &market_account_id {
-        let snapshot = local_snapshots_service.get_snapshot_expected(market_account_id.market_id());
-
-        let order_book_top = OrderBookTop {
-            ask: snapshot
-                .get_top_ask()
-                .map(|(price, amount)| PriceLevel { price, amount }),
-            bid: snapshot
-                .get_top_bid()
-                .map(|(price, amount)| PriceLevel { price, amount }),
-        };
-
-        exchanges_map
-            .get(&market_account_id.exchange_account_id)
-            .map(|exchange| {
-                exchange
-                    .order_book_top
-                    .insert(market_account_id.currency_pair, order_book_top)
-            });
+    let exchange_account_id = order_book_event.exchange_account_id;
+    let exchange = match exchanges_map.get(&exchange_account_id) {
+        Some(exchange) => exchange,
+        None => {
+            log::error!("Failed to get Exchange for {exchange_account_id} to update order book");
+            return;
+        }
+    };
+
+    let resync_requests = {
+        let mut local_snapshots_service = exchange.local_snapshots_service.lock();
+        let market_account_id = local_snapshots_service.update(order_book_event);
+        if let Some(market_account_id) = &market_account_id {
+            let snapshot =
+                local_snapshots_service.get_snapshot_expected(market_account_id.market_id());
+
+            let order_book_top = OrderBookTop {
+                ask: snapshot
+                    .get_top_ask()
+                    .map(|(price, amount)| PriceLevel { price, amount }),
+                bid: snapshot
+                    .get_top_bid()
+                    .map(|(price, amount)| PriceLevel { price, amount }),
+            };
+
+            let _ = exchange
+                .order_book_top
+                .insert(market_account_id.currency_pair, order_book_top);
+        }
+
+        local_snapshots_service.take_resync_requests()
+    };
+
+    for market_account_id in resync_requests {
+        exchange.request_order_book_resync(market_account_id.currency_pair);
     }
 }
 
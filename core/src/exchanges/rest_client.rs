@@ -1,18 +1,24 @@
 use crate::exchanges::traits::ExchangeError;
-use anyhow::Result;
+use crate::settings::RestRetrySettings;
+use anyhow::{anyhow, Result};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
+use chrono::Utc;
 use hyper::client::HttpConnector;
 use hyper::http::request::Builder;
 use hyper::http::uri::{Parts, PathAndQuery};
-use hyper::{Body, Client, Error, Method, Request, Response, StatusCode, Uri};
+use hyper::{Body, Client, Error, HeaderMap, Method, Request, Response, StatusCode, Uri};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use itertools::Itertools;
 use log::log;
 use mmb_domain::market::*;
+use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
 use std::borrow::Cow;
 use std::convert::TryInto;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter, Write};
+use std::time::Duration;
 use uuid::Uuid;
 
 pub type QueryKey = &'static str;
@@ -23,6 +29,7 @@ pub trait RestHeaders {
         builder: Builder,
         uri: &Uri,
         request_type: RequestType,
+        body: Option<&Bytes>,
     ) -> Builder;
 }
 
@@ -35,6 +42,7 @@ impl RestHeaders for RestHeadersEmpty {
         builder: Builder,
         _uri: &Uri,
         _request_type: RequestType,
+        _body: Option<&Bytes>,
     ) -> Builder {
         builder
     }
@@ -94,11 +102,20 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static> ErrorHandlerData<ErrHandl
         log_args: &str,
         response: &RestResponse,
         request_id: &Uuid,
+        log_bodies: bool,
     ) {
-        log::trace!(
-            "{fn_name} response on {}: {response:?}, | params {log_args}, request_id: {request_id}",
-            self.exchange_account_id
-        );
+        match log_bodies {
+            true => log::trace!(
+                "{fn_name} response on {}: status: {:?} content: {}, | params {log_args}, request_id: {request_id}",
+                self.exchange_account_id,
+                response.status,
+                response.content
+            ),
+            false => log::trace!(
+                "{fn_name} response on {}: {response:?}, | params {log_args}, request_id: {request_id}",
+                self.exchange_account_id
+            ),
+        }
     }
 
     pub(super) fn get_rest_error(
@@ -147,6 +164,8 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static> ErrorHandlerData<ErrHandl
 
         let log_level = match error.error_type {
             RateLimit | Authentication | InsufficientFunds | InvalidOrder => log::Level::Error,
+            // Requests fail continuously during maintenance, trading is paused on the first error
+            Maintenance => log::Level::Debug,
             _ => log::Level::Warn,
         };
         log!(
@@ -189,6 +208,22 @@ impl RequestType {
     }
 }
 
+impl RequestType {
+    /// Only idempotent requests can be retried safely without risk of duplicated side effects
+    pub const fn is_idempotent(&self) -> bool {
+        matches!(self, RequestType::Get)
+    }
+
+    fn method(&self) -> Method {
+        match *self {
+            RequestType::Get => Method::GET,
+            RequestType::Post => Method::POST,
+            RequestType::Delete => Method::DELETE,
+            RequestType::Put => Method::PUT,
+        }
+    }
+}
+
 impl Display for RequestType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
@@ -208,9 +243,31 @@ pub struct RestClient<
     client: Client<HttpsConnector<HttpConnector>>,
     error_handler: ErrorHandlerData<ErrHandler>,
     headers: SpecHeaders,
+    // Log full request and response bodies at trace level. Secrets are redacted
+    log_bodies: bool,
+    // Count of retries for requests rejected with `429 Too Many Requests`
+    rate_limit_retries: u32,
+    // Retries of idempotent requests failed with 5xx status or network error
+    transient_error_retries: Option<RestRetrySettings>,
+    // Aborts waiting before retry
+    cancellation_token: CancellationToken,
 }
 
 const KEEP_ALIVE: &str = "keep-alive";
+const REDACTED: &str = "<redacted>";
+// Parts of header and query parameter names which values must not be written to log
+const SENSITIVE_NAME_PARTS: [&str; 6] = [
+    "authorization",
+    "key",
+    "secret",
+    "sign",
+    "token",
+    "passphrase",
+];
+// Delay before retry of rate limited request if response has no valid Retry-After header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+// Upper bound of exponential backoff delay before retry of request failed with transient error
+const MAX_TRANSIENT_RETRY_DELAY: Duration = Duration::from_secs(30);
 // Inner Hyper types. Needed just for unified response handling in handle_response()
 type ResponseType = Result<Response<Body>, Error>;
 
@@ -222,38 +279,51 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
             client: create_client(),
             error_handler,
             headers,
+            log_bodies: false,
+            rate_limit_retries: 0,
+            transient_error_retries: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    /// Enable logging of full request and response bodies at trace level.
+    /// Authorization headers, signatures and keys are redacted.
+    pub fn with_bodies_logging(mut self, log_bodies: bool) -> Self {
+        self.log_bodies = log_bodies;
+        self
+    }
+
+    /// Retry requests rejected with `429 Too Many Requests` up to `retries` times
+    /// after the delay from `Retry-After` header. Waiting is aborted when `cancellation_token` is cancelled.
+    pub fn with_rate_limit_retries(
+        mut self,
+        retries: u32,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        self.rate_limit_retries = retries;
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// Retry idempotent requests failed with 5xx status or network error with exponential backoff.
+    /// Non-idempotent requests (e.g. order creation) are never retried to avoid duplicates.
+    /// Waiting is aborted when cancellation token from `with_rate_limit_retries` is cancelled.
+    pub fn with_transient_error_retries(mut self, retries: Option<RestRetrySettings>) -> Self {
+        self.transient_error_retries = retries;
+        self
+    }
+
     pub async fn get(
         &self,
         uri: Uri,
         action_name: &'static str,
         log_args: String,
     ) -> Result<RestResponse, ExchangeError> {
-        let request_id = Uuid::new_v4();
-        self.error_handler.request_log(action_name, &request_id);
-
-        let builder = Request::builder().method(Method::GET);
-        let request_type = RequestType::Get;
-        let req = self
-            .headers
-            .add_specific_headers(builder, &uri, request_type)
-            .uri(uri)
-            .header(hyper::header::CONNECTION, KEEP_ALIVE)
-            .body(Body::empty())
-            .with_expect(|| {
-                format!("Error during creation of http {request_type} request {request_id}")
-            });
-
-        let response = self.client.request(req).await;
-
-        self.handle_response(
-            response,
-            request_type.as_str(),
+        self.send_request(
+            RequestType::Get,
+            || (uri.clone(), None),
             action_name,
             log_args,
-            request_id,
         )
         .await
     }
@@ -264,29 +334,11 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
         action_name: &'static str,
         log_args: String,
     ) -> Result<RestResponse, ExchangeError> {
-        let request_id = Uuid::new_v4();
-        self.error_handler.request_log(action_name, &request_id);
-
-        let builder = Request::builder().method(Method::PUT);
-        let request_type = RequestType::Put;
-        let req = self
-            .headers
-            .add_specific_headers(builder, &uri, request_type)
-            .header(hyper::header::CONNECTION, KEEP_ALIVE)
-            .uri(uri)
-            .body(Body::empty())
-            .with_expect(|| {
-                format!("Error during creation of http {request_type} request {request_id}")
-            });
-
-        let response = self.client.request(req).await;
-
-        self.handle_response(
-            response,
-            request_type.as_str(),
+        self.send_request(
+            RequestType::Put,
+            || (uri.clone(), None),
             action_name,
             log_args,
-            request_id,
         )
         .await
     }
@@ -298,32 +350,11 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
         action_name: &'static str,
         log_args: String,
     ) -> Result<RestResponse, ExchangeError> {
-        let request_id = Uuid::new_v4();
-        self.error_handler.request_log(action_name, &request_id);
-
-        let builder = Request::builder().method(Method::POST);
-        let request_type = RequestType::Post;
-        let req = self
-            .headers
-            .add_specific_headers(builder, &uri, request_type)
-            .uri(uri)
-            .header(hyper::header::CONNECTION, KEEP_ALIVE)
-            .body(match query {
-                Some(query) => Body::from(query),
-                None => Body::empty(),
-            })
-            .with_expect(|| {
-                format!("Error during creation of http {request_type} request {request_id}")
-            });
-
-        let response = self.client.request(req).await;
-
-        self.handle_response(
-            response,
-            request_type.as_str(),
+        self.send_request(
+            RequestType::Post,
+            || (uri.clone(), query.clone()),
             action_name,
             log_args,
-            request_id,
         )
         .await
     }
@@ -334,33 +365,164 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
         action_name: &'static str,
         log_args: String,
     ) -> Result<RestResponse, ExchangeError> {
-        let request_id = Uuid::new_v4();
-        self.error_handler.request_log(action_name, &request_id);
-
-        let builder = Request::builder().method(Method::DELETE);
-        let request_type = RequestType::Delete;
-        let req = self
-            .headers
-            .add_specific_headers(builder, &uri, request_type)
-            .header(hyper::header::CONNECTION, KEEP_ALIVE)
-            .uri(uri)
-            .body(Body::empty())
-            .with_expect(|| {
-                format!("Error during creation of http {request_type} request {request_id}")
-            });
-
-        let response = self.client.request(req).await;
-
-        self.handle_response(
-            response,
-            request_type.as_str(),
+        self.send_request(
+            RequestType::Delete,
+            || (uri.clone(), None),
+            action_name,
+            log_args,
+        )
+        .await
+    }
+
+    /// Same as `get`, but uri is built by `build_uri` for every attempt,
+    /// so signature with timestamp in query isn't outdated on retries
+    pub async fn get_signed(
+        &self,
+        build_uri: impl Fn() -> Uri + Send + Sync,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        self.send_request(
+            RequestType::Get,
+            || (build_uri(), None),
+            action_name,
+            log_args,
+        )
+        .await
+    }
+
+    /// Same as `post`, but uri and body are built by `build_request` for every attempt,
+    /// so signature with timestamp isn't outdated on retries
+    pub async fn post_signed(
+        &self,
+        build_request: impl Fn() -> (Uri, Bytes) + Send + Sync,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        self.send_request(
+            RequestType::Post,
+            || {
+                let (uri, body) = build_request();
+                (uri, Some(body))
+            },
+            action_name,
+            log_args,
+        )
+        .await
+    }
+
+    /// Same as `delete`, but uri is built by `build_uri` for every attempt,
+    /// so signature with timestamp in query isn't outdated on retries
+    pub async fn delete_signed(
+        &self,
+        build_uri: impl Fn() -> Uri + Send + Sync,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        self.send_request(
+            RequestType::Delete,
+            || (build_uri(), None),
             action_name,
             log_args,
-            request_id,
         )
         .await
     }
 
+    /// Request is built by `build_request` for every attempt, because it can contain signature
+    /// which becomes invalid after some time
+    async fn send_request(
+        &self,
+        request_type: RequestType,
+        build_request: impl Fn() -> (Uri, Option<Bytes>) + Send + Sync,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut rate_limit_retry = 0;
+        let mut transient_error_retry = 0;
+        loop {
+            let request_id = Uuid::new_v4();
+            self.error_handler.request_log(action_name, &request_id);
+
+            let (uri, body) = build_request();
+            let builder = Request::builder().method(request_type.method());
+            let req = self
+                .headers
+                .add_specific_headers(builder, &uri, request_type, body.as_ref())
+                .uri(uri)
+                .header(hyper::header::CONNECTION, KEEP_ALIVE)
+                .body(match body.clone() {
+                    Some(body) => Body::from(body),
+                    None => Body::empty(),
+                })
+                .with_expect(|| {
+                    format!("Error during creation of http {request_type} request {request_id}")
+                });
+
+            self.request_bodies_log(&req, body.as_ref(), &request_id);
+
+            let response = self.client.request(req).await;
+
+            let retry_after = match &response {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    Some(parse_retry_after(response.headers(), Utc::now()))
+                }
+                _ => None,
+            };
+            let is_transient_error = match &response {
+                Ok(response) => response.status().is_server_error(),
+                Err(_) => true,
+            };
+
+            let result = self
+                .handle_response(
+                    response,
+                    request_type.as_str(),
+                    action_name,
+                    log_args.clone(),
+                    request_id,
+                )
+                .await;
+
+            let error = match result {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            let delay = match (retry_after, self.transient_error_retries) {
+                (Some(retry_after), _) if rate_limit_retry < self.rate_limit_retries => {
+                    rate_limit_retry += 1;
+                    let delay = retry_after.unwrap_or(DEFAULT_RETRY_AFTER);
+                    log::warn!(
+                        "{action_name} request {request_id} on {} is rate limited. Retry {rate_limit_retry}/{} after {delay:?}",
+                        self.error_handler.exchange_account_id,
+                        self.rate_limit_retries,
+                    );
+                    delay
+                }
+                (None, Some(retries))
+                    if is_transient_error
+                        && request_type.is_idempotent()
+                        && transient_error_retry + 1 < retries.max_attempts =>
+                {
+                    transient_error_retry += 1;
+                    let delay = backoff_delay(retries.base_delay_ms, transient_error_retry);
+                    log::warn!(
+                        "{action_name} request {request_id} on {} failed on attempt {transient_error_retry}/{}: {error:?}. Retry after {delay:?}",
+                        self.error_handler.exchange_account_id,
+                        retries.max_attempts,
+                    );
+                    delay
+                }
+                _ => return Err(error),
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = self.cancellation_token.when_cancelled() => return Err(error),
+            }
+        }
+    }
+
     async fn handle_response(
         &self,
         response: ResponseType,
@@ -369,15 +531,19 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
         log_args: String,
         request_id: Uuid,
     ) -> Result<RestResponse, ExchangeError> {
-        let response = response.with_expect(|| {
-            format!("Unable to send {rest_action} request, request_id: {request_id}")
-        });
+        let response = response.map_err(|err| {
+            ExchangeError::send(anyhow!(
+                "Unable to send {rest_action} request, request_id: {request_id}: {err}"
+            ))
+        })?;
         let status = response.status();
         let request_bytes = hyper::body::to_bytes(response.into_body())
             .await
-            .with_expect(|| {
-                format!("Unable to convert response body to bytes, request_id: {request_id}")
-            });
+            .map_err(|err| {
+                ExchangeError::send(anyhow!(
+                    "Unable to convert response body to bytes, request_id: {request_id}: {err}"
+                ))
+            })?;
 
         let content = std::str::from_utf8(&request_bytes)
             .with_expect(|| format!("Unable to convert response content from utf8: {request_bytes:?}, request_id: {request_id}"))
@@ -386,11 +552,101 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
         let request_outcome = RestResponse { status, content };
 
         let err_handler_data = &self.error_handler;
-        err_handler_data.response_log(action_name, &log_args, &request_outcome, &request_id);
+        err_handler_data.response_log(
+            action_name,
+            &log_args,
+            &request_outcome,
+            &request_id,
+            self.log_bodies,
+        );
         err_handler_data.get_rest_error(&request_outcome, &log_args, &request_id)?;
 
         Ok(request_outcome)
     }
+
+    fn request_bodies_log(&self, request: &Request<Body>, body: Option<&Bytes>, request_id: &Uuid) {
+        if !self.log_bodies || !log::log_enabled!(log::Level::Trace) {
+            return;
+        }
+
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = match is_sensitive_name(name.as_str()) {
+                    true => REDACTED,
+                    false => value.to_str().unwrap_or("<non-utf8>"),
+                };
+                format!("{name}: {value}")
+            })
+            .join(", ");
+        let body = body.map_or_else(String::new, |body| match std::str::from_utf8(body) {
+            Ok(body) => redact_query(body),
+            Err(_) => format!("<{} bytes of non-utf8 body>", body.len()),
+        });
+
+        log::trace!(
+            "{} request {request_id} on {}: uri: {}, headers: [{headers}], body: {body}",
+            request.method(),
+            self.error_handler.exchange_account_id,
+            redact_uri(request.uri()),
+        );
+    }
+}
+
+fn is_sensitive_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// Replace values of sensitive parameters in url encoded query
+fn redact_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive_name(name) => format!("{name}={REDACTED}"),
+            _ => pair.to_owned(),
+        })
+        .join("&")
+}
+
+fn redact_uri(uri: &Uri) -> String {
+    let uri = uri.to_string();
+    match uri.split_once('?') {
+        Some((uri_without_query, query)) => {
+            format!("{uri_without_query}?{}", redact_query(query))
+        }
+        None => uri,
+    }
+}
+
+/// Exponential backoff delay before specified retry (starting from 1) of request failed with transient error
+fn backoff_delay(base_delay_ms: u64, retry: u32) -> Duration {
+    let multiplier = 2u32.saturating_pow(retry.saturating_sub(1));
+    Duration::from_millis(base_delay_ms)
+        .saturating_mul(multiplier)
+        .min(MAX_TRANSIENT_RETRY_DELAY)
+}
+
+/// Delay from `Retry-After` header specified in seconds or as HTTP date
+fn parse_retry_after(headers: &HeaderMap, now: DateTime) -> Option<Duration> {
+    let value = headers
+        .get(hyper::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let retry_at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    // date in the past means that request can be retried immediately
+    Some(
+        (retry_at.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
 }
 
 fn create_client() -> Client<HttpsConnector<HttpConnector>> {
@@ -403,6 +659,7 @@ fn create_client() -> Client<HttpsConnector<HttpConnector>> {
     Client::builder().build::<_, Body>(https)
 }
 
+#[derive(Clone)]
 pub struct UriBuilder {
     // buffer for path and query parts of uri
     buffer: BytesMut,
@@ -484,7 +741,14 @@ impl UriBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+    use parking_lot::Mutex;
     use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
 
     #[test]
     pub fn build_query_by_builder() {
@@ -551,6 +815,317 @@ mod tests {
         let path_and_query = builder.build_uri(host, true);
         assert_eq!(path_and_query, Uri::from_static("https://host.com/path"))
     }
+
+    #[test]
+    pub fn redact_sensitive_query_params() {
+        let query = "symbol=LTCBTC&apiKey=my_key&timestamp=1499827319559&signature=c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71";
+
+        assert_eq!(
+            redact_query(query),
+            "symbol=LTCBTC&apiKey=<redacted>&timestamp=1499827319559&signature=<redacted>"
+        );
+    }
+
+    #[test]
+    pub fn redact_sensitive_params_in_uri() {
+        let uri = Uri::from_static("https://host.com/path?listenKey=secret_listen_key&side=BUY");
+
+        assert_eq!(
+            redact_uri(&uri),
+            "https://host.com/path?listenKey=<redacted>&side=BUY"
+        );
+        assert_eq!(
+            redact_uri(&Uri::from_static("https://host.com/path")),
+            "https://host.com/path"
+        );
+    }
+
+    fn rate_limited_response(retry_after: &str) -> String {
+        format!("HTTP/1.1 429 Too Many Requests\r\nRetry-After: {retry_after}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+    }
+
+    fn service_unavailable_response() -> String {
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_owned()
+    }
+
+    fn ok_response() -> String {
+        "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok".to_owned()
+    }
+
+    /// HTTP server that sends specified responses one by one for each request
+    /// and records time of receiving requests
+    async fn start_mock_server(responses: Vec<String>) -> (Uri, Arc<Mutex<Vec<Instant>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("in test");
+        let address = listener.local_addr().expect("in test");
+        let requests_times = Arc::new(Mutex::new(Vec::new()));
+
+        let times = requests_times.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.expect("in test");
+
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).await.expect("in test");
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                times.lock().push(Instant::now());
+
+                stream
+                    .write_all(response.as_bytes())
+                    .await
+                    .expect("in test");
+                stream.shutdown().await.expect("in test");
+            }
+        });
+
+        let uri = format!("http://{address}/path").parse().expect("in test");
+        (uri, requests_times)
+    }
+
+    fn rest_client(
+        rate_limit_retries: u32,
+        cancellation_token: CancellationToken,
+    ) -> RestClient<ErrorHandlerEmpty, RestHeadersEmpty> {
+        // mock server doesn't support https
+        let connector = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        RestClient {
+            client: Client::builder().build::<_, Body>(connector),
+            ..RestClient::new(
+                ErrorHandlerData::new(
+                    false,
+                    ExchangeAccountId::new("Binance", 0),
+                    ErrorHandlerEmpty,
+                ),
+                RestHeadersEmpty,
+            )
+            .with_rate_limit_retries(rate_limit_retries, cancellation_token)
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn retry_rate_limited_request_after_delay() {
+        let (uri, requests_times) = start_mock_server(vec![
+            rate_limited_response("1"),
+            rate_limited_response("1"),
+            ok_response(),
+        ])
+        .await;
+
+        let rest_client = rest_client(3, CancellationToken::new());
+        let response = rest_client
+            .get(uri, "test", "".to_owned())
+            .await
+            .expect("in test");
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.content, "ok");
+
+        let requests_times = requests_times.lock();
+        assert_eq!(requests_times.len(), 3);
+        for (prev, next) in requests_times.iter().tuple_windows() {
+            let delay = *next - *prev;
+            assert!(
+                delay >= Duration::from_millis(950) && delay < Duration::from_millis(1500),
+                "unexpected delay between retries {delay:?}"
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn return_error_when_rate_limit_retries_are_exceeded() {
+        let (uri, requests_times) = start_mock_server(vec![
+            rate_limited_response("0"),
+            rate_limited_response("0"),
+            ok_response(),
+        ])
+        .await;
+
+        let rest_client = rest_client(1, CancellationToken::new());
+        let error = rest_client
+            .get(uri, "test", "".to_owned())
+            .await
+            .expect_err("in test");
+
+        assert_eq!(error.error_type, ExchangeErrorType::RateLimit);
+        assert_eq!(requests_times.lock().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn cancellation_aborts_waiting_for_retry() {
+        let (uri, requests_times) =
+            start_mock_server(vec![rate_limited_response("60"), ok_response()]).await;
+
+        let cancellation_token = CancellationToken::new();
+        let rest_client = rest_client(3, cancellation_token.clone());
+
+        let started_at = Instant::now();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancellation_token.cancel();
+        });
+        let error = rest_client
+            .get(uri, "test", "".to_owned())
+            .await
+            .expect_err("in test");
+
+        assert_eq!(error.error_type, ExchangeErrorType::RateLimit);
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+        assert_eq!(requests_times.lock().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn signed_request_is_built_for_every_attempt() {
+        let (uri, requests_times) =
+            start_mock_server(vec![rate_limited_response("0"), ok_response()]).await;
+
+        let rest_client = rest_client(1, CancellationToken::new());
+        let builds_count = AtomicUsize::new(0);
+        let response = rest_client
+            .get_signed(
+                || {
+                    let _ = builds_count.fetch_add(1, Ordering::SeqCst);
+                    uri.clone()
+                },
+                "test",
+                "".to_owned(),
+            )
+            .await
+            .expect("in test");
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(requests_times.lock().len(), 2);
+        assert_eq!(builds_count.load(Ordering::SeqCst), 2);
+    }
+
+    fn transient_error_retries(max_attempts: u32, base_delay_ms: u64) -> Option<RestRetrySettings> {
+        Some(RestRetrySettings {
+            max_attempts,
+            base_delay_ms,
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn retry_get_request_after_transient_error_with_backoff() {
+        let (uri, requests_times) = start_mock_server(vec![
+            service_unavailable_response(),
+            service_unavailable_response(),
+            ok_response(),
+        ])
+        .await;
+
+        let rest_client = rest_client(0, CancellationToken::new())
+            .with_transient_error_retries(transient_error_retries(3, 200));
+        let response = rest_client
+            .get(uri, "test", "".to_owned())
+            .await
+            .expect("in test");
+
+        assert_eq!(response.status, StatusCode::OK);
+
+        let requests_times = requests_times.lock();
+        assert_eq!(requests_times.len(), 3);
+        let delays = requests_times
+            .iter()
+            .tuple_windows()
+            .map(|(prev, next)| *next - *prev)
+            .collect_vec();
+        assert!(
+            delays[0] >= Duration::from_millis(190) && delays[0] < Duration::from_millis(390),
+            "unexpected delay before first retry {:?}",
+            delays[0]
+        );
+        assert!(
+            delays[1] >= Duration::from_millis(390) && delays[1] < Duration::from_millis(790),
+            "unexpected delay before second retry {:?}",
+            delays[1]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn return_error_when_transient_error_attempts_are_exceeded() {
+        let (uri, requests_times) = start_mock_server(vec![
+            service_unavailable_response(),
+            service_unavailable_response(),
+            ok_response(),
+        ])
+        .await;
+
+        let rest_client = rest_client(0, CancellationToken::new())
+            .with_transient_error_retries(transient_error_retries(2, 0));
+        let error = rest_client
+            .get(uri, "test", "".to_owned())
+            .await
+            .expect_err("in test");
+
+        assert_eq!(error.error_type, ExchangeErrorType::ServiceUnavailable);
+        assert_eq!(requests_times.lock().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn do_not_retry_post_request_after_transient_error() {
+        let (uri, requests_times) =
+            start_mock_server(vec![service_unavailable_response(), ok_response()]).await;
+
+        let rest_client = rest_client(0, CancellationToken::new())
+            .with_transient_error_retries(transient_error_retries(3, 0));
+        let error = rest_client
+            .post(uri, None, "test", "".to_owned())
+            .await
+            .expect_err("in test");
+
+        assert_eq!(error.error_type, ExchangeErrorType::ServiceUnavailable);
+        assert_eq!(requests_times.lock().len(), 1);
+    }
+
+    #[test]
+    pub fn backoff_delay_is_doubled_and_capped() {
+        assert_eq!(backoff_delay(100, 1), Duration::from_millis(100));
+        assert_eq!(backoff_delay(100, 2), Duration::from_millis(200));
+        assert_eq!(backoff_delay(100, 4), Duration::from_millis(800));
+        assert_eq!(backoff_delay(100, 100), MAX_TRANSIENT_RETRY_DELAY);
+    }
+
+    #[test]
+    pub fn parse_retry_after_header() {
+        let now = Utc.ymd(2015, 10, 21).and_hms(7, 27, 30);
+        let retry_after = |value: &str| {
+            let mut headers = HeaderMap::new();
+            let _ = headers.insert(hyper::header::RETRY_AFTER, value.parse().expect("in test"));
+            parse_retry_after(&headers, now)
+        };
+
+        assert_eq!(retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after("Wed, 21 Oct 2015 07:27:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_after("soon"), None);
+        assert_eq!(parse_retry_after(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    pub fn detect_sensitive_header_names() {
+        assert!(is_sensitive_name("Authorization"));
+        assert!(is_sensitive_name("X-MBX-APIKEY"));
+        assert!(is_sensitive_name("api-signature"));
+        assert!(!is_sensitive_name("api-expires"));
+        assert!(!is_sensitive_name("content-type"));
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
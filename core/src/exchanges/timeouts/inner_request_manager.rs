@@ -1,6 +1,7 @@
 use super::{
     more_or_equals_available_requests_count_trigger_scheduler::MoreOrEqualsAvailableRequestsCountTriggerScheduler,
-    pre_reserved_group::PreReservedGroup, request::Request,
+    pre_reserved_group::PreReservedGroup,
+    request::{Request, RequestPriority},
     triggers::handle_trigger_trait::TriggerHandler,
 };
 use crate::exchanges::general::request_type::RequestType;
@@ -8,6 +9,7 @@ use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 use anyhow::{bail, Result};
 use chrono::Duration;
 use function_name::named;
+use itertools::Itertools;
 use mmb_domain::market::ExchangeAccountId;
 use mmb_utils::DateTime;
 use std::collections::HashMap;
@@ -106,6 +108,49 @@ impl InnerRequestsTimeoutManager {
         request
     }
 
+    /// Move pending request with high priority ahead of pending requests with normal priority.
+    /// Postponed requests are shifted to the next slots keeping their order.
+    /// Returns new start time of prioritized request
+    pub(super) fn prioritize_request(
+        &mut self,
+        request: &Request,
+        current_time: DateTime,
+    ) -> DateTime {
+        let pending_normal_requests = self
+            .requests
+            .iter()
+            .enumerate()
+            .filter(|(_, stored_request)| {
+                stored_request.allowed_start_time > current_time
+                    && stored_request.priority == RequestPriority::Normal
+                    && stored_request.group_id.is_none()
+            })
+            .map(|(index, _)| index)
+            .collect_vec();
+
+        let request_index = match self
+            .requests
+            .iter()
+            .position(|stored_request| stored_request.id == request.id)
+        {
+            Some(request_index) => request_index,
+            None => return request.allowed_start_time,
+        };
+
+        let mut slot_time = request.allowed_start_time;
+        for &index in pending_normal_requests.iter().rev() {
+            let stored_request = &mut self.requests[index];
+            std::mem::swap(&mut stored_request.allowed_start_time, &mut slot_time);
+        }
+        self.requests[request_index].allowed_start_time = slot_time;
+
+        // stable sorting keeps order of requests with the same start time
+        self.requests
+            .sort_by_key(|stored_request| stored_request.allowed_start_time);
+
+        slot_time
+    }
+
     pub(super) fn handle_all_decreasing_triggers(&mut self) {
         let available_requests_count = self.get_all_available_requests_count();
 
@@ -1,11 +1,31 @@
 use mmb_utils::DateTime;
+use uuid::Uuid;
 
 use crate::exchanges::general::request_type::RequestType;
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 
+/// Requests with `High` priority are executed before pending requests with `Normal` priority
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RequestPriority {
+    High,
+    Normal,
+}
+
+impl From<RequestType> for RequestPriority {
+    fn from(request_type: RequestType) -> Self {
+        match request_type {
+            // cancellation should be done before new orders creation to avoid overfilling positions
+            RequestType::CancelOrder => RequestPriority::High,
+            _ => RequestPriority::Normal,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Request {
+    pub(crate) id: Uuid,
     pub(crate) request_type: RequestType,
+    pub(crate) priority: RequestPriority,
     pub(crate) allowed_start_time: DateTime,
     pub(crate) group_id: Option<RequestGroupId>,
 }
@@ -17,7 +37,9 @@ impl Request {
         group_id: Option<RequestGroupId>,
     ) -> Self {
         Self {
+            id: Uuid::new_v4(),
             request_type,
+            priority: request_type.into(),
             allowed_start_time,
             group_id,
         }
@@ -14,7 +14,8 @@ use uuid::Uuid;
 use super::{
     inner_request_manager::InnerRequestsTimeoutManager,
     more_or_equals_available_requests_count_trigger_scheduler::MoreOrEqualsAvailableRequestsCountTriggerScheduler,
-    pre_reserved_group::PreReservedGroup, request::Request,
+    pre_reserved_group::PreReservedGroup,
+    request::{Request, RequestPriority},
     triggers::every_requests_count_change_trigger::EveryRequestsCountChangeTrigger,
     triggers::less_or_equals_requests_count_trigger::LessOrEqualsRequestsCountTrigger,
 };
@@ -248,9 +249,9 @@ impl RequestsTimeoutManager {
         let _available_requests_count = inner.get_all_available_requests_count();
 
         let mut request_start_time;
-        let delay;
+        let mut delay;
         let available_requests_count_for_period;
-        let request = if let Some(last_request) = inner.requests.last() {
+        let mut request = if let Some(last_request) = inner.requests.last() {
             let last_request_start_time = last_request.allowed_start_time;
 
             available_requests_count_for_period =
@@ -271,6 +272,12 @@ impl RequestsTimeoutManager {
             inner.add_request(request_type, current_time, None)
         };
 
+        if request.priority == RequestPriority::High && delay > Duration::zero() {
+            request_start_time = inner.prioritize_request(&request, current_time);
+            delay = request_start_time - current_time;
+            request.allowed_start_time = request_start_time;
+        }
+
         log::info!("Request {request_type:?} reserved, available in request_start_time {request_start_time}");
 
         // TODO save to DataRecorder. Delete drop
@@ -301,32 +308,54 @@ impl RequestsTimeoutManager {
         delay: Duration,
         cancellation_token: CancellationToken,
     ) -> Result<()> {
-        // Should never panic, because function wait_for_request_availability
-        // has one call with guaranteed non-negative delay.
-        let delay = delay.to_std_expected();
-
-        match timeout(delay, cancellation_token.when_cancelled()).await {
-            Err(_) => {
-                let strong_self = Self::try_get_strong(weak_self)?;
-                (strong_self.inner.lock().time_has_come_for_request)(request);
-            }
-            Ok(()) => {
-                let strong_self = Self::try_get_strong(weak_self)?;
-                let mut inner = strong_self.inner.lock();
-                (inner.time_has_come_for_request)(request.clone());
-                if let Some(position) = inner
-                    .requests
-                    .iter()
-                    .position(|stored_request| *stored_request == request)
-                {
-                    inner.requests.remove(position);
+        let mut request = request;
+        let mut delay = delay;
+        loop {
+            // Should never panic, because function wait_for_request_availability
+            // has one call with guaranteed non-negative delay and request can only be postponed.
+            match timeout(delay.to_std_expected(), cancellation_token.when_cancelled()).await {
+                Err(_) => {
+                    let strong_self = Self::try_get_strong(weak_self.clone())?;
+                    let inner = strong_self.inner.lock();
+
+                    // request can be postponed by request with higher priority while waiting
+                    let postponed_request = inner
+                        .requests
+                        .iter()
+                        .find(|stored_request| {
+                            stored_request.id == request.id
+                                && stored_request.allowed_start_time > request.allowed_start_time
+                        })
+                        .cloned();
+
+                    match postponed_request {
+                        Some(postponed_request) => {
+                            delay =
+                                postponed_request.allowed_start_time - request.allowed_start_time;
+                            request = postponed_request;
+                        }
+                        None => {
+                            (inner.time_has_come_for_request)(request);
+                            return Ok(());
+                        }
+                    }
                 }
+                Ok(()) => {
+                    let strong_self = Self::try_get_strong(weak_self)?;
+                    let mut inner = strong_self.inner.lock();
+                    (inner.time_has_come_for_request)(request.clone());
+                    if let Some(position) = inner
+                        .requests
+                        .iter()
+                        .position(|stored_request| stored_request.id == request.id)
+                    {
+                        inner.requests.remove(position);
+                    }
 
-                bail!(OPERATION_CANCELED_MSG)
-            }
-        };
-
-        Ok(())
+                    bail!(OPERATION_CANCELED_MSG)
+                }
+            };
+        }
     }
 
     fn try_get_strong(
@@ -2203,6 +2232,89 @@ mod test {
 
             Ok(())
         }
+
+        #[rstest]
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn cancel_request_processed_before_pending_create_request(
+            timeout_manager: Arc<RequestsTimeoutManager>,
+        ) -> Result<()> {
+            let _ = init_lifetime_manager();
+
+            // Arrange
+            let period_duration = Duration::milliseconds(200);
+            let next_period_delay = Duration::milliseconds(1);
+            {
+                let mut inner = timeout_manager.inner.lock();
+                inner.requests_per_period = 1;
+                inner.period_duration = period_duration;
+            }
+            let current_time = Utc::now();
+            let processed_requests = Arc::new(Mutex::new(Vec::new()));
+
+            let reserve = |request_type| {
+                let (handle, start_time, delay) = timeout_manager.clone().reserve_when_available(
+                    request_type,
+                    current_time,
+                    CancellationToken::default(),
+                );
+                let processed_requests = processed_requests.clone();
+                let handle = tokio::spawn(async move {
+                    let _ = handle.await;
+                    processed_requests.lock().push(request_type);
+                });
+                (handle, start_time, delay)
+            };
+
+            let (first_create_handle, _, _) = reserve(RequestType::CreateOrder);
+            let (second_create_handle, second_create_start_time, _) =
+                reserve(RequestType::CreateOrder);
+            assert_eq!(
+                second_create_start_time,
+                current_time + period_duration + next_period_delay
+            );
+
+            // Act
+            let (cancel_handle, cancel_start_time, cancel_delay) =
+                reserve(RequestType::CancelOrder);
+
+            // Assert
+            assert_eq!(
+                cancel_start_time,
+                current_time + period_duration + next_period_delay
+            );
+            assert_eq!(cancel_delay, period_duration + next_period_delay);
+
+            {
+                let inner = timeout_manager.inner.lock();
+                assert_eq!(inner.requests.len(), 3);
+
+                let cancel_request = inner.requests[1].clone();
+                assert_eq!(cancel_request.request_type, RequestType::CancelOrder);
+                assert_eq!(cancel_request.allowed_start_time, cancel_start_time);
+
+                let postponed_request = inner.requests[2].clone();
+                assert_eq!(postponed_request.request_type, RequestType::CreateOrder);
+                assert_eq!(
+                    postponed_request.allowed_start_time,
+                    current_time + (period_duration + next_period_delay) * 2
+                );
+            }
+
+            first_create_handle.await?;
+            second_create_handle.await?;
+            cancel_handle.await?;
+
+            assert_eq!(
+                *processed_requests.lock(),
+                vec![
+                    RequestType::CreateOrder,
+                    RequestType::CancelOrder,
+                    RequestType::CreateOrder
+                ]
+            );
+
+            Ok(())
+        }
     }
 
     mod triggers {
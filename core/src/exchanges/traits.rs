@@ -12,11 +12,13 @@ use crate::exchanges::general::order::create::CreateOrderResult;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::settings::ExchangeSettings;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use dashmap::DashMap;
+use mmb_domain::candle::{Candle, KlineInterval};
 use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions, MetricsEventInfo};
 use mmb_domain::events::{ExchangeEvent, Trade};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
 use mmb_domain::market::CurrencyId;
 use mmb_domain::market::{
@@ -24,10 +26,11 @@ use mmb_domain::market::{
     SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
-use mmb_domain::order::snapshot::Price;
+use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{
     ClientOrderId, ExchangeOrderId, OrderInfo, OrderInfoExtensionData, OrderSide,
 };
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 use serde::{Deserialize, Serialize};
@@ -98,6 +101,19 @@ pub trait ExchangeClient: Support {
         exchange_order_id: &ExchangeOrderId,
     ) -> CancelOrderResult;
 
+    /// Atomically replace price and amount of active order by native exchange request (cancel-replace)
+    /// Replacing order keeps the same `ClientOrderId` and should get new `ExchangeOrderId` returned by the method
+    /// Returns `None` if exchange doesn't support native amendment of the order, so core falls back to cancel and create
+    async fn amend_order(
+        &self,
+        _order: &OrderRef,
+        _exchange_order_id: &ExchangeOrderId,
+        _new_price: Price,
+        _new_amount: Amount,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        None
+    }
+
     async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()>;
 
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>>;
@@ -138,10 +154,36 @@ pub trait ExchangeClient: Support {
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>>;
 
+    /// Historical candles ordered from oldest to newest. The last candle can be not closed yet
+    ///
+    /// # Params
+    ///
+    /// * `limit` - max count of the latest candles to return
+    async fn get_klines(
+        &self,
+        currency_pair: CurrencyPair,
+        interval: KlineInterval,
+        limit: u32,
+    ) -> Result<Vec<Candle>>;
+
     /// Only for centralized exchanges
     /// Need for server time latency calculating
     /// Should return server time with millis accuracy
     async fn get_server_time(&self) -> Option<Result<i64>>;
+
+    /// Actual maker and taker fee rates of account for specified market
+    async fn get_trading_fees(&self, currency_pair: CurrencyPair) -> Result<TradingFees>;
+
+    /// Current order book requested via REST API
+    ///
+    /// # Params
+    ///
+    /// * `depth` - max count of price levels on each side
+    async fn get_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: u32,
+    ) -> Result<OrderBookData>;
 }
 
 pub type OrderCreatedCb =
@@ -187,6 +229,11 @@ pub trait Support: Send + Sync {
 
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url>;
 
+    /// Headers of WebSocket handshake request, e.g. for authentication
+    fn create_ws_headers(&self, _role: WebSocketRole) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
     fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair;
 
     fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode>;
@@ -210,6 +257,13 @@ pub trait Support: Send + Sync {
     fn get_initial_extension_data(&self) -> Option<Box<dyn OrderInfoExtensionData>> {
         None
     }
+
+    /// Request fresh order book snapshot because some order book updates were missed.
+    /// Snapshot should be sent as order book event with its update id, so the next updates are
+    /// validated against it. Only exchanges which send update ids in order book events can miss updates
+    fn request_order_book_resync(&self, _currency_pair: CurrencyPair) -> Result<()> {
+        bail!("Order book resync isn't supported")
+    }
 }
 
 pub struct ExchangeClientBuilderResult {
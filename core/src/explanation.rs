@@ -1,9 +1,11 @@
+use anyhow::{Context, Result};
+use itertools::Itertools;
 use mmb_database::impl_event;
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::market::ExchangeId;
 use mmb_domain::order::snapshot::{Amount, Price};
 use serde::Serialize;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 
 pub struct Reason(Option<String>);
 
@@ -31,31 +33,94 @@ impl From<Option<&str>> for Reason {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+/// Typed cause of skipping price level for programmatic analysis of explanations
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub enum ReasonCode {
+    InsufficientBalance,
+    BelowMinProfit,
+    RateLimited,
+    BelowMinNotional,
+    PriceCrossed,
+    MaxPositionExceeded,
+    PriceOutOfBand,
+    ReservationLimitExceeded,
+}
+
+/// Structured cause of decision about price level, e.g. `balance=0.5`
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct ExplanationReason {
+    pub key: &'static str,
+    pub value: String,
+}
+
+impl ExplanationReason {
+    /// Key for reasons added as plain text messages
+    pub const MESSAGE_KEY: &'static str = "reason";
+
+    pub fn new(key: &'static str, value: impl Display) -> Self {
+        ExplanationReason {
+            key,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for ExplanationReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct Explanation {
-    reasons: Vec<String>,
+    reasons: Vec<ExplanationReason>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reason_codes: Vec<ReasonCode>,
 }
 
 impl Explanation {
-    pub(crate) fn get_reasons(&self) -> &[String] {
+    pub(crate) fn get_reasons(&self) -> &[ExplanationReason] {
         self.reasons.as_slice()
     }
+
+    pub(crate) fn get_reason_codes(&self) -> &[ReasonCode] {
+        self.reason_codes.as_slice()
+    }
 }
 
 impl Explanation {
+    /// Add plain text reason. It is stored with key `reason`
     pub fn add_reason(&mut self, reason: impl Into<Reason>) {
         let reason = reason.into();
         if let Reason(Some(reason)) = reason {
-            self.reasons.push(reason);
+            self.add_kv_reason(ExplanationReason::MESSAGE_KEY, reason);
+        }
+    }
+
+    pub fn add_kv_reason(&mut self, key: &'static str, value: impl Display) {
+        self.reasons.push(ExplanationReason::new(key, value));
+    }
+
+    /// Add human readable reason together with its typed code
+    pub fn add_reason_with_code(&mut self, code: ReasonCode, reason: impl Into<Reason>) {
+        self.add_reason(reason);
+        if !self.reason_codes.contains(&code) {
+            self.reason_codes.push(code);
         }
     }
 
     #[cfg(test)]
-    fn reasons(self) -> Vec<String> {
+    fn reasons(self) -> Vec<ExplanationReason> {
         self.reasons
     }
 }
 
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reasons.iter().join(", "))
+    }
+}
+
 pub struct WithExplanation<T> {
     pub value: T,
     pub explanation: Explanation,
@@ -132,7 +197,8 @@ pub struct PriceLevelExplanation<'a> {
     pub mode_name: String,
     pub price: Price,
     pub amount: Amount,
-    pub reasons: &'a [String],
+    pub reasons: &'a [ExplanationReason],
+    pub reason_codes: &'a [ReasonCode],
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -154,6 +220,10 @@ impl<'a> ExplanationSet<'a> {
             set,
         }
     }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).context("Unable to serialize explanation set")
+    }
 }
 
 impl_event!(ExplanationSet<'_>, "disposition_explanations");
@@ -161,6 +231,7 @@ impl_event!(ExplanationSet<'_>, "disposition_explanations");
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
 
     #[test]
     pub fn add_reason() {
@@ -168,7 +239,117 @@ mod tests {
 
         explanation.add_reason("test");
 
-        let expected = vec!["test".to_string()];
+        let expected = vec![ExplanationReason::new("reason", "test")];
         assert_eq!(explanation.reasons(), expected);
     }
+
+    #[test]
+    pub fn add_kv_reason() {
+        let mut explanation = Explanation::default();
+
+        explanation.add_kv_reason("balance", dec!(0.5));
+
+        let expected = vec![ExplanationReason::new("balance", "0.5")];
+        assert_eq!(explanation.reasons(), expected);
+    }
+
+    #[test]
+    pub fn option_explanation_add_reason_uses_message_key() {
+        let mut explanation = Some(Explanation::default());
+
+        explanation.add_reason("test".to_string());
+        explanation.with_reason(|| "lazy test");
+
+        let expected = vec![
+            ExplanationReason::new("reason", "test"),
+            ExplanationReason::new("reason", "lazy test"),
+        ];
+        assert_eq!(explanation.expect("in test").reasons(), expected);
+    }
+
+    #[test]
+    pub fn display_explanation() {
+        let mut explanation = Explanation::default();
+        explanation.add_reason("Cancelling existing orders");
+        explanation.add_kv_reason("amount", dec!(2));
+
+        assert_eq!(
+            explanation.to_string(),
+            "reason=Cancelling existing orders, amount=2"
+        );
+    }
+
+    #[test]
+    pub fn serialize_explanation() {
+        let mut explanation = Explanation::default();
+        explanation.add_reason("test");
+
+        let json = serde_json::to_value(&explanation).expect("serialize explanation");
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "reasons": [{ "key": "reason", "value": "test" }] })
+        );
+    }
+
+    #[test]
+    pub fn add_reason_with_code() {
+        let mut explanation = Explanation::default();
+
+        explanation.add_reason_with_code(ReasonCode::PriceCrossed, "crossed");
+        explanation.add_reason_with_code(ReasonCode::PriceCrossed, "crossed again");
+
+        assert_eq!(explanation.get_reason_codes(), &[ReasonCode::PriceCrossed]);
+        let json = serde_json::to_value(&explanation).expect("serialize explanation");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "reasons": [
+                    { "key": "reason", "value": "crossed" },
+                    { "key": "reason", "value": "crossed again" },
+                ],
+                "reason_codes": ["PriceCrossed"],
+            })
+        );
+    }
+
+    #[test]
+    pub fn explanation_set_to_json() {
+        let reasons = vec![
+            ExplanationReason::new("reason", "Existing amount is enough"),
+            ExplanationReason::new("amount", dec!(2)),
+        ];
+        let explanation_set = ExplanationSet::new(
+            ExchangeId::new("Binance"),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            vec![PriceLevelExplanation {
+                mode_name: "Disposition".to_string(),
+                price: dec!(100.5),
+                amount: dec!(2),
+                reasons: &reasons,
+                reason_codes: &[ReasonCode::RateLimited],
+            }],
+        );
+
+        let json = explanation_set
+            .to_json()
+            .expect("serialize explanation set");
+        let json: serde_json::Value = serde_json::from_str(&json).expect("parse json");
+
+        assert_eq!(json["exchange_id"], "Binance");
+        assert_eq!(json["currency_pair"], "btc/usdt");
+        assert_eq!(
+            json["set"],
+            serde_json::json!([{
+                "mode_name": "Disposition",
+                "price": "100.5",
+                "amount": "2",
+                "reasons": [
+                    { "key": "reason", "value": "Existing amount is enough" },
+                    { "key": "amount", "value": "2" },
+                ],
+                "reason_codes": ["RateLimited"],
+            }])
+        );
+    }
 }
@@ -118,6 +118,29 @@ pub fn spawn_future(
     )
 }
 
+/// Spawn future that is restarted after panic up to `max_retries` times if
+/// `SpawnFutureFlags::RETRY_ON_PANIC` is set. Graceful shutdown is started only after all retries.
+/// Other nuances are the same as spawn_future()
+pub fn spawn_future_with_retry<F, Fut>(
+    action_name: &str,
+    flags: SpawnFutureFlags,
+    max_retries: u32,
+    action: F,
+) -> tokio::task::JoinHandle<FutureOutcome>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    mmb_utils::infrastructure::spawn_future_with_retry(
+        action_name,
+        flags,
+        max_retries,
+        action,
+        spawn_graceful_shutdown,
+        get_futures_cancellation_token(),
+    )
+}
+
 /// Spawn standalone future with logging and error, panic and cancellation handling.
 ///
 /// This fn is needed to call long-working synchronous code inside of a future,
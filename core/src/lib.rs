@@ -33,8 +33,9 @@ pub mod explanation;
 pub mod lifecycle;
 pub mod math;
 pub mod order_book;
-pub(crate) mod services;
+pub mod services;
 pub mod settings;
+pub mod telemetry;
 pub mod text;
 
 #[cfg(test)]
@@ -1,23 +1,44 @@
+use anyhow::Result;
+use futures::future::{join_all, BoxFuture};
 use futures::{Future, FutureExt};
-use mmb_utils::nothing_to_do;
+use mmb_domain::events::{ExchangeEvents, LifecycleState};
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::{nothing_to_do, DateTime};
 use tokio::sync::{Mutex, MutexGuard};
 use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
 
 use std::panic;
 use std::sync::{Arc, Weak};
 
+use crate::infrastructure::spawn_future_ok;
 use crate::lifecycle::trading_engine::EngineContext;
+use crate::misc::time::time_manager;
+use crate::settings::MaintenanceWindow;
 use mmb_utils::cancellation_token::CancellationToken;
 
+/// Max duration of every shutdown hook execution
+const SHUTDOWN_HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, Result<()>> + Send>;
+
 #[derive(Clone, Copy, Debug)]
 pub enum ActionAfterGracefulShutdown {
     Nothing,
     Restart,
 }
 
+#[derive(Default)]
+struct Pause {
+    paused_until: Option<DateTime>,
+    exchange_events: Option<ExchangeEvents>,
+}
+
 pub struct AppLifetimeManager {
     cancellation_token: CancellationToken,
     engine_context: Mutex<Option<Weak<EngineContext>>>,
+    pause: parking_lot::Mutex<Pause>,
+    shutdown_hooks: parking_lot::Mutex<Vec<(String, ShutdownHook)>>,
     pub futures_cancellation_token: CancellationToken,
 }
 
@@ -26,6 +47,8 @@ impl AppLifetimeManager {
         Arc::new(Self {
             cancellation_token,
             engine_context: Mutex::new(None),
+            pause: parking_lot::Mutex::new(Pause::default()),
+            shutdown_hooks: parking_lot::Mutex::new(Vec::new()),
             futures_cancellation_token: CancellationToken::default(),
         })
     }
@@ -41,6 +64,125 @@ impl AppLifetimeManager {
             .try_lock()
             .expect("method should be invoked just after creation when there are no aliases");
         *engine_context_guard = Some(Arc::downgrade(&engine_context));
+
+        self.pause.lock().exchange_events = Some(engine_context.exchange_events());
+    }
+
+    pub fn lifecycle_state(&self) -> LifecycleState {
+        match self.pause.lock().paused_until {
+            None => LifecycleState::Running,
+            Some(_) => LifecycleState::Paused,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lifecycle_state() == LifecycleState::Paused
+    }
+
+    /// Stop placing orders until specified time. If trading is already paused, pause is extended.
+    /// Trading is resumed automatically when pause expires
+    pub fn pause_until(self: &Arc<Self>, until: DateTime, reason: &str) {
+        let mut pause = self.pause.lock();
+        if let Some(paused_until) = pause.paused_until {
+            if until > paused_until {
+                log::debug!("Trading pause is extended until {until}: {reason}");
+                pause.paused_until = Some(until);
+            }
+            return;
+        }
+
+        log::warn!("Trading is paused until {until}: {reason}");
+        pause.paused_until = Some(until);
+        broadcast_lifecycle_state(&pause, LifecycleState::Paused);
+        drop(pause);
+
+        let this = self.clone();
+        spawn_future_ok(
+            "Resume trading after pause",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            async move {
+                loop {
+                    let paused_until = match this.pause.lock().paused_until {
+                        Some(paused_until) => paused_until,
+                        None => return,
+                    };
+
+                    let delay = (paused_until - time_manager::now())
+                        .to_std()
+                        .unwrap_or_default();
+                    tokio::time::sleep(delay).await;
+
+                    if this.resume_if_pause_expired(time_manager::now()) {
+                        return;
+                    }
+                }
+            },
+        );
+    }
+
+    fn resume_if_pause_expired(&self, now: DateTime) -> bool {
+        let mut pause = self.pause.lock();
+        match pause.paused_until {
+            Some(paused_until) if paused_until > now => false,
+            _ => {
+                log::info!("Trading is resumed after pause");
+                pause.paused_until = None;
+                broadcast_lifecycle_state(&pause, LifecycleState::Running);
+                true
+            }
+        }
+    }
+
+    /// Pause trading during scheduled maintenance window
+    pub(crate) fn schedule_maintenance(self: &Arc<Self>, window: MaintenanceWindow) {
+        if window.end <= time_manager::now() {
+            return;
+        }
+
+        let this = self.clone();
+        spawn_future_ok(
+            "Scheduled maintenance pause",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            async move {
+                let delay = (window.start - time_manager::now())
+                    .to_std()
+                    .unwrap_or_default();
+                tokio::time::sleep(delay).await;
+
+                this.pause_until(window.end, "scheduled exchange maintenance");
+            },
+        );
+    }
+
+    /// Register async cleanup of user code (flush files, notify external systems), which is run
+    /// during graceful shutdown before services are stopped
+    pub fn register_shutdown_hook(
+        &self,
+        name: &str,
+        hook: impl FnOnce() -> BoxFuture<'static, Result<()>> + Send + 'static,
+    ) {
+        self.shutdown_hooks
+            .lock()
+            .push((name.to_owned(), Box::new(hook)));
+    }
+
+    /// Run all registered shutdown hooks concurrently. Every hook is limited by `SHUTDOWN_HOOK_TIMEOUT`
+    pub(crate) async fn run_shutdown_hooks(&self) {
+        let hooks = std::mem::take(&mut *self.shutdown_hooks.lock());
+
+        let hooks_futures = hooks.into_iter().map(|(name, hook)| async move {
+            let hook_future = panic::AssertUnwindSafe(hook()).catch_unwind();
+            match timeout(SHUTDOWN_HOOK_TIMEOUT, hook_future).await {
+                Ok(Ok(Ok(()))) => log::trace!("Shutdown hook '{name}' finished"),
+                Ok(Ok(Err(err))) => log::error!("Shutdown hook '{name}' failed: {err:?}"),
+                Ok(Err(_)) => log::error!("Shutdown hook '{name}' panicked"),
+                Err(_) => log::error!(
+                    "Shutdown hook '{name}' was not finished during {} secs",
+                    SHUTDOWN_HOOK_TIMEOUT.as_secs()
+                ),
+            }
+        });
+        join_all(hooks_futures).await;
     }
 
     pub fn spawn_graceful_shutdown(&self, reason: &str) -> Option<JoinHandle<()>> {
@@ -96,6 +238,14 @@ impl AppLifetimeManager {
     }
 }
 
+fn broadcast_lifecycle_state(pause: &Pause, state: LifecycleState) {
+    if let Some(exchange_events) = &pause.exchange_events {
+        if let Err(err) = exchange_events.broadcast_lifecycle_state(state) {
+            log::warn!("{err:?}");
+        }
+    }
+}
+
 fn start_graceful_shutdown_inner(
     engine_context_guard: MutexGuard<'_, Option<Weak<EngineContext>>>,
     reason: &str,
@@ -117,3 +267,64 @@ fn start_graceful_shutdown_inner(
         Some(ctx) => Some(ctx.graceful_shutdown(action, futures_cancellation_token)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::init_lifetime_manager;
+    use anyhow::bail;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn trading_is_resumed_after_pause() {
+        let lifetime_manager = init_lifetime_manager();
+        assert_eq!(lifetime_manager.lifecycle_state(), LifecycleState::Running);
+
+        let paused_until = time_manager::now() + chrono::Duration::milliseconds(100);
+        lifetime_manager.pause_until(paused_until, "test maintenance");
+        assert_eq!(lifetime_manager.lifecycle_state(), LifecycleState::Paused);
+
+        // pause is extended by the next maintenance signal
+        lifetime_manager.pause_until(
+            paused_until + chrono::Duration::milliseconds(200),
+            "test maintenance",
+        );
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(lifetime_manager.is_paused());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(lifetime_manager.lifecycle_state(), LifecycleState::Running);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn all_shutdown_hooks_are_run() {
+        let lifetime_manager = AppLifetimeManager::new(CancellationToken::new());
+        let finished_count = Arc::new(AtomicUsize::new(0));
+
+        for name in ["first", "second"] {
+            let finished_count = finished_count.clone();
+            lifetime_manager.register_shutdown_hook(name, move || {
+                async move {
+                    let _ = finished_count.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+                .boxed()
+            });
+        }
+        lifetime_manager
+            .register_shutdown_hook("failed", || async move { bail!("test error") }.boxed());
+        lifetime_manager.register_shutdown_hook("hanging", || std::future::pending().boxed());
+        lifetime_manager
+            .register_shutdown_hook("panicked", || async move { panic!("test panic") }.boxed());
+
+        let started = tokio::time::Instant::now();
+        lifetime_manager.run_shutdown_hooks().await;
+
+        assert_eq!(finished_count.load(Ordering::SeqCst), 2);
+        assert_eq!(started.elapsed(), SHUTDOWN_HOOK_TIMEOUT);
+
+        // hooks are run only once
+        lifetime_manager.run_shutdown_hooks().await;
+        assert_eq!(finished_count.load(Ordering::SeqCst), 2);
+    }
+}
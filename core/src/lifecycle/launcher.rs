@@ -1,22 +1,29 @@
 use crate::balance::manager::balance_manager::BalanceManager;
-use crate::config::{load_pretty_settings, try_load_settings};
+use crate::config::{load_pretty_settings, try_load_settings, validate_settings};
 use crate::database::events::recorder::EventRecorder;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
 use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
 use crate::exchanges::general::exchange::Exchange;
 use crate::exchanges::general::exchange_creation::create_exchange;
-use crate::exchanges::general::exchange_creation::create_timeout_manager;
+use crate::exchanges::general::exchange_creation::{
+    check_exchange_accounts, create_timeout_manager,
+};
 use crate::exchanges::internal_events_loop::InternalEventsLoop;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::exchanges::traits::ExchangeClientBuilder;
 use crate::infrastructure::spawn_future;
 use crate::infrastructure::{init_lifetime_manager, spawn_by_timer, spawn_future_ok};
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
-use crate::lifecycle::trading_engine::{EngineContext, TradingEngine};
+use crate::lifecycle::trading_engine::{
+    load_balance_reservations_snapshot, save_balance_reservations_snapshot, EngineContext,
+    TradingEngine,
+};
 use crate::rpc::config_waiter::ConfigWaiter;
 use crate::rpc::core_api::CoreApi;
 use crate::services::cleanup_orders::CleanupOrdersService;
+use crate::services::order_audit_log::OrderAuditLogService;
 use crate::settings::{AppSettings, CoreSettings};
+use crate::telemetry;
 use anyhow::{anyhow, bail, Context, Result};
 use core::fmt::Debug;
 use dashmap::DashMap;
@@ -32,13 +39,15 @@ use mmb_utils::logger::print_info;
 use mmb_utils::nothing_to_do;
 use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
 use std::sync::{Arc, Weak};
 use std::time::Duration;
+use thiserror::Error;
 use tokio::signal;
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::time::timeout;
@@ -48,25 +57,74 @@ use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
 use crate::services::cleanup_database::CleanupDatabaseService;
 use crate::services::exchange_time_latency::ExchangeTimeLatencyService;
 use crate::services::live_ranges::LiveRangesService;
+use crate::services::order_book_backfill::OrderBookBackfillService;
 
 pub struct EngineBuildConfig {
     pub supported_exchange_clients: HashMap<ExchangeId, Box<dyn ExchangeClientBuilder + 'static>>,
+    /// Max count of simultaneously used accounts for each exchange
+    pub account_counts: HashMap<ExchangeId, u8>,
+}
+
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+#[error("ExchangeClientBuilder for exchange {exchange_id} is specified more than once in EngineBuildConfig")]
+pub struct DuplicateExchangeError {
+    pub exchange_id: ExchangeId,
 }
 
 impl EngineBuildConfig {
+    /// Panics if there are several builders with the same `ExchangeId`
     pub fn new(client_builders: Vec<Box<dyn ExchangeClientBuilder>>) -> Self {
-        let mut supported_exchange_clients = HashMap::new();
+        Self::try_new(client_builders).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_new(
+        client_builders: Vec<Box<dyn ExchangeClientBuilder>>,
+    ) -> Result<Self, DuplicateExchangeError> {
+        let mut config = EngineBuildConfig {
+            supported_exchange_clients: HashMap::new(),
+            account_counts: HashMap::new(),
+        };
         for builder in client_builders {
-            supported_exchange_clients.insert(builder.get_exchange_id(), builder);
+            config.try_add_builder(builder, 1)?;
         }
 
-        EngineBuildConfig {
-            supported_exchange_clients,
+        Ok(config)
+    }
+
+    /// Register exchange with up to `count` simultaneously used accounts, e.g. `Binance_0` and
+    /// `Binance_1` for spot and margin trading simultaneously.
+    /// Panics if builder with the same `ExchangeId` is already registered
+    pub fn with_account_count(
+        mut self,
+        exchange_builder: Box<dyn ExchangeClientBuilder>,
+        count: u8,
+    ) -> Self {
+        self.try_add_builder(exchange_builder, count)
+            .unwrap_or_else(|err| panic!("{err}"));
+        self
+    }
+
+    fn try_add_builder(
+        &mut self,
+        builder: Box<dyn ExchangeClientBuilder>,
+        account_count: u8,
+    ) -> Result<(), DuplicateExchangeError> {
+        let exchange_id = builder.get_exchange_id();
+        if self
+            .supported_exchange_clients
+            .insert(exchange_id, builder)
+            .is_some()
+        {
+            return Err(DuplicateExchangeError { exchange_id });
         }
+
+        self.account_counts.insert(exchange_id, account_count);
+        Ok(())
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum InitSettings<StrategySettings: Clone> {
     Directly(AppSettings<StrategySettings>),
     Load {
@@ -84,8 +142,9 @@ where
 {
     let (wait_config_tx, mut wait_config_rx) = mpsc::channel::<()>(10);
 
-    let wait_for_config = ConfigWaiter::create_and_start(wait_config_tx)
-        .expect("Failed to start RPC server to waiting for config");
+    let wait_for_config =
+        ConfigWaiter::create_and_start(wait_config_tx, validate_settings::<StrategySettings>)
+            .expect("Failed to start RPC server to waiting for config");
 
     let mut work_finished_receiver = wait_for_config
         .work_finished_receiver
@@ -151,8 +210,11 @@ where
         }
     };
 
+    telemetry::init_tracer(settings.core.otlp_endpoint.as_deref())?;
+
     let (events_sender, events_receiver) = broadcast::channel(CHANNEL_MAX_EVENTS_COUNT);
 
+    check_exchange_accounts(&settings.core, build_settings)?;
     let timeout_manager = create_timeout_manager(&settings.core, build_settings);
 
     let exchange_account_ids = settings
@@ -169,7 +231,7 @@ where
             .await
             .context("unable apply db migrations")?;
 
-        let pool = PgPool::create(&db.url, 5)
+        let pool = PgPool::create_with_settings(&db.url, &db.pool_settings())
             .await
             .with_context(|| format!("from `launcher` with connection_string: {}", &db.url))?;
 
@@ -178,9 +240,13 @@ where
         (None, None)
     };
 
-    let event_recorder = EventRecorder::start(pool.clone(), postponed_events_dir)
-        .await
-        .expect("can't start EventRecorder");
+    let event_recorder = EventRecorder::start(
+        pool.clone(),
+        postponed_events_dir,
+        settings.core.jitter_fraction(),
+    )
+    .await
+    .expect("can't start EventRecorder");
 
     let exchanges = create_exchanges(
         &settings.core,
@@ -222,6 +288,13 @@ where
 
     start_updating_balances(&lifetime_manager, &balance_manager);
 
+    if let Some(expiry_secs) = settings.core.balance_reservation_expiry_secs {
+        balance_manager
+            .lock()
+            .set_reservation_expiry(Some(chrono::Duration::seconds(expiry_secs as i64)));
+        start_unreserving_expired_reservations(&balance_manager);
+    }
+
     let (finish_graceful_shutdown_tx, finish_graceful_shutdown_rx) = oneshot::channel();
 
     let engine_context = EngineContext::new(
@@ -236,6 +309,12 @@ where
         event_recorder,
     );
 
+    for exchange_settings in &settings.core.exchanges {
+        for window in &exchange_settings.maintenance_windows {
+            lifetime_manager.schedule_maintenance(window.clone());
+        }
+    }
+
     Ok((
         events_receiver,
         settings,
@@ -268,8 +347,45 @@ fn start_updating_balances(
     );
 }
 
+/// Snapshot is saved periodically in addition to graceful shutdown to restore reservations after crash
+fn start_saving_balance_reservations_snapshot(
+    balance_manager: &Arc<Mutex<BalanceManager>>,
+    path: PathBuf,
+) {
+    spawn_by_timer(
+        "Save balance reservations snapshot",
+        Duration::from_secs(10),
+        Duration::from_secs(10),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        {
+            let balance_manager = balance_manager.clone();
+            move || {
+                let balance_manager = balance_manager.clone();
+                let path = path.clone();
+                async move { save_balance_reservations_snapshot(&balance_manager, &path) }
+            }
+        },
+    );
+}
+
+fn start_unreserving_expired_reservations(balance_manager: &Arc<Mutex<BalanceManager>>) {
+    spawn_by_timer(
+        "Unreserve expired reservations",
+        Duration::from_secs(10),
+        Duration::from_secs(10),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        {
+            let balance_manager = balance_manager.clone();
+            move || {
+                let balance_manager = balance_manager.clone();
+                async move { balance_manager.lock().unreserve_expired_reservations() }
+            }
+        },
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
-fn run_services<'a, StrategySettings>(
+fn run_services<StrategySettings>(
     engine_context: Arc<EngineContext>,
     events_receiver: broadcast::Receiver<ExchangeEvent>,
     settings: AppSettings<StrategySettings>,
@@ -281,7 +397,7 @@ fn run_services<'a, StrategySettings>(
     exchange_time_latency_service: Arc<ExchangeTimeLatencyService>,
 ) -> TradingEngine<StrategySettings>
 where
-    StrategySettings: Clone + Debug + Deserialize<'a> + Serialize,
+    StrategySettings: Clone + Debug + DeserializeOwned + Serialize,
 {
     let internal_events_loop = InternalEventsLoop::new();
     engine_context
@@ -292,6 +408,11 @@ where
         engine_context.lifetime_manager.clone(),
         load_pretty_settings(init_user_settings),
         engine_context.statistic_service.clone(),
+        engine_context.exchanges.clone(),
+        engine_context.balance_manager.clone(),
+        validate_settings::<StrategySettings>,
+        engine_context.exchange_events(),
+        engine_context.executor_state_graph.clone(),
     )
     .expect("Unable to start control panel");
     engine_context
@@ -312,6 +433,23 @@ where
         ),
     );
 
+    if let Some(order_audit_log_settings) = engine_context.core_settings.order_audit_log.clone() {
+        let order_audit_log_service = OrderAuditLogService::new();
+        engine_context
+            .shutdown_service
+            .register_core_service(order_audit_log_service.clone());
+
+        spawn_future(
+            "order_audit_log start",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            order_audit_log_service.start(
+                order_audit_log_settings,
+                engine_context.get_events_channel(),
+                engine_context.lifetime_manager.stop_token(),
+            ),
+        );
+    }
+
     if let Some(data_services) = data_services {
         engine_context
             .shutdown_service
@@ -461,6 +599,20 @@ where
         pool,
     ) = unwrap_or_handle_panic(action_outcome, message_template, None)??;
 
+    if let Some(path) = &engine_context
+        .core_settings
+        .balance_reservations_snapshot_path
+    {
+        load_balance_reservations_snapshot(
+            &engine_context.exchanges,
+            &engine_context.balance_manager,
+            path,
+        )
+        .await;
+
+        start_saving_balance_reservations_snapshot(&engine_context.balance_manager, path.clone());
+    }
+
     let cloned_lifetime_manager = engine_context.lifetime_manager.clone();
     let action = async move {
         signal::ctrl_c().await.expect("failed to listen for event");
@@ -481,6 +633,17 @@ where
     let data_services = match pool {
         None => None,
         Some(pool) => {
+            if let Some(backfill_settings) = &engine_context.core_settings.order_book_backfill {
+                OrderBookBackfillService::new(
+                    backfill_settings.clone(),
+                    engine_context.exchanges.clone(),
+                    engine_context.event_recorder.clone(),
+                    pool.clone(),
+                )
+                .run()
+                .await;
+            }
+
             let session_id = Uuid::new_v4().to_string();
             let live_range_service = Arc::new(LiveRangesService::new(session_id, pool.clone()));
             let cleanup_database_service = Arc::new(CleanupDatabaseService::new(pool));
@@ -533,6 +696,8 @@ pub async fn create_exchanges(
     join_all(core_settings.exchanges.iter().map(|x| {
         create_exchange(
             x,
+            core_settings.jitter_fraction(),
+            core_settings.price_band_percent,
             build_settings,
             events_channel.clone(),
             lifetime_manager.clone(),
@@ -543,3 +708,127 @@ pub async fn create_exchanges(
     }))
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+    use crate::exchanges::traits::ExchangeClientBuilderResult;
+    use crate::settings::ExchangeSettings;
+    use mmb_domain::order::pool::OrdersPool;
+    use rstest::rstest;
+
+    struct TestBuilder(&'static str);
+
+    impl ExchangeClientBuilder for TestBuilder {
+        fn create_exchange_client(
+            &self,
+            _exchange_settings: ExchangeSettings,
+            _events_channel: broadcast::Sender<ExchangeEvent>,
+            _lifetime_manager: Arc<AppLifetimeManager>,
+            _timeout_manager: Arc<TimeoutManager>,
+            _orders: Arc<OrdersPool>,
+        ) -> ExchangeClientBuilderResult {
+            unimplemented!("not needed for EngineBuildConfig tests")
+        }
+
+        fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+            unimplemented!("not needed for EngineBuildConfig tests")
+        }
+
+        fn get_exchange_id(&self) -> ExchangeId {
+            ExchangeId::new(self.0)
+        }
+    }
+
+    #[test]
+    fn try_new_with_unique_exchange_ids() {
+        let config = EngineBuildConfig::try_new(vec![
+            Box::new(TestBuilder("Binance")),
+            Box::new(TestBuilder("Bitmex")),
+        ])
+        .expect("builders have unique exchange ids");
+
+        assert_eq!(config.supported_exchange_clients.len(), 2);
+    }
+
+    #[test]
+    fn try_new_with_duplicate_exchange_ids() {
+        let result = EngineBuildConfig::try_new(vec![
+            Box::new(TestBuilder("Binance")),
+            Box::new(TestBuilder("Bitmex")),
+            Box::new(TestBuilder("Binance")),
+        ]);
+
+        assert_eq!(
+            result.err(),
+            Some(DuplicateExchangeError {
+                exchange_id: ExchangeId::new("Binance")
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "ExchangeClientBuilder for exchange Binance is specified more than once"
+    )]
+    fn new_panics_with_duplicate_exchange_ids() {
+        EngineBuildConfig::new(vec![
+            Box::new(TestBuilder("Binance")),
+            Box::new(TestBuilder("Binance")),
+        ]);
+    }
+
+    fn core_settings(exchange_account_ids: &[&str]) -> CoreSettings {
+        CoreSettings {
+            exchanges: exchange_account_ids
+                .iter()
+                .map(|x| {
+                    ExchangeSettings::new_short(
+                        x.parse().expect("in test"),
+                        "api_key".into(),
+                        "secret_key".into(),
+                        false,
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::single_account(&["Binance_0"])]
+    #[case::single_not_first_account(&["Binance_1"])]
+    #[case::several_exchanges(&["Binance_1", "Bitmex_0"])]
+    fn check_exchange_accounts_succeeded(#[case] exchange_account_ids: &[&str]) {
+        let config = EngineBuildConfig::new(vec![
+            Box::new(TestBuilder("Binance")),
+            Box::new(TestBuilder("Bitmex")),
+        ]);
+
+        check_exchange_accounts(&core_settings(exchange_account_ids), &config).expect("in test");
+    }
+
+    #[test]
+    fn check_exchange_accounts_with_account_count() {
+        let config = EngineBuildConfig::new(vec![Box::new(TestBuilder("Bitmex"))])
+            .with_account_count(Box::new(TestBuilder("Binance")), 2);
+
+        check_exchange_accounts(&core_settings(&["Binance_0", "Binance_3"]), &config)
+            .expect("in test");
+        assert!(check_exchange_accounts(
+            &core_settings(&["Binance_0", "Binance_1", "Binance_2"]),
+            &config
+        )
+        .is_err());
+    }
+
+    #[rstest]
+    #[case::several_accounts_without_account_count(&["Binance_0", "Binance_1"])]
+    #[case::not_registered_exchange(&["Bitmex_0"])]
+    fn check_exchange_accounts_failed(#[case] exchange_account_ids: &[&str]) {
+        let config = EngineBuildConfig::new(vec![Box::new(TestBuilder("Binance"))]);
+
+        assert!(check_exchange_accounts(&core_settings(exchange_account_ids), &config).is_err());
+    }
+}
@@ -2,7 +2,10 @@ use super::launcher::unwrap_or_handle_panic;
 use crate::balance::manager::balance_manager::BalanceManager;
 use crate::database::events::recorder::EventRecorder;
 use crate::disposition_execution::executor::DispositionExecutorService;
+use crate::disposition_execution::state_graph::ExecutorStateGraph;
 use crate::disposition_execution::strategy::DispositionStrategy;
+use crate::disposition_execution::strategy_metrics::{NoopStrategyMetrics, StrategyMetrics};
+use crate::disposition_execution::watchdog::{spawn_decision_watchdog, DecisionWatchdog};
 use crate::exchanges::block_reasons;
 use crate::exchanges::exchange_blocker::BlockType;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
@@ -16,25 +19,38 @@ use crate::order_book::local_snapshot_service::LocalSnapshotsService;
 use crate::settings::DispositionStrategySettings;
 use crate::settings::{AppSettings, CoreSettings};
 use crate::statistic_service::{StatisticEventHandler, StatisticService};
-use anyhow::Result;
+use crate::telemetry;
+use anyhow::{Context, Result};
 use dashmap::DashMap;
 use futures::future::join_all;
 use futures::FutureExt;
+use itertools::Itertools;
 use mmb_domain::events::{ExchangeEvent, ExchangeEvents};
-use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderInfo, OrderSide, UserOrder,
+};
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::logger::print_info;
 use mmb_utils::nothing_to_do;
 use mmb_utils::send_expected::SendExpected;
 use parking_lot::Mutex;
+use std::any::Any;
+use std::collections::HashMap;
 use std::panic::AssertUnwindSafe;
+use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use thiserror::Error;
 use tokio::sync::{broadcast, oneshot};
 use tokio::time::{timeout, Duration};
 
+/// Max duration of waiting for fills of orders flattening net positions on graceful shutdown
+const FLATTEN_POSITIONS_TIMEOUT: Duration = Duration::from_secs(30);
+const FLATTENING_STRATEGY_NAME: &str = "flatten_on_shutdown";
+
 pub trait Service: Send + Sync + 'static {
     fn name(&self) -> &str;
 
@@ -44,6 +60,24 @@ pub trait Service: Send + Sync + 'static {
     fn graceful_shutdown(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>>;
 }
 
+#[derive(Error, Debug, Eq, PartialEq, Clone)]
+#[error("Exchange {exchange_account_id} not found in EngineContext")]
+pub struct ExchangeNotFoundError {
+    pub exchange_account_id: ExchangeAccountId,
+}
+
+fn get_exchange(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+    exchange_account_id: ExchangeAccountId,
+) -> Result<Arc<Exchange>, ExchangeNotFoundError> {
+    exchanges
+        .get(&exchange_account_id)
+        .map(|x| x.value().clone())
+        .ok_or(ExchangeNotFoundError {
+            exchange_account_id,
+        })
+}
+
 pub struct EngineContext {
     pub core_settings: CoreSettings,
     pub exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
@@ -54,6 +88,8 @@ pub struct EngineContext {
     pub balance_manager: Arc<Mutex<BalanceManager>>,
     pub event_recorder: Arc<EventRecorder>,
     pub statistic_service: Arc<StatisticService>,
+    /// State of DispositionExecutor published for control panel
+    pub executor_state_graph: ExecutorStateGraph,
     is_graceful_shutdown_started: AtomicBool,
     exchange_events: ExchangeEvents,
     finish_graceful_shutdown_sender: Mutex<Option<oneshot::Sender<ActionAfterGracefulShutdown>>>,
@@ -61,7 +97,7 @@ pub struct EngineContext {
 
 impl EngineContext {
     #[allow(clippy::too_many_arguments)]
-    pub(crate) fn new(
+    pub fn new(
         core_settings: CoreSettings,
         exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
         exchange_events: ExchangeEvents,
@@ -72,7 +108,7 @@ impl EngineContext {
         balance_manager: Arc<Mutex<BalanceManager>>,
         event_recorder: Arc<EventRecorder>,
     ) -> Arc<Self> {
-        let statistic_service = StatisticService::new();
+        let statistic_service = StatisticService::new(core_settings.fee_model.clone());
         let engine_context = Arc::new(EngineContext {
             core_settings,
             exchanges,
@@ -83,6 +119,7 @@ impl EngineContext {
             balance_manager,
             event_recorder,
             statistic_service,
+            executor_state_graph: ExecutorStateGraph::new(),
             is_graceful_shutdown_started: Default::default(),
             exchange_events,
             finish_graceful_shutdown_sender: Mutex::new(Some(finish_graceful_shutdown_sender)),
@@ -116,11 +153,18 @@ impl EngineContext {
             )
         });
 
+        self.lifetime_manager.run_shutdown_hooks().await;
+
         self.lifetime_manager.stop_token().cancel();
 
         self.shutdown_service.user_lvl_shutdown().await;
         self.exchange_blocker.stop_blocker().await;
 
+        // reservations should be saved before cancelling orders which they are approved for
+        if let Some(path) = &self.core_settings.balance_reservations_snapshot_path {
+            save_balance_reservations_snapshot(&self.balance_manager, path);
+        }
+
         let cancellation_token = CancellationToken::default();
         const TIMEOUT: Duration = Duration::from_secs(5);
 
@@ -140,6 +184,30 @@ impl EngineContext {
             }
         }
 
+        if self.core_settings.flatten_on_shutdown {
+            // positions should be flattened even if orders cancelling has been stopped by timeout
+            let flattening_cancellation_token = CancellationToken::default();
+            match timeout(
+                FLATTEN_POSITIONS_TIMEOUT,
+                flatten_net_positions(
+                    &self.exchanges,
+                    &self.balance_manager,
+                    flattening_cancellation_token.clone(),
+                ),
+            )
+            .await
+            {
+                Ok(()) => (),
+                Err(_) => {
+                    flattening_cancellation_token.cancel();
+                    log::error!(
+                        "Timeout {} secs is exceeded: net positions flattening has been stopped",
+                        FLATTEN_POSITIONS_TIMEOUT.as_secs(),
+                    );
+                }
+            }
+        }
+
         match timeout(
             TIMEOUT,
             close_active_positions(&self.exchanges, cancellation_token.clone()),
@@ -164,6 +232,8 @@ impl EngineContext {
             Ok(Ok(())) => nothing_to_do(),
         }
 
+        telemetry::shutdown_tracer().await;
+
         let disconnect_websockets = self
             .exchanges
             .iter()
@@ -185,9 +255,26 @@ impl EngineContext {
         print_info("Graceful shutdown finished");
     }
 
+    pub(crate) fn exchange_events(&self) -> ExchangeEvents {
+        self.exchange_events.clone()
+    }
+
+    /// Error is returned if exchange isn't initialized yet or isn't specified in settings
+    pub fn get_exchange(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> Result<Arc<Exchange>, ExchangeNotFoundError> {
+        get_exchange(&self.exchanges, exchange_account_id)
+    }
+
     pub fn get_events_channel(&self) -> broadcast::Receiver<ExchangeEvent> {
         self.exchange_events.get_events_channel()
     }
+
+    /// Broadcast strategy-defined event to all receivers of the events channel
+    pub fn broadcast_custom_event(&self, event: impl Any + Send + Sync) -> Result<()> {
+        self.exchange_events.broadcast_custom_event(event)
+    }
 }
 
 async fn cancel_opened_orders(
@@ -223,6 +310,206 @@ async fn close_active_positions(
     log::info!("Closing active positions finished");
 }
 
+async fn flatten_net_positions(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: &Mutex<BalanceManager>,
+    cancellation_token: CancellationToken,
+) {
+    log::info!("Flattening net positions started");
+
+    let mut positions = Vec::new();
+    for exchange in exchanges.iter() {
+        for symbol in exchange.symbols.iter().filter(|x| x.is_derivative) {
+            let currency_pair = symbol.currency_pair();
+            let net_position = balance_manager
+                .lock()
+                .get_total_net_position(&exchange.exchange_account_id, &currency_pair)
+                .unwrap_or_default();
+            positions.push((exchange.clone(), currency_pair, net_position));
+        }
+    }
+
+    join_all(
+        positions
+            .into_iter()
+            .map(|(exchange, currency_pair, net_position)| {
+                flatten_net_position(
+                    exchange,
+                    currency_pair,
+                    net_position,
+                    cancellation_token.clone(),
+                )
+            }),
+    )
+    .await;
+
+    log::info!("Flattening net positions finished");
+}
+
+async fn flatten_net_position(
+    exchange: Arc<Exchange>,
+    currency_pair: CurrencyPair,
+    net_position: Amount,
+    cancellation_token: CancellationToken,
+) {
+    let exchange_account_id = exchange.exchange_account_id;
+    let Some((side, amount)) = flattening_order(net_position) else {
+        return;
+    };
+
+    // paper orders are never filled, so waiting for the flattening order would only delay shutdown
+    if exchange.is_dry_run() {
+        log::info!("Net position {net_position} on {exchange_account_id} {currency_pair} isn't flattened in dry run mode");
+        return;
+    }
+
+    log::info!("Flattening net position {net_position} on {exchange_account_id} {currency_pair}");
+
+    let header = OrderHeader::with_user_order(
+        ClientOrderId::unique_id(),
+        exchange_account_id,
+        currency_pair,
+        side,
+        amount,
+        UserOrder::Market,
+        None,
+        None,
+        FLATTENING_STRATEGY_NAME.to_owned(),
+    )
+    .with_reduce_only(true);
+
+    let order = match exchange
+        .create_order(&header, None, cancellation_token.clone())
+        .await
+    {
+        Ok(order) => order,
+        Err(err) => {
+            log::error!(
+                "Unable to flatten net position on {exchange_account_id} {currency_pair}: {err:?}"
+            );
+            return;
+        }
+    };
+
+    match exchange
+        .wait_order_finish(&order, None, cancellation_token)
+        .await
+    {
+        Ok(order) => log::info!(
+            "Net position on {exchange_account_id} {currency_pair} flattened by order {} with status {:?}",
+            order.client_order_id(),
+            order.status()
+        ),
+        Err(err) => log::error!(
+            "Unable to wait flattening order {} on {exchange_account_id} {currency_pair}: {err:?}",
+            order.client_order_id()
+        ),
+    }
+}
+
+/// Side and amount of order that brings net position to zero
+fn flattening_order(net_position: Amount) -> Option<(OrderSide, Amount)> {
+    if net_position.is_zero() {
+        return None;
+    }
+
+    let side = match net_position.is_sign_positive() {
+        true => OrderSide::Sell,
+        false => OrderSide::Buy,
+    };
+    Some((side, net_position.abs()))
+}
+
+pub(crate) fn save_balance_reservations_snapshot(
+    balance_manager: &Mutex<BalanceManager>,
+    path: &Path,
+) {
+    let save_result = balance_manager
+        .lock()
+        .reservations_snapshot()
+        .and_then(|snapshot| std::fs::write(path, snapshot).map_err(Into::into));
+
+    match save_result {
+        Ok(()) => log::trace!("Balance reservations snapshot saved to {}", path.display()),
+        Err(err) => log::error!(
+            "Unable to save balance reservations snapshot to {}: {err:?}",
+            path.display()
+        ),
+    }
+}
+
+/// Restore balance reservations saved by previous run if the snapshot file exists.
+/// Only reservations of orders which are still open on exchanges are restored. These orders are
+/// added to orders pool with restored reservation ids, so their fills and cancellation unreserve balance
+pub(crate) async fn load_balance_reservations_snapshot(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: &Mutex<BalanceManager>,
+    path: &Path,
+) {
+    if !path.exists() {
+        return;
+    }
+
+    let restore_result = async {
+        let bytes = std::fs::read(path).context("Unable to read balance reservations snapshot")?;
+        let open_orders = get_open_orders(exchanges).await?;
+        let open_order_ids = open_orders
+            .iter()
+            .map(|(exchange_account_id, orders)| {
+                let client_order_ids = orders
+                    .iter()
+                    .map(|order_info| order_info.client_order_id.clone())
+                    .collect();
+                (*exchange_account_id, client_order_ids)
+            })
+            .collect();
+
+        let reservation_ids = balance_manager
+            .lock()
+            .restore_reservations_snapshot(&bytes, &open_order_ids)?;
+
+        for (exchange_account_id, orders) in &open_orders {
+            if let Some(exchange) = exchanges.get(exchange_account_id) {
+                exchange.add_missing_open_orders(orders, &reservation_ids);
+            }
+        }
+
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+
+    match restore_result {
+        Ok(()) => log::info!(
+            "Balance reservations restored from snapshot {}",
+            path.display()
+        ),
+        Err(err) => log::error!(
+            "Unable to restore balance reservations from snapshot {}: {err:?}",
+            path.display()
+        ),
+    }
+}
+
+async fn get_open_orders(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+) -> Result<HashMap<ExchangeAccountId, Vec<OrderInfo>>> {
+    let exchanges = exchanges.iter().map(|x| x.value().clone()).collect_vec();
+    let open_orders = join_all(exchanges.iter().map(|exchange| async move {
+        let open_orders = exchange.get_open_orders(false).await;
+        (exchange.exchange_account_id, open_orders)
+    }))
+    .await;
+
+    open_orders
+        .into_iter()
+        .map(|(exchange_account_id, open_orders)| {
+            let open_orders = open_orders
+                .with_context(|| format!("Unable to get open orders for {exchange_account_id}"))?;
+            Ok((exchange_account_id, open_orders))
+        })
+        .collect()
+}
+
 pub struct TradingEngine<StrategySettings: Clone> {
     context: Arc<EngineContext>,
     settings: AppSettings<StrategySettings>,
@@ -275,6 +562,18 @@ impl<StrategySettings: Clone> TradingEngine<StrategySettings> {
     pub fn start_disposition_executor(&self, strategy: Box<dyn DispositionStrategy>)
     where
         StrategySettings: DispositionStrategySettings,
+    {
+        self.start_disposition_executor_with_metrics(strategy, Box::new(NoopStrategyMetrics))
+    }
+
+    /// Same as `start_disposition_executor` but latency of every trading context calculation
+    /// is reported to `strategy_metrics`
+    pub fn start_disposition_executor_with_metrics(
+        &self,
+        strategy: Box<dyn DispositionStrategy>,
+        strategy_metrics: Box<dyn StrategyMetrics>,
+    ) where
+        StrategySettings: DispositionStrategySettings,
     {
         let ctx = self.context();
         let settings = self.settings();
@@ -283,18 +582,191 @@ impl<StrategySettings: Clone> TradingEngine<StrategySettings> {
             StatisticEventHandler::new(ctx.get_events_channel(), ctx.statistic_service.clone());
 
         let base_settings = &settings.strategy;
+        if base_settings.dry_run() {
+            let exchange_account_id = base_settings.exchange_account_id();
+            log::warn!("Strategy is started in dry run mode on {exchange_account_id}");
+            match ctx.get_exchange(exchange_account_id) {
+                Ok(exchange) => exchange.set_dry_run(true),
+                Err(err) => {
+                    log::error!("Disposition executor isn't started: {err}");
+                    return;
+                }
+            }
+        }
+
         let disposition_executor_service = DispositionExecutorService::new(
             ctx.clone(),
             ctx.get_events_channel(),
-            LocalSnapshotsService::default(),
+            LocalSnapshotsService::new_with_ttl(
+                HashMap::new(),
+                ctx.core_settings
+                    .order_book_snapshot_ttl_secs
+                    .map(|ttl_secs| chrono::Duration::seconds(ttl_secs as i64)),
+            ),
             base_settings.exchange_account_id(),
             base_settings.currency_pair(),
             strategy,
             ctx.lifetime_manager.stop_token(),
             statistics.stats.clone(),
+            base_settings.price_slots_count(),
+            base_settings.min_profit_bps(),
+            base_settings.order_ttl(),
+            base_settings.timer_interval(),
+            strategy_metrics,
         );
 
+        if let Some(timeout_secs) = ctx.core_settings.stale_decision_timeout_secs {
+            spawn_decision_watchdog(
+                DecisionWatchdog::new(
+                    disposition_executor_service.last_decision_at(),
+                    Duration::from_secs(timeout_secs),
+                ),
+                ctx.lifetime_manager.clone(),
+            );
+        }
+
         ctx.shutdown_service
             .register_user_service(disposition_executor_service);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::{
+        get_test_exchange, get_test_exchange_with_symbol_and_client, get_test_symbol, TestClient,
+    };
+    use crate::infrastructure::init_lifetime_manager;
+    use mmb_domain::order::snapshot::{ExchangeOrderId, OrderStatus, OrderType};
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    fn flattening_test_exchange(exchange_client: TestClient) -> Arc<Exchange> {
+        let symbol = get_test_symbol(true, "PHB", "BTC", "BTC");
+        let (exchange, _events_receiver) =
+            get_test_exchange_with_symbol_and_client(symbol, exchange_client);
+        exchange
+    }
+
+    #[rstest]
+    #[case::long(dec!(1.5), Some((OrderSide::Sell, dec!(1.5))))]
+    #[case::short(dec!(-2), Some((OrderSide::Buy, dec!(2))))]
+    #[case::flat(dec!(0), None)]
+    fn flattening_order_closes_net_position(
+        #[case] net_position: Amount,
+        #[case] expected: Option<(OrderSide, Amount)>,
+    ) {
+        assert_eq!(flattening_order(net_position), expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn flat_net_position_is_not_flattened() {
+        init_lifetime_manager();
+        let exchange_client = TestClient::default();
+        let requests_count = exchange_client.requests_count.clone();
+        let exchange = flattening_test_exchange(exchange_client);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+
+        flatten_net_position(
+            exchange.clone(),
+            currency_pair,
+            dec!(0),
+            CancellationToken::default(),
+        )
+        .await;
+
+        assert_eq!(requests_count.load(Ordering::SeqCst), 0);
+        assert!(exchange.orders.cache_by_client_id.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn net_position_is_not_flattened_in_dry_run() {
+        init_lifetime_manager();
+        let exchange_client = TestClient::default();
+        let requests_count = exchange_client.requests_count.clone();
+        let exchange = flattening_test_exchange(exchange_client);
+        exchange.set_dry_run(true);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+
+        flatten_net_position(
+            exchange.clone(),
+            currency_pair,
+            dec!(1.5),
+            CancellationToken::default(),
+        )
+        .await;
+
+        assert_eq!(requests_count.load(Ordering::SeqCst), 0);
+        assert!(exchange.orders.cache_by_client_id.is_empty());
+    }
+
+    #[rstest]
+    #[case::long(dec!(1.5), OrderSide::Sell, dec!(1.5))]
+    #[case::short(dec!(-2), OrderSide::Buy, dec!(2))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn net_position_is_flattened_by_reduce_only_market_order(
+        #[case] net_position: Amount,
+        #[case] expected_side: OrderSide,
+        #[case] expected_amount: Amount,
+    ) {
+        init_lifetime_manager();
+        let mut exchange_client = TestClient::default();
+        exchange_client.created_exchange_order_id = Some(ExchangeOrderId::new("1".into()));
+        let exchange = flattening_test_exchange(exchange_client);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+
+        // test client never fills orders, so waiting of the order finish is limited by timeout
+        let _ = timeout(
+            Duration::from_millis(200),
+            flatten_net_position(
+                exchange.clone(),
+                currency_pair,
+                net_position,
+                CancellationToken::default(),
+            ),
+        )
+        .await;
+
+        let orders = exchange
+            .orders
+            .cache_by_client_id
+            .iter()
+            .map(|x| x.value().clone())
+            .collect_vec();
+        assert_eq!(orders.len(), 1);
+
+        let header = orders[0].header();
+        assert_eq!(header.currency_pair, currency_pair);
+        assert_eq!(header.side, expected_side);
+        assert_eq!(header.amount, expected_amount);
+        assert!(header.reduce_only);
+        assert_eq!(header.order_type, OrderType::Market);
+        assert_eq!(orders[0].status(), OrderStatus::Created);
+    }
+
+    #[tokio::test]
+    async fn get_existing_exchange() {
+        init_lifetime_manager();
+        let (exchange, _events_receiver) = get_test_exchange(false);
+        let exchange_account_id = exchange.exchange_account_id;
+        let exchanges = DashMap::new();
+        exchanges.insert(exchange_account_id, exchange);
+
+        let exchange = get_exchange(&exchanges, exchange_account_id).expect("in test");
+        assert_eq!(exchange.exchange_account_id, exchange_account_id);
+    }
+
+    #[test]
+    fn get_nonexistent_exchange() {
+        let exchanges = DashMap::new();
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+
+        let err = get_exchange(&exchanges, exchange_account_id).err();
+        assert_eq!(
+            err,
+            Some(ExchangeNotFoundError {
+                exchange_account_id
+            })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::{bail, Context, Result};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
@@ -12,6 +13,57 @@ impl ConvertPercentToRate for Decimal {
     }
 }
 
+/// Product of decimals. Returns error instead of panic if the result doesn't fit into 96-bit mantissa
+pub fn checked_mul(lhs: Decimal, rhs: Decimal) -> Result<Decimal> {
+    lhs.checked_mul(rhs)
+        .with_context(|| format!("Overflow on multiplication {lhs} * {rhs}"))
+}
+
+/// Quotient of decimals. Returns error instead of panic on overflow or division by zero
+pub fn checked_div(lhs: Decimal, rhs: Decimal) -> Result<Decimal> {
+    lhs.checked_div(rhs)
+        .with_context(|| format!("Unable to divide {lhs} by {rhs}"))
+}
+
+/// Average of values weighted by non-negative weights, e.g. VWAP of `(price, amount)` pairs.
+/// If products of values and weights overflow, weights are scaled down relative to the largest
+/// one, so products don't exceed values. Returns error instead of panic if it's still not enough
+pub fn weighted_average(items: &[(Decimal, Decimal)]) -> Result<Decimal> {
+    if let Some((_, weight)) = items.iter().find(|(_, weight)| *weight < Decimal::ZERO) {
+        bail!("Unable to calculate weighted average with negative weight {weight}");
+    }
+
+    let max_weight = items
+        .iter()
+        .map(|&(_, weight)| weight)
+        .max()
+        .unwrap_or_default();
+    if max_weight.is_zero() {
+        bail!("Unable to calculate weighted average because total weight is zero");
+    }
+
+    checked_weighted_average(items.iter().copied())
+        .or_else(|| {
+            checked_weighted_average(
+                items
+                    .iter()
+                    .map(|&(value, weight)| (value, weight / max_weight)),
+            )
+        })
+        .with_context(|| format!("Overflow on calculation of weighted average of {items:?}"))
+}
+
+fn checked_weighted_average(items: impl Iterator<Item = (Decimal, Decimal)>) -> Option<Decimal> {
+    let mut weighted_sum = Decimal::ZERO;
+    let mut total_weight = Decimal::ZERO;
+    for (value, weight) in items {
+        weighted_sum = weighted_sum.checked_add(value.checked_mul(weight)?)?;
+        total_weight = total_weight.checked_add(weight)?;
+    }
+
+    weighted_sum.checked_div(total_weight)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -29,4 +81,33 @@ mod test {
 
         assert_eq!(powered, expected);
     }
+
+    #[rstest]
+    #[case::equal_weights(&[(dec!(1), dec!(1)), (dec!(3), dec!(1))], dec!(2))]
+    #[case::zero_weight(&[(dec!(1), dec!(0)), (dec!(3), dec!(2))], dec!(3))]
+    #[case::vwap(&[(dec!(10), dec!(1)), (dec!(12), dec!(3))], dec!(11.5))]
+    #[case::extreme_weights(
+        &[(dec!(1000000000), dec!(100000000000000000000)), (dec!(1000100000), dec!(400000000000000000000))],
+        dec!(1000080000)
+    )]
+    fn weighted_average_of_values(#[case] items: &[(Decimal, Decimal)], #[case] expected: Decimal) {
+        assert_eq!(weighted_average(items).expect("in test"), expected);
+    }
+
+    #[rstest]
+    #[case::empty(&[])]
+    #[case::zero_total_weight(&[(dec!(1), dec!(0))])]
+    #[case::negative_weight(&[(dec!(1), dec!(-1)), (dec!(3), dec!(2))])]
+    #[case::overflow(&[(Decimal::MAX, dec!(1)), (Decimal::MAX, dec!(1))])]
+    fn weighted_average_error(#[case] items: &[(Decimal, Decimal)]) {
+        assert!(weighted_average(items).is_err());
+    }
+
+    #[test]
+    fn checked_arithmetic_overflow() {
+        assert_eq!(checked_mul(dec!(2), dec!(3.5)).expect("in test"), dec!(7));
+        assert!(checked_mul(Decimal::MAX, dec!(2)).is_err());
+        assert!(checked_div(dec!(1), dec!(0)).is_err());
+        assert!(checked_div(Decimal::MAX, dec!(0.1)).is_err());
+    }
 }
@@ -0,0 +1,199 @@
+use mockall_double::double;
+use std::sync::Arc;
+
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::CurrencyCode;
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use rust_decimal::Decimal;
+
+#[double]
+use crate::services::usd_convertion::usd_converter::UsdConverter;
+
+/// Order sizing by fraction of account equity instead of fixed max amount.
+/// Equity is a sum of balances valued in USD and it should be recalculated periodically
+/// because it's based on the balances and prices at the moment of calculation.
+#[derive(Debug, Clone)]
+pub struct EquitySizing {
+    symbol: Arc<Symbol>,
+    equity_usd: Amount,
+    /// Price of 1 quote currency unit in USD
+    quote_usd_price: Price,
+}
+
+impl EquitySizing {
+    /// Value balances in USD with `usd_converter`.
+    /// Returns `None` if any balance or quote currency of the symbol can't be converted to USD,
+    /// because sizing by partially known equity would be underestimated.
+    pub async fn calculate(
+        symbol: Arc<Symbol>,
+        balances: impl IntoIterator<Item = (CurrencyCode, Amount)>,
+        usd_converter: &UsdConverter,
+        cancellation_token: CancellationToken,
+    ) -> Option<Self> {
+        let mut equity_usd = Decimal::ZERO;
+        for (currency_code, amount) in balances {
+            let amount_usd = usd_converter
+                .convert_amount(currency_code, amount, cancellation_token.clone())
+                .await;
+
+            match amount_usd {
+                Some(amount_usd) => equity_usd += amount_usd,
+                None => {
+                    log::warn!(
+                        "Can't calculate equity: failed to convert {amount} {currency_code} to USD"
+                    );
+                    return None;
+                }
+            }
+        }
+
+        let quote_currency_code = symbol.quote_currency_code();
+        let quote_usd_price = usd_converter
+            .convert_amount(quote_currency_code, Decimal::ONE, cancellation_token)
+            .await
+            .filter(|price| !price.is_zero());
+
+        match quote_usd_price {
+            Some(quote_usd_price) => Some(EquitySizing {
+                symbol,
+                equity_usd,
+                quote_usd_price,
+            }),
+            None => {
+                log::warn!(
+                    "Can't calculate equity: failed to convert {quote_currency_code} to USD"
+                );
+                None
+            }
+        }
+    }
+
+    pub fn equity_usd(&self) -> Amount {
+        self.equity_usd
+    }
+
+    /// Base amount of order with notional `fraction * equity` rounded down by amount step of the symbol
+    /// and limited by symbol max amount.
+    /// Returns `None` if the amount is less than min amount (min notional) of the symbol.
+    pub fn amount_for_equity_fraction(&self, fraction: Decimal, price: Price) -> Option<Amount> {
+        if price.is_zero() {
+            return None;
+        }
+
+        let notional = fraction * self.equity_usd / self.quote_usd_price;
+        let mut amount = self.symbol.amount_round(notional / price, Round::Floor);
+        if let Some(max_amount) = self.symbol.max_amount {
+            amount = amount.min(max_amount);
+        }
+
+        // Symbol without min amount constraints allows any positive amount
+        let min_amount = self.symbol.get_min_amount(price).unwrap_or(Decimal::ZERO);
+        (amount > Decimal::ZERO && amount >= min_amount).then_some(amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::exchanges::symbol::Precision;
+    use parking_lot::ReentrantMutexGuard;
+    use rust_decimal_macros::dec;
+
+    fn symbol(max_amount: Option<Amount>) -> Arc<Symbol> {
+        Arc::new(Symbol::new(
+            false,
+            "BTC".into(),
+            "btc".into(),
+            "USDT".into(),
+            "usdt".into(),
+            None,
+            None,
+            Some(dec!(0.001)),
+            max_amount,
+            Some(dec!(10)),
+            "btc".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ))
+    }
+
+    fn usd_converter() -> (UsdConverter, ReentrantMutexGuard<'static, ()>) {
+        let (mut usd_converter, locker) = UsdConverter::init_mock();
+        usd_converter
+            .expect_convert_amount()
+            .returning(|currency_code, amount, _| match currency_code.as_str() {
+                "btc" => Some(amount * dec!(20000)),
+                "usdt" => Some(amount),
+                _ => None,
+            });
+
+        (usd_converter, locker)
+    }
+
+    async fn equity_sizing(max_amount: Option<Amount>) -> EquitySizing {
+        let (usd_converter, _locker) = usd_converter();
+        EquitySizing::calculate(
+            symbol(max_amount),
+            [("btc".into(), dec!(0.5)), ("usdt".into(), dec!(5000))],
+            &usd_converter,
+            CancellationToken::default(),
+        )
+        .await
+        .expect("in test")
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn amount_by_equity_fraction() {
+        let equity_sizing = equity_sizing(None).await;
+        assert_eq!(equity_sizing.equity_usd(), dec!(15000));
+
+        // notional 0.1 * 15000 = 1500 USDT, amount 1500 / 20000 = 0.075 BTC
+        assert_eq!(
+            equity_sizing.amount_for_equity_fraction(dec!(0.1), dec!(20000)),
+            Some(dec!(0.075))
+        );
+        // 0.01 * 15000 / 21000 = 0.00714... is rounded down by amount step
+        assert_eq!(
+            equity_sizing.amount_for_equity_fraction(dec!(0.01), dec!(21000)),
+            Some(dec!(0.007))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn amount_less_than_min_notional() {
+        let equity_sizing = equity_sizing(None).await;
+
+        // notional 0.0005 * 15000 = 7.5 USDT is less than min cost 10 USDT
+        assert_eq!(
+            equity_sizing.amount_for_equity_fraction(dec!(0.0005), dec!(20000)),
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn amount_limited_by_symbol_max_amount() {
+        let equity_sizing = equity_sizing(Some(dec!(0.05))).await;
+
+        assert_eq!(
+            equity_sizing.amount_for_equity_fraction(dec!(0.5), dec!(20000)),
+            Some(dec!(0.05))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn no_equity_if_balance_is_not_convertible() {
+        let (usd_converter, _locker) = usd_converter();
+
+        let equity_sizing = EquitySizing::calculate(
+            symbol(None),
+            [("btc".into(), dec!(0.5)), ("eth".into(), dec!(1))],
+            &usd_converter,
+            CancellationToken::default(),
+        )
+        .await;
+
+        assert!(equity_sizing.is_none());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod equity_sizing;
 pub(crate) mod position_helper;
 pub(crate) mod price_source_model;
 pub mod reserve_parameters;
@@ -19,28 +19,93 @@ pub mod time_manager {
 #[cfg(test)]
 pub(crate) mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
     use chrono::TimeZone;
+    use mmb_utils::DateTime;
     use mockall_double::double;
     use parking_lot::{Mutex, ReentrantMutexGuard};
 
     #[double]
     use super::time_manager;
 
+    #[derive(Debug, Default)]
+    struct MockClockState {
+        offset: Duration,
+        is_frozen: bool,
+    }
+
+    /// Time returned by `time_manager::now` mock. Time doesn't go by itself, it is changed only by `advance_by`
+    #[derive(Debug, Clone, Default)]
+    pub struct MockClock(Arc<Mutex<MockClockState>>);
+
+    impl MockClock {
+        pub fn now(&self) -> DateTime {
+            let offset = chrono::Duration::from_std(self.0.lock().offset)
+                .expect("offset of mock clock should be less than chrono::Duration::MAX");
+            chrono::Utc.ymd(2021, 9, 20).and_hms(0, 0, 0) + offset
+        }
+
+        /// Move time forward. Ignored while clock is frozen
+        pub fn advance_by(&self, duration: Duration) {
+            let mut state = self.0.lock();
+            if !state.is_frozen {
+                state.offset += duration;
+            }
+        }
+
+        /// Stop time, so `advance_by` calls will be ignored until `unfreeze`
+        pub fn freeze(&self) {
+            self.0.lock().is_frozen = true;
+        }
+
+        pub fn unfreeze(&self) {
+            self.0.lock().is_frozen = false;
+        }
+    }
+
     pub(crate) fn init_mock(
-        seconds_offset: Arc<Mutex<u32>>,
+        clock: MockClock,
     ) -> (
         time_manager::__now::Context,
         ReentrantMutexGuard<'static, ()>,
     ) {
         let mock_locker = crate::MOCK_MUTEX.lock();
         let time_manager_mock_object = time_manager::now_context();
-        time_manager_mock_object.expect().returning(move || {
-            chrono::Utc
-                .ymd(2021, 9, 20)
-                .and_hms(0, 0, *seconds_offset.lock())
-        });
+        // expectations aren't cleared if previous test panicked while mock was set up
+        time_manager_mock_object.checkpoint();
+        time_manager_mock_object
+            .expect()
+            .returning(move || clock.now());
 
         (time_manager_mock_object, mock_locker)
     }
+
+    #[test]
+    fn mock_clock_advance_by() {
+        let clock = MockClock::default();
+        let (_time_manager_mock, _mock_locker) = init_mock(clock.clone());
+        let start = time_manager::now();
+
+        clock.advance_by(Duration::from_secs(1));
+        clock.advance_by(Duration::from_millis(500));
+        assert_eq!(
+            time_manager::now() - start,
+            chrono::Duration::milliseconds(1500)
+        );
+
+        clock.freeze();
+        clock.advance_by(Duration::from_secs(10));
+        assert_eq!(
+            time_manager::now() - start,
+            chrono::Duration::milliseconds(1500)
+        );
+
+        clock.unfreeze();
+        clock.advance_by(Duration::from_secs(10));
+        assert_eq!(
+            time_manager::now() - start,
+            chrono::Duration::milliseconds(11500)
+        );
+    }
 }
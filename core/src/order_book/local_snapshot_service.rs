@@ -1,21 +1,71 @@
+use chrono::Duration;
 use mmb_domain::market::{MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
 use mmb_domain::order_book::event;
 use mmb_domain::order_book::local_order_book_snapshot::{LocalOrderBookSnapshot, ResultAskBidFix};
 use mmb_utils::infrastructure::WithExpect;
-use std::collections::HashMap;
+use mmb_utils::DateTime;
+use mockall_double::double;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+
+use crate::disposition_execution::SmallOrder;
+use crate::math::weighted_average;
+#[double]
+use crate::misc::time::time_manager;
 
 /// Produce and actualize current logical state of order book snapshot according to logical time of handled order book events
 pub struct LocalSnapshotsService {
     local_snapshots: HashMap<MarketId, LocalOrderBookSnapshot>,
+    /// Snapshots which weren't updated during TTL are considered outdated and aren't returned
+    snapshot_ttl: Option<Duration>,
+    /// Markets with outdated snapshots which were already reported. Warning is logged only once
+    /// until snapshot of the market is updated
+    outdated_markets: Mutex<HashSet<MarketId>>,
+    /// Exchange sequence number of the last handled order book event by market
+    last_update_ids: HashMap<MarketId, u64>,
+    /// Markets with missed order book events. Their order books are still served but can be inaccurate
+    /// until a fresh snapshot is received
+    markets_to_resync: HashSet<MarketId>,
+    /// Markets with detected gaps for which fresh snapshots weren't requested yet
+    resync_requests: Vec<MarketAccountId>,
 }
 
 impl LocalSnapshotsService {
     pub fn new(local_snapshots: HashMap<MarketId, LocalOrderBookSnapshot>) -> Self {
-        Self { local_snapshots }
+        Self::new_with_ttl(local_snapshots, None)
+    }
+
+    pub fn new_with_ttl(
+        local_snapshots: HashMap<MarketId, LocalOrderBookSnapshot>,
+        snapshot_ttl: Option<Duration>,
+    ) -> Self {
+        Self {
+            local_snapshots,
+            snapshot_ttl,
+            outdated_markets: Mutex::new(HashSet::new()),
+            last_update_ids: HashMap::new(),
+            markets_to_resync: HashSet::new(),
+            resync_requests: Vec::new(),
+        }
     }
 
+    /// Returns `None` if there is no snapshot or it wasn't updated during snapshot TTL
     pub fn get_snapshot(&self, market_id: MarketId) -> Option<&LocalOrderBookSnapshot> {
-        self.local_snapshots.get(&market_id)
+        let snapshot = self.local_snapshots.get(&market_id)?;
+
+        if let Some(snapshot_ttl) = self.snapshot_ttl {
+            let snapshot_age = time_manager::now() - snapshot.last_update_time;
+            if snapshot_age > snapshot_ttl {
+                if self.outdated_markets.lock().insert(market_id) {
+                    log::warn!("Order book snapshot for {market_id:?} is outdated: last update was {snapshot_age} ago with TTL {snapshot_ttl}");
+                }
+                return None;
+            }
+        }
+
+        Some(snapshot)
     }
 
     pub fn get_snapshot_expected(&self, market_id: MarketId) -> &LocalOrderBookSnapshot {
@@ -24,6 +74,97 @@ impl LocalSnapshotsService {
             .with_expect(|| format!("Can't get snapshot for {:?}", market_id))
     }
 
+    /// Time of the last handled order book event for the market
+    pub fn last_update_time(&self, market_id: MarketId) -> Option<DateTime> {
+        self.get_snapshot(market_id)
+            .map(|snapshot| snapshot.last_update_time)
+    }
+
+    /// Order book is stale if it wasn't updated during `max_age` or there is no order book at all
+    pub fn is_stale(&self, market_id: MarketId, max_age: Duration) -> bool {
+        match self.last_update_time(market_id) {
+            Some(last_update_time) => time_manager::now() - last_update_time > max_age,
+            None => true,
+        }
+    }
+
+    /// Middle price of the order book. If `max_age` specified, returns `None` for stale order book
+    pub fn mid_price(&self, market_id: MarketId, max_age: Option<Duration>) -> Option<Price> {
+        self.get_fresh_snapshot(market_id, max_age)?
+            .calculate_middle_price(market_id)
+    }
+
+    /// Imbalance of top price levels of the order book. If `max_age` specified, returns `None` for stale order book
+    pub fn imbalance(&self, market_id: MarketId, max_age: Option<Duration>) -> Option<Decimal> {
+        self.get_fresh_snapshot(market_id, max_age)?
+            .calculate_imbalance()
+    }
+
+    /// Top prices weighted by amounts of the opposite side, so it's closer to the price which has more
+    /// liquidity behind it. If `max_age` specified, returns `None` for stale order book
+    pub fn microprice(&self, market_id: MarketId, max_age: Option<Duration>) -> Option<Price> {
+        let snapshot = self.get_fresh_snapshot(market_id, max_age)?;
+        let (top_ask_price, top_ask_amount) = snapshot.get_top_ask()?;
+        let (top_bid_price, top_bid_amount) = snapshot.get_top_bid()?;
+
+        weighted_average(&[
+            (top_ask_price, top_bid_amount),
+            (top_bid_price, top_ask_amount),
+        ])
+        .map_err(|err| log::error!("Unable to calculate microprice for {market_id:?}: {err:?}"))
+        .ok()
+    }
+
+    /// Amount which can be filled immediately by order with specified side not worse than `limit_price`.
+    /// Returns zero if there is no actual order book
+    pub fn marketable_amount(
+        &self,
+        market_id: MarketId,
+        side: OrderSide,
+        limit_price: Price,
+    ) -> Amount {
+        self.get_snapshot(market_id)
+            .map(|snapshot| snapshot.calculate_marketable_amount(side, limit_price))
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if an order book event of the market was missed and fresh snapshot wasn't received yet
+    pub fn needs_resync(&self, market_id: MarketId) -> bool {
+        self.markets_to_resync.contains(&market_id)
+    }
+
+    /// Markets for which fresh snapshots should be requested because of missed order book events
+    pub fn take_resync_requests(&mut self) -> Vec<MarketAccountId> {
+        std::mem::take(&mut self.resync_requests)
+    }
+
+    /// Best bid and best ask with their amounts. Returns `None` if any side of the order book is empty
+    pub fn best_bid_ask(&self, market_id: MarketId) -> Option<(SmallOrder, SmallOrder)> {
+        let snapshot = self.get_snapshot(market_id)?;
+        let (bid_price, bid_amount) = snapshot.get_top_bid()?;
+        let (ask_price, ask_amount) = snapshot.get_top_ask()?;
+
+        Some((
+            SmallOrder::new(bid_price, bid_amount),
+            SmallOrder::new(ask_price, ask_amount),
+        ))
+    }
+
+    fn get_fresh_snapshot(
+        &self,
+        market_id: MarketId,
+        max_age: Option<Duration>,
+    ) -> Option<&LocalOrderBookSnapshot> {
+        if let Some(max_age) = max_age {
+            if self.is_stale(market_id, max_age) {
+                log::debug!("Order book for {market_id:?} is stale for max age {max_age}");
+                return None;
+            }
+        }
+
+        self.get_snapshot(market_id)
+    }
+
     /// Create snapshot if it does not exist
     /// Update snapshot if suitable data arrive
     /// Returns `Some(MarketAccountId)` if snapshot update succeeded, otherwise `None`
@@ -41,23 +182,72 @@ impl LocalSnapshotsService {
                 }
 
                 self.local_snapshots.insert(market_id, snapshot);
+                self.set_last_update_id(market_id, event.last_update_id);
+                let _ = self.markets_to_resync.remove(&market_id);
+                let _ = self.outdated_markets.get_mut().remove(&market_id);
 
                 Some(market_account_id)
             }
-            event::EventType::Update => match self.local_snapshots.get_mut(&market_id) {
-                None => None,
-                Some(snapshot) => {
-                    snapshot.apply_update(&event.data, event.creation_time);
-
-                    if let ResultAskBidFix::Fixed { top_ask, top_bid } =
-                        snapshot.fix_asks_bids_if_needed()
-                    {
-                        log::warn!("On {market_account_id} orderbook top asks {top_ask} and bids {top_bid} was crossed (fixed now {})", snapshot.get_top_prices())
-                    }
-
-                    Some(market_account_id)
+            event::EventType::Update => {
+                // order book is still updated after missed event because a stale book is worse than
+                // an inaccurate one, but fresh snapshot is requested to fix it
+                if self.is_gap(market_id, event) && self.markets_to_resync.insert(market_id) {
+                    self.resync_requests.push(market_account_id);
+                }
+
+                let updated_market_account_id = self.apply_update(market_account_id, event)?;
+                self.set_last_update_id(market_id, event.last_update_id);
+                let _ = self.outdated_markets.get_mut().remove(&market_id);
+
+                Some(updated_market_account_id)
+            }
+        }
+    }
+
+    fn is_gap(&self, market_id: MarketId, event: &event::OrderBookEvent) -> bool {
+        let previous_update_id = match self.last_update_ids.get(&market_id) {
+            Some(&previous_update_id) => previous_update_id,
+            None => return false,
+        };
+
+        match event.first_update_id {
+            Some(first_update_id) if first_update_id > previous_update_id + 1 => {
+                log::warn!("Gap detected in order book updates for {market_id:?}: previous update id {previous_update_id}, first update id of incoming update {first_update_id}. Snapshot resync is needed");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_last_update_id(&mut self, market_id: MarketId, last_update_id: Option<u64>) {
+        match last_update_id {
+            Some(last_update_id) => {
+                let _ = self.last_update_ids.insert(market_id, last_update_id);
+            }
+            None => {
+                let _ = self.last_update_ids.remove(&market_id);
+            }
+        }
+    }
+
+    fn apply_update(
+        &mut self,
+        market_account_id: MarketAccountId,
+        event: &event::OrderBookEvent,
+    ) -> Option<MarketAccountId> {
+        match self.local_snapshots.get_mut(&market_account_id.market_id()) {
+            None => None,
+            Some(snapshot) => {
+                snapshot.apply_update(&event.data, event.creation_time);
+
+                if let ResultAskBidFix::Fixed { top_ask, top_bid } =
+                    snapshot.fix_asks_bids_if_needed()
+                {
+                    log::warn!("On {market_account_id} orderbook top asks {top_ask} and bids {top_bid} was crossed (fixed now {})", snapshot.get_top_prices())
                 }
-            },
+
+                Some(market_account_id)
+            }
         }
     }
 }
@@ -71,10 +261,13 @@ impl Default for LocalSnapshotsService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
     use chrono::Utc;
     use mmb_domain::market::{CurrencyPair, ExchangeAccountId, ExchangeId};
     use mmb_domain::order_book::order_book_data;
     use mmb_domain::order_book_data;
+    use mmb_utils::hashmap;
     use rust_decimal_macros::*;
     use std::sync::Arc;
 
@@ -129,6 +322,251 @@ mod tests {
         assert_eq!(snapshot.bids, order_book_data.bids);
     }
 
+    #[test]
+    fn outdated_snapshot_is_not_returned() {
+        let clock = MockClock::default();
+        let (_time_manager_mock, _locker) = time::tests::init_mock(clock.clone());
+
+        let market_id = MarketId::new(
+            "does_not_matter".into(),
+            CurrencyPair::from_codes("base".into(), "quote".into()),
+        );
+        let order_book_data = order_book_data![
+            dec!(3.0) => dec!(1),
+            ;
+            dec!(2.0) => dec!(3),
+        ];
+        let snapshot = order_book_data.to_orderbook_snapshot(clock.now());
+        let snapshot_service = LocalSnapshotsService::new_with_ttl(
+            hashmap![market_id => snapshot],
+            Some(Duration::seconds(10)),
+        );
+
+        clock.advance_by(std::time::Duration::from_secs(10));
+        assert!(snapshot_service.get_snapshot(market_id).is_some());
+
+        clock.advance_by(std::time::Duration::from_secs(1));
+        assert!(snapshot_service.get_snapshot(market_id).is_none());
+    }
+
+    #[test]
+    fn outdated_snapshot_is_reported_until_update() {
+        let clock = MockClock::default();
+        let (_time_manager_mock, _locker) = time::tests::init_mock(clock.clone());
+
+        let exchange_account_id = ExchangeAccountId::new("does_not_matter", 0);
+        let currency_pair = CurrencyPair::from_codes("base".into(), "quote".into());
+        let market_id = MarketId::new(exchange_account_id.exchange_id, currency_pair);
+        let mut snapshot_service =
+            LocalSnapshotsService::new_with_ttl(HashMap::new(), Some(Duration::seconds(10)));
+        let order_book_data = Arc::new(order_book_data![
+            dec!(3.0) => dec!(1),
+            ;
+            dec!(2.0) => dec!(3),
+        ]);
+        let create_snapshot_event = || {
+            event::OrderBookEvent::new(
+                clock.now(),
+                exchange_account_id,
+                currency_pair,
+                "".to_string(),
+                event::EventType::Snapshot,
+                order_book_data.clone(),
+            )
+        };
+        let _ = snapshot_service.update(&create_snapshot_event());
+
+        clock.advance_by(std::time::Duration::from_secs(11));
+        assert!(snapshot_service.get_snapshot(market_id).is_none());
+        assert!(snapshot_service.get_snapshot(market_id).is_none());
+        assert_eq!(
+            *snapshot_service.outdated_markets.lock(),
+            HashSet::from([market_id])
+        );
+
+        let _ = snapshot_service.update(&create_snapshot_event());
+        assert!(snapshot_service.get_snapshot(market_id).is_some());
+        assert!(snapshot_service.outdated_markets.lock().is_empty());
+    }
+
+    #[test]
+    fn stale_order_book() {
+        let clock = MockClock::default();
+        let (_time_manager_mock, _locker) = time::tests::init_mock(clock.clone());
+
+        let mut snapshot_service = LocalSnapshotsService::default();
+        let order_book_data = order_book_data![
+            dec!(3.0) => dec!(1),
+            ;
+            dec!(2.0) => dec!(3),
+        ];
+        let mut order_book_event = create_order_book_event_for_tests(
+            "does_not_matter".into(),
+            CurrencyPair::from_codes("base".into(), "quote".into()),
+            event::EventType::Snapshot,
+            order_book_data,
+        );
+        order_book_event.creation_time = clock.now();
+        let market_id = order_book_event.market_account_id().market_id();
+        let max_age = Duration::seconds(5);
+
+        assert!(snapshot_service.is_stale(market_id, max_age));
+        assert_eq!(snapshot_service.mid_price(market_id, Some(max_age)), None);
+
+        let _ = snapshot_service.update(&order_book_event).expect("in test");
+        clock.advance_by(std::time::Duration::from_secs(3));
+
+        assert!(!snapshot_service.is_stale(market_id, max_age));
+        assert_eq!(
+            snapshot_service.mid_price(market_id, Some(max_age)),
+            Some(dec!(2.5))
+        );
+        assert_eq!(
+            snapshot_service.imbalance(market_id, Some(max_age)),
+            Some(dec!(0.5))
+        );
+
+        clock.advance_by(std::time::Duration::from_secs(3));
+
+        assert!(snapshot_service.is_stale(market_id, max_age));
+        assert_eq!(snapshot_service.mid_price(market_id, Some(max_age)), None);
+        assert_eq!(snapshot_service.imbalance(market_id, Some(max_age)), None);
+        assert_eq!(snapshot_service.mid_price(market_id, None), Some(dec!(2.5)));
+    }
+
+    #[test]
+    fn microprice() {
+        let mut snapshot_service = LocalSnapshotsService::default();
+        let order_book_data = order_book_data![
+            dec!(3.0) => dec!(1),
+            ;
+            dec!(2.0) => dec!(3),
+        ];
+        let order_book_event = create_order_book_event_for_tests(
+            "does_not_matter".into(),
+            CurrencyPair::from_codes("base".into(), "quote".into()),
+            event::EventType::Snapshot,
+            order_book_data,
+        );
+        let market_id = order_book_event.market_account_id().market_id();
+
+        assert_eq!(snapshot_service.microprice(market_id, None), None);
+
+        let _ = snapshot_service.update(&order_book_event).expect("in test");
+
+        assert_eq!(
+            snapshot_service.microprice(market_id, None),
+            Some(dec!(2.75))
+        );
+    }
+
+    #[test]
+    fn microprice_with_extreme_amounts() {
+        let mut snapshot_service = LocalSnapshotsService::default();
+        // products of prices and amounts don't fit into decimal
+        let order_book_data = order_book_data![
+            dec!(1000100000) => dec!(100000000000000000000),
+            ;
+            dec!(1000000000) => dec!(400000000000000000000),
+        ];
+        let order_book_event = create_order_book_event_for_tests(
+            "does_not_matter".into(),
+            CurrencyPair::from_codes("base".into(), "quote".into()),
+            event::EventType::Snapshot,
+            order_book_data,
+        );
+        let market_id = order_book_event.market_account_id().market_id();
+        let _ = snapshot_service.update(&order_book_event).expect("in test");
+
+        assert_eq!(
+            snapshot_service.microprice(market_id, None),
+            Some(dec!(1000080000))
+        );
+    }
+
+    #[test]
+    fn marketable_amount() {
+        let mut snapshot_service = LocalSnapshotsService::default();
+        let order_book_data = order_book_data![
+            dec!(3.0) => dec!(1),
+            dec!(4.0) => dec!(2),
+            ;
+            dec!(2.0) => dec!(3),
+        ];
+        let order_book_event = create_order_book_event_for_tests(
+            "does_not_matter".into(),
+            CurrencyPair::from_codes("base".into(), "quote".into()),
+            event::EventType::Snapshot,
+            order_book_data,
+        );
+        let market_id = order_book_event.market_account_id().market_id();
+
+        assert_eq!(
+            snapshot_service.marketable_amount(market_id, OrderSide::Buy, dec!(5)),
+            dec!(0)
+        );
+
+        let _ = snapshot_service.update(&order_book_event).expect("in test");
+
+        assert_eq!(
+            snapshot_service.marketable_amount(market_id, OrderSide::Buy, dec!(3.5)),
+            dec!(1)
+        );
+        assert_eq!(
+            snapshot_service.marketable_amount(market_id, OrderSide::Buy, dec!(5)),
+            dec!(3)
+        );
+        assert_eq!(
+            snapshot_service.marketable_amount(market_id, OrderSide::Sell, dec!(2.5)),
+            dec!(0)
+        );
+    }
+
+    #[test]
+    fn best_bid_ask() {
+        let mut snapshot_service = LocalSnapshotsService::default();
+        let currency_pair = CurrencyPair::from_codes("base".into(), "quote".into());
+        let market_id = MarketId::new("does_not_matter".into(), currency_pair);
+
+        assert_eq!(snapshot_service.best_bid_ask(market_id), None);
+
+        let _ = snapshot_service
+            .update(&create_order_book_event_for_tests(
+                "does_not_matter".into(),
+                currency_pair,
+                event::EventType::Snapshot,
+                order_book_data![
+                    dec!(3.0) => dec!(1),
+                    dec!(4.0) => dec!(2),
+                    ;
+                    dec!(2.0) => dec!(3),
+                    dec!(1.0) => dec!(4),
+                ],
+            ))
+            .expect("in test");
+        assert_eq!(
+            snapshot_service.best_bid_ask(market_id),
+            Some((
+                SmallOrder::new(dec!(2.0), dec!(3)),
+                SmallOrder::new(dec!(3.0), dec!(1))
+            ))
+        );
+
+        // no asks
+        let _ = snapshot_service
+            .update(&create_order_book_event_for_tests(
+                "does_not_matter".into(),
+                currency_pair,
+                event::EventType::Snapshot,
+                order_book_data![
+                    ;
+                    dec!(2.0) => dec!(3),
+                ],
+            ))
+            .expect("in test");
+        assert_eq!(snapshot_service.best_bid_ask(market_id), None);
+    }
+
     #[test]
     fn update_if_no_such_snapshot() {
         // Construct main object
@@ -314,4 +752,64 @@ mod tests {
         assert_eq!(snapshot.asks, expected.asks);
         assert_eq!(snapshot.bids, expected.bids);
     }
+
+    #[test]
+    fn gap_in_update_ids_requests_resync() {
+        let mut snapshot_service = LocalSnapshotsService::default();
+        let currency_pair = CurrencyPair::from_codes("base".into(), "quote".into());
+        let event = |event_type, first_update_id, last_update_id| {
+            create_order_book_event_for_tests(
+                "does_not_matter".into(),
+                currency_pair,
+                event_type,
+                order_book_data![
+                    dec!(3.0) => dec!(1),
+                    ;
+                    dec!(2.0) => dec!(3),
+                ],
+            )
+            .with_update_ids(first_update_id, last_update_id)
+        };
+
+        let market_id = snapshot_service
+            .update(&event(event::EventType::Snapshot, 10, 10))
+            .expect("in test")
+            .market_id();
+        assert!(snapshot_service
+            .update(&event(event::EventType::Update, 11, 11))
+            .is_some());
+        // several updates in one event
+        assert!(snapshot_service
+            .update(&event(event::EventType::Update, 12, 15))
+            .is_some());
+        assert!(snapshot_service.take_resync_requests().is_empty());
+
+        // update 16 is missed
+        let market_account_id = snapshot_service
+            .update(&event(event::EventType::Update, 17, 17))
+            .expect("in test");
+        assert!(snapshot_service.get_snapshot(market_id).is_some());
+        assert!(snapshot_service.needs_resync(market_id));
+        assert_eq!(
+            snapshot_service.take_resync_requests(),
+            vec![market_account_id]
+        );
+
+        // resync is requested once until a fresh snapshot
+        assert!(snapshot_service
+            .update(&event(event::EventType::Update, 20, 20))
+            .is_some());
+        assert!(snapshot_service.needs_resync(market_id));
+        assert!(snapshot_service.take_resync_requests().is_empty());
+
+        // fresh snapshot starts a new sequence
+        assert!(snapshot_service
+            .update(&event(event::EventType::Snapshot, 30, 30))
+            .is_some());
+        assert!(snapshot_service
+            .update(&event(event::EventType::Update, 31, 31))
+            .is_some());
+        assert!(!snapshot_service.needs_resync(market_id));
+        assert!(snapshot_service.take_resync_requests().is_empty());
+    }
 }
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use crate::lifecycle::app_lifetime_manager::{ActionAfterGracefulShutdown, AppLifetimeManager};
 use anyhow::Context;
-use jsonrpc_core::{MetaIoHandler, Result};
-use jsonrpc_ipc_server::{Server, ServerBuilder};
+use jsonrpc_core::{Error, MetaIoHandler, Result};
+use jsonrpc_ipc_server::{RequestContext, Server, ServerBuilder};
+use jsonrpc_pubsub::Session;
 use mmb_rpc::rest_api::{server_side_error, ErrorCode, MmbRpc, IPC_ADDRESS};
 use mmb_utils::infrastructure::SpawnFutureFlags;
 use parking_lot::Mutex;
@@ -15,7 +16,20 @@ use crate::{
     rpc::core_api::FAILED_TO_SEND_STOP_NOTIFICATION,
 };
 
-pub(super) fn set_config(settings: String) -> Result<()> {
+/// Deserialization and semantic validation of full settings received from control panel.
+/// It is a function pointer because RPC isn't generic by type of strategy settings
+pub type SettingsValidator = fn(&str) -> anyhow::Result<()>;
+
+fn validate_config(settings: &str, validate_settings: SettingsValidator) -> Result<()> {
+    validate_settings(settings).map_err(|err| {
+        log::warn!("Received invalid config in set_config endpoint: {err:?}");
+        Error::invalid_params(format!("{err:#}"))
+    })
+}
+
+pub(super) fn set_config(settings: String, validate_settings: SettingsValidator) -> Result<()> {
+    validate_config(&settings, validate_settings)?;
+
     save_settings(settings.as_str(), CONFIG_PATH, CREDENTIALS_PATH).map_err(|err| {
         log::warn!(
             "Error while trying to save new config in set_config endpoint: {}",
@@ -77,8 +91,8 @@ pub(super) fn stop_server(
     Ok(())
 }
 
-pub(super) fn build_io(rpc: impl MmbRpc) -> MetaIoHandler<()> {
-    let mut io = MetaIoHandler::<()>::default();
+pub(super) fn build_io(rpc: impl MmbRpc<Metadata = Arc<Session>>) -> MetaIoHandler<Arc<Session>> {
+    let mut io = MetaIoHandler::<Arc<Session>>::default();
     io.extend_with(rpc.to_delegate());
 
     io
@@ -90,10 +104,15 @@ pub(super) struct RpcServerAndChannels<T> {
     pub work_finished_receiver: oneshot::Receiver<T>,
 }
 
-pub(super) fn crate_server_and_channels<T>(rpc: impl MmbRpc) -> RpcServerAndChannels<T> {
+pub(super) fn crate_server_and_channels<T>(
+    rpc: impl MmbRpc<Metadata = Arc<Session>>,
+) -> RpcServerAndChannels<T> {
     let (work_finished_sender, work_finished_receiver) = oneshot::channel();
     let io = build_io(rpc);
-    let builder = ServerBuilder::new(io);
+    // Session is needed to send notifications of subscriptions to connected client
+    let builder = ServerBuilder::with_meta_extractor(io, |context: &RequestContext| {
+        Arc::new(Session::new(context.sender.clone()))
+    });
     let server = builder.start(IPC_ADDRESS).expect("Couldn't open socket");
 
     RpcServerAndChannels {
@@ -142,3 +161,73 @@ pub(super) fn spawn_server_stopping_action<T>(
         stopping_action,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validate_settings;
+    use jsonrpc_core::ErrorCode;
+    use rust_decimal::Decimal;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct TestStrategySettings {
+        #[allow(dead_code)]
+        spread: Decimal,
+    }
+
+    fn config(discount_percent: &str) -> String {
+        format!(
+            r#"
+            [strategy]
+            spread = "0.1"
+
+            [core.fee_model]
+            maker_bps = 2
+            taker_bps = 4
+            discount_percent = {discount_percent}
+
+            [[core.exchanges]]
+            exchange_account_id = "Binance_0"
+            api_key = "api_key"
+            secret_key = "secret_key"
+            is_margin_trading = false
+            request_trades = false
+            subscribe_to_market_data = true
+            websocket_channels = ["depth", "trade"]
+            "#
+        )
+    }
+
+    fn validate(settings: &str) -> Result<()> {
+        validate_config(settings, validate_settings::<TestStrategySettings>)
+    }
+
+    #[test]
+    fn valid_config() {
+        assert_eq!(validate(&config("25")), Ok(()));
+    }
+
+    #[test]
+    fn malformed_toml_is_invalid_params() {
+        let error = validate("[strategy\nspread = ").expect_err("malformed toml");
+
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert!(error.message.contains("Unable parse settings"));
+    }
+
+    #[test]
+    fn config_with_missing_fields_is_invalid_params() {
+        let error = validate("[strategy]\nspread = \"0.1\"").expect_err("no core settings");
+
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+    }
+
+    #[test]
+    fn semantically_invalid_config_is_invalid_params() {
+        let error = validate(&config("-5")).expect_err("negative discount");
+
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert!(error.message.contains("discount_percent"));
+    }
+}
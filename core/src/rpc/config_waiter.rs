@@ -10,6 +10,7 @@ use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
 use super::{
     common::{
         crate_server_and_channels, spawn_server_stopping_action, stop_server, RpcServerAndChannels,
+        SettingsValidator,
     },
     rpc_impl_no_config::RpcImplNoConfig,
 };
@@ -20,7 +21,10 @@ pub(crate) struct ConfigWaiter {
 }
 
 impl ConfigWaiter {
-    pub(crate) fn create_and_start(wait_config_tx: mpsc::Sender<()>) -> Result<Arc<Self>> {
+    pub(crate) fn create_and_start(
+        wait_config_tx: mpsc::Sender<()>,
+        validate_settings: SettingsValidator,
+    ) -> Result<Arc<Self>> {
         let (server_stopper_tx, server_stopper_rx) =
             mpsc::channel::<ActionAfterGracefulShutdown>(10);
         let server_stopper_tx = Arc::new(Mutex::new(Some(server_stopper_tx)));
@@ -31,6 +35,7 @@ impl ConfigWaiter {
         } = crate_server_and_channels(RpcImplNoConfig::new(
             server_stopper_tx.clone(),
             wait_config_tx,
+            validate_settings,
         ));
 
         spawn_server_stopping_action(
@@ -1,7 +1,13 @@
 use anyhow::Result;
+use dashmap::DashMap;
+use mmb_domain::events::ExchangeEvents;
+use mmb_domain::market::ExchangeAccountId;
 use parking_lot::Mutex;
 use tokio::sync::{mpsc, oneshot};
 
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::disposition_execution::state_graph::ExecutorStateGraph;
+use crate::exchanges::general::exchange::Exchange;
 use crate::lifecycle::app_lifetime_manager::{ActionAfterGracefulShutdown, AppLifetimeManager};
 use std::sync::Arc;
 
@@ -10,7 +16,9 @@ use crate::{lifecycle::trading_engine::Service, statistic_service::StatisticServ
 use super::{
     common::{
         crate_server_and_channels, spawn_server_stopping_action, stop_server, RpcServerAndChannels,
+        SettingsValidator,
     },
+    events::EventsSubscriptions,
     rpc_impl::RpcImpl,
 };
 
@@ -23,10 +31,16 @@ pub(crate) struct CoreApi {
 }
 
 impl CoreApi {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn create_and_start(
         lifetime_manager: Arc<AppLifetimeManager>,
         engine_settings: String,
         statistics: Arc<StatisticService>,
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        validate_settings: SettingsValidator,
+        exchange_events: ExchangeEvents,
+        executor_state_graph: ExecutorStateGraph,
     ) -> Result<Arc<Self>> {
         let (server_stopper_tx, server_stopper_rx) =
             mpsc::channel::<ActionAfterGracefulShutdown>(10);
@@ -39,6 +53,12 @@ impl CoreApi {
             server_stopper_tx.clone(),
             statistics,
             engine_settings,
+            exchanges,
+            balance_manager,
+            lifetime_manager.clone(),
+            validate_settings,
+            EventsSubscriptions::new(exchange_events),
+            executor_state_graph,
         ));
 
         spawn_server_stopping_action(
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use jsonrpc_core::Result as RpcResult;
+use jsonrpc_pubsub::typed::{Sink, Subscriber};
+use jsonrpc_pubsub::SubscriptionId;
+use mmb_domain::events::{ExchangeEvent, ExchangeEvents};
+use mmb_rpc::rest_api::{server_side_error, ErrorCode};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mockall_double::double;
+use parking_lot::Mutex;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::infrastructure::spawn_future;
+#[double]
+use crate::misc::time::time_manager;
+use crate::services::order_audit_log::OrderAuditRecord;
+
+/// Sends order events to control panels subscribed to them.
+/// Every subscription receives events from its own receiver of exchange events channel,
+/// so slow subscribers skip oldest events without affecting other subscribers
+pub(super) struct EventsSubscriptions {
+    exchange_events: ExchangeEvents,
+    last_subscription_id: AtomicU64,
+    cancellation_tokens: Mutex<HashMap<SubscriptionId, CancellationToken>>,
+}
+
+impl EventsSubscriptions {
+    pub(super) fn new(exchange_events: ExchangeEvents) -> Arc<Self> {
+        Arc::new(Self {
+            exchange_events,
+            last_subscription_id: AtomicU64::new(0),
+            cancellation_tokens: Default::default(),
+        })
+    }
+
+    pub(super) fn subscribe(self: &Arc<Self>, subscriber: Subscriber<String>) {
+        let subscription_id =
+            SubscriptionId::Number(self.last_subscription_id.fetch_add(1, Ordering::SeqCst) + 1);
+        let sink = match subscriber.assign_id(subscription_id.clone()) {
+            Ok(sink) => sink,
+            Err(()) => {
+                log::warn!("Control panel disconnected before subscription to events");
+                return;
+            }
+        };
+
+        let cancellation_token = CancellationToken::new();
+        let _ = self
+            .cancellation_tokens
+            .lock()
+            .insert(subscription_id.clone(), cancellation_token.clone());
+
+        let events_receiver = self.exchange_events.get_events_channel();
+        let this = self.clone();
+        spawn_future(
+            "send events to control panel",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            async move {
+                let result = send_events(sink, events_receiver, cancellation_token).await;
+                let _ = this.cancellation_tokens.lock().remove(&subscription_id);
+                result
+            },
+        );
+    }
+
+    pub(super) fn unsubscribe(&self, subscription_id: SubscriptionId) -> RpcResult<bool> {
+        match self.cancellation_tokens.lock().remove(&subscription_id) {
+            Some(cancellation_token) => {
+                cancellation_token.cancel();
+                Ok(true)
+            }
+            None => Err(server_side_error(ErrorCode::SubscriptionNotFound)),
+        }
+    }
+}
+
+/// Sends order events to subscriber until it is unsubscribed or disconnected
+async fn send_events(
+    sink: Sink<String>,
+    mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+    cancellation_token: CancellationToken,
+) -> Result<()> {
+    loop {
+        let event = tokio::select! {
+            event_res = events_receiver.recv() => event_res,
+            _ = cancellation_token.when_cancelled() => return Ok(()),
+        };
+
+        let order_event = match event {
+            Ok(ExchangeEvent::OrderEvent(order_event)) => order_event,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(skipped_count)) => {
+                log::warn!("Control panel events subscription skipped {skipped_count} events");
+                continue;
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        };
+
+        let record = OrderAuditRecord::from_event(&order_event, time_manager::now());
+        let json = serde_json::to_string(&record)
+            .with_context(|| format!("Failed to serialize order event {record:?}"))?;
+
+        if sink.notify(Ok(json)).is_err() {
+            log::info!("Control panel disconnected from events subscription");
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper;
+    use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
+    use futures::StreamExt;
+    use mmb_domain::events::LifecycleState;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::event::{OrderEvent, OrderEventType};
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderRole, OrderSide};
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+
+    fn create_order_event() -> ExchangeEvent {
+        let order = test_helper::create_order_ref(
+            &ClientOrderId::unique_id(),
+            Some(OrderRole::Maker),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("eth".into(), "btc".into()),
+            dec!(0.5),
+            dec!(2),
+            OrderSide::Buy,
+        );
+
+        ExchangeEvent::OrderEvent(OrderEvent::new(order, OrderEventType::CreateOrderSucceeded))
+    }
+
+    #[tokio::test]
+    async fn send_order_events_to_subscriber() {
+        let (_time_manager_context, _tm_locker) = time::tests::init_mock(MockClock::default());
+        let (events_sender, _) = broadcast::channel(10);
+        let (subscriber, _id_receiver, mut transport) = Subscriber::new_test("events");
+        let sink = subscriber
+            .assign_id(SubscriptionId::Number(1))
+            .expect("in test");
+
+        let cancellation_token = CancellationToken::new();
+        let sending = tokio::spawn(send_events(
+            sink,
+            events_sender.subscribe(),
+            cancellation_token.clone(),
+        ));
+
+        events_sender
+            .send(ExchangeEvent::LifecycleState(LifecycleState::Running))
+            .expect("in test");
+        events_sender.send(create_order_event()).expect("in test");
+
+        let notification = tokio::time::timeout(Duration::from_secs(1), transport.next())
+            .await
+            .expect("in test")
+            .expect("in test");
+        let notification: serde_json::Value = serde_json::from_str(&notification).expect("in test");
+        let record: serde_json::Value =
+            serde_json::from_str(notification["params"]["result"].as_str().expect("in test"))
+                .expect("in test");
+        assert_eq!(record["action"], "Create");
+
+        cancellation_token.cancel();
+        sending.await.expect("in test").expect("in test");
+    }
+}
@@ -1,5 +1,7 @@
 pub mod common;
 pub mod config_waiter;
 pub mod core_api;
+mod events;
+mod orders;
 pub mod rpc_impl;
 pub mod rpc_impl_no_config;
@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use jsonrpc_core::{Error, Result};
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, OrderSide, OrderStatus, Price};
+use mmb_rpc::rest_api::{server_side_error, ErrorCode};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::exchanges::general::exchange::Exchange;
+use crate::infrastructure::spawn_future;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+#[derive(Debug, Serialize)]
+struct OpenOrder {
+    exchange_account_id: String,
+    client_order_id: String,
+    exchange_order_id: Option<String>,
+    currency_pair: String,
+    side: OrderSide,
+    price: Option<Price>,
+    amount: Amount,
+    filled_amount: Amount,
+    status: OrderStatus,
+}
+
+impl OpenOrder {
+    fn new(order: &OrderRef) -> Self {
+        OpenOrder {
+            exchange_account_id: order.exchange_account_id().to_string(),
+            client_order_id: order.client_order_id().to_string(),
+            exchange_order_id: order.exchange_order_id().map(|id| id.to_string()),
+            currency_pair: order.currency_pair().to_string(),
+            side: order.side(),
+            price: order.source_price(),
+            amount: order.amount(),
+            filled_amount: order.filled_amount(),
+            status: order.status(),
+        }
+    }
+}
+
+/// JSON array of not finished orders tracked by all exchanges
+pub(super) fn open_orders_json(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+) -> Result<String> {
+    let open_orders: Vec<OpenOrder> = exchanges
+        .iter()
+        .flat_map(|exchange| {
+            exchange
+                .orders
+                .not_finished
+                .iter()
+                .map(|order| OpenOrder::new(order.value()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    serde_json::to_string(&open_orders).map_err(|err| {
+        log::warn!("Failed to serialize open orders {open_orders:?}: {err}");
+        server_side_error(ErrorCode::FailedToSerializeOrders)
+    })
+}
+
+/// Start cancellation of the order the same way as the strategy does it.
+/// Response is returned without waiting for the order to be cancelled on the exchange
+pub(super) fn cancel_order(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+    client_order_id: String,
+    cancellation_token: CancellationToken,
+) -> Result<String> {
+    let client_order_id = ClientOrderId::new(client_order_id.as_str().into());
+    let found_order = exchanges.iter().find_map(|exchange| {
+        exchange
+            .orders
+            .not_finished
+            .get(&client_order_id)
+            .map(|order| (exchange.value().clone(), order.value().clone()))
+    });
+
+    let (exchange, order) = match found_order {
+        Some(found_order) => found_order,
+        None => {
+            log::warn!(
+                "Unable to cancel order {client_order_id} by control panel: open order isn't found"
+            );
+            return Err(server_side_error(ErrorCode::OrderNotFound));
+        }
+    };
+
+    log::info!(
+        "Cancelling order {client_order_id} on {} by control panel",
+        exchange.exchange_account_id
+    );
+
+    let action = async move {
+        exchange
+            .wait_cancel_order(order, None, false, cancellation_token)
+            .await
+    };
+    spawn_future(
+        "Cancel order by control panel",
+        SpawnFutureFlags::empty(),
+        action,
+    );
+
+    Ok(format!(
+        "Cancellation of order {client_order_id} was started"
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct SimulateOrderRequest {
+    exchange_account_id: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    side: OrderSide,
+    price: Price,
+    amount: Amount,
+    /// Strategy configuration which balance limits are applied to the order
+    configuration_descriptor: ConfigurationDescriptor,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct OrderCheck {
+    name: &'static str,
+    passed: bool,
+    details: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OrderSimulation {
+    /// Order would be accepted only if all checks are passed
+    accepted: bool,
+    price: Price,
+    amount: Amount,
+    checks: Vec<OrderCheck>,
+}
+
+fn check_precision(symbol: &Symbol, price: Price, amount: Amount) -> (Price, Amount, OrderCheck) {
+    let rounded_price = symbol.price_round(price, Round::ToNearest);
+    let rounded_amount = symbol.amount_round(amount, Round::Floor);
+    let check = OrderCheck {
+        name: "precision",
+        passed: rounded_price == price && rounded_amount == amount,
+        details: format!("price {price} is rounded to {rounded_price}, amount {amount} is rounded to {rounded_amount}"),
+    };
+
+    (rounded_price, rounded_amount, check)
+}
+
+fn check_min_notional(symbol: &Symbol, price: Price, amount: Amount) -> OrderCheck {
+    let (passed, details) = match symbol.get_min_amount(price) {
+        Ok(min_amount) => (
+            amount >= min_amount,
+            format!("amount {amount}, min amount {min_amount}"),
+        ),
+        Err(err) => (false, format!("{err:#}")),
+    };
+
+    OrderCheck {
+        name: "min_notional",
+        passed,
+        details,
+    }
+}
+
+fn check_balance(
+    balance_manager: &Mutex<BalanceManager>,
+    reserve_parameters: &ReserveParameters,
+) -> OrderCheck {
+    let passed = balance_manager
+        .lock()
+        .can_reserve(reserve_parameters, &mut None);
+
+    OrderCheck {
+        name: "balance",
+        passed,
+        details: format!(
+            "reservation of {} by price {} is {}",
+            reserve_parameters.amount,
+            reserve_parameters.price,
+            if passed { "possible" } else { "impossible" }
+        ),
+    }
+}
+
+/// Run the same checks which are applied before placing an order: precision rounding,
+/// min notional and balance reservation. Nothing is placed or reserved
+pub(super) fn simulate_order(
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: &Mutex<BalanceManager>,
+    request: String,
+) -> Result<String> {
+    let request: SimulateOrderRequest = serde_json::from_str(&request).map_err(|err| {
+        log::warn!("Received invalid simulate_order request {request}: {err}");
+        Error::invalid_params(err.to_string())
+    })?;
+
+    let exchange_account_id = request.exchange_account_id;
+    let symbol = exchanges
+        .get(&exchange_account_id)
+        .ok_or_else(|| Error::invalid_params(format!("Unknown exchange {exchange_account_id}")))?
+        .get_symbol(request.currency_pair)
+        .map_err(|err| Error::invalid_params(format!("{err:#}")))?;
+
+    let (price, amount, precision_check) = check_precision(&symbol, request.price, request.amount);
+    let min_notional_check = check_min_notional(&symbol, price, amount);
+    let balance_check = check_balance(
+        balance_manager,
+        &ReserveParameters::new(
+            request.configuration_descriptor,
+            exchange_account_id,
+            symbol,
+            request.side,
+            price,
+            amount,
+        ),
+    );
+
+    let checks = vec![precision_check, min_notional_check, balance_check];
+    let simulation = OrderSimulation {
+        accepted: checks.iter().all(|x| x.passed),
+        price,
+        amount,
+        checks,
+    };
+
+    log::info!("Simulated order by control panel: {simulation:?}");
+
+    serde_json::to_string(&simulation).map_err(|err| {
+        log::warn!("Failed to serialize order simulation {simulation:?}: {err}");
+        server_side_error(ErrorCode::FailedToSerializeOrders)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::{create_order_ref, get_test_exchange};
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::order::snapshot::OrderRole;
+    use rust_decimal_macros::dec;
+
+    fn symbol() -> Symbol {
+        Symbol::new(
+            false,
+            "PHB".into(),
+            "PHB".into(),
+            "BTC".into(),
+            "BTC".into(),
+            None,
+            None,
+            Some(dec!(1)),
+            None,
+            Some(dec!(10)),
+            "PHB".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(1) },
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn list_open_orders() {
+        let (exchange, _event_receiver) = get_test_exchange(false);
+        let order_ref = create_order_ref(
+            &ClientOrderId::new("open_order".into()),
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            dec!(0.2),
+            dec!(12),
+            OrderSide::Buy,
+        );
+        let _ = exchange
+            .orders
+            .not_finished
+            .insert(order_ref.client_order_id(), order_ref);
+
+        let exchanges = DashMap::new();
+        let _ = exchanges.insert(exchange.exchange_account_id, exchange);
+
+        let json = open_orders_json(&exchanges).expect("in test");
+        let orders: serde_json::Value = serde_json::from_str(&json).expect("in test");
+        let orders = orders.as_array().expect("in test");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0]["client_order_id"], "open_order");
+        assert_eq!(orders[0]["side"], "Buy");
+        assert_eq!(orders[0]["amount"], "12");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn cancel_not_existing_order() {
+        let (exchange, _event_receiver) = get_test_exchange(false);
+        let exchanges = DashMap::new();
+        let _ = exchanges.insert(exchange.exchange_account_id, exchange);
+
+        let result = cancel_order(
+            &exchanges,
+            "not_existing_order".to_owned(),
+            CancellationToken::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn precision_check_rounds_price_and_amount() {
+        let (price, amount, check) = check_precision(&symbol(), dec!(2.04), dec!(12.7));
+
+        assert_eq!(price, dec!(2.0));
+        assert_eq!(amount, dec!(12));
+        assert!(!check.passed);
+
+        let (_, _, check) = check_precision(&symbol(), dec!(2.1), dec!(12));
+        assert!(check.passed);
+    }
+
+    #[test]
+    fn min_notional_check() {
+        assert!(check_min_notional(&symbol(), dec!(2), dec!(5)).passed);
+        assert!(!check_min_notional(&symbol(), dec!(2), dec!(4)).passed);
+    }
+}
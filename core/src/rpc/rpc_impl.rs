@@ -1,4 +1,8 @@
+use dashmap::DashMap;
 use jsonrpc_core::Result;
+use jsonrpc_pubsub::typed::Subscriber;
+use jsonrpc_pubsub::{Session, SubscriptionId};
+use mmb_domain::market::ExchangeAccountId;
 use mmb_rpc::rest_api::server_side_error;
 use mmb_rpc::rest_api::MmbRpc;
 use parking_lot::Mutex;
@@ -6,35 +10,61 @@ use tokio::sync::mpsc;
 
 use std::sync::Arc;
 
-use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::disposition_execution::state_graph::ExecutorStateGraph;
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::app_lifetime_manager::{ActionAfterGracefulShutdown, AppLifetimeManager};
 use crate::statistic_service::StatisticService;
 use mmb_rpc::rest_api::ErrorCode;
 
 use super::common::send_restart;
 use super::common::send_stop;
-use super::common::set_config;
+use super::common::{set_config, SettingsValidator};
+use super::events::EventsSubscriptions;
+use super::orders;
 
 pub struct RpcImpl {
     server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
     statistics: Arc<StatisticService>,
     engine_settings: String,
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: Arc<Mutex<BalanceManager>>,
+    lifetime_manager: Arc<AppLifetimeManager>,
+    validate_settings: SettingsValidator,
+    events_subscriptions: Arc<EventsSubscriptions>,
+    executor_state_graph: ExecutorStateGraph,
 }
 
 impl RpcImpl {
-    pub fn new(
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
         server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
         statistics: Arc<StatisticService>,
         engine_settings: String,
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        validate_settings: SettingsValidator,
+        events_subscriptions: Arc<EventsSubscriptions>,
+        executor_state_graph: ExecutorStateGraph,
     ) -> Self {
         Self {
             server_stopper_tx,
             statistics,
             engine_settings,
+            exchanges,
+            balance_manager,
+            lifetime_manager,
+            validate_settings,
+            events_subscriptions,
+            executor_state_graph,
         }
     }
 }
 
 impl MmbRpc for RpcImpl {
+    type Metadata = Arc<Session>;
+
     fn health(&self) -> Result<String> {
         Ok("Engine is working".into())
     }
@@ -48,13 +78,13 @@ impl MmbRpc for RpcImpl {
     }
 
     fn set_config(&self, settings: String) -> Result<String> {
-        set_config(settings)?;
+        set_config(settings, self.validate_settings)?;
         send_restart(self.server_stopper_tx.clone())?;
         Ok("Config was successfully updated. Trading engine will be restarted".into())
     }
 
     fn stats(&self) -> Result<String> {
-        let json_statistic = serde_json::to_string(&self.statistics.statistic_service_state)
+        let mut json_statistic = serde_json::to_value(&self.statistics.statistic_service_state)
             .map_err(|err| {
                 log::warn!(
                     "Failed to convert {:?} to string: {}",
@@ -64,6 +94,43 @@ impl MmbRpc for RpcImpl {
                 server_side_error(ErrorCode::FailedToSaveNewConfig)
             })?;
 
-        Ok(json_statistic)
+        if let Some(json_statistic) = json_statistic.as_object_mut() {
+            let reservation_count = self.balance_manager.lock().reservation_count();
+            let _ = json_statistic.insert("reservation_count".to_owned(), reservation_count.into());
+        }
+
+        Ok(json_statistic.to_string())
+    }
+
+    fn list_open_orders(&self) -> Result<String> {
+        orders::open_orders_json(&self.exchanges)
+    }
+
+    fn cancel_order(&self, client_order_id: String) -> Result<String> {
+        orders::cancel_order(
+            &self.exchanges,
+            client_order_id,
+            self.lifetime_manager.stop_token(),
+        )
+    }
+
+    fn simulate_order(&self, order: String) -> Result<String> {
+        orders::simulate_order(&self.exchanges, &self.balance_manager, order)
+    }
+
+    fn subscribe_events(&self, _meta: Self::Metadata, subscriber: Subscriber<String>) {
+        self.events_subscriptions.subscribe(subscriber)
+    }
+
+    fn unsubscribe_events(
+        &self,
+        _meta: Option<Self::Metadata>,
+        subscription_id: SubscriptionId,
+    ) -> Result<bool> {
+        self.events_subscriptions.unsubscribe(subscription_id)
+    }
+
+    fn get_executor_graph(&self) -> Result<String> {
+        Ok(self.executor_state_graph.get())
     }
 }
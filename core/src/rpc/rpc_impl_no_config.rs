@@ -1,5 +1,8 @@
-use jsonrpc_core::Result;
+use jsonrpc_core::{Error, Result};
+use jsonrpc_pubsub::typed::Subscriber;
+use jsonrpc_pubsub::{Session, SubscriptionId};
 use mmb_rpc::rest_api::MmbRpc;
+use mmb_rpc::rest_api::{server_side_error, ErrorCode};
 use mmb_utils::send_expected::SendExpectedByRef;
 use parking_lot::Mutex;
 use tokio::sync::mpsc;
@@ -9,28 +12,33 @@ use std::sync::Arc;
 use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
 
 use super::common::send_stop;
-use super::common::set_config;
+use super::common::{set_config, SettingsValidator};
 
 static CONFIG_IS_NOT_SET: &str = "Config isn't set";
 
 pub struct RpcImplNoConfig {
     server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
     wait_config_tx: mpsc::Sender<()>,
+    validate_settings: SettingsValidator,
 }
 
 impl RpcImplNoConfig {
     pub fn new(
         server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
         wait_config_tx: mpsc::Sender<()>,
+        validate_settings: SettingsValidator,
     ) -> Self {
         Self {
             server_stopper_tx,
             wait_config_tx,
+            validate_settings,
         }
     }
 }
 
 impl MmbRpc for RpcImplNoConfig {
+    type Metadata = Arc<Session>;
+
     fn health(&self) -> Result<String> {
         Ok(CONFIG_IS_NOT_SET.into())
     }
@@ -44,7 +52,7 @@ impl MmbRpc for RpcImplNoConfig {
     }
 
     fn set_config(&self, settings: String) -> Result<String> {
-        set_config(settings)?;
+        set_config(settings, self.validate_settings)?;
         self.wait_config_tx.send_expected(());
         Ok("Config was successfully set. Trading engine will be launched".into())
     }
@@ -52,4 +60,37 @@ impl MmbRpc for RpcImplNoConfig {
     fn stats(&self) -> Result<String> {
         Ok(CONFIG_IS_NOT_SET.into())
     }
+
+    fn list_open_orders(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn cancel_order(&self, _client_order_id: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn simulate_order(&self, _order: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn subscribe_events(&self, _meta: Self::Metadata, subscriber: Subscriber<String>) {
+        if subscriber
+            .reject(Error::invalid_params(CONFIG_IS_NOT_SET))
+            .is_err()
+        {
+            log::warn!("Control panel disconnected before rejection of events subscription");
+        }
+    }
+
+    fn unsubscribe_events(
+        &self,
+        _meta: Option<Self::Metadata>,
+        _subscription_id: SubscriptionId,
+    ) -> Result<bool> {
+        Err(server_side_error(ErrorCode::SubscriptionNotFound))
+    }
+
+    fn get_executor_graph(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
 }
@@ -32,6 +32,7 @@ impl CleanupOrdersService {
         self.exchanges.iter().for_each(|pair| {
             cleanup(&pair.orders.cache_by_exchange_id, deadline);
             cleanup(&pair.orders.cache_by_client_id, deadline);
+            pair.orders.prune_status_index();
         });
     }
 }
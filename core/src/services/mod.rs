@@ -2,5 +2,7 @@ pub mod cleanup_database;
 pub mod cleanup_orders;
 pub mod exchange_time_latency;
 pub mod live_ranges;
-pub(crate) mod market_prices;
+pub mod market_prices;
+pub mod order_audit_log;
+pub mod order_book_backfill;
 pub mod usd_convertion;
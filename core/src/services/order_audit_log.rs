@@ -0,0 +1,335 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::event::{OrderEvent, OrderEventType};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderSide, OrderSnapshot, Price,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use mockall_double::double;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, oneshot};
+
+use crate::lifecycle::trading_engine::Service;
+#[double]
+use crate::misc::time::time_manager;
+use crate::settings::OrderAuditLogSettings;
+
+const DEFAULT_MAX_FILE_SIZE_BYTES: u64 = 100 * 1024 * 1024;
+const DEFAULT_ROTATION_PERIOD_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OrderAuditAction {
+    Create,
+    CreateFailed,
+    Fill,
+    Complete,
+    Cancel,
+    CancelFailed,
+}
+
+/// Single line of audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrderAuditRecord {
+    pub timestamp: DateTime,
+    pub action: OrderAuditAction,
+    pub client_order_id: ClientOrderId,
+    pub exchange_order_id: Option<ExchangeOrderId>,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    /// Price of the last fill for `Fill` action and order price otherwise
+    pub price: Price,
+    /// Amount of the last fill for `Fill` action and order amount otherwise
+    pub amount: Amount,
+}
+
+impl OrderAuditRecord {
+    pub fn from_event(event: &OrderEvent, timestamp: DateTime) -> Self {
+        let (action, fill_order) = match &event.event_type {
+            OrderEventType::CreateOrderSucceeded => (OrderAuditAction::Create, None),
+            OrderEventType::CreateOrderFailed => (OrderAuditAction::CreateFailed, None),
+            OrderEventType::OrderFilled { cloned_order } => {
+                (OrderAuditAction::Fill, Some(cloned_order))
+            }
+            OrderEventType::OrderCompleted { .. } => (OrderAuditAction::Complete, None),
+            OrderEventType::CancelOrderSucceeded => (OrderAuditAction::Cancel, None),
+            OrderEventType::CancelOrderFailed => (OrderAuditAction::CancelFailed, None),
+        };
+
+        let order = &event.order;
+        let (price, amount) = fill_order
+            .and_then(|snapshot| last_fill_price_and_amount(snapshot))
+            .unwrap_or_else(|| (order.price(), order.amount()));
+
+        OrderAuditRecord {
+            timestamp,
+            action,
+            client_order_id: order.client_order_id(),
+            exchange_order_id: order.exchange_order_id(),
+            exchange_account_id: order.exchange_account_id(),
+            currency_pair: order.currency_pair(),
+            side: order.side(),
+            price,
+            amount,
+        }
+    }
+}
+
+fn last_fill_price_and_amount(snapshot: &OrderSnapshot) -> Option<(Price, Amount)> {
+    snapshot
+        .fills
+        .fills
+        .last()
+        .map(|fill| (fill.price(), fill.amount()))
+}
+
+/// Append-only file which is replaced by a new one when it becomes too big or too old
+struct RotatingFile {
+    dir: PathBuf,
+    max_file_size_bytes: u64,
+    rotation_period: chrono::Duration,
+    current: Option<CurrentFile>,
+    /// Count of opened files to keep names unique even if files are rotated at the same time
+    opened_files_count: u64,
+}
+
+struct CurrentFile {
+    file: File,
+    size: u64,
+    created_at: DateTime,
+}
+
+impl RotatingFile {
+    fn new(settings: &OrderAuditLogSettings) -> Self {
+        let rotation_period_secs = settings
+            .rotation_period_secs
+            .unwrap_or(DEFAULT_ROTATION_PERIOD_SECS);
+
+        RotatingFile {
+            dir: settings.dir.clone(),
+            max_file_size_bytes: settings
+                .max_file_size_bytes
+                .unwrap_or(DEFAULT_MAX_FILE_SIZE_BYTES),
+            rotation_period: chrono::Duration::seconds(rotation_period_secs as i64),
+            current: None,
+            opened_files_count: 0,
+        }
+    }
+
+    fn write_line(&mut self, line: &str, now: DateTime) -> Result<()> {
+        let line_size = line.len() as u64 + 1;
+        let current = match self.current.take() {
+            Some(current)
+                if current.size + line_size <= self.max_file_size_bytes
+                    && now - current.created_at < self.rotation_period =>
+            {
+                current
+            }
+            _ => self.open_new_file(now)?,
+        };
+        let current = self.current.insert(current);
+
+        writeln!(current.file, "{line}").context("Unable to write order audit record")?;
+        current
+            .file
+            .flush()
+            .context("Unable to flush order audit log")?;
+        current.size += line_size;
+
+        Ok(())
+    }
+
+    fn open_new_file(&mut self, now: DateTime) -> Result<CurrentFile> {
+        fs::create_dir_all(&self.dir).with_context(|| {
+            format!(
+                "Unable to create order audit log dir {}",
+                self.dir.display()
+            )
+        })?;
+
+        self.opened_files_count += 1;
+        let path = self.dir.join(format!(
+            "orders_audit_{}_{}.jsonl",
+            now.format("%Y%m%d_%H%M%S"),
+            self.opened_files_count
+        ));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Unable to open order audit log {}", path.display()))?;
+        let size = file.metadata().map(|x| x.len()).unwrap_or_default();
+
+        Ok(CurrentFile {
+            file,
+            size,
+            created_at: now,
+        })
+    }
+}
+
+/// Writes every order action to local files independently of `EventRecorder`,
+/// so records are kept even if database is unavailable
+pub struct OrderAuditLogService {
+    work_finished_receiver: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+}
+
+impl OrderAuditLogService {
+    pub fn new() -> Arc<Self> {
+        Arc::new(OrderAuditLogService {
+            work_finished_receiver: Default::default(),
+        })
+    }
+
+    pub async fn start(
+        self: Arc<Self>,
+        settings: OrderAuditLogSettings,
+        mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let mut rotating_file = RotatingFile::new(&settings);
+        let (work_finished_sender, receiver) = oneshot::channel();
+        *self.work_finished_receiver.lock() = Some(receiver);
+
+        loop {
+            let event = tokio::select! {
+                event_res = events_receiver.recv() => event_res,
+                _ = cancellation_token.when_cancelled() => {
+                    let _ = work_finished_sender.send(Ok(()));
+                    return Ok(());
+                }
+            };
+
+            match event {
+                Ok(ExchangeEvent::OrderEvent(order_event)) => {
+                    let record = OrderAuditRecord::from_event(&order_event, time_manager::now());
+                    let line = serde_json::to_string(&record)
+                        .context("Unable to serialize order audit record")?;
+
+                    if let Err(err) = rotating_file.write_line(&line, record.timestamp) {
+                        log::error!("Failed to write order audit record {line}: {err:?}");
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped_count)) => {
+                    log::error!("Order audit log skipped {skipped_count} events")
+                }
+                Err(RecvError::Closed) => {
+                    let _ = work_finished_sender.send(Ok(()));
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+impl Service for OrderAuditLogService {
+    fn name(&self) -> &str {
+        "OrderAuditLogService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>> {
+        self.work_finished_receiver.lock().take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper;
+    use crate::misc::time;
+    use crate::misc::time::tests::MockClock;
+    use mmb_domain::order::snapshot::OrderRole;
+    use rust_decimal_macros::dec;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn create_event(event_type: OrderEventType) -> OrderEvent {
+        let order = test_helper::create_order_ref(
+            &ClientOrderId::unique_id(),
+            Some(OrderRole::Maker),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("eth".into(), "btc".into()),
+            dec!(0.5),
+            dec!(2),
+            OrderSide::Buy,
+        );
+
+        OrderEvent::new(order, event_type)
+    }
+
+    fn settings(max_file_size_bytes: Option<u64>) -> OrderAuditLogSettings {
+        OrderAuditLogSettings {
+            dir: std::env::temp_dir().join(format!("orders_audit_{}", Uuid::new_v4())),
+            max_file_size_bytes,
+            rotation_period_secs: Some(60),
+        }
+    }
+
+    fn files_count(settings: &OrderAuditLogSettings) -> usize {
+        fs::read_dir(&settings.dir).expect("in test").count()
+    }
+
+    #[test]
+    fn record_from_create_event() {
+        let (_time_manager_context, _tm_locker) = time::tests::init_mock(MockClock::default());
+        let event = create_event(OrderEventType::CreateOrderSucceeded);
+
+        let record = OrderAuditRecord::from_event(&event, time_manager::now());
+
+        assert_eq!(record.action, OrderAuditAction::Create);
+        assert_eq!(record.client_order_id, event.order.client_order_id());
+        assert_eq!(record.price, dec!(0.5));
+        assert_eq!(record.amount, dec!(2));
+
+        let json = serde_json::to_value(&record).expect("in test");
+        assert_eq!(json["action"], "Create");
+        assert_eq!(json["exchange_account_id"], "Binance_0");
+        assert_eq!(json["currency_pair"], "eth/btc");
+    }
+
+    #[test]
+    fn rotate_file_by_size() {
+        let settings = settings(Some(10));
+        let mut rotating_file = RotatingFile::new(&settings);
+        let now = MockClock::default().now();
+
+        rotating_file.write_line("record_1", now).expect("in test");
+        rotating_file.write_line("record_2", now).expect("in test");
+
+        assert_eq!(files_count(&settings), 2);
+        fs::remove_dir_all(&settings.dir).expect("in test");
+    }
+
+    #[test]
+    fn rotate_file_by_time() {
+        let settings = settings(None);
+        let mut rotating_file = RotatingFile::new(&settings);
+        let mock_clock = MockClock::default();
+
+        rotating_file
+            .write_line("record_1", mock_clock.now())
+            .expect("in test");
+        rotating_file
+            .write_line("record_2", mock_clock.now())
+            .expect("in test");
+        assert_eq!(files_count(&settings), 1);
+
+        mock_clock.advance_by(Duration::from_secs(60));
+        rotating_file
+            .write_line("record_3", mock_clock.now())
+            .expect("in test");
+
+        assert_eq!(files_count(&settings), 2);
+        fs::remove_dir_all(&settings.dir).expect("in test");
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::Duration;
+use dashmap::DashMap;
+use mmb_database::impl_event;
+use mmb_database::postgres_db::order_book_snapshots::{
+    get_last_order_book_snapshot_times, ORDER_BOOK_SNAPSHOTS_TABLE_NAME,
+};
+use mmb_database::postgres_db::PgPool;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::SortedOrderData;
+use mmb_utils::DateTime;
+use mockall_double::double;
+use serde::Serialize;
+
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+#[double]
+use crate::misc::time::time_manager;
+use crate::settings::OrderBookBackfillSettings;
+
+const DEFAULT_DEPTH: u32 = 100;
+
+/// Order book snapshot requested via REST API
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderBookSnapshotEvent {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub receipt_time: DateTime,
+    /// Time in the gap of recorded history that is filled by the snapshot
+    pub snapshot_time: DateTime,
+    pub asks: SortedOrderData,
+    pub bids: SortedOrderData,
+}
+
+impl_event!(OrderBookSnapshotEvent, ORDER_BOOK_SNAPSHOTS_TABLE_NAME);
+
+/// Times of snapshots that fill the gap in recorded order books of a market within lookback period
+/// with the configured interval. Empty if the latest recorded order book is recent enough
+fn get_backfill_times(
+    last_recorded_time: Option<DateTime>,
+    now: DateTime,
+    settings: &OrderBookBackfillSettings,
+) -> Vec<DateTime> {
+    let lookback_start = now - Duration::seconds(settings.lookback_secs as i64);
+    let gap_start = last_recorded_time.map_or(lookback_start, |time| time.max(lookback_start));
+
+    let interval = Duration::seconds(settings.interval_secs as i64);
+    let slots_count = (now - gap_start).num_seconds() / interval.num_seconds();
+
+    (1..=slots_count)
+        .map(|slot| gap_start + interval * slot as i32)
+        .collect()
+}
+
+/// Fills gaps in recorded order book history on start before trading.
+/// Order books are recorded by the engine to `liquidity_order_books` while it works, so gaps are
+/// detected by them and by earlier backfilled snapshots. Exchanges provide only current state of
+/// order book via REST API, so every gap is filled with the current snapshot at the configured interval
+pub struct OrderBookBackfillService {
+    settings: OrderBookBackfillSettings,
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    event_recorder: Arc<EventRecorder>,
+    pool: PgPool,
+}
+
+impl OrderBookBackfillService {
+    pub fn new(
+        settings: OrderBookBackfillSettings,
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        event_recorder: Arc<EventRecorder>,
+        pool: PgPool,
+    ) -> Self {
+        Self {
+            settings,
+            exchanges,
+            event_recorder,
+            pool,
+        }
+    }
+
+    pub async fn run(&self) {
+        if let Err(err) = self.backfill().await {
+            log::error!("Failed to backfill order book snapshots. {err:?}");
+        }
+    }
+
+    async fn backfill(&self) -> Result<()> {
+        let now = time_manager::now();
+        let lookback_start = now - Duration::seconds(self.settings.lookback_secs as i64);
+
+        let last_recorded_times: HashMap<_, _> =
+            get_last_order_book_snapshot_times(&self.pool, lookback_start)
+                .await
+                .context("Failed to get last order book snapshot times")?
+                .into_iter()
+                .map(|x| ((x.exchange_id, x.currency_pair), x.insert_time))
+                .collect();
+
+        for exchange in self.exchanges.iter() {
+            let exchange_account_id = exchange.exchange_account_id;
+            for currency_pair in exchange.symbols.iter().map(|x| *x.key()) {
+                let last_recorded_time = last_recorded_times
+                    .get(&(
+                        exchange_account_id.exchange_id.to_string(),
+                        currency_pair.to_string(),
+                    ))
+                    .copied();
+
+                let backfill_times = get_backfill_times(last_recorded_time, now, &self.settings);
+                if backfill_times.is_empty() {
+                    continue;
+                }
+
+                let depth = self.settings.depth.unwrap_or(DEFAULT_DEPTH);
+                let order_book = match exchange
+                    .exchange_client
+                    .get_order_book_snapshot(currency_pair, depth)
+                    .await
+                {
+                    Ok(order_book) => order_book,
+                    Err(err) => {
+                        log::warn!("Failed to backfill order book for {exchange_account_id} {currency_pair}: {err:?}");
+                        continue;
+                    }
+                };
+
+                let receipt_time = time_manager::now();
+                for snapshot_time in backfill_times {
+                    self.event_recorder.save(OrderBookSnapshotEvent {
+                        exchange_account_id,
+                        currency_pair,
+                        receipt_time,
+                        snapshot_time,
+                        asks: order_book.asks.clone(),
+                        bids: order_book.bids.clone(),
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::never_recorded(None, &[3_000, 2_000, 1_000, 0])]
+    #[case::recorded_long_ago(Some(2_500), &[1_500, 500])]
+    #[case::recorded_before_lookback(Some(7_200), &[3_000, 2_000, 1_000, 0])]
+    #[case::recorded_recently(Some(999), &[])]
+    fn backfill_times(#[case] recorded_secs_ago: Option<i64>, #[case] expected_secs_ago: &[i64]) {
+        let settings = OrderBookBackfillSettings {
+            lookback_secs: 4_000,
+            interval_secs: 1_000,
+            depth: None,
+        };
+        let now = Utc::now();
+        let last_recorded_time = recorded_secs_ago.map(|secs| now - Duration::seconds(secs));
+
+        assert_eq!(
+            get_backfill_times(last_recorded_time, now, &settings),
+            expected_secs_ago
+                .iter()
+                .map(|&secs| now - Duration::seconds(secs))
+                .collect_vec()
+        );
+    }
+}
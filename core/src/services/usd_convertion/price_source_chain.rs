@@ -1,4 +1,4 @@
-use mmb_domain::market::CurrencyCode;
+use mmb_domain::market::{CurrencyCode, ExchangeId};
 
 use super::rebase_price_step::RebasePriceStep;
 
@@ -21,4 +21,18 @@ impl PriceSourceChain {
             rebase_price_steps,
         }
     }
+
+    /// The same chain with prices taken from another exchange
+    pub fn with_exchange_id(&self, exchange_id: ExchangeId) -> Self {
+        Self::new(
+            self.start_currency_code,
+            self.end_currency_code,
+            self.rebase_price_steps
+                .iter()
+                .map(|step| {
+                    RebasePriceStep::new(exchange_id, step.symbol.clone(), step.direction.clone())
+                })
+                .collect(),
+        )
+    }
 }
@@ -1,6 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
-    fmt,
+    fmt, iter,
     sync::Arc,
 };
 
@@ -18,7 +18,7 @@ use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::market::{CurrencyCode, ExchangeId, MarketId};
+use mmb_domain::market::{CurrencyCode, ExchangeAccountId, ExchangeId, MarketId};
 use mmb_domain::order::snapshot::Amount;
 use mmb_domain::order::snapshot::PriceByOrderSide;
 use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
@@ -77,10 +77,10 @@ impl PriceSourceEventLoop {
                 main_event_res = self.convert_currency_notification_receiver.recv() => {
                    let convert_amount = main_event_res.context("Error during receiving event on convert_currency_notification_receiver")?;
 
-                    let result = prices_calculator::convert_amount(
+                    let result = prices_calculator::convert_amount_by_first_available_chain(
                         convert_amount.src_amount,
                         &self.local_snapshot_service,
-                        &convert_amount.chain,
+                        &convert_amount.chains,
                     );
                     convert_amount.task_finished_sender.send_expected(result);
                 },
@@ -148,6 +148,8 @@ pub struct PriceSourceService {
     tx_main: mpsc::Sender<ConvertAmount>,
     convert_currency_notification_receiver: Mutex<Option<mpsc::Receiver<ConvertAmount>>>,
     price_source_chains: HashMap<ConvertCurrencyDirection, PriceSourceChain>,
+    /// Exchanges which are used in the given order if prices can't be calculated on the primary ones
+    fallback_exchange_account_ids: Vec<ExchangeAccountId>,
 }
 
 impl PriceSourceService {
@@ -155,14 +157,14 @@ impl PriceSourceService {
         currency_pair_to_symbol_converter: Arc<CurrencyPairToSymbolConverter>,
         price_source_settings: &[CurrencyPriceSourceSettings],
         price_sources_loader: PriceSourcesLoader,
-    ) -> Arc<Self> {
+    ) -> Self {
         let price_source_chains = Self::prepare_price_source_chains(
             price_source_settings,
             currency_pair_to_symbol_converter,
         );
         let (tx_main, convert_currency_notification_receiver) = mpsc::channel(20_000);
 
-        Arc::new(Self {
+        Self {
             price_sources_loader,
             tx_main,
             convert_currency_notification_receiver: Mutex::new(Some(
@@ -177,8 +179,15 @@ impl PriceSourceService {
                     )
                 })
                 .collect(),
-        })
+            fallback_exchange_account_ids: Vec::new(),
+        }
+    }
+
+    pub fn with_fallback(mut self, fallbacks: Vec<ExchangeAccountId>) -> Self {
+        self.fallback_exchange_account_ids = fallbacks;
+        self
     }
+
     pub async fn start(
         self: Arc<Self>,
         price_sources_saver: PriceSourcesSaver,
@@ -192,7 +201,10 @@ impl PriceSourceService {
             .expect("PriceSourceEventLoop::convert_currency_notification_receiver is none");
 
         PriceSourceEventLoop::run(
-            self.price_source_chains.values().cloned().collect_vec(),
+            self.price_source_chains
+                .values()
+                .flat_map(|chain| self.chain_with_fallbacks(chain))
+                .collect_vec(),
             price_sources_saver,
             rx_core,
             receiver,
@@ -320,6 +332,17 @@ impl PriceSourceService {
         list.push(RebasePriceStep::new(exchange_id, symbol, direction));
     }
 
+    /// Primary chain followed by the same chain built on every fallback exchange
+    fn chain_with_fallbacks(&self, chain: &PriceSourceChain) -> Vec<PriceSourceChain> {
+        let fallback_chains = self
+            .fallback_exchange_account_ids
+            .iter()
+            .map(|exchange_account_id| chain.with_exchange_id(exchange_account_id.exchange_id))
+            .filter(|fallback_chain| fallback_chain != chain);
+
+        iter::once(chain.clone()).chain(fallback_chains).collect()
+    }
+
     /// Convert amount from 'from' currency position to 'to' currency by current price
     /// Return converted amount or None if can't calculate price for converting and Err if something bad was happened
     pub async fn convert_amount(
@@ -342,7 +365,11 @@ impl PriceSourceService {
         let (tx_result, rx_result) = oneshot::channel();
         if let Err(error) = self
             .tx_main
-            .send(ConvertAmount::new(chain.clone(), src_amount, tx_result))
+            .send(ConvertAmount::new(
+                self.chain_with_fallbacks(chain),
+                src_amount,
+                tx_result,
+            ))
             .await
         {
             let message = format!(
@@ -391,30 +418,35 @@ impl PriceSourceService {
                     convert_currency_direction, self.price_source_chains
                 )
             });
-        prices_calculator::convert_amount_in_past(
-            src_amount,
-            &price_sources,
-            time_in_past,
-            prices_source_chain,
-        )
+        self.chain_with_fallbacks(prices_source_chain)
+            .iter()
+            .find_map(|chain| {
+                prices_calculator::convert_amount_in_past(
+                    src_amount,
+                    &price_sources,
+                    time_in_past,
+                    chain,
+                )
+            })
     }
 }
 
 #[derive(Debug)]
 pub struct ConvertAmount {
-    pub chain: PriceSourceChain,
+    /// Primary chain followed by fallback ones
+    pub chains: Vec<PriceSourceChain>,
     pub src_amount: Amount,
     pub task_finished_sender: oneshot::Sender<Option<Decimal>>,
 }
 
 impl ConvertAmount {
     pub fn new(
-        chain: PriceSourceChain,
+        chains: Vec<PriceSourceChain>,
         src_amount: Amount,
         task_finished_sender: oneshot::Sender<Option<Decimal>>,
     ) -> Self {
         Self {
-            chain,
+            chains,
             src_amount,
             task_finished_sender,
         }
@@ -423,9 +455,12 @@ impl ConvertAmount {
 
 #[cfg(test)]
 pub mod test {
+    use chrono::Utc;
     use mmb_domain::exchanges::symbol::Precision;
     use mmb_domain::market::CurrencyPair;
     use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order_book_data;
+    use mmb_utils::hashmap;
     use rstest::rstest;
     use rust_decimal_macros::dec;
 
@@ -863,4 +898,118 @@ pub mod test {
             Arc::new(converter),
         );
     }
+
+    fn fallback_exchange_account_id() -> ExchangeAccountId {
+        ExchangeAccountId::new("Bitmex", 0)
+    }
+
+    fn create_service_with_fallback(
+        base: CurrencyCode,
+        quote: CurrencyCode,
+        converter: CurrencyPairToSymbolConverter,
+    ) -> PriceSourceService {
+        let price_source_settings = vec![CurrencyPriceSourceSettings::new(
+            quote,
+            base,
+            vec![ExchangeIdCurrencyPairSettings {
+                exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
+                currency_pair: CurrencyPair::from_codes(base, quote),
+            }],
+        )];
+
+        PriceSourceService::new(
+            Arc::new(converter),
+            &price_source_settings,
+            PriceSourcesLoader::default(),
+        )
+        .with_fallback(vec![fallback_exchange_account_id()])
+    }
+
+    #[rstest]
+    #[case::primary_has_price(true, dec!(1) / dec!(6) * dec!(10))]
+    #[case::primary_has_no_price(false, dec!(1) / dec!(9) * dec!(10))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn convert_amount_with_fallback(
+        #[case] primary_has_price: bool,
+        #[case] expected: Amount,
+    ) {
+        let base = "USDT".into();
+        let quote = "BTC".into();
+        let currency_pair = CurrencyPair::from_codes(base, quote);
+
+        let symbol = create_symbol(base, quote);
+        let (mut converter, _locker) = CurrencyPairToSymbolConverter::init_mock();
+        converter
+            .expect_get_symbol()
+            .returning(move |_, _| symbol.clone());
+
+        let service = create_service_with_fallback(base, quote, converter);
+        let chain = service
+            .price_source_chains
+            .get(&ConvertCurrencyDirection::new(quote, base))
+            .expect("in test");
+
+        let primary_market_id =
+            MarketId::new(PriceSourceServiceTestBase::exchange_id(), currency_pair);
+        let fallback_market_id =
+            MarketId::new(fallback_exchange_account_id().exchange_id, currency_pair);
+        let primary_snapshot = match primary_has_price {
+            true => order_book_data![
+                dec!(7) => dec!(1),
+                ;
+                dec!(5) => dec!(1),
+            ],
+            false => order_book_data!(),
+        }
+        .to_orderbook_snapshot(Utc::now());
+        let fallback_snapshot = order_book_data![
+            dec!(10) => dec!(1),
+            ;
+            dec!(8) => dec!(1),
+        ]
+        .to_orderbook_snapshot(Utc::now());
+        let local_snapshot_service = LocalSnapshotsService::new(hashmap![
+            primary_market_id => primary_snapshot,
+            fallback_market_id => fallback_snapshot
+        ]);
+
+        let actual = prices_calculator::convert_amount_by_first_available_chain(
+            dec!(10),
+            &local_snapshot_service,
+            &service.chain_with_fallbacks(chain),
+        );
+
+        assert_eq!(actual, Some(expected));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn fallback_chain_uses_fallback_exchange() {
+        let base = "USDT".into();
+        let quote = "BTC".into();
+
+        let symbol = create_symbol(base, quote);
+        let (mut converter, _locker) = CurrencyPairToSymbolConverter::init_mock();
+        converter
+            .expect_get_symbol()
+            .returning(move |_, _| symbol.clone());
+
+        let service = create_service_with_fallback(base, quote, converter);
+        let chain = service
+            .price_source_chains
+            .get(&ConvertCurrencyDirection::new(quote, base))
+            .expect("in test");
+
+        let chains = service.chain_with_fallbacks(chain);
+
+        assert_eq!(chains.len(), 2);
+        assert_eq!(&chains[0], chain);
+        assert_eq!(
+            chains[1]
+                .rebase_price_steps
+                .iter()
+                .map(|step| step.exchange_id)
+                .collect_vec(),
+            vec![fallback_exchange_account_id().exchange_id]
+        );
+    }
 }
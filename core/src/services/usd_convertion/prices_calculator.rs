@@ -6,6 +6,7 @@ use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_utils::DateTime;
 use rust_decimal_macros::dec;
 
+use crate::math::{checked_div, checked_mul};
 use crate::{
     order_book::local_snapshot_service::LocalSnapshotsService,
     services::usd_convertion::{
@@ -36,12 +37,18 @@ fn calculate_amount_for_chain(
         let market_id = MarketId::new(step.exchange_id, step.symbol.currency_pair());
         let calculated_price = (calculate_price)(market_id)?;
 
-        match step.direction {
-            RebaseDirection::ToQuote => rebase_price *= calculated_price,
-            RebaseDirection::ToBase => rebase_price /= calculated_price,
-        }
+        let rebased = match step.direction {
+            RebaseDirection::ToQuote => checked_mul(rebase_price, calculated_price),
+            RebaseDirection::ToBase => checked_div(rebase_price, calculated_price),
+        };
+        rebase_price = rebased
+            .map_err(|err| log::error!("Unable to rebase price for {market_id:?}: {err:?}"))
+            .ok()?;
     }
-    Some(rebase_price * src_amount)
+
+    checked_mul(rebase_price, src_amount)
+        .map_err(|err| log::error!("Unable to convert amount {src_amount}: {err:?}"))
+        .ok()
 }
 
 pub(crate) fn convert_amount(
@@ -56,6 +63,29 @@ pub(crate) fn convert_amount(
     })
 }
 
+/// Convert amount using the first chain which has prices for all of its steps
+pub(crate) fn convert_amount_by_first_available_chain(
+    src_amount: Amount,
+    local_snapshot_service: &LocalSnapshotsService,
+    price_source_chains: &[PriceSourceChain],
+) -> Option<Amount> {
+    price_source_chains
+        .iter()
+        .enumerate()
+        .find_map(|(index, price_source_chain)| {
+            let amount = convert_amount(src_amount, local_snapshot_service, price_source_chain)?;
+            if index > 0 {
+                log::warn!(
+                    "Amount {} -> {} was converted using fallback chain {:?}",
+                    price_source_chain.start_currency_code,
+                    price_source_chain.end_currency_code,
+                    price_source_chain.rebase_price_steps
+                );
+            }
+            Some(amount)
+        })
+}
+
 pub fn convert_amount_in_past(
     src_amount: Amount,
     price_cache: &HashMap<MarketId, PriceByOrderSide>,
@@ -178,6 +208,28 @@ mod test {
         assert_eq!(dec!(1) / (dec!(12) / dec!(2)) * src_amount, price_now);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_amount_now_with_overflow() {
+        let (currency_pair, price_source_chain, _locker) = generate_one_step_setup();
+
+        let snapshot = order_book_data![
+            dec!(0.0000000002) => dec!(1),
+            ;
+            dec!(0.0000000001) => dec!(1),
+        ]
+        .to_orderbook_snapshot(Utc::now());
+
+        let market_id = MarketId::new(PriceSourceServiceTestBase::exchange_id(), currency_pair);
+
+        let snapshot_service = LocalSnapshotsService::new(hashmap![market_id => snapshot]);
+
+        // converted amount doesn't fit into decimal
+        let src_amount = dec!(100000000000000000000);
+        let price_now = convert_amount(src_amount, &snapshot_service, &price_source_chain);
+
+        assert_eq!(price_now, None);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn calculate_amount_now_using_one_step_without_price() {
         let (currency_pair, price_source_chain, _locker) = generate_one_step_setup();
@@ -1,12 +1,52 @@
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use mmb_database::postgres_db::PgPoolSettings;
+use mmb_domain::events::AllowedEventSourceType;
 use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
-use mmb_domain::order::snapshot::Amount;
+use mmb_domain::order::snapshot::{Amount, OrderFillRole};
+use mmb_utils::DateTime;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
+
+const DEFAULT_MAX_POOL_SIZE: u32 = 5;
 
 pub trait DispositionStrategySettings {
     fn exchange_account_id(&self) -> ExchangeAccountId;
     fn currency_pair(&self) -> CurrencyPair;
     fn max_amount(&self) -> Amount;
+
+    /// Count of price slots by every side. `TradingContextBySide::estimating` calculated by strategy
+    /// should contain the same count of items
+    fn price_slots_count(&self) -> usize {
+        1
+    }
+
+    /// Taker orders are placed only if expected edge after fees exceeds this threshold in bps.
+    /// Taker orders aren't filtered if not set
+    fn min_profit_bps(&self) -> Option<Decimal> {
+        None
+    }
+
+    /// Orders are cancelled if they aren't filled during TTL since creation acknowledged by exchange.
+    /// Orders aren't cancelled by time if not set
+    fn order_ttl(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Paper trading mode: orders aren't sent to exchange and their fills are simulated when
+    /// mid price crosses order price. Only market data streams are used
+    fn dry_run(&self) -> bool {
+        false
+    }
+
+    /// Interval of calling `DispositionStrategy::on_timer`. Timer isn't started if not set
+    fn timer_interval(&self) -> Option<Duration> {
+        None
+    }
 }
 
 /// Application settings
@@ -20,10 +60,192 @@ pub struct AppSettings<StrategySettings: Clone> {
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct CoreSettings {
+    /// Start graceful shutdown if DispositionExecutor loop didn't iterate (handle an event or a timer tick)
+    /// for specified count of seconds. Watchdog is disabled if not set
+    #[serde(default)]
+    pub stale_decision_timeout_secs: Option<u64>,
+    /// Order book snapshots used by disposition executor are ignored if they weren't updated during
+    /// specified count of seconds. Snapshots never become outdated if not set
+    #[serde(default)]
+    pub order_book_snapshot_ttl_secs: Option<u64>,
+    /// Not approved balance reservations are unreserved automatically after specified count of
+    /// seconds. Reservations never expire if not set
+    #[serde(default)]
+    pub balance_reservation_expiry_secs: Option<u64>,
+    /// Fee model for realized PnL in statistics. It is used for fills without reported commission
+    #[serde(default)]
+    pub fee_model: Option<FeeModelSettings>,
+    /// File for saving balance reservations periodically and on graceful shutdown and restoring them on start
+    #[serde(default)]
+    pub balance_reservations_snapshot_path: Option<PathBuf>,
+    /// OTLP collector endpoint for exporting tracing spans. Used only if feature `opentelemetry` is enabled
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Fraction of random jitter added to intervals of periodic tasks (events recording, postponed
+    /// events restoring, fills polling) to avoid simultaneous load from many bot instances. No jitter by default
+    #[serde(default)]
+    pub intervals_jitter_fraction: Decimal,
+    /// Local audit log of all order actions which is written even if database is unavailable.
+    /// Disabled if not set
+    #[serde(default)]
+    pub order_audit_log: Option<OrderAuditLogSettings>,
+    /// Orders with price deviating from mid price of order book by more than specified percent are
+    /// rejected instead of being sent to exchange. Orders are rejected too while there is no order book.
+    /// Price isn't checked if not set
+    #[serde(default)]
+    pub price_band_percent: Option<Decimal>,
+    /// Missing order book snapshots are requested from exchanges and saved to database on start
+    /// before trading. Backfill is disabled if not set
+    #[serde(default)]
+    pub order_book_backfill: Option<OrderBookBackfillSettings>,
+    /// Net positions of derivative markets are closed by reduce-only market orders on graceful
+    /// shutdown after open orders cancelling. Positions are left open by default
+    #[serde(default)]
+    pub flatten_on_shutdown: bool,
     pub database: Option<DbSettings>,
     pub exchanges: Vec<ExchangeSettings>,
 }
 
+impl CoreSettings {
+    pub fn jitter_fraction(&self) -> f64 {
+        self.intervals_jitter_fraction.to_f64().unwrap_or_default()
+    }
+
+    /// Semantic checks of settings which can't be expressed by deserialization
+    pub fn validate(&self) -> Result<()> {
+        if self.stale_decision_timeout_secs == Some(0) {
+            bail!("'core.stale_decision_timeout_secs' should be positive");
+        }
+
+        if let Some(fee_model) = &self.fee_model {
+            fee_model.validate()?;
+        }
+
+        if self.intervals_jitter_fraction < dec!(0) || self.intervals_jitter_fraction > dec!(1) {
+            bail!(
+                "'core.intervals_jitter_fraction' should be in range [0, 1] but it is {}",
+                self.intervals_jitter_fraction
+            );
+        }
+
+        if let Some(price_band_percent) = self.price_band_percent {
+            if price_band_percent <= dec!(0) {
+                bail!(
+                    "'core.price_band_percent' should be positive but it is {price_band_percent}"
+                );
+            }
+        }
+
+        if let Some(order_book_backfill) = &self.order_book_backfill {
+            order_book_backfill.validate()?;
+        }
+
+        for exchange in &self.exchanges {
+            if let Some(retries) = &exchange.rest_transient_error_retries {
+                if retries.max_attempts == 0 {
+                    bail!(
+                        "'rest_transient_error_retries.max_attempts' for {} should be positive",
+                        exchange.exchange_account_id
+                    );
+                }
+            }
+
+            if exchange.received_fills_cache_capacity == Some(0) {
+                bail!(
+                    "'received_fills_cache_capacity' for {} should be positive",
+                    exchange.exchange_account_id
+                );
+            }
+
+            for market in &exchange.max_positions {
+                if market.max_position <= dec!(0) {
+                    bail!(
+                        "'max_position' for {} {} should be positive but it is {}",
+                        exchange.exchange_account_id,
+                        market.currency_pair,
+                        market.max_position
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FeeModelSettings {
+    pub maker_bps: Decimal,
+    pub taker_bps: Decimal,
+    /// Discount in percents applied to both maker and taker fees, e.g. 25 when fees are paid in BNB on Binance
+    #[serde(default)]
+    pub discount_percent: Decimal,
+}
+
+impl FeeModelSettings {
+    /// Fee rate for fill with specified role, e.g. 0.001 for 10 bps
+    pub fn fee_rate(&self, role: OrderFillRole) -> Decimal {
+        let bps = match role {
+            OrderFillRole::Maker => self.maker_bps,
+            OrderFillRole::Taker => self.taker_bps,
+        };
+
+        bps / dec!(10_000) * (Decimal::ONE - self.discount_percent / dec!(100))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.discount_percent < dec!(0) || self.discount_percent > dec!(100) {
+            bail!(
+                "'core.fee_model.discount_percent' should be in range [0, 100] but it is {}",
+                self.discount_percent
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OrderBookBackfillSettings {
+    /// Order book history is checked for specified count of seconds before start
+    pub lookback_secs: u64,
+    /// Expected interval between recorded snapshots of one market. Snapshot is requested only if
+    /// the latest recorded one is older than the interval and it fills the gap with the interval
+    pub interval_secs: u64,
+    /// Max count of price levels on each side of requested snapshot. 100 if not set
+    #[serde(default)]
+    pub depth: Option<u32>,
+}
+
+impl OrderBookBackfillSettings {
+    fn validate(&self) -> Result<()> {
+        if self.interval_secs == 0 {
+            bail!("'core.order_book_backfill.interval_secs' should be positive");
+        }
+
+        if self.lookback_secs < self.interval_secs {
+            bail!(
+                "'core.order_book_backfill.lookback_secs' should be not less than 'interval_secs' but it is {}",
+                self.lookback_secs
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OrderAuditLogSettings {
+    /// Directory for audit log files. Records are saved in JSON Lines format
+    pub dir: PathBuf,
+    /// File is rotated when its size exceeds the limit. 100 MB if not set
+    #[serde(default)]
+    pub max_file_size_bytes: Option<u64>,
+    /// File is rotated after specified count of seconds since its creation. One day if not set
+    #[serde(default)]
+    pub rotation_period_secs: Option<u64>,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct DbSettings {
     pub url: String,
@@ -31,11 +253,43 @@ pub struct DbSettings {
     /// Path to directory for creating temporary directory for save events that was not saved to
     /// database by any reason and will be resaved to db late
     pub postponed_events_dir: Option<PathBuf>,
+    /// Maximum count of connections in pool. 5 if not set
+    #[serde(default)]
+    pub max_pool_size: Option<u32>,
+    /// Timeout for getting connection from pool. 5 seconds if not set
+    #[serde(default)]
+    pub connection_timeout_secs: Option<u64>,
+    /// Minimum count of idle connections in pool. 1 if not set
+    #[serde(default)]
+    pub min_idle: Option<u32>,
+    /// Statements running longer than timeout are aborted by database. No timeout if not set
+    #[serde(default)]
+    pub statement_timeout_secs: Option<u64>,
+}
+
+impl DbSettings {
+    pub fn pool_settings(&self) -> PgPoolSettings {
+        let default = PgPoolSettings::new(self.max_pool_size.unwrap_or(DEFAULT_MAX_POOL_SIZE));
+        PgPoolSettings {
+            max_size: default.max_size,
+            connection_timeout: self
+                .connection_timeout_secs
+                .map_or(default.connection_timeout, Duration::from_secs),
+            min_idle: self.min_idle.or(default.min_idle),
+            statement_timeout: self.statement_timeout_secs.map(Duration::from_secs),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum CurrencyPairSetting {
+    // Should be declared before Ordinary because untagged variants are matched in declaration order
+    Derivative {
+        base: CurrencyCode,
+        quote: CurrencyCode,
+        expiry: NaiveDate,
+    },
     Ordinary {
         base: CurrencyCode,
         quote: CurrencyCode,
@@ -52,12 +306,95 @@ pub struct ExchangeSettings {
     pub exchange_account_id: ExchangeAccountId,
     pub api_key: String,
     pub secret_key: String,
+    /// Passphrase specified on API key creation (required by OKX only)
+    #[serde(default)]
+    pub passphrase: Option<String>,
     pub is_margin_trading: bool,
     pub request_trades: bool,
     pub is_reducing_market_data: Option<bool>,
     pub subscribe_to_market_data: bool,
+    /// Log full bodies of REST requests and responses at trace level. Secrets are redacted
+    #[serde(default)]
+    pub log_rest_bodies: bool,
+    /// Count of retries for REST requests rejected with `429 Too Many Requests`.
+    /// Requests are retried after the delay from `Retry-After` header
+    #[serde(default)]
+    pub rest_rate_limit_retries: u32,
+    /// Retry policy for idempotent (GET) REST requests failed with 5xx status or network error.
+    /// Order placement is never retried automatically. Requests aren't retried if not set
+    #[serde(default)]
+    pub rest_transient_error_retries: Option<RestRetrySettings>,
+    /// Count of recently received fills remembered to ignore the same fill received again from
+    /// another source, e.g. from both websocket and REST fallback during reconnect. 1000 if not set
+    #[serde(default)]
+    pub received_fills_cache_capacity: Option<usize>,
+    /// Reset websocket connection if no frames were received for specified count of seconds
+    #[serde(default)]
+    pub websocket_stale_timeout_secs: Option<u64>,
+    /// Sources of order events handled for the exchange account, e.g. fills can be taken from
+    /// REST fallback only if websocket fills are laggy. Defaults of exchange client are used if not set
+    #[serde(default)]
+    pub allowed_event_sources: AllowedEventSources,
+    /// Discover traded symbols via exchange market scanner on initialization (supported by Interactive Brokers only)
+    #[serde(default)]
+    pub auto_discover: bool,
+    /// Interval of user data stream keepalive requests in seconds (supported by Binance only).
+    /// 20 minutes if not set
+    #[serde(default)]
+    pub listen_key_keepalive_interval_secs: Option<u64>,
+    /// Interval of exchange server time requests in seconds. Measured offset of local clock is
+    /// applied to timestamps of signed requests (supported by Binance only). 10 minutes if not set
+    #[serde(default)]
+    pub server_time_sync_interval_secs: Option<u64>,
+    /// Warning is logged if local clock differs from exchange server time by more than specified
+    /// count of milliseconds. 1000 ms if not set
+    #[serde(default)]
+    pub clock_skew_warning_threshold_ms: Option<u64>,
     pub websocket_channels: Vec<String>,
     pub currency_pairs: Option<Vec<CurrencyPairSetting>>,
+    /// Scheduled maintenance of the exchange. Trading is paused during these windows
+    #[serde(default)]
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    /// Currency pairs which orders can be created for. All pairs are allowed if not specified
+    #[serde(default)]
+    pub allowed_currency_pairs: Option<Vec<CurrencyPair>>,
+    /// Currency pairs which orders never can be created for, even if they are allowed by `allowed_currency_pairs`
+    #[serde(default)]
+    pub denied_currency_pairs: Vec<CurrencyPair>,
+    /// Caps of absolute net position per currency pair. Position isn't limited for pairs not listed here
+    #[serde(default)]
+    pub max_positions: Vec<MarketMaxPosition>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AllowedEventSources {
+    #[serde(default)]
+    pub create: Option<AllowedEventSourceType>,
+    #[serde(default)]
+    pub fill: Option<AllowedEventSourceType>,
+    #[serde(default)]
+    pub cancel: Option<AllowedEventSourceType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RestRetrySettings {
+    /// Max count of attempts including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry. It is doubled on each next retry
+    pub base_delay_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MaintenanceWindow {
+    pub start: DateTime,
+    pub end: DateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MarketMaxPosition {
+    pub currency_pair: CurrencyPair,
+    /// Max absolute net position in amount currency. Orders which would increase position beyond it are rejected
+    pub max_position: Amount,
 }
 
 impl ExchangeSettings {
@@ -72,12 +409,27 @@ impl ExchangeSettings {
             exchange_account_id,
             api_key,
             secret_key,
+            passphrase: None,
             is_margin_trading,
             request_trades: false,
             websocket_channels: vec![],
             currency_pairs: None,
             subscribe_to_market_data: true,
             is_reducing_market_data: None,
+            log_rest_bodies: false,
+            rest_rate_limit_retries: 0,
+            rest_transient_error_retries: None,
+            received_fills_cache_capacity: None,
+            websocket_stale_timeout_secs: None,
+            allowed_event_sources: AllowedEventSources::default(),
+            auto_discover: false,
+            listen_key_keepalive_interval_secs: None,
+            server_time_sync_interval_secs: None,
+            clock_skew_warning_threshold_ms: None,
+            maintenance_windows: vec![],
+            allowed_currency_pairs: None,
+            denied_currency_pairs: vec![],
+            max_positions: vec![],
         }
     }
 }
@@ -88,12 +440,27 @@ impl Default for ExchangeSettings {
             exchange_account_id: ExchangeAccountId::new("", 0),
             api_key: "".to_string(),
             secret_key: "".to_string(),
+            passphrase: None,
             is_margin_trading: false,
             request_trades: false,
             websocket_channels: vec![],
             currency_pairs: None,
             subscribe_to_market_data: true,
             is_reducing_market_data: None,
+            log_rest_bodies: false,
+            rest_rate_limit_retries: 0,
+            rest_transient_error_retries: None,
+            received_fills_cache_capacity: None,
+            websocket_stale_timeout_secs: None,
+            allowed_event_sources: AllowedEventSources::default(),
+            auto_discover: false,
+            listen_key_keepalive_interval_secs: None,
+            server_time_sync_interval_secs: None,
+            clock_skew_warning_threshold_ms: None,
+            maintenance_windows: vec![],
+            allowed_currency_pairs: None,
+            denied_currency_pairs: vec![],
+            max_positions: vec![],
         }
     }
 }
@@ -138,3 +505,148 @@ pub struct StopperCondition {
 pub struct ProfitLossStopperSettings {
     pub conditions: Vec<StopperCondition>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+    struct TestSettings {
+        currency_pair: CurrencyPairSetting,
+    }
+
+    fn round_trip(currency_pair: CurrencyPairSetting) {
+        let settings = TestSettings { currency_pair };
+
+        let serialized = toml_edit::ser::to_string(&settings).expect("in test");
+        let deserialized: TestSettings = toml_edit::de::from_str(&serialized).expect("in test");
+
+        assert_eq!(deserialized, settings);
+    }
+
+    #[test]
+    fn ordinary_currency_pair_round_trip() {
+        round_trip(CurrencyPairSetting::Ordinary {
+            base: "btc".into(),
+            quote: "usdt".into(),
+        });
+    }
+
+    #[test]
+    fn derivative_currency_pair_round_trip() {
+        round_trip(CurrencyPairSetting::Derivative {
+            base: "btc".into(),
+            quote: "usdt".into(),
+            expiry: NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test"),
+        });
+    }
+
+    #[test]
+    fn deserialize_derivative_currency_pair() {
+        let input = r#"currency_pair = { base = "btc", quote = "usdt", expiry = "2022-12-30" }"#;
+
+        let deserialized: TestSettings = toml_edit::de::from_str(input).expect("in test");
+
+        assert_eq!(
+            deserialized.currency_pair,
+            CurrencyPairSetting::Derivative {
+                base: "btc".into(),
+                quote: "usdt".into(),
+                expiry: NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test"),
+            }
+        );
+    }
+
+    #[test]
+    fn fee_model_rates() {
+        let input = r#"
+            maker_bps = 2
+            taker_bps = "4.5"
+            discount_percent = 25
+        "#;
+
+        let fee_model: FeeModelSettings = toml_edit::de::from_str(input).expect("in test");
+
+        assert_eq!(fee_model.fee_rate(OrderFillRole::Maker), dec!(0.00015));
+        assert_eq!(fee_model.fee_rate(OrderFillRole::Taker), dec!(0.0003375));
+    }
+
+    #[test]
+    fn allowed_event_sources() {
+        let input = r#"fill = "FallbackOnly""#;
+
+        let sources: AllowedEventSources = toml_edit::de::from_str(input).expect("in test");
+
+        assert_eq!(
+            sources,
+            AllowedEventSources {
+                create: None,
+                fill: Some(AllowedEventSourceType::FallbackOnly),
+                cancel: None,
+            }
+        );
+    }
+
+    #[test]
+    fn validate_intervals_jitter_fraction() {
+        let mut settings = CoreSettings {
+            intervals_jitter_fraction: dec!(0.1),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.intervals_jitter_fraction = dec!(1.5);
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn validate_price_band_percent() {
+        let mut settings = CoreSettings {
+            price_band_percent: Some(dec!(5)),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.price_band_percent = Some(dec!(0));
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn validate_max_position() {
+        let mut exchange = ExchangeSettings::default();
+        exchange.max_positions.push(MarketMaxPosition {
+            currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            max_position: dec!(1),
+        });
+        let mut settings = CoreSettings {
+            exchanges: vec![exchange],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.exchanges[0].max_positions[0].max_position = dec!(0);
+        assert!(settings.validate().is_err());
+    }
+
+    #[rstest]
+    #[case::valid(3_600, 60, true)]
+    #[case::zero_interval(3_600, 0, false)]
+    #[case::lookback_less_than_interval(30, 60, false)]
+    fn validate_order_book_backfill(
+        #[case] lookback_secs: u64,
+        #[case] interval_secs: u64,
+        #[case] is_valid: bool,
+    ) {
+        let settings = CoreSettings {
+            order_book_backfill: Some(OrderBookBackfillSettings {
+                lookback_secs,
+                interval_secs,
+                depth: None,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -6,14 +6,19 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use mmb_domain::events::ExchangeEvent;
-use mmb_domain::market::MarketAccountId;
+use mmb_domain::market::{MarketAccountId, MarketId};
+use mmb_domain::order::fill::OrderFill;
 use mmb_domain::order::snapshot::ClientOrderId;
-use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_domain::position::PositionEntry;
 use parking_lot::{Mutex, RwLock};
-use serde::{Deserialize, Serialize};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
 use tokio::sync::broadcast;
 
 use super::infrastructure::spawn_future;
+use crate::explanation::ExplanationSet;
+use crate::settings::FeeModelSettings;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct MarketAccountIdStatistic {
@@ -25,6 +30,11 @@ pub struct MarketAccountIdStatistic {
     summary_filled_amount: Amount,
     // Calculated only for completely filled orders
     summary_commission: Amount,
+    // Realized by fills in quote currency with fees subtracted
+    realized_pnl: Amount,
+    // Open position and its weighted average entry price
+    #[serde(default, flatten)]
+    position_entry: PositionEntry,
 }
 
 impl MarketAccountIdStatistic {
@@ -59,6 +69,91 @@ impl MarketAccountIdStatistic {
     fn add_summary_commission(&mut self, commission: Price) {
         self.summary_commission += commission;
     }
+
+    /// Returns realized PnL of the fill
+    fn add_fill_to_realized_pnl(
+        &mut self,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        fee_in_quote: Amount,
+    ) -> Amount {
+        let realized_pnl_before = self.realized_pnl;
+        self.realized_pnl -= fee_in_quote;
+
+        if amount.is_zero() {
+            return self.realized_pnl - realized_pnl_before;
+        }
+
+        let signed_amount = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => -amount,
+        };
+
+        let PositionEntry {
+            position,
+            average_entry_price,
+        } = self.position_entry;
+        if !position.is_zero() && position.is_sign_positive() != signed_amount.is_sign_positive() {
+            let closed_amount = amount.min(position.abs());
+            let pnl_per_unit = match position.is_sign_positive() {
+                true => price - average_entry_price,
+                false => average_entry_price - price,
+            };
+            self.realized_pnl += closed_amount * pnl_per_unit;
+        }
+
+        self.position_entry.apply_fill(signed_amount, price);
+
+        self.realized_pnl - realized_pnl_before
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PnlStatistic {
+    // Realized by fills in quote currency with fees subtracted
+    realized_pnl: Amount,
+    fills_count: u64,
+    // Traded volume in quote currency
+    volume: Amount,
+}
+
+impl PnlStatistic {
+    fn add_fill(&mut self, realized_pnl: Amount, volume: Amount) {
+        self.realized_pnl += realized_pnl;
+        self.fills_count += 1;
+        self.volume += volume;
+    }
+
+    fn add(&mut self, other: &PnlStatistic) {
+        self.realized_pnl += other.realized_pnl;
+        self.fills_count += other.fills_count;
+        self.volume += other.volume;
+    }
+}
+
+/// Serialized as PnL of each market keyed by `MarketId` and total PnL over all markets
+fn serialize_pnl_by_market<S>(
+    pnl_by_market: &RwLock<HashMap<MarketId, PnlStatistic>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let pnl_by_market = pnl_by_market.read();
+
+    let mut total = PnlStatistic::default();
+    pnl_by_market.values().for_each(|pnl| total.add(pnl));
+
+    let by_market: HashMap<String, &PnlStatistic> = pnl_by_market
+        .iter()
+        .map(|(market_id, pnl)| (market_id.to_string(), pnl))
+        .collect();
+
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_entry("total", &total)?;
+    map.serialize_entry("by_market", &by_market)?;
+    map.end()
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -70,6 +165,16 @@ pub struct DispositionExecutorStatistic {
 pub(crate) struct StatisticServiceState {
     market_account_id_stats: RwLock<HashMap<MarketAccountId, MarketAccountIdStatistic>>,
     disposition_executor_stats: Mutex<DispositionExecutorStatistic>,
+    // Last explanations of disposition executor why orders were (or weren't) placed
+    #[serde(default)]
+    market_account_id_explanations: RwLock<HashMap<MarketAccountId, serde_json::Value>>,
+    // PnL attribution by market, fills of all exchange accounts of the market are accumulated together
+    #[serde(
+        default,
+        skip_deserializing,
+        serialize_with = "serialize_pnl_by_market"
+    )]
+    pnl_by_market: RwLock<HashMap<MarketId, PnlStatistic>>,
 }
 
 impl StatisticServiceState {
@@ -137,20 +242,57 @@ impl StatisticServiceState {
             .add_summary_commission(commission);
     }
 
+    fn register_fill(
+        &self,
+        market_account_id: MarketAccountId,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        fee_in_quote: Amount,
+    ) {
+        let realized_pnl = self
+            .market_account_id_stats
+            .write()
+            .entry(market_account_id)
+            .or_default()
+            .add_fill_to_realized_pnl(side, price, amount, fee_in_quote);
+
+        self.pnl_by_market
+            .write()
+            .entry(market_account_id.market_id())
+            .or_default()
+            .add_fill(realized_pnl, price * amount);
+    }
+
     pub(crate) fn register_skipped_event(&self) {
         self.disposition_executor_stats.lock().skipped_events_amount += 1;
     }
+
+    fn register_explanations(
+        &self,
+        market_account_id: MarketAccountId,
+        explanations: serde_json::Value,
+    ) {
+        let _ = self
+            .market_account_id_explanations
+            .write()
+            .insert(market_account_id, explanations);
+    }
 }
 
 #[derive(Default, Debug)]
 pub struct StatisticService {
     pub(crate) statistic_service_state: StatisticServiceState,
     partially_filled_orders: Mutex<HashSet<ClientOrderId>>,
+    fee_model: Option<FeeModelSettings>,
 }
 
 impl StatisticService {
-    pub fn new() -> Arc<Self> {
-        Default::default()
+    pub fn new(fee_model: Option<FeeModelSettings>) -> Arc<Self> {
+        Arc::new(Self {
+            fee_model,
+            ..Default::default()
+        })
     }
 
     pub(crate) fn register_created_order(&self, market_account_id: MarketAccountId) {
@@ -216,9 +358,78 @@ impl StatisticService {
         }
     }
 
+    pub(crate) fn register_fill(
+        &self,
+        market_account_id: MarketAccountId,
+        side: OrderSide,
+        fill: &OrderFill,
+    ) {
+        let fee_in_quote = self.fee_in_quote(market_account_id, fill);
+        self.statistic_service_state.register_fill(
+            market_account_id,
+            side,
+            fill.price(),
+            fill.amount(),
+            fee_in_quote,
+        );
+    }
+
+    /// Fee of the fill in quote currency. Commission reported by exchange is preferred,
+    /// configured fee model is used if commission is zero or can't be converted to quote currency
+    fn fee_in_quote(&self, market_account_id: MarketAccountId, fill: &OrderFill) -> Amount {
+        let reported_fee = Self::reported_fee_in_quote(market_account_id, fill);
+
+        match (reported_fee, &self.fee_model) {
+            (Some(fee), _) if !fee.is_zero() => fee,
+            (_, Some(fee_model)) => fill.price() * fill.amount() * fee_model.fee_rate(fill.role()),
+            (Some(fee), None) => fee,
+            (None, None) => {
+                log::warn!(
+                    "Unable to convert commission {} {} of fill on {market_account_id} to quote currency",
+                    fill.converted_commission_amount(),
+                    fill.converted_commission_currency_code(),
+                );
+                Amount::ZERO
+            }
+        }
+    }
+
+    fn reported_fee_in_quote(
+        market_account_id: MarketAccountId,
+        fill: &OrderFill,
+    ) -> Option<Amount> {
+        let (base, quote) = market_account_id.currency_pair.as_str().split_once('/')?;
+        let quote = quote.split_once(':').map_or(quote, |(quote, _)| quote);
+
+        let commission_currency_code = fill.converted_commission_currency_code();
+        let commission_amount = fill.converted_commission_amount();
+        if commission_currency_code.as_str() == quote {
+            Some(commission_amount)
+        } else if commission_currency_code.as_str() == base {
+            Some(commission_amount * fill.price())
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn register_skipped_event(&self) {
         self.statistic_service_state.register_skipped_event();
     }
+
+    pub(crate) fn register_explanations(
+        &self,
+        market_account_id: MarketAccountId,
+        explanations: &ExplanationSet,
+    ) {
+        match serde_json::to_value(explanations) {
+            Ok(explanations) => self
+                .statistic_service_state
+                .register_explanations(market_account_id, explanations),
+            Err(err) => {
+                log::error!("Unable to serialize explanations for {market_account_id}: {err}")
+            }
+        }
+    }
 }
 
 pub struct StatisticEventHandler {
@@ -279,6 +490,11 @@ impl StatisticEventHandler {
                             market_account_id,
                             &cloned_order.header.client_order_id,
                         );
+
+                        if let Some(fill) = cloned_order.fills.fills.last() {
+                            let side = fill.side().unwrap_or(cloned_order.header.side);
+                            self.stats.register_fill(market_account_id, side, fill);
+                        }
                     }
                     OrderEventType::OrderCompleted { cloned_order } => {
                         let commission = cloned_order
@@ -306,3 +522,156 @@ impl StatisticEventHandler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::fill::OrderFillType;
+    use mmb_domain::order::snapshot::OrderFillRole;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn market_account_id() -> MarketAccountId {
+        MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        )
+    }
+
+    fn order_fill(
+        role: OrderFillRole,
+        commission_currency_code: &str,
+        commission_amount: Amount,
+    ) -> OrderFill {
+        let commission_currency_code = CurrencyCode::from(commission_currency_code);
+        OrderFill::new(
+            Uuid::new_v4(),
+            None,
+            Utc::now(),
+            OrderFillType::UserTrade,
+            None,
+            dec!(100),
+            dec!(2),
+            dec!(200),
+            role,
+            commission_currency_code,
+            commission_amount,
+            dec!(0),
+            commission_currency_code,
+            commission_amount,
+            commission_amount,
+            false,
+            None,
+            None,
+        )
+    }
+
+    fn fee_model() -> FeeModelSettings {
+        FeeModelSettings {
+            maker_bps: dec!(2),
+            taker_bps: dec!(4),
+            discount_percent: dec!(25),
+        }
+    }
+
+    #[test]
+    fn realized_pnl_by_average_entry_price() {
+        let mut stats = MarketAccountIdStatistic::default();
+
+        stats.add_fill_to_realized_pnl(OrderSide::Buy, dec!(100), dec!(1), dec!(0));
+        stats.add_fill_to_realized_pnl(OrderSide::Buy, dec!(110), dec!(1), dec!(0));
+        assert_eq!(stats.position_entry.average_entry_price, dec!(105));
+        assert_eq!(stats.realized_pnl, dec!(0));
+
+        stats.add_fill_to_realized_pnl(OrderSide::Sell, dec!(120), dec!(1), dec!(0.5));
+        assert_eq!(stats.realized_pnl, dec!(14.5));
+        assert_eq!(stats.position_entry.position, dec!(1));
+
+        // flip position: 1 closed with profit and 1 opens short position
+        stats.add_fill_to_realized_pnl(OrderSide::Sell, dec!(100), dec!(2), dec!(0));
+        assert_eq!(stats.realized_pnl, dec!(9.5));
+        assert_eq!(stats.position_entry.position, dec!(-1));
+        assert_eq!(stats.position_entry.average_entry_price, dec!(100));
+
+        stats.add_fill_to_realized_pnl(OrderSide::Buy, dec!(90), dec!(1), dec!(0));
+        assert_eq!(stats.realized_pnl, dec!(19.5));
+        assert_eq!(stats.position_entry.position, dec!(0));
+        assert_eq!(stats.position_entry.average_entry_price, dec!(0));
+    }
+
+    #[test]
+    fn pnl_attributed_by_market() {
+        let state = StatisticServiceState::default();
+        let btc_usdt = market_account_id();
+        let eth_usdt = MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 1),
+            CurrencyPair::from_codes("eth".into(), "usdt".into()),
+        );
+
+        state.register_fill(btc_usdt, OrderSide::Buy, dec!(100), dec!(1), dec!(0.1));
+        state.register_fill(btc_usdt, OrderSide::Sell, dec!(110), dec!(1), dec!(0.1));
+        state.register_fill(eth_usdt, OrderSide::Sell, dec!(10), dec!(2), dec!(0.05));
+        state.register_fill(eth_usdt, OrderSide::Buy, dec!(12), dec!(2), dec!(0.05));
+
+        let stats = serde_json::to_value(&state).expect("serialize statistic");
+        let pnl = &stats["pnl_by_market"];
+
+        assert_eq!(
+            pnl["by_market"]["Binance|btc/usdt"],
+            serde_json::json!({ "realized_pnl": "9.8", "fills_count": 2, "volume": "210" })
+        );
+        assert_eq!(
+            pnl["by_market"]["Binance|eth/usdt"],
+            serde_json::json!({ "realized_pnl": "-4.10", "fills_count": 2, "volume": "44" })
+        );
+        assert_eq!(
+            pnl["total"],
+            serde_json::json!({ "realized_pnl": "5.70", "fills_count": 4, "volume": "254" })
+        );
+    }
+
+    #[test]
+    fn reported_commission_preferred_over_fee_model() {
+        let service = StatisticService::new(Some(fee_model()));
+
+        let quote_fill = order_fill(OrderFillRole::Taker, "usdt", dec!(0.2));
+        assert_eq!(
+            service.fee_in_quote(market_account_id(), &quote_fill),
+            dec!(0.2)
+        );
+
+        let base_fill = order_fill(OrderFillRole::Taker, "btc", dec!(0.001));
+        assert_eq!(
+            service.fee_in_quote(market_account_id(), &base_fill),
+            dec!(0.1)
+        );
+    }
+
+    #[test]
+    fn fee_model_used_without_reported_commission() {
+        let service = StatisticService::new(Some(fee_model()));
+
+        let maker_fill = order_fill(OrderFillRole::Maker, "usdt", dec!(0));
+        assert_eq!(
+            service.fee_in_quote(market_account_id(), &maker_fill),
+            dec!(0.03)
+        );
+
+        // commission in BNB can't be converted to quote currency
+        let taker_fill = order_fill(OrderFillRole::Taker, "bnb", dec!(0.001));
+        assert_eq!(
+            service.fee_in_quote(market_account_id(), &taker_fill),
+            dec!(0.06)
+        );
+    }
+
+    #[test]
+    fn zero_fee_without_fee_model() {
+        let service = StatisticService::new(None);
+
+        let fill = order_fill(OrderFillRole::Taker, "bnb", dec!(0.001));
+        assert_eq!(service.fee_in_quote(market_account_id(), &fill), dec!(0));
+    }
+}
@@ -0,0 +1,186 @@
+//! Optional OpenTelemetry tracing. Spans are exported to OTLP collector only if feature `opentelemetry`
+//! is enabled and `otlp_endpoint` is set in core settings, otherwise all calls are no-op.
+
+use anyhow::Result;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{ClientOrderId, OrderSide};
+
+#[cfg(feature = "opentelemetry")]
+use opentelemetry::{
+    global::{self, BoxedSpan},
+    trace::{Span, Tracer},
+    KeyValue,
+};
+
+#[cfg(feature = "opentelemetry")]
+const TRACER_NAME: &str = "mmb_core";
+
+/// Span which is ended on drop
+pub struct TelemetrySpan {
+    #[cfg(feature = "opentelemetry")]
+    inner: BoxedSpan,
+}
+
+#[cfg(feature = "opentelemetry")]
+pub fn init_tracer(otlp_endpoint: Option<&str>) -> Result<()> {
+    use anyhow::Context;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let Some(otlp_endpoint) = otlp_endpoint else {
+        return Ok(());
+    };
+
+    let _ = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(otlp_endpoint),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .with_context(|| format!("Failed to init OTLP exporter with endpoint {otlp_endpoint}"))?;
+
+    log::info!("OpenTelemetry spans are exported to {otlp_endpoint}");
+
+    Ok(())
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub fn init_tracer(otlp_endpoint: Option<&str>) -> Result<()> {
+    if otlp_endpoint.is_some() {
+        log::warn!("otlp_endpoint is set but mmb_core is built without feature `opentelemetry`");
+    }
+
+    Ok(())
+}
+
+/// Export all finished spans and stop the exporter
+pub async fn shutdown_tracer() {
+    #[cfg(feature = "opentelemetry")]
+    if let Err(err) = tokio::task::spawn_blocking(global::shutdown_tracer_provider).await {
+        log::error!("Failed to shutdown OpenTelemetry tracer provider: {err}");
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+pub fn start_span(
+    name: &'static str,
+    exchange_account_id: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+) -> TelemetrySpan {
+    let mut inner = global::tracer(TRACER_NAME).start(name);
+    inner.set_attribute(KeyValue::new(
+        "exchange_account_id",
+        exchange_account_id.to_string(),
+    ));
+    inner.set_attribute(KeyValue::new("currency_pair", currency_pair.to_string()));
+
+    TelemetrySpan { inner }
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+#[inline(always)]
+pub fn start_span(
+    _name: &'static str,
+    _exchange_account_id: ExchangeAccountId,
+    _currency_pair: CurrencyPair,
+) -> TelemetrySpan {
+    TelemetrySpan {}
+}
+
+#[cfg(feature = "opentelemetry")]
+impl TelemetrySpan {
+    pub fn with_side(mut self, side: OrderSide) -> Self {
+        self.inner
+            .set_attribute(KeyValue::new("side", side.to_string()));
+        self
+    }
+
+    pub fn with_order_id(mut self, client_order_id: &ClientOrderId) -> Self {
+        self.inner
+            .set_attribute(KeyValue::new("order_id", client_order_id.to_string()));
+        self
+    }
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+impl TelemetrySpan {
+    #[inline(always)]
+    pub fn with_side(self, _side: OrderSide) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_order_id(self, _client_order_id: &ClientOrderId) -> Self {
+        self
+    }
+}
+
+#[cfg(all(test, feature = "opentelemetry"))]
+mod tests {
+    use super::*;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use tokio::sync::mpsc;
+
+    /// Collector which accepts any OTLP request and forwards its path and body to receiver
+    fn start_mock_collector() -> (SocketAddr, mpsc::UnboundedReceiver<(String, Vec<u8>)>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let make_service = make_service_fn(move |_| {
+            let tx = tx.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let tx = tx.clone();
+                    async move {
+                        let path = request.uri().path().to_owned();
+                        let body = hyper::body::to_bytes(request.into_body())
+                            .await
+                            .expect("in test");
+                        let _ = tx.send((path, body.to_vec()));
+                        Ok::<_, Infallible>(Response::new(Body::empty()))
+                    }
+                }))
+            }
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        let _server_handle = tokio::spawn(server);
+
+        (addr, rx)
+    }
+
+    fn contains(body: &[u8], value: &str) -> bool {
+        body.windows(value.len()).any(|x| x == value.as_bytes())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn spans_exported_to_collector() {
+        let (addr, mut rx) = start_mock_collector();
+        init_tracer(Some(&format!("http://{addr}"))).expect("in test");
+
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let currency_pair = CurrencyPair::from_codes("btc".into(), "usdt".into());
+        let client_order_id = ClientOrderId::new("test_order_id".into());
+        drop(
+            start_span("place_order", exchange_account_id, currency_pair)
+                .with_side(OrderSide::Buy)
+                .with_order_id(&client_order_id),
+        );
+
+        shutdown_tracer().await;
+
+        let (path, body) = rx.recv().await.expect("in test");
+        assert_eq!(path, "/v1/traces");
+        assert!(contains(&body, "place_order"));
+        assert!(contains(&body, "exchange_account_id"));
+        assert!(contains(&body, &exchange_account_id.to_string()));
+        assert!(contains(&body, "currency_pair"));
+        assert!(contains(&body, "side"));
+        assert!(contains(&body, "order_id"));
+        assert!(contains(&body, "test_order_id"));
+    }
+}
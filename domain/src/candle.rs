@@ -0,0 +1,52 @@
+use mmb_utils::DateTime;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::order::snapshot::{Amount, Price};
+
+/// Time interval which covered by single candle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KlineInterval {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+    OneWeek,
+}
+
+impl KlineInterval {
+    pub fn duration(&self) -> Duration {
+        const MINUTE: u64 = 60;
+        const HOUR: u64 = 60 * MINUTE;
+        const DAY: u64 = 24 * HOUR;
+
+        let secs = match self {
+            KlineInterval::OneMinute => MINUTE,
+            KlineInterval::FiveMinutes => 5 * MINUTE,
+            KlineInterval::FifteenMinutes => 15 * MINUTE,
+            KlineInterval::ThirtyMinutes => 30 * MINUTE,
+            KlineInterval::OneHour => HOUR,
+            KlineInterval::FourHours => 4 * HOUR,
+            KlineInterval::OneDay => DAY,
+            KlineInterval::OneWeek => 7 * DAY,
+        };
+
+        Duration::from_secs(secs)
+    }
+}
+
+/// OHLCV data for single time interval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Candle {
+    /// Start time of candle interval
+    pub open_time: DateTime,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    /// Traded amount in base currency during candle interval
+    pub volume: Amount,
+}
@@ -1,8 +1,12 @@
 use core::panic;
 use itertools::Itertools;
+use std::any::Any;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+use anyhow::{Context, Result};
 use mmb_database::impl_event;
 use mmb_utils::DateTime;
 use rust_decimal::Decimal;
@@ -152,6 +156,15 @@ impl PartialEq for TradeId {
     }
 }
 
+impl Hash for TradeId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            TradeId::Number(number) => number.hash(state),
+            TradeId::String(string) => string.hash(state),
+        }
+    }
+}
+
 impl Display for TradeId {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -219,8 +232,43 @@ pub enum ExchangeEvent {
     BalanceUpdate(BalanceUpdateEvent),
     LiquidationPrice(LiquidationPriceEvent),
     Trades(TradesEvent),
+    CustomEvent(CustomEvent),
+    LifecycleState(LifecycleState),
+    /// Tick of strategy timer in `DispositionExecutor`. It isn't sent through events channel,
+    /// but passed to trading context calculation right after `DispositionStrategy::on_timer`
+    TimerTick,
+}
+
+/// Trading state of the engine. Orders are not placed while engine is paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleState {
+    Running,
+    /// Trading is paused because of exchange maintenance and will be resumed automatically
+    Paused,
+}
+
+/// Strategy-defined event that is broadcast through the engine events channel.
+/// Payload is shared between all receivers, so it is stored behind `Arc`
+#[derive(Clone)]
+pub struct CustomEvent(Arc<dyn Any + Send + Sync>);
+
+impl CustomEvent {
+    pub fn new(event: impl Any + Send + Sync) -> Self {
+        CustomEvent(Arc::new(event))
+    }
+
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref::<T>()
+    }
 }
 
+impl Debug for CustomEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomEvent { .. }")
+    }
+}
+
+#[derive(Clone)]
 pub struct ExchangeEvents {
     events_sender: broadcast::Sender<ExchangeEvent>,
 }
@@ -233,9 +281,23 @@ impl ExchangeEvents {
     pub fn get_events_channel(&self) -> broadcast::Receiver<ExchangeEvent> {
         self.events_sender.subscribe()
     }
+
+    pub fn broadcast_custom_event(&self, event: impl Any + Send + Sync) -> Result<()> {
+        self.events_sender
+            .send(ExchangeEvent::CustomEvent(CustomEvent::new(event)))
+            .map(|_| ())
+            .context("Failed to broadcast custom event: there are no events receivers")
+    }
+
+    pub fn broadcast_lifecycle_state(&self, state: LifecycleState) -> Result<()> {
+        self.events_sender
+            .send(ExchangeEvent::LifecycleState(state))
+            .map(|_| ())
+            .context("Failed to broadcast lifecycle state: there are no events receivers")
+    }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
 pub enum AllowedEventSourceType {
     #[default]
     All,
@@ -332,3 +394,74 @@ impl MetricsEventInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CustomEvent, ExchangeEvent, ExchangeEvents};
+    use mmb_utils::cancellation_token::CancellationToken;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::broadcast;
+
+    struct Heartbeat;
+
+    async fn broadcast_heartbeats(events: Arc<ExchangeEvents>, stop_token: CancellationToken) {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                biased;
+                _ = stop_token.when_cancelled() => return,
+                _ = interval.tick() => events.broadcast_custom_event(Heartbeat).expect("in test"),
+            }
+        }
+    }
+
+    async fn count_heartbeats(
+        mut events_rx: broadcast::Receiver<ExchangeEvent>,
+        expected_count: usize,
+    ) -> usize {
+        let mut received_count = 0;
+        while received_count < expected_count {
+            match events_rx.recv().await.expect("in test") {
+                ExchangeEvent::CustomEvent(event)
+                    if event.downcast_ref::<Heartbeat>().is_some() =>
+                {
+                    received_count += 1
+                }
+                _ => panic!("unexpected event"),
+            }
+        }
+
+        received_count
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn custom_events_received_by_consumer() {
+        let (tx, rx) = broadcast::channel(10);
+        let events = Arc::new(ExchangeEvents::new(tx));
+        let stop_token = CancellationToken::default();
+
+        let heartbeats = tokio::spawn(broadcast_heartbeats(events.clone(), stop_token.clone()));
+        let received_count = count_heartbeats(rx, 5).await;
+        stop_token.cancel();
+        heartbeats.await.expect("in test");
+
+        assert_eq!(received_count, 5);
+    }
+
+    #[test]
+    fn broadcast_custom_event_without_receivers() {
+        let (tx, _) = broadcast::channel(10);
+        let events = ExchangeEvents::new(tx);
+
+        assert!(events.broadcast_custom_event(Heartbeat).is_err());
+    }
+
+    #[test]
+    fn custom_event_downcast_to_other_type() {
+        let event = CustomEvent::new(Heartbeat);
+
+        assert!(event.downcast_ref::<Heartbeat>().is_some());
+        assert!(event.downcast_ref::<u32>().is_none());
+    }
+}
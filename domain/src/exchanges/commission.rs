@@ -36,3 +36,23 @@ impl Commission {
         }
     }
 }
+
+/// Trading fee rates of market on exchange, e.g. 0.001 for 0.1%
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct TradingFees {
+    pub maker: Decimal,
+    pub taker: Decimal,
+}
+
+impl TradingFees {
+    pub fn new(maker: Decimal, taker: Decimal) -> Self {
+        Self { maker, taker }
+    }
+
+    pub fn fee_rate(&self, order_role: OrderRole) -> Decimal {
+        match order_role {
+            OrderRole::Maker => self.maker,
+            OrderRole::Taker => self.taker,
+        }
+    }
+}
@@ -4,10 +4,11 @@ use crate::market::{powi, CurrencyCode, CurrencyId, CurrencyPair};
 use crate::order::snapshot::OrderSide;
 use crate::order::snapshot::{Amount, Price};
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use rust_decimal::MathematicalOps;
 use rust_decimal_macros::dec;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 pub enum Round {
     Floor,
@@ -27,7 +28,7 @@ pub enum BeforeAfter {
 /// ```ignore
 /// Precision::ByTick { tick: dec!(0.001) } // for AmountPrecision = 3 equal pow(0.1, 3)
 /// ```
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Precision {
     /// Rounding is performed to a number divisible to the specified tick
     /// Look at round_by_tick test below
@@ -54,7 +55,7 @@ impl Precision {
 }
 
 /// Metadata for a currency pair
-#[derive(Debug, Clone, Eq, Serialize)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct Symbol {
     pub is_derivative: bool,
     pub base_currency_id: CurrencyId,
@@ -73,6 +74,9 @@ pub struct Symbol {
     pub amount_currency_code: CurrencyCode,
     pub balance_currency_code: Option<CurrencyCode>,
     pub amount_multiplier: Decimal,
+    /// Expiry date of futures contract. `None` for spot and perpetual instruments
+    #[serde(default)]
+    pub expiry: Option<NaiveDate>,
 
     pub price_precision: Precision,
     pub amount_precision: Precision,
@@ -118,14 +122,27 @@ impl Symbol {
             min_cost,
             balance_currency_code,
             amount_multiplier: dec!(1),
+            expiry: None,
             price_precision,
             amount_precision,
         }
     }
 
+    pub fn with_expiry(mut self, expiry: Option<NaiveDate>) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
     // Currency pair in unified for crate format
     pub fn currency_pair(&self) -> CurrencyPair {
-        CurrencyPair::from_codes(self.base_currency_code, self.quote_currency_code)
+        match self.expiry {
+            Some(expiry) => CurrencyPair::from_derivative(
+                self.base_currency_code,
+                self.quote_currency_code,
+                expiry,
+            ),
+            None => CurrencyPair::from_codes(self.base_currency_code, self.quote_currency_code),
+        }
     }
 
     pub const fn get_trade_code(&self, side: OrderSide, before_after: BeforeAfter) -> CurrencyCode {
@@ -151,6 +168,22 @@ impl Symbol {
         }
     }
 
+    /// Price step near the specified price.
+    /// For `ByMantissa` precision the step depends on the order of magnitude of the price
+    pub fn price_tick(&self, price: Price) -> Price {
+        match self.price_precision {
+            Precision::ByTick { tick } => tick,
+            Precision::ByMantissa { precision } => {
+                if price.is_zero() {
+                    return self.price_precision.get_tick();
+                }
+
+                let digits = Self::get_precision_digits_by_fractional(price, precision);
+                powi(dec!(0.1), digits)
+            }
+        }
+    }
+
     pub fn amount_round(&self, amount: Amount, round: Round) -> Amount {
         match self.amount_precision {
             Precision::ByTick { tick } => Self::round_by_tick(amount, tick, round),
@@ -1,3 +1,4 @@
+pub mod candle;
 pub mod events;
 pub mod exchanges;
 pub mod market;
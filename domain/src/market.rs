@@ -1,6 +1,9 @@
 use anyhow::Result;
+use chrono::NaiveDate;
+use dashmap::DashSet;
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::{impl_table_type, impl_table_type_raw};
+use once_cell::sync::Lazy;
 use rust_decimal::{Decimal, MathematicalOps};
 use serde::de::{self, Deserializer, Visitor};
 use serde::ser::Serializer;
@@ -16,6 +19,19 @@ impl_table_type_raw!(CurrencyCode, 16, u16);
 // Unified format currency pair for this mmb
 impl_table_type_raw!(CurrencyPair, 16, u16);
 
+// Expiry date format in derivative currency pair
+const EXPIRY_FORMAT: &str = "%y%m%d";
+
+const DEFAULT_STABLE_CURRENCY_CODES: [&str; 6] = ["usdt", "usdc", "busd", "dai", "usd", "eur"];
+const FIAT_CURRENCY_CODES: [&str; 4] = ["usd", "eur", "gbp", "jpy"];
+
+static STABLE_CURRENCY_CODES: Lazy<DashSet<CurrencyCode>> = Lazy::new(|| {
+    DEFAULT_STABLE_CURRENCY_CODES
+        .into_iter()
+        .map(CurrencyCode::new)
+        .collect()
+});
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExchangeIdParseError(String);
 
@@ -189,10 +205,16 @@ pub enum ExchangeErrorType {
     OrderCompleted,
     InsufficientFunds,
     InvalidOrder,
+    /// Post-only order was rejected because it would immediately take liquidity
+    WouldTake,
     Authentication,
     ParsingError,
     PendingError(Duration),
     ServiceUnavailable,
+    /// Exchange is under maintenance and rejects all requests
+    Maintenance,
+    /// Reduce-only order was rejected because there is no position it could reduce
+    ReduceOnlyRejected,
 }
 
 #[cfg(test)]
@@ -300,6 +322,34 @@ mod tests {
         }
     }
 
+    mod derivative_currency_pair {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        pub fn from_derivative() {
+            let expiry = NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test");
+            let currency_pair = CurrencyPair::from_derivative("btc".into(), "usdt".into(), expiry);
+
+            assert_eq!(currency_pair.as_str(), "btc/usdt:221230");
+            assert_eq!(currency_pair.expiry(), Some(expiry));
+            assert_eq!(
+                currency_pair.to_codes(),
+                CurrencyPairCodes {
+                    base: "btc".into(),
+                    quote: "usdt".into()
+                }
+            );
+            assert_eq!("btc/usdt:221230".parse(), Ok(currency_pair));
+        }
+
+        #[test]
+        pub fn ordinary_has_no_expiry() {
+            let currency_pair = CurrencyPair::from_codes("btc".into(), "usdt".into());
+            assert_eq!(currency_pair.expiry(), None);
+        }
+    }
+
     mod to_string_exchange_account_id {
         use super::*;
         use pretty_assertions::assert_eq;
@@ -311,6 +361,47 @@ mod tests {
             assert_eq!(result, "Binance_1".to_string())
         }
     }
+
+    mod stable_currency_code {
+        use super::*;
+        use rstest::rstest;
+
+        #[rstest]
+        #[case::usdt("usdt", true)]
+        #[case::usdc("usdc", true)]
+        #[case::busd("BUSD", true)]
+        #[case::dai("dai", true)]
+        #[case::usd("usd", true)]
+        #[case::eur("eur", true)]
+        #[case::btc("btc", false)]
+        #[case::gbp("gbp", false)]
+        pub fn is_stable(#[case] code: &str, #[case] expected: bool) {
+            assert_eq!(CurrencyCode::new(code).is_stable(), expected);
+        }
+
+        #[rstest]
+        #[case::usd("usd", true)]
+        #[case::eur("eur", true)]
+        #[case::gbp("GBP", true)]
+        #[case::jpy("jpy", true)]
+        #[case::usdt("usdt", false)]
+        #[case::btc("btc", false)]
+        pub fn is_fiat(#[case] code: &str, #[case] expected: bool) {
+            assert_eq!(CurrencyCode::new(code).is_fiat(), expected);
+        }
+
+        #[test]
+        pub fn register_stable() {
+            // unique code, so other tests don't depend on the global set extension
+            let code = CurrencyCode::new("test_stable_coin");
+            assert!(!code.is_stable());
+
+            CurrencyCode::register_stable(code);
+
+            assert!(code.is_stable());
+            assert!(!code.is_fiat());
+        }
+    }
 }
 
 impl CurrencyCode {
@@ -318,6 +409,21 @@ impl CurrencyCode {
         let currency_code = currency_code.to_lowercase();
         Self(SHARED_CURRENCY_CODE.add_or_get(&currency_code))
     }
+
+    /// Stablecoins and fiat currencies with stable price. Such currency is used as quote currency
+    /// for P&L calculations
+    pub fn is_stable(&self) -> bool {
+        STABLE_CURRENCY_CODES.contains(self)
+    }
+
+    pub fn is_fiat(&self) -> bool {
+        FIAT_CURRENCY_CODES.contains(&self.as_str())
+    }
+
+    /// Adds currency to the set of stable currencies for all subsequent `is_stable` calls
+    pub fn register_stable(code: CurrencyCode) {
+        let _ = STABLE_CURRENCY_CODES.insert(code);
+    }
 }
 
 impl From<&str> for CurrencyCode {
@@ -344,11 +450,25 @@ impl CurrencyPair {
         Self(SHARED_CURRENCY_PAIR.add_or_get(&[base.as_str(), quote.as_str()].join("/")))
     }
 
+    /// Currency pair of derivative instrument with expiry date, e.g. `btc/usdt:221230`
+    pub fn from_derivative(base: CurrencyCode, quote: CurrencyCode, expiry: NaiveDate) -> Self {
+        let currency_pair = format!("{base}/{quote}:{}", expiry.format(EXPIRY_FORMAT));
+        Self(SHARED_CURRENCY_PAIR.add_or_get(&currency_pair))
+    }
+
+    /// Expiry date of derivative instrument
+    pub fn expiry(&self) -> Option<NaiveDate> {
+        let (_, expiry) = self.as_str().split_once(':')?;
+        NaiveDate::parse_from_str(expiry, EXPIRY_FORMAT).ok()
+    }
+
     pub fn to_codes(&self) -> CurrencyPairCodes {
         let (base, quote) = self
             .as_str()
             .split_once('/')
             .with_expect(|| format!("Failed to get base and quote value from CurrencyPair {self}"));
+        // skip expiry of derivative instrument
+        let quote = quote.split_once(':').map_or(quote, |(quote, _)| quote);
 
         CurrencyPairCodes {
             base: base.into(),
@@ -357,6 +477,19 @@ impl CurrencyPair {
     }
 }
 
+impl From<&str> for CurrencyPair {
+    fn from(value: &str) -> Self {
+        if value.contains(':') {
+            return CurrencyPair::from_raw(value);
+        }
+
+        match value.split_once('/') {
+            Some((base, quote)) => CurrencyPair::from_codes(base.into(), quote.into()),
+            None => CurrencyPair::from_raw(value),
+        }
+    }
+}
+
 pub fn powi(value: Decimal, degree: i8) -> Decimal {
     value.powi(degree as i64)
 }
@@ -6,16 +6,20 @@ use crate::order::snapshot::{
     OrderSimpleProps, OrderSnapshot, OrderStatus, Price,
 };
 use crate::order::snapshot::{OrderRole, OrderSide, OrderType};
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use mmb_utils::DateTime;
 use parking_lot::RwLock;
-use std::borrow::{Borrow, BorrowMut};
+use std::borrow::Borrow;
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+
+/// Client order ids of orders in pool grouped by order status
+type StatusIndex = DashMap<OrderStatus, DashSet<ClientOrderId>>;
 
 pub struct OrderRefData {
     header: OrderHeader,
     data: RwLock<OrderMut>,
+    status_index: RwLock<Weak<StatusIndex>>,
 }
 
 impl Debug for OrderRefData {
@@ -37,7 +41,7 @@ impl PartialEq for OrderRef {
 }
 
 impl OrderRef {
-    fn from_snapshot(snapshot: &OrderSnapshot) -> Self {
+    fn from_snapshot(snapshot: &OrderSnapshot, status_index: Weak<StatusIndex>) -> Self {
         Self {
             inner: Arc::new(OrderRefData {
                 header: snapshot.header.clone(),
@@ -48,6 +52,7 @@ impl OrderRef {
                     internal_props: snapshot.internal_props.clone(),
                     extension_data: snapshot.extension_data.clone(),
                 }),
+                status_index: RwLock::new(status_index),
             }),
         }
     }
@@ -99,7 +104,30 @@ impl OrderRef {
 
     /// Lock order for write and provide mutate state of order
     pub fn fn_mut<T: 'static>(&self, f: impl FnOnce(&mut OrderMut) -> T) -> T {
-        f(self.inner.data.write().borrow_mut())
+        let mut order = self.inner.data.write();
+        let status_before = order.status();
+        let result = f(&mut order);
+
+        // Status index is updated under order lock, so concurrent status changes of the order are applied in the same order
+        let status_after = order.status();
+        if status_before != status_after {
+            self.move_in_status_index(status_before, status_after);
+        }
+
+        result
+    }
+
+    fn move_in_status_index(&self, from: OrderStatus, to: OrderStatus) {
+        if let Some(status_index) = self.inner.status_index.read().upgrade() {
+            let client_order_id = &self.header().client_order_id;
+            if let Some(client_order_ids) = status_index.get(&from) {
+                let _ = client_order_ids.remove(client_order_id);
+            }
+            let _ = status_index
+                .entry(to)
+                .or_default()
+                .insert(client_order_id.clone());
+        }
     }
 
     pub fn status(&self) -> OrderStatus {
@@ -147,6 +175,7 @@ pub struct OrdersPool {
     pub cache_by_client_id: DashMap<ClientOrderId, OrderRef>,
     pub cache_by_exchange_id: DashMap<ExchangeOrderId, OrderRef>,
     pub not_finished: DashMap<ClientOrderId, OrderRef>,
+    by_status: Arc<StatusIndex>,
 }
 
 impl OrdersPool {
@@ -157,6 +186,7 @@ impl OrdersPool {
             cache_by_client_id: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
             cache_by_exchange_id: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
             not_finished: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
+            by_status: Default::default(),
         })
     }
 
@@ -164,11 +194,15 @@ impl OrdersPool {
     pub fn add_snapshot_initial(&self, snapshot: &OrderSnapshot) -> OrderRef {
         let client_order_id = snapshot.header.client_order_id.clone();
 
-        let order_ref = OrderRef::from_snapshot(snapshot);
-        let _ = self
+        let order_ref = OrderRef::from_snapshot(snapshot, Arc::downgrade(&self.by_status));
+        let replaced_order = self
             .cache_by_client_id
             .insert(client_order_id.clone(), order_ref.clone());
+        if let Some(replaced_order) = replaced_order {
+            self.remove_from_status_index(&replaced_order);
+        }
         let _ = self.not_finished.insert(client_order_id, order_ref.clone());
+        self.add_to_status_index(&order_ref);
 
         order_ref
     }
@@ -192,6 +226,7 @@ impl OrdersPool {
                             internal_props: Default::default(),
                             extension_data,
                         }),
+                        status_index: RwLock::new(Arc::downgrade(&self.by_status)),
                     }),
                 };
 
@@ -200,6 +235,7 @@ impl OrdersPool {
                     .cache_by_client_id
                     .insert(client_order_id.clone(), order.clone());
                 let _ = self.not_finished.insert(client_order_id, order.clone());
+                self.add_to_status_index(&order);
 
                 order
             }
@@ -209,4 +245,311 @@ impl OrdersPool {
             }
         }
     }
+
+    /// Replace order in pool by a new unfilled order with the same client order id and amended header
+    /// after exchange replaced the order by a new exchange order.
+    /// Replaced order keeps its fills and stays available only by its exchange order id,
+    /// so late fills and cancellation of the replaced exchange order are applied to it instead of the replacement.
+    /// Returns `None` if there is no order with client order id of the header in pool.
+    pub fn amend_in_place(
+        &self,
+        header: OrderHeader,
+        replaced_exchange_order_id: &ExchangeOrderId,
+        new_exchange_order_id: ExchangeOrderId,
+        init_time: DateTime,
+    ) -> Option<OrderRef> {
+        let replaced_order = self
+            .cache_by_client_id
+            .get(&header.client_order_id)?
+            .value()
+            .clone();
+
+        // Exchange order id of the replaced order could be overwritten by creation event of the replacement
+        // with the same client order id if the event was received before response on amendment request
+        let extension_data = replaced_order.fn_mut(|order| {
+            order.props.exchange_order_id = Some(replaced_exchange_order_id.clone());
+            order.extension_data.clone()
+        });
+        // Replaced order isn't available by client order id anymore, so its status changes
+        // shouldn't move the client order id of the replacement in status index
+        *replaced_order.inner.status_index.write() = Weak::new();
+
+        let mut props = OrderSimpleProps::from_init_time(init_time);
+        props.exchange_order_id = Some(new_exchange_order_id.clone());
+        let mut snapshot = OrderSnapshot::new(
+            header,
+            props,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            extension_data,
+        );
+        snapshot.set_status(OrderStatus::Created, init_time);
+
+        let order = self.add_snapshot_initial(&snapshot);
+        let _ = self
+            .cache_by_exchange_id
+            .insert(replaced_exchange_order_id.clone(), replaced_order);
+        let _ = self
+            .cache_by_exchange_id
+            .insert(new_exchange_order_id, order.clone());
+
+        Some(order)
+    }
+
+    /// Orders with specified status.
+    /// Only orders with the status are traversed thanks to status index instead of all orders in pool.
+    pub fn iter_by_status(&self, status: OrderStatus) -> impl Iterator<Item = OrderRef> + '_ {
+        let client_order_ids = self
+            .by_status
+            .get(&status)
+            .map(|ids| ids.iter().map(|id| id.key().clone()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        client_order_ids
+            .into_iter()
+            .filter_map(move |client_order_id| {
+                self.cache_by_client_id
+                    .get(&client_order_id)
+                    .map(|order| order.value().clone())
+                    // status could be changed after we took client order ids from index
+                    .filter(|order| order.status() == status)
+            })
+    }
+
+    /// Remove orders that were removed from `cache_by_client_id` from the status index
+    pub fn prune_status_index(&self) {
+        for client_order_ids in self.by_status.iter() {
+            client_order_ids.retain(|id| self.cache_by_client_id.contains_key(id));
+        }
+    }
+
+    fn add_to_status_index(&self, order: &OrderRef) {
+        let _ = self
+            .by_status
+            .entry(order.status())
+            .or_default()
+            .insert(order.client_order_id());
+    }
+
+    fn remove_from_status_index(&self, order: &OrderRef) {
+        if let Some(client_order_ids) = self.by_status.get(&order.status()) {
+            let _ = client_order_ids.remove(&order.header().client_order_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::snapshot::{OrderOptions, UserOrder};
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn add_order(pool: &OrdersPool, client_order_id: &str) -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            client_order_id.into(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("a".into(), "b".into()),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        pool.add_simple_initial(&header, Utc::now(), None)
+    }
+
+    fn client_order_ids_by_status(pool: &OrdersPool, status: OrderStatus) -> Vec<ClientOrderId> {
+        let mut client_order_ids = pool
+            .iter_by_status(status)
+            .map(|order| order.client_order_id())
+            .collect::<Vec<_>>();
+        client_order_ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        client_order_ids
+    }
+
+    #[test]
+    fn status_index_follows_status_transitions() {
+        let pool = OrdersPool::new();
+        let first = add_order(&pool, "first");
+        let second = add_order(&pool, "second");
+
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::Creating),
+            vec!["first".into(), "second".into()]
+        );
+
+        first.fn_mut(|x| x.set_status(OrderStatus::Created, Utc::now()));
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::Creating),
+            vec!["second".into()]
+        );
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::Created),
+            vec!["first".into()]
+        );
+
+        first.fn_mut(|x| x.set_status(OrderStatus::Completed, Utc::now()));
+        second.fn_mut(|x| x.set_status(OrderStatus::FailedToCreate, Utc::now()));
+        assert!(client_order_ids_by_status(&pool, OrderStatus::Creating).is_empty());
+        assert!(client_order_ids_by_status(&pool, OrderStatus::Created).is_empty());
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::Completed),
+            vec!["first".into()]
+        );
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::FailedToCreate),
+            vec!["second".into()]
+        );
+    }
+
+    #[test]
+    fn status_index_is_consistent_after_concurrent_updates() {
+        const ORDERS_COUNT: usize = 200;
+
+        let pool = OrdersPool::new();
+        let orders = (0..ORDERS_COUNT)
+            .map(|i| add_order(&pool, &format!("order{i}")))
+            .collect::<Vec<_>>();
+
+        std::thread::scope(|scope| {
+            for chunk in orders.chunks(ORDERS_COUNT / 4) {
+                scope.spawn(move || {
+                    for order in chunk {
+                        for status in [
+                            OrderStatus::Created,
+                            OrderStatus::Canceling,
+                            OrderStatus::Canceled,
+                        ] {
+                            order.fn_mut(|x| x.set_status(status, Utc::now()));
+                        }
+                    }
+                });
+                // concurrent status changes of the same orders
+                scope.spawn(move || {
+                    for order in chunk {
+                        order.fn_mut(|x| x.set_status(OrderStatus::Created, Utc::now()));
+                    }
+                });
+            }
+        });
+
+        let count_by_status = |status| pool.iter_by_status(status).count();
+        let created_count = count_by_status(OrderStatus::Created);
+        let canceled_count = count_by_status(OrderStatus::Canceled);
+        assert_eq!(count_by_status(OrderStatus::Creating), 0);
+        assert_eq!(count_by_status(OrderStatus::Canceling), 0);
+        assert_eq!(created_count + canceled_count, ORDERS_COUNT);
+
+        let actual_created_count = orders
+            .iter()
+            .filter(|order| order.status() == OrderStatus::Created)
+            .count();
+        assert_eq!(created_count, actual_created_count);
+        for order in pool.iter_by_status(OrderStatus::Created) {
+            assert_eq!(order.status(), OrderStatus::Created);
+        }
+    }
+
+    #[test]
+    fn removed_orders_are_pruned_from_status_index() {
+        let pool = OrdersPool::new();
+        let order = add_order(&pool, "removed");
+        let _ = add_order(&pool, "kept");
+
+        let _ = pool.cache_by_client_id.remove(&order.client_order_id());
+        pool.prune_status_index();
+
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::Creating),
+            vec!["kept".into()]
+        );
+        let indexed_count = pool
+            .by_status
+            .get(&OrderStatus::Creating)
+            .map(|ids| ids.len());
+        assert_eq!(indexed_count, Some(1));
+    }
+
+    #[test]
+    fn amend_in_place_replaces_partially_filled_order_by_unfilled_one() {
+        let pool = OrdersPool::new();
+        let order = add_order(&pool, "amended");
+        let old_exchange_order_id: ExchangeOrderId = "old".into();
+        order.fn_mut(|x| {
+            x.props.exchange_order_id = Some(old_exchange_order_id.clone());
+            x.set_status(OrderStatus::Created, Utc::now());
+            x.fills.filled_amount = dec!(0.5);
+        });
+        let _ = pool
+            .cache_by_exchange_id
+            .insert(old_exchange_order_id.clone(), order.clone());
+
+        // creation event of the replacement is received before amendment response
+        let new_exchange_order_id: ExchangeOrderId = "new".into();
+        order.fn_mut(|x| x.props.exchange_order_id = Some(new_exchange_order_id.clone()));
+
+        let header = order
+            .header()
+            .clone()
+            .with_price_and_amount(dec!(0.7), dec!(1));
+        let amended = pool
+            .amend_in_place(
+                header,
+                &old_exchange_order_id,
+                new_exchange_order_id.clone(),
+                Utc::now(),
+            )
+            .expect("in test");
+
+        assert_eq!(amended.client_order_id(), order.client_order_id());
+        assert_eq!(amended.price(), dec!(0.7));
+        assert_eq!(amended.amount(), dec!(1));
+        assert!(matches!(
+            amended.header().options,
+            OrderOptions::User(UserOrder::Limit { price, .. }) if price == dec!(0.7)
+        ));
+        assert_eq!(amended.status(), OrderStatus::Created);
+        assert_eq!(amended.filled_amount(), dec!(0));
+        assert_eq!(
+            amended.exchange_order_id(),
+            Some(new_exchange_order_id.clone())
+        );
+
+        assert_eq!(order.filled_amount(), dec!(0.5));
+        assert_eq!(
+            order.exchange_order_id(),
+            Some(old_exchange_order_id.clone())
+        );
+
+        let by_client_id = pool
+            .cache_by_client_id
+            .get(&order.client_order_id())
+            .map(|x| x.value().clone());
+        assert_eq!(by_client_id, Some(amended.clone()));
+        let not_finished = pool
+            .not_finished
+            .get(&order.client_order_id())
+            .map(|x| x.value().clone());
+        assert_eq!(not_finished, Some(amended.clone()));
+        let by_old_exchange_id = pool
+            .cache_by_exchange_id
+            .get(&old_exchange_order_id)
+            .map(|x| x.value().clone());
+        assert_eq!(by_old_exchange_id, Some(order.clone()));
+        let by_exchange_id = pool
+            .cache_by_exchange_id
+            .get(&new_exchange_order_id)
+            .map(|x| x.value().clone());
+        assert_eq!(by_exchange_id, Some(amended));
+
+        // cancellation of replaced exchange order doesn't affect the replacement in status index
+        order.fn_mut(|x| x.set_status(OrderStatus::Canceled, Utc::now()));
+        assert_eq!(
+            client_order_ids_by_status(&pool, OrderStatus::Created),
+            vec!["amended".into()]
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use crate::events::EventSourceType;
-use crate::market::CurrencyPair;
+use crate::market::{CurrencyCode, CurrencyPair};
 use crate::market::{ExchangeAccountId, ExchangeErrorType, MarketAccountId, MarketId};
 use crate::order::fill::OrderFill;
 use chrono::Utc;
@@ -19,6 +19,7 @@ use std::fmt::Write;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use std::vec::Vec;
 use uuid::Uuid;
 
@@ -111,6 +112,20 @@ pub enum OrderExecutionType {
     MakerOnly = 1,
 }
 
+/// How long an order remains active on the exchange before it is executed or expired
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub enum TimeInForce {
+    /// GTC: order stays on the book until it is filled or canceled
+    #[default]
+    GoodTillCancelled,
+    /// IOC: order is filled immediately as much as possible, the rest is canceled
+    ImmediateOrCancel,
+    /// FOK: order is filled immediately and completely or canceled
+    FillOrKill,
+    /// GTX: post-only order that is rejected if it would take liquidity
+    GoodTillCrossing,
+}
+
 impl_str_id!(ClientOrderId);
 
 impl_from_for_str_id!(i64, ClientOrderId);
@@ -296,6 +311,17 @@ pub struct OrderHeader {
 
     pub signal_id: Option<String>,
     pub strategy_name: String,
+
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+
+    /// Order can only reduce current position on futures market and never open or flip it
+    #[serde(default)]
+    pub reduce_only: bool,
+
+    /// Order is cancelled automatically if it isn't filled during TTL since creation acknowledged by exchange
+    #[serde(default)]
+    pub ttl: Option<Duration>,
 }
 
 impl OrderHeader {
@@ -348,7 +374,39 @@ impl OrderHeader {
             reservation_id,
             signal_id,
             strategy_name,
+            time_in_force: TimeInForce::default(),
+            reduce_only: false,
+            ttl: None,
+        }
+    }
+
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    pub fn with_reduce_only(mut self, reduce_only: bool) -> Self {
+        self.reduce_only = reduce_only;
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Header of limit order amended by cancel-replace
+    pub fn with_price_and_amount(mut self, price: Price, amount: Amount) -> Self {
+        if let OrderOptions::User(UserOrder::Limit {
+            price: options_price,
+            ..
+        }) = &mut self.options
+        {
+            *options_price = price;
         }
+        self.source_price = Some(price);
+        self.amount = amount;
+        self
     }
 
     pub fn market_account_id(&self) -> MarketAccountId {
@@ -442,9 +500,47 @@ impl From<OrderRole> for OrderFillRole {
 pub struct OrderFills {
     pub fills: Vec<OrderFill>,
     pub filled_amount: Amount,
+    /// Sum of commissions of fills in `cumulative_commission_currency`
+    #[serde(default)]
+    pub cumulative_commission: Amount,
+    /// Commission currency of the first fill. Commissions of fills in other currencies aren't
+    /// included into `cumulative_commission` (see `has_mixed_commission_currencies`)
+    #[serde(default)]
+    pub cumulative_commission_currency: Option<CurrencyCode>,
 }
 
 impl OrderFills {
+    pub fn add_fill(&mut self, fill: OrderFill) {
+        self.filled_amount += fill.amount();
+
+        let commission_currency_code = fill.commission_currency_code();
+        match self.cumulative_commission_currency {
+            Some(currency_code) if currency_code != commission_currency_code => {
+                log::warn!(
+                    "Commission {} {commission_currency_code} of fill {} isn't added to cumulative commission in {currency_code}",
+                    fill.commission_amount(),
+                    fill.id(),
+                );
+            }
+            _ => {
+                self.cumulative_commission_currency = Some(commission_currency_code);
+                self.cumulative_commission += fill.commission_amount();
+            }
+        }
+
+        self.fills.push(fill);
+    }
+
+    /// Commissions of fills were paid in different currencies, so `cumulative_commission` doesn't include all of them
+    pub fn has_mixed_commission_currencies(&self) -> bool {
+        self.cumulative_commission_currency
+            .is_some_and(|currency_code| {
+                self.fills
+                    .iter()
+                    .any(|fill| fill.commission_currency_code() != currency_code)
+            })
+    }
+
     pub fn last_fill_received_time(&self) -> Option<DateTime> {
         self.fills.last().map(|x| x.receive_time())
     }
@@ -616,8 +712,26 @@ pub struct OrderMut {
 
 impl OrderMut {
     pub fn add_fill(&mut self, fill: OrderFill) {
-        self.fills.filled_amount += fill.amount();
-        self.fills.fills.push(fill);
+        self.fills.add_fill(fill);
+    }
+
+    /// Add fill if the order has no fill with the same trade id yet.
+    /// Returns `false` for a duplicate, so the same trade can be safely received
+    /// from several sources (e.g. WebSocket and REST fallback) concurrently.
+    pub fn try_add_fill(&mut self, fill: OrderFill) -> bool {
+        if let Some(trade_id) = fill.trade_id() {
+            let is_duplicate = self
+                .fills
+                .fills
+                .iter()
+                .any(|existing| existing.trade_id() == Some(trade_id));
+            if is_duplicate {
+                return false;
+            }
+        }
+
+        self.add_fill(fill);
+        true
     }
 
     pub fn status(&self) -> OrderStatus {
@@ -749,8 +863,7 @@ impl OrderSnapshot {
     }
 
     pub fn add_fill(&mut self, fill: OrderFill) {
-        self.fills.filled_amount += fill.amount();
-        self.fills.fills.push(fill);
+        self.fills.add_fill(fill);
     }
 
     pub fn set_status(&mut self, new_status: OrderStatus, time: DateTime) {
@@ -792,3 +905,60 @@ fn set_status(
         time,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::fill::OrderFillType;
+    use rust_decimal_macros::dec;
+
+    fn order_fill(amount: Amount, commission_currency_code: &str, commission: Amount) -> OrderFill {
+        let commission_currency_code = CurrencyCode::from(commission_currency_code);
+        OrderFill::new(
+            Uuid::new_v4(),
+            None,
+            Utc::now(),
+            OrderFillType::UserTrade,
+            None,
+            dec!(100),
+            amount,
+            dec!(100) * amount,
+            OrderFillRole::Taker,
+            commission_currency_code,
+            commission,
+            dec!(0),
+            commission_currency_code,
+            commission,
+            commission,
+            false,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn cumulative_commission_after_partial_fills() {
+        let mut fills = OrderFills::default();
+
+        fills.add_fill(order_fill(dec!(1), "bnb", dec!(0.001)));
+        fills.add_fill(order_fill(dec!(2), "bnb", dec!(0.002)));
+        fills.add_fill(order_fill(dec!(0.5), "bnb", dec!(0.0005)));
+
+        assert_eq!(fills.filled_amount, dec!(3.5));
+        assert_eq!(fills.cumulative_commission, dec!(0.0035));
+        assert_eq!(fills.cumulative_commission_currency, Some("bnb".into()));
+        assert!(!fills.has_mixed_commission_currencies());
+    }
+
+    #[test]
+    fn commission_in_other_currency_not_added_to_cumulative() {
+        let mut fills = OrderFills::default();
+
+        fills.add_fill(order_fill(dec!(1), "bnb", dec!(0.001)));
+        fills.add_fill(order_fill(dec!(1), "usdt", dec!(0.1)));
+
+        assert_eq!(fills.cumulative_commission, dec!(0.001));
+        assert_eq!(fills.cumulative_commission_currency, Some("bnb".into()));
+        assert!(fills.has_mixed_commission_currencies());
+    }
+}
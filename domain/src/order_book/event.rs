@@ -27,6 +27,12 @@ pub struct OrderBookEvent {
 
     pub event_type: EventType,
     pub data: Arc<OrderBookData>,
+
+    /// Exchange sequence number of the first update included into the event.
+    /// It's equal to `last_update_id` if event contains a single update
+    pub first_update_id: Option<u64>,
+    /// Exchange sequence number of the last update included into the event
+    pub last_update_id: Option<u64>,
 }
 
 impl OrderBookEvent {
@@ -46,9 +52,18 @@ impl OrderBookEvent {
             _event_id,
             event_type,
             data,
+            first_update_id: None,
+            last_update_id: None,
         }
     }
 
+    /// Set exchange sequence numbers of updates included into the event for gap detection
+    pub fn with_update_ids(mut self, first_update_id: u64, last_update_id: u64) -> Self {
+        self.first_update_id = Some(first_update_id);
+        self.last_update_id = Some(last_update_id);
+        self
+    }
+
     pub fn market_account_id(&self) -> MarketAccountId {
         MarketAccountId::new(self.exchange_account_id, self.currency_pair)
     }
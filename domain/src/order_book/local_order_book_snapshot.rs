@@ -3,6 +3,7 @@ use crate::order::snapshot::*;
 use crate::order::snapshot::{PriceByOrderSide, SortedOrderData};
 use crate::order_book::order_book_data::OrderBookData;
 use mmb_utils::DateTime;
+use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
 /// Fields from OrderSnapshot for exclude order
@@ -156,6 +157,36 @@ impl LocalOrderBookSnapshot {
         Some((top_ask + top_bid) * dec!(0.5))
     }
 
+    /// Imbalance of amounts on top price levels in range [-1, 1].
+    /// Positive value means buying pressure, negative value means selling pressure
+    pub fn calculate_imbalance(&self) -> Option<Decimal> {
+        let (_, top_ask_amount) = self.get_top_ask()?;
+        let (_, top_bid_amount) = self.get_top_bid()?;
+
+        let total_amount = top_bid_amount + top_ask_amount;
+        if total_amount.is_zero() {
+            return None;
+        }
+
+        Some((top_bid_amount - top_ask_amount) / total_amount)
+    }
+
+    /// Amount which can be filled immediately by order with specified side not worse than `limit_price`
+    pub fn calculate_marketable_amount(&self, side: OrderSide, limit_price: Price) -> Amount {
+        match side {
+            OrderSide::Buy => self
+                .get_asks_price_levels()
+                .take_while(|&(&price, _)| price <= limit_price)
+                .map(|(_, amount)| amount)
+                .sum(),
+            OrderSide::Sell => self
+                .get_bids_price_levels()
+                .take_while(|&(&price, _)| price >= limit_price)
+                .map(|(_, amount)| amount)
+                .sum(),
+        }
+    }
+
     /// Removed asks and bids between top price levels if it's crossed
     pub fn fix_asks_bids_if_needed(&mut self) -> ResultAskBidFix {
         match self.get_top_prices() {
@@ -199,6 +230,7 @@ impl LocalOrderBookSnapshot {
 mod tests {
     use super::*;
     use chrono::Utc;
+    use rstest::rstest;
 
     #[test]
     fn get_top_ask() {
@@ -243,6 +275,69 @@ mod tests {
         assert_eq!(top_bid, (dec!(3.0), dec!(4.2)))
     }
 
+    #[test]
+    fn calculate_imbalance() {
+        let mut asks = SortedOrderData::new();
+        asks.insert(dec!(3.0), dec!(1));
+        asks.insert(dec!(4.0), dec!(10));
+        let mut bids = SortedOrderData::new();
+        bids.insert(dec!(2.0), dec!(3));
+        bids.insert(dec!(1.0), dec!(10));
+
+        let order_book_snapshot = LocalOrderBookSnapshot::new(asks, bids, Utc::now());
+
+        assert_eq!(order_book_snapshot.calculate_imbalance(), Some(dec!(0.5)));
+    }
+
+    #[test]
+    fn calculate_imbalance_without_bids() {
+        let mut asks = SortedOrderData::new();
+        asks.insert(dec!(3.0), dec!(1));
+        let bids = SortedOrderData::new();
+
+        let order_book_snapshot = LocalOrderBookSnapshot::new(asks, bids, Utc::now());
+
+        assert_eq!(order_book_snapshot.calculate_imbalance(), None);
+    }
+
+    #[rstest]
+    #[case::buy_up_to_limit(OrderSide::Buy, dec!(4.0), dec!(11))]
+    #[case::buy_below_top(OrderSide::Buy, dec!(2.5), dec!(0))]
+    #[case::buy_beyond_all_levels(OrderSide::Buy, dec!(100), dec!(16))]
+    #[case::sell_up_to_limit(OrderSide::Sell, dec!(1.5), dec!(3))]
+    #[case::sell_beyond_all_levels(OrderSide::Sell, dec!(0.1), dec!(13))]
+    fn calculate_marketable_amount(
+        #[case] side: OrderSide,
+        #[case] limit_price: Price,
+        #[case] expected: Amount,
+    ) {
+        let mut asks = SortedOrderData::new();
+        asks.insert(dec!(3.0), dec!(1));
+        asks.insert(dec!(4.0), dec!(10));
+        asks.insert(dec!(5.0), dec!(5));
+        let mut bids = SortedOrderData::new();
+        bids.insert(dec!(2.0), dec!(3));
+        bids.insert(dec!(1.0), dec!(10));
+
+        let order_book_snapshot = LocalOrderBookSnapshot::new(asks, bids, Utc::now());
+
+        assert_eq!(
+            order_book_snapshot.calculate_marketable_amount(side, limit_price),
+            expected
+        );
+    }
+
+    #[test]
+    fn calculate_marketable_amount_for_empty_book() {
+        let order_book_snapshot =
+            LocalOrderBookSnapshot::new(SortedOrderData::new(), SortedOrderData::new(), Utc::now());
+
+        assert_eq!(
+            order_book_snapshot.calculate_marketable_amount(OrderSide::Buy, dec!(10)),
+            dec!(0)
+        );
+    }
+
     #[test]
     fn get_bids_price_levels() {
         let asks = SortedOrderData::new();
@@ -0,0 +1,198 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::order::snapshot::{Amount, Price, SortedOrderData};
+use crate::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use crate::order_book::order_book_data::OrderBookData;
+
+/// Changes of price levels on one side of order book
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceLevelsDiff {
+    /// Price levels that are absent in old snapshot
+    pub added: Vec<(Price, Amount)>,
+    /// Prices of levels that are absent in new snapshot
+    pub removed: Vec<Price>,
+    /// Price levels with different amounts in the form of (price, old amount, new amount)
+    pub changed: Vec<(Price, Amount, Amount)>,
+}
+
+impl PriceLevelsDiff {
+    /// Compare price levels in one pass, because both sides are sorted by price
+    pub fn new(old: &SortedOrderData, new: &SortedOrderData) -> Self {
+        let mut diff = PriceLevelsDiff::default();
+        let mut old_levels = old.iter().peekable();
+        let mut new_levels = new.iter().peekable();
+
+        loop {
+            let ordering = match (old_levels.peek(), new_levels.peek()) {
+                (Some((old_price, _)), Some((new_price, _))) => old_price.cmp(new_price),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return diff,
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    if let Some((&price, _)) = old_levels.next() {
+                        diff.removed.push(price);
+                    }
+                }
+                Ordering::Greater => {
+                    if let Some((&price, &amount)) = new_levels.next() {
+                        diff.added.push((price, amount));
+                    }
+                }
+                Ordering::Equal => {
+                    if let (Some((&price, &old_amount)), Some((_, &new_amount))) =
+                        (old_levels.next(), new_levels.next())
+                    {
+                        if old_amount != new_amount {
+                            diff.changed.push((price, old_amount, new_amount));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Changes between two states of order book.
+/// Asks and bids are compared separately, so price can't be mixed up between sides
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBookDiff {
+    pub asks: PriceLevelsDiff,
+    pub bids: PriceLevelsDiff,
+}
+
+impl OrderBookDiff {
+    pub fn from_snapshots(old: &LocalOrderBookSnapshot, new: &LocalOrderBookSnapshot) -> Self {
+        OrderBookDiff {
+            asks: PriceLevelsDiff::new(&old.asks, &new.asks),
+            bids: PriceLevelsDiff::new(&old.bids, &new.bids),
+        }
+    }
+
+    pub fn from_order_book_data(old: &OrderBookData, new: &OrderBookData) -> Self {
+        OrderBookDiff {
+            asks: PriceLevelsDiff::new(&old.asks, &new.asks),
+            bids: PriceLevelsDiff::new(&old.bids, &new.bids),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.asks.is_empty() && self.bids.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_book_data;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn snapshot(order_book: OrderBookData) -> LocalOrderBookSnapshot {
+        order_book.to_orderbook_snapshot(Utc::now())
+    }
+
+    #[test]
+    fn diff_of_same_snapshots_is_empty() {
+        let order_book = order_book_data![
+            dec!(10) => dec!(1),
+            dec!(11) => dec!(2),
+            ;
+            dec!(9) => dec!(3),
+        ];
+
+        let diff =
+            OrderBookDiff::from_snapshots(&snapshot(order_book.clone()), &snapshot(order_book));
+
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn diff_of_updated_snapshot() {
+        let old = order_book_data![
+            dec!(10) => dec!(1),
+            dec!(11) => dec!(2),
+            dec!(13) => dec!(4),
+            ;
+            dec!(8) => dec!(5),
+            dec!(9) => dec!(3),
+        ];
+        let new = order_book_data![
+            dec!(10) => dec!(1),
+            dec!(11) => dec!(2.5),
+            dec!(12) => dec!(7),
+            ;
+            dec!(7) => dec!(1),
+            dec!(9) => dec!(3),
+        ];
+
+        let diff = OrderBookDiff::from_snapshots(&snapshot(old), &snapshot(new));
+
+        assert_eq!(
+            diff.asks,
+            PriceLevelsDiff {
+                added: vec![(dec!(12), dec!(7))],
+                removed: vec![dec!(13)],
+                changed: vec![(dec!(11), dec!(2), dec!(2.5))],
+            }
+        );
+        assert_eq!(
+            diff.bids,
+            PriceLevelsDiff {
+                added: vec![(dec!(7), dec!(1))],
+                removed: vec![dec!(8)],
+                changed: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn diff_from_empty_snapshot() {
+        let new = order_book_data![
+            dec!(10) => dec!(1),
+            dec!(11) => dec!(2),
+            ;
+        ];
+
+        let diff = OrderBookDiff::from_order_book_data(&OrderBookData::default(), &new);
+        assert_eq!(
+            diff.asks.added,
+            vec![(dec!(10), dec!(1)), (dec!(11), dec!(2))]
+        );
+        assert!(diff.bids.is_empty());
+
+        let diff = OrderBookDiff::from_order_book_data(&new, &OrderBookData::default());
+        assert_eq!(diff.asks.removed, vec![dec!(10), dec!(11)]);
+        assert!(diff.asks.added.is_empty() && diff.asks.changed.is_empty());
+    }
+
+    #[test]
+    fn serialize_diff() {
+        let old = order_book_data![
+            dec!(10) => dec!(1),
+            ;
+        ];
+        let new = order_book_data![
+            dec!(10) => dec!(2),
+            ;
+        ];
+
+        let diff = OrderBookDiff::from_order_book_data(&old, &new);
+        let json = serde_json::to_value(&diff).expect("in test");
+
+        assert_eq!(json["asks"]["changed"][0][0], "10");
+        assert_eq!(json["asks"]["changed"][0][2], "2");
+        assert_eq!(
+            serde_json::from_value::<OrderBookDiff>(json).expect("in test"),
+            diff
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::order::snapshot::{Amount, ExchangeOrderId, OrderSide, Price, String16
 use mmb_utils::DateTime;
 use once_cell::sync::Lazy;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -45,6 +46,39 @@ impl DerivativePosition {
     }
 }
 
+/// Signed position (positive is long, negative is short) and weighted average price of fills opened it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionEntry {
+    pub position: Amount,
+    pub average_entry_price: Price,
+}
+
+impl PositionEntry {
+    /// Updates position by fill with signed amount. Fill increasing position moves average entry price,
+    /// reducing fill keeps it. If fill flips position, the rest of fill opens new position by fill price
+    pub fn apply_fill(&mut self, signed_amount: Amount, price: Price) {
+        if signed_amount.is_zero() {
+            return;
+        }
+
+        let new_position = self.position + signed_amount;
+        let is_increasing = self.position.is_zero()
+            || self.position.is_sign_positive() == signed_amount.is_sign_positive();
+
+        if is_increasing {
+            self.average_entry_price = (self.position.abs() * self.average_entry_price
+                + signed_amount.abs() * price)
+                / new_position.abs();
+        } else if new_position.is_zero() {
+            self.average_entry_price = Price::ZERO;
+        } else if new_position.is_sign_positive() != self.position.is_sign_positive() {
+            self.average_entry_price = price;
+        }
+
+        self.position = new_position;
+    }
+}
+
 #[derive(Debug)]
 pub struct ClosedPosition {
     pub exchange_order_id: ExchangeOrderId,
@@ -121,3 +155,37 @@ impl ActivePosition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    fn entry(position: Amount, average_entry_price: Price) -> PositionEntry {
+        PositionEntry {
+            position,
+            average_entry_price,
+        }
+    }
+
+    #[rstest]
+    #[case::open_long(entry(dec!(0), dec!(0)), dec!(2), dec!(100), entry(dec!(2), dec!(100)))]
+    #[case::increase_long(entry(dec!(1), dec!(100)), dec!(3), dec!(120), entry(dec!(4), dec!(115)))]
+    #[case::increase_short(entry(dec!(-1), dec!(100)), dec!(-1), dec!(90), entry(dec!(-2), dec!(95)))]
+    #[case::reduce_long(entry(dec!(3), dec!(100)), dec!(-1), dec!(150), entry(dec!(2), dec!(100)))]
+    #[case::reduce_short(entry(dec!(-3), dec!(100)), dec!(2), dec!(80), entry(dec!(-1), dec!(100)))]
+    #[case::close_long(entry(dec!(2), dec!(100)), dec!(-2), dec!(110), entry(dec!(0), dec!(0)))]
+    #[case::flip_long_to_short(entry(dec!(1), dec!(100)), dec!(-3), dec!(90), entry(dec!(-2), dec!(90)))]
+    #[case::flip_short_to_long(entry(dec!(-1), dec!(100)), dec!(2), dec!(110), entry(dec!(1), dec!(110)))]
+    #[case::zero_fill(entry(dec!(1), dec!(100)), dec!(0), dec!(110), entry(dec!(1), dec!(100)))]
+    fn apply_fill(
+        #[case] mut position_entry: PositionEntry,
+        #[case] signed_amount: Amount,
+        #[case] price: Price,
+        #[case] expected: PositionEntry,
+    ) {
+        position_entry.apply_fill(signed_amount, price);
+        assert_eq!(position_entry, expected);
+    }
+}
@@ -46,13 +46,17 @@ async fn main() -> Result<()> {
         let engine = launch_trading_engine(&engine_config, init_settings.clone()).await?;
 
         let settings = engine.settings();
-        let strategy = ExampleStrategy::new(
+        let mut strategy = ExampleStrategy::new(
             settings.strategy.exchange_account_id(),
             settings.strategy.currency_pair(),
             settings.strategy.spread,
+            settings.strategy.spread_volatility.clone(),
             settings.strategy.max_amount,
+            settings.strategy.equity_fraction,
+            None,
             engine.context(),
-        );
+        )?;
+        strategy.set_improve_price_by_tick(settings.strategy.improve_price_by_tick);
 
         engine.start_disposition_executor(strategy);
 
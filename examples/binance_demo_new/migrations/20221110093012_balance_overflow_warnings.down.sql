@@ -0,0 +1 @@
+DROP TABLE balance_overflow_warnings;
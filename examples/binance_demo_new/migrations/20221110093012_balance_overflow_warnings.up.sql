@@ -0,0 +1,11 @@
+CREATE TABLE balance_overflow_warnings (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX balance_overflow_warnings__insert_time_idx ON balance_overflow_warnings USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('balance_overflow_warnings', '1 mons', 'insert_time');
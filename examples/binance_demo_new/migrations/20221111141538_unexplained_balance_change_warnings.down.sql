@@ -0,0 +1 @@
+DROP TABLE unexplained_balance_change_warnings;
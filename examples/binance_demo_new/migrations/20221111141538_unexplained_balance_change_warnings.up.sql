@@ -0,0 +1,11 @@
+CREATE TABLE unexplained_balance_change_warnings (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX unexplained_balance_change_warnings__insert_time_idx ON unexplained_balance_change_warnings USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('unexplained_balance_change_warnings', '1 mons', 'insert_time');
@@ -0,0 +1,8 @@
+CREATE TABLE daily_pnl_summaries (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX daily_pnl_summaries__insert_time_idx ON daily_pnl_summaries USING btree (insert_time);
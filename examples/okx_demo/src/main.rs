@@ -0,0 +1,59 @@
+#![deny(
+    non_ascii_idents,
+    non_shorthand_field_patterns,
+    no_mangle_generic_items,
+    overflowing_literals,
+    path_statements,
+    unused_allocation,
+    unused_comparisons,
+    unused_parens,
+    while_true,
+    trivial_numeric_casts,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_must_use,
+    clippy::unwrap_used
+)]
+
+use anyhow::Result;
+use mmb_core::config::{CONFIG_PATH, CREDENTIALS_PATH};
+use mmb_core::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
+use mmb_core::lifecycle::launcher::{launch_trading_engine, EngineBuildConfig, InitSettings};
+use mmb_core::settings::DispositionStrategySettings;
+use okx::okx::OkxBuilder;
+use strategies::example_strategy::{ExampleStrategy, ExampleStrategySettings};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let engine_config = EngineBuildConfig::new(vec![Box::new(OkxBuilder)]);
+
+    let init_settings = InitSettings::<ExampleStrategySettings>::Load {
+        config_path: CONFIG_PATH.to_owned(),
+        credentials_path: CREDENTIALS_PATH.to_owned(),
+    };
+    loop {
+        let engine = launch_trading_engine(&engine_config, init_settings.clone()).await?;
+
+        let settings = engine.settings();
+        let mut strategy = ExampleStrategy::new(
+            settings.strategy.exchange_account_id(),
+            settings.strategy.currency_pair(),
+            settings.strategy.spread,
+            settings.strategy.spread_volatility.clone(),
+            settings.strategy.max_amount,
+            settings.strategy.equity_fraction,
+            None,
+            engine.context(),
+        )?;
+        strategy.set_improve_price_by_tick(settings.strategy.improve_price_by_tick);
+
+        engine.start_disposition_executor(strategy);
+
+        match engine.run().await {
+            ActionAfterGracefulShutdown::Nothing => break,
+            ActionAfterGracefulShutdown::Restart => continue,
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use anyhow::Result;
+use crate::market_making_spread::{MarketMakingSpread, SpreadVolatilitySettings};
+use crate::price_improvement::improve_price_by_tick;
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use mmb_core::balance::manager::balance_manager::BalanceManager;
 use mmb_core::disposition_execution::strategy::DispositionStrategy;
@@ -6,29 +8,49 @@ use mmb_core::disposition_execution::{
     PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
 };
 use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::infrastructure::spawn_by_timer;
 use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_core::misc::equity_sizing::EquitySizing;
 use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
 use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::services::usd_convertion::usd_converter::UsdConverter;
 use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
 use mmb_domain::events::ExchangeEvent;
-use mmb_domain::exchanges::symbol::Round;
+use mmb_domain::exchanges::symbol::{Round, Symbol};
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::market::{ExchangeAccountId, MarketAccountId, MarketId};
 use mmb_domain::order::snapshot::Amount;
 use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderSnapshot};
 use mmb_utils::cancellation_token::CancellationToken;
-use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
 use mmb_utils::DateTime;
+use parking_lot::Mutex;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
+
+const EQUITY_REFRESH_PERIOD: Duration = Duration::from_secs(60);
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ExampleStrategySettings {
     pub spread: Decimal,
+    /// Widen spread in volatile periods. Spread is constant if not set
+    #[serde(default)]
+    pub spread_volatility: Option<SpreadVolatilitySettings>,
     pub currency_pair: CurrencyPairSetting,
     pub max_amount: Decimal,
+    /// Size orders as a fraction of account equity in USD instead of available balance.
+    /// `max_amount` is still used as a limit of position. Requires `UsdConverter` for the strategy
+    #[serde(default)]
+    pub equity_fraction: Option<Decimal>,
+    /// Quote one tick inside the book instead of the best bid/ask when the spread allows it
+    #[serde(default)]
+    pub improve_price_by_tick: bool,
+    /// Paper trading mode without placing real orders
+    #[serde(default)]
+    pub dry_run: bool,
     pub exchange_account_id: ExchangeAccountId,
 }
 
@@ -38,13 +60,16 @@ impl DispositionStrategySettings for ExampleStrategySettings {
     }
 
     fn currency_pair(&self) -> CurrencyPair {
-        if let CurrencyPairSetting::Ordinary { base, quote } = self.currency_pair {
-            CurrencyPair::from_codes(base, quote)
-        } else {
-            panic!(
+        match self.currency_pair {
+            CurrencyPairSetting::Ordinary { base, quote } => CurrencyPair::from_codes(base, quote),
+            CurrencyPairSetting::Derivative { .. } => panic!(
+                "ExampleStrategy supports only spot currency pairs but derivative was specified: {:?}",
+                self.currency_pair
+            ),
+            CurrencyPairSetting::Specific(_) => panic!(
                 "Incorrect currency pair setting enum type {:?}",
                 self.currency_pair
-            );
+            ),
         }
     }
 
@@ -52,25 +77,40 @@ impl DispositionStrategySettings for ExampleStrategySettings {
     fn max_amount(&self) -> Amount {
         self.max_amount
     }
+
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
 }
 
 pub struct ExampleStrategy {
     target_eai: ExchangeAccountId,
     currency_pair: CurrencyPair,
-    spread: Decimal,
+    spread: MarketMakingSpread,
     engine_context: Arc<EngineContext>,
     configuration_descriptor: ConfigurationDescriptor,
     max_amount: Decimal,
+    equity_fraction: Option<Decimal>,
+    equity_sizing: Arc<Mutex<Option<EquitySizing>>>,
+    improve_price_by_tick: bool,
 }
 
 impl ExampleStrategy {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         target_eai: ExchangeAccountId,
         currency_pair: CurrencyPair,
         spread: Decimal,
+        spread_volatility: Option<SpreadVolatilitySettings>,
         max_amount: Decimal,
+        equity_fraction: Option<Decimal>,
+        usd_converter: Option<Arc<UsdConverter>>,
         engine_context: Arc<EngineContext>,
-    ) -> Box<Self> {
+    ) -> Result<Box<Self>> {
+        if equity_fraction.is_some() && usd_converter.is_none() {
+            bail!("ExampleStrategy: equity_fraction is specified but there is no UsdConverter to value balances in USD");
+        }
+
         let configuration_descriptor = ConfigurationDescriptor::new(
             "ExampleStrategy".into(),
             format!("{target_eai};{currency_pair}").as_str().into(),
@@ -82,27 +122,65 @@ impl ExampleStrategy {
         let amount_limit = max_amount * dec!(0.5);
 
         let symbol = engine_context
-            .exchanges
-            .get(&target_eai)
-            .with_expect(|| format!("failed to get exchange from trading_engine for {target_eai}"))
+            .get_exchange(target_eai)?
             .symbols
             .get(&currency_pair)
-            .with_expect(|| format!("failed to get symbol from exchange for {currency_pair}"))
+            .with_context(|| format!("failed to get symbol from exchange for {currency_pair}"))?
             .clone();
 
         engine_context
             .balance_manager
             .lock()
-            .set_target_amount_limit(configuration_descriptor, target_eai, symbol, amount_limit);
+            .set_target_amount_limit(
+                configuration_descriptor,
+                target_eai,
+                symbol.clone(),
+                amount_limit,
+            );
+
+        let spread = MarketMakingSpread::new(
+            MarketId::new(target_eai.exchange_id, currency_pair),
+            spread,
+            spread_volatility,
+        );
 
-        Box::new(ExampleStrategy {
+        let equity_sizing = Arc::new(Mutex::new(None));
+        if let Some(usd_converter) = usd_converter.filter(|_| equity_fraction.is_some()) {
+            let engine_context = engine_context.clone();
+            let equity_sizing = equity_sizing.clone();
+            spawn_by_timer(
+                "ExampleStrategy::refresh_equity_sizing()",
+                Duration::ZERO,
+                EQUITY_REFRESH_PERIOD,
+                SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+                move || {
+                    refresh_equity_sizing(
+                        engine_context.clone(),
+                        target_eai,
+                        symbol.clone(),
+                        usd_converter.clone(),
+                        equity_sizing.clone(),
+                    )
+                },
+            );
+        }
+
+        Ok(Box::new(ExampleStrategy {
             target_eai,
             currency_pair,
             spread,
             engine_context,
             configuration_descriptor,
             max_amount,
-        })
+            equity_fraction,
+            equity_sizing,
+            improve_price_by_tick: false,
+        }))
+    }
+
+    /// Quote one tick inside the book when order price is equal to the best price of the side
+    pub fn set_improve_price_by_tick(&mut self, improve_price_by_tick: bool) {
+        self.improve_price_by_tick = improve_price_by_tick;
     }
 
     fn strategy_name() -> &'static str {
@@ -138,66 +216,88 @@ impl ExampleStrategy {
             .get(&self.currency_pair)?
             .clone();
 
-        let price = if current_spread < self.spread {
+        let spread = self.spread.dynamic_spread();
+        let price = if current_spread < spread {
             let order_book_middle = (bid_max_price + ask_min_price) * dec!(0.5);
 
             match side {
                 OrderSide::Sell => {
-                    let price = order_book_middle + (self.spread * dec!(0.5));
+                    let price = order_book_middle + (spread * dec!(0.5));
                     symbol.price_round(price, Round::Ceiling)
                 }
                 OrderSide::Buy => {
-                    let price = order_book_middle - (self.spread * dec!(0.5));
+                    let price = order_book_middle - (spread * dec!(0.5));
                     symbol.price_round(price, Round::Floor)
                 }
             }
         } else {
-            snapshot.get_top(side)?.0
+            let best_price = snapshot.get_top(side)?.0;
+            if self.improve_price_by_tick {
+                let opposite_best_price = match side {
+                    OrderSide::Buy => ask_min_price,
+                    OrderSide::Sell => bid_max_price,
+                };
+                improve_price_by_tick(&symbol, side, best_price, Some(opposite_best_price))
+            } else {
+                best_price
+            }
         };
 
-        let amount;
-        explanation = {
-            let mut explanation = Some(explanation);
-
-            // TODO: delete deep_clone
-            let orders = self
-                .engine_context
-                .exchanges
-                .iter()
-                .flat_map(|x| {
-                    x.orders
-                        .not_finished
+        let amount = match self.equity_fraction {
+            // orders aren't created until equity is calculated because sizing by available balance
+            // can be much bigger than the fraction of equity
+            Some(fraction) => self
+                .equity_sizing
+                .lock()
+                .as_ref()?
+                // fraction of equity is less than min amount for the symbol
+                .amount_for_equity_fraction(fraction, price)?,
+            None => {
+                let amount;
+                explanation = {
+                    let mut explanation = Some(explanation);
+
+                    // TODO: delete deep_clone
+                    let orders = self
+                        .engine_context
+                        .exchanges
                         .iter()
-                        .map(|y| y.clone())
-                        .collect_vec()
-                })
-                .collect_vec();
-
-            let balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
-                self.engine_context.balance_manager.clone(),
-                Some(&mut orders.iter()),
-            )
-            .expect("ExampleStrategy::calc_trading_context_by_side: failed to clone and subtract not approved data for BalanceManager");
+                        .flat_map(|x| {
+                            x.orders
+                                .not_finished
+                                .iter()
+                                .map(|y| y.clone())
+                                .collect_vec()
+                        })
+                        .collect_vec();
 
-            amount = balance_manager
-                .lock()
-                .get_leveraged_balance_in_amount_currency_code(
-                    self.configuration_descriptor,
-                    side,
-                    self.target_eai,
-                    symbol.clone(),
-                    price,
-                    &mut explanation,
-                )
-                .with_expect(|| format!("Failed to get balance for {}", self.target_eai));
-
-            // This expect can happened if get_leveraged_balance_in_amount_currency_code() sets the explanation to None
-            explanation.expect(
-                "ExampleStrategy::calc_trading_context_by_side(): Explanation should be non None here"
-            )
-        };
+                    let balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
+                        self.engine_context.balance_manager.clone(),
+                        Some(&mut orders.iter()),
+                    )
+                    .expect("ExampleStrategy::calc_trading_context_by_side: failed to clone and subtract not approved data for BalanceManager");
+
+                    amount = balance_manager
+                        .lock()
+                        .get_leveraged_balance_in_amount_currency_code(
+                            self.configuration_descriptor,
+                            side,
+                            self.target_eai,
+                            symbol.clone(),
+                            price,
+                            &mut explanation,
+                        )
+                        .with_expect(|| format!("Failed to get balance for {}", self.target_eai));
+
+                    // This expect can happened if get_leveraged_balance_in_amount_currency_code() sets the explanation to None
+                    explanation.expect(
+                        "ExampleStrategy::calc_trading_context_by_side(): Explanation should be non None here"
+                    )
+                };
 
-        let amount = symbol.amount_round(amount, Round::Floor);
+                symbol.amount_round(amount, Round::Floor)
+            }
+        };
 
         Some(TradingContextBySide {
             max_amount: self.max_amount,
@@ -211,6 +311,7 @@ impl ExampleStrategy {
                         price,
                         amount,
                     ),
+                    max_position_size: None,
                 }),
                 explanation,
             }],
@@ -218,6 +319,45 @@ impl ExampleStrategy {
     }
 }
 
+/// Recalculate equity of the target exchange account by balances of the traded currencies.
+/// Last calculated equity is kept if balances can't be valued in USD at the moment
+async fn refresh_equity_sizing(
+    engine_context: Arc<EngineContext>,
+    target_eai: ExchangeAccountId,
+    symbol: Arc<Symbol>,
+    usd_converter: Arc<UsdConverter>,
+    equity_sizing: Arc<Mutex<Option<EquitySizing>>>,
+) {
+    let balances = {
+        let balance_manager = engine_context.balance_manager.lock();
+        [symbol.base_currency_code(), symbol.quote_currency_code()]
+            .into_iter()
+            .filter_map(|currency_code| {
+                balance_manager
+                    .get_exchange_balance(target_eai, symbol.clone(), currency_code)
+                    .map(|balance| (currency_code, balance))
+            })
+            .collect_vec()
+    };
+    if balances.is_empty() {
+        log::warn!("Can't calculate equity: balances of {target_eai} aren't received yet");
+        return;
+    }
+
+    let calculated = EquitySizing::calculate(
+        symbol,
+        balances,
+        &usd_converter,
+        engine_context.lifetime_manager.stop_token(),
+    )
+    .await;
+
+    if let Some(calculated) = calculated {
+        log::info!("Equity of {target_eai} is {} USD", calculated.equity_usd());
+        *equity_sizing.lock() = Some(calculated);
+    }
+}
+
 impl DispositionStrategy for ExampleStrategy {
     fn calculate_trading_context(
         &mut self,
@@ -226,6 +366,8 @@ impl DispositionStrategy for ExampleStrategy {
         local_snapshots_service: &LocalSnapshotsService,
         explanation: &mut Explanation,
     ) -> Option<TradingContext> {
+        self.spread.update(local_snapshots_service);
+
         let buy_trading_ctx = self.calc_trading_context_by_side(
             OrderSide::Buy,
             now,
@@ -258,3 +400,304 @@ impl DispositionStrategy for ExampleStrategy {
         self.configuration_descriptor
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use binance::binance::BinanceBuilder;
+    use dashmap::DashMap;
+    use mmb_core::database::events::recorder::EventRecorder;
+    use mmb_core::disposition_execution::TradingContext;
+    use mmb_core::exchanges::exchange_blocker::ExchangeBlocker;
+    use mmb_core::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+    use mmb_core::exchanges::general::exchange::Exchange;
+    use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestsTimeoutManagerFactory;
+    use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+    use mmb_core::exchanges::traits::ExchangeClientBuilder;
+    use mmb_core::infrastructure::init_lifetime_manager;
+    use mmb_core::misc::traits::market_service::{CreateMarketService, GetMarketCurrencyCodePrice};
+    use mmb_core::services::market_prices::market_currency_code_price::MarketCurrencyCodePrice;
+    use mmb_core::services::usd_convertion::price_source_service::PriceSourceService;
+    use mmb_core::services::usd_convertion::price_sources_loader::PriceSourcesLoader;
+    use mmb_core::services::usd_convertion::usd_denominator::UsdDenominator;
+    use mmb_core::settings::{CoreSettings, CurrencyPriceSourceSettings, ExchangeSettings};
+    use mmb_domain::events::{ExchangeBalance, ExchangeBalancesAndPositions, ExchangeEvents};
+    use mmb_domain::exchanges::commission::Commission;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+    use mmb_domain::order_book::order_book_data::OrderBookData;
+    use mmb_utils::hashmap;
+    use std::collections::{BTreeMap, HashMap};
+    use tokio::sync::{broadcast, oneshot};
+
+    struct TestMarketService;
+
+    impl CreateMarketService for TestMarketService {
+        fn new() -> Arc<Self> {
+            Arc::new(TestMarketService)
+        }
+    }
+
+    #[async_trait]
+    impl GetMarketCurrencyCodePrice for TestMarketService {
+        async fn get_market_currency_code_price(&self) -> Vec<MarketCurrencyCodePrice> {
+            vec![MarketCurrencyCodePrice::new(
+                "btc".into(),
+                Some(dec!(20000)),
+            )]
+        }
+    }
+
+    fn btc_usdt() -> CurrencyPair {
+        CurrencyPair::from_codes("btc".into(), "usdt".into())
+    }
+
+    fn symbol() -> Arc<Symbol> {
+        Arc::new(Symbol::new(
+            false,
+            "BTC".into(),
+            "btc".into(),
+            "USDT".into(),
+            "usdt".into(),
+            None,
+            None,
+            Some(dec!(0.001)),
+            None,
+            Some(dec!(10)),
+            "btc".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ))
+    }
+
+    async fn engine_context(exchange_account_id: ExchangeAccountId) -> Arc<EngineContext> {
+        let lifetime_manager = init_lifetime_manager();
+        let (events_sender, _) = broadcast::channel(10);
+        let exchange_client_builder = BinanceBuilder;
+        let timeout_manager = TimeoutManager::new(hashmap![
+            exchange_account_id => RequestsTimeoutManagerFactory::from_requests_per_period(
+                exchange_client_builder.get_timeout_arguments(),
+                exchange_account_id,
+            )
+        ]);
+        let exchange_client = exchange_client_builder.create_exchange_client(
+            ExchangeSettings::new_short(exchange_account_id, String::new(), String::new(), false),
+            events_sender.clone(),
+            lifetime_manager.clone(),
+            timeout_manager.clone(),
+            OrdersPool::new(),
+        );
+        let exchange_blocker = ExchangeBlocker::new(vec![exchange_account_id]);
+        let event_recorder = EventRecorder::start(None, None, 0.0)
+            .await
+            .expect("in test");
+
+        let exchange = Exchange::new(
+            exchange_account_id,
+            exchange_client.client,
+            OrdersPool::new(),
+            exchange_client.features,
+            exchange_client_builder.get_timeout_arguments(),
+            0.0,
+            events_sender.clone(),
+            lifetime_manager.clone(),
+            timeout_manager.clone(),
+            Arc::downgrade(&exchange_blocker),
+            Commission::default(),
+            event_recorder.clone(),
+        );
+        let _ = exchange.symbols.insert(btc_usdt(), symbol());
+
+        let balance_manager = BalanceManager::new(
+            CurrencyPairToSymbolConverter::new(hashmap![exchange_account_id => exchange.clone()]),
+            None,
+        );
+        exchange.setup_balance_manager(balance_manager.clone());
+        balance_manager
+            .lock()
+            .update_exchange_balance(
+                exchange_account_id,
+                &ExchangeBalancesAndPositions {
+                    balances: vec![
+                        ExchangeBalance {
+                            currency_code: "btc".into(),
+                            balance: dec!(0.5),
+                        },
+                        ExchangeBalance {
+                            currency_code: "usdt".into(),
+                            balance: dec!(5000),
+                        },
+                    ],
+                    positions: None,
+                },
+            )
+            .expect("in test");
+
+        let (finish_graceful_shutdown_sender, _) = oneshot::channel();
+        EngineContext::new(
+            CoreSettings::default(),
+            DashMap::from_iter([(exchange_account_id, exchange)]),
+            ExchangeEvents::new(events_sender),
+            finish_graceful_shutdown_sender,
+            exchange_blocker,
+            timeout_manager,
+            lifetime_manager,
+            balance_manager,
+            event_recorder,
+        )
+    }
+
+    async fn usd_converter(engine_context: &EngineContext) -> Arc<UsdConverter> {
+        // there are no price sources, so prices are taken from UsdDenominator
+        let price_source_service = PriceSourceService::new(
+            CurrencyPairToSymbolConverter::new(HashMap::new()),
+            &[CurrencyPriceSourceSettings::new(
+                "usdt".into(),
+                "usdt".into(),
+                vec![],
+            )],
+            PriceSourcesLoader::default(),
+        );
+        let usd_denominator = UsdDenominator::create_async::<TestMarketService>(
+            false,
+            engine_context.lifetime_manager.clone(),
+        )
+        .await;
+
+        Arc::new(UsdConverter::new(
+            &["usdt".into()],
+            price_source_service,
+            usd_denominator,
+        ))
+    }
+
+    fn order_book_event(exchange_account_id: ExchangeAccountId) -> OrderBookEvent {
+        let order_book = OrderBookData::new(
+            BTreeMap::from([(dec!(20000), dec!(1))]),
+            BTreeMap::from([(dec!(19990), dec!(1))]),
+        );
+        OrderBookEvent::new(
+            chrono::Utc::now(),
+            exchange_account_id,
+            btc_usdt(),
+            String::new(),
+            EventType::Snapshot,
+            Arc::new(order_book),
+        )
+    }
+
+    fn order_amount(trading_context: &TradingContext, side: OrderSide) -> Amount {
+        trading_context.by_side[side].estimating[0]
+            .value
+            .as_ref()
+            .expect("in test")
+            .disposition
+            .order
+            .amount
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn orders_are_sized_by_equity_fraction() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let engine_context = engine_context(exchange_account_id).await;
+        let usd_converter = usd_converter(&engine_context).await;
+
+        let mut strategy = ExampleStrategy::new(
+            exchange_account_id,
+            btc_usdt(),
+            dec!(1),
+            None,
+            dec!(1),
+            Some(dec!(0.1)),
+            Some(usd_converter.clone()),
+            engine_context.clone(),
+        )
+        .expect("in test");
+        let order_book_event = order_book_event(exchange_account_id);
+        let mut local_snapshots_service = LocalSnapshotsService::default();
+        let _ = local_snapshots_service.update(&order_book_event);
+
+        refresh_equity_sizing(
+            engine_context,
+            exchange_account_id,
+            symbol(),
+            usd_converter,
+            strategy.equity_sizing.clone(),
+        )
+        .await;
+
+        let trading_context = strategy
+            .calculate_trading_context(
+                &ExchangeEvent::OrderBookEvent(order_book_event),
+                chrono::Utc::now(),
+                &local_snapshots_service,
+                &mut Explanation::default(),
+            )
+            .expect("in test");
+
+        // equity 0.5 * 20000 + 5000 = 15000 USD, notional 0.1 * 15000 = 1500 USDT
+        // buy 1500 / 19990 = 0.07503... and sell 1500 / 20000 = 0.075 are rounded down by amount step
+        assert_eq!(order_amount(&trading_context, OrderSide::Buy), dec!(0.075));
+        assert_eq!(order_amount(&trading_context, OrderSide::Sell), dec!(0.075));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn equity_fraction_without_usd_converter_is_rejected() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let engine_context = engine_context(exchange_account_id).await;
+
+        let strategy = ExampleStrategy::new(
+            exchange_account_id,
+            btc_usdt(),
+            dec!(1),
+            None,
+            dec!(1),
+            Some(dec!(0.1)),
+            None,
+            engine_context,
+        );
+
+        assert!(strategy.is_err());
+    }
+
+    fn settings(currency_pair: CurrencyPairSetting) -> ExampleStrategySettings {
+        ExampleStrategySettings {
+            spread: dec!(1),
+            spread_volatility: None,
+            currency_pair,
+            max_amount: dec!(1),
+            equity_fraction: None,
+            improve_price_by_tick: false,
+            dry_run: false,
+            exchange_account_id: ExchangeAccountId::new("Binance", 0),
+        }
+    }
+
+    #[test]
+    fn ordinary_currency_pair() {
+        let settings = settings(CurrencyPairSetting::Ordinary {
+            base: "btc".into(),
+            quote: "usdt".into(),
+        });
+
+        assert_eq!(
+            settings.currency_pair(),
+            CurrencyPair::from_codes("btc".into(), "usdt".into())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ExampleStrategy supports only spot currency pairs")]
+    fn derivative_currency_pair_is_rejected() {
+        let settings = settings(CurrencyPairSetting::Derivative {
+            base: "btc".into(),
+            quote: "usdt".into(),
+            expiry: chrono::NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test"),
+        });
+
+        let _ = settings.currency_pair();
+    }
+}
@@ -0,0 +1,375 @@
+use anyhow::{Context, Result};
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::{
+    PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
+};
+use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderSnapshot};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Distance between neighboring levels of the grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridSpacing {
+    /// Count of price ticks of the symbol
+    Ticks(u32),
+    Price(Price),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GridStrategySettings {
+    /// Count of orders by every side
+    pub levels: usize,
+    pub spacing: GridSpacing,
+    /// Amount of order on every level
+    pub level_amount: Amount,
+    pub currency_pair: CurrencyPairSetting,
+    pub max_amount: Decimal,
+    pub exchange_account_id: ExchangeAccountId,
+}
+
+impl DispositionStrategySettings for GridStrategySettings {
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    fn currency_pair(&self) -> CurrencyPair {
+        match self.currency_pair {
+            CurrencyPairSetting::Ordinary { base, quote } => CurrencyPair::from_codes(base, quote),
+            CurrencyPairSetting::Derivative { .. } => panic!(
+                "GridStrategy supports only spot currency pairs but derivative was specified: {:?}",
+                self.currency_pair
+            ),
+            CurrencyPairSetting::Specific(_) => panic!(
+                "Incorrect currency pair setting enum type {:?}",
+                self.currency_pair
+            ),
+        }
+    }
+
+    // Max amount for orders that will be created
+    fn max_amount(&self) -> Amount {
+        self.max_amount
+    }
+
+    fn price_slots_count(&self) -> usize {
+        self.levels
+    }
+}
+
+/// Ladder of maker orders by both sides around the center of the grid.
+/// Level which order was filled is refilled with the same price on the next trading context calculation.
+/// Grid is centered by the middle price of order book at start and recentered
+/// when the middle price leaves the price range of the grid
+pub struct GridStrategy {
+    target_eai: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    levels: usize,
+    spacing: GridSpacing,
+    level_amount: Amount,
+    max_amount: Decimal,
+    center: Option<Price>,
+    symbol: Arc<Symbol>,
+    configuration_descriptor: ConfigurationDescriptor,
+}
+
+impl GridStrategy {
+    pub fn new(
+        settings: &GridStrategySettings,
+        engine_context: Arc<EngineContext>,
+    ) -> Result<Box<Self>> {
+        let target_eai = settings.exchange_account_id();
+        let currency_pair = settings.currency_pair();
+
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            Self::strategy_name().into(),
+            format!("{target_eai};{currency_pair}").as_str().into(),
+        );
+
+        let symbol = engine_context
+            .get_exchange(target_eai)?
+            .symbols
+            .get(&currency_pair)
+            .with_context(|| format!("failed to get symbol from exchange for {currency_pair}"))?
+            .clone();
+
+        // the same limit for position changing as in ExampleStrategy
+        let amount_limit = settings.max_amount * dec!(0.5);
+        engine_context
+            .balance_manager
+            .lock()
+            .set_target_amount_limit(
+                configuration_descriptor,
+                target_eai,
+                symbol.clone(),
+                amount_limit,
+            );
+
+        Ok(Box::new(GridStrategy {
+            target_eai,
+            currency_pair,
+            levels: settings.levels,
+            spacing: settings.spacing,
+            level_amount: settings.level_amount,
+            max_amount: settings.max_amount,
+            center: None,
+            symbol,
+            configuration_descriptor,
+        }))
+    }
+
+    fn strategy_name() -> &'static str {
+        "GridStrategy"
+    }
+
+    fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.target_eai, self.currency_pair)
+    }
+
+    fn market_id(&self) -> MarketId {
+        self.market_account_id().market_id()
+    }
+
+    fn calc_trading_context_by_side(
+        &self,
+        side: OrderSide,
+        center: Price,
+        step: Price,
+        best_prices: (Price, Price),
+        explanation: &Explanation,
+    ) -> TradingContextBySide {
+        let (bid_max_price, ask_min_price) = best_prices;
+        let amount = self.symbol.amount_round(self.level_amount, Round::Floor);
+
+        let estimating = (0..self.levels)
+            .map(|level_index| {
+                let mut explanation = explanation.clone();
+                let price = grid_level_price(&self.symbol, side, center, step, level_index);
+
+                let is_crossed = match side {
+                    OrderSide::Buy => price >= ask_min_price,
+                    OrderSide::Sell => price <= bid_max_price,
+                };
+                if is_crossed || price <= Price::ZERO {
+                    explanation.add_reason(format!(
+                        "Grid level {level_index} with price {price} is crossing the order book"
+                    ));
+                    return WithExplanation {
+                        value: None,
+                        explanation,
+                    };
+                }
+
+                WithExplanation {
+                    value: Some(TradeCycle {
+                        order_role: OrderRole::Maker,
+                        strategy_name: Self::strategy_name().to_string(),
+                        disposition: TradeDisposition::new(
+                            self.market_account_id(),
+                            side,
+                            price,
+                            amount,
+                        ),
+                        max_position_size: None,
+                    }),
+                    explanation,
+                }
+            })
+            .collect();
+
+        TradingContextBySide {
+            max_amount: self.max_amount,
+            estimating,
+        }
+    }
+}
+
+impl DispositionStrategy for GridStrategy {
+    fn calculate_trading_context(
+        &mut self,
+        _: &ExchangeEvent,
+        _now: DateTime,
+        local_snapshots_service: &LocalSnapshotsService,
+        explanation: &mut Explanation,
+    ) -> Option<TradingContext> {
+        let snapshot = local_snapshots_service.get_snapshot(self.market_id())?;
+        let ask_min_price = snapshot.get_top_ask()?.0;
+        let bid_max_price = snapshot.get_top_bid()?.0;
+
+        let middle_price = (bid_max_price + ask_min_price) * dec!(0.5);
+        let step = spacing_step(&self.symbol, self.spacing, middle_price);
+        let center = recenter(self.center, middle_price, step, self.levels);
+        if self.center != Some(center) {
+            log::info!(
+                "GridStrategy center is moved from {:?} to {center}",
+                self.center
+            );
+            self.center = Some(center);
+        }
+
+        let best_prices = (bid_max_price, ask_min_price);
+        let buy_trading_ctx = self.calc_trading_context_by_side(
+            OrderSide::Buy,
+            center,
+            step,
+            best_prices,
+            explanation,
+        );
+        let sell_trading_ctx = self.calc_trading_context_by_side(
+            OrderSide::Sell,
+            center,
+            step,
+            best_prices,
+            explanation,
+        );
+
+        Some(TradingContext::new(buy_trading_ctx, sell_trading_ctx))
+    }
+
+    fn handle_order_fill(
+        &self,
+        cloned_order: &Arc<OrderSnapshot>,
+        price_slot: &PriceSlot,
+        _target_eai: ExchangeAccountId,
+        _cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        log::info!(
+            "GridStrategy level {} {} filled by order {}, it will be refilled",
+            price_slot.id.level_index,
+            cloned_order.side(),
+            cloned_order.header.client_order_id
+        );
+        Ok(())
+    }
+
+    fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+        self.configuration_descriptor
+    }
+}
+
+fn spacing_step(symbol: &Symbol, spacing: GridSpacing, price: Price) -> Price {
+    match spacing {
+        GridSpacing::Ticks(ticks) => symbol.price_tick(price) * Decimal::from(ticks),
+        GridSpacing::Price(step) => step,
+    }
+}
+
+/// Level with index 0 is the nearest to the center
+fn grid_level_price(
+    symbol: &Symbol,
+    side: OrderSide,
+    center: Price,
+    step: Price,
+    level_index: usize,
+) -> Price {
+    let distance = step * Decimal::from(level_index + 1);
+    match side {
+        OrderSide::Buy => symbol.price_round(center - distance, Round::Floor),
+        OrderSide::Sell => symbol.price_round(center + distance, Round::Ceiling),
+    }
+}
+
+/// Center is kept while middle price stays inside the grid so filled levels can be refilled with the same prices
+fn recenter(center: Option<Price>, middle_price: Price, step: Price, levels: usize) -> Price {
+    match center {
+        Some(center) if (middle_price - center).abs() <= step * Decimal::from(levels) => center,
+        _ => middle_price,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::exchanges::symbol::Precision;
+
+    fn symbol() -> Symbol {
+        Symbol::new(
+            false,
+            "BTC".into(),
+            "btc".into(),
+            "USDT".into(),
+            "usdt".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "btc".into(),
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        )
+    }
+
+    #[test]
+    fn level_prices_around_center() {
+        let symbol = symbol();
+
+        let buy_prices = (0..3)
+            .map(|level| grid_level_price(&symbol, OrderSide::Buy, dec!(100.05), dec!(1), level))
+            .collect::<Vec<_>>();
+        let sell_prices = (0..3)
+            .map(|level| grid_level_price(&symbol, OrderSide::Sell, dec!(100.05), dec!(1), level))
+            .collect::<Vec<_>>();
+
+        assert_eq!(buy_prices, vec![dec!(99.0), dec!(98.0), dec!(97.0)]);
+        assert_eq!(sell_prices, vec![dec!(101.1), dec!(102.1), dec!(103.1)]);
+    }
+
+    #[test]
+    fn spacing_by_ticks() {
+        let symbol = symbol();
+
+        assert_eq!(
+            spacing_step(&symbol, GridSpacing::Ticks(5), dec!(100)),
+            dec!(0.5)
+        );
+        assert_eq!(
+            spacing_step(&symbol, GridSpacing::Price(dec!(2)), dec!(100)),
+            dec!(2)
+        );
+    }
+
+    #[test]
+    fn center_kept_inside_grid() {
+        assert_eq!(recenter(None, dec!(100), dec!(1), 3), dec!(100));
+        assert_eq!(
+            recenter(Some(dec!(100)), dec!(102.5), dec!(1), 3),
+            dec!(100)
+        );
+        assert_eq!(recenter(Some(dec!(100)), dec!(97), dec!(1), 3), dec!(100));
+        assert_eq!(
+            recenter(Some(dec!(100)), dec!(103.5), dec!(1), 3),
+            dec!(103.5)
+        );
+    }
+
+    #[test]
+    fn spacing_deserialization() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            spacing: GridSpacing,
+        }
+
+        let ticks: Wrapper = serde_json::from_str(r#"{"spacing":{"ticks":10}}"#).expect("in test");
+        let price: Wrapper =
+            serde_json::from_str(r#"{"spacing":{"price":"0.5"}}"#).expect("in test");
+
+        assert_eq!(ticks.spacing, GridSpacing::Ticks(10));
+        assert_eq!(price.spacing, GridSpacing::Price(dec!(0.5)));
+    }
+}
@@ -17,3 +17,6 @@
 )]
 
 pub mod example_strategy;
+pub mod grid_strategy;
+pub mod market_making_spread;
+pub mod price_improvement;
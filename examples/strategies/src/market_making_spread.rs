@@ -0,0 +1,147 @@
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_domain::market::MarketId;
+use mmb_domain::order::snapshot::Price;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SpreadVolatilitySettings {
+    /// Count of last mid-price returns used for volatility calculation
+    pub window_size: usize,
+    /// Multiplier for volatility part of the spread
+    pub multiplier: Decimal,
+}
+
+/// Spread that is wider than base spread when mid-price of order book is volatile:
+/// `spread = base_spread + multiplier * stddev(returns) * mid_price`
+/// where returns are relative changes of mid-price between last `window_size` updates.
+/// Without volatility settings it is always equal to base spread.
+pub struct MarketMakingSpread {
+    market_id: MarketId,
+    base_spread: Decimal,
+    volatility: Option<SpreadVolatilitySettings>,
+    mid_prices: VecDeque<Price>,
+}
+
+impl MarketMakingSpread {
+    pub fn new(
+        market_id: MarketId,
+        base_spread: Decimal,
+        volatility: Option<SpreadVolatilitySettings>,
+    ) -> Self {
+        MarketMakingSpread {
+            market_id,
+            base_spread,
+            volatility,
+            mid_prices: VecDeque::new(),
+        }
+    }
+
+    /// Take mid-price from current order book snapshot into the rolling window
+    pub fn update(&mut self, local_snapshots_service: &LocalSnapshotsService) {
+        let snapshot = match local_snapshots_service.get_snapshot(self.market_id) {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+
+        if let (Some((ask, _)), Some((bid, _))) = (snapshot.get_top_ask(), snapshot.get_top_bid()) {
+            self.add_mid_price((ask + bid) * dec!(0.5));
+        }
+    }
+
+    pub fn add_mid_price(&mut self, mid_price: Price) {
+        let window_size = match &self.volatility {
+            Some(volatility) => volatility.window_size,
+            None => return,
+        };
+
+        self.mid_prices.push_back(mid_price);
+        // window_size returns are calculated from window_size + 1 prices
+        while self.mid_prices.len() > window_size + 1 {
+            let _ = self.mid_prices.pop_front();
+        }
+    }
+
+    pub fn dynamic_spread(&self) -> Decimal {
+        let (volatility, last_mid_price) = match (&self.volatility, self.mid_prices.back()) {
+            (Some(volatility), Some(last_mid_price)) => (volatility, *last_mid_price),
+            _ => return self.base_spread,
+        };
+
+        match self.returns_stddev() {
+            Some(stddev) => self.base_spread + volatility.multiplier * stddev * last_mid_price,
+            None => self.base_spread,
+        }
+    }
+
+    fn returns_stddev(&self) -> Option<Decimal> {
+        let returns: Vec<Decimal> = self
+            .mid_prices
+            .iter()
+            .zip(self.mid_prices.iter().skip(1))
+            .filter(|(previous, _)| !previous.is_zero())
+            .map(|(previous, current)| current / previous - Decimal::ONE)
+            .collect();
+
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let count = Decimal::from(returns.len());
+        let mean = returns.iter().sum::<Decimal>() / count;
+        let variance = returns
+            .iter()
+            .map(|value| (value - mean) * (value - mean))
+            .sum::<Decimal>()
+            / count;
+
+        variance.sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::{CurrencyPair, ExchangeId};
+
+    fn market_id() -> MarketId {
+        MarketId::new(
+            ExchangeId::new("Binance"),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        )
+    }
+
+    #[test]
+    fn base_spread_without_volatility_settings() {
+        let mut spread = MarketMakingSpread::new(market_id(), dec!(5), None);
+        for mid_price in [dec!(100), dec!(110), dec!(90)] {
+            spread.add_mid_price(mid_price);
+        }
+
+        assert_eq!(spread.dynamic_spread(), dec!(5));
+    }
+
+    #[test]
+    fn spread_widens_with_volatility() {
+        let volatility = SpreadVolatilitySettings {
+            window_size: 2,
+            multiplier: dec!(2),
+        };
+        let mut spread = MarketMakingSpread::new(market_id(), dec!(5), Some(volatility));
+
+        spread.add_mid_price(dec!(100));
+        spread.add_mid_price(dec!(100));
+        spread.add_mid_price(dec!(100));
+        assert_eq!(spread.dynamic_spread(), dec!(5));
+
+        // returns are +10% and -10% so stddev is 0.1
+        spread.add_mid_price(dec!(110));
+        spread.add_mid_price(dec!(99));
+        assert_eq!(
+            spread.dynamic_spread(),
+            dec!(5) + dec!(2) * dec!(0.1) * dec!(99)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::order::snapshot::{OrderSide, Price};
+
+/// Price one tick inside the book for post-only order on the `side`:
+/// one tick above the best bid for buy and one tick below the best ask for sell.
+/// Improved price is clamped so it never crosses the best price of the opposite side,
+/// otherwise post-only order would be rejected. In that case (and when there is nowhere to improve)
+/// `best_price` is returned as is.
+pub fn improve_price_by_tick(
+    symbol: &Symbol,
+    side: OrderSide,
+    best_price: Price,
+    opposite_best_price: Option<Price>,
+) -> Price {
+    let tick = symbol.price_tick(best_price);
+    let improved_price = match side {
+        OrderSide::Buy => symbol.price_round(best_price + tick, Round::Floor),
+        OrderSide::Sell => symbol.price_round(best_price - tick, Round::Ceiling),
+    };
+
+    let is_crossed = match (side, opposite_best_price) {
+        (OrderSide::Buy, Some(best_ask)) => improved_price >= best_ask,
+        (OrderSide::Sell, Some(best_bid)) => improved_price <= best_bid,
+        (_, None) => false,
+    };
+
+    if is_crossed || improved_price <= Price::ZERO {
+        return best_price;
+    }
+
+    improved_price
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::exchanges::symbol::Precision;
+    use rust_decimal_macros::dec;
+
+    fn symbol(price_precision: Precision) -> Symbol {
+        Symbol::new(
+            false,
+            "BTC".into(),
+            "btc".into(),
+            "USDT".into(),
+            "usdt".into(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            "btc".into(),
+            None,
+            price_precision,
+            Precision::ByTick { tick: dec!(0.001) },
+        )
+    }
+
+    #[test]
+    fn improve_by_one_tick() {
+        let symbol = symbol(Precision::ByTick { tick: dec!(0.5) });
+
+        let buy_price = improve_price_by_tick(&symbol, OrderSide::Buy, dec!(100), Some(dec!(102)));
+        assert_eq!(buy_price, dec!(100.5));
+
+        let sell_price =
+            improve_price_by_tick(&symbol, OrderSide::Sell, dec!(102), Some(dec!(100)));
+        assert_eq!(sell_price, dec!(101.5));
+    }
+
+    #[test]
+    fn improve_by_mantissa_precision() {
+        let symbol = symbol(Precision::ByMantissa { precision: 3 });
+
+        // tick depends on the price: 0.1 for 12.3 and 0.01 for 9.87
+        let buy_price =
+            improve_price_by_tick(&symbol, OrderSide::Buy, dec!(12.3), Some(dec!(12.6)));
+        assert_eq!(buy_price, dec!(12.4));
+
+        let sell_price =
+            improve_price_by_tick(&symbol, OrderSide::Sell, dec!(9.87), Some(dec!(9.8)));
+        assert_eq!(sell_price, dec!(9.86));
+    }
+
+    #[test]
+    fn improve_up_to_tick_before_opposite_side() {
+        let symbol = symbol(Precision::ByTick { tick: dec!(0.5) });
+
+        // spread is 2 ticks, so improved price is one tick before the opposite side
+        let buy_price = improve_price_by_tick(&symbol, OrderSide::Buy, dec!(100), Some(dec!(101)));
+        assert_eq!(buy_price, dec!(100.5));
+
+        let sell_price =
+            improve_price_by_tick(&symbol, OrderSide::Sell, dec!(101), Some(dec!(100)));
+        assert_eq!(sell_price, dec!(100.5));
+    }
+
+    #[test]
+    fn no_improvement_if_spread_is_one_tick() {
+        let symbol = symbol(Precision::ByTick { tick: dec!(0.5) });
+
+        let buy_price =
+            improve_price_by_tick(&symbol, OrderSide::Buy, dec!(100), Some(dec!(100.5)));
+        assert_eq!(buy_price, dec!(100));
+
+        let sell_price =
+            improve_price_by_tick(&symbol, OrderSide::Sell, dec!(100.5), Some(dec!(100)));
+        assert_eq!(sell_price, dec!(100.5));
+    }
+
+    #[test]
+    fn improve_without_opposite_side() {
+        let symbol = symbol(Precision::ByTick { tick: dec!(0.5) });
+
+        let buy_price = improve_price_by_tick(&symbol, OrderSide::Buy, dec!(100), None);
+        assert_eq!(buy_price, dec!(100.5));
+    }
+
+    #[test]
+    fn no_improvement_to_non_positive_price() {
+        let symbol = symbol(Precision::ByTick { tick: dec!(0.5) });
+
+        let sell_price = improve_price_by_tick(&symbol, OrderSide::Sell, dec!(0.5), None);
+        assert_eq!(sell_price, dec!(0.5));
+    }
+}
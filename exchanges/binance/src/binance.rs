@@ -1,24 +1,30 @@
 use anyhow::{anyhow, bail, Context, Result};
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use dashmap::DashMap;
 use function_name::named;
 use hmac::digest::generic_array;
 use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
 use hyper::header::CONTENT_TYPE;
 use hyper::http::request::Builder;
 use hyper::Uri;
 use itertools::Itertools;
 use mmb_utils::time::{get_current_milliseconds, u64_to_date_time};
+use mmb_utils::value_to_decimal::value_to_decimal;
 use mmb_utils::DateTime;
 use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
 use serde_json::Value;
 use sha2::Sha256;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, UNIX_EPOCH};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
+use super::order_book_sequence::OrderBookSequences;
+use super::stream_subscriptions::StreamSubscriptions;
 use super::support::{
     BinanceDerivativeAccountInfo, BinanceOrderInfo, BinancePosition, BinanceSpotAccountInfo,
 };
@@ -38,7 +44,7 @@ use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
 use mmb_core::exchanges::traits::{ExchangeClientBuilder, ExchangeError, HandleMetricsCb};
 use mmb_core::exchanges::traits::{
     ExchangeClientBuilderResult, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb,
-    OrderCreatedCb, Support,
+    OrderCreatedCb, SendWebsocketMessageCb, Support,
 };
 use mmb_core::exchanges::{
     general::features::{ExchangeFeatures, OpenOrdersType},
@@ -46,8 +52,10 @@ use mmb_core::exchanges::{
 };
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use mmb_core::settings::ExchangeSettings;
+use mmb_domain::candle::{Candle, KlineInterval};
 use mmb_domain::events::{AllowedEventSourceType, EventSourceType};
 use mmb_domain::events::{ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::{Precision, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId};
 use mmb_domain::market::{ExchangeAccountId, SpecificCurrencyPair};
@@ -55,6 +63,7 @@ use mmb_domain::order::fill::OrderFillType;
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::*;
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, DerivativePosition};
 use mmb_utils::value_to_decimal::GetOrErr;
 use serde::{Deserialize, Serialize};
@@ -76,6 +85,7 @@ impl RestHeaders for RestHeadersBinance {
         builder: Builder,
         _uri: &Uri,
         _request_type: RequestType,
+        _body: Option<&Bytes>,
     ) -> Builder {
         match self.is_usd_m_futures {
             true => builder.header(CONTENT_TYPE, "application/x-www-form-urlencoded"),
@@ -130,13 +140,26 @@ impl ErrorHandler for ErrorHandlerBinance {
             | "Filter failure: PERCENT_PRICE"
             | "Quantity less than zero."
             | "Precision is over the maximum defined for this asset." => InvalidOrder,
+            "Order would immediately match and take." => WouldTake,
+            msg if msg.contains("the Post Only order will be rejected") => WouldTake,
             msg if msg.contains("Too many requests;") => RateLimit,
+            // -2022 REDUCE_ONLY_REJECT
+            _ if error.code == Some(-2022) => ReduceOnlyRejected,
+            // -1016 SERVICE_SHUTTING_DOWN
+            _ if error.code == Some(-1016) => Maintenance,
+            msg if msg.to_lowercase().contains("maintenance") => Maintenance,
+            // -1125 INVALID_LISTEN_KEY
+            // -2014 BAD_API_KEY_FMT
+            // -2015 REJECTED_MBX_KEY
+            _ if matches!(error.code, Some(-1125) | Some(-2014) | Some(-2015)) => Authentication,
             _ => Unknown,
         }
     }
 }
 
 const EMPTY_RESPONSE_IS_OK: bool = false;
+const DEFAULT_CLOCK_SKEW_WARNING_THRESHOLD_MS: u64 = 1_000;
+pub(super) const ORDER_BOOK_SNAPSHOT_LIMIT: u32 = 20;
 
 pub struct Binance {
     pub settings: ExchangeSettings,
@@ -147,6 +170,7 @@ pub struct Binance {
     pub handle_order_filled_callback: HandleOrderFilledCb,
     pub handle_trade_callback: HandleTradeCb,
     pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(super) websocket_message_callback: SendWebsocketMessageCb,
 
     pub unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
     pub specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
@@ -156,8 +180,12 @@ pub struct Binance {
     pub working_currencies_ids: RwLock<Vec<CurrencyId>>,
     pub(super) timeout_manager: Arc<TimeoutManager>,
 
-    // Currencies used for trading according to user settings
-    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    // Public streams of main websocket for currencies used for trading
+    pub(super) stream_subscriptions: StreamSubscriptions,
+    // Count of public stream messages skipped because they couldn't be parsed
+    pub(super) stream_parse_errors: AtomicU64,
+    // Difference between Binance server time and local time in milliseconds
+    pub(super) server_time_offset_ms: AtomicI64,
 
     pub(super) last_trade_ids: DashMap<CurrencyPair, TradeId>,
 
@@ -172,6 +200,14 @@ pub struct Binance {
 
     // NOTE: None when websocket is disconnected
     pub(super) listen_key: RwLock<Option<String>>,
+
+    pub(super) order_book_sequences: OrderBookSequences,
+    // Currency pairs with detected gap in order book updates that need a fresh snapshot
+    pub(super) order_book_resync_sender: mpsc::UnboundedSender<CurrencyPair>,
+    pub(super) order_book_resync_receiver: Mutex<Option<mpsc::UnboundedReceiver<CurrencyPair>>>,
+    // Requests to re-establish user data stream with a new listen key after auth failure
+    pub(super) user_data_reauth_sender: mpsc::UnboundedSender<()>,
+    pub(super) user_data_reauth_receiver: Mutex<Option<mpsc::UnboundedReceiver<()>>>,
 }
 
 impl Binance {
@@ -212,6 +248,8 @@ impl Binance {
 
         let hosts = Self::make_hosts(settings.is_margin_trading);
         let exchange_account_id = settings.exchange_account_id;
+        let (order_book_resync_sender, order_book_resync_receiver) = mpsc::unbounded_channel();
+        let (user_data_reauth_sender, user_data_reauth_receiver) = mpsc::unbounded_channel();
 
         Self {
             id,
@@ -220,11 +258,14 @@ impl Binance {
             handle_order_filled_callback: Box::new(|_| {}),
             handle_trade_callback: Box::new(|_, _| {}),
             handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
             unified_to_specific: Default::default(),
             specific_to_unified: Default::default(),
             supported_currencies: Default::default(),
             working_currencies_ids: Default::default(),
-            traded_specific_currencies: Default::default(),
+            stream_subscriptions: Default::default(),
+            stream_parse_errors: Default::default(),
+            server_time_offset_ms: Default::default(),
             last_trade_ids: Default::default(),
             subscribe_to_market_data: settings.subscribe_to_market_data,
             rest_client: RestClient::new(
@@ -237,7 +278,13 @@ impl Binance {
                     api_key: settings.api_key.clone(),
                     is_usd_m_futures: settings.is_margin_trading,
                 },
-            ),
+            )
+            .with_bodies_logging(settings.log_rest_bodies)
+            .with_rate_limit_retries(
+                settings.rest_rate_limit_retries,
+                lifetime_manager.stop_token(),
+            )
+            .with_transient_error_retries(settings.rest_transient_error_retries),
             timeout_manager,
             is_reducing_market_data,
             settings,
@@ -245,6 +292,11 @@ impl Binance {
             events_channel,
             lifetime_manager,
             listen_key: Default::default(),
+            order_book_sequences: Default::default(),
+            order_book_resync_sender,
+            order_book_resync_receiver: Mutex::new(Some(order_book_resync_receiver)),
+            user_data_reauth_sender,
+            user_data_reauth_receiver: Mutex::new(Some(user_data_reauth_receiver)),
         }
     }
 
@@ -305,13 +357,6 @@ impl Binance {
         todo!("reconnect")
     }
 
-    pub(super) fn get_stream_name(
-        specific_currency_pair: &SpecificCurrencyPair,
-        channel: &str,
-    ) -> String {
-        format!("{specific_currency_pair}@{channel}")
-    }
-
     fn _is_websocket_reconnecting(&self) -> bool {
         todo!("is_websocket_reconnecting")
     }
@@ -339,13 +384,28 @@ impl Binance {
         builder.add_kv("signature", hexer);
     }
 
-    pub(super) fn add_authentification(&self, builder: &mut UriBuilder) {
-        let time_stamp = get_current_milliseconds();
+    fn add_authentification(&self, builder: &mut UriBuilder) {
+        let time_stamp =
+            get_current_milliseconds() + self.server_time_offset_ms.load(Ordering::Relaxed);
         builder.add_kv("timestamp", time_stamp);
 
         self.write_signature_to_builder(builder);
     }
 
+    /// Signature contains timestamp, so signed uri should be built again for every attempt of request
+    pub(super) fn build_signed_uri(&self, builder: &UriBuilder) -> Uri {
+        let mut builder = builder.clone();
+        self.add_authentification(&mut builder);
+        builder.build_uri(self.hosts.rest_uri_host(), true)
+    }
+
+    /// Same as `build_signed_uri`, but signed query is returned separately to be sent as request body
+    pub(super) fn build_signed_uri_and_query(&self, builder: &UriBuilder) -> (Uri, Bytes) {
+        let mut builder = builder.clone();
+        self.add_authentification(&mut builder);
+        builder.build_uri_and_query(self.hosts.rest_uri_host(), false)
+    }
+
     pub(super) fn get_unified_currency_pair(
         &self,
         currency_pair: &SpecificCurrencyPair,
@@ -431,7 +491,7 @@ impl Binance {
                 // We get notification of rejected orders from the rest responses
             }
             "EXPIRED" => match time_in_force {
-                "GTX" => {
+                "GTX" | "IOC" | "FOK" => {
                     (self.order_cancelled_callback)(
                         client_order_id.into(),
                         exchange_order_id.into(),
@@ -567,10 +627,12 @@ impl Binance {
         &self,
         builder: UriBuilder,
     ) -> Result<RestResponse, ExchangeError> {
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
-
         self.rest_client
-            .get(uri, function_name!(), "".to_string())
+            .get_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                "".to_string(),
+            )
             .await
     }
 
@@ -586,12 +648,16 @@ impl Binance {
         let mut builder = UriBuilder::from_path(path);
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("origClientOrderId", &client_order_id);
-        self.add_authentification(&mut builder);
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
         let log_args = format!("order {client_order_id}");
 
-        self.rest_client.get(uri, function_name!(), log_args).await
+        self.rest_client
+            .get_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                log_args,
+            )
+            .await
     }
 
     pub(super) fn parse_order_info(&self, response: &RestResponse) -> OrderInfo {
@@ -606,8 +672,7 @@ impl Binance {
     }
 
     pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path(self.get_open_order_path());
-        self.add_authentification(&mut builder);
+        let builder = UriBuilder::from_path(self.get_open_order_path());
 
         self.request_open_orders_by_http_header(builder).await
     }
@@ -620,7 +685,6 @@ impl Binance {
 
         let mut builder = UriBuilder::from_path(self.get_open_order_path());
         builder.add_kv("symbol", specific_currency_pair);
-        self.add_authentification(&mut builder);
 
         self.request_open_orders_by_http_header(builder).await
     }
@@ -659,25 +723,26 @@ impl Binance {
             None => builder.add_kv("type", "MARKET"),
         }
 
-        self.add_authentification(&mut builder);
-
-        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
-
         let log_args = format!("Close position response for {position:?} {price:?}");
         self.rest_client
-            .post(uri, Some(query), function_name!(), log_args)
+            .post_signed(
+                || self.build_signed_uri_and_query(&builder),
+                function_name!(),
+                log_args,
+            )
             .await
     }
 
     #[named]
     pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path("/fapi/v2/positionRisk");
-        self.add_authentification(&mut builder);
-
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let builder = UriBuilder::from_path("/fapi/v2/positionRisk");
 
         self.rest_client
-            .get(uri, function_name!(), "".to_string())
+            .get_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                "".to_string(),
+            )
             .await
     }
 
@@ -726,12 +791,14 @@ impl Binance {
     #[named]
     pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
         let path = self.get_uri_path("/fapi/v2/account", "/api/v3/account");
-        let mut builder = UriBuilder::from_path(path);
-        self.add_authentification(&mut builder);
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let builder = UriBuilder::from_path(path);
 
         self.rest_client
-            .get(uri, function_name!(), "".to_string())
+            .get_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                "".to_string(),
+            )
             .await
     }
 
@@ -787,13 +854,14 @@ impl Binance {
         let mut builder = UriBuilder::from_path(path);
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("orderId", exchange_order_id);
-        self.add_authentification(&mut builder);
-
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
         let log_args = format!("Cancel order for {}", order.client_order_id());
         self.rest_client
-            .delete(uri, function_name!(), log_args)
+            .delete_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                log_args,
+            )
             .await
     }
 
@@ -814,12 +882,13 @@ impl Binance {
             );
         }
         builder.add_kv("symbol", specific_currency_pair);
-        self.add_authentification(&mut builder);
-
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
         self.rest_client
-            .get(uri, function_name!(), "".to_string())
+            .get_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                "".to_string(),
+            )
             .await
     }
 
@@ -901,19 +970,24 @@ impl Binance {
         builder.add_kv("quantity", header.amount);
         builder.add_kv("newClientOrderId", &header.client_order_id);
 
+        if header.reduce_only {
+            if !is_margin_trading {
+                return Err(ExchangeError::new(
+                    ExchangeErrorType::InvalidOrder,
+                    "Reduce-only orders are supported only for futures".to_owned(),
+                    None,
+                ));
+            }
+            builder.add_kv("reduceOnly", "true");
+        }
+
         match (is_margin_trading, &header.options) {
             (false, OrderOptions::User(user_order)) => match user_order {
                 UserOrder::Limit {
                     price,
                     execution_type,
                 } => {
-                    match execution_type {
-                        OrderExecutionType::None => {
-                            builder.add_kv("type", "LIMIT");
-                            builder.add_kv("timeInForce", "GTC");
-                        }
-                        OrderExecutionType::MakerOnly => builder.add_kv("type", "LIMIT_MAKER"),
-                    }
+                    add_spot_limit_order_type(&mut builder, *execution_type, header.time_in_force);
                     builder.add_kv("price", price);
                 }
                 UserOrder::Market => builder.add_kv("type", "MARKET"),
@@ -942,10 +1016,11 @@ impl Binance {
                 } => {
                     builder.add_kv("type", "LIMIT");
                     builder.add_kv("price", price);
-                    match *execution_type == OrderExecutionType::MakerOnly {
-                        true => builder.add_kv("timeInForce", "GTX"),
-                        false => builder.add_kv("timeInForce", "GTC"),
-                    }
+                    let time_in_force = match execution_type {
+                        OrderExecutionType::MakerOnly => TimeInForce::GoodTillCrossing,
+                        OrderExecutionType::None => header.time_in_force,
+                    };
+                    builder.add_kv("timeInForce", get_server_time_in_force(time_in_force));
                 }
                 UserOrder::Market => builder.add_kv("type", "MARKET"),
                 UserOrder::StopLoss { stop_price } => {
@@ -960,16 +1035,105 @@ impl Binance {
             _ => return Err(ExchangeError::unknown("Unexpected order type")),
         }
 
-        self.add_authentification(&mut builder);
+        let log_args = format!("Create order for {header:?}");
+        self.rest_client
+            .post_signed(
+                || self.build_signed_uri_and_query(&builder),
+                function_name!(),
+                log_args,
+            )
+            .await
+    }
 
-        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+    /// Cancel order and place a new one with the same client order id in a single request.
+    /// Available only for spot market
+    #[named]
+    pub(super) async fn request_cancel_replace_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        execution_type: OrderExecutionType,
+        new_price: Price,
+        new_amount: Amount,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
 
-        let log_args = format!("Create order for {header:?}");
+        let mut builder = UriBuilder::from_path("/api/v3/order/cancelReplace");
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("side", get_server_order_side(header.side));
+        // new order isn't placed if cancellation failed, e.g. because the order was already filled
+        builder.add_kv("cancelReplaceMode", "STOP_ON_FAILURE");
+        builder.add_kv("cancelOrderId", exchange_order_id);
+        builder.add_kv("newClientOrderId", &header.client_order_id);
+        builder.add_kv("quantity", new_amount);
+        add_spot_limit_order_type(&mut builder, execution_type, header.time_in_force);
+        builder.add_kv("price", new_price);
+
+        let log_args = format!(
+            "Cancel-replace order {} {exchange_order_id} with price {new_price} and amount {new_amount}",
+            header.client_order_id
+        );
         self.rest_client
-            .post(uri, Some(query), function_name!(), log_args)
+            .post_signed(
+                || self.build_signed_uri_and_query(&builder),
+                function_name!(),
+                log_args,
+            )
             .await
     }
 
+    pub(super) fn parse_cancel_replace_order(
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct NewOrderResponse {
+            order_id: u64,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CancelReplaceResponse {
+            new_order_result: String,
+            new_order_response: Option<NewOrderResponse>,
+        }
+
+        let deserialized: CancelReplaceResponse =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse cancel-replace response: {err:?}"))
+            })?;
+
+        match (
+            deserialized.new_order_result.as_str(),
+            deserialized.new_order_response,
+        ) {
+            ("SUCCESS", Some(new_order)) => Ok(new_order.order_id.into()),
+            (new_order_result, _) => Err(ExchangeError::new(
+                ExchangeErrorType::InvalidOrder,
+                format!("New order wasn't placed by cancel-replace: {new_order_result}"),
+                None,
+            )),
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        limit: u32,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+        let path = self.get_uri_path("/fapi/v1/depth", "/api/v3/depth");
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("limit", limit);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Order book snapshot for {currency_pair}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
     #[named]
     pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
         let path = self.get_uri_path("/fapi/v1/exchangeInfo", "/api/v3/exchangeInfo");
@@ -991,7 +1155,7 @@ impl Binance {
 
         let mut supported_symbols = Vec::new();
         for symbol in symbols {
-            if Binance::is_unsupported_symbol(symbol) {
+            if self.is_unsupported_symbol(symbol) {
                 continue;
             }
 
@@ -1008,7 +1172,11 @@ impl Binance {
                 .get_as_str("symbol")
                 .expect("Unable to get specific currency pair");
             let specific_currency_pair = specific_currency_pair_id.as_str().into();
-            let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+            let expiry = Binance::get_contract_expiry(&specific_currency_pair_id);
+            let unified_currency_pair = match expiry {
+                Some(expiry) => CurrencyPair::from_derivative(base, quote, expiry),
+                None => CurrencyPair::from_codes(base, quote),
+            };
             self.unified_to_specific
                 .write()
                 .insert(unified_currency_pair, specific_currency_pair);
@@ -1091,7 +1259,8 @@ impl Binance {
                 balance_currency_code,
                 price_precision,
                 amount_precision,
-            );
+            )
+            .with_expiry(expiry);
 
             supported_symbols.push(Arc::new(symbol))
         }
@@ -1099,13 +1268,23 @@ impl Binance {
         Ok(supported_symbols)
     }
 
-    fn is_unsupported_symbol(symbol: &Value) -> bool {
+    fn is_unsupported_symbol(&self, symbol: &Value) -> bool {
         let code = &symbol
             .get_as_str("symbol")
             .expect("Unable to get symbol code from Binance");
 
         // Binance adds "_<NUMBERS>" to old symbol's code
-        code.contains('_') || symbol["status"] != "TRADING"
+        // Futures delivery contracts have "_<yymmdd>" suffix in code, e.g. `BTCUSDT_221230`
+        let is_delivery_contract =
+            self.settings.is_margin_trading && Binance::get_contract_expiry(code).is_some();
+
+        (code.contains('_') && !is_delivery_contract) || symbol["status"] != "TRADING"
+    }
+
+    /// Expiry date from code of futures delivery contract, e.g. `BTCUSDT_221230`
+    fn get_contract_expiry(code: &str) -> Option<NaiveDate> {
+        let (_, expiry) = code.split_once('_')?;
+        NaiveDate::parse_from_str(expiry, "%y%m%d").ok()
     }
 
     pub(super) fn get_event_time(data: &Value) -> Result<DateTime> {
@@ -1127,6 +1306,33 @@ impl Binance {
             .await
     }
 
+    /// Measure offset of local clock from server time and apply it to timestamps of signed requests
+    pub(super) async fn sync_server_time(&self) -> Result<()> {
+        let local_send_time = get_current_milliseconds();
+        let response = self
+            .request_get_server_time()
+            .await
+            .map_err(|err| anyhow!("Get server time request failed: {err:?}"))?;
+        let local_receive_time = get_current_milliseconds();
+        let server_time = self.parse_get_server_time(&response)?;
+
+        let offset = calc_server_time_offset(local_send_time, local_receive_time, server_time);
+        self.server_time_offset_ms.store(offset, Ordering::Relaxed);
+
+        let threshold = self
+            .settings
+            .clock_skew_warning_threshold_ms
+            .unwrap_or(DEFAULT_CLOCK_SKEW_WARNING_THRESHOLD_MS);
+        if offset.unsigned_abs() > threshold {
+            log::warn!(
+                "Local clock differs from server time of {} by {offset} ms which exceeds {threshold} ms",
+                self.id
+            );
+        }
+
+        Ok(())
+    }
+
     pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
         #[derive(Deserialize)]
         struct ServerTime {
@@ -1138,6 +1344,135 @@ impl Binance {
             .context("Failed to parse Binance get time response")?;
         Ok(server_time_struct.time)
     }
+
+    #[named]
+    pub(super) async fn request_klines(
+        &self,
+        currency_pair: CurrencyPair,
+        interval: KlineInterval,
+        limit: u32,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+
+        let path = self.get_uri_path("/fapi/v1/klines", "/api/v3/klines");
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("interval", get_server_kline_interval(interval));
+        builder.add_kv("limit", limit);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_trading_fees(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+
+        let path = self.get_uri_path("/fapi/v1/commissionRate", "/sapi/v1/asset/tradeFee");
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("symbol", specific_currency_pair);
+
+        self.rest_client
+            .get_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                "".to_string(),
+            )
+            .await
+    }
+
+    /// Futures API returns single object with fee rates, spot API returns array of objects by symbols
+    pub(super) fn parse_trading_fees(&self, response: &RestResponse) -> Result<TradingFees> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct FuturesTradingFees {
+            maker_commission_rate: Decimal,
+            taker_commission_rate: Decimal,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SpotTradingFees {
+            maker_commission: Decimal,
+            taker_commission: Decimal,
+        }
+
+        if self.settings.is_margin_trading {
+            let fees: FuturesTradingFees = serde_json::from_str(&response.content)
+                .context("Failed to parse Binance commission rate response")?;
+            return Ok(TradingFees::new(
+                fees.maker_commission_rate,
+                fees.taker_commission_rate,
+            ));
+        }
+
+        let fees: Vec<SpotTradingFees> = serde_json::from_str(&response.content)
+            .context("Failed to parse Binance trade fee response")?;
+        let fees = fees
+            .first()
+            .with_context(|| format!("Empty Binance trade fee response: {}", response.content))?;
+        Ok(TradingFees::new(
+            fees.maker_commission,
+            fees.taker_commission,
+        ))
+    }
+
+    pub(super) fn parse_order_book_snapshot(response: &RestResponse) -> Result<OrderBookData> {
+        #[derive(Deserialize)]
+        struct Depth {
+            asks: Vec<(Price, Amount)>,
+            bids: Vec<(Price, Amount)>,
+        }
+
+        let depth: Depth = serde_json::from_str(&response.content)
+            .context("Failed to parse Binance depth response")?;
+
+        Ok(OrderBookData::new(
+            depth.asks.into_iter().collect(),
+            depth.bids.into_iter().collect(),
+        ))
+    }
+
+    pub(super) fn parse_klines(response: &RestResponse) -> Result<Vec<Candle>> {
+        let klines: Vec<Vec<Value>> = serde_json::from_str(&response.content)
+            .context("Failed to parse Binance klines response")?;
+
+        klines
+            .iter()
+            .map(|kline| {
+                let field = |index: usize| {
+                    kline.get(index).with_context(|| {
+                        format!("Unable to get field {index} from kline {kline:?}")
+                    })
+                };
+                let decimal_field = |index: usize| {
+                    field(index)?
+                        .as_str()
+                        .with_context(|| format!("Field {index} of kline {kline:?} isn't string"))
+                        .and_then(value_to_decimal)
+                };
+
+                let open_time = field(0)?
+                    .as_u64()
+                    .with_context(|| format!("Unable to get open time from kline {kline:?}"))?;
+
+                Ok(Candle {
+                    open_time: u64_to_date_time(open_time),
+                    open: decimal_field(1)?,
+                    high: decimal_field(2)?,
+                    low: decimal_field(3)?,
+                    close: decimal_field(4)?,
+                    volume: decimal_field(5)?,
+                })
+            })
+            .collect()
+    }
 }
 
 pub(super) fn get_server_order_side(side: OrderSide) -> &'static str {
@@ -1147,6 +1482,51 @@ pub(super) fn get_server_order_side(side: OrderSide) -> &'static str {
     }
 }
 
+/// Server time is considered as taken in the middle of request round trip
+fn calc_server_time_offset(local_send_time: i64, local_receive_time: i64, server_time: i64) -> i64 {
+    let local_time = local_send_time + (local_receive_time - local_send_time) / 2;
+    server_time - local_time
+}
+
+fn add_spot_limit_order_type(
+    builder: &mut UriBuilder,
+    execution_type: OrderExecutionType,
+    time_in_force: TimeInForce,
+) {
+    match (execution_type, time_in_force) {
+        (OrderExecutionType::MakerOnly, _)
+        | (OrderExecutionType::None, TimeInForce::GoodTillCrossing) => {
+            builder.add_kv("type", "LIMIT_MAKER")
+        }
+        (OrderExecutionType::None, time_in_force) => {
+            builder.add_kv("type", "LIMIT");
+            builder.add_kv("timeInForce", get_server_time_in_force(time_in_force));
+        }
+    }
+}
+
+pub(super) fn get_server_time_in_force(time_in_force: TimeInForce) -> &'static str {
+    match time_in_force {
+        TimeInForce::GoodTillCancelled => "GTC",
+        TimeInForce::ImmediateOrCancel => "IOC",
+        TimeInForce::FillOrKill => "FOK",
+        TimeInForce::GoodTillCrossing => "GTX",
+    }
+}
+
+pub(super) fn get_server_kline_interval(interval: KlineInterval) -> &'static str {
+    match interval {
+        KlineInterval::OneMinute => "1m",
+        KlineInterval::FiveMinutes => "5m",
+        KlineInterval::FifteenMinutes => "15m",
+        KlineInterval::ThirtyMinutes => "30m",
+        KlineInterval::OneHour => "1h",
+        KlineInterval::FourHours => "4h",
+        KlineInterval::OneDay => "1d",
+        KlineInterval::OneWeek => "1w",
+    }
+}
+
 pub(super) fn get_local_order_side(side: &str) -> OrderSide {
     match side {
         "BUY" => OrderSide::Buy,
@@ -1195,7 +1575,10 @@ impl ExchangeClientBuilder for BinanceBuilder {
                     ..OrderFeatures::default()
                 },
                 OrderTradeOption::default(),
-                WebSocketOptions::default(),
+                WebSocketOptions {
+                    supports_ping_pong: true,
+                    ..WebSocketOptions::default()
+                },
                 EMPTY_RESPONSE_IS_OK,
                 AllowedEventSourceType::All,
                 AllowedEventSourceType::All,
@@ -1220,6 +1603,7 @@ mod tests {
     use mmb_core::lifecycle::launcher::EngineBuildConfig;
     use mmb_utils::cancellation_token::CancellationToken;
     use mmb_utils::hashmap;
+    use rust_decimal_macros::dec;
 
     pub(crate) fn get_timeout_manager(
         exchange_account_id: ExchangeAccountId,
@@ -1237,6 +1621,14 @@ mod tests {
         TimeoutManager::new(hashmap![exchange_account_id => request_timeout_manager])
     }
 
+    #[test]
+    fn server_time_offset_is_measured_from_round_trip_middle() {
+        // local clock is 500 ms behind server, round trip takes 100 ms
+        assert_eq!(calc_server_time_offset(1_000, 1_100, 1_550), 500);
+        // local clock is ahead of server
+        assert_eq!(calc_server_time_offset(1_000, 1_100, 800), -250);
+    }
+
     #[test]
     fn generate_signature() {
         // All values and strings gotten from binanсe API example
@@ -1279,4 +1671,259 @@ mod tests {
 
         assert_eq!(signature_value, expected);
     }
+
+    #[test]
+    fn clarify_post_only_rejection_as_would_take() {
+        let error_handler = ErrorHandlerBinance;
+
+        let spot_error = ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            "Order would immediately match and take.".to_owned(),
+            Some(-2010),
+        );
+        assert_eq!(
+            error_handler.clarify_error_type(&spot_error),
+            ExchangeErrorType::WouldTake
+        );
+
+        let futures_error = ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            "Due to the order could not be executed as maker, the Post Only order will be rejected. The order will not be recorded in the order history".to_owned(),
+            Some(-5022),
+        );
+        assert_eq!(
+            error_handler.clarify_error_type(&futures_error),
+            ExchangeErrorType::WouldTake
+        );
+    }
+
+    #[test]
+    fn clarify_maintenance() {
+        let error_handler = ErrorHandlerBinance;
+
+        let maintenance_error = ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            "System is under maintenance.".to_owned(),
+            Some(1),
+        );
+        assert_eq!(
+            error_handler.clarify_error_type(&maintenance_error),
+            ExchangeErrorType::Maintenance
+        );
+
+        let shutting_down_error = ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            "This service is no longer available.".to_owned(),
+            Some(-1016),
+        );
+        assert_eq!(
+            error_handler.clarify_error_type(&shutting_down_error),
+            ExchangeErrorType::Maintenance
+        );
+    }
+
+    #[test]
+    fn clarify_reduce_only_rejection() {
+        let error_handler = ErrorHandlerBinance;
+
+        let error = ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            "ReduceOnly Order is rejected.".to_owned(),
+            Some(-2022),
+        );
+        assert_eq!(
+            error_handler.clarify_error_type(&error),
+            ExchangeErrorType::ReduceOnlyRejected
+        );
+    }
+
+    #[test]
+    fn clarify_expired_listen_key() {
+        let error_handler = ErrorHandlerBinance;
+
+        let error = ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            "This listenKey does not exist.".to_owned(),
+            Some(-1125),
+        );
+        assert_eq!(
+            error_handler.clarify_error_type(&error),
+            ExchangeErrorType::Authentication
+        );
+    }
+
+    #[test]
+    fn parse_trading_fees() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let binance = |is_margin_trading| {
+            let settings = ExchangeSettings::new_short(
+                exchange_account_id,
+                "api_key".into(),
+                "secret_key".into(),
+                is_margin_trading,
+            );
+            let (tx, _) = broadcast::channel(10);
+            Binance::new(
+                exchange_account_id,
+                settings,
+                tx,
+                AppLifetimeManager::new(CancellationToken::default()),
+                get_timeout_manager(exchange_account_id),
+                false,
+            )
+        };
+        let response = |content: &str| RestResponse {
+            status: hyper::StatusCode::OK,
+            content: content.to_owned(),
+        };
+
+        let spot_fees = binance(false)
+            .parse_trading_fees(&response(
+                r#"[{"symbol": "BTCUSDT", "makerCommission": "0.001", "takerCommission": "0.0015"}]"#,
+            ))
+            .expect("in test");
+        assert_eq!(spot_fees, TradingFees::new(dec!(0.001), dec!(0.0015)));
+
+        let futures_fees = binance(true)
+            .parse_trading_fees(&response(
+                r#"{"symbol": "BTCUSDT", "makerCommissionRate": "0.0002", "takerCommissionRate": "0.0004"}"#,
+            ))
+            .expect("in test");
+        assert_eq!(futures_fees, TradingFees::new(dec!(0.0002), dec!(0.0004)));
+    }
+
+    #[test]
+    fn parse_futures_delivery_symbols() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(
+            exchange_account_id,
+            "api_key".into(),
+            "secret_key".into(),
+            true,
+        );
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+
+        let symbol = |code: &str, contract_type: &str| {
+            format!(
+                r#"{{
+                    "symbol": "{code}", "pair": "BTCUSDT", "contractType": "{contract_type}", "status": "TRADING",
+                    "baseAsset": "BTC", "quoteAsset": "USDT",
+                    "filters": [
+                        {{"filterType": "PRICE_FILTER", "minPrice": "556.80", "maxPrice": "4529764", "tickSize": "0.10"}},
+                        {{"filterType": "LOT_SIZE", "minQty": "0.001", "maxQty": "1000", "stepSize": "0.001"}},
+                        {{"filterType": "MIN_NOTIONAL", "notional": "5"}}
+                    ]
+                }}"#
+            )
+        };
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: format!(
+                r#"{{"symbols": [{}, {}]}}"#,
+                symbol("BTCUSDT", "PERPETUAL"),
+                symbol("BTCUSDT_221230", "CURRENT_QUARTER"),
+            ),
+        };
+
+        let symbols = binance.parse_all_symbols(&response).expect("in test");
+
+        let expiry = NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test");
+        let delivery_currency_pair =
+            CurrencyPair::from_derivative("BTC".into(), "USDT".into(), expiry);
+        assert_eq!(
+            symbols.iter().map(|x| x.currency_pair()).collect_vec(),
+            vec![
+                CurrencyPair::from_codes("BTC".into(), "USDT".into()),
+                delivery_currency_pair
+            ]
+        );
+        assert_eq!(symbols[1].expiry, Some(expiry));
+        assert_eq!(
+            binance
+                .get_unified_currency_pair(&"BTCUSDT_221230".into())
+                .expect("in test"),
+            delivery_currency_pair
+        );
+    }
+
+    #[test]
+    fn parse_order_book_snapshot() {
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: r#"{
+                "lastUpdateId": 1027024,
+                "bids": [["4.00000000", "431.00000000"], ["3.90000000", "12.00000000"]],
+                "asks": [["4.00000200", "12.00000000"]]
+            }"#
+            .to_owned(),
+        };
+
+        let order_book = Binance::parse_order_book_snapshot(&response).expect("in test");
+
+        assert_eq!(order_book.asks.get(&dec!(4.000002)), Some(&dec!(12)));
+        assert_eq!(order_book.bids.len(), 2);
+        assert_eq!(order_book.bids.get(&dec!(3.9)), Some(&dec!(12)));
+    }
+
+    #[test]
+    fn parse_cancel_replace_order() {
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: r#"{
+                "cancelResult": "SUCCESS",
+                "newOrderResult": "SUCCESS",
+                "cancelResponse": {"symbol": "BTCUSDT", "orderId": 9, "status": "CANCELED"},
+                "newOrderResponse": {"symbol": "BTCUSDT", "orderId": 10, "status": "NEW"}
+            }"#
+            .to_owned(),
+        };
+
+        let exchange_order_id = Binance::parse_cancel_replace_order(&response).expect("in test");
+
+        assert_eq!(exchange_order_id, "10".into());
+    }
+
+    #[test]
+    fn parse_klines() {
+        let response = RestResponse {
+            status: hyper::StatusCode::OK,
+            content: r#"[
+                [1499040000000, "0.01634790", "0.80000000", "0.01575800", "0.01577100", "148976.11427815", 1499644799999, "2434.19055334", 308, "1756.87402397", "28.46694368", "0"],
+                [1499644800000, "0.01577100", "0.01600000", "0.01500000", "0.01510000", "1.5e-3", 1500249599999, "0.0000226", 2, "0", "0", "0"]
+            ]"#
+            .to_owned(),
+        };
+
+        let candles = Binance::parse_klines(&response).expect("in test");
+
+        assert_eq!(
+            candles,
+            vec![
+                Candle {
+                    open_time: u64_to_date_time(1499040000000),
+                    open: dec!(0.01634790),
+                    high: dec!(0.80000000),
+                    low: dec!(0.01575800),
+                    close: dec!(0.01577100),
+                    volume: dec!(148976.11427815),
+                },
+                Candle {
+                    open_time: u64_to_date_time(1499644800000),
+                    open: dec!(0.01577100),
+                    high: dec!(0.01600000),
+                    low: dec!(0.01500000),
+                    close: dec!(0.01510000),
+                    volume: dec!(0.0015),
+                },
+            ]
+        );
+    }
 }
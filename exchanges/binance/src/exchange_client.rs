@@ -11,12 +11,15 @@ use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::general::request_type::RequestType;
 use mmb_core::exchanges::rest_client::UriBuilder;
 use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError, Support};
+use mmb_domain::candle::{Candle, KlineInterval};
 use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::market::CurrencyPair;
+use mmb_domain::market::{CurrencyPair, ExchangeErrorType};
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::Price;
 use mmb_domain::order::snapshot::*;
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 use std::sync::Arc;
@@ -46,18 +49,48 @@ impl ExchangeClient for Binance {
         }
     }
 
+    async fn amend_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_price: Price,
+        new_amount: Amount,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        // Binance supports cancel-replace only for spot limit orders
+        if self.settings.is_margin_trading {
+            return None;
+        }
+
+        let execution_type = match order.header().options {
+            OrderOptions::User(UserOrder::Limit { execution_type, .. }) => execution_type,
+            _ => return None,
+        };
+
+        let response = self
+            .request_cancel_replace_order(
+                order,
+                exchange_order_id,
+                execution_type,
+                new_price,
+                new_amount,
+            )
+            .await;
+        Some(response.and_then(|response| Binance::parse_cancel_replace_order(&response)))
+    }
+
     #[named]
     async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
         let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
 
         let mut builder = UriBuilder::from_path("/api/v3/openOrders");
         builder.add_kv("symbol", specific_currency_pair);
-        self.add_authentification(&mut builder);
-
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
         self.rest_client
-            .delete(uri, function_name!(), String::new())
+            .delete_signed(
+                || self.build_signed_uri(&builder),
+                function_name!(),
+                String::new(),
+            )
             .await?;
 
         Ok(())
@@ -154,12 +187,48 @@ impl ExchangeClient for Binance {
         self.parse_all_symbols(response)
     }
 
+    async fn get_klines(
+        &self,
+        currency_pair: CurrencyPair,
+        interval: KlineInterval,
+        limit: u32,
+    ) -> Result<Vec<Candle>> {
+        let response = self
+            .request_klines(currency_pair, interval, limit)
+            .await
+            .map_err(|err| anyhow!("Get klines request failed: {err:?}"))?;
+
+        Self::parse_klines(&response)
+    }
+
     async fn get_server_time(&self) -> Option<Result<i64>> {
         match self.request_get_server_time().await {
             Ok(response) => Some(self.parse_get_server_time(&response)),
             Err(err) => Some(Err(anyhow!("Get server time request failed: {err:?}"))),
         }
     }
+
+    async fn get_trading_fees(&self, currency_pair: CurrencyPair) -> Result<TradingFees> {
+        let response = self
+            .request_trading_fees(currency_pair)
+            .await
+            .map_err(|err| anyhow!("Get trading fees request failed: {err:?}"))?;
+
+        self.parse_trading_fees(&response)
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: u32,
+    ) -> Result<OrderBookData> {
+        let response = self
+            .request_order_book_snapshot(currency_pair, depth)
+            .await
+            .map_err(|err| anyhow!("Get order book snapshot request failed: {err:?}"))?;
+
+        Self::parse_order_book_snapshot(&response)
+    }
 }
 
 impl Binance {
@@ -225,8 +294,22 @@ impl Binance {
         };
 
         match self.request_update_listen_key(&listen_key).await {
-            Ok(_) => log::trace!("Updated listenKey"),
-            Err(err) => log::warn!("Failed to update listenKey {err}"),
+            Ok(_) => log::info!("Keepalive of listenKey succeeded on {exchange_account_id}"),
+            Err(err) if err.error_type == ExchangeErrorType::Authentication => {
+                log::warn!("Failed to update listenKey on {exchange_account_id}: {err}");
+                self.request_user_data_reauth();
+            }
+            Err(err) => log::warn!("Failed to update listenKey on {exchange_account_id}: {err}"),
+        }
+    }
+
+    /// Request reconnection of websockets, so user data stream will be opened with a new listen key
+    pub(super) fn request_user_data_reauth(&self) {
+        if self.user_data_reauth_sender.send(()).is_err() {
+            log::error!(
+                "Unable to request user data stream re-authentication on {}",
+                self.id
+            );
         }
     }
 }
@@ -19,4 +19,7 @@
 pub mod binance;
 pub mod exchange_client;
 
+mod order_book_sequence;
+mod stream_message;
+mod stream_subscriptions;
 mod support;
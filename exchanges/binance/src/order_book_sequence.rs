@@ -0,0 +1,213 @@
+use mmb_domain::market::CurrencyPair;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum SequenceState {
+    /// Id of the last applied update
+    Synced(u64),
+    /// Gap was detected, updates are discarded until a fresh snapshot is received
+    Resyncing,
+    /// Id of the last update included into a snapshot received by REST
+    AfterSnapshot(u64),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum SequenceCheck {
+    Apply,
+    /// Update is outdated or received while waiting for a fresh snapshot
+    Discard,
+    /// Update is not contiguous with the last applied one so a fresh snapshot is needed
+    Gap {
+        last_applied_update_id: u64,
+        previous_update_id: u64,
+    },
+}
+
+/// Tracks `lastUpdateId` sequence of order book updates by currency pair
+#[derive(Debug, Default)]
+pub(crate) struct OrderBookSequences {
+    by_currency_pair: Mutex<HashMap<CurrencyPair, SequenceState>>,
+}
+
+impl OrderBookSequences {
+    /// `previous_update_id` is id of the update preceding the incoming one if exchange sends it
+    /// (`pu` field for futures), `last_update_id` is id of the incoming update
+    pub(crate) fn check_update(
+        &self,
+        currency_pair: CurrencyPair,
+        previous_update_id: Option<u64>,
+        last_update_id: u64,
+    ) -> SequenceCheck {
+        let mut by_currency_pair = self.by_currency_pair.lock();
+        let state = by_currency_pair.get(&currency_pair).copied();
+
+        let check = match (state, previous_update_id) {
+            (None, _) => SequenceCheck::Apply,
+            (Some(SequenceState::Resyncing), _) => SequenceCheck::Discard,
+            (Some(SequenceState::AfterSnapshot(snapshot_update_id)), _) => {
+                match last_update_id < snapshot_update_id {
+                    true => SequenceCheck::Discard,
+                    false => SequenceCheck::Apply,
+                }
+            }
+            (Some(SequenceState::Synced(last_applied_update_id)), Some(previous_update_id)) => {
+                match previous_update_id == last_applied_update_id {
+                    true => SequenceCheck::Apply,
+                    false => SequenceCheck::Gap {
+                        last_applied_update_id,
+                        previous_update_id,
+                    },
+                }
+            }
+            (Some(SequenceState::Synced(last_applied_update_id)), None) => {
+                match last_update_id <= last_applied_update_id {
+                    true => SequenceCheck::Discard,
+                    false => SequenceCheck::Apply,
+                }
+            }
+        };
+
+        let new_state = match check {
+            SequenceCheck::Apply => Some(SequenceState::Synced(last_update_id)),
+            SequenceCheck::Gap { .. } => Some(SequenceState::Resyncing),
+            SequenceCheck::Discard => None,
+        };
+        if let Some(new_state) = new_state {
+            let _ = by_currency_pair.insert(currency_pair, new_state);
+        }
+
+        check
+    }
+
+    /// Whether any update or snapshot of the currency pair was received since the last reset
+    pub(crate) fn contains(&self, currency_pair: CurrencyPair) -> bool {
+        self.by_currency_pair.lock().contains_key(&currency_pair)
+    }
+
+    pub(crate) fn snapshot_received(&self, currency_pair: CurrencyPair, last_update_id: u64) {
+        let _ = self
+            .by_currency_pair
+            .lock()
+            .insert(currency_pair, SequenceState::AfterSnapshot(last_update_id));
+    }
+
+    /// Updates are discarded until a fresh snapshot is received
+    pub(crate) fn resync_requested(&self, currency_pair: CurrencyPair) {
+        let _ = self
+            .by_currency_pair
+            .lock()
+            .insert(currency_pair, SequenceState::Resyncing);
+    }
+
+    /// Sequence is started again from the next update if a fresh snapshot can't be received
+    pub(crate) fn resync_failed(&self, currency_pair: CurrencyPair) {
+        let _ = self.by_currency_pair.lock().remove(&currency_pair);
+    }
+
+    pub(crate) fn reset(&self) {
+        self.by_currency_pair.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency_pair() -> CurrencyPair {
+        CurrencyPair::from_codes("btc".into(), "usdt".into())
+    }
+
+    #[test]
+    fn contiguous_updates_applied() {
+        let sequences = OrderBookSequences::default();
+
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(5), 10),
+            SequenceCheck::Apply
+        );
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(10), 15),
+            SequenceCheck::Apply
+        );
+    }
+
+    #[test]
+    fn gap_discards_updates_until_snapshot() {
+        let sequences = OrderBookSequences::default();
+        let _ = sequences.check_update(currency_pair(), Some(5), 10);
+
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(12), 15),
+            SequenceCheck::Gap {
+                last_applied_update_id: 10,
+                previous_update_id: 12,
+            }
+        );
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(15), 20),
+            SequenceCheck::Discard
+        );
+
+        sequences.snapshot_received(currency_pair(), 22);
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(20), 21),
+            SequenceCheck::Discard
+        );
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(21), 25),
+            SequenceCheck::Apply
+        );
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(25), 30),
+            SequenceCheck::Apply
+        );
+    }
+
+    #[test]
+    fn outdated_update_without_previous_id_discarded() {
+        let sequences = OrderBookSequences::default();
+        let _ = sequences.check_update(currency_pair(), None, 10);
+
+        assert_eq!(
+            sequences.check_update(currency_pair(), None, 10),
+            SequenceCheck::Discard
+        );
+        assert_eq!(
+            sequences.check_update(currency_pair(), None, 11),
+            SequenceCheck::Apply
+        );
+    }
+
+    #[test]
+    fn requested_resync_discards_updates_until_snapshot() {
+        let sequences = OrderBookSequences::default();
+        let _ = sequences.check_update(currency_pair(), Some(5), 10);
+
+        sequences.resync_requested(currency_pair());
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(10), 15),
+            SequenceCheck::Discard
+        );
+
+        sequences.snapshot_received(currency_pair(), 17);
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(15), 20),
+            SequenceCheck::Apply
+        );
+    }
+
+    #[test]
+    fn sequence_restarted_after_failed_resync() {
+        let sequences = OrderBookSequences::default();
+        let _ = sequences.check_update(currency_pair(), Some(5), 10);
+        let _ = sequences.check_update(currency_pair(), Some(12), 15);
+
+        sequences.resync_failed(currency_pair());
+
+        assert_eq!(
+            sequences.check_update(currency_pair(), Some(15), 20),
+            SequenceCheck::Apply
+        );
+    }
+}
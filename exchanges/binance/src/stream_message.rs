@@ -0,0 +1,344 @@
+use mmb_domain::order::snapshot::{Amount, Price};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+
+/// Partial order book of spot `depth<levels>` streams
+/// {
+/// "lastUpdateId": 160,
+/// "bids": [["0.0024", "10"]],
+/// "asks": [["0.0026", "100"]]
+/// }
+#[derive(Deserialize, Debug)]
+struct SpotPartialDepth {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: u64,
+    asks: Vec<(Price, Amount)>,
+    bids: Vec<(Price, Amount)>,
+}
+
+/// Order book update of spot `depth` stream and all futures depth streams.
+/// `T` and `pu` are sent for futures only
+/// {
+/// "e": "depthUpdate",
+/// "E": 1571889248277, // Event time
+/// "T": 1571889248276, // Transaction time
+/// "s": "BTCUSDT",
+/// "U": 390497796, // First update id in event
+/// "u": 390497878, // Final update id in event
+/// "pu": 390497794, // Final update id in last stream
+/// "b": [["7403.89", "0.002"]],
+/// "a": [["7405.96", "3.340"]]
+/// }
+#[derive(Deserialize, Debug)]
+struct DepthUpdateEvent {
+    #[serde(rename = "E")]
+    event_time: i64,
+    #[serde(rename = "T")]
+    transaction_time: Option<i64>,
+    #[serde(rename = "U")]
+    first_update_id: u64,
+    #[serde(rename = "u")]
+    last_update_id: u64,
+    #[serde(rename = "pu")]
+    previous_update_id: Option<u64>,
+    #[serde(rename = "a")]
+    asks: Vec<(Price, Amount)>,
+    #[serde(rename = "b")]
+    bids: Vec<(Price, Amount)>,
+}
+
+/// Order book levels of any depth stream
+#[derive(Debug, PartialEq)]
+pub(crate) struct DepthData {
+    pub(crate) last_update_id: u64,
+    /// Id of the update preceding this one. Partial depth of spot market doesn't have it
+    pub(crate) previous_update_id: Option<u64>,
+    /// Time of the update on exchange in milliseconds if it is sent
+    pub(crate) time: Option<i64>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+    pub(crate) bids: Vec<(Price, Amount)>,
+}
+
+impl From<SpotPartialDepth> for DepthData {
+    fn from(depth: SpotPartialDepth) -> Self {
+        DepthData {
+            last_update_id: depth.last_update_id,
+            previous_update_id: None,
+            time: None,
+            asks: depth.asks,
+            bids: depth.bids,
+        }
+    }
+}
+
+impl From<DepthUpdateEvent> for DepthData {
+    fn from(event: DepthUpdateEvent) -> Self {
+        // spot updates are contiguous if first update id follows final update id of the previous one
+        let previous_update_id = event
+            .previous_update_id
+            .unwrap_or_else(|| event.first_update_id.saturating_sub(1));
+
+        DepthData {
+            last_update_id: event.last_update_id,
+            previous_update_id: Some(previous_update_id),
+            time: Some(event.transaction_time.unwrap_or(event.event_time)),
+            asks: event.asks,
+            bids: event.bids,
+        }
+    }
+}
+
+/// Public trade
+/// {
+/// "e": "trade",
+/// "E": 123456789,
+/// "s": "BNBBTC",
+/// "t": 12345, // Trade id
+/// "p": "0.001",
+/// "q": "100",
+/// "T": 123456785, // Trade time
+/// "m": true // Is the buyer the market maker
+/// }
+#[derive(Deserialize, Debug, PartialEq)]
+pub(crate) struct TradeData {
+    #[serde(rename = "t")]
+    pub(crate) trade_id: u64,
+    #[serde(rename = "p")]
+    pub(crate) price: Price,
+    #[serde(rename = "q")]
+    pub(crate) quantity: Amount,
+    #[serde(rename = "T")]
+    pub(crate) time: i64,
+    #[serde(rename = "m")]
+    pub(crate) is_buyer_maker: bool,
+}
+
+/// Best bid and ask
+/// {
+/// "u": 400900217, // Order book update id
+/// "s": "BNBUSDT",
+/// "b": "25.35190000", // Best bid price
+/// "B": "31.21000000", // Best bid amount
+/// "a": "25.36520000", // Best ask price
+/// "A": "40.66000000" // Best ask amount
+/// }
+#[derive(Deserialize, Debug, PartialEq)]
+#[allow(dead_code)]
+pub(crate) struct BookTicker {
+    #[serde(rename = "u")]
+    pub(crate) update_id: u64,
+    #[serde(rename = "b")]
+    pub(crate) bid_price: Price,
+    #[serde(rename = "B")]
+    pub(crate) bid_amount: Amount,
+    #[serde(rename = "a")]
+    pub(crate) ask_price: Price,
+    #[serde(rename = "A")]
+    pub(crate) ask_amount: Amount,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ParsedMessage {
+    /// Top levels of order book from `depth<levels>` streams
+    PartialDepth(DepthData),
+    /// Diff of order book from `depth` stream
+    DepthUpdate(DepthData),
+    Trade(TradeData),
+    BookTicker(BookTicker),
+}
+
+#[derive(Debug)]
+pub(crate) enum ParseError {
+    /// Stream isn't supported in current implementation
+    UnsupportedStream(String),
+    /// Message of the stream has unexpected shape
+    InvalidPayload {
+        stream: String,
+        error: serde_json::Error,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnsupportedStream(stream) => write!(f, "Unsupported stream {stream}"),
+            ParseError::InvalidPayload { stream, error } => {
+                write!(f, "Unable to parse message of stream {stream}: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse `data` field of a message of public stream by the stream name, e.g. `btcusdt@depth20@100ms`
+pub(crate) fn parse_stream_message(stream: &str, data: Value) -> Result<ParsedMessage, ParseError> {
+    let unsupported = || ParseError::UnsupportedStream(stream.to_owned());
+
+    let (_, channel) = stream.split_once('@').ok_or_else(unsupported)?;
+    let channel = channel.to_lowercase();
+    // update speed suffix like `@100ms` doesn't change message shape
+    let channel_name = channel.split('@').next().unwrap_or_default();
+
+    match channel_name {
+        "trade" => parse(stream, data).map(ParsedMessage::Trade),
+        "bookticker" => parse(stream, data).map(ParsedMessage::BookTicker),
+        "depth" => parse::<DepthUpdateEvent>(stream, data)
+            .map(|event| ParsedMessage::DepthUpdate(event.into())),
+        "depth5" | "depth10" | "depth20" => {
+            let depth = match data.get("e") {
+                Some(_) => parse::<DepthUpdateEvent>(stream, data)?.into(),
+                None => parse::<SpotPartialDepth>(stream, data)?.into(),
+            };
+            Ok(ParsedMessage::PartialDepth(depth))
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+fn parse<T: DeserializeOwned>(stream: &str, data: Value) -> Result<T, ParseError> {
+    serde_json::from_value(data).map_err(|error| ParseError::InvalidPayload {
+        stream: stream.to_owned(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    #[test]
+    fn spot_partial_depth() {
+        let data = json!({
+            "lastUpdateId": 160,
+            "bids": [["0.0024", "10"]],
+            "asks": [["0.0026", "100"]]
+        });
+
+        let message = parse_stream_message("bnbbtc@depth20@100ms", data).expect("in test");
+
+        assert_eq!(
+            message,
+            ParsedMessage::PartialDepth(DepthData {
+                last_update_id: 160,
+                previous_update_id: None,
+                time: None,
+                asks: vec![(dec!(0.0026), dec!(100))],
+                bids: vec![(dec!(0.0024), dec!(10))],
+            })
+        );
+    }
+
+    #[test]
+    fn futures_partial_depth() {
+        let data = json!({
+            "e": "depthUpdate",
+            "E": 1571889248277i64,
+            "T": 1571889248276i64,
+            "s": "BTCUSDT",
+            "U": 390497796,
+            "u": 390497878,
+            "pu": 390497794,
+            "b": [["7403.89", "0.002"]],
+            "a": [["7405.96", "3.340"]]
+        });
+
+        let message = parse_stream_message("btcusdt@depth20", data).expect("in test");
+
+        assert_eq!(
+            message,
+            ParsedMessage::PartialDepth(DepthData {
+                last_update_id: 390497878,
+                previous_update_id: Some(390497794),
+                time: Some(1571889248276),
+                asks: vec![(dec!(7405.96), dec!(3.340))],
+                bids: vec![(dec!(7403.89), dec!(0.002))],
+            })
+        );
+    }
+
+    #[test]
+    fn spot_depth_update() {
+        let data = json!({
+            "e": "depthUpdate",
+            "E": 123456789,
+            "s": "BNBBTC",
+            "U": 157,
+            "u": 160,
+            "b": [["0.0024", "10"]],
+            "a": [["0.0026", "100"]]
+        });
+
+        let message = parse_stream_message("bnbbtc@depth", data).expect("in test");
+
+        assert_eq!(
+            message,
+            ParsedMessage::DepthUpdate(DepthData {
+                last_update_id: 160,
+                previous_update_id: Some(156),
+                time: Some(123456789),
+                asks: vec![(dec!(0.0026), dec!(100))],
+                bids: vec![(dec!(0.0024), dec!(10))],
+            })
+        );
+    }
+
+    #[test]
+    fn trade_and_book_ticker() {
+        let trade = json!({
+            "e": "trade",
+            "E": 123456789,
+            "s": "BNBBTC",
+            "t": 12345,
+            "p": "0.001",
+            "q": "100",
+            "T": 123456785,
+            "m": true
+        });
+        assert_eq!(
+            parse_stream_message("bnbbtc@trade", trade).expect("in test"),
+            ParsedMessage::Trade(TradeData {
+                trade_id: 12345,
+                price: dec!(0.001),
+                quantity: dec!(100),
+                time: 123456785,
+                is_buyer_maker: true,
+            })
+        );
+
+        let book_ticker = json!({
+            "u": 400900217,
+            "s": "BNBUSDT",
+            "b": "25.35190000",
+            "B": "31.21000000",
+            "a": "25.36520000",
+            "A": "40.66000000"
+        });
+        assert_eq!(
+            parse_stream_message("bnbusdt@bookTicker", book_ticker).expect("in test"),
+            ParsedMessage::BookTicker(BookTicker {
+                update_id: 400900217,
+                bid_price: dec!(25.35190000),
+                bid_amount: dec!(31.21000000),
+                ask_price: dec!(25.36520000),
+                ask_amount: dec!(40.66000000),
+            })
+        );
+    }
+
+    #[test]
+    fn unexpected_shape_is_error() {
+        // spot partial depth without `lastUpdateId`
+        let data = json!({ "bids": [["0.0024", "10"]], "asks": "unexpected" });
+
+        let error = parse_stream_message("bnbbtc@depth20", data).expect_err("in test");
+        assert!(matches!(error, ParseError::InvalidPayload { .. }));
+
+        let error = parse_stream_message("bnbbtc@depth1000", json!({})).expect_err("in test");
+        assert!(matches!(error, ParseError::UnsupportedStream(_)));
+    }
+}
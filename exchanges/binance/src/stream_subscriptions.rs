@@ -0,0 +1,187 @@
+use mmb_domain::market::SpecificCurrencyPair;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum SubscriptionMethod {
+    Subscribe,
+    Unsubscribe,
+}
+
+impl SubscriptionMethod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionMethod::Subscribe => "SUBSCRIBE",
+            SubscriptionMethod::Unsubscribe => "UNSUBSCRIBE",
+        }
+    }
+}
+
+/// Public streams multiplexed on the main websocket via combined streams endpoint.
+/// Incoming messages are routed to currency pair by `stream` field.
+#[derive(Debug, Default)]
+pub(crate) struct StreamSubscriptions {
+    /// Currency pair by lowercase stream name, e.g. `btcusdt@depth`
+    streams: Mutex<BTreeMap<String, SpecificCurrencyPair>>,
+    last_request_id: AtomicU64,
+}
+
+impl StreamSubscriptions {
+    pub(crate) fn stream_name(
+        specific_currency_pair: SpecificCurrencyPair,
+        channel: &str,
+    ) -> String {
+        format!("{specific_currency_pair}@{channel}").to_lowercase()
+    }
+
+    /// Streams of all specified currency pairs for each channel
+    pub(crate) fn streams_for(
+        specific_currency_pairs: &[SpecificCurrencyPair],
+        channels: &[String],
+    ) -> Vec<(String, SpecificCurrencyPair)> {
+        specific_currency_pairs
+            .iter()
+            .flat_map(|&currency_pair| {
+                channels
+                    .iter()
+                    .map(move |channel| (Self::stream_name(currency_pair, channel), currency_pair))
+            })
+            .collect()
+    }
+
+    /// Replace all subscriptions, used for streams specified in the connection url
+    pub(crate) fn reset(&self, streams: Vec<(String, SpecificCurrencyPair)>) {
+        *self.streams.lock() = streams.into_iter().collect();
+    }
+
+    /// Returns names of streams which weren't subscribed before
+    pub(crate) fn add(&self, streams: Vec<(String, SpecificCurrencyPair)>) -> Vec<String> {
+        let mut current_streams = self.streams.lock();
+        streams
+            .into_iter()
+            .filter_map(|(stream, currency_pair)| {
+                match current_streams.insert(stream.clone(), currency_pair) {
+                    None => Some(stream),
+                    Some(_) => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns names of streams which were subscribed before
+    pub(crate) fn remove(&self, streams: Vec<(String, SpecificCurrencyPair)>) -> Vec<String> {
+        let mut current_streams = self.streams.lock();
+        streams
+            .into_iter()
+            .filter_map(|(stream, _)| current_streams.remove(&stream).map(|_| stream))
+            .collect()
+    }
+
+    pub(crate) fn stream_names(&self) -> Vec<String> {
+        self.streams.lock().keys().cloned().collect()
+    }
+
+    /// Currency pair of subscribed stream, `None` if there is no subscription for the stream
+    pub(crate) fn currency_pair(&self, stream: &str) -> Option<SpecificCurrencyPair> {
+        self.streams.lock().get(stream).copied()
+    }
+
+    /// Websocket message for live subscription changes
+    pub(crate) fn request(&self, method: SubscriptionMethod, streams: &[String]) -> String {
+        let id = self.last_request_id.fetch_add(1, Ordering::Relaxed) + 1;
+
+        json!({
+            "method": method.as_str(),
+            "params": streams,
+            "id": id,
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn channels() -> Vec<String> {
+        vec!["depth".into(), "trade".into()]
+    }
+
+    #[test]
+    fn streams_for_currency_pairs() {
+        let streams =
+            StreamSubscriptions::streams_for(&["BTCUSDT".into(), "ETHBTC".into()], &channels());
+
+        let names = streams.into_iter().map(|(x, _)| x).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "btcusdt@depth",
+                "btcusdt@trade",
+                "ethbtc@depth",
+                "ethbtc@trade"
+            ]
+        );
+    }
+
+    #[test]
+    fn add_and_remove_streams_at_runtime() {
+        let subscriptions = StreamSubscriptions::default();
+        subscriptions.reset(StreamSubscriptions::streams_for(
+            &["BTCUSDT".into()],
+            &channels(),
+        ));
+
+        let added = subscriptions.add(StreamSubscriptions::streams_for(
+            &["BTCUSDT".into(), "ETHBTC".into()],
+            &channels(),
+        ));
+        assert_eq!(added, vec!["ethbtc@depth", "ethbtc@trade"]);
+        assert_eq!(
+            subscriptions.currency_pair("ethbtc@trade"),
+            Some("ETHBTC".into())
+        );
+
+        let removed = subscriptions.remove(StreamSubscriptions::streams_for(
+            &["BTCUSDT".into()],
+            &channels(),
+        ));
+        assert_eq!(removed, vec!["btcusdt@depth", "btcusdt@trade"]);
+        assert_eq!(subscriptions.currency_pair("btcusdt@depth"), None);
+        assert_eq!(
+            subscriptions.stream_names(),
+            vec!["ethbtc@depth", "ethbtc@trade"]
+        );
+
+        let removed_again = subscriptions.remove(StreamSubscriptions::streams_for(
+            &["BTCUSDT".into()],
+            &channels(),
+        ));
+        assert!(removed_again.is_empty());
+    }
+
+    #[test]
+    fn subscription_requests_have_unique_ids() {
+        let subscriptions = StreamSubscriptions::default();
+        let streams = vec!["btcusdt@depth".to_string()];
+
+        let subscribe: Value =
+            serde_json::from_str(&subscriptions.request(SubscriptionMethod::Subscribe, &streams))
+                .expect("parse subscribe request");
+        let unsubscribe: Value =
+            serde_json::from_str(&subscriptions.request(SubscriptionMethod::Unsubscribe, &streams))
+                .expect("parse unsubscribe request");
+
+        assert_eq!(
+            subscribe,
+            json!({ "method": "SUBSCRIBE", "params": ["btcusdt@depth"], "id": 1 })
+        );
+        assert_eq!(
+            unsubscribe,
+            json!({ "method": "UNSUBSCRIBE", "params": ["btcusdt@depth"], "id": 2 })
+        );
+    }
+}
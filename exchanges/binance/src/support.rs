@@ -10,11 +10,15 @@ use mmb_domain::order::snapshot::{Amount, Price};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
-use super::binance::Binance;
+use super::binance::{Binance, ORDER_BOOK_SNAPSHOT_LIMIT};
+use super::order_book_sequence::SequenceCheck;
+use super::stream_message::{parse_stream_message, DepthData, ParsedMessage, TradeData};
+use super::stream_subscriptions::{StreamSubscriptions, SubscriptionMethod};
 use mmb_core::connectivity::WebSocketRole;
 use mmb_core::exchanges::common::send_event;
 use mmb_core::exchanges::general::exchange::Exchange;
@@ -22,7 +26,7 @@ use mmb_core::exchanges::traits::{HandleMetricsCb, Support};
 use mmb_core::exchanges::traits::{
     HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
 };
-use mmb_core::infrastructure::spawn_by_timer;
+use mmb_core::infrastructure::{spawn_by_timer, spawn_future};
 use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{
     EventSourceType, ExchangeEvent, MetricsEventInfo, MetricsEventType, Trade, TradeId,
@@ -33,8 +37,12 @@ use mmb_domain::order::snapshot::SortedOrderData;
 use mmb_domain::order::snapshot::*;
 use mmb_domain::order_book::event::{EventType, OrderBookEvent};
 use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::nothing_to_do;
 use mmb_utils::time::get_current_milliseconds;
 
+const DEFAULT_LISTEN_KEY_KEEPALIVE_INTERVAL_SECS: u64 = 20 * 60;
+const DEFAULT_SERVER_TIME_SYNC_INTERVAL_SECS: u64 = 10 * 60;
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct BinanceOrderInfo {
     #[serde(rename = "symbol")]
@@ -114,41 +122,63 @@ impl Support for Binance {
     async fn initialized(&self, exchange: Arc<Exchange>) {
         self.initialize_working_currencies(&exchange);
 
-        start_updating_listen_key(&exchange);
+        // signed requests are rejected if local clock is ahead of server time, so offset should be known before them
+        if let Err(err) = self.sync_server_time().await {
+            log::warn!("Unable to sync server time on {}: {err:?}", self.id);
+        }
+
+        self.start_updating_listen_key(&exchange);
+        self.start_server_time_sync(&exchange);
+        self.start_order_book_resync(&exchange);
+        self.start_user_data_reauth(&exchange);
     }
 
     fn on_websocket_message(&self, msg: &str) -> Result<()> {
         let mut data: Value =
             serde_json::from_str(msg).context("Unable to parse websocket message")?;
+        // Response on live subscription request
+        if let Some(request_id) = data.get("id") {
+            match data.get("error") {
+                Some(error) => log::error!(
+                    "Subscription request {request_id} failed on {}: {error}",
+                    self.id
+                ),
+                None => log::trace!("Subscription request {request_id} succeeded on {}", self.id),
+            }
+
+            return Ok(());
+        }
+
         // Public stream
         if let Some(stream) = data.get("stream") {
             let stream = stream
                 .as_str()
-                .ok_or_else(|| anyhow!("Unable to parse stream data"))?;
-
-            if let Some(byte_index) = stream.find('@') {
-                let currency_pair = self.currency_pair_from_web_socket(&stream[..byte_index])?;
-                let data = &data["data"];
-
-                if stream.ends_with("@trade") {
-                    self.handle_trade(currency_pair, data)?;
+                .ok_or_else(|| anyhow!("Unable to parse stream data"))?
+                .to_owned();
+
+            let specific_currency_pair = match self.stream_subscriptions.currency_pair(&stream) {
+                Some(v) => v,
+                None => {
+                    // message could be received after unsubscription
+                    log::trace!(
+                        "Skipped message of not subscribed stream {stream} on {}",
+                        self.id
+                    );
                     return Ok(());
                 }
-
-                // TODO handle public stream
-                let stream_tail = &stream[byte_index + 1..];
-                if stream_tail.starts_with("depth1000") {
-                    log::warn!("depth1000 is unsuported for Binance in current implementation");
-                    return Ok(());
-                }
-
-                if stream_tail.starts_with("depth") {
-                    self.process_snapshot_update(currency_pair, data)?;
-                    return Ok(());
+            };
+
+            let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+            return match parse_stream_message(&stream, data["data"].take()) {
+                Ok(message) => self.handle_stream_message(currency_pair, message),
+                Err(err) => {
+                    // skip the message to continue processing of the stream
+                    let _ = self.stream_parse_errors.fetch_add(1, Ordering::Relaxed);
+                    log::warn!("{err} on {}", self.id);
+                    log::debug!("Skipped message of stream {stream} on {}: {msg}", self.id);
+                    Ok(())
                 }
-            }
-
-            return Ok(());
+            };
         }
 
         // so it is userData stream
@@ -162,6 +192,9 @@ impl Support for Binance {
             let json_response = data["o"].take();
             let event_time = Self::get_event_time(&data)?;
             self.handle_order_fill(msg, json_response, event_time)?;
+        } else if event_type == "listenKeyExpired" {
+            log::warn!("User data stream listenKey expired on {}", self.id);
+            self.request_user_data_reauth();
         } else {
             self.log_unknown_message(self.id, msg);
         }
@@ -170,6 +203,8 @@ impl Support for Binance {
     }
 
     fn on_connecting(&self) -> Result<()> {
+        self.order_book_sequences.reset();
+
         self.unified_to_specific
             .read()
             .iter()
@@ -192,7 +227,9 @@ impl Support for Binance {
         Ok(())
     }
 
-    fn set_send_websocket_message_callback(&mut self, _callback: SendWebsocketMessageCb) {}
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
 
     fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
         self.order_created_callback = callback;
@@ -215,7 +252,11 @@ impl Support for Binance {
     }
 
     fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
-        *self.traded_specific_currencies.lock() = currencies;
+        self.stream_subscriptions
+            .reset(StreamSubscriptions::streams_for(
+                &currencies,
+                &self.settings.websocket_channels,
+            ));
     }
 
     fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
@@ -229,10 +270,7 @@ impl Support for Binance {
 
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
         let (host, path) = match role {
-            WebSocketRole::Main => (
-                &self.hosts.web_socket_host,
-                self.build_ws_main_path(&self.settings.websocket_channels[..]),
-            ),
+            WebSocketRole::Main => (&self.hosts.web_socket_host, self.build_ws_main_path()),
             WebSocketRole::Secondary => (
                 &self.hosts.web_socket2_host,
                 self.build_ws_secondary_path().await?,
@@ -266,11 +304,47 @@ impl Support for Binance {
     fn get_settings(&self) -> &ExchangeSettings {
         &self.settings
     }
+
+    fn request_order_book_resync(&self, currency_pair: CurrencyPair) -> Result<()> {
+        // updates are discarded until the snapshot is received, then they are validated against its update id
+        self.order_book_sequences.resync_requested(currency_pair);
+        self.order_book_resync_sender
+            .send(currency_pair)
+            .context("Unable to request order book resync")
+    }
 }
 
 impl Binance {
-    pub(crate) fn handle_trade(&self, currency_pair: CurrencyPair, data: &Value) -> Result<()> {
-        let trade_id = TradeId::from(data["t"].clone());
+    /// Count of public stream messages skipped because of parsing errors
+    pub fn stream_parse_errors_count(&self) -> u64 {
+        self.stream_parse_errors.load(Ordering::Relaxed)
+    }
+
+    fn handle_stream_message(
+        &self,
+        currency_pair: CurrencyPair,
+        message: ParsedMessage,
+    ) -> Result<()> {
+        match message {
+            ParsedMessage::PartialDepth(depth) => {
+                self.process_depth(currency_pair, depth, EventType::Snapshot)
+            }
+            ParsedMessage::DepthUpdate(depth) => {
+                self.process_depth(currency_pair, depth, EventType::Update)
+            }
+            ParsedMessage::Trade(trade) => self.handle_trade(currency_pair, trade),
+            ParsedMessage::BookTicker(book_ticker) => {
+                log::trace!(
+                    "Skipped best bid and ask of {currency_pair} on {}: {book_ticker:?}",
+                    self.id
+                );
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn handle_trade(&self, currency_pair: CurrencyPair, trade: TradeData) -> Result<()> {
+        let trade_id = TradeId::Number(trade.trade_id);
 
         let mut trade_id_from_lasts =
             self.last_trade_ids.get_mut(&currency_pair).with_expect(|| {
@@ -285,26 +359,13 @@ impl Binance {
 
         *trade_id_from_lasts = trade_id.clone();
 
-        let price: Decimal = data["p"]
-            .as_str()
-            .context("Unable to get string from 'p' field json data")?
-            .parse()?;
-
-        let quantity: Decimal = data["q"]
-            .as_str()
-            .context("Unable to get string from 'q' field json data")?
-            .parse()?;
-        let order_side = if data["m"] == true {
-            OrderSide::Sell
-        } else {
-            OrderSide::Buy
+        let order_side = match trade.is_buyer_maker {
+            true => OrderSide::Sell,
+            false => OrderSide::Buy,
         };
-        let datetime = data["T"]
-            .as_i64()
-            .context("Unable to get i64 from 'T' field json data")?;
 
         (self.handle_metrics_callback)(MetricsEventInfo::new(
-            datetime,
+            trade.time,
             get_current_milliseconds(),
             EventSourceType::WebSocket,
             MetricsEventType::TradeEvent,
@@ -314,62 +375,116 @@ impl Binance {
             currency_pair,
             Trade {
                 trade_id,
-                price,
-                quantity,
+                price: trade.price,
+                quantity: trade.quantity,
                 side: order_side,
-                transaction_time: Utc.timestamp_millis(datetime),
+                transaction_time: Utc.timestamp_millis(trade.time),
             },
         );
 
         Ok(())
     }
 
-    pub fn process_snapshot_update(&self, currency_pair: CurrencyPair, data: &Value) -> Result<()> {
-        let (last_update_id, raw_asks, raw_bids) = match self.settings.is_margin_trading {
-            true => {
-                let last_update_id = data["u"].to_string();
-                let raw_asks = data["a"]
-                    .as_array()
-                    .ok_or_else(|| anyhow!("Unable to parse 'asks' in Binance"))?;
-                let raw_bids = data["b"]
-                    .as_array()
-                    .ok_or_else(|| anyhow!("Unable to parse 'bids' in Binance"))?;
-                (last_update_id, raw_asks, raw_bids)
-            }
-            false => {
-                let last_update_id = data["lastUpdateId"].to_string();
-                let raw_asks = data["asks"]
-                    .as_array()
-                    .ok_or_else(|| anyhow!("Unable to parse 'asks' in Binance"))?;
-                let raw_bids = data["bids"]
-                    .as_array()
-                    .ok_or_else(|| anyhow!("Unable to parse 'bids' in Binance"))?;
-                (last_update_id, raw_asks, raw_bids)
+    fn process_depth(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: DepthData,
+        event_type: EventType,
+    ) -> Result<()> {
+        if matches!(event_type, EventType::Update)
+            && !self.order_book_sequences.contains(currency_pair)
+        {
+            // diffs of order book can be applied only to a snapshot requested by REST
+            self.order_book_resync_sender
+                .send(currency_pair)
+                .context("Unable to request order book snapshot")?;
+        }
+
+        match self.order_book_sequences.check_update(
+            currency_pair,
+            depth.previous_update_id,
+            depth.last_update_id,
+        ) {
+            SequenceCheck::Apply => nothing_to_do(),
+            SequenceCheck::Discard => return Ok(()),
+            SequenceCheck::Gap {
+                last_applied_update_id,
+                previous_update_id,
+            } => {
+                log::warn!("Gap detected in order book updates for {currency_pair} on {}: last applied update id {last_applied_update_id}, previous update id of incoming update {previous_update_id}. Requesting a fresh snapshot", self.id);
+                return self
+                    .order_book_resync_sender
+                    .send(currency_pair)
+                    .context("Unable to request order book resync");
             }
-        };
+        }
 
-        let datetime = data["T"]
-            .as_i64()
-            .context("Unable to get i64 from 'T' field json data")?;
+        // partial depth of spot market doesn't contain time of the update
+        if let Some(time) = depth.time {
+            (self.handle_metrics_callback)(MetricsEventInfo::new(
+                time,
+                get_current_milliseconds(),
+                EventSourceType::WebSocket,
+                MetricsEventType::OrderBookEvent,
+            ));
+        }
 
-        (self.handle_metrics_callback)(MetricsEventInfo::new(
-            datetime,
-            get_current_milliseconds(),
-            EventSourceType::WebSocket,
-            MetricsEventType::OrderBookEvent,
-        ));
+        let order_book_data = OrderBookData::new(
+            depth.asks.into_iter().collect(),
+            depth.bids.into_iter().collect(),
+        );
+        let first_update_id = depth
+            .previous_update_id
+            .map_or(depth.last_update_id, |previous_update_id| {
+                previous_update_id + 1
+            });
+        self.handle_order_book_event(
+            currency_pair,
+            (first_update_id, depth.last_update_id),
+            event_type,
+            order_book_data,
+            None,
+        )
+    }
+
+    pub(super) async fn resync_order_book(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let response = self
+            .request_order_book_snapshot(currency_pair, ORDER_BOOK_SNAPSHOT_LIMIT)
+            .await?;
+        let data: Value = serde_json::from_str(&response.content)
+            .context("Unable to parse order book snapshot")?;
+
+        let last_update_id = data["lastUpdateId"]
+            .as_u64()
+            .context("Unable to get u64 from 'lastUpdateId' field json data")?;
+        let raw_asks = data["asks"]
+            .as_array()
+            .ok_or_else(|| anyhow!("Unable to parse 'asks' in Binance"))?;
+        let raw_bids = data["bids"]
+            .as_array()
+            .ok_or_else(|| anyhow!("Unable to parse 'bids' in Binance"))?;
+        let order_book_data = OrderBookData::new(
+            get_order_book_side(raw_asks)?,
+            get_order_book_side(raw_bids)?,
+        );
 
-        let asks = get_order_book_side(raw_asks)?;
-        let bids = get_order_book_side(raw_bids)?;
+        self.order_book_sequences
+            .snapshot_received(currency_pair, last_update_id);
 
-        let order_book_data = OrderBookData::new(asks, bids);
-        self.handle_order_book_snapshot(currency_pair, &last_update_id, order_book_data, None)
+        self.handle_order_book_event(
+            currency_pair,
+            (last_update_id, last_update_id),
+            EventType::Snapshot,
+            order_book_data,
+            None,
+        )
     }
 
-    fn handle_order_book_snapshot(
+    fn handle_order_book_event(
         &self,
         currency_pair: CurrencyPair,
-        event_id: &str,
+        (first_update_id, last_update_id): (u64, u64),
+        event_type: EventType,
         mut order_book_data: OrderBookData,
         order_book_update: Option<Vec<OrderBookData>>,
     ) -> Result<()> {
@@ -387,10 +502,11 @@ impl Binance {
             Utc::now(),
             self.id,
             currency_pair,
-            event_id.to_string(),
-            EventType::Snapshot,
+            last_update_id.to_string(),
+            event_type,
             Arc::new(order_book_data),
-        );
+        )
+        .with_update_ids(first_update_id, last_update_id);
 
         let event = ExchangeEvent::OrderBookEvent(order_book_event);
 
@@ -404,27 +520,63 @@ impl Binance {
         )
     }
 
-    fn currency_pair_from_web_socket(&self, currency_pair: &str) -> Result<CurrencyPair> {
-        let specific_currency_pair = currency_pair.to_uppercase().as_str().into();
-        self.get_unified_currency_pair(&specific_currency_pair)
+    fn build_ws_main_path(&self) -> String {
+        let stream_names = self.stream_subscriptions.stream_names().join("/");
+        format!("/stream?streams={stream_names}")
+    }
+
+    /// Subscribe to public streams of specified currency pairs on the already opened main websocket.
+    /// Subscriptions are kept for reconnections even if sending of request failed.
+    pub fn subscribe_market_data(&self, currency_pairs: &[CurrencyPair]) -> Result<()> {
+        let streams = self.streams_for(currency_pairs);
+        for (_, specific_currency_pair) in &streams {
+            let currency_pair = self.get_unified_currency_pair(specific_currency_pair)?;
+            let _ = self
+                .last_trade_ids
+                .entry(currency_pair)
+                .or_insert(TradeId::Number(0));
+        }
+
+        let new_streams = self.stream_subscriptions.add(streams);
+        self.send_subscription_request(SubscriptionMethod::Subscribe, &new_streams)
+    }
+
+    /// Unsubscribe from public streams of specified currency pairs on the main websocket
+    pub fn unsubscribe_market_data(&self, currency_pairs: &[CurrencyPair]) -> Result<()> {
+        let removed_streams = self
+            .stream_subscriptions
+            .remove(self.streams_for(currency_pairs));
+        self.send_subscription_request(SubscriptionMethod::Unsubscribe, &removed_streams)
     }
 
-    fn build_ws_main_path(&self, websocket_channels: &[String]) -> String {
-        let stream_names = self
-            .traded_specific_currencies
-            .lock()
+    fn streams_for(&self, currency_pairs: &[CurrencyPair]) -> Vec<(String, SpecificCurrencyPair)> {
+        let specific_currency_pairs = currency_pairs
             .iter()
-            .flat_map(|currency_pair| {
-                let mut results = Vec::new();
-                for channel in websocket_channels {
-                    let result = Self::get_stream_name(currency_pair, channel);
-                    results.push(result);
-                }
-                results
-            })
-            .join("/");
-        let ws_path = format!("/stream?streams={stream_names}");
-        ws_path.to_lowercase()
+            .map(|&x| self.get_specific_currency_pair(x))
+            .collect_vec();
+
+        StreamSubscriptions::streams_for(
+            &specific_currency_pairs,
+            &self.settings.websocket_channels,
+        )
+    }
+
+    fn send_subscription_request(
+        &self,
+        method: SubscriptionMethod,
+        streams: &[String],
+    ) -> Result<()> {
+        if streams.is_empty() {
+            return Ok(());
+        }
+
+        let request = self.stream_subscriptions.request(method, streams);
+        (self.websocket_message_callback)(WebSocketRole::Main, request).with_context(|| {
+            format!(
+                "Unable to send {method:?} request for streams {streams:?} on {}",
+                self.id
+            )
+        })
     }
 
     async fn build_ws_secondary_path(&self) -> Result<String> {
@@ -438,32 +590,149 @@ impl Binance {
     }
 }
 
-fn start_updating_listen_key(exchange: &Arc<Exchange>) {
-    let exchange_wk = Arc::downgrade(exchange);
-    let period = Duration::from_secs(20 * 60);
-    spawn_by_timer(
-        "Update listen key",
-        period,
-        period,
-        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
-        move || {
-            let exchange_wk = exchange_wk.clone();
+impl Binance {
+    fn start_updating_listen_key(&self, exchange: &Arc<Exchange>) {
+        let exchange_wk = Arc::downgrade(exchange);
+        let period = Duration::from_secs(
+            self.settings
+                .listen_key_keepalive_interval_secs
+                .unwrap_or(DEFAULT_LISTEN_KEY_KEEPALIVE_INTERVAL_SECS),
+        );
+        spawn_by_timer(
+            "Update listen key",
+            period,
+            period,
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            move || {
+                let exchange_wk = exchange_wk.clone();
+                async move {
+                    let exchange = match exchange_wk.upgrade() {
+                        None => return,
+                        Some(v) => v,
+                    };
+
+                    exchange
+                        .exchange_client
+                        .as_any()
+                        .downcast_ref::<Binance>()
+                        .expect("received non Binance exchange client in method of updating listen keys by timer")
+                        .ping_listen_key()
+                        .await;
+                }
+            },
+        );
+    }
+
+    fn start_server_time_sync(&self, exchange: &Arc<Exchange>) {
+        let exchange_wk = Arc::downgrade(exchange);
+        // server time is synced on initialization, so the first sync by timer is after the period
+        let period = Duration::from_secs(
+            self.settings
+                .server_time_sync_interval_secs
+                .unwrap_or(DEFAULT_SERVER_TIME_SYNC_INTERVAL_SECS),
+        );
+        spawn_by_timer(
+            "Sync server time",
+            period,
+            period,
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            move || {
+                let exchange_wk = exchange_wk.clone();
+                async move {
+                    let exchange = match exchange_wk.upgrade() {
+                        None => return,
+                        Some(v) => v,
+                    };
+
+                    let binance = exchange
+                        .exchange_client
+                        .as_any()
+                        .downcast_ref::<Binance>()
+                        .expect("received non Binance exchange client in method of syncing server time by timer");
+                    if let Err(err) = binance.sync_server_time().await {
+                        log::warn!("Unable to sync server time on {}: {err:?}", binance.id);
+                    }
+                }
+            },
+        );
+    }
+
+    /// Reconnect websockets on user data stream auth failure. Secondary websocket url is built
+    /// with a newly received listen key on connection, so the stream is transparently re-established
+    fn start_user_data_reauth(&self, exchange: &Arc<Exchange>) {
+        let mut reauth_receiver = match self.user_data_reauth_receiver.lock().take() {
+            None => return,
+            Some(v) => v,
+        };
+
+        let exchange_wk = Arc::downgrade(exchange);
+        spawn_future(
+            "Re-authenticate user data stream",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
             async move {
-                let exchange = match exchange_wk.upgrade() {
-                    None => return,
-                    Some(v) => v,
-                };
-
-                exchange
-                    .exchange_client
-                    .as_any()
-                    .downcast_ref::<Binance>()
-                    .expect("received non Binance exchange client in method of updating listen keys by timer")
-                    .ping_listen_key()
-                    .await;
-            }
-        },
-    );
+                while reauth_receiver.recv().await.is_some() {
+                    // requests received before reconnection are satisfied by it
+                    while reauth_receiver.try_recv().is_ok() {}
+
+                    let exchange = match exchange_wk.upgrade() {
+                        None => return Ok(()),
+                        Some(v) => v,
+                    };
+
+                    let exchange_account_id = exchange.exchange_account_id;
+                    log::warn!(
+                        "Forced re-authentication of user data stream on {exchange_account_id}"
+                    );
+                    match exchange.reconnect_ws().await {
+                        Ok(()) => log::info!(
+                            "User data stream re-authenticated on {exchange_account_id}"
+                        ),
+                        Err(err) => log::error!(
+                            "Failed to re-authenticate user data stream on {exchange_account_id}: {err:?}"
+                        ),
+                    }
+                }
+
+                Ok(())
+            },
+        );
+    }
+
+    fn start_order_book_resync(&self, exchange: &Arc<Exchange>) {
+        let mut resync_receiver = match self.order_book_resync_receiver.lock().take() {
+            None => return,
+            Some(v) => v,
+        };
+
+        let exchange_wk = Arc::downgrade(exchange);
+        spawn_future(
+            "Resync order book",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            async move {
+                while let Some(currency_pair) = resync_receiver.recv().await {
+                    let exchange = match exchange_wk.upgrade() {
+                        None => return Ok(()),
+                        Some(v) => v,
+                    };
+
+                    let binance = exchange
+                        .exchange_client
+                        .as_any()
+                        .downcast_ref::<Binance>()
+                        .expect(
+                            "received non Binance exchange client in method of order book resync",
+                        );
+
+                    if let Err(err) = binance.resync_order_book(currency_pair).await {
+                        log::error!("Unable to resync order book for {currency_pair}: {err:?}");
+                        binance.order_book_sequences.resync_failed(currency_pair);
+                    }
+                }
+
+                Ok(())
+            },
+        );
+    }
 }
 
 fn get_order_book_side(levels: &[Value]) -> Result<SortedOrderData> {
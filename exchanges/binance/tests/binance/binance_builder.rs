@@ -192,7 +192,7 @@ impl BinanceBuilder {
 
         let exchange_blocker = ExchangeBlocker::new(vec![exchange_account_id]);
 
-        let event_recorder = EventRecorder::start(None, None)
+        let event_recorder = EventRecorder::start(None, None, 0.0)
             .await
             .expect("Failure start EventRecorder");
 
@@ -203,6 +203,7 @@ impl BinanceBuilder {
             OrdersPool::new(),
             features,
             RequestTimeoutArguments::from_requests_per_minute(1200),
+            0.0,
             tx.clone(),
             lifetime_manager,
             timeout_manager,
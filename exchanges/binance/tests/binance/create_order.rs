@@ -1,5 +1,6 @@
 use crate::binance::binance_builder::BinanceBuilder;
 use core_tests::order::OrderProxy;
+use itertools::Itertools;
 use mmb_domain::events::{AllowedEventSourceType, ExchangeEvent};
 use mmb_domain::order::event::OrderEventType;
 use mmb_utils::cancellation_token::CancellationToken;
@@ -91,3 +92,45 @@ async fn should_fail() {
         error.to_string()
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn create_batch_successfully() {
+    init_logger();
+
+    let binance_builder = match BinanceBuilder::build_account_0().await {
+        Ok(binance_builder) => binance_builder,
+        Err(_) => return,
+    };
+
+    let order_proxies = (0..3)
+        .map(|_| {
+            OrderProxy::new(
+                binance_builder.exchange.exchange_account_id,
+                Some("FromCreateBatchSuccessfullyTest".to_owned()),
+                CancellationToken::default(),
+                binance_builder.min_price,
+                binance_builder.min_amount,
+                binance_builder.default_currency_pair,
+            )
+        })
+        .collect_vec();
+    let order_headers = order_proxies
+        .iter()
+        .map(|order_proxy| order_proxy.make_header())
+        .collect_vec();
+
+    let results = binance_builder
+        .exchange
+        .create_orders_batch(&order_headers, 2, CancellationToken::default())
+        .await;
+
+    assert_eq!(results.len(), order_proxies.len());
+    for (order_proxy, result) in order_proxies.iter().zip(results) {
+        let order_ref = result.expect("Create order failed with error");
+        assert_eq!(order_ref.client_order_id(), order_proxy.client_order_id);
+
+        order_proxy
+            .cancel_order_or_fail(&order_ref, binance_builder.exchange.clone())
+            .await;
+    }
+}
@@ -4,6 +4,7 @@ use binance::binance::BinanceBuilder;
 use mmb_core::config::parse_settings;
 use mmb_core::infrastructure::spawn_future_ok;
 use mmb_core::lifecycle::launcher::{launch_trading_engine, EngineBuildConfig, InitSettings};
+use mmb_domain::market::ExchangeAccountId;
 use mmb_utils::infrastructure::SpawnFutureFlags;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -47,3 +48,47 @@ async fn launch_engine() {
 
     engine.run().await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn launch_engine_with_two_accounts() {
+    let config = EngineBuildConfig::new(vec![]).with_account_count(Box::new(BinanceBuilder), 2);
+
+    let (api_key, secret_key) = get_binance_credentials_or_exit!();
+    let credentials = format!(
+        "[Binance_0]\napi_key = \"{api_key}\"\nsecret_key = \"{secret_key}\"\n\
+         [Binance_1]\napi_key = \"{api_key}\"\nsecret_key = \"{secret_key}\""
+    );
+
+    // Credentials are available here, so settings parsing failure is a real error
+    let settings = parse_settings::<TestStrategySettings>(
+        include_str!("lifecycle_two_accounts.toml"),
+        &credentials,
+    )
+    .expect("in test");
+
+    let init_settings = InitSettings::Directly(settings);
+    let engine = launch_trading_engine(&config, init_settings)
+        .await
+        .expect("in tests");
+
+    let context = engine.context();
+    for account_number in 0..2 {
+        let exchange_account_id = ExchangeAccountId::new("Binance", account_number);
+        assert!(
+            context.exchanges.contains_key(&exchange_account_id),
+            "{exchange_account_id} should be in exchanges registry"
+        );
+    }
+
+    let action = async move {
+        sleep(Duration::from_millis(200)).await;
+        context.lifetime_manager.run_graceful_shutdown("test").await;
+    };
+    spawn_future_ok(
+        "run graceful_shutdown in launch_engine_with_two_accounts test",
+        SpawnFutureFlags::DENY_CANCELLATION,
+        action,
+    );
+
+    engine.run().await;
+}
@@ -8,6 +8,7 @@ use arrayvec::{ArrayString, ArrayVec};
 use dashmap::DashMap;
 use function_name::named;
 use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
 use hyper::http::request::Builder;
 use hyper::{StatusCode, Uri};
 use itertools::Itertools;
@@ -30,14 +31,15 @@ use mmb_core::exchanges::traits::{
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::{Precision, Symbol};
 use mmb_domain::market::{
     CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId, SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::{
-    ExchangeOrderId, ExternalOrder, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
-    OrderSide, OrderStatus, Price, UserOrder,
+    ExchangeOrderId, ExternalOrder, OrderExecutionType, OrderHeader, OrderInfo, OrderOptions,
+    OrderRole, OrderSide, OrderStatus, Price, UserOrder,
 };
 use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
 use mmb_utils::DateTime;
@@ -144,6 +146,7 @@ impl RestHeaders for RestHeadersBitmex {
         builder: Builder,
         uri: &Uri,
         request_type: RequestType,
+        _body: Option<&Bytes>,
     ) -> Builder {
         let path_and_query = match uri.path_and_query() {
             Some(path_and_query) => path_and_query.as_str(),
@@ -200,7 +203,13 @@ impl Bitmex {
                     ErrorHandlerBitmex::default(),
                 ),
                 RestHeadersBitmex::new(settings.api_key.clone(), settings.secret_key.clone()),
-            ),
+            )
+            .with_bodies_logging(settings.log_rest_bodies)
+            .with_rate_limit_retries(
+                settings.rest_rate_limit_retries,
+                lifetime_manager.stop_token(),
+            )
+            .with_transient_error_retries(settings.rest_transient_error_retries),
             settings,
             hosts: Self::make_hosts(),
             unified_to_specific: Default::default(),
@@ -250,7 +259,13 @@ impl Bitmex {
                     let quote = symbol.quote_id.into();
 
                     let specific_currency_pair = symbol.id.into();
-                    let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+                    // Futures contract code contains only month code and year of expiry, e.g. `XBTZ22`,
+                    // so exact expiry date is taken from instrument description
+                    let expiry = symbol.expiry.map(|expiry| expiry.date_naive());
+                    let unified_currency_pair = match expiry {
+                        Some(expiry) => CurrencyPair::from_derivative(base, quote, expiry),
+                        None => CurrencyPair::from_codes(base, quote),
+                    };
                     self.unified_to_specific
                         .write()
                         .insert(unified_currency_pair, specific_currency_pair);
@@ -265,26 +280,29 @@ impl Bitmex {
                             false => (base, None),
                         };
 
-                    Arc::new(Symbol::new(
-                        self.settings.is_margin_trading,
-                        symbol.base_id.into(),
-                        base,
-                        symbol.quote_id.into(),
-                        quote,
-                        None,
-                        symbol.max_price,
-                        Some(symbol.amount_tick),
-                        symbol.max_amount,
-                        None,
-                        amount_currency_code,
-                        balance_currency_code,
-                        Precision::ByTick {
-                            tick: symbol.price_tick,
-                        },
-                        Precision::ByTick {
-                            tick: symbol.amount_tick,
-                        },
-                    ))
+                    Arc::new(
+                        Symbol::new(
+                            self.settings.is_margin_trading,
+                            symbol.base_id.into(),
+                            base,
+                            symbol.quote_id.into(),
+                            quote,
+                            None,
+                            symbol.max_price,
+                            Some(symbol.amount_tick),
+                            symbol.max_amount,
+                            None,
+                            amount_currency_code,
+                            balance_currency_code,
+                            Precision::ByTick {
+                                tick: symbol.price_tick,
+                            },
+                            Precision::ByTick {
+                                tick: symbol.amount_tick,
+                            },
+                        )
+                        .with_expiry(expiry),
+                    )
                 })
             })
             .collect_vec())
@@ -295,7 +313,11 @@ impl Bitmex {
 
         let is_active_symbol = symbol.state == "Open";
         let is_supported = match self.settings.is_margin_trading {
-            true => symbol_type == BitmexSymbolType::PerpetualContract && symbol.id != "ETHUSD_ETH", // ETHUSD_ETH is a ETH-margined perpetual swap. We don't support it at the moment
+            true => match symbol_type {
+                BitmexSymbolType::PerpetualContract => symbol.id != "ETHUSD_ETH", // ETHUSD_ETH is a ETH-margined perpetual swap. We don't support it at the moment
+                BitmexSymbolType::Future => symbol.expiry.is_some(),
+                _ => false,
+            },
             false => symbol_type == BitmexSymbolType::Spot,
         };
 
@@ -322,15 +344,9 @@ impl Bitmex {
 
         match header.options {
             OrderOptions::User(user_order) => match user_order {
-                UserOrder::Limit {
-                    price,
-                    execution_type,
-                } => {
+                UserOrder::Limit { price, .. } => {
                     builder.add_kv("ordType", "Limit");
                     builder.add_kv("price", price);
-                    if execution_type == OrderExecutionType::MakerOnly {
-                        builder.add_kv("execInst", "ParticipateDoNotInitiate");
-                    }
                 }
                 UserOrder::Market => builder.add_kv("ordType", "Market"),
                 UserOrder::StopLoss { stop_price } => {
@@ -357,6 +373,10 @@ impl Bitmex {
             _ => return Err(ExchangeError::unknown("Unexpected order type")),
         }
 
+        if let Some(exec_inst) = Self::get_exec_inst(header) {
+            builder.add_kv("execInst", exec_inst);
+        }
+
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
         let log_args = format!("Create order for {header:?}");
         self.rest_client
@@ -662,6 +682,61 @@ impl Bitmex {
         Ok(ClosedPosition::new(bitmex_order.exchange_order_id, amount))
     }
 
+    #[named]
+    pub(super) async fn request_trading_fees(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v1/user/commission");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Instructions for order handling separated by comma if more than one is applicable
+    fn get_exec_inst(header: &OrderHeader) -> Option<String> {
+        let mut instructions = Vec::new();
+        if let OrderOptions::User(UserOrder::Limit {
+            execution_type: OrderExecutionType::MakerOnly,
+            ..
+        }) = header.options
+        {
+            instructions.push("ParticipateDoNotInitiate");
+        }
+        if header.reduce_only {
+            instructions.push("ReduceOnly");
+        }
+
+        match instructions.is_empty() {
+            true => None,
+            false => Some(instructions.join(",")),
+        }
+    }
+
+    /// Response contains fee rates of account by all symbols
+    pub(super) fn parse_trading_fees(
+        response: &RestResponse,
+        specific_currency_pair: SpecificCurrencyPair,
+    ) -> Result<TradingFees> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct BitmexCommission {
+            #[serde(with = "rust_decimal::serde::float")]
+            maker_fee: Decimal,
+            #[serde(with = "rust_decimal::serde::float")]
+            taker_fee: Decimal,
+        }
+
+        let commissions: HashMap<String, BitmexCommission> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize commission response from Bitmex")?;
+
+        let commission = commissions
+            .get(specific_currency_pair.as_str())
+            .with_context(|| format!("There is no commission for {specific_currency_pair}"))?;
+
+        Ok(TradingFees::new(commission.maker_fee, commission.taker_fee))
+    }
+
     #[named]
     pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
         let mut builder = UriBuilder::from_path("/api/v1/user/margin");
@@ -809,6 +884,8 @@ impl ExchangeClientBuilder for BitmexBuilder {
                     cancellation_notification: true,
                     supports_ping_pong: true,
                     supports_subscription_response: false,
+                    stale_timeout: None,
+                    max_frame_size: None,
                 },
                 EMPTY_RESPONSE_IS_OK,
                 AllowedEventSourceType::default(),
@@ -831,6 +908,10 @@ impl ExchangeClientBuilder for BitmexBuilder {
 mod tests {
     use super::*;
     use bstr::ByteSlice;
+    use chrono::NaiveDate;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::ClientOrderId;
+    use rstest::rstest;
 
     #[test]
     fn generate_signature() {
@@ -857,4 +938,94 @@ mod tests {
             "e2f422547eecb5b3cb29ade2127e21b858b235b386bfa45e1c1756eb3383919f"
         );
     }
+
+    #[test]
+    fn parse_trading_fees() {
+        let response = RestResponse {
+            status: StatusCode::OK,
+            content: r#"{
+                "XBTUSD": {"makerFee": -0.0001, "takerFee": 0.00075, "settlementFee": 0, "maxFee": 0.00075},
+                "ETHUSD": {"makerFee": -0.0001, "takerFee": 0.0005, "settlementFee": 0, "maxFee": 0.0005}
+            }"#
+            .to_owned(),
+        };
+
+        let trading_fees = Bitmex::parse_trading_fees(&response, "ETHUSD".into()).expect("in test");
+        assert_eq!(trading_fees, TradingFees::new(dec!(-0.0001), dec!(0.0005)));
+
+        assert!(Bitmex::parse_trading_fees(&response, "SOLUSD".into()).is_err());
+    }
+
+    #[test]
+    fn parse_futures_symbols() {
+        let settings = ExchangeSettings::new_short(
+            ExchangeAccountId::new("Bitmex", 0),
+            "api_key".into(),
+            "secret_key".into(),
+            true,
+        );
+        let (tx, _) = broadcast::channel(10);
+        let bitmex = Bitmex::new(settings, tx, AppLifetimeManager::new(Default::default()));
+
+        let response = RestResponse {
+            status: StatusCode::OK,
+            content: r#"[
+                {"symbol": "XBTUSD", "typ": "FFWCSX", "state": "Open", "underlying": "XBT", "quoteCurrency": "USD",
+                 "tickSize": 0.5, "lotSize": 100, "maxPrice": 1000000, "maxOrderQty": 10000000, "expiry": null},
+                {"symbol": "XBTZ22", "typ": "FFCCSX", "state": "Open", "underlying": "XBT", "quoteCurrency": "USD",
+                 "tickSize": 0.5, "lotSize": 100, "maxPrice": 1000000, "maxOrderQty": 10000000, "expiry": "2022-12-30T12:00:00.000Z"},
+                {"symbol": ".BXBT", "typ": "MRCXXX", "state": "Unlisted", "underlying": "XBT", "quoteCurrency": "USD",
+                 "tickSize": 0.01, "lotSize": 1, "maxPrice": null, "maxOrderQty": null, "expiry": null}
+            ]"#
+            .to_owned(),
+        };
+
+        let symbols = bitmex.parse_all_symbols(&response).expect("in test");
+
+        let expiry = NaiveDate::from_ymd_opt(2022, 12, 30).expect("in test");
+        let futures_currency_pair =
+            CurrencyPair::from_derivative("XBT".into(), "USD".into(), expiry);
+        assert_eq!(
+            symbols.iter().map(|x| x.currency_pair()).collect_vec(),
+            vec![
+                CurrencyPair::from_codes("XBT".into(), "USD".into()),
+                futures_currency_pair
+            ]
+        );
+        assert_eq!(symbols[1].expiry, Some(expiry));
+        assert_eq!(
+            bitmex.get_specific_currency_pair(futures_currency_pair),
+            "XBTZ22".into()
+        );
+    }
+
+    #[rstest]
+    #[case::market(UserOrder::Market, false, None)]
+    #[case::reduce_only_market(UserOrder::Market, true, Some("ReduceOnly"))]
+    #[case::maker_only(UserOrder::maker_only(dec!(1)), false, Some("ParticipateDoNotInitiate"))]
+    #[case::reduce_only_maker_only(
+        UserOrder::maker_only(dec!(1)),
+        true,
+        Some("ParticipateDoNotInitiate,ReduceOnly")
+    )]
+    fn exec_inst_of_order(
+        #[case] user_order: UserOrder,
+        #[case] reduce_only: bool,
+        #[case] expected: Option<&str>,
+    ) {
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Bitmex", 0),
+            CurrencyPair::from_codes("xbt".into(), "usd".into()),
+            OrderSide::Sell,
+            dec!(1),
+            user_order,
+            None,
+            None,
+            "test".to_owned(),
+        )
+        .with_reduce_only(reduce_only);
+
+        assert_eq!(Bitmex::get_exec_inst(&header).as_deref(), expected);
+    }
 }
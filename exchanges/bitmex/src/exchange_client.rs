@@ -1,17 +1,20 @@
 use crate::bitmex::Bitmex;
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
 use itertools::Itertools;
 use mmb_core::exchanges::general::exchange::RequestResult;
 use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
 use mmb_core::exchanges::general::order::create::CreateOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
-use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError, Support};
+use mmb_domain::candle::{Candle, KlineInterval};
 use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 use std::sync::Arc;
@@ -141,8 +144,31 @@ impl ExchangeClient for Bitmex {
         Ok(symbols)
     }
 
+    async fn get_klines(
+        &self,
+        _currency_pair: CurrencyPair,
+        _interval: KlineInterval,
+        _limit: u32,
+    ) -> Result<Vec<Candle>> {
+        Err(anyhow!("Getting klines isn't supported for Bitmex yet"))
+    }
+
     async fn get_server_time(&self) -> Option<Result<i64>> {
         // TODO Need to receive Bitmex server time
         None
     }
+
+    async fn get_trading_fees(&self, currency_pair: CurrencyPair) -> Result<TradingFees> {
+        let response = self.request_trading_fees().await?;
+
+        Self::parse_trading_fees(&response, self.get_specific_currency_pair(currency_pair))
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        _currency_pair: CurrencyPair,
+        _depth: u32,
+    ) -> Result<OrderBookData> {
+        Err(anyhow!("Getting order book snapshot isn't supported for Bitmex yet"))
+    }
 }
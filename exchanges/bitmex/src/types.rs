@@ -125,14 +125,15 @@ pub(crate) struct BitmexSymbol<'a> {
     #[serde(rename = "quoteCurrency")]
     pub(crate) quote_id: &'a str,
     pub(crate) state: &'a str,
-    #[serde(rename = "tickSize")]
+    #[serde(rename = "tickSize", with = "rust_decimal::serde::float")]
     pub(crate) price_tick: Decimal,
-    #[serde(rename = "lotSize")]
+    #[serde(rename = "lotSize", with = "rust_decimal::serde::float")]
     pub(crate) amount_tick: Decimal,
-    #[serde(rename = "maxPrice")]
+    #[serde(rename = "maxPrice", with = "rust_decimal::serde::float_option")]
     pub(crate) max_price: Option<Price>,
-    #[serde(rename = "maxOrderQty")]
+    #[serde(rename = "maxOrderQty", with = "rust_decimal::serde::float_option")]
     pub(crate) max_amount: Option<Amount>,
+    pub(crate) expiry: Option<DateTime>,
 }
 
 #[derive(PartialEq)]
@@ -166,7 +166,7 @@ impl BitmexBuilder {
         let hosts = bitmex.hosts.clone();
 
         let exchange_blocker = ExchangeBlocker::new(vec![settings.exchange_account_id]);
-        let event_recorder = EventRecorder::start(None, None)
+        let event_recorder = EventRecorder::start(None, None, 0.0)
             .await
             .expect("Failure start EventRecorder");
 
@@ -177,6 +177,7 @@ impl BitmexBuilder {
             OrdersPool::new(),
             features,
             RequestTimeoutArguments::from_requests_per_minute(1200),
+            0.0,
             tx.clone(),
             lifetime_manager,
             timeout_manager,
@@ -5,6 +5,7 @@ use ibtwsapi::core::messages::ServerRspMsg;
 pub enum ChannelType {
     CancelOrder,
     CreateOrder,
+    DiscoverSymbols,
     GetBalance,
     GetMyTrades,
     GetOpenOrders,
@@ -12,10 +13,11 @@ pub enum ChannelType {
 }
 
 impl ChannelType {
-    pub fn get_all() -> &'static [Self; 6] {
+    pub fn get_all() -> &'static [Self; 7] {
         &[
             Self::CancelOrder,
             Self::CreateOrder,
+            Self::DiscoverSymbols,
             Self::GetBalance,
             Self::GetMyTrades,
             Self::GetOpenOrders,
@@ -38,6 +40,8 @@ impl ChannelType {
             ServerRspMsg::OrderStatus { .. } => &[Self::CancelOrder],
             ServerRspMsg::PositionData { .. } => &[Self::GetPositions],
             ServerRspMsg::PositionEnd { .. } => &[Self::GetPositions],
+            ServerRspMsg::ScannerData { .. } => &[Self::DiscoverSymbols],
+            ServerRspMsg::ScannerDataEnd { .. } => &[Self::DiscoverSymbols],
             _ => {
                 log::debug!("fn {f_n}: received unsupported message: {:?}.", msg);
 
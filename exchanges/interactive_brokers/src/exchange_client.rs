@@ -7,11 +7,14 @@ use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
 use mmb_core::exchanges::general::order::create::CreateOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError, Support};
+use mmb_domain::candle::{Candle, KlineInterval};
 use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::{Precision, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType};
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 use rust_decimal_macros::dec;
@@ -255,7 +258,32 @@ impl ExchangeClient for InteractiveBrokers {
         Ok(symbols)
     }
 
+    async fn get_klines(
+        &self,
+        _currency_pair: CurrencyPair,
+        _interval: KlineInterval,
+        _limit: u32,
+    ) -> anyhow::Result<Vec<Candle>> {
+        Err(anyhow!("Getting klines isn't supported for Interactive Brokers yet"))
+    }
+
     async fn get_server_time(&self) -> Option<anyhow::Result<i64>> {
         todo!()
     }
+
+    async fn get_trading_fees(&self, _currency_pair: CurrencyPair) -> anyhow::Result<TradingFees> {
+        Err(anyhow!(
+            "Getting trading fees isn't supported for Interactive Brokers"
+        ))
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        _currency_pair: CurrencyPair,
+        _depth: u32,
+    ) -> anyhow::Result<OrderBookData> {
+        Err(anyhow!(
+            "Getting order book snapshot isn't supported for Interactive Brokers"
+        ))
+    }
 }
@@ -19,16 +19,21 @@ pub struct InteractiveBrokersBuilder;
 impl ExchangeClientBuilder for InteractiveBrokersBuilder {
     fn create_exchange_client(
         &self,
-        _exchange_settings: ExchangeSettings,
-        _events_channel: Sender<ExchangeEvent>,
-        _lifetime_manager: Arc<AppLifetimeManager>,
+        exchange_settings: ExchangeSettings,
+        events_channel: Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
         _timeout_manager: Arc<TimeoutManager>,
         _orders: Arc<OrdersPool>,
     ) -> ExchangeClientBuilderResult {
         let empty_response_is_ok = false;
 
         ExchangeClientBuilderResult {
-            client: Box::new(InteractiveBrokers::new()),
+            client: Box::new(InteractiveBrokers::new(
+                exchange_settings.exchange_account_id,
+                exchange_settings.auto_discover,
+                events_channel,
+                lifetime_manager,
+            )),
             features: ExchangeFeatures::new(
                 OpenOrdersType::AllCurrencyPair,
                 RestFillsFeatures::new(RestFillsType::None),
@@ -16,22 +16,27 @@ use ibtwsapi::core::contract::Contract;
 use ibtwsapi::core::errors::IBKRApiLibError;
 use ibtwsapi::core::messages::ServerRspMsg;
 use ibtwsapi::core::order::Order;
+use ibtwsapi::core::scanner::ScannerSubscription;
 use ibtwsapi::examples::order_samples;
+use mmb_core::exchanges::common::send_event;
 use mmb_core::exchanges::general::exchange::{Exchange, RequestResult};
 use mmb_core::exchanges::general::handlers::handle_order_filled::{
     FillAmount, FillEvent, SpecialOrderData,
 };
 use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
-use mmb_core::exchanges::traits::ExchangeError;
-use mmb_domain::events::{EventSourceType, ExchangeBalance, TradeId};
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_domain::events::{EventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeErrorType};
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, ExchangeErrorType};
 use mmb_domain::order::fill::OrderFillType;
 use mmb_domain::order::snapshot::{
     ClientOrderId, ExchangeOrderId, OrderInfo, OrderRole, OrderSide as MmbOrderSide,
     OrderStatus as MmbOrderStatus,
 };
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ActivePositionId, DerivativePosition};
 use mmb_utils::DateTime;
 use rust_decimal::Decimal;
@@ -39,10 +44,16 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
 use tokio::sync::{Mutex, MutexGuard, RwLock};
+use tokio::time::timeout;
 
 pub struct InteractiveBrokers {
+    pub id: ExchangeAccountId,
+
+    auto_discover: bool,
+
     // `Mutex` is required here, because `EClient::evt_chan` doesn't implement `Sync`
     client: Arc<Mutex<EClient>>,
 
@@ -58,10 +69,19 @@ pub struct InteractiveBrokers {
     pub mutexes: Mutexes,
 
     pub event_listener_fields: RwLock<Option<EventListenerFields>>,
+
+    events_channel: broadcast::Sender<ExchangeEvent>,
+
+    lifetime_manager: Arc<AppLifetimeManager>,
 }
 
 impl InteractiveBrokers {
-    pub fn new() -> Self {
+    pub fn new(
+        id: ExchangeAccountId,
+        auto_discover: bool,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Self {
         let client = Arc::new(Mutex::new(EClient::new()));
         let (channel_senders, ch_rx) = make_channels();
 
@@ -77,6 +97,8 @@ impl InteractiveBrokers {
             .as_secs() as i32;
 
         InteractiveBrokers {
+            id,
+            auto_discover,
             client,
             next_order_id: AtomicI32::new(seed),
             symbols: RwLock::new(HashMap::new()),
@@ -84,6 +106,8 @@ impl InteractiveBrokers {
             req_id_seed: AtomicI32::new(seed),
             mutexes: Mutexes::default(),
             event_listener_fields: RwLock::new(Some(event_listener_fields)),
+            events_channel,
+            lifetime_manager,
         }
     }
 
@@ -490,6 +514,169 @@ impl InteractiveBrokers {
         self.get_positions_response().await
     }
 
+    pub async fn discover_symbols_request(
+        &self,
+        scan_code: &str,
+        number_of_rows: i32,
+    ) -> anyhow::Result<i32> {
+        let req_id = self.req_id_seed.fetch_add(1, Ordering::Relaxed);
+        let subscription = ScannerSubscription {
+            number_of_rows,
+            instrument: "STK".to_string(),
+            location_code: "STK.US.MAJOR".to_string(),
+            scan_code: scan_code.to_string(),
+            ..ScannerSubscription::default()
+        };
+
+        self.get_client()
+            .await
+            .req_scanner_subscription(req_id, subscription, vec![], vec![])?;
+
+        Ok(req_id)
+    }
+
+    pub async fn discover_symbols_response(
+        &self,
+        expected_req_id: i32,
+    ) -> anyhow::Result<Vec<CurrencyPair>> {
+        let mut currency_pairs = Vec::new();
+
+        loop {
+            let msg = self.ch_rx.recv(ChannelType::DiscoverSymbols).await;
+
+            if Self::handle_scanner_msg(expected_req_id, msg, &mut currency_pairs)? {
+                break;
+            }
+        }
+
+        Ok(currency_pairs)
+    }
+
+    /// Collects currency pairs from scanner results with `expected_req_id`.
+    /// Returns `true` when all results of the scanner are received
+    #[named]
+    fn handle_scanner_msg(
+        expected_req_id: i32,
+        msg: ServerRspMsg,
+        currency_pairs: &mut Vec<CurrencyPair>,
+    ) -> anyhow::Result<bool> {
+        let f_n = function_name!();
+
+        match &msg {
+            ServerRspMsg::ErrMsg { req_id, .. } => {
+                if req_id == &expected_req_id {
+                    // TODO: Maybe use here `TwsError`?
+                    return Err(anyhow!(msg));
+                }
+
+                // Message for someone else, but not for me. Ignore it.
+                Ok(false)
+            }
+            ServerRspMsg::ScannerData { req_id, .. } => {
+                if req_id == &expected_req_id {
+                    let currency_pair = Self::parse_currency_pair_from_scanner_data_msg(&msg)?;
+
+                    currency_pairs.push(currency_pair);
+                }
+
+                Ok(false)
+            }
+            ServerRspMsg::ScannerDataEnd { req_id } => Ok(req_id == &expected_req_id),
+            _ => unreachable!("fn {f_n}: received unsupported message: {:?}", msg),
+        }
+    }
+
+    /// Top `number_of_rows` currency pairs of the market scanner with specified `scan_code`
+    pub async fn discover_symbols(
+        &self,
+        scan_code: &str,
+        number_of_rows: i32,
+    ) -> anyhow::Result<Vec<CurrencyPair>> {
+        const TIMEOUT: Duration = Duration::from_secs(30);
+
+        // There we need `Mutex` that locks the entire function,
+        // because methods that return `Vec` cannot be called simultaneously
+        let _guard = self.mutexes.discover_symbols.lock().await;
+
+        let req_id = self
+            .discover_symbols_request(scan_code, number_of_rows)
+            .await?;
+
+        let currency_pairs = timeout(TIMEOUT, self.discover_symbols_response(req_id))
+            .await
+            .unwrap_or_else(|_| {
+                Err(anyhow!(
+                    "Scanner results weren't received during {} secs.",
+                    TIMEOUT.as_secs()
+                ))
+            });
+
+        // Scanner subscription should be cancelled after receiving results
+        self.get_client()
+            .await
+            .cancel_scanner_subscription(req_id)
+            .context("Cancel scanner subscription error.")?;
+
+        currency_pairs
+    }
+
+    /// Discover symbols, register them on the exchange and in local snapshots service with empty order books
+    pub async fn auto_discover_symbols(&self, exchange: &Exchange) -> anyhow::Result<()> {
+        if !self.auto_discover {
+            return Ok(());
+        }
+
+        // 10 most active stocks are enough to start trading without full list of symbols in settings
+        let currency_pairs = self
+            .discover_symbols("MOST_ACTIVE", 10)
+            .await
+            .context("Discover symbols error.")?;
+
+        let all_symbols = self
+            .build_all_symbols()
+            .await
+            .context("Build symbols for discovered currency pairs error.")?;
+
+        for currency_pair in currency_pairs {
+            let symbol = match all_symbols
+                .iter()
+                .find(|symbol| symbol.currency_pair() == currency_pair)
+            {
+                Some(symbol) => symbol.clone(),
+                None => {
+                    log::warn!(
+                        "Discovered symbol {currency_pair} on {} is skipped because it isn't supported",
+                        self.id
+                    );
+                    continue;
+                }
+            };
+
+            log::info!("Discovered symbol {currency_pair} on {}", self.id);
+
+            let _ = exchange.symbols.insert(currency_pair, symbol.clone());
+            let _ = self.symbols.write().await.insert(currency_pair, symbol);
+
+            let order_book_event = OrderBookEvent::new(
+                Utc::now(),
+                self.id,
+                currency_pair,
+                "".to_string(),
+                EventType::Snapshot,
+                Arc::new(OrderBookData::default()),
+            );
+
+            send_event(
+                &self.events_channel,
+                self.lifetime_manager.clone(),
+                self.id,
+                ExchangeEvent::OrderBookEvent(order_book_event),
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub async fn get_client(&self) -> MutexGuard<EClient> {
         self.client.lock().await
     }
@@ -692,6 +879,33 @@ impl InteractiveBrokers {
         }
     }
 
+    #[named]
+    fn parse_currency_pair_from_scanner_data_msg(
+        msg: &ServerRspMsg,
+    ) -> anyhow::Result<CurrencyPair> {
+        let f_n = function_name!();
+
+        if let ServerRspMsg::ScannerData {
+            contract_details, ..
+        } = msg
+        {
+            let contract = &contract_details.contract;
+            if contract.symbol.is_empty() || contract.currency.is_empty() {
+                return Err(anyhow!(
+                    "fn {f_n}: scanner result has no symbol or currency: {:?}.",
+                    contract
+                ));
+            }
+
+            let base = CurrencyCode::from(contract.symbol.as_str());
+            let quote = CurrencyCode::from(contract.currency.as_str());
+
+            Ok(CurrencyPair::from_codes(base, quote))
+        } else {
+            unreachable!("fn {f_n}: received unsupported message: {:?}", msg);
+        }
+    }
+
     /// TODO: Fill `FillEvent::trade_id` from `ServerRspMsg::ExecutionData::execution::exec_id`
     /// TODO: Fill `FillEvent::fill_date` from `ServerRspMsg::ExecutionData::execution::time`
     /// TODO: Figure out where we can get `FillEvent::order_role`
@@ -839,3 +1053,81 @@ impl InteractiveBrokers {
         ExchangeError::new(error_type, error_msg, None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ibtwsapi::core::contract::ContractDetails;
+
+    fn scanner_data(req_id: i32, rank: i32, symbol: &str, currency: &str) -> ServerRspMsg {
+        ServerRspMsg::ScannerData {
+            req_id,
+            rank,
+            contract_details: ContractDetails {
+                contract: Contract {
+                    symbol: symbol.to_string(),
+                    currency: currency.to_string(),
+                    sec_type: "STK".to_string(),
+                    exchange: "SMART".to_string(),
+                    ..Contract::default()
+                },
+                market_name: "NMS".to_string(),
+                ..ContractDetails::default()
+            },
+            distance: "".to_string(),
+            benchmark: "".to_string(),
+            projection: "".to_string(),
+            legs_str: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_scanner_response() {
+        let req_id = 7;
+        // Captured `MOST_ACTIVE` scanner response mixed with results of another scanner
+        let response = vec![
+            scanner_data(req_id, 0, "AAPL", "USD"),
+            scanner_data(req_id + 1, 0, "IBM", "USD"),
+            scanner_data(req_id, 1, "TSLA", "USD"),
+            ServerRspMsg::ErrMsg {
+                req_id: -1,
+                error_code: 2104,
+                error_str: "Market data farm connection is OK:usfarm".to_string(),
+            },
+            scanner_data(req_id, 2, "AMD", "USD"),
+            ServerRspMsg::ScannerDataEnd { req_id },
+        ];
+
+        let mut currency_pairs = Vec::new();
+        let mut is_completed = false;
+        for msg in response {
+            assert!(!is_completed, "messages after the end of scanner results");
+            is_completed = InteractiveBrokers::handle_scanner_msg(req_id, msg, &mut currency_pairs)
+                .expect("handle scanner message");
+        }
+
+        assert!(is_completed);
+        assert_eq!(
+            currency_pairs,
+            vec![
+                CurrencyPair::from_codes("AAPL".into(), "USD".into()),
+                CurrencyPair::from_codes("TSLA".into(), "USD".into()),
+                CurrencyPair::from_codes("AMD".into(), "USD".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn scanner_error_for_request_is_returned() {
+        let req_id = 7;
+        let msg = ServerRspMsg::ErrMsg {
+            req_id,
+            error_code: 165,
+            error_str: "Historical Market Data Service query message".to_string(),
+        };
+
+        let result = InteractiveBrokers::handle_scanner_msg(req_id, msg, &mut Vec::new());
+
+        assert!(result.is_err());
+    }
+}
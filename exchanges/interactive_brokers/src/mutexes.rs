@@ -2,6 +2,7 @@ use tokio::sync::Mutex;
 
 #[derive(Default)]
 pub struct Mutexes {
+    pub discover_symbols: Mutex<()>,
     pub get_balance: Mutex<()>,
     pub get_my_trades: Mutex<()>,
     pub get_open_orders: Mutex<()>,
@@ -26,7 +26,7 @@ impl Support for InteractiveBrokers {
     }
 
     async fn initialized(&self, exchange: Arc<Exchange>) {
-        self.set_symbols(exchange).await;
+        self.set_symbols(exchange.clone()).await;
 
         self.get_client()
             .await
@@ -44,6 +44,11 @@ impl Support for InteractiveBrokers {
             SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
             Self::response_listener(client, channel_senders, handlers),
         );
+
+        // trading on symbols from settings is possible without discovered ones, so it isn't a fatal error
+        if let Err(err) = self.auto_discover_symbols(&exchange).await {
+            log::error!("Auto discover symbols on {} failed: {err:?}", self.id);
+        }
     }
 
     fn on_websocket_message(&self, _msg: &str) -> Result<()> {
@@ -0,0 +1,153 @@
+use crate::okx::Okx;
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_domain::candle::{Candle, KlineInterval};
+use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::commission::TradingFees;
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_utils::DateTime;
+use std::sync::Arc;
+
+#[async_trait]
+impl ExchangeClient for Okx {
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        match self.do_create_order(order).await {
+            Ok(request_outcome) => match self.get_order_id(&request_outcome) {
+                Ok(order_id) => CreateOrderResult::succeed(&order_id, EventSourceType::Rest),
+                Err(error) => CreateOrderResult::failed(error, EventSourceType::Rest),
+            },
+            Err(err) => CreateOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> CancelOrderResult {
+        match self.do_cancel_order(order, exchange_order_id).await {
+            Ok(_) => {
+                CancelOrderResult::succeed(order.client_order_id(), EventSourceType::Rest, None)
+            }
+            Err(err) => CancelOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        match self.do_cancel_all_orders(currency_pair).await {
+            Ok(_) => Ok(()),
+            Err(error) => bail!("Failed to cancel all orders: {error:?}"),
+        }
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        self.request_all_open_orders(None).await
+    }
+
+    async fn get_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderInfo>> {
+        self.request_all_open_orders(Some(currency_pair)).await
+    }
+
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        let response = self.request_order_info(order).await?;
+
+        self.parse_order_info(&response)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order info: {err:?}")))
+    }
+
+    async fn close_position(
+        &self,
+        _position: &ActivePosition,
+        _price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        Err(anyhow!("Closing positions isn't supported for OKX yet"))
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        Err(anyhow!("Getting active positions isn't supported for OKX yet"))
+    }
+
+    async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        let response = self.request_get_balance().await?;
+
+        Ok(ExchangeBalancesAndPositions {
+            balances: Okx::parse_get_balance(&response)?,
+            positions: None,
+        })
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> RequestResult<Vec<OrderTrade>> {
+        match self.request_my_trades(symbol, last_date_time).await {
+            Ok(response) => match self.parse_my_trades(&response) {
+                Ok(data) => RequestResult::Success(data),
+                Err(err) => RequestResult::Error(ExchangeError::parsing(format!(
+                    "Unable to parse trades: {err:?}"
+                ))),
+            },
+            Err(err) => RequestResult::Error(ExchangeError::unknown(
+                format!("Failed to get trades: {err:?}").as_str(),
+            )),
+        }
+    }
+
+    async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        let response = self.request_all_symbols().await?;
+
+        self.parse_all_symbols(&response)
+    }
+
+    async fn get_klines(
+        &self,
+        _currency_pair: CurrencyPair,
+        _interval: KlineInterval,
+        _limit: u32,
+    ) -> Result<Vec<Candle>> {
+        Err(anyhow!("Getting klines isn't supported for OKX yet"))
+    }
+
+    async fn get_server_time(&self) -> Option<Result<i64>> {
+        match self.request_get_server_time().await {
+            Ok(response) => Some(Okx::parse_get_server_time(&response)),
+            Err(err) => Some(Err(anyhow!("Get server time request failed: {err:?}"))),
+        }
+    }
+
+    async fn get_trading_fees(&self, currency_pair: CurrencyPair) -> Result<TradingFees> {
+        let response = self
+            .request_trading_fees(currency_pair)
+            .await
+            .map_err(|err| anyhow!("Get trading fees request failed: {err:?}"))?;
+
+        Okx::parse_trading_fees(&response)
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: u32,
+    ) -> Result<OrderBookData> {
+        let response = self
+            .request_order_book_snapshot(currency_pair, depth)
+            .await
+            .map_err(|err| anyhow!("Get order book snapshot request failed: {err:?}"))?;
+
+        Okx::parse_order_book_snapshot(&response)
+    }
+}
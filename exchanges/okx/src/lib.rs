@@ -0,0 +1,23 @@
+#![deny(
+    non_ascii_idents,
+    non_shorthand_field_patterns,
+    no_mangle_generic_items,
+    overflowing_literals,
+    path_statements,
+    unused_allocation,
+    unused_comparisons,
+    unused_parens,
+    while_true,
+    trivial_numeric_casts,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_must_use,
+    clippy::unwrap_used
+)]
+
+mod exchange_client;
+pub mod okx;
+mod order_book_checksum;
+mod support;
+pub mod types;
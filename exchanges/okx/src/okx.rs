@@ -0,0 +1,972 @@
+use crate::order_book_checksum::OrderBookChecksums;
+use crate::types::{
+    OkxBalance, OkxFill, OkxOrderBook, OkxOrderBookLevel, OkxOrderInfo, OkxOrderResult,
+    OkxResponse, OkxSymbol,
+};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::commission::TradingFees;
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole, OrderSide,
+    OrderStatus, SortedOrderData, UserOrder,
+};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::time::u64_to_date_time;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Instrument type of spot markets in OKX terms
+pub(crate) const SPOT_INSTRUMENT_TYPE: &str = "SPOT";
+/// Max count of orders in a single batch cancel request
+const CANCEL_BATCH_SIZE: usize = 20;
+/// Max count of orders returned by one request of pending orders
+const OPEN_ORDERS_PAGE_SIZE: usize = 100;
+
+#[derive(Default)]
+pub struct ErrorHandlerOkx;
+
+impl ErrorHandler for ErrorHandlerOkx {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct OkxError {
+            code: String,
+            msg: String,
+            #[serde(default)]
+            data: Option<Vec<OkxOrderError>>,
+        }
+        // Operations with orders return code "1" and specify error for every order in data
+        #[derive(Deserialize)]
+        struct OkxOrderError {
+            #[serde(rename = "sCode", default)]
+            code: String,
+            #[serde(rename = "sMsg", default)]
+            msg: String,
+        }
+
+        let error: OkxError = match serde_json::from_str(&response.content) {
+            Ok(error) => error,
+            Err(_) => return Err(ExchangeError::unknown(&response.content)),
+        };
+        if error.code == "0" {
+            return Ok(());
+        }
+
+        let (code, message) = match error
+            .data
+            .unwrap_or_default()
+            .into_iter()
+            .find(|order_error| !order_error.code.is_empty() && order_error.code != "0")
+        {
+            Some(order_error) => (order_error.code, order_error.msg),
+            None => (error.code, error.msg),
+        };
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            message,
+            code.parse().ok(),
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        use ExchangeErrorType::*;
+        // https://www.okx.com/docs-v5/en/#error-code
+        match error.code {
+            // 51400 Cancellation failed as the order does not exist
+            // 51401 Cancellation failed as the order is already canceled
+            // 51603 Order does not exist
+            Some(51400) | Some(51401) | Some(51603) => OrderNotFound,
+            // 51402 Cancellation failed as the order is already completed
+            Some(51402) => OrderCompleted,
+            // 51008 Order placement failed due to insufficient balance
+            Some(51008) => InsufficientFunds,
+            // 51000 Parameter error
+            // 51006 Order price is out of the limit
+            // 51020 Order amount should be greater than the min available amount
+            // 51121 Order count should be the integer multiples of the lot size
+            Some(51000) | Some(51006) | Some(51020) | Some(51121) => InvalidOrder,
+            // 50011 Too many requests
+            Some(50011) => RateLimit,
+            // 50001 Service temporarily unavailable
+            Some(50001) => Maintenance,
+            // 50102 Timestamp request expired
+            // 50103..50105 Missing or wrong OK-ACCESS-KEY, OK-ACCESS-PASSPHRASE headers
+            // 50111 Invalid OK-ACCESS-KEY
+            // 50113 Invalid signature
+            Some(50102..=50105) | Some(50111) | Some(50113) => Authentication,
+            _ => Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersOkx {
+    api_key: String,
+    secret_key: String,
+    passphrase: String,
+}
+
+impl RestHeadersOkx {
+    pub fn new(api_key: String, secret_key: String, passphrase: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+            passphrase,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersOkx {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: Option<&Bytes>,
+    ) -> Builder {
+        let builder = builder.header(CONTENT_TYPE, "application/json");
+        // Public endpoints don't need authentication
+        if self.api_key.is_empty() {
+            return builder;
+        }
+
+        let path_and_query = match uri.path_and_query() {
+            Some(path_and_query) => path_and_query.as_str(),
+            None => uri.path(),
+        };
+        let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let body = body.map(|body| &body[..]).unwrap_or_default();
+        let signature = Okx::create_signature(
+            &self.secret_key,
+            &[
+                timestamp.as_bytes(),
+                request_type.as_str().as_bytes(),
+                path_and_query.as_bytes(),
+                body,
+            ],
+        );
+
+        builder
+            .header("OK-ACCESS-KEY", &self.api_key)
+            .header("OK-ACCESS-SIGN", signature)
+            .header("OK-ACCESS-TIMESTAMP", timestamp)
+            .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+/// OKX exchange client. Only spot trading is supported for now
+pub struct Okx {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerOkx, RestHeadersOkx>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(super) order_book_checksums: OrderBookChecksums,
+}
+
+impl Okx {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Okx {
+        Self {
+            rest_client: RestClient::new(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerOkx,
+                ),
+                RestHeadersOkx::new(
+                    settings.api_key.clone(),
+                    settings.secret_key.clone(),
+                    settings.passphrase.clone().unwrap_or_default(),
+                ),
+            )
+            .with_bodies_logging(settings.log_rest_bodies)
+            .with_rate_limit_retries(
+                settings.rest_rate_limit_retries,
+                lifetime_manager.stop_token(),
+            )
+            .with_transient_error_retries(settings.rest_transient_error_retries),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+            order_book_checksums: Default::default(),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://ws.okx.com:8443/ws/v5/public",
+            web_socket2_host: "wss://ws.okx.com:8443/ws/v5/private",
+            rest_host: "https://www.okx.com",
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/public/instruments");
+        builder.add_kv("instType", SPOT_INSTRUMENT_TYPE);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let symbols: Vec<OkxSymbol> = parse_data(response, "get_all_symbols")?;
+
+        Ok(symbols
+            .iter()
+            .filter(|symbol| symbol.state == "live")
+            .map(|symbol| {
+                let base = symbol.base_id.into();
+                let quote = symbol.quote_id.into();
+
+                let specific_currency_pair = symbol.id.into();
+                let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+                self.unified_to_specific
+                    .write()
+                    .insert(unified_currency_pair, specific_currency_pair);
+                self.specific_to_unified
+                    .write()
+                    .insert(specific_currency_pair, unified_currency_pair);
+
+                self.supported_currencies
+                    .insert(symbol.base_id.into(), base);
+                self.supported_currencies
+                    .insert(symbol.quote_id.into(), quote);
+
+                Arc::new(Symbol::new(
+                    false,
+                    symbol.base_id.into(),
+                    base,
+                    symbol.quote_id.into(),
+                    quote,
+                    None,
+                    None,
+                    Some(symbol.min_amount),
+                    Some(symbol.max_amount),
+                    None,
+                    base,
+                    None,
+                    Precision::ByTick {
+                        tick: symbol.price_tick,
+                    },
+                    Precision::ByTick {
+                        tick: symbol.amount_tick,
+                    },
+                ))
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let mut body = json!({
+            "instId": specific_currency_pair.as_str(),
+            "tdMode": "cash",
+            "clOrdId": header.client_order_id.as_str(),
+            "side": Self::to_okx_side(header.side),
+            "sz": header.amount.to_string(),
+        });
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                body["ordType"] = match execution_type {
+                    OrderExecutionType::MakerOnly => "post_only",
+                    _ => "limit",
+                }
+                .into();
+                body["px"] = price.to_string().into();
+            }
+            OrderOptions::User(UserOrder::Market) => {
+                body["ordType"] = "market".into();
+                // Amount of spot market buy orders is specified in quote currency by default
+                body["tgtCcy"] = "base_ccy".into();
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        let builder = UriBuilder::from_path("/api/v5/trade/order");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+        let log_args = format!("Create order for {header:?}");
+
+        self.rest_client
+            .post(
+                uri,
+                Some(Bytes::from(body.to_string())),
+                function_name!(),
+                log_args,
+            )
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let results: Vec<OkxOrderResult> = parse_data(response, "create_order")
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse orderId: {err:?}")))?;
+
+        results
+            .into_iter()
+            .next()
+            .map(|result| result.exchange_order_id)
+            .ok_or_else(|| ExchangeError::parsing("No one order id received".to_owned()))
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+        after: Option<&ExchangeOrderId>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/trade/orders-pending");
+        builder.add_kv("instType", SPOT_INSTRUMENT_TYPE);
+        if let Some(pair) = currency_pair {
+            builder.add_kv("instId", self.get_specific_currency_pair(pair));
+        }
+        if let Some(exchange_order_id) = after {
+            builder.add_kv("after", exchange_order_id.as_str());
+        }
+        builder.add_kv("limit", OPEN_ORDERS_PAGE_SIZE);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let okx_orders: Vec<OkxOrderInfo> = parse_data(response, "get_open_orders")?;
+
+        okx_orders
+            .iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    fn specific_order_info_to_unified(&self, specific: &OkxOrderInfo) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.specific_currency_pair)?,
+            specific.exchange_order_id.clone(),
+            specific.client_order_id.clone(),
+            specific.side.into(),
+            Self::get_local_order_status(specific.state)?,
+            specific.price.unwrap_or_default(),
+            specific.amount,
+            specific.average_fill_price.unwrap_or_default(),
+            specific.filled_amount.unwrap_or_default(),
+            // Fee is accumulated for all fills of order so there is no commission rate of a fill
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(state: &str) -> Result<OrderStatus> {
+        Ok(match state {
+            "live" | "partially_filled" => OrderStatus::Created,
+            "filled" => OrderStatus::Completed,
+            "canceled" | "mmp_canceled" => OrderStatus::Canceled,
+            _ => bail!("Unexpected OKX order state {state}"),
+        })
+    }
+
+    pub(super) fn get_order_role(exec_type: &str) -> Result<OrderRole> {
+        Ok(match exec_type {
+            "M" => OrderRole::Maker,
+            "T" => OrderRole::Taker,
+            _ => bail!("Unexpected OKX execution type {exec_type}"),
+        })
+    }
+
+    pub(super) fn to_okx_side(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let mut builder = UriBuilder::from_path("/api/v5/trade/order");
+        builder.add_kv(
+            "instId",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("clOrdId", client_order_id.as_str());
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let specific_orders: Vec<OkxOrderInfo> = parse_data(response, "get_order_info")?;
+
+        let order = specific_orders
+            .first()
+            .context("No one order info received")?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = json!({
+            "instId": self.get_specific_currency_pair(order.currency_pair()).as_str(),
+            "ordId": exchange_order_id.as_str(),
+        });
+
+        let builder = UriBuilder::from_path("/api/v5/trade/cancel-order");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+
+        self.rest_client
+            .post(
+                uri,
+                Some(Bytes::from(body.to_string())),
+                function_name!(),
+                log_args,
+            )
+            .await
+    }
+
+    /// Requests open orders page by page because OKX returns only `OPEN_ORDERS_PAGE_SIZE`
+    /// orders per request. Pages are ordered from the newest orders to the oldest ones.
+    pub(super) async fn request_all_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<Vec<OrderInfo>> {
+        let mut open_orders: Vec<OrderInfo> = Vec::new();
+        loop {
+            let after = open_orders.last().map(|order| &order.exchange_order_id);
+            let response = self.request_open_orders(currency_pair, after).await?;
+            let page = self.parse_open_orders(&response)?;
+
+            let is_last_page = page.len() < OPEN_ORDERS_PAGE_SIZE;
+            open_orders.extend(page);
+            if is_last_page {
+                return Ok(open_orders);
+            }
+        }
+    }
+
+    /// OKX doesn't have a request to cancel all orders, so open orders are canceled by batches
+    pub(super) async fn do_cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let open_orders = self.request_all_open_orders(Some(currency_pair)).await?;
+
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+        for orders in open_orders.chunks(CANCEL_BATCH_SIZE) {
+            let body = orders
+                .iter()
+                .map(|order| {
+                    json!({
+                        "instId": specific_currency_pair.as_str(),
+                        "ordId": order.exchange_order_id.as_str(),
+                    })
+                })
+                .collect_vec();
+
+            self.request_cancel_batch_orders(currency_pair, body)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[named]
+    async fn request_cancel_batch_orders(
+        &self,
+        currency_pair: CurrencyPair,
+        body: Vec<serde_json::Value>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v5/trade/cancel-batch-orders");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+        let log_args = format!("Cancel {} orders for {currency_pair}", body.len());
+
+        self.rest_client
+            .post(
+                uri,
+                Some(Bytes::from(serde_json::Value::from(body).to_string())),
+                function_name!(),
+                log_args,
+            )
+            .await
+    }
+
+    pub(super) fn create_signature(secret_key: &str, message_parts: &[&[u8]]) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for OKX signature");
+        for part in message_parts {
+            hmac.update(part);
+        }
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/trade/fills");
+        builder.add_kv("instType", SPOT_INSTRUMENT_TYPE);
+        builder.add_kv(
+            "instId",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("begin", date_time.timestamp_millis());
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let fills: Vec<OkxFill> = parse_data(response, "get_my_trades")?;
+
+        fills
+            .into_iter()
+            .map(|fill| {
+                Ok(OrderTrade {
+                    exchange_order_id: fill.exchange_order_id,
+                    trade_id: TradeId::String(fill.trade_id.into()),
+                    datetime: parse_timestamp(fill.timestamp)?,
+                    price: fill.price,
+                    amount: fill.amount,
+                    order_role: Self::get_order_role(fill.exec_type)?,
+                    fee_currency_code: fill.fee_currency.into(),
+                    fee_rate: None,
+                    // OKX sends charged fee as negative value
+                    fee_amount: Some(-fill.fee),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_trading_fees(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/account/trade-fee");
+        builder.add_kv("instType", SPOT_INSTRUMENT_TYPE);
+        builder.add_kv("instId", self.get_specific_currency_pair(currency_pair));
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    /// OKX sends charged fee rates as negative values and rebates as positive ones
+    pub(super) fn parse_trading_fees(response: &RestResponse) -> Result<TradingFees> {
+        #[derive(Deserialize)]
+        struct OkxTradingFees {
+            maker: Decimal,
+            taker: Decimal,
+        }
+
+        let fees: Vec<OkxTradingFees> = parse_data(response, "get_trading_fees")?;
+        let fees = fees.first().context("No one trading fee received")?;
+
+        Ok(TradingFees::new(-fees.maker, -fees.taker))
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v5/account/balance");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(response: &RestResponse) -> Result<Vec<ExchangeBalance>> {
+        let balances: Vec<OkxBalance> = parse_data(response, "get_balance")?;
+
+        Ok(balances
+            .into_iter()
+            .flat_map(|balance| balance.details)
+            .map(|balance| ExchangeBalance {
+                currency_code: balance.currency.into(),
+                balance: balance.balance,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: u32,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/market/books");
+        builder.add_kv("instId", self.get_specific_currency_pair(currency_pair));
+        builder.add_kv("sz", depth);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), format!("{currency_pair} {depth}"))
+            .await
+    }
+
+    pub(super) fn parse_order_book_snapshot(response: &RestResponse) -> Result<OrderBookData> {
+        let order_books: Vec<OkxOrderBook> = parse_data(response, "get_order_book_snapshot")?;
+        let order_book = order_books
+            .into_iter()
+            .next()
+            .context("No one order book received")?;
+
+        Ok(order_book.into())
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v5/public/time");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(response: &RestResponse) -> Result<i64> {
+        #[derive(Deserialize)]
+        struct ServerTime<'a> {
+            ts: &'a str,
+        }
+
+        let server_times: Vec<ServerTime> = parse_data(response, "get_server_time")?;
+        let server_time = server_times
+            .first()
+            .context("No one server time received")?;
+
+        server_time
+            .ts
+            .parse()
+            .with_context(|| format!("Unable to parse server time {}", server_time.ts))
+    }
+}
+
+impl From<OkxOrderBook> for OrderBookData {
+    fn from(order_book: OkxOrderBook) -> Self {
+        let to_sorted_data = |levels: Vec<OkxOrderBookLevel>| -> SortedOrderData {
+            levels
+                .into_iter()
+                .map(|OkxOrderBookLevel(price, amount, ..)| (price, amount))
+                .collect()
+        };
+
+        OrderBookData::new(
+            to_sorted_data(order_book.asks),
+            to_sorted_data(order_book.bids),
+        )
+    }
+}
+
+/// Extracts data from OKX response envelope
+pub(crate) fn parse_data<'a, T: Deserialize<'a>>(
+    response: &'a RestResponse,
+    request_name: &str,
+) -> Result<Vec<T>> {
+    let response: OkxResponse<T> = serde_json::from_str(&response.content)
+        .with_context(|| format!("Unable to parse response content for {request_name} request"))?;
+
+    Ok(response.data)
+}
+
+/// OKX sends timestamps as strings with count of milliseconds since UNIX epoch
+pub(crate) fn parse_timestamp(timestamp: &str) -> Result<DateTime> {
+    let millis = timestamp
+        .parse()
+        .with_context(|| format!("Unable to parse timestamp {timestamp}"))?;
+
+    Ok(u64_to_date_time(millis))
+}
+
+pub struct OkxBuilder;
+
+impl ExchangeClientBuilder for OkxBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Okx::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: true,
+                    supports_stop_loss_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: true,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    // text pings are sent by timer, so silence means that connection is broken
+                    stale_timeout: Some(Duration::from_secs(30)),
+                    ..WebSocketOptions::default()
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Trading endpoints allow 60 requests per 2 seconds
+        RequestTimeoutArguments::from_requests_per_minute(1800)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Okx".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use rust_decimal_macros::dec;
+
+    fn response(content: &str) -> RestResponse {
+        RestResponse {
+            status: StatusCode::OK,
+            content: content.to_owned(),
+        }
+    }
+
+    #[test]
+    fn generate_signature() {
+        let signature = Okx::create_signature(
+            "22582BD0CFF14C41EDBF1AB98506286D",
+            &[
+                b"2020-12-08T09:08:57.715Z",
+                b"GET",
+                b"/api/v5/account/balance?ccy=BTC",
+                b"",
+            ],
+        );
+
+        assert_eq!(signature, "HiZhvSfMtWJA3uUIVXV3a/bSXNPCWvYFXoGCVS8V4zY=");
+    }
+
+    #[test]
+    fn generate_signature_with_body() {
+        let signature = Okx::create_signature(
+            "22582BD0CFF14C41EDBF1AB98506286D",
+            &[
+                b"2020-12-08T09:08:57.715Z",
+                b"POST",
+                b"/api/v5/trade/order",
+                br#"{"instId":"BTC-USDT"}"#,
+            ],
+        );
+
+        assert_eq!(signature, "YQ/tkEzvXm0I2aOIDXzW4cvOJ+Hn6Vy0Xqh6kJ1Nu7g=");
+    }
+
+    #[test]
+    fn check_order_error() {
+        let error = ErrorHandlerOkx
+            .check_spec_rest_error(&response(
+                r#"{"code":"1","msg":"Operation failed.","data":[{"clOrdId":"1","ordId":"","sCode":"51008","sMsg":"Order placement failed due to insufficient balance"}]}"#,
+            ))
+            .expect_err("in test");
+
+        assert_eq!(error.code, Some(51008));
+        assert_eq!(
+            ErrorHandlerOkx.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+        assert!(ErrorHandlerOkx
+            .check_spec_rest_error(&response(r#"{"code":"0","msg":"","data":[]}"#))
+            .is_ok());
+    }
+
+    #[test]
+    fn parse_trading_fees() {
+        let fees = Okx::parse_trading_fees(&response(
+            r#"{"code":"0","msg":"","data":[{"instType":"SPOT","maker":"-0.0008","taker":"-0.001","ts":"1637634400000"}]}"#,
+        ))
+        .expect("in test");
+
+        assert_eq!(fees, TradingFees::new(dec!(0.0008), dec!(0.001)));
+    }
+
+    #[test]
+    fn parse_balance() {
+        let balances = Okx::parse_get_balance(&response(
+            r#"{"code":"0","msg":"","data":[{"totalEq":"41624.32","details":[
+                {"ccy":"USDT","cashBal":"4850.85","availBal":"4834.32","frozenBal":"16.53"},
+                {"ccy":"BTC","cashBal":"0.5","availBal":"0.5","frozenBal":"0"}
+            ]}]}"#,
+        ))
+        .expect("in test");
+
+        assert_eq!(
+            balances
+                .iter()
+                .map(|x| (x.currency_code, x.balance))
+                .collect_vec(),
+            vec![("usdt".into(), dec!(4850.85)), ("btc".into(), dec!(0.5))]
+        );
+    }
+
+    #[test]
+    fn parse_order_book_snapshot() {
+        let order_book = Okx::parse_order_book_snapshot(&response(
+            r#"{"code":"0","msg":"","data":[{
+                "asks":[["41006.8","0.60038921","0","1"],["41007","0.1","0","2"]],
+                "bids":[["41006.3","0.30178218","0","2"]],
+                "ts":"1629966436396"
+            }]}"#,
+        ))
+        .expect("in test");
+
+        assert_eq!(
+            order_book,
+            OrderBookData::new(
+                SortedOrderData::from([
+                    (dec!(41006.8), dec!(0.60038921)),
+                    (dec!(41007), dec!(0.1)),
+                ]),
+                SortedOrderData::from([(dec!(41006.3), dec!(0.30178218))]),
+            )
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use mmb_domain::market::SpecificCurrencyPair;
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Count of price levels per side used by OKX for checksum calculation
+const CHECKSUM_DEPTH: usize = 25;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum ChecksumCheck {
+    Valid,
+    /// Update is received while waiting for a fresh snapshot
+    Discard,
+    /// Local order book diverged from exchange one so a fresh snapshot is needed
+    Mismatch {
+        expected: i32,
+        actual: i32,
+    },
+}
+
+/// Keeps local copies of order books by currency pair to validate checksums of depth updates
+#[derive(Debug, Default)]
+pub(crate) struct OrderBookChecksums {
+    by_currency_pair: Mutex<HashMap<SpecificCurrencyPair, OrderBookData>>,
+}
+
+impl OrderBookChecksums {
+    pub(crate) fn apply_snapshot(
+        &self,
+        currency_pair: SpecificCurrencyPair,
+        snapshot: &OrderBookData,
+        expected: i32,
+    ) -> ChecksumCheck {
+        let mut by_currency_pair = self.by_currency_pair.lock();
+        let check = Self::check(snapshot, expected);
+        if check == ChecksumCheck::Valid {
+            let _ = by_currency_pair.insert(currency_pair, snapshot.clone());
+        } else {
+            let _ = by_currency_pair.remove(&currency_pair);
+        }
+
+        check
+    }
+
+    pub(crate) fn apply_update(
+        &self,
+        currency_pair: SpecificCurrencyPair,
+        update: &OrderBookData,
+        expected: i32,
+    ) -> ChecksumCheck {
+        let mut by_currency_pair = self.by_currency_pair.lock();
+        let order_book = match by_currency_pair.get_mut(&currency_pair) {
+            Some(order_book) => order_book,
+            None => return ChecksumCheck::Discard,
+        };
+
+        OrderBookData::apply_update(&mut order_book.asks, &mut order_book.bids, update);
+
+        let check = Self::check(order_book, expected);
+        if check != ChecksumCheck::Valid {
+            let _ = by_currency_pair.remove(&currency_pair);
+        }
+
+        check
+    }
+
+    pub(crate) fn reset(&self) {
+        self.by_currency_pair.lock().clear();
+    }
+
+    fn check(order_book: &OrderBookData, expected: i32) -> ChecksumCheck {
+        let actual = calculate_checksum(order_book);
+        match actual == expected {
+            true => ChecksumCheck::Valid,
+            false => ChecksumCheck::Mismatch { expected, actual },
+        }
+    }
+}
+
+/// CRC32 of best bid and ask levels interleaved as `bidPx:bidSz:askPx:askSz`.
+/// Levels of a longer side are appended as is when the other side is exhausted
+/// https://www.okx.com/docs-v5/en/#websocket-api-public-channel-order-book-channel
+pub(crate) fn calculate_checksum(order_book: &OrderBookData) -> i32 {
+    let mut bids = order_book.bids.iter().rev().take(CHECKSUM_DEPTH);
+    let mut asks = order_book.asks.iter().take(CHECKSUM_DEPTH);
+
+    let mut levels = String::with_capacity(CHECKSUM_DEPTH * 4 * 16);
+    loop {
+        let bid = bids.next();
+        let ask = asks.next();
+        if bid.is_none() && ask.is_none() {
+            break;
+        }
+
+        for (price, amount) in bid.into_iter().chain(ask) {
+            if !levels.is_empty() {
+                levels.push(':');
+            }
+            write!(levels, "{price}:{amount}").expect("Writing to String can't fail");
+        }
+    }
+
+    crc32fast::hash(levels.as_bytes()) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::order::snapshot::SortedOrderData;
+    use rust_decimal_macros::dec;
+
+    fn currency_pair() -> SpecificCurrencyPair {
+        "BTC-USDT".into()
+    }
+
+    fn order_book() -> OrderBookData {
+        OrderBookData::new(
+            SortedOrderData::from([(dec!(3366.8), dec!(9)), (dec!(3368), dec!(8))]),
+            SortedOrderData::from([(dec!(3366.1), dec!(7)), (dec!(3366), dec!(6))]),
+        )
+    }
+
+    #[test]
+    fn checksum_of_equal_sides() {
+        // Example from OKX docs: "3366.1:7:3366.8:9:3366:6:3368:8"
+        assert_eq!(calculate_checksum(&order_book()), -1881014294);
+    }
+
+    #[test]
+    fn checksum_of_unequal_sides() {
+        // "3366.1:7:3366.8:9:3366:6:3365.5:2"
+        let order_book = OrderBookData::new(
+            SortedOrderData::from([(dec!(3366.8), dec!(9))]),
+            SortedOrderData::from([
+                (dec!(3366.1), dec!(7)),
+                (dec!(3366), dec!(6)),
+                (dec!(3365.5), dec!(2)),
+            ]),
+        );
+
+        assert_eq!(calculate_checksum(&order_book), 168259878);
+    }
+
+    #[test]
+    fn valid_update_applied() {
+        let checksums = OrderBookChecksums::default();
+        assert_eq!(
+            checksums.apply_snapshot(currency_pair(), &order_book(), -1881014294),
+            ChecksumCheck::Valid
+        );
+
+        let update = OrderBookData::new(
+            SortedOrderData::new(),
+            SortedOrderData::from([(dec!(3366), dec!(5))]),
+        );
+        assert_eq!(
+            checksums.apply_update(currency_pair(), &update, 24049673),
+            ChecksumCheck::Valid
+        );
+    }
+
+    #[test]
+    fn mismatch_discards_updates_until_snapshot() {
+        let checksums = OrderBookChecksums::default();
+        let _ = checksums.apply_snapshot(currency_pair(), &order_book(), -1881014294);
+
+        let update = OrderBookData::new(
+            SortedOrderData::new(),
+            SortedOrderData::from([(dec!(3366), dec!(5))]),
+        );
+        assert_eq!(
+            checksums.apply_update(currency_pair(), &update, 42),
+            ChecksumCheck::Mismatch {
+                expected: 42,
+                actual: 24049673,
+            }
+        );
+        assert_eq!(
+            checksums.apply_update(currency_pair(), &update, 24049673),
+            ChecksumCheck::Discard
+        );
+
+        assert_eq!(
+            checksums.apply_snapshot(currency_pair(), &order_book(), -1881014294),
+            ChecksumCheck::Valid
+        );
+    }
+}
@@ -0,0 +1,594 @@
+use crate::okx::{parse_timestamp, Okx, SPOT_INSTRUMENT_TYPE};
+use crate::order_book_checksum::ChecksumCheck;
+use crate::types::{OkxOrderBook, OkxOrderInfo, OkxTrade};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::infrastructure::spawn_by_timer;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+const BOOKS_CHANNEL: &str = "books";
+const TRADES_CHANNEL: &str = "trades";
+const ORDERS_CHANNEL: &str = "orders";
+/// OKX closes connection if there were no messages during 30 seconds, so client should send text
+/// `ping` more often than that. Server responds with text `pong`
+const PING_MESSAGE: &str = "ping";
+const PING_PERIOD: Duration = Duration::from_secs(20);
+
+#[async_trait]
+impl Support for Okx {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    async fn initialized(&self, exchange: Arc<Exchange>) {
+        self.start_sending_pings(&exchange);
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        // Response on text ping message
+        if msg == "pong" {
+            return Ok(());
+        }
+
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{}", msg))?;
+
+        match message {
+            WebsocketMessage::Event(event) => self.handle_event(event)?,
+            WebsocketMessage::Push { arg } => match arg.channel {
+                BOOKS_CHANNEL => self.handle_order_book(serde_json::from_str(msg)?)?,
+                TRADES_CHANNEL => self.handle_trades(serde_json::from_str(msg)?)?,
+                ORDERS_CHANNEL => self.handle_orders(serde_json::from_str(msg)?)?,
+                channel => bail!("Unsupported OKX websocket channel {channel}: {msg}"),
+            },
+            WebsocketMessage::Unknown(_) => bail!("Unsupported OKX websocket message: {msg}"),
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let market_data_subscriptions = {
+            let traded_currencies = self.traded_specific_currencies.lock();
+            let channels = traded_currencies
+                .iter()
+                .flat_map(|&currency_pair| {
+                    [BOOKS_CHANNEL, TRADES_CHANNEL]
+                        .map(|channel| Channel::with_instrument(channel, currency_pair))
+                })
+                .collect();
+            Self::create_request(Operation::Subscribe, channels)
+        };
+        (self.websocket_message_callback)(WebSocketRole::Main, market_data_subscriptions)?;
+
+        // Private channels are available only after login
+        if self.is_websocket_enabled(WebSocketRole::Secondary) {
+            (self.websocket_message_callback)(WebSocketRole::Secondary, self.create_login())?;
+        }
+
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        self.order_book_checksums.reset();
+
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""channel":"orders""#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+}
+
+impl Okx {
+    fn start_sending_pings(&self, exchange: &Arc<Exchange>) {
+        let exchange_wk = Arc::downgrade(exchange);
+        spawn_by_timer(
+            "Send OKX websocket pings",
+            PING_PERIOD,
+            PING_PERIOD,
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            move || {
+                let exchange_wk = exchange_wk.clone();
+                async move {
+                    let exchange = match exchange_wk.upgrade() {
+                        None => return,
+                        Some(v) => v,
+                    };
+
+                    exchange
+                        .exchange_client
+                        .as_any()
+                        .downcast_ref::<Okx>()
+                        .expect(
+                            "received non OKX exchange client in method of sending pings by timer",
+                        )
+                        .send_pings();
+                }
+            },
+        );
+    }
+
+    /// Send text ping to every enabled websocket
+    pub(crate) fn send_pings(&self) {
+        for role in [WebSocketRole::Main, WebSocketRole::Secondary] {
+            if !self.is_websocket_enabled(role) {
+                continue;
+            }
+
+            // websocket can be reconnecting at the moment, so ping will be sent by next timer tick
+            if let Err(err) = (self.websocket_message_callback)(role, PING_MESSAGE.to_owned()) {
+                log::debug!(
+                    "Unable to send ping to {role:?} websocket on {}: {err:?}",
+                    self.settings.exchange_account_id
+                );
+            }
+        }
+    }
+
+    fn handle_event(&self, event: EventMessage) -> Result<()> {
+        match event.event {
+            "login" if event.code == "0" => {
+                log::info!("OKX websocket: successful login");
+                let subscriptions = Self::create_request(
+                    Operation::Subscribe,
+                    vec![Channel {
+                        channel: ORDERS_CHANNEL,
+                        inst_id: None,
+                        inst_type: Some(SPOT_INSTRUMENT_TYPE),
+                    }],
+                );
+
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscriptions)
+            }
+            "subscribe" | "unsubscribe" => {
+                log::info!("OKX websocket: successful {} {:?}", event.event, event.arg);
+                Ok(())
+            }
+            _ => {
+                let err = format!(
+                    "OKX websocket: {} failed with code {}: {}",
+                    event.event, event.code, event.msg
+                );
+                log::error!("{err}");
+                bail!(err)
+            }
+        }
+    }
+
+    fn handle_order_book(&self, push: PushMessage<OkxOrderBook>) -> Result<()> {
+        let specific_currency_pair = push
+            .arg
+            .inst_id
+            .context("No instrument id in order book message")?;
+
+        for order_book in push.data {
+            let checksum = order_book
+                .checksum
+                .context("No checksum in order book message")?;
+            let order_book: OrderBookData = order_book.into();
+
+            let (check, event_type) = match push.action {
+                Some("snapshot") => (
+                    self.order_book_checksums.apply_snapshot(
+                        specific_currency_pair,
+                        &order_book,
+                        checksum,
+                    ),
+                    EventType::Snapshot,
+                ),
+                Some("update") => (
+                    self.order_book_checksums.apply_update(
+                        specific_currency_pair,
+                        &order_book,
+                        checksum,
+                    ),
+                    EventType::Update,
+                ),
+                action => bail!("Unexpected order book action {action:?}"),
+            };
+
+            match check {
+                ChecksumCheck::Valid => {
+                    self.send_order_book_event(specific_currency_pair, order_book, event_type)?
+                }
+                ChecksumCheck::Discard => {}
+                ChecksumCheck::Mismatch { expected, actual } => {
+                    log::warn!("OKX order book checksum mismatch for {specific_currency_pair}: expected {expected}, actual {actual}. Resubscribing");
+                    return self.resubscribe_order_book(specific_currency_pair);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Exchange sends a fresh snapshot on subscription
+    fn resubscribe_order_book(&self, currency_pair: SpecificCurrencyPair) -> Result<()> {
+        for operation in [Operation::Unsubscribe, Operation::Subscribe] {
+            let request = Self::create_request(
+                operation,
+                vec![Channel::with_instrument(BOOKS_CHANNEL, currency_pair)],
+            );
+            (self.websocket_message_callback)(WebSocketRole::Main, request)?;
+        }
+
+        Ok(())
+    }
+
+    fn send_order_book_event(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        order_book: OrderBookData,
+        update_type: EventType,
+    ) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            update_type,
+            Arc::new(order_book),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trades(&self, push: PushMessage<OkxTrade>) -> Result<()> {
+        for trade in push.data {
+            (self.handle_trade_callback)(
+                self.get_unified_currency_pair(&trade.specific_currency_pair)?,
+                Trade {
+                    trade_id: TradeId::String(trade.trade_id.into()),
+                    price: trade.price,
+                    quantity: trade.amount,
+                    side: trade.side.into(),
+                    transaction_time: parse_timestamp(trade.timestamp)?,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn handle_orders(&self, push: PushMessage<OkxOrderInfo>) -> Result<()> {
+        for order in push.data {
+            match order.state {
+                "live" => (self.order_created_callback)(
+                    order.client_order_id,
+                    order.exchange_order_id,
+                    EventSourceType::WebSocket,
+                ),
+                "partially_filled" | "filled" => {
+                    let fill_event = Self::create_fill_event(order)?;
+                    (self.handle_order_filled_callback)(fill_event);
+                }
+                "canceled" | "mmp_canceled" => (self.order_cancelled_callback)(
+                    order.client_order_id,
+                    order.exchange_order_id,
+                    EventSourceType::WebSocket,
+                ),
+                state => bail!("Unexpected OKX order state {state}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_fill_event(order: OkxOrderInfo) -> Result<FillEvent> {
+        let fill_amount = order.fill_amount.context("No fill amount in order fill")?;
+        let fill_price = order.fill_price.context("No fill price in order fill")?;
+
+        Ok(FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::String(order.trade_id.into())),
+            client_order_id: Some(order.client_order_id),
+            exchange_order_id: order.exchange_order_id,
+            fill_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount,
+                total_filled_amount: order.filled_amount,
+            },
+            order_role: Some(Self::get_order_role(order.exec_type)?),
+            commission_currency_code: Some(order.fill_fee_currency.into()),
+            commission_rate: None,
+            // OKX sends charged fee as negative value
+            commission_amount: order.fill_fee.map(|fee| -fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(parse_timestamp(order.fill_time)?),
+        })
+    }
+
+    fn create_login(&self) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_secs()
+            .to_string();
+        let signature = Okx::create_signature(
+            &self.settings.secret_key,
+            &[timestamp.as_bytes(), b"GET", b"/users/self/verify"],
+        );
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Login<'a> {
+            api_key: &'a str,
+            passphrase: &'a str,
+            timestamp: &'a str,
+            sign: &'a str,
+        }
+        let request = Request {
+            operation: Operation::Login,
+            args: vec![Login {
+                api_key: &self.settings.api_key,
+                passphrase: self.settings.passphrase.as_deref().unwrap_or_default(),
+                timestamp: &timestamp,
+                sign: &signature,
+            }],
+        };
+
+        serde_json::to_string(&request).expect("Failed to serialize OKX login message")
+    }
+
+    fn create_request(operation: Operation, channels: Vec<Channel>) -> String {
+        let request = Request {
+            operation,
+            args: channels,
+        };
+
+        serde_json::to_string(&request).expect("Failed to serialize subscription message")
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+#[serde(untagged)]
+enum WebsocketMessage<'a> {
+    Event(EventMessage<'a>),
+    Push { arg: Channel<'a> },
+    Unknown(IgnoredAny),
+}
+
+/// Response on operation request or error
+/// {"event": "login", "code": "0", "msg": ""}
+/// {"event": "subscribe", "arg": {"channel": "books", "instId": "BTC-USDT"}}
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+struct EventMessage<'a> {
+    event: &'a str,
+    #[serde(default)]
+    code: &'a str,
+    #[serde(default)]
+    msg: String,
+    #[serde(default)]
+    arg: Option<Value>,
+}
+
+/// Data of subscribed channel
+/// {"arg": {"channel": "books", "instId": "BTC-USDT"}, "action": "snapshot", "data": []}
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a, T: Deserialize<'de>"))]
+struct PushMessage<'a, T> {
+    arg: Channel<'a>,
+    #[serde(default)]
+    action: Option<&'a str>,
+    data: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+struct Channel<'a> {
+    channel: &'a str,
+    #[serde(rename = "instId", default, skip_serializing_if = "Option::is_none")]
+    inst_id: Option<SpecificCurrencyPair>,
+    #[serde(rename = "instType", default, skip_serializing_if = "Option::is_none")]
+    inst_type: Option<&'a str>,
+}
+
+impl<'a> Channel<'a> {
+    fn with_instrument(channel: &'a str, currency_pair: SpecificCurrencyPair) -> Self {
+        Self {
+            channel,
+            inst_id: Some(currency_pair),
+            inst_type: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<T> {
+    #[serde(rename = "op")]
+    operation: Operation,
+    args: Vec<T>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum Operation {
+    Subscribe,
+    Unsubscribe,
+    Login,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use parking_lot::Mutex;
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn pings_are_sent_to_all_enabled_websockets() {
+        let settings = ExchangeSettings::new_short(
+            "OKX_0".parse().expect("in test"),
+            "api_key".into(),
+            "secret_key".into(),
+            false,
+        );
+        let (events_sender, _events_receiver) = broadcast::channel(10);
+        let mut okx = Okx::new(
+            settings,
+            events_sender,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+        let sent_messages = Arc::new(Mutex::new(Vec::new()));
+        okx.set_send_websocket_message_callback(Box::new({
+            let sent_messages = sent_messages.clone();
+            move |role, message| {
+                sent_messages.lock().push((role, message));
+                Ok(())
+            }
+        }));
+
+        okx.send_pings();
+
+        assert_eq!(
+            *sent_messages.lock(),
+            vec![
+                (WebSocketRole::Main, "ping".to_owned()),
+                (WebSocketRole::Secondary, "ping".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn create_subscription_request() {
+        let request = Okx::create_request(
+            Operation::Subscribe,
+            vec![
+                Channel::with_instrument(BOOKS_CHANNEL, "BTC-USDT".into()),
+                Channel {
+                    channel: ORDERS_CHANNEL,
+                    inst_id: None,
+                    inst_type: Some(SPOT_INSTRUMENT_TYPE),
+                },
+            ],
+        );
+
+        assert_eq!(
+            request,
+            r#"{"op":"subscribe","args":[{"channel":"books","instId":"BTC-USDT"},{"channel":"orders","instType":"SPOT"}]}"#
+        );
+    }
+
+    #[test]
+    fn parse_order_book_push() {
+        let msg = r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"update","data":[{"asks":[["8476.98","415","0","13"]],"bids":[],"ts":"1597026383085","checksum":-855196043}]}"#;
+
+        match serde_json::from_str(msg).expect("in test") {
+            WebsocketMessage::Push { arg } => assert_eq!(arg.channel, BOOKS_CHANNEL),
+            message => panic!("Unexpected message {message:?}"),
+        }
+
+        let push: PushMessage<OkxOrderBook> = serde_json::from_str(msg).expect("in test");
+        assert_eq!(push.action, Some("update"));
+        assert_eq!(push.data[0].checksum, Some(-855196043));
+    }
+
+    #[test]
+    fn parse_event() {
+        let msg = r#"{"event":"login","code":"0","msg":""}"#;
+
+        match serde_json::from_str(msg).expect("in test") {
+            WebsocketMessage::Event(event) => {
+                assert_eq!(event.event, "login");
+                assert_eq!(event.code, "0");
+            }
+            message => panic!("Unexpected message {message:?}"),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use mmb_domain::market::SpecificCurrencyPair;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use rust_decimal::Decimal;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer};
+
+/// Envelope of all OKX REST responses. Request is successful if `code` is "0".
+/// Errors are checked by `ErrorHandlerOkx` so only data is deserialized here
+/// {
+/// "code": "0",
+/// "msg": "",
+/// "data": []
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct OkxResponse<T> {
+    pub(crate) data: Vec<T>,
+}
+
+/// Result of a single order operation. OKX returns it for create and cancel requests
+/// {
+/// "clOrdId": "oktswap6",
+/// "ordId": "312269865356374016",
+/// "tag": "",
+/// "sCode": "0",
+/// "sMsg": ""
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct OkxOrderResult {
+    #[serde(rename = "ordId")]
+    pub(crate) exchange_order_id: ExchangeOrderId,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum OkxOrderSide {
+    Buy,
+    Sell,
+}
+
+impl From<OkxOrderSide> for OrderSide {
+    fn from(side: OkxOrderSide) -> Self {
+        match side {
+            OkxOrderSide::Buy => OrderSide::Buy,
+            OkxOrderSide::Sell => OrderSide::Sell,
+        }
+    }
+}
+
+/// OKX instrument description
+/// {
+/// "instType": "SPOT",
+/// "instId": "BTC-USDT",
+/// "baseCcy": "BTC",
+/// "quoteCcy": "USDT",
+/// "tickSz": "0.1", // Tick size of price
+/// "lotSz": "0.00000001", // Lot size of amount
+/// "minSz": "0.00001", // Minimum order amount
+/// "maxLmtSz": "9999999999", // Maximum amount of limit order
+/// "state": "live" // live, suspend, preopen, test
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct OkxSymbol<'a> {
+    #[serde(rename = "instId")]
+    pub(crate) id: &'a str,
+    #[serde(rename = "baseCcy")]
+    pub(crate) base_id: &'a str,
+    #[serde(rename = "quoteCcy")]
+    pub(crate) quote_id: &'a str,
+    #[serde(rename = "tickSz")]
+    pub(crate) price_tick: Decimal,
+    #[serde(rename = "lotSz")]
+    pub(crate) amount_tick: Decimal,
+    #[serde(rename = "minSz")]
+    pub(crate) min_amount: Amount,
+    #[serde(rename = "maxLmtSz")]
+    pub(crate) max_amount: Amount,
+    pub(crate) state: &'a str,
+}
+
+/// OKX order description used by REST responses and `orders` websocket channel.
+/// Empty strings are sent for fields without value
+/// {
+/// "instId": "BTC-USDT",
+/// "ordId": "312269865356374016",
+/// "clOrdId": "b1",
+/// "px": "999", // Empty for market orders
+/// "sz": "3",
+/// "side": "buy",
+/// "ordType": "limit",
+/// "state": "partially_filled", // live, partially_filled, filled, canceled
+/// "accFillSz": "1", // Accumulated filled amount
+/// "avgPx": "999", // Average filled price
+/// "fillPx": "999", // Price of the last fill
+/// "fillSz": "1", // Amount of the last fill
+/// "tradeId": "123", // Trade id of the last fill
+/// "execType": "M", // Liquidity role of the last fill: T (taker), M (maker)
+/// "fee": "-0.001", // Accumulated fee, negative value means that fee is charged
+/// "feeCcy": "BTC",
+/// "fillTime": "1597026383085",
+/// "uTime": "1597026383085"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct OkxOrderInfo<'a> {
+    #[serde(rename = "instId")]
+    pub(crate) specific_currency_pair: SpecificCurrencyPair,
+    #[serde(rename = "ordId")]
+    pub(crate) exchange_order_id: ExchangeOrderId,
+    #[serde(rename = "clOrdId")]
+    pub(crate) client_order_id: ClientOrderId,
+    #[serde(rename = "px", deserialize_with = "optional_decimal")]
+    pub(crate) price: Option<Price>,
+    #[serde(rename = "sz")]
+    pub(crate) amount: Amount,
+    pub(crate) side: OkxOrderSide,
+    pub(crate) state: &'a str,
+    #[serde(rename = "accFillSz", deserialize_with = "optional_decimal")]
+    pub(crate) filled_amount: Option<Amount>,
+    #[serde(rename = "avgPx", deserialize_with = "optional_decimal")]
+    pub(crate) average_fill_price: Option<Price>,
+    #[serde(rename = "fillPx", default, deserialize_with = "optional_decimal")]
+    pub(crate) fill_price: Option<Price>,
+    #[serde(rename = "fillSz", default, deserialize_with = "optional_decimal")]
+    pub(crate) fill_amount: Option<Amount>,
+    #[serde(rename = "tradeId", default)]
+    pub(crate) trade_id: &'a str,
+    #[serde(rename = "execType", default)]
+    pub(crate) exec_type: &'a str,
+    #[serde(rename = "fillFee", default, deserialize_with = "optional_decimal")]
+    pub(crate) fill_fee: Option<Decimal>,
+    #[serde(rename = "fillFeeCcy", default)]
+    pub(crate) fill_fee_currency: &'a str,
+    #[serde(rename = "fillTime", default)]
+    pub(crate) fill_time: &'a str,
+}
+
+/// OKX fill description
+/// {
+/// "instId": "BTC-USDT",
+/// "tradeId": "123",
+/// "ordId": "312269865356374016",
+/// "clOrdId": "b1",
+/// "fillPx": "999",
+/// "fillSz": "1",
+/// "side": "buy",
+/// "execType": "M",
+/// "fee": "-0.001",
+/// "feeCcy": "BTC",
+/// "ts": "1597026383085"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct OkxFill<'a> {
+    #[serde(rename = "tradeId")]
+    pub(crate) trade_id: &'a str,
+    #[serde(rename = "ordId")]
+    pub(crate) exchange_order_id: ExchangeOrderId,
+    #[serde(rename = "fillPx")]
+    pub(crate) price: Price,
+    #[serde(rename = "fillSz")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "execType")]
+    pub(crate) exec_type: &'a str,
+    pub(crate) fee: Decimal,
+    #[serde(rename = "feeCcy")]
+    pub(crate) fee_currency: &'a str,
+    #[serde(rename = "ts")]
+    pub(crate) timestamp: &'a str,
+}
+
+/// Balance of trading account. Only fields we use are listed
+/// {
+/// "totalEq": "41624.32",
+/// "details": [{ "ccy": "USDT", "cashBal": "4850.85", "availBal": "4834.32", "frozenBal": "16.53" }]
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct OkxBalance<'a> {
+    pub(crate) details: Vec<OkxCurrencyBalance<'a>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct OkxCurrencyBalance<'a> {
+    #[serde(rename = "ccy")]
+    pub(crate) currency: &'a str,
+    #[serde(rename = "cashBal")]
+    pub(crate) balance: Decimal,
+}
+
+/// Order book level: price, amount, deprecated liquidated orders count, orders count
+/// ["411.8", "10", "0", "4"]
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+pub(crate) struct OkxOrderBookLevel(
+    pub(crate) Price,
+    pub(crate) Amount,
+    pub(crate) IgnoredAny,
+    pub(crate) IgnoredAny,
+);
+
+/// Order book snapshot or update. The same structure is used by REST and websocket API.
+/// Checksum is sent via websocket only
+/// {
+/// "asks": [["8476.98", "415", "0", "13"]],
+/// "bids": [["8476.97", "256", "0", "12"]],
+/// "ts": "1597026383085",
+/// "checksum": -855196043
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct OkxOrderBook {
+    pub(crate) asks: Vec<OkxOrderBookLevel>,
+    pub(crate) bids: Vec<OkxOrderBookLevel>,
+    #[serde(default)]
+    pub(crate) checksum: Option<i32>,
+}
+
+/// Public trade
+/// {
+/// "instId": "BTC-USDT",
+/// "tradeId": "130639474",
+/// "px": "42219.9",
+/// "sz": "0.12060306",
+/// "side": "buy",
+/// "ts": "1630048897897"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct OkxTrade<'a> {
+    #[serde(rename = "instId")]
+    pub(crate) specific_currency_pair: SpecificCurrencyPair,
+    #[serde(rename = "tradeId")]
+    pub(crate) trade_id: &'a str,
+    #[serde(rename = "px")]
+    pub(crate) price: Price,
+    #[serde(rename = "sz")]
+    pub(crate) amount: Amount,
+    pub(crate) side: OkxOrderSide,
+    #[serde(rename = "ts")]
+    pub(crate) timestamp: &'a str,
+}
+
+/// OKX sends empty string instead of null for absent values
+fn optional_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: &str = Deserialize::deserialize(deserializer)?;
+    match value {
+        "" => Ok(None),
+        value => value.parse().map(Some).map_err(serde::de::Error::custom),
+    }
+}
@@ -0,0 +1,22 @@
+use crate::okx::okx_builder::OkxBuilder;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::logger::init_logger;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_balance_successfully() {
+    init_logger();
+
+    let okx_builder = match OkxBuilder::build_account().await {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+
+    let result = okx_builder
+        .exchange
+        .get_balance(CancellationToken::default())
+        .await;
+
+    log::info!("Balance: {result:?}");
+
+    assert!(result.is_ok());
+}
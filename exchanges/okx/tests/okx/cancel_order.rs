@@ -0,0 +1,112 @@
+use crate::okx::okx_builder::OkxBuilder;
+use core_tests::order::OrderProxy;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_domain::market::ExchangeErrorType;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::logger::init_logger;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cancelled_successfully() {
+    init_logger();
+
+    let okx_builder = match OkxBuilder::build_account().await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    let mut order_proxy = OrderProxy::new(
+        okx_builder.exchange.exchange_account_id,
+        Some("FromCancelledSuccessfullyTest".to_owned()),
+        CancellationToken::default(),
+        okx_builder.min_price,
+        okx_builder.min_amount,
+        okx_builder.default_currency_pair,
+    );
+    order_proxy.timeout = Duration::from_secs(15);
+
+    let order_ref = order_proxy
+        .create_order(okx_builder.exchange.clone())
+        .await
+        .expect("Create order failed with error:");
+
+    order_proxy
+        .cancel_order_or_fail(&order_ref, okx_builder.exchange.clone())
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cancel_all() {
+    init_logger();
+
+    // More orders than fit into one batch of cancellation request
+    const ORDERS_COUNT: usize = 21;
+
+    let okx_builder = match OkxBuilder::build_account().await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    for _ in 0..ORDERS_COUNT {
+        let mut order_proxy = OrderProxy::new(
+            okx_builder.exchange.exchange_account_id,
+            Some("FromCancelAllTest".to_owned()),
+            CancellationToken::default(),
+            okx_builder.min_price,
+            okx_builder.min_amount,
+            okx_builder.default_currency_pair,
+        );
+        order_proxy.timeout = Duration::from_secs(15);
+
+        order_proxy
+            .create_order(okx_builder.exchange.clone())
+            .await
+            .expect("Create order failed with error:");
+    }
+
+    okx_builder
+        .exchange
+        .cancel_all_orders(okx_builder.default_currency_pair)
+        .await
+        .expect("Failed to cancel all orders");
+
+    let orders = &okx_builder
+        .exchange
+        .get_open_orders(false)
+        .await
+        .expect("Opened orders not found for exchange account id");
+
+    assert_eq!(orders.len(), 0);
+}
+
+/// Test for situation when we're trying to cancel an order that's not exist in exchange
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn nothing_to_cancel() {
+    init_logger();
+
+    let okx_builder = match OkxBuilder::build_account().await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    let mut order_proxy = OrderProxy::new(
+        okx_builder.exchange.exchange_account_id,
+        Some("FromNothingToCancelTest".to_owned()),
+        CancellationToken::default(),
+        okx_builder.min_price,
+        okx_builder.min_amount,
+        okx_builder.default_currency_pair,
+    );
+    order_proxy.timeout = Duration::from_secs(15);
+
+    let order_to_cancel = order_proxy.created_order_ref_stub(okx_builder.exchange.orders.clone());
+
+    let cancel_outcome = okx_builder
+        .exchange
+        .cancel_order(&order_to_cancel, CancellationToken::default())
+        .await
+        .expect("in test");
+    if let RequestResult::Error(error) = cancel_outcome.outcome {
+        assert_eq!(error.error_type, ExchangeErrorType::OrderNotFound);
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::{bail, Result};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestsTimeoutManagerFactory;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::lifecycle::launcher::EngineBuildConfig;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_utils::hashmap;
+use okx::okx::OkxBuilder;
+use std::sync::Arc;
+
+pub(crate) fn default_currency_pair() -> CurrencyPair {
+    CurrencyPair::from_codes("btc".into(), "usdt".into())
+}
+
+pub(crate) fn get_timeout_manager(exchange_account_id: ExchangeAccountId) -> Arc<TimeoutManager> {
+    let engine_build_config = EngineBuildConfig::new(vec![Box::new(OkxBuilder)]);
+    let timeout_arguments = engine_build_config.supported_exchange_clients
+        [&exchange_account_id.exchange_id]
+        .get_timeout_arguments();
+
+    let request_timeout_manager = RequestsTimeoutManagerFactory::from_requests_per_period(
+        timeout_arguments,
+        exchange_account_id,
+    );
+
+    TimeoutManager::new(hashmap![exchange_account_id => request_timeout_manager])
+}
+
+/// Returns tuple of api_key, secret_key and passphrase of OKX account
+pub(crate) fn get_okx_credentials() -> Result<(String, String, String)> {
+    let get_env_var = |name: &str| match std::env::var(name) {
+        Ok(v) if !v.is_empty() => Ok(v),
+        _ => bail!("Environment variable {name} is not set. Unable to continue test"),
+    };
+
+    Ok((
+        get_env_var("OKX_API_KEY")?,
+        get_env_var("OKX_SECRET_KEY")?,
+        get_env_var("OKX_PASSPHRASE")?,
+    ))
+}
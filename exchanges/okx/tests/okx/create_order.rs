@@ -0,0 +1,79 @@
+use crate::okx::okx_builder::OkxBuilder;
+use core_tests::order::OrderProxy;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::order::event::OrderEventType;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::logger::init_logger;
+use rust_decimal_macros::dec;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn create_successfully() {
+    init_logger();
+
+    let mut okx_builder = match OkxBuilder::build_account().await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    let mut order_proxy = OrderProxy::new(
+        okx_builder.exchange.exchange_account_id,
+        Some("FromCreateSuccessfullyTest".to_owned()),
+        CancellationToken::default(),
+        okx_builder.min_price,
+        okx_builder.min_amount,
+        okx_builder.default_currency_pair,
+    );
+    order_proxy.timeout = Duration::from_secs(15);
+
+    let order_ref = order_proxy
+        .create_order(okx_builder.exchange.clone())
+        .await
+        .expect("Create order failed with error");
+
+    let event = okx_builder
+        .rx
+        .recv()
+        .await
+        .expect("CreateOrderSucceeded event had to be occurred");
+
+    let order_event = if let ExchangeEvent::OrderEvent(order_event) = event {
+        order_event
+    } else {
+        panic!("Should receive OrderEvent")
+    };
+
+    match order_event.event_type {
+        OrderEventType::CreateOrderSucceeded => {}
+        _ => panic!("Should receive CreateOrderSucceeded event type"),
+    }
+
+    order_proxy
+        .cancel_order_or_fail(&order_ref, okx_builder.exchange)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_fail() {
+    init_logger();
+
+    let okx_builder = match OkxBuilder::build_account().await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    let mut order_proxy = OrderProxy::new(
+        okx_builder.exchange.exchange_account_id,
+        Some("FromShouldFailTest".to_owned()),
+        CancellationToken::default(),
+        dec!(0.0000000000000000001),
+        dec!(1),
+        okx_builder.default_currency_pair,
+    );
+    order_proxy.timeout = Duration::from_secs(15);
+
+    let _ = order_proxy
+        .create_order(okx_builder.exchange.clone())
+        .await
+        .expect_err("should be error");
+}
@@ -0,0 +1,49 @@
+use crate::okx::okx_builder::{default_features, OkxBuilder};
+use core_tests::order::OrderProxy;
+use mmb_core::exchanges::general::features::OpenOrdersType;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::logger::init_logger;
+use rstest::rstest;
+use std::time::Duration;
+
+#[rstest]
+#[case::all_currency_pairs(OpenOrdersType::AllCurrencyPair)]
+#[case::one_currency_pair(OpenOrdersType::OneCurrencyPair)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_open_orders(#[case] open_orders_type: OpenOrdersType) {
+    init_logger();
+
+    let mut features = default_features();
+    features.open_orders_type = open_orders_type;
+    let okx_builder = match OkxBuilder::build_account_with_features(features).await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    let mut order_proxy = OrderProxy::new(
+        okx_builder.exchange.exchange_account_id,
+        Some("FromGetOpenOrdersTest".to_owned()),
+        CancellationToken::default(),
+        okx_builder.min_price,
+        okx_builder.min_amount,
+        okx_builder.default_currency_pair,
+    );
+    order_proxy.timeout = Duration::from_secs(15);
+
+    let order_ref = order_proxy
+        .create_order(okx_builder.exchange.clone())
+        .await
+        .expect("Create order failed with error");
+
+    let open_orders = okx_builder
+        .exchange
+        .get_open_orders(false)
+        .await
+        .expect("Failed to get open orders");
+
+    order_proxy
+        .cancel_order_or_fail(&order_ref, okx_builder.exchange)
+        .await;
+
+    assert_eq!(open_orders.len(), 1);
+}
@@ -0,0 +1,47 @@
+use crate::okx::okx_builder::OkxBuilder;
+use core_tests::order::OrderProxy;
+use mmb_domain::order::snapshot::ReservationId;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::logger::init_logger;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_order_info() {
+    init_logger();
+
+    let okx_builder = match OkxBuilder::build_account().await {
+        Ok(okx_builder) => okx_builder,
+        Err(_) => return,
+    };
+
+    let mut order_proxy = OrderProxy::new(
+        okx_builder.exchange.exchange_account_id,
+        Some("FromGetOrderInfoTest".to_owned()),
+        CancellationToken::default(),
+        okx_builder.min_price,
+        okx_builder.min_amount,
+        okx_builder.default_currency_pair,
+    );
+    order_proxy.timeout = Duration::from_secs(15);
+    order_proxy.reservation_id = Some(ReservationId::generate());
+
+    let order_ref = order_proxy
+        .create_order(okx_builder.exchange.clone())
+        .await
+        .expect("in test");
+
+    let order_info = okx_builder
+        .exchange
+        .get_order_info(&order_ref)
+        .await
+        .expect("in test");
+
+    let created_exchange_order_id = order_ref.exchange_order_id().expect("in test");
+    let gotten_info_exchange_order_id = order_info.exchange_order_id;
+
+    order_proxy
+        .cancel_order_or_fail(&order_ref, okx_builder.exchange.clone())
+        .await;
+
+    assert_eq!(created_exchange_order_id, gotten_info_exchange_order_id);
+}
@@ -0,0 +1,8 @@
+mod account_balance;
+mod cancel_order;
+pub(crate) mod common;
+mod create_order;
+mod get_open_orders;
+mod get_order_info;
+pub(crate) mod okx_builder;
+mod request_symbol;
@@ -0,0 +1,182 @@
+use crate::okx::common::{default_currency_pair, get_okx_credentials, get_timeout_manager};
+use anyhow::Result;
+use mmb_core::balance::manager::balance_manager::BalanceManager;
+use mmb_core::database::events::recorder::EventRecorder;
+use mmb_core::exchanges::exchange_blocker::ExchangeBlocker;
+use mmb_core::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::infrastructure::init_lifetime_manager;
+use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings};
+use mmb_domain::events::{AllowedEventSourceType, ExchangeEvent};
+use mmb_domain::exchanges::commission::Commission;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::pool::OrdersPool;
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_utils::hashmap;
+use mmb_utils::infrastructure::WithExpect;
+use okx::okx::Okx;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+const ORDER_BOOK_DEPTH: u32 = 25;
+
+pub(crate) fn default_exchange_account_id() -> ExchangeAccountId {
+    const EXCHANGE_ACCOUNT_ID: &str = "Okx_0";
+
+    EXCHANGE_ACCOUNT_ID.parse().expect("in test")
+}
+
+pub(crate) fn default_features() -> ExchangeFeatures {
+    ExchangeFeatures::new(
+        OpenOrdersType::AllCurrencyPair,
+        RestFillsFeatures::new(RestFillsType::MyTrades),
+        OrderFeatures {
+            supports_get_order_info_by_client_order_id: true,
+            ..OrderFeatures::default()
+        },
+        OrderTradeOption::default(),
+        WebSocketOptions {
+            execution_notification: true,
+            cancellation_notification: true,
+            ..WebSocketOptions::default()
+        },
+        false,
+        AllowedEventSourceType::default(),
+        AllowedEventSourceType::default(),
+        AllowedEventSourceType::default(),
+    )
+}
+
+#[allow(dead_code)]
+pub(crate) struct OkxBuilder {
+    pub(crate) exchange: Arc<Exchange>,
+    exchange_settings: ExchangeSettings,
+    pub(crate) execution_price: Price,
+    pub(crate) min_price: Price,
+    pub(crate) min_amount: Amount,
+    pub(crate) default_currency_pair: CurrencyPair,
+    tx: broadcast::Sender<ExchangeEvent>,
+    pub(crate) rx: broadcast::Receiver<ExchangeEvent>,
+}
+
+impl OkxBuilder {
+    pub(crate) async fn build_account() -> Result<Self> {
+        OkxBuilder::build_account_with_features(default_features()).await
+    }
+
+    pub(crate) async fn build_account_with_features(features: ExchangeFeatures) -> Result<Self> {
+        let (api_key, secret_key, passphrase) = get_okx_credentials()?;
+
+        let mut settings =
+            ExchangeSettings::new_short(default_exchange_account_id(), api_key, secret_key, false);
+        settings.passphrase = Some(passphrase);
+
+        // Default currency pair for tests
+        settings.currency_pairs = Some(vec![CurrencyPairSetting::Ordinary {
+            base: "BTC".into(),
+            quote: "USDT".into(),
+        }]);
+
+        Ok(Self::try_new_with_settings(settings, features, Commission::default()).await)
+    }
+
+    async fn try_new_with_settings(
+        settings: ExchangeSettings,
+        features: ExchangeFeatures,
+        commission: Commission,
+    ) -> Self {
+        let lifetime_manager = init_lifetime_manager();
+        let (tx, rx) = broadcast::channel(10);
+
+        let okx = Box::new(Okx::new(
+            settings.clone(),
+            tx.clone(),
+            lifetime_manager.clone(),
+        ));
+
+        let exchange_blocker = ExchangeBlocker::new(vec![settings.exchange_account_id]);
+        let event_recorder = EventRecorder::start(None, None, 0.0)
+            .await
+            .expect("Failure start EventRecorder");
+
+        let timeout_manager = get_timeout_manager(settings.exchange_account_id);
+        let exchange = Exchange::new(
+            settings.exchange_account_id,
+            okx,
+            OrdersPool::new(),
+            features,
+            RequestTimeoutArguments::from_requests_per_minute(1200),
+            0.0,
+            tx.clone(),
+            lifetime_manager,
+            timeout_manager,
+            Arc::downgrade(&exchange_blocker),
+            commission,
+            event_recorder,
+        );
+        exchange.build_symbols(&settings.currency_pairs).await;
+        exchange.connect_ws().await.with_expect(move || {
+            format!(
+                "Failed to connect to websockets on exchange {}",
+                settings.exchange_account_id
+            )
+        });
+
+        let currency_pair_to_symbol_converter = CurrencyPairToSymbolConverter::new(
+            hashmap![ settings.exchange_account_id => exchange.clone() ],
+        );
+
+        let balance_manager = BalanceManager::new(currency_pair_to_symbol_converter, None);
+
+        exchange.setup_balance_manager(balance_manager);
+
+        let currency_pair = default_currency_pair();
+        let symbol = exchange
+            .symbols
+            .get(&currency_pair)
+            .with_expect(|| format!("Can't find symbol {currency_pair})"))
+            .value()
+            .clone();
+
+        let order_book = exchange
+            .exchange_client
+            .get_order_book_snapshot(currency_pair, ORDER_BOOK_DEPTH)
+            .await
+            .expect("Failed to request order book");
+
+        // Buy order with such price supposed to be executed immediately
+        let execution_price = *order_book
+            .asks
+            .keys()
+            .next()
+            .expect("No asks in order book")
+            + symbol.price_precision.get_tick() * dec!(2);
+        // Buy order with such price supposed to be opened after creation for a some time
+        let min_price = *order_book
+            .bids
+            .keys()
+            .next()
+            .expect("No bids in order book");
+
+        let min_amount = symbol
+            .get_min_amount(execution_price)
+            .expect("Failed to calc min amount");
+
+        Self {
+            exchange,
+            exchange_settings: settings,
+            execution_price,
+            min_price,
+            min_amount,
+            default_currency_pair: currency_pair,
+            tx,
+            rx,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use crate::okx::okx_builder::OkxBuilder;
+use mmb_utils::logger::init_logger;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn request_metadata() {
+    init_logger();
+
+    let _ = OkxBuilder::build_account().await;
+}
@@ -1,5 +1,5 @@
 use crate::serum::Serum;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 use futures::future::join_all;
 use futures::try_join;
@@ -16,11 +16,14 @@ use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
 use mmb_core::exchanges::general::order::create::CreateOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_domain::candle::{Candle, KlineInterval};
 use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::commission::TradingFees;
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::{CurrencyCode, CurrencyPair};
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 
@@ -169,7 +172,28 @@ impl ExchangeClient for Serum {
         symbols
     }
 
+    async fn get_klines(
+        &self,
+        _currency_pair: CurrencyPair,
+        _interval: KlineInterval,
+        _limit: u32,
+    ) -> Result<Vec<Candle>> {
+        bail!("Serum doesn't support klines")
+    }
+
     async fn get_server_time(&self) -> Option<Result<i64>> {
         None
     }
+
+    async fn get_trading_fees(&self, _currency_pair: CurrencyPair) -> Result<TradingFees> {
+        bail!("Serum doesn't support trading fees request")
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        _currency_pair: CurrencyPair,
+        _depth: u32,
+    ) -> Result<OrderBookData> {
+        bail!("Serum doesn't support order book snapshot request")
+    }
 }
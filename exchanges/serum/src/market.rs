@@ -1,3 +1,6 @@
+use anyhow::{Context, Result};
+use mmb_domain::order::snapshot::{Amount, OrderInfo, Price, SortedOrderData};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_utils::infrastructure::WithExpect;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::{Decimal, MathematicalOps};
@@ -6,6 +9,7 @@ use serde::Deserialize;
 use serum_dex::matching::Side;
 use serum_dex::state::MarketState;
 use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
 
 #[repr(C, packed)]
 #[derive(Debug, Copy, Clone)]
@@ -118,3 +122,95 @@ pub struct Order {
     pub owner: Pubkey,
     pub side: Side,
 }
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct L3Order {
+    pub price: Price,
+    pub amount: Amount,
+}
+
+/// Order-by-order book of serum market.
+/// Orders are keyed by serum order id which consists of price in the high 64 bits and sequence number
+/// in the low 64 bits (inverted for bids), so ascending order of asks and descending order of bids
+/// is the matching priority of orders.
+#[derive(Debug, Clone, Default)]
+pub struct L3OrderBook {
+    asks: BTreeMap<u128, L3Order>,
+    bids: BTreeMap<u128, L3Order>,
+}
+
+impl L3OrderBook {
+    /// Replace all orders of the side with orders from the slab of this side
+    pub(super) fn update_side(&mut self, side: Side, orders: &[OrderInfo]) -> Result<()> {
+        let side_orders: BTreeMap<_, _> = orders
+            .iter()
+            .map(|order| {
+                let order_id = order.exchange_order_id.as_str().parse().with_context(|| {
+                    format!("Failed to parse serum order id {}", order.exchange_order_id)
+                })?;
+                let l3_order = L3Order {
+                    price: order.price,
+                    amount: order.amount,
+                };
+                Ok((order_id, l3_order))
+            })
+            .collect::<Result<_>>()?;
+
+        *self.side_mut(side) = side_orders;
+        Ok(())
+    }
+
+    pub fn get_order(&self, order_id: u128) -> Option<(Side, L3Order)> {
+        self.asks
+            .get(&order_id)
+            .map(|order| (Side::Ask, *order))
+            .or_else(|| self.bids.get(&order_id).map(|order| (Side::Bid, *order)))
+    }
+
+    /// Amount of orders with the same price which will be matched before the specified order.
+    /// `None` if there is no order with such id in the book
+    pub fn queue_position(&self, order_id: u128) -> Option<Amount> {
+        let (side, order) = self.get_order(order_id)?;
+        let same_price = |(_, x): &(&u128, &L3Order)| x.price == order.price;
+        let amount = |(_, x): (&u128, &L3Order)| x.amount;
+
+        let amount_ahead = match side {
+            Side::Ask => self
+                .asks
+                .range(..order_id)
+                .rev()
+                .take_while(same_price)
+                .map(amount)
+                .sum(),
+            Side::Bid => self
+                .bids
+                .range(order_id + 1..)
+                .take_while(same_price)
+                .map(amount)
+                .sum(),
+        };
+
+        Some(amount_ahead)
+    }
+
+    /// Aggregate orders by price levels
+    pub fn to_l2(&self) -> OrderBookData {
+        OrderBookData::new(aggregate(&self.asks), aggregate(&self.bids))
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<u128, L3Order> {
+        match side {
+            Side::Ask => &mut self.asks,
+            Side::Bid => &mut self.bids,
+        }
+    }
+}
+
+fn aggregate(orders: &BTreeMap<u128, L3Order>) -> SortedOrderData {
+    let mut levels = SortedOrderData::new();
+    for order in orders.values() {
+        *levels.entry(order.price).or_default() += order.amount;
+    }
+
+    levels
+}
@@ -15,7 +15,7 @@ use serum_dex::instruction::{cancel_order, MarketInstruction};
 use serum_dex::matching::Side;
 use serum_dex::state::OpenOrders;
 use serum_dex::state::{
-    gen_vault_signer_key, strip_header, Event, EventQueueHeader, Market, MarketState,
+    gen_vault_signer_key, strip_header, Event, EventQueue, EventQueueHeader, Market, MarketState,
 };
 use solana_account_decoder::UiAccount;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
@@ -42,8 +42,8 @@ use tokio::sync::broadcast;
 use tokio::time::sleep;
 
 use crate::helpers::{FromU64Array, ToOrderSide, ToSerumSide, ToU128};
-use crate::market::{MarketData, MarketInfo, MarketMetaData, OpenOrderData};
-use crate::solana_client::{NetworkType, SolanaClient};
+use crate::market::{L3OrderBook, MarketData, MarketInfo, MarketMetaData, OpenOrderData};
+use crate::solana_client::{NetworkType, SolanaClient, SolanaClientConfig};
 use crate::support::FillEventView;
 use mmb_core::exchanges::general::exchange::BoxExchangeClient;
 use mmb_core::exchanges::general::features::{
@@ -67,6 +67,7 @@ use mmb_domain::market::{
     CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, ExchangeErrorType, ExchangeId,
     SpecificCurrencyPair,
 };
+use mmb_domain::order::fill::OrderFill;
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::{
     ClientOrderId, ExchangeOrderId, OrderInfo, OrderInfoExtensionData, OrderSide, OrderStatus,
@@ -94,19 +95,20 @@ impl OrderInfoExtensionData for SerumExtensionData {
 
 /// Trait - a wrapper for compact casting to a SerumExtensionData type
 trait DowncastToSerumExtensionData {
-    fn downcast_to_serum_extension_data(&self) -> &SerumExtensionData;
+    fn downcast_to_serum_extension_data(&self) -> SerumExtensionData;
 }
 
 impl DowncastToSerumExtensionData for OrderRef {
-    fn downcast_to_serum_extension_data(&self) -> &SerumExtensionData {
-        let extension_data = self.fn_ref(|snapshot| snapshot.extension_data);
-        downcast_to_serum_extension_data(extension_data.as_deref())
+    fn downcast_to_serum_extension_data(&self) -> SerumExtensionData {
+        self.fn_ref(|order| {
+            downcast_to_serum_extension_data(order.extension_data.as_deref()).clone()
+        })
     }
 }
 
 impl DowncastToSerumExtensionData for OrderInfo {
-    fn downcast_to_serum_extension_data(&self) -> &SerumExtensionData {
-        downcast_to_serum_extension_data(self.extension_data.as_deref())
+    fn downcast_to_serum_extension_data(&self) -> SerumExtensionData {
+        downcast_to_serum_extension_data(self.extension_data.as_deref()).clone()
     }
 }
 
@@ -150,11 +152,11 @@ pub struct Serum {
     pub(super) rest_client: RestClient<ErrorHandlerEmpty, RestHeadersEmpty>,
     pub(super) rpc_client: Arc<SolanaClient>,
     pub(super) markets_data: RwLock<HashMap<CurrencyPair, MarketData>>,
+    pub(super) order_books: Mutex<HashMap<CurrencyPair, L3OrderBook>>,
     pub network_type: NetworkType,
     pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
     pub(super) lifetime_manager: Arc<AppLifetimeManager>,
     pub(super) fill_events_cache: Mutex<FillEventsCache>,
-    trade_id_seed: AtomicU64,
 }
 
 impl Serum {
@@ -169,6 +171,9 @@ impl Serum {
     ) -> Self {
         let payer = Keypair::from_base58_string(&settings.secret_key);
         let exchange_account_id = settings.exchange_account_id;
+        let log_rest_bodies = settings.log_rest_bodies;
+        let rest_rate_limit_retries = settings.rest_rate_limit_retries;
+        let rest_transient_error_retries = settings.rest_transient_error_retries;
 
         Self {
             id,
@@ -189,19 +194,21 @@ impl Serum {
                     ErrorHandlerEmpty::default(),
                 ),
                 RestHeadersEmpty::default(),
-            ),
-            rpc_client: Arc::new(SolanaClient::new(&network_type)),
+            )
+            .with_bodies_logging(log_rest_bodies)
+            .with_rate_limit_retries(rest_rate_limit_retries, lifetime_manager.stop_token())
+            .with_transient_error_retries(rest_transient_error_retries),
+            rpc_client: Arc::new(SolanaClient::new(
+                &network_type,
+                SolanaClientConfig::default(),
+                lifetime_manager.stop_token(),
+            )),
             markets_data: Default::default(),
+            order_books: Default::default(),
             network_type,
             events_channel,
             lifetime_manager,
             fill_events_cache: FillEventsCache::new().into(),
-            trade_id_seed: AtomicU64::new(
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .expect("Failed to calc duration since UNIX start time")
-                    .as_secs(),
-            ),
         }
     }
 
@@ -493,28 +500,37 @@ impl Serum {
         market_info: &MarketMetaData,
     ) -> Result<HashSet<FillEventView>> {
         if let Some(mut account) = ui_account.decode::<Account>() {
-            let account_info = (&market_info.event_queue_address, &mut account).into_account_info();
-            let (_, buf) = strip_header::<EventQueueHeader, Event>(&account_info, false)
-                .context("Failed to parse data from event queue account")?;
-
-            Ok(buf
-                .iter()
-                .filter_map(|event| {
-                    event
-                        .as_view()
-                        .map_err(|err| {
-                            log::error!("Error during getting Serum event: {:#?}", err);
-                            err
-                        })
-                        .ok()
-                })
-                .filter_map(|view| FillEventView::try_from_event_view(&view))
-                .collect())
+            let fill_events = parse_fill_events(&mut account, market_info, None)?;
+            Ok(fill_events.into_iter().collect())
         } else {
             bail!("Failed to decode ui account")
         }
     }
 
+    /// Load fills of our orders directly from the market event queue account.
+    /// `since` is the event queue sequence number from which fill events are taken, all events are taken if `None`.
+    /// New fills are passed to the order filled handler the same way as fills received via websocket.
+    pub async fn get_order_fills(
+        &self,
+        currency_pair: CurrencyPair,
+        since: Option<u64>,
+    ) -> Result<Vec<OrderFill>> {
+        let market_data = self.get_market_data(currency_pair)?;
+        let market_info = &market_data.metadata;
+        let event_queue_address = &market_info.event_queue_address;
+
+        let mut account = self
+            .rpc_client
+            .get_account(event_queue_address)
+            .await
+            .with_context(|| {
+                format!("Failed to get event queue account {event_queue_address} for market {currency_pair}")
+            })?;
+        let fill_events = parse_fill_events(&mut account, market_info, since)?;
+
+        Ok(self.handle_fetched_fill_events(fill_events, currency_pair, market_info))
+    }
+
     pub(super) fn get_orders_from_open_orders_account(
         &self,
         ui_account: UiAccount,
@@ -572,6 +588,11 @@ impl Serum {
         orders
     }
 
+    /// Order-by-order book of the market, `None` if there were no order book updates for the market yet
+    pub fn get_l3_order_book(&self, currency_pair: CurrencyPair) -> Option<L3OrderBook> {
+        self.order_books.lock().get(&currency_pair).cloned()
+    }
+
     pub async fn subscribe_to_all_market(&self) {
         let markets_data: HashMap<CurrencyPair, MarketData> = self.markets_data.read().clone();
 
@@ -625,10 +646,7 @@ impl Serum {
         let mut instructions = Vec::new();
         let mut signers = Vec::new();
         let orders_keypair: Keypair;
-        let (client_order_id, currency_pair) = order.fn_ref(|order| {
-            let header = order.header.as_ref();
-            (header.client_order_id.clone(), header.currency_pair)
-        });
+        let (client_order_id, currency_pair) = (order.client_order_id(), order.currency_pair());
 
         let market_data = self.get_market_data(currency_pair)?;
         let accounts = self
@@ -774,7 +792,7 @@ impl Serum {
         )
         .await
         .into_iter()
-        .try_collect()?;
+        .collect::<Result<()>>()?;
 
         Ok(())
     }
@@ -929,10 +947,6 @@ impl Serum {
         let price_mint_data = Mint::unpack_from_slice(&pc_data).context("Unpack price data")?;
         Ok((coin_mint_data, price_mint_data))
     }
-
-    pub(super) fn generate_trade_id(&self) -> u64 {
-        self.trade_id_seed.fetch_add(1, Ordering::Relaxed)
-    }
 }
 
 pub struct SerumBuilder;
@@ -967,7 +981,10 @@ impl ExchangeClientBuilder for SerumBuilder {
                     ..OrderFeatures::default()
                 },
                 OrderTradeOption::default(),
-                WebSocketOptions::default(),
+                WebSocketOptions {
+                    supports_ping_pong: true,
+                    ..WebSocketOptions::default()
+                },
                 empty_response_is_ok,
                 AllowedEventSourceType::All,
                 AllowedEventSourceType::All,
@@ -985,6 +1002,47 @@ impl ExchangeClientBuilder for SerumBuilder {
     }
 }
 
+const EVENT_QUEUE_SEQ_NUM_OFFSET: usize = size_of::<EventQueueHeader>() - size_of::<u64>();
+
+fn parse_fill_events(
+    account: &mut Account,
+    market_info: &MarketMetaData,
+    since: Option<u64>,
+) -> Result<Vec<FillEventView>> {
+    let account_info = (&market_info.event_queue_address, account).into_account_info();
+    let (header, buf) = strip_header::<EventQueueHeader, Event>(&account_info, false)
+        .context("Failed to parse data from event queue account")?;
+
+    // EventQueueHeader doesn't expose sequence number, so it's read from the last field of the header.
+    // Sequence number of the header is the one for the next pushed event
+    let next_seq_num = u64::from_le_bytes(
+        bytemuck::bytes_of(&*header)[EVENT_QUEUE_SEQ_NUM_OFFSET..]
+            .try_into()
+            .context("Failed to read event queue sequence number")?,
+    );
+    let event_queue = EventQueue::new(header, buf);
+    let first_seq_num = next_seq_num.wrapping_sub(event_queue.len());
+
+    let fill_events = event_queue
+        .iter()
+        .zip(first_seq_num..)
+        .filter(|(_, seq_num)| since.map_or(true, |since| *seq_num >= since))
+        .filter_map(|(event, seq_num)| {
+            event
+                .as_view()
+                .map_err(|err| {
+                    log::error!("Error during getting Serum event: {:#?}", err);
+                    err
+                })
+                .ok()
+                .map(|view| (view, seq_num))
+        })
+        .filter_map(|(view, seq_num)| FillEventView::try_from_event_view(&view, seq_num))
+        .collect();
+
+    Ok(fill_events)
+}
+
 pub(super) struct FillEventsCache {
     events: HashSet<FillEventView>,
     last_prune_time: Instant,
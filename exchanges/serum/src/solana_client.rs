@@ -6,8 +6,12 @@ use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -15,6 +19,7 @@ use serum_dex::matching::Side;
 
 use solana_account_decoder::parse_token::UiTokenAmount;
 use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::client_error::{ClientError, ClientErrorKind};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
 use solana_client::rpc_response::Response;
@@ -30,10 +35,32 @@ use tokio::join;
 use mmb_core::connectivity::WebSocketRole;
 use mmb_core::exchanges::traits::SendWebsocketMessageCb;
 use mmb_domain::market::CurrencyPair;
+use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::{impl_u64_id, time::get_atomic_current_secs};
 
 pub const ALLOW_FLAG: bool = false;
 
+/// Upper bound of backoff delay before retry of transaction sending
+const MAX_SEND_TRANSACTION_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Retries of sending transaction failed with transient error, e.g. during validator leader changes
+#[derive(Debug, Clone, Copy)]
+pub struct SolanaClientConfig {
+    /// Max count of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry. It is doubled on each next retry and extended by random jitter
+    pub initial_backoff_ms: u64,
+}
+
+impl Default for SolanaClientConfig {
+    fn default() -> Self {
+        SolanaClientConfig {
+            max_retries: 3,
+            initial_backoff_ms: 200,
+        }
+    }
+}
+
 pub struct SolanaHosts {
     url: String,
     ws: String,
@@ -145,14 +172,23 @@ pub struct SolanaClient {
     send_websocket_message_callback: Mutex<SendWebsocketMessageCb>,
     subscription_requests: RwLock<HashMap<RequestId, SubscriptionMarketData>>,
     subscriptions: RwLock<HashMap<RequestId, SubscriptionMarketData>>,
+    config: SolanaClientConfig,
+    /// Aborts waiting before retry of transaction sending
+    cancellation_token: CancellationToken,
 }
 
 impl SolanaClient {
-    pub fn new(network_type: &NetworkType) -> Self {
+    pub fn new(
+        network_type: &NetworkType,
+        config: SolanaClientConfig,
+        cancellation_token: CancellationToken,
+    ) -> Self {
         let async_rpc_client = RpcClient::new(network_type.url().to_string());
 
         Self {
             rpc_client: Arc::new(async_rpc_client),
+            config,
+            cancellation_token,
             send_websocket_message_callback: Mutex::new(Box::new(|_, _| {
                 Err(anyhow::anyhow!("not connected!"))
             })),
@@ -210,7 +246,11 @@ impl SolanaClient {
             recent_hash,
         );
 
-        self.rpc_client.send_transaction(&transaction).await?;
+        // The same signed transaction is sent on retries, so it can't be executed twice
+        send_with_retries(&self.config, &self.cancellation_token, || {
+            self.rpc_client.send_transaction(&transaction)
+        })
+        .await?;
         Ok(())
     }
 
@@ -371,3 +411,130 @@ impl SolanaClient {
             .expect("failed to send websocket message")
     }
 }
+
+/// Errors of RPC transport and RPC responses (e.g. failed preflight check) can be fixed by retry.
+/// Errors of transaction itself aren't retried
+fn is_transient_error(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) | ClientErrorKind::RpcError(_)
+    )
+}
+
+/// Exponential backoff delay with random jitter before specified retry (starting from 1)
+fn backoff_delay(initial_backoff_ms: u64, retry: u32) -> Duration {
+    let multiplier = 2u64.saturating_pow(retry.saturating_sub(1));
+    let backoff_ms = initial_backoff_ms
+        .saturating_mul(multiplier)
+        .min(MAX_SEND_TRANSACTION_BACKOFF.as_millis() as u64);
+    let jitter_ms = match backoff_ms {
+        0 => 0,
+        _ => rand::thread_rng().gen_range(0..backoff_ms),
+    };
+
+    Duration::from_millis(backoff_ms + jitter_ms)
+}
+
+async fn send_with_retries<T, F, Fut>(
+    config: &SolanaClientConfig,
+    cancellation_token: &CancellationToken,
+    mut send: F,
+) -> Result<T, ClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut retry = 0;
+    loop {
+        let error = match send().await {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+
+        if retry >= config.max_retries || !is_transient_error(&error) {
+            return Err(error);
+        }
+
+        retry += 1;
+        let delay = backoff_delay(config.initial_backoff_ms, retry);
+        log::warn!(
+            "Failed to send transaction: {error}. Retry {retry}/{} after {delay:?}",
+            config.max_retries
+        );
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancellation_token.when_cancelled() => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+    use std::sync::atomic::AtomicU32;
+
+    fn send_transaction_error() -> ClientError {
+        RpcError::RpcResponseError {
+            code: -32002,
+            message: "Transaction simulation failed: Blockhash not found".to_owned(),
+            data: RpcResponseErrorData::Empty,
+        }
+        .into()
+    }
+
+    fn config() -> SolanaClientConfig {
+        SolanaClientConfig {
+            max_retries: 3,
+            initial_backoff_ms: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn transaction_sent_after_transient_errors() {
+        let attempts = AtomicU32::new(0);
+
+        let result = send_with_retries(&config(), &CancellationToken::new(), || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(send_transaction_error()),
+                _ => Ok("signature"),
+            }
+        })
+        .await;
+
+        assert_eq!(result.expect("in test"), "signature");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_aborted_by_cancellation() {
+        let attempts = AtomicU32::new(0);
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+
+        let config = SolanaClientConfig {
+            max_retries: 3,
+            initial_backoff_ms: 60_000,
+        };
+        let result = send_with_retries(&config, &cancellation_token, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(send_transaction_error())
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn backoff_delay_is_doubled_with_jitter() {
+        for retry in 1..=3 {
+            let backoff = Duration::from_millis(100 * 2u64.pow(retry - 1));
+            let delay = backoff_delay(100, retry);
+            assert!(backoff <= delay && delay < backoff * 2, "{delay:?}");
+        }
+
+        assert_eq!(backoff_delay(0, 1), Duration::ZERO);
+    }
+}
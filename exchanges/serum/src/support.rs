@@ -14,6 +14,7 @@ use serum_dex::matching::Side;
 use serum_dex::state::EventView;
 use solana_account_decoder::UiAccount;
 use url::Url;
+use uuid::Uuid;
 
 use crate::helpers::ToOrderSide;
 use crate::market::MarketMetaData;
@@ -31,14 +32,13 @@ use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
 use mmb_domain::exchanges::commission::Percent;
 use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
-use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::fill::{OrderFill, OrderFillType};
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{
     Amount, ClientOrderId, ExchangeOrderId, OrderInfo, OrderInfoExtensionData, OrderRole,
-    OrderSide, OrderStatus, Price, SortedOrderData,
+    OrderSide, OrderStatus, Price,
 };
 use mmb_domain::order_book::event::{EventType, OrderBookEvent};
-use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::{nothing_to_do, DateTime};
 
@@ -158,7 +158,7 @@ impl Serum {
                 let orders =
                     self.get_orders_from_order_book(ui_account, market_info, side, currency_pair)?;
                 self.handle_order_event(&orders, currency_pair);
-                self.handle_order_book_snapshot(&orders, currency_pair)?;
+                self.handle_order_book_snapshot(&orders, side, currency_pair)?;
             }
             SubscriptionAccountType::EventQueue => {
                 let events = self.get_event_queue_data(ui_account, market_info)?;
@@ -176,19 +176,16 @@ impl Serum {
     fn handle_order_book_snapshot(
         &self,
         orders: &[OrderInfo],
+        side: Side,
         currency_pair: CurrencyPair,
     ) -> Result<()> {
-        let mut asks = SortedOrderData::new();
-        let mut bids = SortedOrderData::new();
-        for order in orders.iter() {
-            match order.order_side {
-                OrderSide::Buy => &mut bids,
-                OrderSide::Sell => &mut asks,
-            }
-            .entry(order.price)
-            .and_modify(|amount| *amount += order.amount)
-            .or_insert(order.amount);
-        }
+        // Serum sends bids and asks in separate accounts, so L2 snapshot is built from the both sides of L3 book
+        let order_book_data = {
+            let mut order_books = self.order_books.lock();
+            let order_book = order_books.entry(currency_pair).or_default();
+            order_book.update_side(side, orders)?;
+            order_book.to_l2()
+        };
 
         let order_book_event = OrderBookEvent::new(
             Utc::now(),
@@ -196,7 +193,7 @@ impl Serum {
             currency_pair,
             "".to_string(),
             EventType::Snapshot,
-            Arc::new(OrderBookData::new(asks, bids)),
+            Arc::new(order_book_data),
         );
 
         let event = ExchangeEvent::OrderBookEvent(order_book_event);
@@ -221,29 +218,8 @@ impl Serum {
         self.fill_events_cache.lock().prune_events(&events);
         for fill_event in events {
             if !self.fill_events_cache.lock().has_event(&fill_event) {
-                let (price, fill_amount) =
-                    calc_order_fill_price_and_amount(&fill_event, market_meta_data);
-                let fill_data = OrderFillData {
-                    // Serum doesn't store trade id so we have to create it by ourself
-                    trade_id: TradeId::Number(self.generate_trade_id()),
-                    client_order_id: fill_event.client_order_id.clone(),
-                    exchange_order_id: fill_event.exchange_order_id.clone(),
-                    price,
-                    fill_amount,
-                    order_role: fill_event.order_role,
-                    commission: OrderTradeCommission {
-                        currency_code: currency_pair.to_codes().quote,
-                        amount: calc_order_fee(
-                            fill_event.order_role,
-                            fill_event.native_fee_or_rebate,
-                            market_meta_data,
-                        ),
-                    },
-                    fill_type: OrderFillType::UserTrade,
-                    currency_pair,
-                    order_side: fill_event.side,
-                    date: time_manager::now(),
-                };
+                let fill_data =
+                    self.create_order_fill_data(&fill_event, currency_pair, market_meta_data);
                 if let Some(order) = self
                     .orders
                     .cache_by_client_id
@@ -260,6 +236,68 @@ impl Serum {
         Ok(())
     }
 
+    pub(super) fn handle_fetched_fill_events(
+        &self,
+        events: Vec<FillEventView>,
+        currency_pair: CurrencyPair,
+        market_meta_data: &MarketMetaData,
+    ) -> Vec<OrderFill> {
+        let mut order_fills = Vec::new();
+        for fill_event in events {
+            // Event queue contains fills of all market participants so we take only fills of our orders
+            let order = match self
+                .orders
+                .cache_by_client_id
+                .get(&fill_event.client_order_id)
+            {
+                Some(order) => order.value().clone(),
+                None => continue,
+            };
+
+            let fill_data =
+                self.create_order_fill_data(&fill_event, currency_pair, market_meta_data);
+            // Fill may have been already handled from websocket event queue notification
+            if !self.fill_events_cache.lock().has_event(&fill_event) {
+                self.handle_order_fill(&order, &fill_data);
+                self.fill_events_cache.lock().add_event(fill_event);
+            }
+
+            order_fills.push(fill_data.to_order_fill());
+        }
+
+        order_fills
+    }
+
+    fn create_order_fill_data(
+        &self,
+        fill_event: &FillEventView,
+        currency_pair: CurrencyPair,
+        market_meta_data: &MarketMetaData,
+    ) -> OrderFillData {
+        let (price, fill_amount) = calc_order_fill_price_and_amount(fill_event, market_meta_data);
+        OrderFillData {
+            // Serum doesn't store trade id so sequence number of the fill event in the market event queue is used
+            trade_id: TradeId::Number(fill_event.seq_num),
+            client_order_id: fill_event.client_order_id.clone(),
+            exchange_order_id: fill_event.exchange_order_id.clone(),
+            price,
+            fill_amount,
+            order_role: fill_event.order_role,
+            commission: OrderTradeCommission {
+                currency_code: currency_pair.to_codes().quote,
+                amount: calc_order_fee(
+                    fill_event.order_role,
+                    fill_event.native_fee_or_rebate,
+                    market_meta_data,
+                ),
+            },
+            fill_type: OrderFillType::UserTrade,
+            currency_pair,
+            order_side: fill_event.side,
+            date: time_manager::now(),
+        }
+    }
+
     fn handle_order_event(&self, orders: &[OrderInfo], currency_pair: CurrencyPair) {
         let orders: DashMap<ClientOrderId, &OrderInfo> = orders
             .iter()
@@ -271,8 +309,8 @@ impl Serum {
             .iter()
             .filter(|order| order.currency_pair() == currency_pair)
             .for_each(|order_ref| {
+                let client_order_id = &order_ref.client_order_id();
                 order_ref.fn_mut(|order| {
-                    let client_order_id = &order.header.client_order_id;
                     match order.props.status {
                         OrderStatus::Creating => {
                             let serum_extension_data =
@@ -371,6 +409,32 @@ struct OrderFillData {
     date: DateTime,
 }
 
+impl OrderFillData {
+    fn to_order_fill(&self) -> OrderFill {
+        let commission = &self.commission;
+        OrderFill::new(
+            Uuid::new_v4(),
+            None,
+            self.date,
+            self.fill_type,
+            Some(self.trade_id.clone()),
+            self.price,
+            self.fill_amount,
+            self.price * self.fill_amount,
+            self.order_role.into(),
+            commission.currency_code,
+            commission.amount,
+            dec!(0),
+            commission.currency_code,
+            commission.amount,
+            commission.amount,
+            true,
+            Some(EventSourceType::Rpc),
+            Some(self.order_side),
+        )
+    }
+}
+
 #[derive(Debug)]
 struct OrderTradeCommission {
     currency_code: CurrencyCode,
@@ -476,6 +540,7 @@ impl From<u8> for FeeTier {
 
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub(super) struct FillEventView {
+    seq_num: u64,
     side: OrderSide,
     order_role: OrderRole,
     native_qty_paid: u64,
@@ -486,7 +551,7 @@ pub(super) struct FillEventView {
 }
 
 impl FillEventView {
-    pub(super) fn try_from_event_view(event: &EventView) -> Option<FillEventView> {
+    pub(super) fn try_from_event_view(event: &EventView, seq_num: u64) -> Option<FillEventView> {
         // We use only these fields of event fill variant cause they are exhaustive for unique trade definition
         if let &EventView::Fill {
             side,
@@ -500,6 +565,7 @@ impl FillEventView {
         } = event
         {
             Some(Self {
+                seq_num,
                 side: side.to_order_side(),
                 order_role: if maker {
                     OrderRole::Maker
@@ -18,8 +18,8 @@ async fn cancelled_successfully() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
@@ -47,8 +47,8 @@ async fn cancel_all_orders() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
@@ -62,8 +62,8 @@ async fn cancel_all_orders() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(2),
         dec!(10),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
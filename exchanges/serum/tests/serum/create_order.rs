@@ -22,8 +22,8 @@ async fn create_successfully() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
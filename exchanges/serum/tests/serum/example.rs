@@ -71,13 +71,18 @@ async fn example() {
             },
         );
 
-        let strategy = ExampleStrategy::new(
+        let mut strategy = ExampleStrategy::new(
             settings.strategy.exchange_account_id(),
             settings.strategy.currency_pair(),
             settings.strategy.spread,
+            settings.strategy.spread_volatility.clone(),
             settings.strategy.max_amount,
+            settings.strategy.equity_fraction,
+            None,
             ctx.clone(),
-        );
+        )
+        .expect("Failed to create strategy");
+        strategy.set_improve_price_by_tick(settings.strategy.improve_price_by_tick);
 
         engine.start_disposition_executor(strategy);
 
@@ -24,8 +24,8 @@ async fn get_open_orders() {
         Some("FromGetOpenOrdersTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -40,8 +40,8 @@ async fn get_open_orders() {
         Some("FromGetOpenOrdersTest".to_owned()),
         dec!(2),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -89,8 +89,8 @@ async fn get_open_orders_for_currency_pair() {
         Some("FromGetOpenOrdersTest".to_owned()),
         dec!(1),
         dec!(1),
+        first_currency_pair,
     )
-    .currency_pair(first_currency_pair)
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -109,8 +109,8 @@ async fn get_open_orders_for_currency_pair() {
         Some("FromGetOpenOrdersTest".to_owned()),
         dec!(2),
         dec!(1),
+        second_currency_pair,
     )
-    .currency_pair(second_currency_pair)
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -22,8 +22,8 @@ async fn get_order_info() {
         Some("FromGetOpenOrdersTest".to_owned()),
         dec!(1),
         dec!(1),
+        currency_pair,
     )
-    .currency_pair(currency_pair)
     .side(OrderSide::Sell)
     .build();
 
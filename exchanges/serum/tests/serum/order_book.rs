@@ -19,8 +19,8 @@ async fn fill_order_book() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price1,
         amount1,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .build();
 
@@ -34,8 +34,8 @@ async fn fill_order_book() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price2,
         amount2,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .build();
 
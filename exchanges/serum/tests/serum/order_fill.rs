@@ -8,6 +8,7 @@ use mmb_utils::infrastructure::init_infrastructure;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use scopeguard::defer;
+use serum::serum::Serum;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
@@ -26,8 +27,8 @@ async fn partial_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         first_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .timeout(Duration::from_secs(30))
     .build();
@@ -40,8 +41,8 @@ async fn partial_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         second_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -83,7 +84,7 @@ async fn partial_order_fill() {
         .map(|fill| fill.commission_amount())
         .sum();
 
-    assert_eq!(first_amount, order_snapshot.filled_amount());
+    assert_eq!(first_amount, order_snapshot.fills.filled_amount);
     assert_eq!(dec!(0.004), commission);
 }
 
@@ -101,8 +102,8 @@ async fn full_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         first_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Buy)
     .timeout(Duration::from_secs(30))
     .build();
@@ -115,8 +116,8 @@ async fn full_order_fill() {
         Some("FromCreateSuccessfullyTest".to_owned()),
         price,
         second_amount,
+        CurrencyPair::from_codes("sol".into(), "test".into()),
     )
-    .currency_pair(CurrencyPair::from_codes("sol".into(), "test".into()))
     .side(OrderSide::Sell)
     .timeout(Duration::from_secs(30))
     .build();
@@ -151,7 +152,88 @@ async fn full_order_fill() {
     let order_snapshot =
         receive_exchange_order_event(&mut second_serum_builder.rx, second_client_order_id).await;
 
-    assert_eq!(second_amount, order_snapshot.filled_amount());
+    assert_eq!(second_amount, order_snapshot.fills.filled_amount);
+}
+
+#[ignore = "need solana keypair"]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn get_order_fills_from_event_queue() {
+    init_infrastructure();
+
+    let first_serum_builder = SerumBuilder::build_account_0().await;
+    let first_exchange_account_id = first_serum_builder.exchange.exchange_account_id;
+    let currency_pair = CurrencyPair::from_codes("sol".into(), "test".into());
+    let price = dec!(1);
+    let amount = dec!(5);
+    let first_order_proxy = OrderProxyBuilder::new(
+        first_exchange_account_id,
+        Some("FromCreateSuccessfullyTest".to_owned()),
+        price,
+        amount,
+        currency_pair,
+    )
+    .side(OrderSide::Buy)
+    .timeout(Duration::from_secs(30))
+    .build();
+
+    let mut second_serum_builder = SerumBuilder::build_account_1().await;
+    let second_exchange_account_id = second_serum_builder.exchange.exchange_account_id;
+    let second_order_proxy = OrderProxyBuilder::new(
+        second_exchange_account_id,
+        Some("FromCreateSuccessfullyTest".to_owned()),
+        price,
+        amount,
+        currency_pair,
+    )
+    .side(OrderSide::Sell)
+    .timeout(Duration::from_secs(30))
+    .build();
+
+    let first_order_ref = first_order_proxy
+        .create_order(first_serum_builder.exchange.clone())
+        .await
+        .expect("Create first order failed with error");
+    let first_builder_exchange = first_serum_builder.exchange.clone();
+    defer! {
+        tokio::spawn(async move {
+            first_order_proxy
+                .cancel_order_or_fail(&first_order_ref, first_builder_exchange.clone())
+                .await;
+        });
+    }
+
+    let second_order_ref = second_order_proxy
+        .create_order(second_serum_builder.exchange.clone())
+        .await
+        .expect("Create second order failed with error");
+    let second_client_order_id = second_order_ref.client_order_id();
+    let second_builder_exchange = second_serum_builder.exchange.clone();
+    defer! {
+        tokio::spawn(async move {
+            second_order_proxy
+                .cancel_order_or_fail(&second_order_ref, second_builder_exchange.clone())
+                .await;
+        });
+    }
+
+    // wait until fill events are placed into the event queue
+    let _ =
+        receive_exchange_order_event(&mut second_serum_builder.rx, second_client_order_id).await;
+
+    let serum = second_serum_builder
+        .exchange
+        .exchange_client
+        .as_any()
+        .downcast_ref::<Serum>()
+        .expect("Failed to downcast exchange client to Serum");
+    let order_fills = serum
+        .get_order_fills(currency_pair, None)
+        .await
+        .expect("Failed to get order fills from event queue");
+
+    let filled_amount: Decimal = order_fills.iter().map(|fill| fill.amount()).sum();
+    assert_eq!(amount, filled_amount);
+    assert!(order_fills.iter().all(|fill| fill.price() == price));
 }
 
 async fn receive_exchange_order_event(
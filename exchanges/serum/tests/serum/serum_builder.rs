@@ -120,7 +120,7 @@ impl SerumBuilder {
         ));
 
         let exchange_blocker = ExchangeBlocker::new(vec![exchange_account_id]);
-        let event_recorder = EventRecorder::start(None)
+        let event_recorder = EventRecorder::start(None, None, 0.0)
             .await
             .expect("Failure start EventRecorder");
 
@@ -130,6 +130,7 @@ impl SerumBuilder {
             orders_pool,
             features,
             RequestTimeoutArguments::from_requests_per_minute(240),
+            0.0,
             tx.clone(),
             lifetime_manager,
             timeout_manager,
@@ -0,0 +1,8 @@
+DROP TABLE order_book_snapshots;
+
+DO $$
+BEGIN
+    IF to_regclass('public.cleanup_settings') IS NOT NULL THEN
+        delete from public.cleanup_settings where table_name = 'order_book_snapshots';
+    END IF;
+END $$;
@@ -0,0 +1,16 @@
+CREATE TABLE order_book_snapshots (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX order_book_snapshots__insert_time_idx ON order_book_snapshots USING btree (insert_time);
+
+DO $$
+BEGIN
+    IF to_regclass('public.cleanup_settings') IS NOT NULL THEN
+        insert into public.cleanup_settings (table_name, period, column_name)
+        values ('order_book_snapshots', '1 mons', 'insert_time');
+    END IF;
+END $$;
@@ -1,7 +1,5 @@
-use crate::postgres_db::PgPool;
+use crate::postgres_db::{PgConnection, PgPool};
 use anyhow::{bail, Context, Result};
-use bb8_postgres::bb8::PooledConnection;
-use bb8_postgres::PostgresConnectionManager;
 use chrono::{DateTime, Utc};
 use futures::pin_mut;
 use serde::{Deserialize, Serialize};
@@ -9,7 +7,7 @@ use serde_json::Value as JsonValue;
 use std::fmt::{Display, Formatter};
 use tokio_postgres::binary_copy::BinaryCopyInWriter;
 use tokio_postgres::types::Type;
-use tokio_postgres::{NoTls, Statement};
+use tokio_postgres::Statement;
 pub type TableName = &'static str;
 pub type TableNameRef<'a> = &'a str;
 
@@ -104,10 +102,7 @@ pub async fn save_events_one_by_one(
     async fn prepare_connection<'a>(
         pool: &'a PgPool,
         table_name: &'_ str,
-    ) -> Result<(
-        PooledConnection<'a, PostgresConnectionManager<NoTls>>,
-        Statement,
-    )> {
+    ) -> Result<(PgConnection<'a>, Statement)> {
         let sql = format!("INSERT INTO {table_name} (version, json) VALUES($1, $2)");
 
         let connection = pool
@@ -2,27 +2,88 @@ pub mod cleanup_database;
 pub mod events;
 pub mod live_ranges;
 pub mod migrator;
+pub mod order_book_snapshots;
 pub mod tests;
 
 use anyhow::{Context, Result};
 use bb8_postgres::bb8::{Pool, PooledConnection};
 use bb8_postgres::PostgresConnectionManager;
+use rustls::{ClientConfig, OwnedTrustAnchor, RootCertStore};
+use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
-use tokio_postgres::{Config, NoTls};
+use tokio_postgres::Config;
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+pub type PgConnectionManager = PostgresConnectionManager<MakeRustlsConnect>;
+pub type PgConnection<'a> = PooledConnection<'a, PgConnectionManager>;
+
+#[derive(Debug, Clone)]
+pub struct PgPoolSettings {
+    pub max_size: u32,
+    pub connection_timeout: Duration,
+    pub min_idle: Option<u32>,
+    /// Statement is aborted by server if it takes more than specified time. No limit if `None`
+    pub statement_timeout: Option<Duration>,
+}
+
+impl PgPoolSettings {
+    pub fn new(max_size: u32) -> Self {
+        Self {
+            max_size,
+            connection_timeout: Duration::from_secs(5),
+            min_idle: Some(1),
+            statement_timeout: None,
+        }
+    }
+}
 
 #[derive(Clone)]
-pub struct PgPool(Pool<PostgresConnectionManager<NoTls>>);
+pub struct PgPool(Pool<PgConnectionManager>);
 
 impl PgPool {
     pub async fn create(database_url: &str, max_size: u32) -> Result<PgPool> {
-        // TODO enable tls
-        let config = Config::from_str(database_url).context("building db connection config")?;
-        let pg_mgr = PostgresConnectionManager::new(config, NoTls);
+        Self::create_with_settings(database_url, &PgPoolSettings::new(max_size)).await
+    }
+
+    /// Create pool and apply migrations from `migrations_dir` that aren't applied yet.
+    /// Applied migrations are tracked by `sqlx` in `_sqlx_migrations` table
+    pub async fn create_with_migrations(
+        database_url: &str,
+        max_size: u32,
+        migrations_dir: &Path,
+    ) -> Result<PgPool> {
+        let pool = Self::create(database_url, max_size).await?;
+
+        migrator::apply_migrations(database_url, vec![migrations_dir.to_path_buf()])
+            .await
+            .with_context(|| format!("applying migrations from {}", migrations_dir.display()))?;
+
+        Ok(pool)
+    }
+
+    /// Create pool with SSL mode from `database_url` (`sslmode=disable|prefer|require`, `prefer` by default)
+    pub async fn create_with_settings(
+        database_url: &str,
+        settings: &PgPoolSettings,
+    ) -> Result<PgPool> {
+        let mut config = Config::from_str(database_url).context("building db connection config")?;
+
+        if let Some(statement_timeout) = settings.statement_timeout {
+            let statement_timeout_option =
+                format!("-c statement_timeout={}", statement_timeout.as_millis());
+            let options = match config.get_options() {
+                Some(options) => format!("{options} {statement_timeout_option}"),
+                None => statement_timeout_option,
+            };
+            config.options(&options);
+        }
+
+        let pg_mgr = PostgresConnectionManager::new(config, make_tls_connect());
         let pool = Pool::builder()
-            .connection_timeout(Duration::from_secs(5))
-            .min_idle(Some(1))
-            .max_size(max_size)
+            .connection_timeout(settings.connection_timeout)
+            .min_idle(settings.min_idle)
+            .max_size(settings.max_size)
             .build(pg_mgr)
             .await
             .context("building postgres connection pool")?;
@@ -32,13 +93,75 @@ impl PgPool {
 
     // compilation error without explicit lifetimes
     #[allow(clippy::needless_lifetimes)]
-    pub async fn get_connection_expected<'a>(
-        &'a self,
-    ) -> PooledConnection<'a, PostgresConnectionManager<NoTls>> {
+    pub async fn get_connection_expected<'a>(&'a self) -> PgConnection<'a> {
         self.0.get().await.expect("getting db connection from pool")
     }
 
     pub async fn is_connection_health(&self) -> bool {
         self.0.get().await.is_ok()
     }
+
+    /// Count of connections currently taken from pool
+    pub fn active_connections(&self) -> u32 {
+        let state = self.0.state();
+        state.connections - state.idle_connections
+    }
+}
+
+/// TLS connector with Mozilla root certificates. It is used only for connections with SSL enabled
+fn make_tls_connect() -> MakeRustlsConnect {
+    let mut root_store = RootCertStore::empty();
+    root_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|anchor| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            anchor.subject,
+            anchor.spki,
+            anchor.name_constraints,
+        )
+    }));
+
+    let tls_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+
+    MakeRustlsConnect::new(tls_config)
+}
+
+#[cfg(test)]
+mod pool_tests {
+    use crate::postgres_db::tests::{get_database_url, PgPoolMutex};
+    use crate::postgres_db::PgPool;
+    use std::time::{Duration, Instant};
+
+    const QUERY_DURATION: Duration = Duration::from_millis(500);
+
+    async fn sleep_query(pool: &PgPool) -> Instant {
+        let connection = pool.get_connection_expected().await;
+        let started = Instant::now();
+        connection
+            .batch_execute(&format!(
+                "SELECT pg_sleep({})",
+                QUERY_DURATION.as_secs_f64()
+            ))
+            .await
+            .expect("in test");
+        started
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn third_query_waits_for_free_connection() {
+        let pool_mutex = PgPoolMutex::create(&get_database_url(), 2).await;
+        let pool = &pool_mutex.pool;
+
+        let started = Instant::now();
+        let (first, second, third) =
+            futures::join!(sleep_query(pool), sleep_query(pool), sleep_query(pool));
+
+        // only 2 queries are executed simultaneously, the last one waits until any of them completes
+        let mut starts = [first, second, third];
+        starts.sort();
+        assert!(starts[1] - started < QUERY_DURATION);
+        assert!(starts[2] - started >= QUERY_DURATION);
+        assert_eq!(pool.active_connections(), 0);
+    }
 }
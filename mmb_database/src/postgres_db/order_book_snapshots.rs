@@ -0,0 +1,70 @@
+use crate::postgres_db::PgPool;
+
+use chrono::{DateTime, Utc};
+use tokio_postgres::Row;
+
+pub const ORDER_BOOK_SNAPSHOTS_TABLE_NAME: &str = "order_book_snapshots";
+pub const LIQUIDITY_ORDER_BOOKS_TABLE_NAME: &str = "liquidity_order_books";
+
+#[derive(Debug, Clone)]
+pub struct LastOrderBookSnapshotTime {
+    pub exchange_id: String,
+    pub currency_pair: String,
+    pub insert_time: DateTime<Utc>,
+}
+
+impl From<&Row> for LastOrderBookSnapshotTime {
+    fn from(row: &Row) -> Self {
+        LastOrderBookSnapshotTime {
+            exchange_id: row.get(0),
+            currency_pair: row.get(1),
+            insert_time: row.get(2),
+        }
+    }
+}
+
+/// Time of the latest recorded order book for every market that has order books newer than `since`.
+/// Order books are recorded continuously to `liquidity_order_books` (if the table exists)
+/// and gaps are filled with REST snapshots in `order_book_snapshots`, so both tables are checked
+pub async fn get_last_order_book_snapshot_times(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+) -> anyhow::Result<Vec<LastOrderBookSnapshotTime>> {
+    let connection = pool.0.get().await?;
+
+    let has_liquidity_order_books: bool = connection
+        .query_one(
+            &format!("select to_regclass('{LIQUIDITY_ORDER_BOOKS_TABLE_NAME}') is not null"),
+            &[],
+        )
+        .await?
+        .get(0);
+
+    let liquidity_order_books_times = match has_liquidity_order_books {
+        true => format!(
+            "select json->>'exchange_id', json->>'currency_pair', insert_time
+             from {LIQUIDITY_ORDER_BOOKS_TABLE_NAME}
+             where insert_time >= $1
+             union all"
+        ),
+        false => String::new(),
+    };
+
+    let sql = format!(
+        "select exchange_id, currency_pair, max(insert_time)
+         from (
+             {liquidity_order_books_times}
+             select regexp_replace(json->>'exchange_account_id', '_\\d+$', ''), json->>'currency_pair', insert_time
+             from {ORDER_BOOK_SNAPSHOTS_TABLE_NAME}
+             where insert_time >= $1
+         ) as recorded (exchange_id, currency_pair, insert_time)
+         group by 1, 2"
+    );
+    let rows = connection
+        .query(&sql, &[&since])
+        .await?
+        .iter()
+        .map(LastOrderBookSnapshotTime::from)
+        .collect();
+    Ok(rows)
+}
@@ -0,0 +1,4 @@
+CREATE TABLE test_mig4(
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL
+);
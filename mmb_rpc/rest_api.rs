@@ -1,5 +1,7 @@
 use jsonrpc_core::{Error, Result};
 use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::typed::Subscriber;
+use jsonrpc_pubsub::SubscriptionId;
 
 #[cfg(unix)]
 pub static IPC_ADDRESS: &str = "/tmp/mmb_core.ipc";
@@ -8,6 +10,8 @@ pub static IPC_ADDRESS: &str = r#"\\.\pipe\mmb_core"#;
 
 #[rpc]
 pub trait MmbRpc {
+    type Metadata;
+
     #[rpc(name = "health")]
     fn health(&self) -> Result<String>;
 
@@ -22,12 +26,40 @@ pub trait MmbRpc {
 
     #[rpc(name = "stats")]
     fn stats(&self) -> Result<String>;
+
+    #[rpc(name = "list_open_orders")]
+    fn list_open_orders(&self) -> Result<String>;
+
+    #[rpc(name = "cancel_order")]
+    fn cancel_order(&self, client_order_id: String) -> Result<String>;
+
+    /// Check if an order in JSON format would be accepted without placing it
+    #[rpc(name = "simulate_order")]
+    fn simulate_order(&self, order: String) -> Result<String>;
+
+    /// Subscribe to order events in JSON format
+    #[pubsub(subscription = "events", subscribe, name = "subscribe_events")]
+    fn subscribe_events(&self, meta: Self::Metadata, subscriber: Subscriber<String>);
+
+    #[pubsub(subscription = "events", unsubscribe, name = "unsubscribe_events")]
+    fn unsubscribe_events(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription_id: SubscriptionId,
+    ) -> Result<bool>;
+
+    /// Price slots and orders of DispositionExecutor in Graphviz DOT format
+    #[rpc(name = "get_executor_graph")]
+    fn get_executor_graph(&self) -> Result<String>;
 }
 
 pub enum ErrorCode {
     StopperIsNone = 1,
     UnableToSendSignal = 2,
     FailedToSaveNewConfig = 3,
+    FailedToSerializeOrders = 4,
+    OrderNotFound = 5,
+    SubscriptionNotFound = 6,
 }
 
 pub fn server_side_error(code: ErrorCode) -> Error {
@@ -35,6 +67,9 @@ pub fn server_side_error(code: ErrorCode) -> Error {
         ErrorCode::StopperIsNone => "Server stopper is none",
         ErrorCode::UnableToSendSignal => "Unable to send signal",
         ErrorCode::FailedToSaveNewConfig => "Failed to save new config",
+        ErrorCode::FailedToSerializeOrders => "Failed to serialize orders",
+        ErrorCode::OrderNotFound => "Open order isn't found",
+        ErrorCode::SubscriptionNotFound => "Subscription isn't found",
     };
     log::error!("Rest API error: {}", reason);
     Error::new(jsonrpc_core::ErrorCode::ServerError(code as i64))
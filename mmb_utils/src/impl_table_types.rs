@@ -1,6 +1,7 @@
 use parking_lot::Mutex;
 use paste::paste;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
 use crate::infrastructure::WithExpect;
 
@@ -128,21 +129,56 @@ impl AppendTable16 {
 
 impl_append_table!(16);
 
+/// Error of parsing table type from string that doesn't match string representation of parsed value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableTypeParseError {
+    pub type_name: &'static str,
+    pub value: String,
+    pub parsed: String,
+}
+
+impl TableTypeParseError {
+    pub fn new(type_name: &'static str, value: &str, parsed: &str) -> Self {
+        Self {
+            type_name,
+            value: value.to_owned(),
+            parsed: parsed.to_owned(),
+        }
+    }
+}
+
+impl Display for TableTypeParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unable to parse '{}' as {} because it is represented as '{}'",
+            self.value, self.type_name, self.parsed
+        )
+    }
+}
+
+impl std::error::Error for TableTypeParseError {}
+
 // Implement type with specified name based on AppendTable8 or AppendTable16 with methods:
 // from_raw - private constructor
 // as_str
 //
 // and implementations for traits:
 // fmt::Display
+// str::FromStr - checks that parsed value is displayed the same as input string
+// TryFrom<String>
 // serde::Serialize
-// serde::Deserialize
+// serde::Deserialize - via TryFrom<String>
+//
+// Type should implement From<&str>
 #[macro_export]
 macro_rules! impl_table_type_raw {
     ($ty: ident, $bits:literal, $bits_ty:ty) => {
         // parameter `$bits_ty` added for correct work of code completions in IntelliJ Rust
         // (we can declare `pub struct $ty($bits_ty);` without using procedural macro `paste!`)
 
-        #[derive(Copy, Clone, Eq, PartialEq, Hash)]
+        #[derive(Copy, Clone, Eq, PartialEq, Hash, serde::Deserialize)]
+        #[serde(try_from = "String")]
         pub struct $ty($bits_ty);
 
         paste::paste! {
@@ -152,6 +188,7 @@ macro_rules! impl_table_type_raw {
         }
 
         impl $ty {
+            #[allow(dead_code)]
             fn from_raw(value: &str) -> Self {
                 Self(paste::paste! { [<SHARED_ $ty:snake:upper>] }.add_or_get(value))
             }
@@ -176,37 +213,29 @@ macro_rules! impl_table_type_raw {
             }
         }
 
-        paste::paste! {
-            struct [<$ty Visitor>];
-        }
-
-        paste::paste! {
-            #[allow(unused_qualifications)]
-            impl<'de> serde::de::Visitor<'de> for [<$ty Visitor>] {
-                type Value = $ty;
-
-                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                    write!(formatter, "string for {}", stringify!($ty))
-                }
-
-                fn visit_str<E>(self, v: &str) -> std::result::Result<Self::Value, E>
-                where
-                    E: serde::de::Error,
-                {
-                    Ok(<$ty>::from_raw(v))
+        #[allow(unused_qualifications)]
+        impl std::str::FromStr for $ty {
+            type Err = mmb_utils::impl_table_types::TableTypeParseError;
+
+            fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+                let parsed = <$ty as From<&str>>::from(value);
+                match parsed.as_str() == value {
+                    true => Ok(parsed),
+                    false => Err(mmb_utils::impl_table_types::TableTypeParseError::new(
+                        stringify!($ty),
+                        value,
+                        parsed.as_str(),
+                    )),
                 }
             }
         }
 
         #[allow(unused_qualifications)]
-        impl<'de> serde::de::Deserialize<'de> for $ty {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::de::Deserializer<'de>,
-            {
-                paste::paste! {
-                    deserializer.deserialize_str([<$ty Visitor>])
-                }
+        impl std::convert::TryFrom<String> for $ty {
+            type Error = mmb_utils::impl_table_types::TableTypeParseError;
+
+            fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+                value.parse()
             }
         }
 
@@ -229,6 +258,7 @@ macro_rules! impl_table_type_raw {
 //
 // and implementations for traits:
 // fmt::Display
+// str::FromStr
 // serde::Serialize
 // serde::Deserialize
 // From
@@ -24,9 +24,15 @@ bitflags! {
         const DENY_CANCELLATION = 0b00000001;
         /// If this flag is set the future will be forced to stop at the end of graceful_shutdown
         const STOP_BY_TOKEN = 0b00000010;
+        /// Restart the future after panic instead of graceful shutdown. Works only with
+        /// spawn_future_with_retry() that limits count of restarts
+        const RETRY_ON_PANIC = 0b00000100;
     }
 }
 
+const RETRY_ON_PANIC_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const RETRY_ON_PANIC_MAX_DELAY: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct FutureOutcome {
     name: String,
@@ -157,6 +163,72 @@ pub fn spawn_future_standalone(
     })
 }
 
+/// Spawn future that is created by `action` and restarted after panic up to `max_retries` times
+/// with exponential backoff if `SpawnFutureFlags::RETRY_ON_PANIC` is set.
+/// Graceful shutdown is started only if the future panicked after all retries.
+/// Other nuances are the same as spawn_future()
+pub fn spawn_future_with_retry<F, Fut>(
+    action_name: &str,
+    flags: SpawnFutureFlags,
+    max_retries: u32,
+    action: F,
+    graceful_shutdown_spawner: impl FnOnce(String, &str) + 'static + Send,
+    cancellation_token: CancellationToken,
+) -> tokio::task::JoinHandle<FutureOutcome>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let action_name = action_name.to_owned();
+    let future_id = Uuid::new_v4();
+    let max_retries = match flags.intersects(SpawnFutureFlags::RETRY_ON_PANIC) {
+        true => max_retries,
+        false => 0,
+    };
+
+    log::info!("Future '{action_name}' with id '{future_id}' started");
+
+    tokio::spawn(async move {
+        let mut retry_delay = RETRY_ON_PANIC_INITIAL_DELAY;
+        for retry in 1..=max_retries {
+            let future_outcome = handle_action_outcome(
+                action_name.clone(),
+                future_id,
+                flags,
+                action(),
+                |_, _| {},
+                cancellation_token.clone(),
+            )
+            .await;
+
+            if future_outcome.completion_reason != CompletionReason::Panicked {
+                return future_outcome;
+            }
+
+            log::warn!("Future '{action_name}' with id {future_id} will be restarted after panic in {retry_delay:?} (retry {retry} of {max_retries})");
+
+            tokio::select! {
+                _ = tokio::time::sleep(retry_delay) => {}
+                _ = cancellation_token.when_cancelled() => {
+                    return FutureOutcome::new(action_name, future_id, CompletionReason::Canceled);
+                }
+            }
+
+            retry_delay = (retry_delay * 2).min(RETRY_ON_PANIC_MAX_DELAY);
+        }
+
+        handle_action_outcome(
+            action_name,
+            future_id,
+            flags,
+            action(),
+            graceful_shutdown_spawner,
+            cancellation_token,
+        )
+        .await
+    })
+}
+
 async fn handle_action_outcome(
     action_name: String,
     future_id: Uuid,
@@ -441,6 +513,136 @@ mod test {
         assert!(!*test_value.lock());
     }
 
+    mod with_retry {
+        use super::*;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        fn panicking_action(
+            attempts: Arc<AtomicU32>,
+            panics_count: u32,
+        ) -> impl Fn() -> futures::future::BoxFuture<'static, Result<()>> {
+            move || {
+                let attempts = attempts.clone();
+                async move {
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                    if attempt <= panics_count {
+                        panic!("Test panic on attempt {attempt}");
+                    }
+
+                    Ok(())
+                }
+                .boxed()
+            }
+        }
+
+        #[tokio::test]
+        async fn restarted_until_retries_are_exhausted() -> Result<()> {
+            // Arrange
+            let attempts = Arc::new(AtomicU32::new(0));
+            let shutdown_calls = Arc::new(AtomicU32::new(0));
+            let shutdown_calls_in_spawner = shutdown_calls.clone();
+
+            // Act
+            let future_outcome = spawn_future_with_retry(
+                "test_action_name",
+                SpawnFutureFlags::RETRY_ON_PANIC | SpawnFutureFlags::DENY_CANCELLATION,
+                3,
+                panicking_action(attempts.clone(), u32::MAX),
+                move |_, _| {
+                    let _ = shutdown_calls_in_spawner.fetch_add(1, Ordering::SeqCst);
+                },
+                CancellationToken::default(),
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(future_outcome.completion_reason, CompletionReason::Panicked);
+            assert_eq!(attempts.load(Ordering::SeqCst), 4);
+            assert_eq!(shutdown_calls.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn completed_after_restart() -> Result<()> {
+            // Arrange
+            let attempts = Arc::new(AtomicU32::new(0));
+            let shutdown_calls = Arc::new(AtomicU32::new(0));
+            let shutdown_calls_in_spawner = shutdown_calls.clone();
+
+            // Act
+            let future_outcome = spawn_future_with_retry(
+                "test_action_name",
+                SpawnFutureFlags::RETRY_ON_PANIC | SpawnFutureFlags::DENY_CANCELLATION,
+                3,
+                panicking_action(attempts.clone(), 2),
+                move |_, _| {
+                    let _ = shutdown_calls_in_spawner.fetch_add(1, Ordering::SeqCst);
+                },
+                CancellationToken::default(),
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(
+                future_outcome.completion_reason,
+                CompletionReason::CompletedSuccessfully
+            );
+            assert_eq!(attempts.load(Ordering::SeqCst), 3);
+            assert_eq!(shutdown_calls.load(Ordering::SeqCst), 0);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn not_restarted_without_flag() -> Result<()> {
+            // Arrange
+            let attempts = Arc::new(AtomicU32::new(0));
+
+            // Act
+            let future_outcome = spawn_future_with_retry(
+                "test_action_name",
+                SpawnFutureFlags::DENY_CANCELLATION,
+                3,
+                panicking_action(attempts.clone(), u32::MAX),
+                |_, _| {},
+                CancellationToken::default(),
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(future_outcome.completion_reason, CompletionReason::Panicked);
+            assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        }
+
+        #[tokio::test]
+        async fn retries_stopped_by_cancellation_token() -> Result<()> {
+            // Arrange
+            let attempts = Arc::new(AtomicU32::new(0));
+            let cancellation_token = CancellationToken::default();
+            cancellation_token.cancel();
+
+            // Act
+            let future_outcome = spawn_future_with_retry(
+                "test_action_name",
+                SpawnFutureFlags::RETRY_ON_PANIC | SpawnFutureFlags::DENY_CANCELLATION,
+                3,
+                panicking_action(attempts.clone(), u32::MAX),
+                |_, _| {},
+                cancellation_token,
+            )
+            .await?;
+
+            // Assert
+            assert_eq!(future_outcome.completion_reason, CompletionReason::Canceled);
+            assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+            Ok(())
+        }
+    }
+
     mod with_timer {
         use std::sync::Arc;
 
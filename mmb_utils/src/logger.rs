@@ -1,6 +1,13 @@
 use anyhow::{Context, Result};
-use log4rs::config::Deserializers;
-use log4rs::init_file;
+use log::LevelFilter;
+use log4rs::append::console::ConsoleAppender;
+use log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller;
+use log4rs::append::rolling_file::policy::compound::trigger::size::SizeTrigger;
+use log4rs::append::rolling_file::policy::compound::CompoundPolicy;
+use log4rs::append::rolling_file::RollingFileAppender;
+use log4rs::config::{Appender, Config, Deserializers, Root};
+use log4rs::encode::pattern::PatternEncoder;
+use log4rs::{init_config, init_file};
 use serde_yaml::Value;
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Display, Formatter};
@@ -24,6 +31,94 @@ pub fn init_logger() {
     ));
 }
 
+const LOG_PATTERN: &str = "{d(%Y-%m-%d %H:%M:%S):<20} {M:>20.30}:{L:>3} {h({l})}    {m}\n";
+const BYTES_IN_MB: u64 = 1024 * 1024;
+
+/// Rotation settings of log file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggerConfig {
+    /// Log file is rotated when its size exceeds this value
+    pub max_file_size_mb: u64,
+    /// Count of rotated files to keep. The oldest one is removed when the count is exceeded
+    pub max_files: u32,
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        LoggerConfig {
+            max_file_size_mb: 100,
+            max_files: 5,
+        }
+    }
+}
+
+/// Init logger which writes to stdout and to file by `path` that is rotated by size.
+/// Rotated files are named `{path}.0`, `{path}.1`, etc. where `{path}.0` is the newest one
+pub fn init_logger_with_rotation(path: &str, config: LoggerConfig) {
+    if env::var("MMB_NO_LOGS").is_ok() {
+        return;
+    }
+
+    static INIT_LOGGER: Once = Once::new();
+    INIT_LOGGER.call_once(|| {
+        let log_config = rotating_log_config(path, config).expect("Unable to set up logger");
+        init_config(log_config).expect("Unable to set up logger");
+    });
+
+    print_info(format_args!(
+        "Logger has been initialized all logs will be stored here: stdout, {path} (rotated by {} MB, {} files are kept)",
+        config.max_file_size_mb, config.max_files
+    ));
+}
+
+fn rotating_log_config(path: &str, config: LoggerConfig) -> Result<Config> {
+    let file_appender = rolling_file_appender(
+        path,
+        config.max_file_size_mb * BYTES_IN_MB,
+        config.max_files,
+    )?;
+    let console_appender = ConsoleAppender::builder()
+        .encoder(Box::new(PatternEncoder::new(LOG_PATTERN)))
+        .build();
+
+    Config::builder()
+        .appender(
+            Appender::builder()
+                .filter(Box::new(outer_modules_filter::Filter))
+                .build("stdout", Box::new(console_appender)),
+        )
+        .appender(
+            Appender::builder()
+                .filter(Box::new(outer_modules_filter::Filter))
+                .build("file", Box::new(file_appender)),
+        )
+        .build(
+            Root::builder()
+                .appenders(["stdout", "file"])
+                .build(LevelFilter::Trace),
+        )
+        .context("Failed to build logger config")
+}
+
+fn rolling_file_appender(
+    path: &str,
+    max_file_size_bytes: u64,
+    max_files: u32,
+) -> Result<RollingFileAppender> {
+    let roller = FixedWindowRoller::builder()
+        .build(&format!("{path}.{{}}"), max_files)
+        .context("Failed to create log files roller")?;
+    let policy = CompoundPolicy::new(
+        Box::new(SizeTrigger::new(max_file_size_bytes)),
+        Box::new(roller),
+    );
+
+    RollingFileAppender::builder()
+        .encoder(Box::new(PatternEncoder::new(LOG_PATTERN)))
+        .build(path, Box::new(policy))
+        .with_context(|| format!("Failed to create log file {path}"))
+}
+
 struct Loggers {
     info: Vec<LoggerType>,
 }
@@ -147,6 +242,45 @@ where
     println!("{msg}");
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::{Level, Record};
+    use log4rs::append::Append;
+    use uuid::Uuid;
+
+    #[test]
+    fn rotate_log_file_by_size() {
+        let dir = env::temp_dir().join(format!("mmb_logs_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("in test");
+        let path = dir.join("log.log");
+        let path = path.to_str().expect("in test");
+
+        let appender = rolling_file_appender(path, 100, 2).expect("in test");
+        for i in 0..20 {
+            appender
+                .append(
+                    &Record::builder()
+                        .level(Level::Info)
+                        .args(format_args!("log record number {i}"))
+                        .build(),
+                )
+                .expect("in test");
+        }
+        appender.flush();
+
+        let rotated_file = format!("{path}.0");
+        assert!(Path::new(&rotated_file).exists());
+        assert!(!fs::read_to_string(&rotated_file)
+            .expect("in test")
+            .is_empty());
+        assert!(Path::new(&format!("{path}.1")).exists());
+        assert!(!Path::new(&format!("{path}.2")).exists());
+
+        fs::remove_dir_all(&dir).expect("in test");
+    }
+}
+
 pub mod outer_modules_filter {
     use anyhow::Result;
     use log::{Level, Record};
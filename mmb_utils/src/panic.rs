@@ -1,8 +1,8 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::fmt::Display;
 
-use backtrace::Backtrace;
 use uuid::Uuid;
 
 use crate::{
@@ -16,12 +16,31 @@ thread_local! {
 
 pub static HOOK_IS_NOT_SET: &str = "Panic hook isn't set backtrace won't be logged";
 pub static PANIC_DETECTED_IN_NO_PANIC_STATE: &str = "Panic detected but PanicState is NoPanic";
+pub static BACKTRACE_IS_NOT_CAPTURED: &str =
+    "Backtrace isn't captured, set RUST_BACKTRACE=1 to capture it";
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum PanicState {
     PanicHookIsNotSet,
     NoPanic,
-    PanicHappened(String),
+    PanicHappened { message: String, backtrace: String },
+}
+
+/// Backtrace of current thread if it is enabled by `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`
+fn capture_backtrace() -> Option<String> {
+    let backtrace = Backtrace::capture();
+    match backtrace.status() {
+        BacktraceStatus::Captured => Some(backtrace.to_string()),
+        _ => None,
+    }
+}
+
+/// Appends backtrace of current thread to message if backtrace capturing is enabled
+pub fn with_backtrace(message: &str) -> String {
+    match capture_backtrace() {
+        Some(backtrace) => format!("{message}\n{backtrace}"),
+        None => message.to_owned(),
+    }
 }
 
 pub fn set_panic_hook() {
@@ -31,14 +50,17 @@ pub fn set_panic_hook() {
             None => &"Failed to get location from PanicInfo",
         };
 
-        let location_and_backtrace = format!("At {}\n{:?}", location, Backtrace::new());
+        let thread = std::thread::current();
+        let thread_name = thread.name().unwrap_or("<unnamed>");
+        let message = format!("Thread '{thread_name}' panicked at {location}");
+        let backtrace = capture_backtrace().unwrap_or_else(|| BACKTRACE_IS_NOT_CAPTURED.to_owned());
 
         PANIC_STATE
             .try_with(|panic_state| {
-                *panic_state.borrow_mut() = PanicState::PanicHappened(location_and_backtrace);
+                *panic_state.borrow_mut() = PanicState::PanicHappened { message, backtrace };
             })
             .unwrap_or_else(|_| {
-                log::error!("Unable write `location_and_backtrace` to `PANIC_STATE`");
+                log::error!("Unable write panic message and backtrace to `PANIC_STATE`");
             });
     }));
 
@@ -64,14 +86,16 @@ pub fn handle_future_panic(
         .try_with(
             |panic_state| match panic_state.replace(PanicState::NoPanic) {
                 PanicState::PanicHookIsNotSet => {
-                    log::warn!("{HOOK_IS_NOT_SET}");
+                    log::warn!("{}", with_backtrace(HOOK_IS_NOT_SET));
                     Cow::Borrowed("")
                 }
                 PanicState::NoPanic => {
-                    log::error!("{PANIC_DETECTED_IN_NO_PANIC_STATE}");
+                    log::error!("{}", with_backtrace(PANIC_DETECTED_IN_NO_PANIC_STATE));
                     Cow::Borrowed("")
                 }
-                PanicState::PanicHappened(msg) => Cow::Owned(msg),
+                PanicState::PanicHappened { message, backtrace } => {
+                    Cow::Owned(format!("{message}\n{backtrace}"))
+                }
             },
         )
         .unwrap_or(Cow::Borrowed(
@@ -82,3 +106,31 @@ pub fn handle_future_panic(
     (graceful_shutdown_spawner)(log_template, panic_message);
     FutureOutcome::new(action_name, future_id, CompletionReason::Panicked)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::catch_unwind;
+
+    #[test]
+    fn panic_state_contains_thread_name() {
+        let panic_state = std::thread::Builder::new()
+            .name("panicking_thread".into())
+            .spawn(|| {
+                set_panic_hook();
+                let _ = catch_unwind(|| panic!("test panic"));
+                PANIC_STATE.with(|panic_state| panic_state.borrow().clone())
+            })
+            .expect("in test")
+            .join()
+            .expect("in test");
+
+        match panic_state {
+            PanicState::PanicHappened { message, backtrace } => {
+                assert!(message.contains("panicking_thread"), "{message}");
+                assert!(!backtrace.is_empty());
+            }
+            state => panic!("unexpected panic state {state:?}"),
+        }
+    }
+}
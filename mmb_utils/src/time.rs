@@ -49,3 +49,37 @@ impl ToStdExpected for chrono::Duration {
         })
     }
 }
+
+/// Interval increased by random jitter in range `[0, base * jitter_fraction]`.
+/// Used to avoid simultaneous requests from many bot instances working by the same schedule
+pub fn jittered_interval(base: Duration, jitter_fraction: f64) -> Duration {
+    let jitter_fraction = jitter_fraction.clamp(0.0, 1.0);
+    if jitter_fraction == 0.0 {
+        return base;
+    }
+
+    base + base.mul_f64(jitter_fraction * rand::random::<f64>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jittered_interval_without_jitter() {
+        let base = Duration::from_secs(30);
+
+        assert_eq!(jittered_interval(base, 0.0), base);
+    }
+
+    #[test]
+    fn jittered_interval_in_range() {
+        let base = Duration::from_secs(30);
+
+        for _ in 0..100 {
+            let interval = jittered_interval(base, 0.1);
+            assert!(interval >= base);
+            assert!(interval <= Duration::from_secs(33));
+        }
+    }
+}
@@ -4,6 +4,9 @@ use anyhow::{Context, Result};
 use rust_decimal::Decimal;
 use serde_json::Value;
 
+/// Max count of digits after decimal point kept for values in scientific notation
+pub const SCIENTIFIC_NOTATION_MAX_SCALE: u32 = 18;
+
 pub trait GetOrErr {
     fn get_as_str(&self, key: &str) -> Result<String>;
     fn get_as_decimal(&self, key: &str) -> Option<Decimal>;
@@ -22,6 +25,73 @@ impl GetOrErr for Value {
     fn get_as_decimal(&self, key: &str) -> Option<Decimal> {
         self.get(key)
             .and_then(|value| value.as_str())
-            .and_then(|value| Decimal::from_str(value).ok())
+            .and_then(|value| value_to_decimal(value).ok())
+    }
+}
+
+/// Parse decimal value from string which can be in scientific notation like `1.5e-8`
+pub fn value_to_decimal(value: &str) -> Result<Decimal> {
+    value_to_decimal_with_max_scale(value, SCIENTIFIC_NOTATION_MAX_SCALE)
+}
+
+/// Parse decimal value from string which can be in scientific notation.
+/// Values in scientific notation are rounded to `max_scale` digits after decimal point
+pub fn value_to_decimal_with_max_scale(value: &str, max_scale: u32) -> Result<Decimal> {
+    if !value.contains(['e', 'E']) {
+        return Decimal::from_str(value)
+            .with_context(|| format!("Unable to parse decimal from {value}"));
+    }
+
+    let float = f64::from_str(value)
+        .with_context(|| format!("Unable to parse scientific notation from {value}"))?;
+    let decimal = Decimal::from_f64_retain(float)
+        .with_context(|| format!("Unable to convert {value} to decimal"))?;
+
+    Ok(decimal.round_dp(max_scale).normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value_to_decimal::{value_to_decimal, value_to_decimal_with_max_scale};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn parse_small_value_in_scientific_notation() {
+        assert_eq!(
+            value_to_decimal("1.5e-8").expect("in test"),
+            dec!(0.000000015)
+        );
+    }
+
+    #[test]
+    fn parse_big_value_in_scientific_notation() {
+        assert_eq!(
+            value_to_decimal("2.5E10").expect("in test"),
+            dec!(25000000000)
+        );
+    }
+
+    #[test]
+    fn parse_zero() {
+        assert_eq!(value_to_decimal("0").expect("in test"), dec!(0));
+    }
+
+    #[test]
+    fn parse_plain_decimal() {
+        assert_eq!(value_to_decimal("0.00012").expect("in test"), dec!(0.00012));
+    }
+
+    #[test]
+    fn round_scientific_notation_to_max_scale() {
+        assert_eq!(
+            value_to_decimal_with_max_scale("1.23456789e-3", 4).expect("in test"),
+            dec!(0.0012)
+        );
+    }
+
+    #[test]
+    fn error_for_not_a_number() {
+        assert!(value_to_decimal("abc").is_err());
+        assert!(value_to_decimal("1.5e").is_err());
     }
 }
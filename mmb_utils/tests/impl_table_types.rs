@@ -0,0 +1,7 @@
+#[test]
+fn impl_table_types_expansion() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/impl_table_type_round_trip.rs");
+    cases.pass("tests/ui/impl_table_type_raw_normalized.rs");
+    cases.compile_fail("tests/ui/impl_table_type_raw_without_from.rs");
+}
@@ -0,0 +1,29 @@
+use mmb_utils::impl_table_type_raw;
+use std::convert::TryFrom;
+
+impl_table_type_raw!(ExampleCode, 16, u16);
+
+impl From<&str> for ExampleCode {
+    fn from(value: &str) -> Self {
+        ExampleCode::from_raw(&value.to_lowercase())
+    }
+}
+
+fn main() {
+    let code = "btc"
+        .parse::<ExampleCode>()
+        .expect("Failed to parse ExampleCode");
+    assert_eq!(code.to_string(), "btc");
+
+    let error = "BTC"
+        .parse::<ExampleCode>()
+        .expect_err("Parsing must fail because of normalization");
+    assert_eq!(error.value, "BTC");
+    assert_eq!(error.parsed, "btc");
+    assert!(ExampleCode::try_from("BTC".to_owned()).is_err());
+
+    assert!(serde_json::from_str::<ExampleCode>(r#""BTC""#).is_err());
+    let deserialized: ExampleCode =
+        serde_json::from_str(r#""btc""#).expect("Failed to deserialize ExampleCode");
+    assert_eq!(deserialized, code);
+}
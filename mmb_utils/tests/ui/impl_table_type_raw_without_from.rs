@@ -0,0 +1,6 @@
+use mmb_utils::impl_table_type_raw;
+
+// FromStr implementation requires From<&str> to be implemented for the type
+impl_table_type_raw!(ExampleCode, 16, u16);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use mmb_utils::impl_table_type;
+
+impl_table_type!(ExampleId, 8, u8);
+
+fn main() {
+    let id = "Binance"
+        .parse::<ExampleId>()
+        .expect("Failed to parse ExampleId");
+    assert_eq!(id.to_string(), "Binance");
+    assert_eq!(id, ExampleId::new("Binance"));
+
+    let deserialized: ExampleId =
+        serde_json::from_str(r#""Binance""#).expect("Failed to deserialize ExampleId");
+    assert_eq!(deserialized, id);
+    assert_eq!(
+        serde_json::to_string(&deserialized).expect("Failed to serialize ExampleId"),
+        r#""Binance""#
+    );
+}
@@ -1,3 +1,4 @@
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
 use mmb_domain::order::snapshot::Amount;
@@ -29,3 +30,137 @@ pub fn load_config(filepath: &str) -> AppConfig {
         .unwrap_or_else(|_| panic!("Failure open file {filepath}"));
     toml::from_str(&file_content).expect("Failure parse config file")
 }
+
+pub const ACCESS_TOKEN_SECRET_VAR: &str = "MMB_JWT_SECRET";
+pub const REFRESH_TOKEN_SECRET_VAR: &str = "MMB_REFRESH_SECRET";
+
+pub struct TokenSecrets {
+    pub access_token_secret: String,
+    pub refresh_token_secret: String,
+}
+
+/// Load secrets for signing JWT from environment variables.
+/// In development variables can be specified in `.env` file
+pub fn load_token_secrets() -> Result<TokenSecrets> {
+    Ok(TokenSecrets {
+        access_token_secret: load_secret(ACCESS_TOKEN_SECRET_VAR)?,
+        refresh_token_secret: load_secret(REFRESH_TOKEN_SECRET_VAR)?,
+    })
+}
+
+fn load_secret(var_name: &str) -> Result<String> {
+    let secret = std::env::var(var_name).with_context(|| {
+        format!("Environment variable {var_name} with secret for signing JWT is not set")
+    })?;
+
+    if secret.is_empty() {
+        bail!("Environment variable {var_name} with secret for signing JWT is empty");
+    }
+
+    Ok(secret)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::services::token::TokenService;
+
+    static ENV_MUTEX: Mutex<()> = Mutex::new(());
+
+    /// Run `f` with specified environment variables. `None` value means that variable is unset.
+    /// Previous values of variables are restored after `f` is completed
+    pub(crate) fn with_env_vars<T>(vars: &[(&str, Option<&str>)], f: impl FnOnce() -> T) -> T {
+        let _guard = ENV_MUTEX.lock().unwrap_or_else(|err| err.into_inner());
+
+        let previous_values: Vec<_> = vars
+            .iter()
+            .map(|(name, _)| (*name, std::env::var(name).ok()))
+            .collect();
+
+        let set_vars = |vars: &[(&str, Option<&str>)]| {
+            for (name, value) in vars {
+                match value {
+                    Some(value) => std::env::set_var(name, value),
+                    None => std::env::remove_var(name),
+                }
+            }
+        };
+
+        set_vars(vars);
+        let result = f();
+
+        let previous_values: Vec<_> = previous_values
+            .iter()
+            .map(|(name, value)| (*name, value.as_deref()))
+            .collect();
+        set_vars(&previous_values);
+
+        result
+    }
+
+    fn token_service() -> Result<TokenService> {
+        let secrets = load_token_secrets()?;
+        Ok(TokenService::new(
+            secrets.access_token_secret,
+            secrets.refresh_token_secret,
+            60,
+            60,
+        ))
+    }
+
+    #[test]
+    fn tokens_generated_with_secrets_from_env() {
+        let vars = [
+            (ACCESS_TOKEN_SECRET_VAR, Some("access_secret")),
+            (REFRESH_TOKEN_SECRET_VAR, Some("refresh_secret")),
+        ];
+
+        with_env_vars(&vars, || {
+            let token_service = token_service().expect("secrets are set");
+
+            let (access_token, _) = token_service
+                .generate_access_token("admin", "admin")
+                .expect("generate access token");
+            let claim = token_service
+                .parse_access_token(&access_token)
+                .expect("parse access token");
+            assert_eq!(claim.username, "admin");
+
+            let refresh_token = token_service
+                .generate_refresh_token("admin", "admin")
+                .expect("generate refresh token");
+            // token signed by refresh secret can't be used as access token
+            assert!(token_service.parse_access_token(&refresh_token).is_err());
+        });
+    }
+
+    #[test]
+    fn token_service_not_created_without_access_secret() {
+        let vars = [
+            (ACCESS_TOKEN_SECRET_VAR, None),
+            (REFRESH_TOKEN_SECRET_VAR, Some("refresh_secret")),
+        ];
+
+        let error = with_env_vars(&vars, token_service)
+            .err()
+            .expect("access token secret is not set");
+
+        assert!(error.to_string().contains(ACCESS_TOKEN_SECRET_VAR));
+    }
+
+    #[test]
+    fn token_service_not_created_with_empty_refresh_secret() {
+        let vars = [
+            (ACCESS_TOKEN_SECRET_VAR, Some("access_secret")),
+            (REFRESH_TOKEN_SECRET_VAR, Some("")),
+        ];
+
+        let error = with_env_vars(&vars, token_service)
+            .err()
+            .expect("refresh token secret is empty");
+
+        assert!(error.to_string().contains(REFRESH_TOKEN_SECRET_VAR));
+    }
+}
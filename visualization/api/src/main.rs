@@ -18,8 +18,9 @@
 
 use casbin::{CoreApi, Enforcer};
 use chrono::Duration;
+use mmb_utils::logger::LoggerConfig;
 
-use crate::config::load_config;
+use crate::config::{load_config, load_token_secrets};
 use crate::handlers::ws::ws_client;
 use crate::server::start;
 use crate::services::data_provider::liquidity::LiquidityService;
@@ -40,15 +41,22 @@ mod ws;
 async fn main() -> std::io::Result<()> {
     configure_logger();
 
+    // `.env` file is optional, it is used in development to specify environment variables
+    if let Err(err) = dotenv::dotenv() {
+        log::info!("Environment variables are not loaded from .env file: {err}");
+    }
+
     let config = load_config("config/base.toml");
+    let token_secrets = load_token_secrets()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::NotFound, format!("{err:#}")))?;
     let enforcer = Enforcer::new("policy/model.conf", "policy/policy.csv")
         .await
         .expect("Failure to load enforcer policy");
 
     start(
         &config.address,
-        "somesecretkey1".to_string(),
-        "somesecretkey2".to_string(),
+        token_secrets.access_token_secret,
+        token_secrets.refresh_token_secret,
         Duration::days(1).num_seconds(),   // one day
         Duration::days(365).num_seconds(), // one year
         &config.database_url,
@@ -60,5 +68,5 @@ async fn main() -> std::io::Result<()> {
 }
 
 fn configure_logger() {
-    mmb_utils::logger::init_logger();
+    mmb_utils::logger::init_logger_with_rotation("log.log", LoggerConfig::default());
 }
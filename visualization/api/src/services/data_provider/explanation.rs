@@ -1,7 +1,7 @@
 use chrono::DateTime;
 use itertools::Itertools;
 use paperclip::actix::Apiv2Schema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::{Pool, Postgres};
 
 use mmb_domain::order::snapshot::{Amount, Price};
@@ -20,10 +20,32 @@ pub struct ExplanationService {
 pub struct PriceLevelRecord {
     pub price: Price,
     pub amount: Amount,
+    #[serde(deserialize_with = "deserialize_reasons")]
     pub reasons: Vec<String>,
     pub mode_name: String,
 }
 
+/// Reasons are stored as `key`/`value` objects, but earlier records contain plain strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReasonRecord {
+    Message(String),
+    KeyValue { key: String, value: String },
+}
+
+fn deserialize_reasons<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let reasons = Vec::<ReasonRecord>::deserialize(deserializer)?;
+    Ok(reasons
+        .into_iter()
+        .map(|reason| match reason {
+            ReasonRecord::Message(message) => message,
+            ReasonRecord::KeyValue { key, value } => format!("{key}={value}"),
+        })
+        .collect())
+}
+
 #[derive(Serialize, Deserialize, Apiv2Schema)]
 #[serde(rename_all(deserialize = "snake_case", serialize = "camelCase"))]
 pub struct ExplanationRecord {
@@ -75,3 +97,39 @@ impl ExplanationService {
         Ok(list)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_legacy_and_key_value_reasons() {
+        let json = serde_json::json!({
+            "set": [
+                {
+                    "price": "100.5",
+                    "amount": "2",
+                    "reasons": ["Existing amount is enough"],
+                    "mode_name": "Disposition",
+                },
+                {
+                    "price": "101",
+                    "amount": "1",
+                    "reasons": [
+                        { "key": "reason", "value": "Cancelling existing orders" },
+                        { "key": "amount", "value": "1" },
+                    ],
+                    "mode_name": "Disposition",
+                },
+            ]
+        });
+
+        let record: ExplanationRecord = serde_json::from_value(json).expect("in test");
+
+        assert_eq!(record.set[0].reasons, vec!["Existing amount is enough"]);
+        assert_eq!(
+            record.set[1].reasons,
+            vec!["reason=Cancelling existing orders", "amount=1"]
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 
-use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order::snapshot::{Amount, Price, SortedOrderData};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 
 use crate::services::data_provider::model::EventRecord;
 use crate::types::{CurrencyPair, ExchangeId};
@@ -52,6 +53,19 @@ pub struct OrderBookSnapshotRecord {
     pub bids: Vec<PriceLevelRecord>,
 }
 
+impl OrderBookSnapshotRecord {
+    pub fn to_order_book_data(&self) -> OrderBookData {
+        fn sorted(price_levels: &[PriceLevelRecord]) -> SortedOrderData {
+            price_levels
+                .iter()
+                .map(|price_level| (price_level.price, price_level.amount))
+                .collect()
+        }
+
+        OrderBookData::new(sorted(&self.asks), sorted(&self.bids))
+    }
+}
+
 #[derive(Deserialize, Clone)]
 pub struct OrderBookOrderRecord;
 
@@ -1,14 +1,22 @@
+use std::collections::HashMap;
+
 use actix::{Actor, Context, Handler};
 use actix_broker::BrokerIssue;
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_domain::order_book::order_book_diff::OrderBookDiff;
 
 use crate::ws::broker_messages::{
     BalancesResponseMessage, LiquidityResponseMessage, NewBalancesDataMessage,
     NewLiquidityDataMessage,
 };
 use crate::ws::commands::liquidity::LiquidityResponseBody;
+use crate::ws::subscribes::liquidity::LiquiditySubscription;
 
 #[derive(Default)]
-pub struct NewDataListener;
+pub struct NewDataListener {
+    /// Last sent order book for each subscription to calculate changes for next message
+    last_order_books: HashMap<LiquiditySubscription, OrderBookData>,
+}
 
 /// This Actor intercepts external events
 impl Actor for NewDataListener {
@@ -27,10 +35,19 @@ impl Handler<NewLiquidityDataMessage> for NewDataListener {
     type Result = ();
 
     fn handle(&mut self, data: NewLiquidityDataMessage, _ctx: &mut Context<Self>) -> Self::Result {
+        let order_book = data.data.order_book.snapshot.to_order_book_data();
+        let order_book_diff = self
+            .last_order_books
+            .insert(data.subscription.clone(), order_book.clone())
+            .map(|last_order_book| {
+                OrderBookDiff::from_order_book_data(&last_order_book, &order_book)
+            });
+
         let body: LiquidityResponseBody = LiquidityResponseBody::from(data.data);
         let liquidity_response_message = LiquidityResponseMessage {
             command: "UpdateOrdersState",
             body,
+            order_book_diff,
             subscription: data.subscription,
         };
         self.issue_system_async(liquidity_response_message);
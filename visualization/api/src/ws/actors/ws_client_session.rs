@@ -20,6 +20,8 @@ use crate::ws::subscribes::Subscription;
 pub struct WsClientSession {
     subscriptions: HashSet<u64>,
     subscribed_liquidity: Option<LiquiditySubscription>,
+    /// Full order book snapshot is sent once after subscription and only changes are sent next
+    is_order_book_snapshot_sent: bool,
     subscribed_balances: Option<BalancesSubscription>,
     token_service: Data<TokenService>,
     is_auth: bool,
@@ -34,6 +36,7 @@ impl WsClientSession {
         Self {
             subscriptions: HashSet::new(),
             subscribed_liquidity: None,
+            is_order_book_snapshot_sent: false,
             subscribed_balances: None,
             token_service,
             is_auth: false,
@@ -97,9 +100,18 @@ impl Handler<LiquidityResponseMessage> for WsClientSession {
             }
         };
 
-        match serde_json::to_value(&msg.body) {
+        let (command, body) = match msg.order_book_diff {
+            Some(order_book_diff) if self.is_order_book_snapshot_sent => (
+                "UpdateOrdersStateDiff",
+                msg.body.with_order_book_diff(order_book_diff),
+            ),
+            _ => (msg.command, msg.body),
+        };
+
+        match serde_json::to_value(&body) {
             Ok(body) => {
-                send_message(ctx, msg.command, body);
+                send_message(ctx, command, body);
+                self.is_order_book_snapshot_sent = true;
             }
             Err(e) => {
                 log::error!("Failure convert to json. Error: {e:?}")
@@ -266,6 +278,7 @@ impl WsClientSession {
             Ok(subscription) => {
                 self.subscriptions.insert(subscription.get_hash());
                 self.subscribed_liquidity = Some(subscription);
+                self.is_order_book_snapshot_sent = false;
             }
             Err(e) => {
                 ctx.stop();
@@ -292,6 +305,7 @@ impl WsClientSession {
             Some(subscription) => {
                 self.subscriptions.remove(&subscription.get_hash());
                 self.subscribed_liquidity = None;
+                self.is_order_book_snapshot_sent = false;
             }
         }
     }
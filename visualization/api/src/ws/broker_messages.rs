@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use actix::prelude::*;
+use mmb_domain::order_book::order_book_diff::OrderBookDiff;
 use serde_json::Value;
 
 use crate::services::data_provider::balances::BalancesData;
@@ -15,6 +16,8 @@ use crate::ws::subscribes::liquidity::LiquiditySubscription;
 pub struct LiquidityResponseMessage {
     pub command: &'static str,
     pub body: LiquidityResponseBody,
+    /// Changes of order book since previous message for the same subscription
+    pub order_book_diff: Option<OrderBookDiff>,
     pub subscription: LiquiditySubscription,
 }
 
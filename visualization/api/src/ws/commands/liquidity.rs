@@ -8,6 +8,7 @@ use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::order_book_diff::OrderBookDiff;
 
 use crate::services::data_provider::liquidity::{
     LiquidityData, LiquidityOrderSide, TransactionOrderSide, TransactionTradeSide,
@@ -18,6 +19,19 @@ use crate::services::data_provider::liquidity::{
 #[serde(rename_all = "camelCase")]
 pub struct LiquidityResponseBody {
     pub orders_state_and_transactions: OrderStateAndTransactions,
+    /// Set instead of order book snapshots for clients that have already received full snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_book_diff: Option<OrderBookDiff>,
+}
+
+impl LiquidityResponseBody {
+    pub fn with_order_book_diff(mut self, order_book_diff: OrderBookDiff) -> Self {
+        let state = &mut self.orders_state_and_transactions;
+        state.sell.snapshot.clear();
+        state.buy.snapshot.clear();
+        self.order_book_diff = Some(order_book_diff);
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -171,6 +185,7 @@ impl From<LiquidityData> for LiquidityResponseBody {
 
         Self {
             orders_state_and_transactions: state,
+            order_book_diff: None,
         }
     }
 }
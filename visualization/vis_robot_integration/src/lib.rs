@@ -19,6 +19,7 @@
 mod liquidity_order_book;
 mod transaction;
 
+use crate::liquidity_order_book::LiquidityOrderBookBatch;
 use crate::transaction::{
     transaction_service, TransactionSnapshot, TransactionStatus, TransactionTrade,
 };
@@ -26,17 +27,72 @@ use anyhow::{Context, Error, Result};
 use function_name::named;
 use mmb_core::lifecycle::trading_engine::EngineContext;
 use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
-use mmb_domain::events::ExchangeEvent;
+use mmb_domain::events::{CustomEvent, ExchangeEvent};
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::snapshot::OrderSnapshot;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub use crate::liquidity_order_book::LiquidityBatchSettings;
+
+/// Handler of strategy-defined events received by visualization data saving loop
+pub type CustomEventHandler = Box<dyn FnMut(&CustomEvent) + Send>;
 
-#[named]
 pub async fn start_visualization_data_saving(
     ctx: Arc<EngineContext>,
     strategy_name: &'static str,
+) -> Result<(), Error> {
+    visualization_data_saving(ctx, strategy_name, LiquidityBatchSettings::default(), None).await
+}
+
+/// Same as `start_visualization_data_saving` but also forwards custom events to `custom_event_handler`
+pub async fn start_visualization_data_saving_with_handler(
+    ctx: Arc<EngineContext>,
+    strategy_name: &'static str,
+    custom_event_handler: CustomEventHandler,
+) -> Result<(), Error> {
+    visualization_data_saving(
+        ctx,
+        strategy_name,
+        LiquidityBatchSettings::default(),
+        Some(custom_event_handler),
+    )
+    .await
+}
+
+/// Same as `start_visualization_data_saving` but with specified batching of liquidity order books saving
+pub async fn start_visualization_data_saving_with_batching(
+    ctx: Arc<EngineContext>,
+    strategy_name: &'static str,
+    liquidity_batch_settings: LiquidityBatchSettings,
+) -> Result<(), Error> {
+    visualization_data_saving(ctx, strategy_name, liquidity_batch_settings, None).await
+}
+
+/// Same as `start_visualization_data_saving` but only the last liquidity order book of a market
+/// is saved for every `compaction_window` instead of saving it on every order book update
+pub async fn start_visualization_data_saving_with_compaction(
+    ctx: Arc<EngineContext>,
+    strategy_name: &'static str,
+    compaction_window: Duration,
+) -> Result<(), Error> {
+    let liquidity_batch_settings = LiquidityBatchSettings {
+        compaction_window: Some(compaction_window),
+        ..LiquidityBatchSettings::default()
+    };
+    visualization_data_saving(ctx, strategy_name, liquidity_batch_settings, None).await
+}
+
+#[named]
+async fn visualization_data_saving(
+    ctx: Arc<EngineContext>,
+    strategy_name: &'static str,
+    liquidity_batch_settings: LiquidityBatchSettings,
+    mut custom_event_handler: Option<CustomEventHandler>,
 ) -> Result<(), Error> {
     let mut snapshots_service = LocalSnapshotsService::default();
+    let mut liquidity_batch =
+        LiquidityOrderBookBatch::new(liquidity_batch_settings, Instant::now());
     let mut events_rx = ctx.get_events_channel();
 
     let stop_token = ctx.lifetime_manager.stop_token();
@@ -74,12 +130,19 @@ pub async fn start_visualization_data_saving(
                         }
                         _ => None,
                     },
+                    ExchangeEvent::CustomEvent(ref custom_event) => {
+                        if let Some(handler) = custom_event_handler.as_mut() {
+                            handler(custom_event);
+                        }
+                        None
+                    }
                     _ => None,
                 };
 
                 liquidity_order_book::save_liquidity_order_book_if_can(
                     &ctx,
                     &mut snapshots_service,
+                    &mut liquidity_batch,
                     market_account_id,
                 )
                 .context("in start_visualization_data_saving")?;
@@ -87,7 +150,9 @@ pub async fn start_visualization_data_saving(
         }
     }
 
-    Ok(())
+    liquidity_batch
+        .flush(&ctx)
+        .context("in start_visualization_data_saving")
 }
 
 fn save_transaction(
@@ -124,6 +189,8 @@ fn save_transaction(
         price: Some(fill.price()),
         amount: fill.amount(),
         side: fill.side(),
+        fee_currency: fill.commission_currency_code(),
+        fee_amount: fill.commission_amount(),
     });
 
     transaction_service::save(&mut transaction, status, &ctx.event_recorder)
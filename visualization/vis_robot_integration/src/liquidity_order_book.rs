@@ -8,9 +8,11 @@ use mmb_domain::order::pool::OrdersPool;
 use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{ClientOrderId, OrderSide, OrderStatus};
 use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
-use mmb_utils::infrastructure::WithExpect;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::mem;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct LiquidityOrder {
@@ -90,11 +92,147 @@ pub fn create_liquidity_order_book_snapshot(
     }
 }
 
-pub fn save_liquidity_order_book_if_can(
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidityBatchSettings {
+    /// Count of liquidity order books accumulated before saving
+    pub max_pending: usize,
+    /// Max time between savings of accumulated liquidity order books
+    pub flush_interval: Duration,
+    /// If set, only the last liquidity order book of a market is saved for every window of this duration.
+    /// `None` means every order book update is saved
+    pub compaction_window: Option<Duration>,
+}
+
+impl Default for LiquidityBatchSettings {
+    fn default() -> Self {
+        LiquidityBatchSettings {
+            max_pending: 50,
+            flush_interval: Duration::from_secs(1),
+            compaction_window: None,
+        }
+    }
+}
+
+/// Latest liquidity order book of a market within the current compaction window
+struct CompactionWindow {
+    started_at: Instant,
+    liquidity_order_book: LiquidityOrderBook,
+}
+
+/// Accumulates liquidity order books for saving them to database together
+pub(crate) struct LiquidityOrderBookBatch {
+    settings: LiquidityBatchSettings,
+    pending: VecDeque<LiquidityOrderBook>,
+    compacting: HashMap<MarketId, CompactionWindow>,
+    last_flush_time: Instant,
+}
+
+impl LiquidityOrderBookBatch {
+    pub(crate) fn new(settings: LiquidityBatchSettings, now: Instant) -> Self {
+        LiquidityOrderBookBatch {
+            settings,
+            pending: VecDeque::with_capacity(settings.max_pending),
+            compacting: HashMap::new(),
+            last_flush_time: now,
+        }
+    }
+
+    fn push(&mut self, liquidity_order_book: LiquidityOrderBook, now: Instant) {
+        let compaction_window = match self.settings.compaction_window {
+            Some(compaction_window) => compaction_window,
+            None => return self.pending.push_back(liquidity_order_book),
+        };
+
+        let market_id = MarketId::new(
+            liquidity_order_book.exchange_id,
+            liquidity_order_book.currency_pair,
+        );
+        match self.compacting.get_mut(&market_id) {
+            Some(window) if now.duration_since(window.started_at) < compaction_window => {
+                window.liquidity_order_book = liquidity_order_book;
+            }
+            _ => {
+                let window = CompactionWindow {
+                    started_at: now,
+                    liquidity_order_book,
+                };
+                if let Some(closed) = self.compacting.insert(market_id, window) {
+                    self.pending.push_back(closed.liquidity_order_book);
+                }
+            }
+        }
+    }
+
+    /// Move liquidity order books of elapsed compaction windows to pending ones
+    fn close_compaction_windows(&mut self, now: Instant) {
+        let compaction_window = match self.settings.compaction_window {
+            Some(compaction_window) => compaction_window,
+            None => return,
+        };
+
+        let closed = self
+            .compacting
+            .iter()
+            .filter(|(_, window)| now.duration_since(window.started_at) >= compaction_window)
+            .map(|(&market_id, _)| market_id)
+            .collect_vec();
+
+        for market_id in closed {
+            if let Some(window) = self.compacting.remove(&market_id) {
+                self.pending.push_back(window.liquidity_order_book);
+            }
+        }
+    }
+
+    fn is_ready(&self, now: Instant) -> bool {
+        !self.pending.is_empty()
+            && (self.pending.len() >= self.settings.max_pending
+                || now.duration_since(self.last_flush_time) >= self.settings.flush_interval)
+    }
+
+    /// Take accumulated liquidity order books if count reached `max_pending` or `flush_interval` elapsed
+    fn take_if_ready(&mut self, now: Instant) -> Option<VecDeque<LiquidityOrderBook>> {
+        self.close_compaction_windows(now);
+        self.is_ready(now).then(|| self.take(now))
+    }
+
+    fn take(&mut self, now: Instant) -> VecDeque<LiquidityOrderBook> {
+        self.last_flush_time = now;
+        let capacity = self.settings.max_pending;
+        mem::replace(&mut self.pending, VecDeque::with_capacity(capacity))
+    }
+
+    /// Save all accumulated liquidity order books regardless of batch bounds
+    pub(crate) fn flush(&mut self, ctx: &EngineContext) -> anyhow::Result<()> {
+        self.pending.extend(
+            self.compacting
+                .drain()
+                .map(|(_, window)| window.liquidity_order_book),
+        );
+        save_liquidity_order_books(ctx, self.take(Instant::now()))
+    }
+}
+
+fn save_liquidity_order_books(
+    ctx: &EngineContext,
+    liquidity_order_books: VecDeque<LiquidityOrderBook>,
+) -> anyhow::Result<()> {
+    for liquidity_order_book in liquidity_order_books {
+        ctx.event_recorder
+            .save(liquidity_order_book)
+            .context("failed saving liquidity_order_book")?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn save_liquidity_order_book_if_can(
     ctx: &EngineContext,
     snapshots_service: &mut LocalSnapshotsService,
+    batch: &mut LiquidityOrderBookBatch,
     market_account_id: Option<MarketAccountId>,
 ) -> anyhow::Result<()> {
+    let now = Instant::now();
     if let Some(market_account_id) = market_account_id {
         let market_id = market_account_id.market_id();
         if let Some(snapshot) = snapshots_service.get_snapshot(market_id) {
@@ -102,15 +240,142 @@ pub fn save_liquidity_order_book_if_can(
             let liquidity_order_book = create_liquidity_order_book_snapshot(
                 snapshot,
                 market_id,
-                &ctx.exchanges.get(&exchange_account_id)
-                    .with_expect(|| format!("exchange {exchange_account_id} should exists in `Save order book` events loop"))
-                    .orders,
+                &ctx.get_exchange(exchange_account_id)?.orders,
             );
-            ctx.event_recorder
-                .save(liquidity_order_book)
-                .context("failed saving liquidity_order_book")?;
+            batch.push(liquidity_order_book, now);
         }
     }
 
-    Ok(())
+    match batch.take_if_ready(now) {
+        Some(liquidity_order_books) => save_liquidity_order_books(ctx, liquidity_order_books),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn liquidity_order_book() -> LiquidityOrderBook {
+        liquidity_order_book_with_price("btc", dec!(0))
+    }
+
+    fn liquidity_order_book_with_price(base: &str, price: Price) -> LiquidityOrderBook {
+        LiquidityOrderBook {
+            exchange_id: ExchangeId::new("Binance"),
+            currency_pair: CurrencyPair::from_codes(base.into(), "usdt".into()),
+            snapshot: LiquiditySnapshot {
+                asks: vec![PriceLevel {
+                    price,
+                    amount: dec!(1),
+                }],
+                bids: vec![],
+            },
+            orders: vec![],
+        }
+    }
+
+    fn batch(max_pending: usize, now: Instant) -> LiquidityOrderBookBatch {
+        LiquidityOrderBookBatch::new(
+            LiquidityBatchSettings {
+                max_pending,
+                flush_interval: Duration::from_secs(10),
+                compaction_window: None,
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn flush_when_max_pending_reached() {
+        let now = Instant::now();
+        let mut batch = batch(3, now);
+
+        for _ in 0..2 {
+            batch.push(liquidity_order_book(), now);
+            assert!(batch.take_if_ready(now).is_none());
+        }
+
+        batch.push(liquidity_order_book(), now);
+        let flushed = batch.take_if_ready(now).expect("batch should be flushed");
+        assert_eq!(flushed.len(), 3);
+        assert!(batch.pending.is_empty());
+
+        batch.push(liquidity_order_book(), now);
+        assert!(batch.take_if_ready(now).is_none());
+        assert_eq!(batch.pending.len(), 1);
+    }
+
+    #[test]
+    fn flush_when_interval_elapsed() {
+        let now = Instant::now();
+        let mut batch = batch(50, now);
+
+        assert!(batch.take_if_ready(now + Duration::from_secs(11)).is_none());
+
+        batch.push(liquidity_order_book(), now);
+        assert!(batch.take_if_ready(now + Duration::from_secs(5)).is_none());
+
+        let flushed = batch
+            .take_if_ready(now + Duration::from_secs(11))
+            .expect("batch should be flushed");
+        assert_eq!(flushed.len(), 1);
+        assert!(batch.pending.is_empty());
+
+        // interval is counted from the last flush
+        batch.push(liquidity_order_book(), now);
+        assert!(batch.take_if_ready(now + Duration::from_secs(15)).is_none());
+    }
+
+    fn ask_prices(liquidity_order_books: &VecDeque<LiquidityOrderBook>) -> Vec<Price> {
+        liquidity_order_books
+            .iter()
+            .map(|x| x.snapshot.asks[0].price)
+            .collect()
+    }
+
+    #[test]
+    fn compact_order_books_within_window() {
+        let now = Instant::now();
+        let window = Duration::from_millis(100);
+        let mut batch = LiquidityOrderBookBatch::new(
+            LiquidityBatchSettings {
+                max_pending: 1,
+                flush_interval: Duration::from_secs(10),
+                compaction_window: Some(window),
+            },
+            now,
+        );
+
+        for (i, price) in [dec!(1), dec!(2), dec!(3)].into_iter().enumerate() {
+            let time = now + Duration::from_millis(30 * i as u64);
+            batch.push(liquidity_order_book_with_price("btc", price), time);
+            assert!(batch.take_if_ready(time).is_none());
+        }
+        batch.push(liquidity_order_book_with_price("eth", dec!(10)), now);
+
+        let flushed = batch
+            .take_if_ready(now + window)
+            .expect("batch should be flushed");
+        let mut prices = ask_prices(&flushed);
+        prices.sort();
+        assert_eq!(prices, vec![dec!(3), dec!(10)]);
+        assert!(batch.compacting.is_empty());
+
+        // order book received after window elapsed starts a new window
+        batch.push(
+            liquidity_order_book_with_price("btc", dec!(4)),
+            now + window,
+        );
+        batch.push(
+            liquidity_order_book_with_price("btc", dec!(5)),
+            now + window * 2,
+        );
+        let flushed = batch
+            .take_if_ready(now + window * 2)
+            .expect("batch should be flushed");
+        assert_eq!(ask_prices(&flushed), vec![dec!(4)]);
+        assert_eq!(batch.compacting.len(), 1);
+    }
 }
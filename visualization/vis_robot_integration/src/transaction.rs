@@ -1,10 +1,11 @@
 use mmb_core::misc::time::time_manager;
 use mmb_database::impl_event;
 use mmb_domain::market::ExchangeId;
-use mmb_domain::market::MarketId;
+use mmb_domain::market::{CurrencyCode, MarketId};
 use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{ExchangeOrderId, OrderSide};
 use mmb_utils::DateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -21,6 +22,33 @@ pub struct TransactionTrade {
     pub price: Option<Price>,
     pub amount: Amount,
     pub side: Option<OrderSide>,
+    pub fee_currency: CurrencyCode,
+    pub fee_amount: Decimal,
+}
+
+impl TransactionTrade {
+    /// Quote currency amount received (positive) or spent (negative) by the trade excluding fee
+    fn gross_quote_amount(&self) -> Option<Decimal> {
+        let cost = self.price? * self.amount;
+        match self.side? {
+            OrderSide::Buy => Some(-cost),
+            OrderSide::Sell => Some(cost),
+        }
+    }
+
+    /// Fee of the trade in quote currency. Fee paid in other currency (e.g. BNB)
+    /// is converted with `fee_currency_price` returning price of the currency in quote currency
+    fn fee_in_quote(
+        &self,
+        quote_currency: CurrencyCode,
+        fee_currency_price: impl Fn(CurrencyCode) -> Option<Price>,
+    ) -> Option<Decimal> {
+        if self.fee_amount.is_zero() || self.fee_currency == quote_currency {
+            return Some(self.fee_amount);
+        }
+
+        Some(self.fee_amount * fee_currency_price(self.fee_currency)?)
+    }
 }
 
 pub type TransactionId = Uuid;
@@ -112,6 +140,31 @@ impl TransactionSnapshot {
     pub fn creation_time(&self) -> DateTime {
         self.transaction_creation_time
     }
+
+    /// Profit/loss of transaction trades in quote currency excluding fees.
+    /// Returns `None` if price or side of some trade is unknown
+    pub fn gross_profit_loss(&self) -> Option<Decimal> {
+        self.trades
+            .iter()
+            .map(|trade| trade.gross_quote_amount())
+            .sum()
+    }
+
+    /// Profit/loss of transaction trades in quote currency with fees subtracted.
+    /// Returns `None` if price or side of some trade is unknown or fee currency can't be converted to quote currency
+    pub fn net_profit_loss(
+        &self,
+        quote_currency: CurrencyCode,
+        fee_currency_price: impl Fn(CurrencyCode) -> Option<Price>,
+    ) -> Option<Decimal> {
+        let fees: Option<Decimal> = self
+            .trades
+            .iter()
+            .map(|trade| trade.fee_in_quote(quote_currency, &fee_currency_price))
+            .sum();
+
+        Some(self.gross_profit_loss()? - fees?)
+    }
 }
 
 pub mod transaction_service {
@@ -132,3 +185,97 @@ pub mod transaction_service {
             .context("in transaction_service::save()")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::CurrencyPair;
+    use rust_decimal_macros::dec;
+
+    fn trade(
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        fee_currency: &str,
+        fee_amount: Decimal,
+    ) -> TransactionTrade {
+        TransactionTrade {
+            exchange_order_id: "test".into(),
+            exchange_id: ExchangeId::new("Binance"),
+            price: Some(price),
+            amount,
+            side: Some(side),
+            fee_currency: fee_currency.into(),
+            fee_amount,
+        }
+    }
+
+    fn transaction(trades: Vec<TransactionTrade>) -> TransactionSnapshot {
+        let mut transaction = TransactionSnapshot::new(
+            MarketId::new(
+                ExchangeId::new("Binance"),
+                CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            ),
+            OrderSide::Buy,
+            Some(dec!(100)),
+            dec!(2),
+            TransactionStatus::Finished,
+            "test".to_owned(),
+        );
+        transaction.trades = trades;
+        transaction
+    }
+
+    fn bnb_price(currency: CurrencyCode) -> Option<Price> {
+        (currency == "bnb".into()).then_some(dec!(300))
+    }
+
+    #[test]
+    fn net_profit_loss_with_fees() {
+        let transaction = transaction(vec![
+            trade(OrderSide::Buy, dec!(100), dec!(2), "usdt", dec!(0.2)),
+            trade(OrderSide::Sell, dec!(110), dec!(2), "bnb", dec!(0.001)),
+        ]);
+
+        assert_eq!(transaction.gross_profit_loss(), Some(dec!(20)));
+        assert_eq!(
+            transaction.net_profit_loss("usdt".into(), bnb_price),
+            Some(dec!(19.5))
+        );
+    }
+
+    #[test]
+    fn net_profit_loss_without_fees_is_gross() {
+        let transaction = transaction(vec![
+            trade(OrderSide::Buy, dec!(100), dec!(2), "usdt", Decimal::ZERO),
+            trade(OrderSide::Sell, dec!(110), dec!(2), "bnb", Decimal::ZERO),
+        ]);
+
+        assert_eq!(
+            transaction.net_profit_loss("usdt".into(), |_| None),
+            transaction.gross_profit_loss()
+        );
+    }
+
+    #[test]
+    fn net_profit_loss_unknown_fee_currency_price() {
+        let transaction = transaction(vec![trade(
+            OrderSide::Sell,
+            dec!(110),
+            dec!(2),
+            "eth",
+            dec!(0.001),
+        )]);
+
+        assert_eq!(transaction.net_profit_loss("usdt".into(), bnb_price), None);
+    }
+
+    #[test]
+    fn fee_serialized_with_trade() {
+        let trade = trade(OrderSide::Sell, dec!(110), dec!(2), "bnb", dec!(0.001));
+
+        let json = serde_json::to_value(&trade).expect("serialize trade");
+        assert_eq!(json["fee_currency"], "bnb");
+        assert_eq!(json["fee_amount"], "0.001");
+    }
+}
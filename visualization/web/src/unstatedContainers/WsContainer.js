@@ -6,6 +6,25 @@ import CryptolpAxios from "../cryptolpaxios";
 import { toast } from "react-toastify";
 import "react-toastify/dist/ReactToastify.css";
 
+// Apply changes of price levels from server to the snapshot keeping its sort direction
+function applyPriceLevelsDiff(snapshot, diff) {
+  const isDescending =
+    snapshot.length > 1 &&
+    Number(snapshot[0][0]) > Number(snapshot[snapshot.length - 1][0]);
+  const removed = new Set(diff.removed.map(Number));
+  const changed = new Map(
+    diff.changed.map(([price, , newAmount]) => [Number(price), newAmount])
+  );
+
+  return snapshot
+    .filter(([price]) => !removed.has(Number(price)))
+    .map(([price, amount]) => [price, changed.get(Number(price)) ?? amount])
+    .concat(diff.added)
+    .sort(([a], [b]) =>
+      isDescending ? Number(b) - Number(a) : Number(a) - Number(b)
+    );
+}
+
 class WsContainer extends Container {
   constructor(props) {
     super(props);
@@ -55,6 +74,11 @@ class WsContainer extends Container {
         await this.updateOrderState(message);
         break;
       }
+      case "UpdateOrdersStateDiff": {
+        console.log("OrderState diff update");
+        await this.updateOrderStateDiff(message);
+        break;
+      }
       case "UpdateDashboard": {
         console.log("Dashboard update");
         await this.updateIndicators(message);
@@ -221,6 +245,24 @@ class WsContainer extends Container {
     }
   }
 
+  async updateOrderStateDiff(data) {
+    const { orderState } = this.state;
+    if (!orderState) {
+      return;
+    }
+
+    const { ordersStateAndTransactions, orderBookDiff } = data;
+    ordersStateAndTransactions.sell.snapshot = applyPriceLevelsDiff(
+      orderState.sell.snapshot,
+      orderBookDiff.asks
+    );
+    ordersStateAndTransactions.buy.snapshot = applyPriceLevelsDiff(
+      orderState.buy.snapshot,
+      orderBookDiff.bids
+    );
+    await this.updateOrderState(data);
+  }
+
   async updateOrderState(data) {
     const { exchangeName, currencyCodePair } = this.state;
     const { ordersStateAndTransactions, indicators } = data;